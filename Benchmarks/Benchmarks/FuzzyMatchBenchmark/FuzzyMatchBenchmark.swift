@@ -757,4 +757,35 @@ let benchmarks: @Sendable () -> Void = {
             blackHole(totalMatches)
         }
     }
+
+    // MARK: - Cancellation Latency
+
+    // Wall time from cancel() to the scan returning, cancelled while it is well
+    // under way. A stale scan has to give way before the next keystroke's scan
+    // starts, so the p50 should stay under a millisecond; only that window is
+    // measured, and `baseline check` flags a regression against main.
+    Benchmark(
+        "Cancellation latency - 1M candidates",
+        configuration: .init(
+            metrics: [.wallClock], warmupIterations: 1, scalingFactor: .one, maxIterations: 50,
+            thresholds: [.wallClock: concurrentThreshold]
+        )
+    ) { benchmark in
+        let dataset = DatasetHolder.shared.fullDataset
+        let matcher = FuzzyMatcher()
+        let query = matcher.prepare("getUs")
+
+        for _ in benchmark.scaledIterations {
+            let token = CancellationToken()
+            let scan = Task.detached {
+                matcher.topMatches(dataset, against: query, limit: 50, cancellation: token)
+            }
+            try? await Task.sleep(for: .milliseconds(2))
+
+            benchmark.startMeasurement()
+            token.cancel()
+            blackHole(await scan.value)
+            benchmark.stopMeasurement()
+        }
+    }
 }
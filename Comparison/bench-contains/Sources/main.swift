@@ -49,6 +49,7 @@ struct App {
     static let categoryOrder = [
         "exact_symbol", "exact_name", "exact_isin", "prefix",
         "typo", "substring", "multi_word", "symbol_spaces", "abbreviation",
+        "adversarial",
     ]
 
     static func main() {
@@ -0,0 +1,211 @@
+import FuzzyMatch
+import Foundation
+
+// MARK: - Accent Folding Strategies
+
+/// Ways of lowercasing a candidate and folding its accented letters to
+/// ASCII, compared by `--folding`. Each returns the folded UTF-8 bytes.
+///
+/// `branch` mirrors what FuzzyMatch does when it prepares a query or
+/// scores a candidate (`lowercaseUTF8` and `latin1ToASCII`): a `switch`
+/// over the second byte of each Latin-1 Supplement letter. The others fold
+/// the same letters or more, with different costs.
+enum FoldStrategy: String, CaseIterable {
+    /// The library's own folding: a `switch` per Latin-1 letter.
+    case branch
+    /// A 64-entry array indexed by the lowercased second byte.
+    case latin1Array = "latin-1 array"
+    /// A table over U+0000–U+024F (Latin-1 and Latin Extended-A/B), built
+    /// once from each scalar's compatibility decomposition.
+    case foldTable = "fold table"
+    /// Compatibility decomposition (NFKD) of every candidate as it is
+    /// folded, then combining marks dropped.
+    case nfkd
+
+    func fold(_ text: String) -> [UInt8] {
+        switch self {
+        case .branch: Self.foldLatin1(text) { Self.branchFold($0) }
+        case .latin1Array: Self.foldLatin1(text) { Self.latin1Table[Int($0 & 0x3F)] }
+        case .foldTable: Self.foldWithTable(text)
+        case .nfkd: Self.foldNFKD(text)
+        }
+    }
+
+    /// The library's byte loop: ASCII lowercased, combining marks
+    /// (U+0300–U+036F) skipped, and Latin-1 letters folded by `base`, which
+    /// maps a lowercased second byte to its ASCII letter or `0`.
+    private static func foldLatin1(_ text: String, base: (UInt8) -> UInt8) -> [UInt8] {
+        let source = Array(text.utf8)
+        var out: [UInt8] = []
+        out.reserveCapacity(source.count)
+        var i = 0
+        while i < source.count {
+            let byte = source[i]
+            if i + 1 < source.count {
+                let second = source[i + 1]
+                if (byte == 0xCC && second >= 0x80 && second <= 0xBF) || (byte == 0xCD && second >= 0x80 && second <= 0xAF) {
+                    i += 2
+                    continue
+                }
+                if byte == 0xC3 {
+                    let lowered = second >= 0x80 && second <= 0x9E && second != 0x97 ? second + 0x20 : second
+                    let ascii = base(lowered)
+                    if ascii != 0 {
+                        out.append(ascii)
+                    } else {
+                        out.append(byte)
+                        out.append(lowered)
+                    }
+                    i += 2
+                    continue
+                }
+            }
+            out.append(byte >= 0x41 && byte <= 0x5A ? byte | 0x20 : byte)
+            i += 1
+        }
+        return out
+    }
+
+    /// Copy of FuzzyMatch's `latin1ToASCII`.
+    private static func branchFold(_ lowered: UInt8) -> UInt8 {
+        switch lowered {
+        case 0xA0...0xA5: 0x61
+        case 0xA7: 0x63
+        case 0xA8...0xAB: 0x65
+        case 0xAC...0xAF: 0x69
+        case 0xB1: 0x6E
+        case 0xB2...0xB6: 0x6F
+        case 0xB9...0xBC: 0x75
+        case 0xBD, 0xBF: 0x79
+        default: 0
+        }
+    }
+
+    /// `branchFold` of every second byte from 0x80 to 0xBF.
+    private static let latin1Table: [UInt8] = (0x80...0xBF).map { branchFold(UInt8($0)) }
+
+    /// The ASCII letter each scalar up to U+024F decomposes to, or `0`.
+    private static let scalarTable: [UInt8] = (0..<0x250).map { value -> UInt8 in
+        guard let scalar = Unicode.Scalar(UInt32(value)) else { return 0 }
+        let decomposed = String(Character(scalar)).decomposedStringWithCompatibilityMapping.lowercased()
+        guard let first = decomposed.unicodeScalars.first, first.isASCII,
+              decomposed.unicodeScalars.dropFirst().allSatisfy(isCombiningMark) else { return 0 }
+        return UInt8(first.value)
+    }
+
+    private static func foldWithTable(_ text: String) -> [UInt8] {
+        var out: [UInt8] = []
+        out.reserveCapacity(text.utf8.count)
+        for scalar in text.unicodeScalars {
+            if isCombiningMark(scalar) {
+                continue
+            }
+            let ascii = scalar.value < 0x250 ? scalarTable[Int(scalar.value)] : 0
+            if ascii != 0 {
+                out.append(ascii)
+            } else {
+                out.append(contentsOf: String(scalar).lowercased().utf8)
+            }
+        }
+        return out
+    }
+
+    private static func foldNFKD(_ text: String) -> [UInt8] {
+        var out: [UInt8] = []
+        out.reserveCapacity(text.utf8.count)
+        for scalar in text.decomposedStringWithCompatibilityMapping.lowercased().unicodeScalars where !isCombiningMark(scalar) {
+            out.append(contentsOf: String(scalar).utf8)
+        }
+        return out
+    }
+
+    private static func isCombiningMark(_ scalar: Unicode.Scalar) -> Bool {
+        (0x300...0x36F).contains(scalar.value)
+    }
+}
+
+extension App {
+    /// Results compared per query by `--folding`.
+    static let foldingTopK = 10
+
+    /// Times each folding strategy over the name column and checks what it
+    /// does to results. Every name and name query is folded by the
+    /// strategy before the default matcher scores it; a query is changed
+    /// when its top results differ from those of the unfolded names, which
+    /// the library folds itself. `branch` mirrors the library, so it should
+    /// change nothing; any other strategy that changes nothing folds no
+    /// differently where it matters and can be judged on time alone.
+    static func runFoldingBenchmark(
+        matchConfig: MatchConfig,
+        modeName: String,
+        queries: [Query],
+        names: [String],
+        iterations: Int
+    ) {
+        let nameQueries = queries.filter { $0.field == "name" }
+        let matcher = FuzzyMatcher(config: matchConfig)
+        var buffer = matcher.makeBuffer()
+
+        func top(_ query: String, in candidates: [String]) -> [Int] {
+            let prepared = matcher.prepare(query)
+            return scoreQuery(matcher: matcher, prepared: prepared, buffer: &buffer, candidates: candidates)
+                .top.prefix(foldingTopK).map(\.index)
+        }
+
+        print("=== Folding strategies: \(names.count) names, \(nameQueries.count) name queries, FuzzyMatch (\(modeName)), top \(foldingTopK) ===")
+        print("")
+        let baseline = nameQueries.map { top($0.text, in: names) }
+        let branchFolded = names.map { FoldStrategy.branch.fold($0) }
+
+        var rows: [String] = []
+        var examples: [(strategy: String, name: String, folded: String)] = []
+        for strategy in FoldStrategy.allCases {
+            _ = names.map { strategy.fold($0) }
+            var timingsMs: [Double] = []
+            var folded: [[UInt8]] = []
+            for _ in 0..<iterations {
+                let start = now()
+                folded = names.map { strategy.fold($0) }
+                timingsMs.append(msFrom(start, to: now()))
+            }
+            let medianMs = timingsMs.sorted()[iterations / 2]
+
+            var differing = 0
+            for (index, bytes) in folded.enumerated() where bytes != branchFolded[index] {
+                differing += 1
+                if examples.count(where: { $0.strategy == strategy.rawValue }) < 5 {
+                    examples.append((strategy.rawValue, names[index], String(decoding: bytes, as: UTF8.self)))
+                }
+            }
+
+            let foldedNames = folded.map { String(decoding: $0, as: UTF8.self) }
+            var changed = 0
+            var changedFirst = 0
+            for (qi, q) in nameQueries.enumerated() {
+                let results = top(String(decoding: strategy.fold(q.text), as: UTF8.self), in: foldedNames)
+                if results != baseline[qi] {
+                    changed += 1
+                    changedFirst += results.first != baseline[qi].first ? 1 : 0
+                }
+            }
+
+            let nsPerName = medianMs * 1_000_000 / Double(max(1, names.count))
+            rows.append("\(pad(strategy.rawValue, 14)) \(pad(fmtD(medianMs, 1), 10, right: true)) \(pad(fmtD(nsPerName, 0), 8, right: true)) \(pad("\(differing)", 10, right: true)) \(pad("\(changed)", 8, right: true)) \(pad("\(changedFirst)", 10, right: true))")
+        }
+
+        print("\(pad("Strategy", 14)) \(pad("Fold(ms)", 10, right: true)) \(pad("ns/name", 8, right: true)) \(pad("Differ", 10, right: true)) \(pad("Changed", 8, right: true)) \(pad("Changed #1", 10, right: true))")
+        print(String(repeating: "-", count: 66))
+        rows.forEach { print($0) }
+        print("")
+        print("Differ: names folded differently from the library's folding (branch).")
+        print("Changed: name queries whose top \(foldingTopK) differ from the unfolded names'.")
+
+        guard !examples.isEmpty else { return }
+        print("")
+        print("=== Names folded differently from the library ===")
+        print("")
+        for example in examples {
+            print("\(pad(example.strategy, 14)) \(example.name) → \(example.folded)")
+        }
+    }
+}
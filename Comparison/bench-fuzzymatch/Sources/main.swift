@@ -34,6 +34,7 @@ struct App {
     static let categoryOrder = [
         "exact_symbol", "exact_name", "exact_isin", "prefix",
         "typo", "substring", "multi_word", "symbol_spaces", "abbreviation",
+        "adversarial",
     ]
 
     static func main() {
@@ -57,6 +58,28 @@ struct App {
             }
         }
 
+        if config.prefilterImpact {
+            runPrefilterImpact(
+                baseConfig: matchConfig,
+                modeName: modeName,
+                queries: queries,
+                candidates: candidates,
+                iterations: config.iterations
+            )
+            return
+        }
+
+        if config.folding {
+            runFoldingBenchmark(
+                matchConfig: matchConfig,
+                modeName: modeName,
+                queries: queries,
+                names: nameCandidates,
+                iterations: config.iterations
+            )
+            return
+        }
+
         print("Running \(queries.count) queries")
         print("")
 
@@ -142,6 +165,103 @@ struct App {
         return (matchCount, results)
     }
 
+    // MARK: - Prefilter Impact
+
+    /// The prefilter sets compared against the default: each leaves one filter out.
+    static let prefilterVariants: [(name: String, prefilters: PrefilterSet)] = [
+        ("length bounds", PrefilterSet.all.subtracting(.lengthBounds)),
+        ("char bitmask", PrefilterSet.all.subtracting(.charBitmask)),
+        ("trigrams", PrefilterSet.all.subtracting(.trigrams)),
+    ]
+
+    /// Runs every query with all prefilters and again with each one left out.
+    /// A result is lost to a filter when it is in the top-K scored without the
+    /// filter and the filter rejects it; the time saved is the difference in
+    /// summed per-query medians.
+    static func runPrefilterImpact(
+        baseConfig: MatchConfig,
+        modeName: String,
+        queries: [Query],
+        candidates: (String) -> [String],
+        iterations: Int
+    ) {
+        func timed(_ matcher: FuzzyMatcher) -> (mediansMs: [Double], tops: [[ScoredResult]]) {
+            var buffer = matcher.makeBuffer()
+            var mediansMs: [Double] = []
+            var tops: [[ScoredResult]] = []
+            for q in queries {
+                let pool = candidates(q.field)
+                let prepared = matcher.prepare(q.text)
+                var timingsMs: [Double] = []
+                var top: [ScoredResult] = []
+                for _ in 0..<iterations {
+                    let start = now()
+                    top = scoreQuery(matcher: matcher, prepared: prepared, buffer: &buffer, candidates: pool).top
+                    timingsMs.append(msFrom(start, to: now()))
+                }
+                mediansMs.append(timingsMs.sorted()[iterations / 2])
+                tops.append(top)
+            }
+            return (mediansMs, tops)
+        }
+
+        print("=== Prefilter impact: FuzzyMatch (\(modeName)), \(queries.count) queries, top \(topK) ===")
+        print("")
+
+        let filtered = FuzzyMatcher(config: baseConfig)
+        _ = timed(filtered)
+        let baseline = timed(filtered)
+        let baselineMs = baseline.mediansMs.reduce(0, +)
+        var buffer = filtered.makeBuffer()
+
+        var lostQueries: [(filter: String, query: Query, lost: [ScoredResult], first: Bool)] = []
+        var rows: [String] = []
+        for variant in prefilterVariants {
+            var config = baseConfig
+            config.prefilters = variant.prefilters
+            let unfiltered = timed(FuzzyMatcher(config: config))
+            var lostTotal = 0
+            var lostFirst = 0
+            var affected = 0
+            for (qi, q) in queries.enumerated() {
+                let pool = candidates(q.field)
+                let prepared = filtered.prepare(q.text)
+                let lost = unfiltered.tops[qi].filter { result in
+                    filtered.score(pool[result.index], against: prepared, buffer: &buffer) == nil
+                }
+                guard !lost.isEmpty else { continue }
+                let first = lost.contains { $0.index == unfiltered.tops[qi].first?.index }
+                affected += 1
+                lostTotal += lost.count
+                lostFirst += first ? 1 : 0
+                lostQueries.append((variant.name, q, lost, first))
+            }
+            let withoutMs = unfiltered.mediansMs.reduce(0, +)
+            let savedMs = withoutMs - baselineMs
+            rows.append("\(pad(variant.name, 16)) \(pad("\(affected)", 8, right: true)) \(pad("\(lostTotal)", 8, right: true)) \(pad("\(lostFirst)", 8, right: true)) \(pad(fmtD(withoutMs, 1), 10, right: true)) \(pad(fmtD(savedMs, 1), 10, right: true)) \(pad(fmtD(withoutMs > 0 ? savedMs / withoutMs * 100 : 0, 1) + "%", 8, right: true))")
+        }
+
+        print("All prefilters: \(fmtD(baselineMs, 1))ms (sum of per-query medians)")
+        print("")
+        print("\(pad("Without", 16)) \(pad("Queries", 8, right: true)) \(pad("Lost", 8, right: true)) \(pad("Lost #1", 8, right: true)) \(pad("Time(ms)", 10, right: true)) \(pad("Saved(ms)", 10, right: true)) \(pad("Saved", 8, right: true))")
+        print(String(repeating: "-", count: 74))
+        rows.forEach { print($0) }
+
+        guard !lostQueries.isEmpty else { return }
+        print("")
+        print("=== Results lost to each prefilter ===")
+        print("")
+        print("\(pad("Filter", 16)) \(pad("Query", 32)) \(pad("Field", 8)) \(pad("Category", 16)) \(pad("Lost", 6, right: true))  Best lost")
+        print(String(repeating: "-", count: 112))
+        for entry in lostQueries {
+            let display = entry.query.text.count > 30 ? String(entry.query.text.prefix(27)) + "..." : entry.query.text
+            let best = entry.lost[0]
+            let bestText = candidates(entry.query.field)[best.index]
+            let marker = entry.first ? " (#1)" : ""
+            print("\(pad(entry.filter, 16)) \(pad(display, 32)) \(pad(entry.query.field, 8)) \(pad(entry.query.category, 16)) \(pad("\(entry.lost.count)", 6, right: true))  \(bestText) \(fmtD(best.score, 3))\(marker)")
+        }
+    }
+
     // MARK: - Argument Parsing
 
     struct Config {
@@ -149,6 +269,8 @@ struct App {
         let queriesPath: String
         let iterations: Int
         let useSmithWaterman: Bool
+        let prefilterImpact: Bool
+        let folding: Bool
     }
 
     static func parseArgs() -> Config {
@@ -157,7 +279,16 @@ struct App {
         let queriesPath = argValue(for: "--queries", in: args) ?? "../../Resources/queries.tsv"
         let iterations = argValue(for: "--iterations", in: args).flatMap(Int.init) ?? 5
         let useSmithWaterman = args.contains("--sw")
-        return Config(tsvPath: tsvPath, queriesPath: queriesPath, iterations: max(1, iterations), useSmithWaterman: useSmithWaterman)
+        let prefilterImpact = args.contains("--prefilter-impact")
+        let folding = args.contains("--folding")
+        return Config(
+            tsvPath: tsvPath,
+            queriesPath: queriesPath,
+            iterations: max(1, iterations),
+            useSmithWaterman: useSmithWaterman,
+            prefilterImpact: prefilterImpact,
+            folding: folding
+        )
     }
 
     static func argValue(for flag: String, in args: [String]) -> String? {
@@ -163,7 +163,8 @@ print("")
 // Per-category summary
 let categorySet = Set(queries.map(\.category))
 let categories = ["exact_symbol", "exact_name", "exact_isin", "prefix",
-                   "typo", "substring", "multi_word", "symbol_spaces", "abbreviation"]
+                   "typo", "substring", "multi_word", "symbol_spaces", "abbreviation",
+                   "adversarial"]
     .filter { categorySet.contains($0) }
 
 func pad(_ s: String, _ width: Int, right: Bool = false) -> String {
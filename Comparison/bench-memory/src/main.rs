@@ -0,0 +1,282 @@
+//! Measures the memory each representation of a corpus's candidates holds.
+//!
+//! Usage: bench-memory --tsv PATH [--map symbol=KEY,name=KEY,isin=KEY]
+//!                     [--sizes 100000,500000,1000000] [--results PATH]
+//!                     [--run LABEL] [--db PATH]
+//!
+//! The symbol, name and ISIN of the first N rows are held five ways:
+//! `strings`, a `Vec<String>` per field; `arena`, the interned per-field
+//! arenas of `PreparedCorpus`; `utf32`, a nucleo `Utf32String` per value,
+//! as nucleo's own item cache keeps them; `serialized`, the bytes of a
+//! saved `PreparedCorpus` read back from disk; and `mapped`, the same file
+//! mapped with `PreparedCorpus::open`. A mapped index's pages count as
+//! resident, but they are the page cache's, shared by every process that
+//! maps the file, and only its frequency tables are on the heap. Each
+//! representation and size
+//! is measured in a fresh process (this binary run again with `--measure`),
+//! so memory freed by one measurement is never reused by the next. The
+//! serialized corpus is written by a process of its own beforehand, and the
+//! one reading it back never loads the corpus at all, as on a device that
+//! ships the file.
+//!
+//! Heap bytes are counted exactly by the allocator; resident bytes are the
+//! growth of the process's resident set, which is what a device has to
+//! find room for. Sizes larger than the corpus are skipped, so generate one
+//! big enough with `corpus-gen --rows 1000000`.
+
+use harness::cli::{flag_value, parsed_flag};
+use harness::corpus::{load_corpus_mapped, FieldMap};
+use harness::fingerprint::Fingerprint;
+use harness::manifest::Manifest;
+use harness::memory::{live_bytes, resident_bytes, CountingAlloc};
+use harness::prepared::PreparedCorpus;
+use harness::results::{Footprint, ResultSet};
+use harness::store::{self, Store};
+use nucleo_matcher::Utf32String;
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::hint::black_box;
+use std::path::Path;
+use std::process::{self, Command};
+
+#[global_allocator]
+static ALLOC: CountingAlloc = CountingAlloc;
+
+/// Ways of holding the candidates, in the order they are measured.
+const REPRESENTATIONS: [&str; 5] = ["strings", "arena", "utf32", "serialized", "mapped"];
+
+/// The fields every representation holds.
+const FIELDS: [&str; 3] = ["symbol", "name", "isin"];
+
+const DEFAULT_SIZES: &str = "100000,500000,1000000";
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let tsv_path = flag_value(&args, "--tsv").unwrap_or_else(|| {
+        eprintln!("Usage: bench-memory --tsv PATH [--sizes N,...] [--results PATH]");
+        process::exit(2);
+    });
+    let field_map: FieldMap = parsed_flag(&args, "--map", FieldMap::default());
+    let rows: usize = parsed_flag(&args, "--rows", usize::MAX);
+    if let Some(path) = flag_value(&args, "--write-index") {
+        let mut corpus = load_corpus_mapped(tsv_path, &field_map).expect("Failed to read corpus");
+        corpus.truncate(rows);
+        fs::write(path, PreparedCorpus::new(&corpus, []).to_bytes())
+            .expect("Failed to write serialized corpus");
+        return;
+    }
+    if let Some(representation) = flag_value(&args, "--measure") {
+        measure(representation, &args, &field_map, rows);
+        return;
+    }
+
+    let mut sizes: Vec<usize> = flag_value(&args, "--sizes")
+        .unwrap_or(DEFAULT_SIZES)
+        .split(',')
+        .map(|size| {
+            size.trim()
+                .replace('_', "")
+                .parse()
+                .unwrap_or_else(|_| panic!("Invalid --sizes entry `{size}`"))
+        })
+        .collect();
+    sizes.sort_unstable();
+    sizes.dedup();
+
+    let name = Path::new(tsv_path)
+        .file_stem()
+        .map_or(tsv_path.to_string(), |stem| {
+            stem.to_string_lossy().to_string()
+        });
+    println!("=== Memory footprint: {} ===", name);
+    println!();
+    println!(
+        "{:>10}  {:<12} {:>12} {:>12} {:>14}",
+        "Candidates", "Held as", "Heap", "Resident", "Heap/candidate"
+    );
+    println!("{}", "-".repeat(64));
+
+    let index_path = env::temp_dir().join(format!("bench-memory-{}.bin", process::id()));
+    let index_path = index_path.to_string_lossy();
+    let run = |mode: &str, value: &str, size: usize| -> String {
+        let mut command = Command::new(env::current_exe().expect("Failed to locate bench-memory"));
+        command.args([
+            mode,
+            value,
+            "--tsv",
+            tsv_path,
+            "--index",
+            &index_path,
+            "--rows",
+        ]);
+        command.arg(size.to_string());
+        if let Some(map) = flag_value(&args, "--map") {
+            command.args(["--map", map]);
+        }
+        let output = command.output().expect("Failed to run a measurement");
+        if !output.status.success() {
+            panic!(
+                "Measuring {} at {} candidates failed:\n{}",
+                value,
+                size,
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+        String::from_utf8_lossy(&output.stdout).to_string()
+    };
+    let mut footprints = Vec::new();
+    'sizes: for &size in &sizes {
+        for representation in REPRESENTATIONS {
+            if representation == "serialized" {
+                run("--write-index", &index_path, size);
+            }
+            let footprint =
+                parse_measurement(representation, &run("--measure", representation, size))
+                    .expect("Measurement printed no footprint");
+            if footprint.candidates < size {
+                println!();
+                println!(
+                    "The corpus has {} rows; skipped {} candidates and up",
+                    footprint.candidates, size
+                );
+                break 'sizes;
+            }
+            println!(
+                "{:>10}  {:<12} {:>12} {:>12} {:>12.1} B",
+                footprint.candidates,
+                footprint.representation,
+                mib(footprint.heap_bytes),
+                footprint.resident_bytes.map_or("—".to_string(), mib),
+                footprint.heap_bytes_per_candidate()
+            );
+            footprints.push(footprint);
+        }
+    }
+    let _ = fs::remove_file(&*index_path);
+
+    let results_path = flag_value(&args, "--results");
+    let db_path = flag_value(&args, "--db");
+    if results_path.is_some() || db_path.is_some() {
+        let config: BTreeMap<String, String> = [
+            ("representations", REPRESENTATIONS.join(",")),
+            ("fields", FIELDS.join(",")),
+            (
+                "map",
+                flag_value(&args, "--map").unwrap_or_default().to_string(),
+            ),
+        ]
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), value))
+        .collect();
+        let results = ResultSet {
+            backend: "nucleo".to_string(),
+            run: flag_value(&args, "--run").unwrap_or_default().to_string(),
+            corpus: Some(name),
+            corpus_sha256: Manifest::read_for(tsv_path)
+                .ok()
+                .flatten()
+                .map(|m| m.sha256),
+            fingerprint: Some(Fingerprint::capture(&[], config)),
+            startup: None,
+            steady_state: None,
+            memory: footprints,
+            queries: Vec::new(),
+        };
+        println!();
+        if let Some(path) = results_path {
+            results.write(path).expect("Failed to write results file");
+            println!("Results written to {}", path);
+        }
+        if let Some(path) = db_path {
+            let mut db = Store::open(path).expect("Failed to open results database");
+            let id = db
+                .append(&results, store::now())
+                .expect("Failed to record results");
+            println!("Recorded as run {} in {}", id, path);
+        }
+    }
+}
+
+/// Builds one representation of the first `rows` rows and prints the rows
+/// held, heap bytes and resident bytes, tab-separated.
+fn measure(representation: &str, args: &[String], field_map: &FieldMap, rows: usize) {
+    if representation == "serialized" {
+        let path = flag_value(args, "--index").expect("--measure serialized needs --index PATH");
+        let (held, heap, resident) =
+            held(|| fs::read(path).expect("Failed to read serialized corpus"));
+        let prepared = PreparedCorpus::from_bytes(&held).expect("Invalid serialized corpus");
+        print_measurement(prepared.column("name").len(), heap, resident);
+        return;
+    }
+    if representation == "mapped" {
+        let path = flag_value(args, "--index").expect("--measure mapped needs --index PATH");
+        let (prepared, heap, resident) =
+            held(|| PreparedCorpus::open(path).expect("Invalid serialized corpus"));
+        print_measurement(prepared.column("name").len(), heap, resident);
+        return;
+    }
+    let tsv_path = flag_value(args, "--tsv").expect("--measure needs --tsv PATH");
+    let mut corpus = load_corpus_mapped(tsv_path, field_map).expect("Failed to read corpus");
+    corpus.truncate(rows);
+    let (heap, resident) = match representation {
+        "strings" => footprint(|| {
+            FIELDS.map(|field| {
+                (0..corpus.len())
+                    .map(|idx| corpus.text(idx, field).to_string())
+                    .collect::<Vec<String>>()
+            })
+        }),
+        "arena" => footprint(|| PreparedCorpus::new(&corpus, [])),
+        "utf32" => footprint(|| {
+            FIELDS.map(|field| {
+                (0..corpus.len())
+                    .map(|idx| Utf32String::from(corpus.text(idx, field)))
+                    .collect::<Vec<Utf32String>>()
+            })
+        }),
+        other => panic!(
+            "Unknown representation `{}` (supported: {})",
+            other,
+            REPRESENTATIONS.join(", ")
+        ),
+    };
+    print_measurement(corpus.len(), heap, resident);
+}
+
+fn print_measurement(rows: usize, heap: u64, resident: Option<u64>) {
+    let resident = resident.map_or("-".to_string(), |bytes| bytes.to_string());
+    println!("{}\t{}\t{}", rows, heap, resident);
+}
+
+/// What `build` returns, with the heap and resident bytes it holds.
+fn held<T>(build: impl FnOnce() -> T) -> (T, u64, Option<u64>) {
+    let (heap, resident) = (live_bytes(), resident_bytes());
+    let built = black_box(build());
+    let heap = live_bytes().saturating_sub(heap) as u64;
+    let resident = resident_bytes()
+        .zip(resident)
+        .map(|(after, before)| after.saturating_sub(before));
+    (built, heap, resident)
+}
+
+/// The heap and resident bytes held by what `build` returns.
+fn footprint<T>(build: impl FnOnce() -> T) -> (u64, Option<u64>) {
+    let (_, heap, resident) = held(build);
+    (heap, resident)
+}
+
+/// The footprint a `--measure` run printed.
+fn parse_measurement(representation: &str, output: &str) -> Option<Footprint> {
+    let mut fields = output.trim().split('\t');
+    Some(Footprint {
+        representation: representation.to_string(),
+        candidates: fields.next()?.parse().ok()?,
+        heap_bytes: fields.next()?.parse().ok()?,
+        resident_bytes: fields.next()?.parse().ok(),
+    })
+}
+
+fn mib(bytes: u64) -> String {
+    format!("{:.1} MiB", bytes as f64 / (1u64 << 20) as f64)
+}
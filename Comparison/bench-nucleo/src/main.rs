@@ -1,139 +1,655 @@
-use nucleo_matcher::pattern::{AtomKind, CaseMatching, Normalization, Pattern};
-use nucleo_matcher::{Config, Matcher, Utf32Str};
-use std::cmp::Reverse;
-use std::collections::BinaryHeap;
+use harness::affinity::Placement;
+use harness::budget::{BudgetStats, Deadline};
+use harness::cache::CacheBuster;
+use harness::cli::{flag_value, flag_values, has_flag, parsed_flag};
+use harness::corpus::{load_corpus_mapped, Corpus, FieldMap};
+use harness::cost::{CostModel, QueryCost};
+use harness::dedup::Duplicates;
+use harness::display;
+use harness::filter::{CompiledFilter, Filter};
+use harness::fingerprint::Fingerprint;
+use harness::heap::HeapProfile;
+use harness::incremental::Incremental;
+use harness::input;
+use harness::logging;
+use harness::manifest;
+use harness::metrics::percentile;
+use harness::prefilter::CharMask;
+use harness::prepared::PreparedCorpus;
+use harness::preset::Preset;
+use harness::queries::{load_queries, Query, CATEGORY_ORDER};
+use harness::resource_path;
+use harness::results::{Hit, QueryResult, Recheck, ResultSet, Startup, SteadyState};
+use harness::scratch::Scratch;
+use harness::store::{self, Store};
+use harness::topk::TopK;
+use harness::warmup::Warmup;
+use harness::window;
+use nucleo_matcher::pattern::Pattern;
+use nucleo_matcher::{Matcher, Utf32Str};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
 use std::env;
+#[cfg(feature = "flamegraph")]
 use std::fs;
-use std::path::PathBuf;
-use std::time::Instant;
+use std::io::{self, IsTerminal};
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant};
+use tracing::{info, info_span};
 
 const TOP_K: usize = 100;
+/// Results per query kept in a `--results` file.
+const RECORDED_RESULTS: usize = 10;
+/// Slowest queries re-run after the main pass, unless `--recheck` says otherwise.
+const DEFAULT_RECHECK: usize = 5;
 
-struct Instrument {
-    symbol: String,
+#[cfg(feature = "heap-profile")]
+#[global_allocator]
+static ALLOC: harness::heap::Alloc = harness::heap::Alloc;
+
+/// Headline numbers of one corpus's run, for the cross-corpus summary.
+struct CorpusSummary {
     name: String,
-    isin: String,
+    instruments: usize,
+    median_total_ms: f64,
+    /// Summed per-query medians for each category present, in report order.
+    category_median_ms: Vec<(&'static str, f64)>,
 }
 
-struct Query {
-    text: String,
-    field: String,
-    category: String,
-}
+fn main() {
+    // Resolve paths from arguments
+    let args: Vec<String> = env::args().collect();
+    logging::init(&args).unwrap_or_else(|e| panic!("Invalid logging flags: {e}"));
+    let corpora = corpora(&args);
+    let queries_path = flag_value(&args, "--queries")
+        .map(str::to_string)
+        .unwrap_or_else(|| resource_path("queries.tsv"));
 
-fn load_queries(path: &str) -> Vec<Query> {
-    let content = fs::read_to_string(path).expect("Failed to read queries TSV file");
-    let mut queries = Vec::new();
-    for line in content.lines() {
-        if line.is_empty() {
-            continue;
+    // Pin before loading anything, so the corpus is allocated on the same
+    // NUMA node as the thread that scans it
+    if let Some(placement) =
+        Placement::from_args(&args).unwrap_or_else(|e| panic!("Cannot pin cores: {e}"))
+    {
+        let core = placement
+            .pin(0)
+            .unwrap_or_else(|e| panic!("Cannot pin cores: {e}"));
+        println!("Pinned to core {} on NUMA node {}", core, placement.node);
+    }
+
+    // Verify dataset manifests, where present, before spending time on a run
+    for path in corpora.iter().map(|(_, path)| path).chain([&queries_path]) {
+        if let Some(manifest) = manifest::verify(path).expect("Dataset does not match its manifest")
+        {
+            println!("Verified {}: {}", path, manifest.summary());
         }
-        let cols: Vec<&str> = line.split('\t').collect();
-        if cols.len() >= 3 {
-            queries.push(Query {
-                text: cols[0].to_string(),
-                field: cols[1].to_string(),
-                category: cols[2].to_string(),
-            });
+    }
+
+    // A preset replaces the default matcher settings everywhere below;
+    // --save-preset writes the settings in effect
+    let preset = Preset::from_args(&args).unwrap_or_else(|e| panic!("Invalid preset: {e}"));
+    if flag_value(&args, "--preset").is_some() {
+        println!("Preset: {}", preset.name);
+    }
+    if let Some(path) = flag_value(&args, "--save-preset") {
+        preset.save(path).expect("Failed to write preset");
+        println!("Preset written to {}", path);
+    }
+
+    // Load queries from TSV
+    let queries = load_queries(&queries_path).expect("Failed to read queries TSV file");
+
+    let mut summaries = Vec::new();
+    for (name, path) in &corpora {
+        if corpora.len() > 1 {
+            println!();
+            println!("##### Corpus {} #####", name);
+            println!();
+        }
+        // With several corpora each gets its own results file, named after it
+        let results_path = flag_value(&args, "--results").map(|results| {
+            if corpora.len() > 1 {
+                per_corpus_path(results, name)
+            } else {
+                results.to_string()
+            }
+        });
+        // With --incremental, only queries added or changed since the
+        // baseline are timed; the others keep the baseline's results
+        let incremental = flag_value(&args, "--incremental").and_then(|baseline| {
+            let baseline = if corpora.len() > 1 {
+                per_corpus_path(baseline, name)
+            } else {
+                baseline.to_string()
+            };
+            incremental_against(&baseline, path, &queries, &args, &preset)
+        });
+        match &incremental {
+            Some(incremental) if incremental.stale.is_empty() => {
+                println!("No added or changed queries; keeping the baseline's results");
+                let iterations: usize = parsed_flag(&args, "--iterations", 5);
+                let results = ResultSet {
+                    backend: "nucleo".to_string(),
+                    run: flag_value(&args, "--run").unwrap_or_default().to_string(),
+                    corpus: Some(name.to_string()),
+                    corpus_sha256: corpus_sha256(path),
+                    fingerprint: Some(Fingerprint::capture(
+                        &[],
+                        matcher_config(&args, &preset, iterations),
+                    )),
+                    startup: None,
+                    steady_state: None,
+                    memory: Vec::new(),
+                    queries: incremental.merge(Vec::new()),
+                };
+                record(&results, results_path.as_deref(), flag_value(&args, "--db"));
+            }
+            Some(incremental) => summaries.push(bench_corpus(
+                name,
+                path,
+                &incremental.stale,
+                &args,
+                &preset,
+                results_path.as_deref(),
+                Some(incremental),
+            )),
+            None => summaries.push(bench_corpus(
+                name,
+                path,
+                &queries,
+                &args,
+                &preset,
+                results_path.as_deref(),
+                None,
+            )),
         }
     }
-    queries
+    if summaries.len() > 1 {
+        print_overall(&summaries, queries.len());
+    }
 }
 
-fn main() {
-    // Resolve paths from arguments
-    let args: Vec<String> = env::args().collect();
-    let tsv_path = if let Some(idx) = args.iter().position(|a| a == "--tsv") {
-        args.get(idx + 1)
-            .expect("--tsv requires a path argument")
-            .clone()
+/// Diffs `queries` against the baseline results at `baseline_path`, or
+/// returns `None`, after saying why, when the baseline cannot stand in for
+/// this run: it was another backend, or its settings or corpus differ.
+fn incremental_against(
+    baseline_path: &str,
+    tsv_path: &str,
+    queries: &[Query],
+    args: &[String],
+    preset: &Preset,
+) -> Option<Incremental> {
+    let baseline = ResultSet::read(baseline_path)
+        .unwrap_or_else(|e| panic!("Failed to read baseline results {baseline_path}: {e}"));
+    let iterations: usize = parsed_flag(args, "--iterations", 5);
+    let config =
+        Fingerprint::capture(&[], matcher_config(args, preset, iterations)).config_sha256();
+    let mismatch = if baseline.backend != "nucleo" {
+        Some(format!("it was run with {}", baseline.backend))
+    } else if baseline
+        .fingerprint
+        .as_ref()
+        .map(Fingerprint::config_sha256)
+        != Some(config)
+    {
+        Some("its matcher settings differ or were not recorded".to_string())
+    } else if let (Some(then), Some(now)) = (&baseline.corpus_sha256, corpus_sha256(tsv_path)) {
+        (*then != now).then(|| "the corpus has changed".to_string())
     } else {
-        let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
-        manifest_dir
-            .join("../../Resources/instruments-export.tsv")
-            .to_string_lossy()
-            .to_string()
+        None
     };
+    if let Some(reason) = mismatch {
+        println!("Not reusing {baseline_path}: {reason}; running every query");
+        return None;
+    }
+    let incremental = Incremental::against(&baseline, queries);
+    println!(
+        "Incremental against {}: {}",
+        baseline_path,
+        incremental.summary()
+    );
+    Some(incremental)
+}
 
-    let queries_path = if let Some(idx) = args.iter().position(|a| a == "--queries") {
-        args.get(idx + 1)
-            .expect("--queries requires a path argument")
-            .clone()
-    } else {
-        let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
-        manifest_dir
-            .join("../../Resources/queries.tsv")
-            .to_string_lossy()
-            .to_string()
-    };
+/// The corpus checksum from its manifest, if it has one.
+fn corpus_sha256(tsv_path: &str) -> Option<String> {
+    manifest::Manifest::read_for(tsv_path)
+        .ok()
+        .flatten()
+        .map(|m| m.sha256)
+}
 
-    // Load queries from TSV
-    let queries = load_queries(&queries_path);
+/// Writes `results` to `results_path` and appends them to the database at
+/// `db_path`, for whichever is given.
+fn record(results: &ResultSet, results_path: Option<&str>, db_path: Option<&str>) {
+    println!();
+    if let Some(path) = results_path {
+        results.write(path).expect("Failed to write results file");
+        println!("Results written to {}", path);
+    }
+    if let Some(path) = db_path {
+        let mut db = Store::open(path).expect("Failed to open results database");
+        let id = db
+            .append(results, store::now())
+            .expect("Failed to record results");
+        println!("Recorded as run {} in {}", id, path);
+    }
+}
 
-    // Load corpus into memory
-    println!("Loading corpus from {}...", tsv_path);
-    let content = fs::read_to_string(&tsv_path).expect("Failed to read TSV file");
-    let mut instruments: Vec<Instrument> = Vec::with_capacity(272_000);
+/// Every setting that shapes what the benchmark matched and measured, for
+/// the results file's fingerprint.
+fn matcher_config(args: &[String], preset: &Preset, iterations: usize) -> BTreeMap<String, String> {
+    let flag = |name: &str| flag_value(args, name).unwrap_or_default().to_string();
+    let mut config: BTreeMap<String, String> = [
+        ("matcher", "nucleo-matcher 0.3, Config::DEFAULT".to_string()),
+        ("case", "ignore".to_string()),
+        ("normalization", "smart".to_string()),
+        ("atom", "fuzzy".to_string()),
+        ("top_k", TOP_K.to_string()),
+        ("iterations", iterations.to_string()),
+        ("dedup", has_flag(args, "--dedup").to_string()),
+        ("filter", flag("--filter")),
+        ("map", flag("--map")),
+        (
+            "weighted_sampling",
+            has_flag(args, "--weighted-sampling").to_string(),
+        ),
+    ]
+    .into_iter()
+    .map(|(key, value)| (key.to_string(), value))
+    .collect();
+    // Recorded only when on, so runs without it keep their config hash
+    if has_flag(args, "--prefilter") {
+        config.insert("prefilter".to_string(), "charmask".to_string());
+    }
+    if let Some(order) = flag_value(args, "--atom-order") {
+        config.insert("atom_order".to_string(), order.to_string());
+    }
+    config.extend(warmup_policy(args).entries(&Warmup::IN_PROCESS));
+    // Likewise a preset's settings, which replace the defaults above
+    if flag_value(args, "--preset").is_some() {
+        config.insert("matcher".to_string(), "nucleo-matcher 0.3".to_string());
+        config.extend(preset.entries());
+    }
+    config
+}
 
-    for (i, line) in content.lines().enumerate() {
-        if i == 0 {
-            continue;
+/// How the benchmark is warmed up: `--warmup-queries N|all` (default all),
+/// `--warmup-index` and `--discard-first-pass`.
+fn warmup_policy(args: &[String]) -> Warmup {
+    Warmup {
+        queries: flag_value(args, "--warmup-queries").map_or(Warmup::IN_PROCESS.queries, |n| {
+            Warmup::parse_queries(n)
+                .unwrap_or_else(|e| panic!("--warmup-queries has an invalid value: {e}"))
+        }),
+        index: has_flag(args, "--warmup-index"),
+        discard_first: has_flag(args, "--discard-first-pass"),
+    }
+}
+
+/// Prints the scratch memory each category's scans needed at most, and the
+/// high-water mark over the whole query set.
+fn print_scratch(queries: &[Query], categories: &[&str], scratch: &[Scratch]) {
+    println!();
+    println!("=== Scratch memory (high-water mark per scan) ===");
+    println!();
+    println!(
+        "{:<22} {:>12} {:>12} {:>12} {:>12}",
+        "Category", "UTF-32 chars", "Heap entries", "Max bytes", "Median bytes"
+    );
+    println!("{}", "-".repeat(74));
+    for cat in categories {
+        let in_category: Vec<Scratch> = queries
+            .iter()
+            .zip(scratch)
+            .filter(|(q, _)| q.category == *cat)
+            .map(|(_, s)| *s)
+            .collect();
+        let peak = in_category
+            .iter()
+            .fold(Scratch::default(), |peak, s| peak.max(*s));
+        let mut bytes: Vec<usize> = in_category.iter().map(Scratch::bytes).collect();
+        bytes.sort_unstable();
+        println!(
+            "{:<22} {:>12} {:>12} {:>12} {:>12}",
+            cat,
+            peak.utf32_chars,
+            peak.heap_entries,
+            in_category
+                .iter()
+                .map(Scratch::bytes)
+                .max()
+                .unwrap_or_default(),
+            bytes[bytes.len() / 2]
+        );
+    }
+    let peak = scratch
+        .iter()
+        .fold(Scratch::default(), |peak, s| peak.max(*s));
+    println!();
+    println!(
+        "High-water mark: UTF-32 buffer {} chars ({} bytes) + top-K heap {} entries ({} bytes) = {} bytes",
+        peak.utf32_chars,
+        peak.utf32_bytes(),
+        peak.heap_entries,
+        peak.heap_bytes(),
+        peak.bytes()
+    );
+    println!("(nucleo's matcher slab is fixed and allocated once per matcher, on top of this)");
+}
+
+/// Prints the work each category's scans did, summed over its queries, and
+/// the per-step costs fitted to every query's counts and median time.
+///
+/// `estimated` holds each query's cost as the column's character table
+/// predicts it before scanning; the model's predictions from those show how
+/// well a query's latency can be told in advance.
+fn print_query_costs(
+    queries: &[Query],
+    categories: &[&str],
+    costs: &[QueryCost],
+    estimated: &[QueryCost],
+    timings_ms: &[Vec<f64>],
+) {
+    println!();
+    println!("=== Query cost (work per scan, summed per category) ===");
+    println!();
+    println!(
+        "{:<22} {:>10} {:>8} {:>10} {:>9} {:>10} {:>9}",
+        "Category", "Scanned", "Skipped", "Scored", "Matched", "Estimated", "Inserted"
+    );
+    println!("{}", "-".repeat(83));
+    for cat in categories {
+        let mut total = QueryCost::default();
+        let mut estimate = QueryCost::default();
+        for (qi, _) in queries
+            .iter()
+            .enumerate()
+            .filter(|(_, q)| q.category == *cat)
+        {
+            total += costs[qi];
+            estimate += estimated[qi];
         }
-        let cols: Vec<&str> = line.split('\t').collect();
-        if cols.len() >= 3 {
-            instruments.push(Instrument {
-                symbol: cols[0].to_string(),
-                name: cols[1].to_string(),
-                isin: cols[2].to_string(),
-            });
+        println!(
+            "{:<22} {:>10} {:>7.1}% {:>10} {:>9} {:>10} {:>9}",
+            cat,
+            total.scanned,
+            total.skip_ratio() * 100.0,
+            total.scored,
+            total.matched,
+            estimate.matched,
+            total.inserted
+        );
+    }
+
+    let samples: Vec<(QueryCost, f64)> = costs
+        .iter()
+        .zip(timings_ms)
+        .map(|(cost, times)| {
+            let mut sorted = times.clone();
+            sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            (*cost, sorted[sorted.len() / 2])
+        })
+        .collect();
+    println!();
+    match CostModel::fit(&samples) {
+        Some(model) => {
+            println!(
+                "Cost model: {:.2}ns per candidate scanned + {:.2}ns per score + {:.1}ns per match + {:.1}ns per insertion",
+                model.ns_per_scanned, model.ns_per_scored, model.ns_per_matched, model.ns_per_inserted
+            );
+            println!(
+                "Explains {:.1}% of the variance in per-query medians",
+                model.r_squared * 100.0
+            );
+            let predicted: Vec<(QueryCost, f64)> = estimated
+                .iter()
+                .zip(&samples)
+                .map(|(cost, (_, ms))| (*cost, *ms))
+                .collect();
+            println!(
+                "From character-table estimates before scanning: explains {:.1}%",
+                model.explained(&predicted) * 100.0
+            );
+        }
+        None => println!("Cost model: too few queries to fit"),
+    }
+}
+
+/// Corpora to benchmark as (name, path) pairs: every `--tsv` flag, the
+/// entries of a `--corpora` file, or the bundled export.
+///
+/// A corpora file lists one corpus per line as `path` or `name \t path`;
+/// blank lines and `#` comments are skipped. Paths may use Windows
+/// separators. Unnamed corpora take their file name.
+fn corpora(args: &[String]) -> Vec<(String, String)> {
+    let mut paths: Vec<(Option<String>, String)> = flag_values(args, "--tsv")
+        .into_iter()
+        .map(|path| (None, path.to_string()))
+        .collect();
+    if let Some(list) = flag_value(args, "--corpora") {
+        let content = input::read_to_string(list).expect("Failed to read corpora file");
+        for line in content.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            match line.split_once('\t') {
+                Some((name, path)) => paths.push((
+                    Some(name.trim().to_string()),
+                    input::native_path(path.trim()),
+                )),
+                None => paths.push((None, input::native_path(line))),
+            }
         }
     }
+    if paths.is_empty() {
+        paths.push((None, resource_path("instruments-export.tsv")));
+    }
+    paths
+        .into_iter()
+        .map(|(name, path)| {
+            let name = name.unwrap_or_else(|| {
+                Path::new(&path)
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_else(|| path.clone())
+            });
+            (name, path)
+        })
+        .collect()
+}
+
+/// `results.json` for corpus `nasdaq` becomes `results.nasdaq.json`.
+fn per_corpus_path(path: &str, corpus: &str) -> String {
+    let corpus: String = corpus
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    match path.rsplit_once('.') {
+        Some((stem, ext)) if !ext.contains(['/', '\\']) => format!("{stem}.{corpus}.{ext}"),
+        _ => format!("{path}.{corpus}"),
+    }
+}
+
+/// Benchmarks the query set against one corpus, printing its full report.
+fn bench_corpus(
+    name: &str,
+    tsv_path: &str,
+    queries: &[Query],
+    args: &[String],
+    preset: &Preset,
+    results_path: Option<&str>,
+    incremental: Option<&Incremental>,
+) -> CorpusSummary {
+    let _run = info_span!("run", backend = "nucleo", corpus = name).entered();
+
+    // Load corpus into memory
+    println!("Loading corpus from {}...", tsv_path);
+    let mut startup = Startup::default();
+    let load_start = Instant::now();
+    let field_map: FieldMap = parsed_flag(args, "--map", FieldMap::default());
+    let mut instruments = load_corpus_mapped(tsv_path, &field_map).expect("Failed to read corpus");
     println!("Loaded {} instruments", instruments.len());
+    if !instruments.repairs().is_empty() {
+        println!("Encoding: {}", instruments.repairs().summary());
+    }
+    let duplicates = Duplicates::find(&instruments);
+    println!("Duplicates: {}", duplicates.summary());
+    if has_flag(args, "--dedup") {
+        instruments = duplicates.remove_from(&instruments);
+        println!("Deduplicated to {} instruments", instruments.len());
+    }
+    startup.push("load", load_start.elapsed().as_secs_f64() * 1000.0);
 
-    // Pre-extract candidate arrays
-    let symbol_candidates: Vec<&str> = instruments.iter().map(|i| i.symbol.as_str()).collect();
-    let name_candidates: Vec<&str> = instruments.iter().map(|i| i.name.as_str()).collect();
-    let isin_candidates: Vec<&str> = instruments.iter().map(|i| i.isin.as_str()).collect();
+    let filter: Filter = parsed_flag(args, "--filter", Filter::default());
+    let filter = (!filter.is_empty()).then(|| {
+        filter
+            .compile(&instruments)
+            .unwrap_or_else(|e| panic!("Invalid filter: {e}"))
+    });
+
+    // Each searched field gets an arena of its own, so a scan reads one
+    // contiguous run of text instead of striding over whole rows. With
+    // --prefilter, each candidate's character mask is computed alongside, so
+    // a scan rejects candidates lacking a query letter without scoring them
+    // --index maps saved columns instead, shared with every other process
+    // reading the same index
+    let prepare_start = Instant::now();
+    let prefilter = has_flag(args, "--prefilter");
+    let show_progress = io::stderr().is_terminal();
+    let prepared = match flag_value(args, "--index") {
+        Some(path) => {
+            // --trust-index skips the checksum pass, for an index known intact
+            let open = if has_flag(args, "--trust-index") {
+                PreparedCorpus::open_trusted
+            } else {
+                PreparedCorpus::open
+            };
+            let prepared = open(path).unwrap_or_else(|e| panic!("Failed to open index: {e}"));
+            prepared
+                .check(&instruments, queries.iter().map(|q| q.field.as_str()))
+                .unwrap_or_else(|e| panic!("Index {path} does not match the corpus: {e}"));
+            prepared
+        }
+        None => PreparedCorpus::builder(&instruments)
+            .fields(queries.iter().map(|q| q.field.as_str()))
+            .threads(parsed_flag(
+                args,
+                "--prepare-threads",
+                thread::available_parallelism().map_or(1, |n| n.get()),
+            ))
+            .masks(prefilter)
+            .progress(|p| {
+                if show_progress {
+                    eprint!(
+                        "\rPreparing {}: {:?} {}%\x1b[K",
+                        p.field,
+                        p.stage,
+                        100 * p.done / p.total.max(1)
+                    );
+                }
+            })
+            .build(),
+    };
+    if show_progress {
+        eprint!("\r\x1b[K");
+    }
+    startup.push("prepare", prepare_start.elapsed().as_secs_f64() * 1000.0);
+    println!("Prepared {}", prepared.summary());
+    println!(
+        "Startup: {:.1}ms (load {:.1}ms, prepare {:.1}ms)",
+        startup.total_ms(),
+        startup.phase_ms("load").unwrap_or_default(),
+        startup.phase_ms("prepare").unwrap_or_default()
+    );
+
+    // A mapped index holds no masks, so they are computed here
+    let masks: Option<HashMap<&str, Cow<[CharMask]>>> = prefilter.then(|| {
+        prepared
+            .fields()
+            .map(|field| {
+                let column = prepared.column(field);
+                let masks = column.masks().map_or_else(
+                    || Cow::Owned(column.iter().map(CharMask::of).collect()),
+                    Cow::Borrowed,
+                );
+                (field, masks)
+            })
+            .collect()
+    });
 
     println!("Running {} queries", queries.len());
     println!();
 
-    let iterations: usize = if let Some(idx) = args.iter().position(|a| a == "--iterations") {
-        args.get(idx + 1)
-            .expect("--iterations requires a number")
-            .parse()
-            .expect("--iterations must be a positive integer")
+    let iterations: usize = parsed_flag(args, "--iterations", 5);
+
+    // Per-query weights for the aggregates, normalised to a mean of 1 so a
+    // weighted total still reads as "time for this many queries"
+    let weighted = has_flag(args, "--weighted-sampling");
+    let weights: Vec<f64> = if weighted {
+        let total: u64 = queries.iter().map(|q| q.frequency).sum();
+        let mean = total as f64 / queries.len() as f64;
+        queries.iter().map(|q| q.frequency as f64 / mean).collect()
     } else {
-        5
+        vec![1.0; queries.len()]
     };
 
-    // Warmup
+    // Warmup: by default every query once, untimed. --warmup-index first
+    // reads the columns and prefilter masks through, so the first queries
+    // do not fault their pages in
+    let warmup = warmup_policy(args);
     {
-        let mut matcher = Matcher::new(Config::DEFAULT);
+        if warmup.index {
+            for field in prepared.fields() {
+                prepared.column(field).touch();
+            }
+            for column_masks in masks.iter().flat_map(|masks| masks.values()) {
+                std::hint::black_box(column_masks.iter().fold(0, |acc, mask| acc ^ mask.0));
+            }
+        }
+        let mut matcher = Matcher::new(preset.config());
         let mut buf = Vec::new();
-        for q in &queries {
-            let candidates = if q.field == "symbol" {
-                &symbol_candidates
-            } else if q.field == "isin" {
-                &isin_candidates
-            } else {
-                &name_candidates
-            };
-            let pattern =
-                Pattern::new(&q.text, CaseMatching::Ignore, Normalization::Smart, AtomKind::Fuzzy);
-            for candidate in candidates {
+        let warmup_queries = warmup.query_count(queries.len());
+        for q in &queries[..warmup_queries] {
+            let pattern = preset.pattern(&q.text);
+            for candidate in prepared.column(&q.field) {
                 buf.clear();
-                let haystack = Utf32Str::new(candidate, &mut buf);
-                let _ = pattern.score(haystack, &mut matcher);
+                let _ = pattern.score(Utf32Str::new(candidate, &mut buf), &mut matcher);
             }
         }
-        println!("Warmup complete");
+        println!(
+            "Warmup complete ({} of {} queries{}{})",
+            warmup_queries,
+            queries.len(),
+            if warmup.index {
+                ", index read through"
+            } else {
+                ""
+            },
+            if warmup.discard_first {
+                ", first pass discarded"
+            } else {
+                ""
+            }
+        );
     }
+    let mut query_scratch: Vec<Scratch> = vec![Scratch::default(); queries.len()];
 
     // Per-query timing storage
     let query_count = queries.len();
     let mut query_timings_ms: Vec<Vec<f64>> = vec![Vec::new(); query_count];
     let mut query_match_counts: Vec<usize> = vec![0; query_count];
+    let mut query_top_results: Vec<Vec<(u32, usize)>> = vec![Vec::new(); query_count];
+    let mut query_costs: Vec<QueryCost> = vec![QueryCost::default(); query_count];
+    // Each query's whole top-K from the first pass, and how later passes differed from it
+    let mut first_tops: Vec<Vec<(u32, usize)>> = vec![Vec::new(); query_count];
+    let mut top_changes: Vec<TopChanges> = vec![TopChanges::default(); query_count];
     let mut iteration_totals_ms: Vec<f64> = Vec::new();
 
     println!();
@@ -144,54 +660,136 @@ fn main() {
     );
     println!();
 
-    let mut matcher = Matcher::new(Config::DEFAULT);
-    for iter in 0..iterations {
+    // With --profile heap, dhat records every allocation the timed passes make
+    let heap_profile = match flag_value(args, "--profile") {
+        Some("heap") => HeapProfile::start(&per_corpus_path("dhat-heap.json", name)),
+        Some(other) => panic!("Unknown profile `{}` (supported: heap)", other),
+        None => None,
+    };
+
+    // Multi-atom patterns try their most selective atom first, as
+    // estimated from the column's character table, unless told otherwise
+    let order_atoms = match flag_value(args, "--atom-order") {
+        None | Some("selectivity") => true,
+        Some("written") => false,
+        Some(other) => panic!(
+            "Unknown atom order `{}` (supported: selectivity, written)",
+            other
+        ),
+    };
+    let mut matcher = Matcher::new(preset.config());
+    // A discarded first pass runs like the others, but nothing of it is kept
+    let discarded = warmup.timed_passes(iterations) - iterations;
+    for pass in 0..warmup.timed_passes(iterations) {
+        let kept = pass.checked_sub(discarded);
         let mut buf = Vec::new();
         let iter_start = Instant::now();
 
         for (qi, q) in queries.iter().enumerate() {
-            let candidates = if q.field == "symbol" {
-                &symbol_candidates
-            } else if q.field == "isin" {
-                &isin_candidates
-            } else {
-                &name_candidates
-            };
+            let candidates = prepared.column(&q.field);
+            let column_masks = masks.as_ref().map(|masks| &masks[q.field.as_str()]);
             let q_start = Instant::now();
 
-            let pattern =
-                Pattern::new(&q.text, CaseMatching::Ignore, Normalization::Smart, AtomKind::Fuzzy);
+            let mut pattern = preset.pattern(&q.text);
+            if order_atoms {
+                candidates.frequency().order_atoms(&mut pattern);
+            }
+            let query_mask = CharMask::of(&q.text);
             let mut match_count: usize = 0;
-            let mut heap: BinaryHeap<Reverse<(u32, usize)>> = BinaryHeap::with_capacity(TOP_K + 1);
+            let mut top = TopK::new(TOP_K);
+            let mut cost = QueryCost {
+                scanned: candidates.len(),
+                ..QueryCost::default()
+            };
 
             for (ci, candidate) in candidates.iter().enumerate() {
+                if column_masks.is_some_and(|masks| !masks[ci].may_contain(query_mask)) {
+                    cost.prefiltered += 1;
+                    continue;
+                }
                 buf.clear();
                 let haystack = Utf32Str::new(candidate, &mut buf);
                 if let Some(score) = pattern.score(haystack, &mut matcher) {
                     match_count += 1;
-                    heap.push(Reverse((score, ci)));
-                    if heap.len() > TOP_K {
-                        heap.pop(); // remove the lowest score
+                    if top.push(score, ci) {
+                        cost.inserted += 1;
                     }
                 }
             }
 
-            // Drain heap into a sorted Vec (highest score first)
-            let mut top_results: Vec<(u32, usize)> = heap.into_iter().map(|Reverse(x)| x).collect();
-            top_results.sort_by(|a, b| b.0.cmp(&a.0));
+            let heap_entries = top.len();
+            let mut top_results = top.into_sorted();
 
             let q_elapsed = q_start.elapsed();
             let q_ms = q_elapsed.as_secs_f64() * 1000.0;
+            let Some(iter) = kept else { continue };
             query_timings_ms[qi].push(q_ms);
+            if iter > 0 {
+                top_changes[qi].compare(&first_tops[qi], &top_results);
+            }
             if iter == 0 {
+                first_tops[qi] = top_results.clone();
+                cost.scored = cost.scanned - cost.prefiltered;
+                cost.matched = match_count;
+                query_costs[qi] = cost;
                 query_match_counts[qi] = match_count;
+                query_scratch[qi].heap_entries = heap_entries;
+                preset.rank(&mut top_results, |idx| candidates.get(idx));
+                top_results.truncate(RECORDED_RESULTS);
+                query_top_results[qi] = top_results;
             }
         }
 
         let iter_elapsed = iter_start.elapsed();
         let iter_ms = iter_elapsed.as_secs_f64() * 1000.0;
+        let Some(iter) = kept else {
+            println!("Discarded pass: {:.1}ms total", iter_ms);
+            continue;
+        };
         iteration_totals_ms.push(iter_ms);
         println!("Iteration {}: {:.1}ms total", iter + 1, iter_ms);
+        info!(iteration = iter + 1, total_ms = iter_ms, "pass");
+    }
+    // The longest candidate each query converts to UTF-32, measured after
+    // the timed passes so that neither they nor the warmup pay for it
+    {
+        let mut buf = Vec::new();
+        for (qi, q) in queries.iter().enumerate() {
+            for candidate in prepared.column(&q.field) {
+                buf.clear();
+                let _ = Utf32Str::new(candidate, &mut buf);
+                query_scratch[qi].utf32_chars = query_scratch[qi].utf32_chars.max(buf.len());
+            }
+        }
+    }
+    for (qi, q) in queries.iter().enumerate() {
+        let mut sorted = query_timings_ms[qi].clone();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let _query = info_span!(
+            "query",
+            query = %q.text,
+            field = %q.field,
+            category = %q.category
+        )
+        .entered();
+        info!(
+            duration_ms = sorted[iterations / 2],
+            min_ms = sorted[0],
+            matches = query_match_counts[qi],
+            scored = query_costs[qi].scored,
+            prefiltered = query_costs[qi].prefiltered,
+            scratch_bytes = query_scratch[qi].bytes(),
+            top_stable = top_changes[qi].is_stable(),
+            "timed"
+        );
+    }
+
+    if let Some(profile) = heap_profile {
+        let report = profile.finish().expect("Failed to read heap profile");
+        println!();
+        println!("=== Heap profile of the scoring loop (timings above are slowed by it) ===");
+        println!();
+        println!("{}", report);
     }
 
     // Results
@@ -199,6 +797,20 @@ fn main() {
     println!("=== Results ===");
     println!();
 
+    // Weighted totals are rebuilt from per-query times, since each iteration
+    // ran every query once regardless of its frequency
+    if weighted {
+        println!("Aggregates weighted by query frequency");
+        println!();
+        iteration_totals_ms = (0..iterations)
+            .map(|iter| {
+                (0..query_count)
+                    .map(|qi| weights[qi] * query_timings_ms[qi][iter])
+                    .sum()
+            })
+            .collect();
+    }
+
     let mut sorted_totals = iteration_totals_ms.clone();
     sorted_totals.sort_by(|a, b| a.partial_cmp(b).unwrap());
     let median_total = sorted_totals[iterations / 2];
@@ -224,21 +836,9 @@ fn main() {
     println!();
 
     // Per-category summary — use preferred order, skip missing
-    let preferred_categories = [
-        "exact_symbol",
-        "exact_name",
-        "exact_isin",
-        "prefix",
-        "typo",
-        "substring",
-        "multi_word",
-        "symbol_spaces",
-        "abbreviation",
-    ];
-
     let category_set: std::collections::HashSet<&str> =
         queries.iter().map(|q| q.category.as_str()).collect();
-    let categories: Vec<&str> = preferred_categories
+    let categories: Vec<&str> = CATEGORY_ORDER
         .iter()
         .filter(|c| category_set.contains(**c))
         .copied()
@@ -250,6 +850,7 @@ fn main() {
     );
     println!("{}", "-".repeat(60));
 
+    let mut category_median_ms = Vec::new();
     for cat in &categories {
         let indices: Vec<usize> = queries
             .iter()
@@ -266,20 +867,22 @@ fn main() {
             .map(|&qi| {
                 let mut sorted = query_timings_ms[qi].clone();
                 sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
-                sorted[iterations / 2]
+                weights[qi] * sorted[iterations / 2]
             })
             .sum();
         let total_min: f64 = indices
             .iter()
             .map(|&qi| {
-                query_timings_ms[qi]
-                    .iter()
-                    .cloned()
-                    .reduce(f64::min)
-                    .unwrap()
+                weights[qi]
+                    * query_timings_ms[qi]
+                        .iter()
+                        .cloned()
+                        .reduce(f64::min)
+                        .unwrap()
             })
             .sum();
         let total_matches: usize = indices.iter().map(|&qi| query_match_counts[qi]).sum();
+        category_median_ms.push((*cat, total_median));
 
         println!(
             "{:<22} {:>8} {:>8.2} {:>8.2} {:>8}",
@@ -306,25 +909,1314 @@ fn main() {
         sa.sort_by(|x, y| x.partial_cmp(y).unwrap());
         let mut sb = query_timings_ms[b].clone();
         sb.sort_by(|x, y| x.partial_cmp(y).unwrap());
-        sb[iterations / 2]
-            .partial_cmp(&sa[iterations / 2])
-            .unwrap()
+        sb[iterations / 2].partial_cmp(&sa[iterations / 2]).unwrap()
     });
 
-    for qi in sorted_indices {
+    for &qi in &sorted_indices {
         let q = &queries[qi];
         let mut sorted = query_timings_ms[qi].clone();
         sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
         let med = sorted[iterations / 2];
         let mn = sorted[0];
-        let display_query = if q.text.len() > 30 {
-            format!("{}...", &q.text[..27])
+        println!(
+            "{} {:<8} {:<16} {:>8.2} {:>8.2} {:>8}",
+            display::cell(&q.text, 32),
+            q.field,
+            q.category,
+            med,
+            mn,
+            query_match_counts[qi]
+        );
+    }
+
+    // The same counts as the character tables predict them, matches
+    // bounded by the rarest character or pair of the query's atoms
+    let estimated_costs: Vec<QueryCost> = queries
+        .iter()
+        .zip(&query_costs)
+        .map(|(q, cost)| {
+            let column = prepared.column(&q.field);
+            let share = column
+                .frequency()
+                .estimate_pattern(&preset.pattern(&q.text));
+            let matched = ((share * cost.scored as f64).round() as usize).min(cost.scored);
+            QueryCost {
+                matched,
+                inserted: matched.min(TOP_K),
+                ..*cost
+            }
+        })
+        .collect();
+    print_query_costs(
+        queries,
+        &categories,
+        &query_costs,
+        &estimated_costs,
+        &query_timings_ms,
+    );
+    print_scratch(queries, &categories, &query_scratch);
+    print_top_stability(queries, &top_changes, iterations);
+
+    // Re-run the slowest queries on their own, since one noisy moment during
+    // the main pass can make an ordinary query look slow
+    let recheck_count: usize = parsed_flag(args, "--recheck", DEFAULT_RECHECK);
+    let recheck_iterations: usize = parsed_flag(args, "--recheck-iterations", 100);
+    let mut rechecks: HashMap<usize, Recheck> = HashMap::new();
+    if recheck_count > 0 && recheck_iterations > 0 {
+        let slowest = &sorted_indices[..recheck_count.min(query_count)];
+        let flamegraph_dir = flag_value(args, "--recheck-flamegraph");
+        rechecks = recheck_slowest(
+            queries,
+            slowest,
+            &prepared,
+            preset,
+            recheck_iterations,
+            flamegraph_dir,
+        );
+
+        println!();
+        println!(
+            "=== Slow-query recheck: {} queries x {} iterations ===",
+            slowest.len(),
+            recheck_iterations
+        );
+        println!();
+        println!(
+            "{:<32} {:<8} {:>9} {:>9} {:>9} {:>9} {:>9}  Note",
+            "Query", "Field", "First(ms)", "Med(ms)", "P90(ms)", "P99(ms)", "Std(ms)"
+        );
+        println!("{}", "-".repeat(106));
+        for &qi in slowest {
+            let q = &queries[qi];
+            let recheck = &rechecks[&qi];
+            let mut sorted = query_timings_ms[qi].clone();
+            sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            let first = sorted[iterations / 2];
+            // A first-pass median well off the refined one was noise
+            let drift = first / recheck.median_ms - 1.0;
+            let note = if drift.abs() > 0.2 {
+                format!("first pass {:+.0}%", drift * 100.0)
+            } else {
+                String::new()
+            };
+            println!(
+                "{} {:<8} {:>9.3} {:>9.3} {:>9.3} {:>9.3} {:>9.3}  {}",
+                display::cell(&q.text, 32),
+                q.field,
+                first,
+                recheck.median_ms,
+                recheck.p90_ms,
+                recheck.p99_ms,
+                recheck.stddev_ms,
+                note
+            );
+            if let Some(path) = &recheck.flamegraph {
+                println!("{:<32} flamegraph: {}", "", path);
+            }
+        }
+    }
+
+    // Exact lookups, typos and multi-word queries spend their time in
+    // different places, which the per-query profiles alone do not show
+    if let Some(dir) = flag_value(args, "--recheck-flamegraph") {
+        println!();
+        println!("=== Category flamegraphs: {} passes each ===", iterations);
+        println!();
+        println!("{:<22} {:>8}  Flamegraph", "Category", "Queries");
+        println!("{}", "-".repeat(60));
+        for (category, count, path) in
+            profile_categories(queries, &categories, &prepared, preset, iterations, dir)
+        {
+            println!(
+                "{:<22} {:>8}  {}",
+                category,
+                count,
+                path.as_deref().unwrap_or("—")
+            );
+        }
+    }
+
+    let db_path = flag_value(args, "--db");
+    if results_path.is_some() || db_path.is_some() {
+        let mut results = ResultSet {
+            backend: "nucleo".to_string(),
+            run: flag_value(args, "--run").unwrap_or_default().to_string(),
+            corpus: Some(name.to_string()),
+            corpus_sha256: corpus_sha256(tsv_path),
+            fingerprint: Some(Fingerprint::capture(
+                &[],
+                matcher_config(args, preset, iterations),
+            )),
+            startup: Some(startup),
+            steady_state: SteadyState::from_passes(&iteration_totals_ms, query_count),
+            memory: Vec::new(),
+            queries: queries
+                .iter()
+                .enumerate()
+                .map(|(qi, q)| {
+                    let mut sorted = query_timings_ms[qi].clone();
+                    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+                    let mut result = QueryResult::new(q);
+                    result.median_ms = Some(sorted[iterations / 2]);
+                    result.min_ms = Some(sorted[0]);
+                    result.matches = Some(query_match_counts[qi]);
+                    result.recheck = rechecks.get(&qi).cloned();
+                    result.cost = Some(query_costs[qi]);
+                    result.results = Some(
+                        query_top_results[qi]
+                            .iter()
+                            .map(|&(score, idx)| {
+                                let inst = instruments.get(idx);
+                                Hit {
+                                    symbol: inst.symbol.to_string(),
+                                    name: inst.name.to_string(),
+                                    score: Some(score as f64),
+                                }
+                            })
+                            .collect(),
+                    );
+                    result
+                })
+                .collect(),
+        };
+        // Unchanged queries take their results from the baseline; the
+        // passes covered only the stale ones, so say nothing of a full pass
+        if let Some(incremental) = incremental {
+            results.queries = incremental.merge(results.queries);
+            results.steady_state = None;
+        }
+        record(&results, results_path, db_path);
+    }
+
+    if let Some(filter) = &filter {
+        bench_filtered(queries, &instruments, filter, preset, iterations);
+    }
+    let budgets_ms: Vec<f64> = flag_values(args, "--budget-ms")
+        .into_iter()
+        .map(|ms| {
+            ms.parse()
+                .unwrap_or_else(|_| panic!("Invalid --budget-ms `{ms}`"))
+        })
+        .collect();
+    if !budgets_ms.is_empty() {
+        bench_budgets(
+            queries,
+            &prepared,
+            masks.as_ref(),
+            preset,
+            iterations,
+            &budgets_ms,
+        );
+    }
+    if has_flag(args, "--prefetch") {
+        let batch: usize = parsed_flag(args, "--prefetch-batch", 32);
+        bench_prefetch(queries, &prepared, preset, iterations, batch.max(1));
+    }
+    if has_flag(args, "--atoms") {
+        bench_atoms(queries, &prepared, preset, iterations);
+    }
+    if has_flag(args, "--highlights") {
+        bench_highlights(queries, &prepared, preset, iterations);
+    }
+    if has_flag(args, "--batch-api") {
+        let threads: usize = parsed_flag(
+            args,
+            "--batch-threads",
+            thread::available_parallelism().map_or(1, |n| n.get()),
+        );
+        bench_batch_api(queries, &prepared, preset, iterations, threads.max(1));
+    }
+    if has_flag(args, "--cold-cache") {
+        let mb: usize = parsed_flag(args, "--cache-buster-mb", CacheBuster::DEFAULT_MB);
+        bench_cold_cache(queries, &categories, &prepared, preset, iterations, mb);
+    }
+    let long_text: Vec<&Query> = queries
+        .iter()
+        .filter(|q| q.category == "long_text")
+        .collect();
+    if !long_text.is_empty() {
+        bench_long_text(&long_text, &instruments, preset, iterations);
+    }
+
+    CorpusSummary {
+        name: name.to_string(),
+        instruments: instruments.len(),
+        median_total_ms: median_total,
+        category_median_ms,
+    }
+}
+
+/// Times long-text queries with nucleo against the best-window matcher on
+/// the same column. Subsequence matching accepts scattered letters in long
+/// text, so the match counts matter as much as the times.
+fn bench_long_text(queries: &[&Query], instruments: &Corpus, preset: &Preset, iterations: usize) {
+    println!();
+    println!(
+        "=== Long-text queries: nucleo vs best window ({} queries) ===",
+        queries.len()
+    );
+    println!();
+    println!(
+        "{:<12} {:>10} {:>10} {:>10}",
+        "Matcher", "Med(ms)", "Min(ms)", "Matches"
+    );
+    println!("{}", "-".repeat(46));
+
+    let mut matcher = Matcher::new(preset.config());
+    let mut buf = Vec::new();
+    for label in ["nucleo", "window"] {
+        let mut totals_ms: Vec<f64> = Vec::with_capacity(iterations);
+        let mut matches = 0;
+        for _ in 0..iterations {
+            let start = Instant::now();
+            matches = 0;
+            for q in queries {
+                let pattern = preset.pattern(&q.text);
+                let max_distance = window::default_max_distance(&q.text);
+                for idx in 0..instruments.len() {
+                    let text = instruments.text(idx, &q.field);
+                    let matched = if label == "nucleo" {
+                        buf.clear();
+                        pattern
+                            .score(Utf32Str::new(text, &mut buf), &mut matcher)
+                            .is_some()
+                    } else {
+                        window::best_window(&q.text, text, max_distance).is_some()
+                    };
+                    if matched {
+                        matches += 1;
+                    }
+                }
+            }
+            totals_ms.push(start.elapsed().as_secs_f64() * 1000.0);
+        }
+        totals_ms.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        println!(
+            "{:<12} {:>10.1} {:>10.1} {:>10}",
+            label,
+            totals_ms[iterations / 2],
+            totals_ms[0],
+            matches
+        );
+    }
+}
+
+/// Compares the corpora of a multi-corpus run side by side and totals them.
+fn print_overall(summaries: &[CorpusSummary], query_count: usize) {
+    println!();
+    println!("=== Overall ({} corpora) ===", summaries.len());
+    println!();
+    println!(
+        "{:<24} {:>12} {:>10} {:>12} {:>10}",
+        "Corpus", "Instruments", "Total(ms)", "Cand/sec", "Query(ms)"
+    );
+    println!("{}", "-".repeat(72));
+    let row = |name: &str, instruments: usize, total_ms: f64| {
+        let scored = instruments as f64 * query_count as f64;
+        println!(
+            "{:<24} {:>12} {:>10.1} {:>11.0}M {:>10.2}",
+            name,
+            instruments,
+            total_ms,
+            scored / (total_ms / 1000.0) / 1_000_000.0,
+            total_ms / query_count as f64
+        );
+    };
+    for summary in summaries {
+        row(&summary.name, summary.instruments, summary.median_total_ms);
+    }
+    println!("{}", "-".repeat(72));
+    row(
+        "all",
+        summaries.iter().map(|s| s.instruments).sum(),
+        summaries.iter().map(|s| s.median_total_ms).sum(),
+    );
+
+    // Category medians per corpus, one column each
+    println!();
+    print!("{:<22}", "Category (Med ms)");
+    for summary in summaries {
+        // A corpus name's end usually tells corpora apart
+        print!(
+            " {}",
+            display::pad_start(&display::truncate_start(&summary.name, 12), 12)
+        );
+    }
+    println!(" {:>10}", "all");
+    for cat in CATEGORY_ORDER {
+        let per_corpus: Vec<Option<f64>> = summaries
+            .iter()
+            .map(|s| {
+                s.category_median_ms
+                    .iter()
+                    .find(|(c, _)| *c == cat)
+                    .map(|(_, ms)| *ms)
+            })
+            .collect();
+        if per_corpus.iter().all(Option::is_none) {
+            continue;
+        }
+        print!("{:<22}", cat);
+        for ms in &per_corpus {
+            match ms {
+                Some(ms) => print!(" {:>12.2}", ms),
+                None => print!(" {:>12}", "-"),
+            }
+        }
+        println!(" {:>10.2}", per_corpus.iter().flatten().sum::<f64>());
+    }
+}
+
+/// How a query's top-K in later passes differed from the first pass's.
+#[derive(Clone, Copy, Default)]
+struct TopChanges {
+    /// Passes that returned different rows.
+    set: usize,
+    /// Passes that returned the same rows in a different order.
+    order: usize,
+}
+
+impl TopChanges {
+    fn compare(&mut self, first: &[(u32, usize)], top: &[(u32, usize)]) {
+        if top == first {
+            return;
+        }
+        let rows = |top: &[(u32, usize)]| {
+            let mut rows: Vec<usize> = top.iter().map(|&(_, idx)| idx).collect();
+            rows.sort_unstable();
+            rows
+        };
+        if rows(top) == rows(first) {
+            self.order += 1;
         } else {
-            q.text.to_string()
+            self.set += 1;
+        }
+    }
+
+    fn is_stable(&self) -> bool {
+        self.set == 0 && self.order == 0
+    }
+}
+
+/// Lists the queries whose top-K was not identical in every pass. The scan
+/// is meant to be deterministic, so any entry here points at a tie broken
+/// differently from pass to pass, or at state carried between scans.
+fn print_top_stability(queries: &[Query], changes: &[TopChanges], iterations: usize) {
+    let unstable: Vec<usize> = (0..queries.len())
+        .filter(|&qi| !changes[qi].is_stable())
+        .collect();
+    println!();
+    println!(
+        "=== Top-{} stability across {} iterations ===",
+        TOP_K, iterations
+    );
+    println!();
+    if unstable.is_empty() {
+        println!(
+            "All {} queries returned the same top {} in the same order every pass",
+            queries.len(),
+            TOP_K
+        );
+        return;
+    }
+    println!(
+        "{:<32} {:<8} {:>12} {:>12}",
+        "Query", "Field", "Set changed", "Reordered"
+    );
+    println!("{}", "-".repeat(68));
+    for &qi in &unstable {
+        let q = &queries[qi];
+        println!(
+            "{} {:<8} {:>12} {:>12}",
+            display::cell(&q.text, 32),
+            q.field,
+            changes[qi].set,
+            changes[qi].order
+        );
+    }
+    println!();
+    println!(
+        "WARNING: {} of {} queries returned an unstable top {}",
+        unstable.len(),
+        queries.len(),
+        TOP_K
+    );
+}
+
+/// How a scan walks a prepared column.
+#[derive(Clone, Copy, PartialEq)]
+enum ScanMode {
+    /// One row after another (the baseline).
+    Plain,
+    /// Rows in fixed-size batches, to separate the cost of batching itself.
+    Batched,
+    /// Batches, prefetching the next batch's text before scoring this one.
+    Prefetched,
+}
+
+/// How a metadata filter is combined with scoring.
+#[derive(Clone, Copy)]
+enum FilterMode {
+    /// Score every candidate, ignoring the filter (the baseline).
+    Unfiltered,
+    /// Select the passing rows first, then score only those.
+    Before,
+    /// Scan every row and test the filter just before scoring it.
+    During,
+}
+
+/// Re-runs each query in `slowest` alone for `iterations` timed passes and
+/// summarises them. With `flamegraph_dir`, a further profiled set of passes
+/// per query is written there as `<rank>-<query>.svg`; profiling runs apart
+/// from the timed passes so its sampling overhead never skews them.
+fn recheck_slowest(
+    queries: &[Query],
+    slowest: &[usize],
+    prepared: &PreparedCorpus,
+    preset: &Preset,
+    iterations: usize,
+    flamegraph_dir: Option<&str>,
+) -> HashMap<usize, Recheck> {
+    let mut matcher = Matcher::new(preset.config());
+    let mut buf = Vec::new();
+    let mut rechecks = HashMap::new();
+    for (rank, &qi) in slowest.iter().enumerate() {
+        let q = &queries[qi];
+        let candidates = prepared.column(&q.field);
+        let pattern = preset.pattern(&q.text);
+        let mut run = || {
+            let mut matches = 0usize;
+            for candidate in candidates {
+                buf.clear();
+                if pattern
+                    .score(Utf32Str::new(candidate, &mut buf), &mut matcher)
+                    .is_some()
+                {
+                    matches += 1;
+                }
+            }
+            std::hint::black_box(matches);
         };
+        let timings: Vec<f64> = (0..iterations)
+            .map(|_| {
+                let start = Instant::now();
+                run();
+                start.elapsed().as_nanos() as f64 / 1_000_000.0
+            })
+            .collect();
+        let mut recheck = Recheck::from_timings(&timings).expect("at least one recheck iteration");
+        if let Some(dir) = flamegraph_dir {
+            let file_name: String = q
+                .text
+                .chars()
+                .take(40)
+                .map(|c| if c.is_alphanumeric() { c } else { '_' })
+                .collect();
+            let path = Path::new(dir).join(format!("{:02}-{}.svg", rank + 1, file_name));
+            recheck.flamegraph = flamegraph(&path, || {
+                for _ in 0..iterations {
+                    run();
+                }
+            });
+        }
+        rechecks.insert(qi, recheck);
+    }
+    rechecks
+}
+
+/// Profiles `iterations` passes over each category's queries into
+/// `dir/category-<category>.svg`, and returns each category with its query
+/// count and the flamegraph written, if any.
+fn profile_categories(
+    queries: &[Query],
+    categories: &[&str],
+    prepared: &PreparedCorpus,
+    preset: &Preset,
+    iterations: usize,
+    dir: &str,
+) -> Vec<(String, usize, Option<String>)> {
+    let mut matcher = Matcher::new(preset.config());
+    let mut buf = Vec::new();
+    categories
+        .iter()
+        .map(|&category| {
+            let members: Vec<(&Query, Pattern)> = queries
+                .iter()
+                .filter(|q| q.category == category)
+                .map(|q| (q, preset.pattern(&q.text)))
+                .collect();
+            let path = Path::new(dir).join(format!("category-{}.svg", category));
+            let written = flamegraph(&path, || {
+                for _ in 0..iterations {
+                    for (q, pattern) in &members {
+                        let mut matches = 0usize;
+                        for candidate in prepared.column(&q.field) {
+                            buf.clear();
+                            if pattern
+                                .score(Utf32Str::new(candidate, &mut buf), &mut matcher)
+                                .is_some()
+                            {
+                                matches += 1;
+                            }
+                        }
+                        std::hint::black_box(matches);
+                    }
+                }
+            });
+            (category.to_string(), members.len(), written)
+        })
+        .collect()
+}
+
+/// Profiles `work` into a flamegraph SVG at `path` and returns the path, or
+/// `None` when profiling failed.
+#[cfg(feature = "flamegraph")]
+fn flamegraph(path: &Path, work: impl FnOnce()) -> Option<String> {
+    let written = (|| -> Result<(), Box<dyn std::error::Error>> {
+        let guard = pprof::ProfilerGuardBuilder::default()
+            .frequency(10_000)
+            .blocklist(&["libc", "libgcc", "pthread", "vdso"])
+            .build()?;
+        work();
+        let report = guard.report().build()?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        report.flamegraph(fs::File::create(path)?)?;
+        Ok(())
+    })();
+    match written {
+        Ok(()) => Some(path.to_string_lossy().to_string()),
+        Err(e) => {
+            eprintln!("Warning: no flamegraph for {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+#[cfg(not(feature = "flamegraph"))]
+fn flamegraph(path: &Path, _work: impl FnOnce()) -> Option<String> {
+    eprintln!(
+        "Warning: no flamegraph for {}: bench-nucleo was built without the `flamegraph` feature",
+        path.display()
+    );
+    None
+}
+
+/// Scans the query set with each time budget, stopping each scan at the
+/// first look at the clock past it (`harness::budget`), and reports how
+/// often the budget cut a scan off, how much of the column those scans
+/// saw, and how their top results compare with an unbudgeted scan's.
+fn bench_budgets(
+    queries: &[Query],
+    prepared: &PreparedCorpus,
+    masks: Option<&HashMap<&str, Cow<[CharMask]>>>,
+    preset: &Preset,
+    iterations: usize,
+    budgets_ms: &[f64],
+) {
+    let mut matcher = Matcher::new(preset.config());
+    let mut buf = Vec::new();
+    // Ranked top rows of one scan, which stops once `deadline` expires,
+    // and the rows it looked at
+    let mut scan = |q: &Query, deadline: Option<Deadline>| -> (Vec<usize>, usize) {
+        let column = prepared.column(&q.field);
+        let column_masks = masks.map(|masks| &masks[q.field.as_str()]);
+        let mut pattern = preset.pattern(&q.text);
+        column.frequency().order_atoms(&mut pattern);
+        let query_mask = CharMask::of(&q.text);
+        let mut top = TopK::new(TOP_K);
+        let mut scanned = column.len();
+        for (ci, candidate) in column.iter().enumerate() {
+            if deadline.is_some_and(|deadline| deadline.expired(ci)) {
+                scanned = ci;
+                break;
+            }
+            if column_masks.is_some_and(|masks| !masks[ci].may_contain(query_mask)) {
+                continue;
+            }
+            buf.clear();
+            if let Some(score) = pattern.score(Utf32Str::new(candidate, &mut buf), &mut matcher) {
+                top.push(score, ci);
+            }
+        }
+        let mut top = top.into_sorted();
+        preset.rank(&mut top, |idx| column.get(idx));
+        (
+            top.into_iter()
+                .take(RECORDED_RESULTS)
+                .map(|(_, idx)| idx)
+                .collect(),
+            scanned,
+        )
+    };
+
+    let unbudgeted: Vec<Vec<usize>> = queries.iter().map(|q| scan(q, None).0).collect();
+
+    println!();
+    println!(
+        "=== Time budgets: cut-offs and top {} against full scans ({} queries x {} iterations) ===",
+        RECORDED_RESULTS,
+        queries.len(),
+        iterations
+    );
+    println!();
+    println!(
+        "{:>10} {:>9} {:>9} {:>10} {:>10} {:>10} {:>8} {:>8} {:>8}",
+        "Budget(ms)",
+        "Cut off",
+        "Coverage",
+        "Min cover",
+        "Recall",
+        "Same #1",
+        "P50(ms)",
+        "P99(ms)",
+        "Max(ms)"
+    );
+    println!("{}", "-".repeat(90));
+    for &budget_ms in budgets_ms {
+        let limit = Duration::from_secs_f64(budget_ms / 1000.0);
+        let mut stats = BudgetStats::default();
+        let mut cut_by_category: HashMap<&str, usize> = HashMap::new();
+        for _ in 0..iterations {
+            for (qi, q) in queries.iter().enumerate() {
+                let start = Instant::now();
+                let (top, scanned) = scan(q, Some(Deadline::new(limit)));
+                let ms = start.elapsed().as_secs_f64() * 1000.0;
+                let rows = prepared.column(&q.field).len();
+                stats.record(ms, scanned, rows, &top, &unbudgeted[qi]);
+                if scanned < rows {
+                    *cut_by_category.entry(q.category.as_str()).or_default() += 1;
+                }
+            }
+        }
+        println!(
+            "{:>10} {:>8.1}% {:>8.1}% {:>9.1}% {:>9.1}% {:>9.1}% {:>8.2} {:>8.2} {:>8.2}",
+            budget_ms,
+            stats.cut_off() as f64 / stats.scans().max(1) as f64 * 100.0,
+            stats.mean_coverage() * 100.0,
+            stats.min_coverage() * 100.0,
+            stats.recall() * 100.0,
+            stats.same_first() * 100.0,
+            stats.time_ms(50.0),
+            stats.time_ms(99.0),
+            stats.time_ms(100.0)
+        );
+        if !cut_by_category.is_empty() {
+            let cut: Vec<String> = CATEGORY_ORDER
+                .iter()
+                .filter_map(|category| {
+                    cut_by_category
+                        .get(category)
+                        .map(|n| format!("{category} {n}"))
+                })
+                .collect();
+            println!("{:>10} cut off: {}", "", cut.join(", "));
+        }
+    }
+    println!();
+    println!("Coverage is the mean share of the column seen by scans that were cut off.");
+    println!(
+        "Recall is the share of the unbudgeted top {} each scan still found.",
+        RECORDED_RESULTS
+    );
+}
+
+/// Times the query set scanning each column row by row, in batches of
+/// `batch` rows, and in batches that prefetch the next batch's text while
+/// scoring the current one. If the scan waits on memory, prefetching wins;
+/// if it is bound by scoring, all three come out alike.
+fn bench_prefetch(
+    queries: &[Query],
+    prepared: &PreparedCorpus,
+    preset: &Preset,
+    iterations: usize,
+    batch: usize,
+) {
+    println!();
+    println!("=== Prefetching scans (batches of {} rows) ===", batch);
+    println!();
+    println!(
+        "{:<12} {:>10} {:>10} {:>10} {:>10}",
+        "Mode", "Med(ms)", "Min(ms)", "vs plain", "Matches"
+    );
+    println!("{}", "-".repeat(56));
+
+    let mut matcher = Matcher::new(preset.config());
+    let mut buf = Vec::new();
+    let mut plain_ms = 0.0;
+    for (label, mode) in [
+        ("plain", ScanMode::Plain),
+        ("batched", ScanMode::Batched),
+        ("prefetched", ScanMode::Prefetched),
+    ] {
+        let mut totals_ms: Vec<f64> = Vec::with_capacity(iterations);
+        let mut matches = 0;
+        for _ in 0..iterations {
+            let start = Instant::now();
+            matches = 0;
+            for q in queries {
+                let pattern = preset.pattern(&q.text);
+                let column = prepared.column(&q.field);
+                let mut score = |idx: usize| {
+                    buf.clear();
+                    let haystack = Utf32Str::new(column.get(idx), &mut buf);
+                    if pattern.score(haystack, &mut matcher).is_some() {
+                        matches += 1;
+                    }
+                };
+                if mode == ScanMode::Plain {
+                    (0..column.len()).for_each(&mut score);
+                    continue;
+                }
+                if mode == ScanMode::Prefetched {
+                    column.prefetch(0..batch);
+                }
+                for from in (0..column.len()).step_by(batch) {
+                    let to = (from + batch).min(column.len());
+                    if mode == ScanMode::Prefetched {
+                        column.prefetch(to..to + batch);
+                    }
+                    (from..to).for_each(&mut score);
+                }
+            }
+            totals_ms.push(start.elapsed().as_secs_f64() * 1000.0);
+        }
+        totals_ms.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let median = totals_ms[iterations / 2];
+        if mode == ScanMode::Plain {
+            plain_ms = median;
+        }
+        println!(
+            "{:<12} {:>10.1} {:>10.1} {:>9.1}% {:>10}",
+            label,
+            median,
+            totals_ms[0],
+            (median / plain_ms - 1.0) * 100.0,
+            matches
+        );
+    }
+    if !cfg!(target_arch = "x86_64") {
+        println!("(prefetching is a no-op on this architecture)");
+    }
+}
+
+/// Breaks each multi-atom query down by atom: the time to scan its column
+/// with that atom alone, and the share of rows the atom matches. nucleo
+/// stops at the first atom a candidate fails, so the pattern is then timed
+/// with its atoms as written, with the most selective atom first, and in the
+/// order the column's character table estimates, which the main passes use.
+fn bench_atoms(queries: &[Query], prepared: &PreparedCorpus, preset: &Preset, iterations: usize) {
+    let multi: Vec<(&Query, Pattern)> = queries
+        .iter()
+        .map(|q| (q, preset.pattern(&q.text)))
+        .filter(|(_, pattern)| pattern.atoms.len() > 1)
+        .collect();
+    println!();
+    println!(
+        "=== Per-atom cost of multi-atom queries ({} queries) ===",
+        multi.len()
+    );
+    println!();
+    println!(
+        "{:<32} {:<8} {:<20} {:>10} {:>12} {:>10}",
+        "Query", "Field", "Atom", "Scan(ms)", "Selectivity", "Estimate"
+    );
+    println!("{}", "-".repeat(97));
+
+    let mut matcher = Matcher::new(preset.config());
+    let mut buf = Vec::new();
+    // Median scan time and matches of one scan with `score`
+    let mut time = |column: &harness::prepared::Column,
+                    score: &dyn Fn(Utf32Str, &mut Matcher) -> bool| {
+        let mut times_ms: Vec<f64> = Vec::with_capacity(iterations);
+        let mut matches = 0;
+        for _ in 0..iterations {
+            let start = Instant::now();
+            matches = 0;
+            for candidate in column {
+                buf.clear();
+                matches += score(Utf32Str::new(candidate, &mut buf), &mut matcher) as usize;
+            }
+            times_ms.push(start.elapsed().as_secs_f64() * 1000.0);
+        }
+        (percentile(&times_ms, 50.0).unwrap_or_default(), matches)
+    };
+
+    let mut written_total = 0.0;
+    let mut reordered_total = 0.0;
+    let mut estimated_total = 0.0;
+    for (q, pattern) in &multi {
+        let column = prepared.column(&q.field);
+        let display_query = display::cell(&q.text, 32);
+        let mut selectivity = Vec::with_capacity(pattern.atoms.len());
+        for atom in &pattern.atoms {
+            let (ms, matches) = time(column, &|haystack, matcher| {
+                atom.score(haystack, matcher).is_some()
+            });
+            let share = matches as f64 / column.len().max(1) as f64;
+            selectivity.push(share);
+            let needle = atom.needle_text().to_string();
+            let estimate = column.frequency().estimate_atom(atom);
+            println!(
+                "{} {:<8} {:<20} {:>10.3} {:>11.2}% {:>9.2}%",
+                display_query,
+                q.field,
+                if atom.negative {
+                    format!("!{needle}")
+                } else {
+                    needle
+                },
+                ms,
+                share * 100.0,
+                estimate * 100.0
+            );
+        }
+
+        let mut order: Vec<usize> = (0..pattern.atoms.len()).collect();
+        order.sort_by(|&a, &b| selectivity[a].partial_cmp(&selectivity[b]).unwrap());
+        let mut reordered = pattern.clone();
+        reordered.atoms = order.iter().map(|&i| pattern.atoms[i].clone()).collect();
+        let (written_ms, _) = time(column, &|haystack, matcher| {
+            pattern.score(haystack, matcher).is_some()
+        });
+        let (reordered_ms, _) = time(column, &|haystack, matcher| {
+            reordered.score(haystack, matcher).is_some()
+        });
+        let mut estimated = pattern.clone();
+        column.frequency().order_atoms(&mut estimated);
+        let (estimated_ms, _) = time(column, &|haystack, matcher| {
+            estimated.score(haystack, matcher).is_some()
+        });
+        written_total += written_ms;
+        reordered_total += reordered_ms;
+        estimated_total += estimated_ms;
+        println!(
+            "{:<32} {:<8} {:<20} {:>10.3}",
+            "", "", "as written", written_ms
+        );
+        println!(
+            "{:<32} {:<8} {:<20} {:>10.3} {:>+11.1}%",
+            "",
+            "",
+            "rarest first",
+            reordered_ms,
+            (reordered_ms / written_ms - 1.0) * 100.0
+        );
+        println!(
+            "{:<32} {:<8} {:<20} {:>10.3} {:>+11.1}%",
+            "",
+            "",
+            "estimated order",
+            estimated_ms,
+            (estimated_ms / written_ms - 1.0) * 100.0
+        );
+    }
+    if !multi.is_empty() {
+        println!();
+        println!(
+            "All multi-atom queries: {:.2}ms as written, {:.2}ms rarest first ({:+.1}%), {:.2}ms estimated order ({:+.1}%)",
+            written_total,
+            reordered_total,
+            (reordered_total / written_total - 1.0) * 100.0,
+            estimated_total,
+            (estimated_total / written_total - 1.0) * 100.0
+        );
+    }
+}
+
+/// Times computing highlights for the best K rows of each query apart from
+/// the scan that found them, at several K. A list that draws only the
+/// visible rows can compute their highlights lazily, so the gap between a
+/// screenful and the whole top-K says whether eager highlighting is worth
+/// doing away with.
+fn bench_highlights(
+    queries: &[Query],
+    prepared: &PreparedCorpus,
+    preset: &Preset,
+    iterations: usize,
+) {
+    let ks: Vec<usize> = [1, 10, 25, 50, TOP_K]
+        .into_iter()
+        .filter(|&k| k <= TOP_K)
+        .collect();
+    let mut matcher = Matcher::new(preset.config());
+    let mut buf = Vec::new();
+    let mut indices = Vec::new();
+    let mut scan_totals_ms: Vec<f64> = Vec::with_capacity(iterations);
+    let mut highlight_totals_ms: Vec<Vec<f64>> = vec![Vec::with_capacity(iterations); ks.len()];
+    let mut rows = vec![0; ks.len()];
+    for _ in 0..iterations {
+        let mut scan_ms = 0.0;
+        let mut highlight_ms = vec![0.0; ks.len()];
+        rows.iter_mut().for_each(|r| *r = 0);
+        for q in queries {
+            let pattern = preset.pattern(&q.text);
+            let column = prepared.column(&q.field);
+            let start = Instant::now();
+            let mut top = TopK::new(TOP_K);
+            for (idx, candidate) in column.iter().enumerate() {
+                buf.clear();
+                if let Some(score) = pattern.score(Utf32Str::new(candidate, &mut buf), &mut matcher)
+                {
+                    top.push(score, idx);
+                }
+            }
+            let top = top.into_sorted();
+            scan_ms += start.elapsed().as_secs_f64() * 1000.0;
+
+            for (i, &k) in ks.iter().enumerate() {
+                let start = Instant::now();
+                for &(_, idx) in top.iter().take(k) {
+                    let text = column.get(idx);
+                    buf.clear();
+                    indices.clear();
+                    pattern.indices(Utf32Str::new(text, &mut buf), &mut matcher, &mut indices);
+                    std::hint::black_box(harness::highlight::ranges(
+                        &indices,
+                        text.chars().count(),
+                    ));
+                }
+                highlight_ms[i] += start.elapsed().as_secs_f64() * 1000.0;
+                rows[i] += top.len().min(k);
+            }
+        }
+        scan_totals_ms.push(scan_ms);
+        for (totals, ms) in highlight_totals_ms.iter_mut().zip(highlight_ms) {
+            totals.push(ms);
+        }
+    }
+
+    let scan_ms = percentile(&scan_totals_ms, 50.0).unwrap_or_default();
+    println!();
+    println!(
+        "=== Highlight cost of the top K ({} queries, scan {:.2}ms) ===",
+        queries.len(),
+        scan_ms
+    );
+    println!();
+    println!(
+        "{:>6} {:>10} {:>16} {:>12} {:>10}",
+        "K", "Rows", "Highlights(ms)", "Per row(us)", "Of scan"
+    );
+    println!("{}", "-".repeat(58));
+    let mut medians = Vec::with_capacity(ks.len());
+    for ((&k, totals), &rows) in ks.iter().zip(&highlight_totals_ms).zip(&rows) {
+        let median = percentile(totals, 50.0).unwrap_or_default();
+        medians.push(median);
+        println!(
+            "{:>6} {:>10} {:>16.3} {:>12.2} {:>9.1}%",
+            k,
+            rows,
+            median,
+            median * 1000.0 / rows.max(1) as f64,
+            median / scan_ms * 100.0
+        );
+    }
+    if let Some(visible) = ks.iter().position(|&k| k == 10) {
+        let saved = medians[medians.len() - 1] - medians[visible];
+        println!();
+        println!(
+            "Highlighting 10 visible rows instead of the top {}: saves {:.3}ms per query set ({:.1}% of the scan)",
+            TOP_K,
+            saved,
+            saved / scan_ms * 100.0
+        );
+    }
+}
+
+/// Times the query set over growing prefixes of the corpus with three ways
+/// of calling nucleo: one `score` call per candidate, one `match_list` call
+/// per query, and `match_list` over `threads` chunks in parallel. Each way
+/// returns every match sorted best first, so they do the same work; the
+/// table shows the corpus size from which the batch and parallel calls pay
+/// for their setup.
+fn bench_batch_api(
+    queries: &[Query],
+    prepared: &PreparedCorpus,
+    preset: &Preset,
+    iterations: usize,
+    threads: usize,
+) {
+    let largest = queries
+        .iter()
+        .map(|q| prepared.column(&q.field).len())
+        .max()
+        .unwrap_or(0);
+    let mut sizes: Vec<usize> = std::iter::successors(Some(100), |n| Some(n * 10))
+        .take_while(|&n| n < largest)
+        .collect();
+    sizes.push(largest);
+
+    println!();
+    println!(
+        "=== Batch API: per-candidate vs batch vs parallel batch ({} threads) ===",
+        threads
+    );
+    println!();
+    println!(
+        "{:>10} {:>14} {:>12} {:>14} {:>10}",
+        "Rows", "Per-cand(ms)", "Batch(ms)", "Parallel(ms)", "Matches"
+    );
+    println!("{}", "-".repeat(64));
+
+    let mut matchers: Vec<Matcher> = (0..threads)
+        .map(|_| Matcher::new(preset.config()))
+        .collect();
+    let mut buf = Vec::new();
+    let mut batch_from = None;
+    let mut parallel_from = None;
+    for &rows in &sizes {
+        let mut medians = [0.0; 3];
+        let mut matches = 0;
+        for (mode, median) in medians.iter_mut().enumerate() {
+            let mut totals_ms: Vec<f64> = Vec::with_capacity(iterations);
+            for _ in 0..iterations {
+                let start = Instant::now();
+                matches = 0;
+                for q in queries {
+                    let pattern = preset.pattern(&q.text);
+                    let column = prepared.column(&q.field);
+                    let candidates = || column.iter().take(rows);
+                    let found = match mode {
+                        0 => {
+                            let mut found: Vec<(&str, u32)> = Vec::new();
+                            for candidate in candidates() {
+                                buf.clear();
+                                if let Some(score) = pattern
+                                    .score(Utf32Str::new(candidate, &mut buf), &mut matchers[0])
+                                {
+                                    found.push((candidate, score));
+                                }
+                            }
+                            found.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
+                            found.len()
+                        }
+                        1 => pattern.match_list(candidates(), &mut matchers[0]).len(),
+                        _ => {
+                            let chunk = column.len().min(rows).div_ceil(threads).max(1);
+                            let mut found: Vec<(&str, u32)> = thread::scope(|scope| {
+                                let handles: Vec<_> = matchers
+                                    .iter_mut()
+                                    .enumerate()
+                                    .map(|(t, matcher)| {
+                                        let pattern = &pattern;
+                                        scope.spawn(move || {
+                                            pattern.match_list(
+                                                candidates().skip(t * chunk).take(chunk),
+                                                matcher,
+                                            )
+                                        })
+                                    })
+                                    .collect();
+                                handles
+                                    .into_iter()
+                                    .flat_map(|h| h.join().expect("Scoring thread panicked"))
+                                    .collect()
+                            });
+                            found.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
+                            found.len()
+                        }
+                    };
+                    matches += found;
+                }
+                totals_ms.push(start.elapsed().as_secs_f64() * 1000.0);
+            }
+            *median = percentile(&totals_ms, 50.0).unwrap_or_default();
+        }
+        // A crossover holds from the smallest size after which a way stays ahead
+        if medians[1] < medians[0] {
+            batch_from.get_or_insert(rows);
+        } else {
+            batch_from = None;
+        }
+        if medians[2] < medians[0].min(medians[1]) {
+            parallel_from.get_or_insert(rows);
+        } else {
+            parallel_from = None;
+        }
+        println!(
+            "{:>10} {:>14.2} {:>12.2} {:>14.2} {:>10}",
+            rows, medians[0], medians[1], medians[2], matches
+        );
+    }
+    println!();
+    let crossover = |from: Option<usize>| {
+        from.map_or("never, up to the full corpus".to_string(), |rows| {
+            format!("from {} rows", rows)
+        })
+    };
+    println!("Batch beats per-candidate calls: {}", crossover(batch_from));
+    println!("Parallel beats both: {}", crossover(parallel_from));
+}
+
+/// Times each query warm, straight after a run of the same query, and cold,
+/// after the cache buster has evicted everything, and reports both medians.
+fn bench_cold_cache(
+    queries: &[Query],
+    categories: &[&str],
+    prepared: &PreparedCorpus,
+    preset: &Preset,
+    iterations: usize,
+    mb: usize,
+) {
+    let mut buster = CacheBuster::new(mb);
+    let mut matcher = Matcher::new(preset.config());
+    let mut buf = Vec::new();
+    let mut run = |q: &Query, matcher: &mut Matcher| {
+        let start = Instant::now();
+        let pattern = preset.pattern(&q.text);
+        let column = prepared.column(&q.field);
+        let mut top = TopK::new(TOP_K);
+        for (ci, candidate) in column.iter().enumerate() {
+            buf.clear();
+            if let Some(score) = pattern.score(Utf32Str::new(candidate, &mut buf), matcher) {
+                top.push(score, ci);
+            }
+        }
+        std::hint::black_box(top.into_sorted());
+        start.elapsed().as_secs_f64() * 1000.0
+    };
+
+    // Per query: warm and cold times, one of each per iteration
+    let mut warm_ms: Vec<Vec<f64>> = vec![Vec::with_capacity(iterations); queries.len()];
+    let mut cold_ms: Vec<Vec<f64>> = vec![Vec::with_capacity(iterations); queries.len()];
+    for _ in 0..iterations {
+        for (qi, q) in queries.iter().enumerate() {
+            buster.flush();
+            cold_ms[qi].push(run(q, &mut matcher));
+            run(q, &mut matcher);
+            warm_ms[qi].push(run(q, &mut matcher));
+        }
+    }
+    let median = |times: &[f64]| percentile(times, 50.0).unwrap_or_default();
+    let warm: Vec<f64> = warm_ms.iter().map(|t| median(t)).collect();
+    let cold: Vec<f64> = cold_ms.iter().map(|t| median(t)).collect();
+
+    println!();
+    println!(
+        "=== Warm vs cold cache ({} MiB buster, {} iterations) ===",
+        buster.bytes() >> 20,
+        iterations
+    );
+    println!();
+    println!(
+        "{:<22} {:>8} {:>10} {:>10} {:>9}",
+        "Category", "Queries", "Warm(ms)", "Cold(ms)", "Cold/warm"
+    );
+    println!("{}", "-".repeat(63));
+    for cat in categories {
+        let indices: Vec<usize> = (0..queries.len())
+            .filter(|&qi| queries[qi].category == *cat)
+            .collect();
+        let warm_total: f64 = indices.iter().map(|&qi| warm[qi]).sum();
+        let cold_total: f64 = indices.iter().map(|&qi| cold[qi]).sum();
+        println!(
+            "{:<22} {:>8} {:>10.2} {:>10.2} {:>8.2}x",
+            cat,
+            indices.len(),
+            warm_total,
+            cold_total,
+            cold_total / warm_total
+        );
+    }
+
+    println!();
+    println!(
+        "{:<32} {:<8} {:>10} {:>10} {:>9}",
+        "Query", "Field", "Warm(ms)", "Cold(ms)", "Cold/warm"
+    );
+    println!("{}", "-".repeat(73));
+    let mut by_penalty: Vec<usize> = (0..queries.len()).collect();
+    by_penalty.sort_by(|&a, &b| {
+        (cold[b] - warm[b])
+            .partial_cmp(&(cold[a] - warm[a]))
+            .unwrap()
+    });
+    for qi in by_penalty {
+        let q = &queries[qi];
+        println!(
+            "{} {:<8} {:>10.3} {:>10.3} {:>8.2}x",
+            display::cell(&q.text, 32),
+            q.field,
+            warm[qi],
+            cold[qi],
+            cold[qi] / warm[qi]
+        );
+    }
+}
+
+/// Times the query set with `filter` applied before and during scoring
+/// against an unfiltered scan of the same corpus.
+fn bench_filtered(
+    queries: &[Query],
+    instruments: &Corpus,
+    filter: &CompiledFilter,
+    preset: &Preset,
+    iterations: usize,
+) {
+    let passing = filter.select(instruments).len();
+    println!();
+    println!(
+        "=== Filtered scans ({} of {} candidates pass the filter) ===",
+        passing,
+        instruments.len()
+    );
+    println!();
+    println!(
+        "{:<12} {:>10} {:>10} {:>10} {:>12}",
+        "Mode", "Med(ms)", "Min(ms)", "Matches", "Scored/query"
+    );
+    println!("{}", "-".repeat(58));
+
+    let mut matcher = Matcher::new(preset.config());
+    let mut buf = Vec::new();
+    for (label, mode) in [
+        ("unfiltered", FilterMode::Unfiltered),
+        ("before", FilterMode::Before),
+        ("during", FilterMode::During),
+    ] {
+        let mut totals_ms: Vec<f64> = Vec::with_capacity(iterations);
+        let mut matches = 0;
+        let mut scored = 0;
+        for _ in 0..iterations {
+            let start = Instant::now();
+            matches = 0;
+            scored = 0;
+            for q in queries {
+                let pattern = preset.pattern(&q.text);
+                // Selection runs per query, as it would when each search
+                // carries its own filter
+                let selected: Vec<usize> = match mode {
+                    FilterMode::Before => filter.select(instruments),
+                    _ => Vec::new(),
+                };
+                let mut score = |idx: usize| {
+                    buf.clear();
+                    let haystack = Utf32Str::new(instruments.get(idx).field(&q.field), &mut buf);
+                    scored += 1;
+                    if pattern.score(haystack, &mut matcher).is_some() {
+                        matches += 1;
+                    }
+                };
+                match mode {
+                    FilterMode::Unfiltered => (0..instruments.len()).for_each(&mut score),
+                    FilterMode::Before => selected.iter().for_each(|&idx| score(idx)),
+                    FilterMode::During => (0..instruments.len())
+                        .filter(|&idx| filter.matches(&instruments.metadata(idx)))
+                        .for_each(&mut score),
+                }
+            }
+            totals_ms.push(start.elapsed().as_secs_f64() * 1000.0);
+        }
+        totals_ms.sort_by(|a, b| a.partial_cmp(b).unwrap());
         println!(
-            "{:<32} {:<8} {:<16} {:>8.2} {:>8.2} {:>8}",
-            display_query, q.field, q.category, med, mn, query_match_counts[qi]
+            "{:<12} {:>10.1} {:>10.1} {:>10} {:>12}",
+            label,
+            totals_ms[iterations / 2],
+            totals_ms[0],
+            matches,
+            scored / queries.len().max(1)
         );
     }
 }
@@ -255,7 +255,8 @@ int main(int argc, char* argv[]) {
     // Per-category summary — use preferred order, skip missing
     const char* preferred_categories[] = {
         "exact_symbol", "exact_name", "exact_isin", "prefix",
-        "typo", "substring", "multi_word", "symbol_spaces", "abbreviation"
+        "typo", "substring", "multi_word", "symbol_spaces", "abbreviation",
+        "adversarial"
     };
 
     std::set<std::string> category_set;
@@ -0,0 +1,100 @@
+//! Rewrites a corpus into realistic fake instruments that can be shared.
+//!
+//! Usage: corpus-anonymize [--tsv PATH] [--map symbol=KEY,name=KEY,isin=KEY]
+//!                         [--out PATH] [--key-file PATH] [--keep-metadata COL,COL]
+//!
+//! The mapping is [`harness::anonymize`]'s: consistent across the corpus,
+//! shape-preserving, and keyed so that it cannot be reversed by running
+//! known tickers, names or ISINs through it. The key is random for each run
+//! and is not written anywhere. To map several corpora the same way, pass
+//! `--key-file` with at least 16 bytes of secret; it must stay with whoever
+//! holds the real data.
+//!
+//! Metadata columns are dropped, since free-text columns can identify an
+//! instrument as well as its name can. Columns named in `--keep-metadata`
+//! (exchange or currency codes, say) are copied unchanged.
+
+use harness::anonymize::{Anonymizer, MIN_KEY_BYTES};
+use harness::cli::{flag_value, parsed_flag};
+use harness::corpus::{load_corpus_mapped, FieldMap};
+use harness::manifest::{Manifest, Schema};
+use harness::resource_path;
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let tsv_path = flag_value(&args, "--tsv")
+        .map(str::to_string)
+        .unwrap_or_else(|| resource_path("instruments-export.tsv"));
+    let field_map: FieldMap = parsed_flag(&args, "--map", FieldMap::default());
+    let key = match flag_value(&args, "--key-file") {
+        Some(path) => {
+            let key = fs::read(path).expect("Failed to read key file");
+            assert!(
+                key.len() >= MIN_KEY_BYTES,
+                "Key file must hold at least {MIN_KEY_BYTES} bytes"
+            );
+            key
+        }
+        None => {
+            let mut key = vec![0; 32];
+            getrandom::getrandom(&mut key).expect("Failed to generate a key");
+            key
+        }
+    };
+
+    let instruments = load_corpus_mapped(&tsv_path, &field_map).expect("Failed to read corpus");
+    let kept: Vec<usize> = flag_value(&args, "--keep-metadata")
+        .unwrap_or("")
+        .split(',')
+        .filter(|name| !name.trim().is_empty())
+        .map(|name| {
+            instruments
+                .metadata_column(name.trim())
+                .unwrap_or_else(|| panic!("Corpus has no metadata column {name}"))
+        })
+        .collect();
+    let kept_columns: Vec<String> = kept
+        .iter()
+        .map(|&column| instruments.metadata_columns()[column].clone())
+        .collect();
+
+    let mut out: Box<dyn Write> = match flag_value(&args, "--out") {
+        Some(path) => Box::new(BufWriter::new(
+            File::create(path).expect("Failed to create output file"),
+        )),
+        None => Box::new(BufWriter::new(io::stdout().lock())),
+    };
+
+    let mut anonymizer = Anonymizer::new(key);
+    let mut header = vec!["Symbol".to_string(), "Name".into(), "ISIN".into()];
+    header.extend(kept_columns.iter().cloned());
+    writeln!(out, "{}", header.join("\t")).expect("Failed to write corpus");
+    for (idx, inst) in instruments.iter().enumerate() {
+        let symbol = anonymizer.text(inst.symbol);
+        let name = anonymizer.text(inst.name);
+        let isin = anonymizer.isin(inst.isin);
+        write!(out, "{symbol}\t{name}\t{isin}").expect("Failed to write corpus");
+        let metadata = instruments.metadata(idx);
+        for &column in &kept {
+            write!(out, "\t{}", metadata.value(column)).expect("Failed to write corpus");
+        }
+        writeln!(out).expect("Failed to write corpus");
+    }
+    out.flush().expect("Failed to write corpus");
+    if let Some(path) = flag_value(&args, "--out") {
+        let schema = Schema::instruments_with_metadata(&kept_columns);
+        // The manifest travels with the data, so it records nothing of the key
+        Manifest::write_for(path, "corpus-anonymize", None, schema)
+            .expect("Failed to write manifest");
+    }
+
+    eprintln!(
+        "Anonymized {} instruments ({} distinct tokens, {} distinct ISINs)",
+        instruments.len(),
+        anonymizer.tokens(),
+        anonymizer.isins()
+    );
+}
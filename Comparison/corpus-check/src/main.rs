@@ -0,0 +1,416 @@
+//! Lints an instrument corpus before it is used for benchmarking.
+//!
+//! Usage: corpus-check [--tsv PATH] [--map symbol=KEY,name=KEY,isin=KEY]
+//!                     [--json] [--outlier-factor F] [--examples N] [--strict]
+//!        corpus-check --near-duplicates [--tsv PATH] [--map ...] [--threshold F]
+//!                     [--window N] [--examples N] [--json] [--out PATH]
+//!
+//! Reports duplicate ISINs, empty fields, invalid ISIN check digits,
+//! mojibake, control characters, length outliers, malformed rows and rows
+//! that are not valid UTF-8. With
+//! `--json` the full issue list is written to stdout as one JSON document;
+//! `--strict` exits with status 1 when any issue is found.
+//!
+//! `--near-duplicates` runs a different pass: it clusters rows whose names
+//! are within a normalized edit distance of `--threshold` (default 0.1) of
+//! each other (see `harness::near_duplicates`) and prints the largest
+//! clusters, or all of them with `--json`. `--out` writes every clustered
+//! row as TSV with its cluster's key, the leader's line, for grouping
+//! results by instrument.
+
+use harness::cli::{flag_value, has_flag, parsed_flag};
+use harness::corpus::{is_json_lines, load_corpus_mapped, tsv_columns, Corpus, FieldMap};
+use harness::near_duplicates::{NearDuplicates, THRESHOLD, WINDOW};
+use harness::resource_path;
+use harness::{input, isin, tsv};
+use serde_json::json;
+use std::collections::HashMap;
+use std::env;
+use std::fs::File;
+use std::io::{BufRead, BufWriter, Write};
+use std::process;
+
+const KINDS: [&str; 8] = [
+    "malformed_row",
+    "invalid_utf8",
+    "empty_field",
+    "duplicate_isin",
+    "invalid_isin",
+    "control_character",
+    "mojibake",
+    "length_outlier",
+];
+
+/// Characters Windows-1252 maps bytes 0x80–0x9F to. Together with C1
+/// controls and Latin-1 punctuation they are what UTF-8 continuation bytes
+/// turn into when decoded with the wrong code page.
+const CP1252_EXTRAS: &str = "€‚ƒ„…†‡ˆ‰Š‹ŒŽ‘’“”•–—˜™š›œžŸ";
+
+struct Issue {
+    kind: &'static str,
+    line: usize,
+    field: &'static str,
+    value: String,
+    detail: String,
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let tsv_path = flag_value(&args, "--tsv")
+        .map(str::to_string)
+        .unwrap_or_else(|| resource_path("instruments-export.tsv"));
+    let field_map: FieldMap = parsed_flag(&args, "--map", FieldMap::default());
+    let outlier_factor: f64 = parsed_flag(&args, "--outlier-factor", 3.0);
+    let examples: usize = parsed_flag(&args, "--examples", 10);
+
+    let instruments = load_corpus_mapped(&tsv_path, &field_map).expect("Failed to read corpus");
+    if has_flag(&args, "--near-duplicates") {
+        let threshold: f64 = parsed_flag(&args, "--threshold", THRESHOLD);
+        let window: usize = parsed_flag(&args, "--window", WINDOW);
+        let near = NearDuplicates::find(&instruments, threshold, window);
+        if let Some(path) = flag_value(&args, "--out") {
+            write_clusters(path, &instruments, &near).expect("Failed to write clusters");
+        }
+        if has_flag(&args, "--json") {
+            print_clusters_json(&tsv_path, &instruments, &near, threshold);
+        } else {
+            print_clusters(&tsv_path, &instruments, &near, threshold, examples);
+        }
+        return;
+    }
+
+    let mut issues: Vec<Issue> = Vec::new();
+    if !is_json_lines(&tsv_path) {
+        check_row_shapes(&tsv_path, &field_map, &mut issues);
+    }
+    check_encoding(&instruments, &mut issues);
+    check_fields(&instruments, &mut issues);
+    check_duplicate_isins(&instruments, &mut issues);
+    check_length_outliers(&instruments, outlier_factor, &mut issues);
+    issues.sort_by_key(|issue| issue.line);
+
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for issue in &issues {
+        *counts.entry(issue.kind).or_default() += 1;
+    }
+
+    if has_flag(&args, "--json") {
+        print_json(&tsv_path, instruments.len(), &counts, &issues);
+    } else {
+        print_summary(&tsv_path, instruments.len(), &counts, &issues, examples);
+    }
+
+    if has_flag(&args, "--strict") && !issues.is_empty() {
+        process::exit(1);
+    }
+}
+
+/// Rows the loader silently drops because they are too short to hold the
+/// mapped columns.
+fn check_row_shapes(path: &str, map: &FieldMap, issues: &mut Vec<Issue>) {
+    let mut lines = input::open(path)
+        .expect("Failed to read corpus")
+        .split(b'\n');
+    let header = match lines.next() {
+        Some(header) => {
+            String::from_utf8_lossy(&header.expect("Failed to read corpus")).into_owned()
+        }
+        None => return,
+    };
+    let width = tsv_columns(&header, map)
+        .expect("Failed to read corpus")
+        .into_iter()
+        .max()
+        .unwrap()
+        + 1;
+    for (i, line) in lines.enumerate() {
+        let bytes = line.expect("Failed to read corpus");
+        let line = String::from_utf8_lossy(bytes.strip_suffix(b"\r").unwrap_or(&bytes));
+        let cols = tsv::fields(&line).count();
+        if !line.is_empty() && cols < width {
+            issues.push(Issue {
+                kind: "malformed_row",
+                line: i + 2,
+                field: "",
+                value: line.to_string(),
+                detail: format!("{cols} column(s), expected at least {width}"),
+            });
+        }
+    }
+}
+
+/// Rows the loader decoded as Windows-1252 or dropped as undecodable.
+fn check_encoding(instruments: &Corpus, issues: &mut Vec<Issue>) {
+    let repairs = instruments.repairs();
+    for (lines, detail) in [
+        (&repairs.repaired, "repaired as Windows-1252"),
+        (&repairs.dropped, "undecodable, row dropped"),
+    ] {
+        for &line in lines {
+            issues.push(Issue {
+                kind: "invalid_utf8",
+                line,
+                field: "",
+                value: String::new(),
+                detail: detail.to_string(),
+            });
+        }
+    }
+}
+
+fn check_fields(instruments: &Corpus, issues: &mut Vec<Issue>) {
+    for inst in instruments {
+        for (field, value) in [
+            ("symbol", &inst.symbol),
+            ("name", &inst.name),
+            ("isin", &inst.isin),
+        ] {
+            let mut push = |kind: &'static str, detail: String| {
+                issues.push(Issue {
+                    kind,
+                    line: inst.line,
+                    field,
+                    value: value.to_string(),
+                    detail,
+                });
+            };
+            if value.trim().is_empty() {
+                push("empty_field", String::new());
+                continue;
+            }
+            if let Some(c) = value.chars().find(|c| c.is_control()) {
+                push("control_character", format!("U+{:04X}", c as u32));
+            }
+            if let Some(pattern) = mojibake(value) {
+                push("mojibake", format!("contains {pattern:?}"));
+            }
+        }
+        if !inst.isin.is_empty() && !isin::is_valid(inst.isin) {
+            issues.push(Issue {
+                kind: "invalid_isin",
+                line: inst.line,
+                field: "isin",
+                value: inst.isin.to_string(),
+                detail: "bad format or check digit".to_string(),
+            });
+        }
+    }
+}
+
+/// Detects UTF-8 text that was decoded as Latin-1 or Windows-1252 somewhere
+/// upstream (`MÃ¼nchener` for `Münchener`), and replacement characters left
+/// behind by lossy conversions.
+fn mojibake(value: &str) -> Option<String> {
+    let chars: Vec<char> = value.chars().collect();
+    if chars.contains(&'\u{FFFD}') {
+        return Some("\u{FFFD}".to_string());
+    }
+    chars.windows(2).find_map(|pair| {
+        let lead = matches!(pair[0], 'Ã' | 'Â' | 'â' | 'Å' | 'Ä');
+        let continuation =
+            matches!(pair[1], '\u{80}'..='\u{BF}') || CP1252_EXTRAS.contains(pair[1]);
+        (lead && continuation).then(|| pair.iter().collect())
+    })
+}
+
+fn check_duplicate_isins(instruments: &Corpus, issues: &mut Vec<Issue>) {
+    let mut lines_by_isin: HashMap<&str, Vec<usize>> = HashMap::new();
+    for inst in instruments.iter().filter(|i| !i.isin.is_empty()) {
+        lines_by_isin.entry(inst.isin).or_default().push(inst.line);
+    }
+    for (isin, lines) in lines_by_isin.into_iter().filter(|(_, l)| l.len() > 1) {
+        let shown: Vec<String> = lines.iter().take(5).map(|l| l.to_string()).collect();
+        let more = if lines.len() > 5 { ", ..." } else { "" };
+        issues.push(Issue {
+            kind: "duplicate_isin",
+            line: lines[0],
+            field: "isin",
+            value: isin.to_string(),
+            detail: format!("{} rows: lines {}{more}", lines.len(), shown.join(", ")),
+        });
+    }
+}
+
+/// Flags values longer than `factor` times the field's 99th-percentile
+/// length. A quantile fence rather than a z-score, because the corpus is
+/// dominated by short derivative names and long fund names would otherwise
+/// all count as outliers.
+fn check_length_outliers(instruments: &Corpus, factor: f64, issues: &mut Vec<Issue>) {
+    for field in ["symbol", "name", "isin"] {
+        let mut lengths: Vec<usize> = instruments
+            .iter()
+            .map(|i| i.field(field).chars().count())
+            .filter(|&len| len > 0)
+            .collect();
+        if lengths.is_empty() {
+            continue;
+        }
+        lengths.sort_unstable();
+        let p99 = lengths[(lengths.len() - 1) * 99 / 100];
+        let limit = p99 as f64 * factor;
+        for inst in instruments {
+            let value = inst.field(field);
+            let len = value.chars().count();
+            if len as f64 > limit {
+                issues.push(Issue {
+                    kind: "length_outlier",
+                    line: inst.line,
+                    field,
+                    value: value.to_string(),
+                    detail: format!("{len} chars, p99 is {p99}"),
+                });
+            }
+        }
+    }
+}
+
+fn print_summary(
+    path: &str,
+    rows: usize,
+    counts: &HashMap<&str, usize>,
+    issues: &[Issue],
+    examples: usize,
+) {
+    println!("Checked {} rows from {}", rows, path);
+    println!();
+    println!("{:<20} {:>8}", "Issue", "Count");
+    println!("{}", "-".repeat(29));
+    for kind in KINDS {
+        println!("{:<20} {:>8}", kind, counts.get(kind).unwrap_or(&0));
+    }
+
+    for kind in KINDS {
+        let of_kind: Vec<&Issue> = issues.iter().filter(|i| i.kind == kind).collect();
+        if of_kind.is_empty() {
+            continue;
+        }
+        println!();
+        println!(
+            "{} (first {} of {}):",
+            kind,
+            examples.min(of_kind.len()),
+            of_kind.len()
+        );
+        for issue in of_kind.iter().take(examples) {
+            println!(
+                "  line {:<8} {:<7} {:?} {}",
+                issue.line, issue.field, issue.value, issue.detail
+            );
+        }
+    }
+}
+
+fn print_json(path: &str, rows: usize, counts: &HashMap<&str, usize>, issues: &[Issue]) {
+    let counts: serde_json::Map<String, serde_json::Value> = KINDS
+        .iter()
+        .map(|kind| (kind.to_string(), json!(counts.get(kind).unwrap_or(&0))))
+        .collect();
+    let issues: Vec<serde_json::Value> = issues
+        .iter()
+        .map(|i| {
+            json!({
+                "kind": i.kind,
+                "line": i.line,
+                "field": i.field,
+                "value": i.value,
+                "detail": i.detail,
+            })
+        })
+        .collect();
+    let report = json!({
+        "path": path,
+        "rows": rows,
+        "counts": counts,
+        "issues": issues,
+    });
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&report).expect("Failed to serialize report")
+    );
+}
+
+fn print_clusters(
+    path: &str,
+    instruments: &Corpus,
+    near: &NearDuplicates,
+    threshold: f64,
+    examples: usize,
+) {
+    println!("Checked {} rows from {}", instruments.len(), path);
+    println!(
+        "{} clusters of near-duplicate names (normalized edit distance < {}) cover {} rows",
+        near.clusters.len(),
+        threshold,
+        near.rows()
+    );
+    for cluster in near.clusters.iter().take(examples) {
+        println!();
+        println!(
+            "cluster {} ({} rows):",
+            instruments.get(cluster.leader()).line,
+            cluster.rows.len()
+        );
+        for &row in cluster.rows.iter().take(5) {
+            let inst = instruments.get(row);
+            println!(
+                "  line {:<8} {:<14} {:?}",
+                inst.line, inst.symbol, inst.name
+            );
+        }
+        if cluster.rows.len() > 5 {
+            println!("  ...");
+        }
+    }
+}
+
+fn print_clusters_json(path: &str, instruments: &Corpus, near: &NearDuplicates, threshold: f64) {
+    let clusters: Vec<serde_json::Value> = near
+        .clusters
+        .iter()
+        .map(|cluster| {
+            let rows: Vec<serde_json::Value> = cluster
+                .rows
+                .iter()
+                .map(|&row| {
+                    let inst = instruments.get(row);
+                    json!({"line": inst.line, "symbol": inst.symbol, "name": inst.name})
+                })
+                .collect();
+            json!({"key": instruments.get(cluster.leader()).line, "rows": rows})
+        })
+        .collect();
+    let report = json!({
+        "path": path,
+        "rows": instruments.len(),
+        "threshold": threshold,
+        "clustered_rows": near.rows(),
+        "clusters": clusters,
+    });
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&report).expect("Failed to serialize report")
+    );
+}
+
+/// `line`, `cluster`, `symbol`, `name` and `isin` of every clustered row, in
+/// corpus order.
+fn write_clusters(path: &str, instruments: &Corpus, near: &NearDuplicates) -> std::io::Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+    writeln!(out, "line\tcluster\tsymbol\tname\tisin")?;
+    for (row, leader) in near.leaders(instruments.len()).into_iter().enumerate() {
+        let Some(leader) = leader else {
+            continue;
+        };
+        let inst = instruments.get(row);
+        writeln!(
+            out,
+            "{}\t{}\t{}\t{}\t{}",
+            inst.line,
+            instruments.get(leader).line,
+            inst.symbol,
+            inst.name,
+            inst.isin
+        )?;
+    }
+    out.flush()
+}
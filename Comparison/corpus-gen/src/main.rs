@@ -0,0 +1,253 @@
+//! Generates a synthetic instrument corpus from locale profiles.
+//!
+//! Usage: corpus-gen [--rows N] [--seed N] [--out PATH]
+//!                   [--profile english|german|french|japanese|mixed]
+//!                   [--accent-ratio R] [--cjk-ratio R] [--descriptions]
+//!
+//! Names are composed from each profile's vocabulary, so the share of
+//! umlauts, accents and CJK text is known up front instead of whatever the
+//! export happens to contain. `--accent-ratio` sets the share of words drawn
+//! from a profile's accented vocabulary and `--cjk-ratio` the share of
+//! Japanese names written in native script; both default to the profile's
+//! own ratio. `mixed` picks a profile per row. ISINs carry the profile's
+//! country codes and valid check digits. `--descriptions` adds a long
+//! free-text `Description` column, prospectus-style, for `long_text`
+//! queries.
+
+mod profiles;
+
+use harness::cli::{flag_value, has_flag, parsed_flag};
+use harness::isin;
+use harness::manifest::{Manifest, Schema};
+use harness::rng::Rng;
+use profiles::{Profile, Selection};
+use std::collections::HashSet;
+use std::env;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+/// Clauses of generated descriptions, in the order they appear. A
+/// description takes the name, then one clause from most of these groups.
+const DESCRIPTION_CLAUSES: &[&[&str]] = &[
+    &[
+        "Accumulating share class of a fund",
+        "Distributing share class of a fund",
+        "Exchange traded fund",
+        "Ordinary shares of a company",
+        "Senior unsecured notes",
+        "Open-ended investment company",
+    ],
+    &[
+        "tracking the performance of",
+        "investing primarily in",
+        "providing exposure to",
+        "seeking long-term capital growth through",
+        "with a focus on",
+    ],
+    &[
+        "global semiconductor equities",
+        "European small and mid cap companies",
+        "investment grade corporate bonds",
+        "emerging market government debt",
+        "listed real estate investment trusts",
+        "renewable energy infrastructure",
+        "Japanese large cap equities",
+        "short duration euro money market instruments",
+    ],
+    &[
+        "screened for environmental, social and governance criteria",
+        "hedged to the euro",
+        "denominated in US dollars",
+        "with quarterly distributions",
+        "rebalanced semi-annually",
+    ],
+    &[
+        "and listed on regulated markets in several jurisdictions.",
+        "and governed by the laws of Luxembourg.",
+        "as described in the prospectus dated 2024.",
+        "subject to the risk factors set out in the offering memorandum.",
+    ],
+];
+
+/// `--accent-ratio` and `--cjk-ratio`, when given.
+struct Ratios {
+    accent: Option<f64>,
+    native: Option<f64>,
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let rows: usize = parsed_flag(&args, "--rows", 100_000);
+    let seed: u64 = parsed_flag(&args, "--seed", 42);
+    let selection: Selection = parsed_flag(&args, "--profile", Selection::Mixed);
+    let ratios = Ratios {
+        accent: ratio_flag(&args, "--accent-ratio"),
+        native: ratio_flag(&args, "--cjk-ratio"),
+    };
+
+    let descriptions = has_flag(&args, "--descriptions");
+    // A separate stream, so adding descriptions leaves the other columns as
+    // they were for the same seed.
+    let mut description_rng = Rng::new(seed.wrapping_add(1));
+
+    let mut out: Box<dyn Write> = match flag_value(&args, "--out") {
+        Some(path) => Box::new(BufWriter::new(
+            File::create(path).expect("Failed to create output file"),
+        )),
+        None => Box::new(BufWriter::new(io::stdout().lock())),
+    };
+
+    let mut rng = Rng::new(seed);
+    let mut isins: HashSet<String> = HashSet::with_capacity(rows);
+    let mut non_ascii = 0;
+    let mut cjk = 0;
+
+    let header = if descriptions {
+        "Symbol\tName\tISIN\tDescription"
+    } else {
+        "Symbol\tName\tISIN"
+    };
+    writeln!(out, "{header}").expect("Failed to write corpus");
+    for _ in 0..rows {
+        let profile = match selection {
+            Selection::Single(profile) => profile,
+            Selection::Mixed => *rng.choose(&profiles::ALL).unwrap(),
+        };
+        let name = name(profile, &ratios, &mut rng);
+        let symbol = symbol(profile, &name, &mut rng);
+        let isin = loop {
+            let candidate = isin(profile, &mut rng);
+            if isins.insert(candidate.clone()) {
+                break candidate;
+            }
+        };
+        if !name.is_ascii() {
+            non_ascii += 1;
+        }
+        if name.chars().any(is_cjk) {
+            cjk += 1;
+        }
+        write!(out, "{symbol}\t{name}\t{isin}").expect("Failed to write corpus");
+        if descriptions {
+            write!(out, "\t{}", description(&name, &mut description_rng))
+                .expect("Failed to write corpus");
+        }
+        writeln!(out).expect("Failed to write corpus");
+    }
+    out.flush().expect("Failed to write corpus");
+    if let Some(path) = flag_value(&args, "--out") {
+        let schema = if descriptions {
+            Schema::instruments_with_metadata(&["Description".to_string()])
+        } else {
+            Schema::instruments()
+        };
+        Manifest::write_for(path, "corpus-gen", Some(seed), schema)
+            .expect("Failed to write manifest");
+    }
+
+    eprintln!(
+        "Generated {rows} instruments ({:.1}% non-ASCII names, {:.1}% CJK names)",
+        percent(non_ascii, rows),
+        percent(cjk, rows)
+    );
+}
+
+/// A ratio flag in `0..=1`, or `None` to keep the profile default.
+fn ratio_flag(args: &[String], flag: &str) -> Option<f64> {
+    flag_value(args, flag)?;
+    let ratio: f64 = parsed_flag(args, flag, 0.0);
+    assert!(
+        (0.0..=1.0).contains(&ratio),
+        "{flag} must be between 0 and 1"
+    );
+    Some(ratio)
+}
+
+fn name(profile: &Profile, ratios: &Ratios, rng: &mut Rng) -> String {
+    let native_ratio = ratios.native.unwrap_or(profile.native_ratio);
+    if !profile.native_words.is_empty() && rng.chance(native_ratio) {
+        let mut name: String = (0..rng.range(2, 3))
+            .map(|_| *rng.choose(profile.native_words).unwrap())
+            .collect();
+        if let Some(suffix) = rng
+            .choose(profile.native_suffixes)
+            .filter(|_| rng.chance(0.5))
+        {
+            name.push_str(suffix);
+        }
+        return name;
+    }
+
+    let accent_ratio = ratios.accent.unwrap_or(profile.accent_ratio);
+    let mut words: Vec<&str> = Vec::new();
+    for _ in 0..rng.range(1, 3) {
+        let vocabulary = if !profile.accented_words.is_empty() && rng.chance(accent_ratio) {
+            profile.accented_words
+        } else {
+            profile.plain_words
+        };
+        let word = *rng.choose(vocabulary).unwrap();
+        if !words.contains(&word) {
+            words.push(word);
+        }
+    }
+    if rng.chance(0.7) {
+        words.push(rng.choose(profile.suffixes).unwrap());
+    }
+    words.join(" ")
+}
+
+/// A prospectus-style sentence about the instrument, opening with its name.
+fn description(name: &str, rng: &mut Rng) -> String {
+    let mut text = name.to_string();
+    for (i, clauses) in DESCRIPTION_CLAUSES.iter().enumerate() {
+        // The opening clauses are always present so the sentence reads.
+        if i < 3 || rng.chance(0.6) {
+            text.push_str(if i == 0 { ": " } else { " " });
+            text.push_str(rng.choose(clauses).unwrap());
+        }
+    }
+    text
+}
+
+/// A four-digit code for numeric markets, otherwise a ticker built from the
+/// name's ASCII initials and padded with random letters.
+fn symbol(profile: &Profile, name: &str, rng: &mut Rng) -> String {
+    if profile.numeric_symbols {
+        return rng.range(1000, 9999).to_string();
+    }
+    let mut symbol: String = name
+        .split_whitespace()
+        .filter_map(|word| word.chars().next())
+        .filter(char::is_ascii_alphabetic)
+        .map(|c| c.to_ascii_uppercase())
+        .take(4)
+        .collect();
+    let len = rng.range(3, 4).max(symbol.len());
+    while symbol.len() < len {
+        symbol.push((b'A' + rng.below(26) as u8) as char);
+    }
+    symbol
+}
+
+fn isin(profile: &Profile, rng: &mut Rng) -> String {
+    let country = rng.choose(profile.countries).unwrap();
+    let body: String = (0..9)
+        .map(|_| (b'0' + rng.below(10) as u8) as char)
+        .collect();
+    let partial = format!("{country}{body}");
+    let digit = isin::check_digit(&partial).expect("body is alphanumeric");
+    format!("{partial}{digit}")
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(c as u32, 0x3040..=0x30FF | 0x4E00..=0x9FFF)
+}
+
+fn percent(count: usize, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        count as f64 * 100.0 / total as f64
+    }
+}
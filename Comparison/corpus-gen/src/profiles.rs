@@ -0,0 +1,269 @@
+//! Locale profiles for synthetic instrument names.
+//!
+//! Each profile supplies vocabulary and identifiers typical of one market.
+//! Plain and accented words are kept apart, and native-script names apart
+//! from romanized ones, so the share of non-ASCII text can be dialled in
+//! independently of the market.
+
+use std::str::FromStr;
+
+pub struct Profile {
+    pub name: &'static str,
+    /// Words without diacritics.
+    pub plain_words: &'static [&'static str],
+    /// Words with umlauts, accents or ligatures.
+    pub accented_words: &'static [&'static str],
+    /// Native-script words, joined without spaces as written.
+    pub native_words: &'static [&'static str],
+    pub suffixes: &'static [&'static str],
+    pub native_suffixes: &'static [&'static str],
+    /// ISIN country codes.
+    pub countries: &'static [&'static str],
+    /// Listings use numeric codes (`7203`) instead of letter tickers.
+    pub numeric_symbols: bool,
+    /// Default share of words drawn from `accented_words`.
+    pub accent_ratio: f64,
+    /// Default share of names written in native script.
+    pub native_ratio: f64,
+}
+
+pub const ENGLISH: Profile = Profile {
+    name: "english",
+    plain_words: &[
+        "Global",
+        "Capital",
+        "Holdings",
+        "Energy",
+        "Systems",
+        "Industries",
+        "Financial",
+        "Partners",
+        "Resources",
+        "Technologies",
+        "Bank",
+        "Insurance",
+        "Pharmaceuticals",
+        "Motors",
+        "Trust",
+        "Group",
+        "American",
+        "National",
+        "General",
+        "United",
+        "Pacific",
+        "Atlantic",
+        "Healthcare",
+        "Realty",
+        "Semiconductor",
+        "Airlines",
+        "Foods",
+        "Brands",
+        "Logistics",
+        "Networks",
+        "Materials",
+        "Mining",
+        "Royal",
+        "Western",
+        "Northern",
+    ],
+    accented_words: &[],
+    native_words: &[],
+    suffixes: &["Inc.", "Corp.", "PLC", "Ltd", "Co.", "Holdings Inc."],
+    native_suffixes: &[],
+    countries: &["US", "GB"],
+    numeric_symbols: false,
+    accent_ratio: 0.0,
+    native_ratio: 0.0,
+};
+
+pub const GERMAN: Profile = Profile {
+    name: "german",
+    plain_words: &[
+        "Deutsche",
+        "Bayerische",
+        "Motoren",
+        "Werke",
+        "Energie",
+        "Versicherung",
+        "Holding",
+        "Immobilien",
+        "Landesbank",
+        "Chemie",
+        "Industrie",
+        "Beteiligungen",
+        "Verkehrs",
+        "Handel",
+        "Stahl",
+        "Maschinenbau",
+        "Berliner",
+        "Hamburger",
+        "Elektro",
+        "Bau",
+    ],
+    accented_words: &[
+        "Münchener",
+        "Rückversicherung",
+        "Börse",
+        "Straße",
+        "Überland",
+        "Kölner",
+        "Düsseldorfer",
+        "Großhandel",
+        "Bürgschaft",
+        "Württemberg",
+        "Südzucker",
+        "Nürnberger",
+        "Schiffahrtsgesellschaft",
+        "Lübecker",
+        "Gebäude",
+        "Wärme",
+        "Flughäfen",
+    ],
+    native_words: &[],
+    suffixes: &["AG", "SE", "GmbH", "KGaA", "AG & Co. KGaA"],
+    native_suffixes: &[],
+    countries: &["DE", "AT", "CH"],
+    numeric_symbols: false,
+    accent_ratio: 0.3,
+    native_ratio: 0.0,
+};
+
+pub const FRENCH: Profile = Profile {
+    name: "french",
+    plain_words: &[
+        "Groupe",
+        "Banque",
+        "Compagnie",
+        "Nationale",
+        "Capital",
+        "Industries",
+        "Paris",
+        "Lyon",
+        "Air",
+        "Total",
+        "Vinci",
+        "Assurances",
+        "Transports",
+        "Distribution",
+        "Investissement",
+        "Foncier",
+        "Maritime",
+        "Partenaires",
+    ],
+    accented_words: &[
+        "Société",
+        "Générale",
+        "Crédit",
+        "Électricité",
+        "Réseau",
+        "Hôtels",
+        "Télécom",
+        "Énergies",
+        "Éléments",
+        "Dépôt",
+        "Financière",
+        "Industrielle",
+        "Immobilière",
+        "Pétrole",
+        "Château",
+        "Français",
+        "Lyonnaise",
+        "Cœur",
+        "Métropole",
+    ],
+    native_words: &[],
+    suffixes: &["SA", "SCA", "SE", "SAS"],
+    native_suffixes: &[],
+    countries: &["FR", "BE", "LU"],
+    numeric_symbols: false,
+    accent_ratio: 0.3,
+    native_ratio: 0.0,
+};
+
+pub const JAPANESE: Profile = Profile {
+    name: "japanese",
+    plain_words: &[
+        "Toyota",
+        "Mitsubishi",
+        "Sumitomo",
+        "Mitsui",
+        "Nippon",
+        "Tokyo",
+        "Osaka",
+        "Hitachi",
+        "Electric",
+        "Motor",
+        "Trading",
+        "Chemical",
+        "Steel",
+        "Financial",
+        "Heavy",
+        "Industries",
+        "Pharmaceutical",
+        "Railway",
+        "Telegraph",
+        "Telephone",
+        "Realty",
+    ],
+    accented_words: &[],
+    native_words: &[
+        "トヨタ",
+        "三菱",
+        "住友",
+        "三井",
+        "日本",
+        "東京",
+        "大阪",
+        "日立",
+        "自動車",
+        "商事",
+        "電力",
+        "銀行",
+        "証券",
+        "化学",
+        "工業",
+        "製薬",
+        "不動産",
+        "通信",
+        "信託",
+        "電機",
+        "重工",
+        "鉄道",
+        "ソニー",
+        "任天堂",
+        "ホールディングス",
+        "フィナンシャル",
+        "グループ",
+    ],
+    suffixes: &["Corp.", "Co., Ltd.", "Holdings", "Inc."],
+    native_suffixes: &["株式会社", "ホールディングス"],
+    countries: &["JP"],
+    numeric_symbols: true,
+    accent_ratio: 0.0,
+    native_ratio: 0.5,
+};
+
+/// Profiles drawn from, per row, by the `mixed` selection.
+pub const ALL: [&Profile; 4] = [&ENGLISH, &GERMAN, &FRENCH, &JAPANESE];
+
+/// A `--profile` selection: one profile, or every profile row by row.
+pub enum Selection {
+    Single(&'static Profile),
+    Mixed,
+}
+
+impl FromStr for Selection {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "mixed" {
+            return Ok(Selection::Mixed);
+        }
+        ALL.iter()
+            .find(|p| p.name == s)
+            .map(|p| Selection::Single(p))
+            .ok_or_else(|| {
+                format!("unknown profile `{s}` (english, german, french, japanese, mixed)")
+            })
+    }
+}
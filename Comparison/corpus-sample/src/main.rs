@@ -0,0 +1,86 @@
+//! Writes a small corpus subset that keeps the full corpus's shape.
+//!
+//! Usage: corpus-sample [--tsv PATH] [--map symbol=KEY,name=KEY,isin=KEY]
+//!                      [--rows N] [--seed N] [--out PATH]
+//!
+//! Rows are sampled within strata of symbol length, name length, script
+//! class and ISIN presence (see `harness::sample`), so quick runs on the
+//! subset hit the same mix of short codes, long fund names and non-Latin
+//! text as the full corpus. Metadata columns are copied unchanged. The share
+//! of each stratum in the corpus and the sample is printed to stderr.
+
+use harness::cli::{flag_value, parsed_flag};
+use harness::corpus::{load_corpus_mapped, FieldMap};
+use harness::manifest::{Manifest, Schema};
+use harness::resource_path;
+use harness::rng::Rng;
+use harness::sample::{strata, stratified_sample, Stratum};
+use std::collections::HashMap;
+use std::env;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let tsv_path = flag_value(&args, "--tsv")
+        .map(str::to_string)
+        .unwrap_or_else(|| resource_path("instruments-export.tsv"));
+    let field_map: FieldMap = parsed_flag(&args, "--map", FieldMap::default());
+    let rows: usize = parsed_flag(&args, "--rows", 10_000);
+    let seed: u64 = parsed_flag(&args, "--seed", 42);
+
+    let instruments = load_corpus_mapped(&tsv_path, &field_map).expect("Failed to read corpus");
+    let picked = stratified_sample(&instruments, rows, &mut Rng::new(seed));
+
+    let mut out: Box<dyn Write> = match flag_value(&args, "--out") {
+        Some(path) => Box::new(BufWriter::new(
+            File::create(path).expect("Failed to create output file"),
+        )),
+        None => Box::new(BufWriter::new(io::stdout().lock())),
+    };
+    let mut header = vec!["Symbol", "Name", "ISIN"];
+    header.extend(instruments.metadata_columns().iter().map(String::as_str));
+    writeln!(out, "{}", header.join("\t")).expect("Failed to write sample");
+    for &idx in &picked {
+        let inst = instruments.get(idx);
+        let mut row = vec![inst.symbol, inst.name, inst.isin];
+        row.extend(instruments.metadata(idx).values());
+        writeln!(out, "{}", row.join("\t")).expect("Failed to write sample");
+    }
+    out.flush().expect("Failed to write sample");
+    if let Some(path) = flag_value(&args, "--out") {
+        let schema = Schema::instruments_with_metadata(instruments.metadata_columns());
+        Manifest::write_for(path, "corpus-sample", Some(seed), schema)
+            .expect("Failed to write manifest");
+    }
+
+    let mut sampled: HashMap<Stratum, usize> = HashMap::new();
+    for &idx in &picked {
+        *sampled
+            .entry(Stratum::of(&instruments.get(idx)))
+            .or_default() += 1;
+    }
+    let mut full: Vec<(Stratum, usize)> = strata(&instruments)
+        .into_iter()
+        .map(|(stratum, members)| (stratum, members.len()))
+        .collect();
+    full.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+
+    eprintln!(
+        "Sampled {} of {} instruments across {} strata",
+        picked.len(),
+        instruments.len(),
+        full.len()
+    );
+    eprintln!("{:<40} {:>8} {:>8}", "Stratum", "Corpus", "Sample");
+    eprintln!("{}", "-".repeat(58));
+    for (stratum, count) in full {
+        let share = |n: usize, total: usize| n as f64 * 100.0 / total as f64;
+        eprintln!(
+            "{:<40} {:>7.2}% {:>7.2}%",
+            stratum.to_string(),
+            share(count, instruments.len()),
+            share(sampled[&stratum], picked.len())
+        );
+    }
+}
@@ -0,0 +1,21 @@
+//! Reference answers for the differential tests against nucleo.
+//!
+//! The oracles here are the plainest statement of what a match means, with
+//! no scoring, folding tables or shortcuts, so that a disagreement points
+//! at the matcher under test rather than at the oracle.
+
+/// Whether the characters of `query` appear in `candidate` in order,
+/// ignoring ASCII case.
+pub fn is_subsequence(query: &str, candidate: &str) -> bool {
+    let mut rest = candidate.chars();
+    query
+        .chars()
+        .all(|q| rest.any(|c| c.eq_ignore_ascii_case(&q)))
+}
+
+/// Whether `query` appears in `candidate` as one run, ignoring ASCII case.
+pub fn is_substring(query: &str, candidate: &str) -> bool {
+    candidate
+        .to_ascii_lowercase()
+        .contains(&query.to_ascii_lowercase())
+}
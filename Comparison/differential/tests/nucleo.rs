@@ -0,0 +1,127 @@
+//! Generated query/candidate pairs on which nucleo and the harness's ports
+//! of the Swift matcher's pieces must agree.
+//!
+//! Scores are not compared: the two matchers weigh bonuses differently by
+//! design. What is compared is whether a pair matches at all, on inputs
+//! where that is a plain subsequence or substring question, and what a
+//! match highlights.
+
+use differential::{is_subsequence, is_substring};
+use harness::prefilter::CharMask;
+use harness::window::best_window;
+use nucleo_matcher::pattern::{AtomKind, CaseMatching, Normalization, Pattern};
+use nucleo_matcher::{Config, Matcher, Utf32Str};
+use proptest::prelude::*;
+
+/// A few letters in both cases, digits and punctuation, so that generated
+/// queries hit often enough to test the matched side too. No spaces: they
+/// split a nucleo pattern into atoms.
+const ASCII: &[char] = &[
+    'a', 'b', 'c', 'x', 'A', 'B', 'C', 'X', '1', '2', '-', '.', '&',
+];
+
+/// ASCII plus characters nucleo folds or normalizes.
+const UNICODE: &[char] = &['a', 'e', 'E', 'é', 'É', 'ß', 'İ', 'ﬁ', 'Σ', 'σ', '-', '1'];
+
+fn text(alphabet: &'static [char], len: std::ops::Range<usize>) -> impl Strategy<Value = String> {
+    prop::collection::vec(prop::sample::select(alphabet), len)
+        .prop_map(|chars| chars.into_iter().collect())
+}
+
+fn score(query: &str, kind: AtomKind, candidate: &str, indices: &mut Vec<u32>) -> Option<u32> {
+    let mut matcher = Matcher::new(Config::DEFAULT);
+    let pattern = Pattern::new(query, CaseMatching::Ignore, Normalization::Smart, kind);
+    let mut buf = Vec::new();
+    indices.clear();
+    let score = pattern.indices(Utf32Str::new(candidate, &mut buf), &mut matcher, indices);
+    indices.sort_unstable();
+    indices.dedup();
+    score
+}
+
+/// Whether nucleo's case-insensitive substring search is exact for
+/// `query`. In nucleo-matcher 0.3, when the needle's first letter comes two
+/// or more characters in, the search runs case-sensitively and never looks
+/// at the last position the needle could start at, so "&-a" misses both
+/// "x&-a" and "&-A".
+fn nucleo_substring_is_exact(query: &str) -> bool {
+    query
+        .chars()
+        .position(|c| c.is_ascii_alphabetic())
+        .is_none_or(|first| first < 2)
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(2048))]
+
+    #[test]
+    fn fuzzy_matches_exactly_the_subsequences(
+        query in text(ASCII, 1..6),
+        candidate in text(ASCII, 0..24),
+    ) {
+        let matched = score(&query, AtomKind::Fuzzy, &candidate, &mut Vec::new()).is_some();
+        prop_assert_eq!(matched, is_subsequence(&query, &candidate));
+    }
+
+    #[test]
+    fn the_char_mask_keeps_every_fuzzy_match(
+        query in text(ASCII, 1..6),
+        candidate in text(ASCII, 0..24),
+    ) {
+        if score(&query, AtomKind::Fuzzy, &candidate, &mut Vec::new()).is_some() {
+            prop_assert!(CharMask::of(&candidate).may_contain(CharMask::of(&query)));
+        }
+    }
+
+    #[test]
+    fn highlights_spell_the_query_in_order(
+        query in text(ASCII, 1..6),
+        candidate in text(ASCII, 0..24),
+    ) {
+        let mut indices = Vec::new();
+        if score(&query, AtomKind::Fuzzy, &candidate, &mut indices).is_some() {
+            let chars: Vec<char> = candidate.chars().collect();
+            prop_assert_eq!(indices.len(), query.chars().count());
+            for (&idx, q) in indices.iter().zip(query.chars()) {
+                let c = chars.get(idx as usize).copied();
+                prop_assert!(c.is_some_and(|c| c.eq_ignore_ascii_case(&q)), "{:?} at {}", c, idx);
+            }
+        }
+    }
+
+    #[test]
+    fn exact_windows_are_the_substrings(
+        query in text(ASCII, 1..5),
+        candidate in text(ASCII, 0..24),
+    ) {
+        let window = best_window(&query, &candidate, 0);
+        let substring = score(&query, AtomKind::Substring, &candidate, &mut Vec::new()).is_some();
+        prop_assert_eq!(window.is_some(), is_substring(&query, &candidate));
+        if nucleo_substring_is_exact(&query) {
+            prop_assert_eq!(substring, is_substring(&query, &candidate));
+        }
+        if let Some(window) = window {
+            prop_assert_eq!(window.end - window.start, query.len());
+            prop_assert!(candidate[window.start..window.end].eq_ignore_ascii_case(&query));
+        }
+    }
+
+    #[test]
+    fn unicode_highlights_stay_inside_the_candidate(
+        query in text(UNICODE, 1..5),
+        candidate in text(UNICODE, 0..16),
+    ) {
+        let mut indices = Vec::new();
+        let fuzzy = score(&query, AtomKind::Fuzzy, &candidate, &mut indices);
+        let len = candidate.chars().count();
+        prop_assert!(indices.iter().all(|&idx| (idx as usize) < len));
+        if let Some(window) = best_window(&query, &candidate, 0) {
+            prop_assert!(window.start <= window.end && window.end <= len);
+        }
+        // Folding only widens what matches, so a plain subsequence always
+        // matches.
+        if is_subsequence(&query, &candidate) {
+            prop_assert!(fuzzy.is_some());
+        }
+    }
+}
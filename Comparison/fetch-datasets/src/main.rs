@@ -0,0 +1,227 @@
+//! Downloads public reference corpora into a local cache.
+//!
+//! Usage: fetch-datasets [--cache DIR] [--list] [--force] [NAME...]
+//!
+//! Each dataset is downloaded once, checked against its checksum and
+//! converted to the harness corpus format (`<cache>/<name>.tsv`, with a
+//! manifest), so comparisons can run without access to the internal export:
+//!
+//!     cargo run --release -p bench-nucleo -- --tsv ~/.cache/fuzzymatch-comparison/nasdaq-listed.tsv
+//!
+//! Datasets with a pinned checksum must match it. Lists that change upstream
+//! every day cannot be pinned; their checksum is recorded on first download
+//! in `<cache>/checksums.tsv` and cached copies are verified against it.
+//! Without `--force`, a verified cached copy is reused instead of refetched.
+
+use harness::cli::{flag_value, has_flag};
+use harness::manifest::{sha256_file, Manifest, Schema};
+use std::collections::BTreeMap;
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// How a downloaded file becomes a corpus.
+#[derive(Clone, Copy)]
+enum Format {
+    /// Nasdaq Trader symbol directory: pipe-separated, symbol and security
+    /// name in the first two columns, a trailing "File Creation Time" line.
+    NasdaqDirectory,
+    /// One word per line, searched by name.
+    Wordlist,
+}
+
+struct Dataset {
+    name: &'static str,
+    description: &'static str,
+    url: &'static str,
+    /// Pinned SHA-256 for datasets that never change upstream.
+    sha256: Option<&'static str>,
+    format: Format,
+}
+
+const DATASETS: &[Dataset] = &[
+    Dataset {
+        name: "nasdaq-listed",
+        description: "Nasdaq-listed securities (~5K tickers and names, updated daily)",
+        url: "https://www.nasdaqtrader.com/dynamic/SymDir/nasdaqlisted.txt",
+        sha256: None,
+        format: Format::NasdaqDirectory,
+    },
+    Dataset {
+        name: "nasdaq-other",
+        description: "NYSE and other exchange listings (~7K tickers and names, updated daily)",
+        url: "https://www.nasdaqtrader.com/dynamic/SymDir/otherlisted.txt",
+        sha256: None,
+        format: Format::NasdaqDirectory,
+    },
+    Dataset {
+        name: "english-words",
+        description: "English wordlist (~370K lowercase words)",
+        url: "https://raw.githubusercontent.com/dwyl/english-words/master/words_alpha.txt",
+        sha256: None,
+        format: Format::Wordlist,
+    },
+];
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if has_flag(&args, "--list") {
+        for dataset in DATASETS {
+            println!("{:<16} {}", dataset.name, dataset.description);
+        }
+        return;
+    }
+    let cache = flag_value(&args, "--cache")
+        .map(PathBuf::from)
+        .unwrap_or_else(default_cache);
+    let force = has_flag(&args, "--force");
+
+    // Positional arguments are dataset names; skip flags and flag values.
+    let value_flags = ["--cache"];
+    let names: Vec<&str> = args
+        .iter()
+        .enumerate()
+        .skip(1)
+        .filter(|(i, a)| !a.starts_with("--") && !value_flags.contains(&args[i - 1].as_str()))
+        .map(|(_, a)| a.as_str())
+        .collect();
+    let selected: Vec<&Dataset> = if names.is_empty() {
+        DATASETS.iter().collect()
+    } else {
+        names
+            .iter()
+            .map(|name| {
+                DATASETS
+                    .iter()
+                    .find(|d| d.name == *name)
+                    .unwrap_or_else(|| panic!("Unknown dataset {name} (see --list)"))
+            })
+            .collect()
+    };
+
+    fs::create_dir_all(&cache).expect("Failed to create cache directory");
+    let mut recorded = read_checksums(&cache);
+    for dataset in selected {
+        let raw = cache.join(format!("{}.raw", dataset.name));
+        // --force re-records unpinned checksums, accepting upstream changes.
+        let expected = match dataset.sha256 {
+            Some(pinned) => Some(pinned.to_string()),
+            None if force => None,
+            None => recorded.get(dataset.name).cloned(),
+        };
+
+        if raw.exists() && !force && expected.is_some() {
+            println!("{}: using cached {}", dataset.name, raw.display());
+        } else {
+            println!("{}: downloading {}", dataset.name, dataset.url);
+            download(dataset.url, &raw).expect("Failed to download dataset");
+        }
+
+        let sha256 = sha256_file(&path_str(&raw)).expect("Failed to hash download");
+        match &expected {
+            Some(expected) if *expected != sha256 => {
+                let _ = fs::remove_file(&raw);
+                let hint = if dataset.sha256.is_some() {
+                    "the pinned checksum"
+                } else {
+                    "the recorded checksum (use --force to accept an upstream change)"
+                };
+                panic!(
+                    "{}: checksum {sha256} does not match {hint} {expected}; removed the file",
+                    dataset.name
+                );
+            }
+            Some(_) => {}
+            None => {
+                println!("{}: recorded checksum {sha256}", dataset.name);
+                recorded.insert(dataset.name.to_string(), sha256);
+            }
+        }
+
+        let tsv = path_str(&cache.join(format!("{}.tsv", dataset.name)));
+        let rows = convert(&raw, &tsv, dataset.format).expect("Failed to convert dataset");
+        Manifest::write_for(&tsv, "fetch-datasets", None, Schema::instruments())
+            .expect("Failed to write manifest");
+        println!("{}: {rows} instruments in {tsv}", dataset.name);
+    }
+    write_checksums(&cache, &recorded).expect("Failed to record checksums");
+}
+
+/// `$XDG_CACHE_HOME/fuzzymatch-comparison`, falling back to `~/.cache`.
+fn default_cache() -> PathBuf {
+    let base = env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".cache")))
+        .unwrap_or_else(|| PathBuf::from("."));
+    base.join("fuzzymatch-comparison")
+}
+
+/// Downloads to a temporary file first, so an interrupted transfer never
+/// leaves a truncated dataset that looks cached.
+fn download(url: &str, dest: &Path) -> io::Result<()> {
+    let response = ureq::get(url)
+        .call()
+        .map_err(|e| io::Error::other(e.to_string()))?;
+    let partial = dest.with_extension("partial");
+    let mut file = File::create(&partial)?;
+    io::copy(&mut response.into_reader(), &mut file)?;
+    file.sync_all()?;
+    fs::rename(partial, dest)
+}
+
+fn convert(raw: &Path, tsv: &str, format: Format) -> io::Result<usize> {
+    let input = BufReader::new(File::open(raw)?);
+    let mut out = BufWriter::new(File::create(tsv)?);
+    writeln!(out, "Symbol\tName\tISIN")?;
+    let mut rows = 0;
+    for (i, line) in input.lines().enumerate() {
+        let line = line?;
+        let line = line.trim();
+        let (symbol, name) = match format {
+            Format::NasdaqDirectory => {
+                if i == 0 || line.starts_with("File Creation Time") {
+                    continue;
+                }
+                let mut cols = line.split('|');
+                (cols.next().unwrap_or(""), cols.next().unwrap_or(""))
+            }
+            Format::Wordlist => ("", line),
+        };
+        if symbol.is_empty() && name.is_empty() {
+            continue;
+        }
+        writeln!(out, "{}\t{}\t", clean(symbol), clean(name))?;
+        rows += 1;
+    }
+    out.flush()?;
+    Ok(rows)
+}
+
+/// Tabs would shift columns in the corpus TSV.
+fn clean(value: &str) -> String {
+    value.replace('\t', " ")
+}
+
+fn read_checksums(cache: &Path) -> BTreeMap<String, String> {
+    let Ok(content) = fs::read_to_string(cache.join("checksums.tsv")) else {
+        return BTreeMap::new();
+    };
+    content
+        .lines()
+        .filter_map(|line| line.split_once('\t'))
+        .map(|(name, sha)| (name.to_string(), sha.to_string()))
+        .collect()
+}
+
+fn write_checksums(cache: &Path, checksums: &BTreeMap<String, String>) -> io::Result<()> {
+    let mut out = BufWriter::new(File::create(cache.join("checksums.tsv"))?);
+    for (name, sha) in checksums {
+        writeln!(out, "{name}\t{sha}")?;
+    }
+    out.flush()
+}
+
+fn path_str(path: &Path) -> String {
+    path.to_string_lossy().to_string()
+}
@@ -0,0 +1,263 @@
+//! An interactive fuzzy finder over the instrument corpus.
+//!
+//! Usage: finder [--tsv PATH] [--map symbol=KEY,name=KEY,isin=KEY]
+//!               [--field name|symbol|isin] [--limit N]
+//!
+//! Every keystroke rescans the searched field with the nucleo pattern the
+//! harnesses time and redraws the best rows, matched characters
+//! highlighted, with the match count and how long the scan took. Only the
+//! rows on screen are highlighted, when first drawn, so a long `--limit`
+//! costs nothing for rows never scrolled to. Tab
+//! cycles the searched field, Up and Down move the selection, Enter prints
+//! the selected row and exits, Esc exits. It is the quickest way to see
+//! how a ranking change feels on real queries before measuring it.
+
+use harness::cli::{flag_value, parsed_flag};
+use harness::corpus::{load_corpus_mapped, Corpus, FieldMap};
+use harness::highlight::LazyHighlight;
+use harness::prepared::PreparedCorpus;
+use harness::resource_path;
+use harness::topk::TopK;
+use nucleo_matcher::pattern::{AtomKind, CaseMatching, Normalization, Pattern};
+use nucleo_matcher::{Config, Matcher, Utf32Str};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph};
+use ratatui::Frame;
+use std::env;
+use std::time::{Duration, Instant};
+
+const FIELDS: [&str; 3] = ["name", "symbol", "isin"];
+
+/// One ranked row, with the highlighted ranges of the searched field once
+/// it has been drawn.
+struct Row {
+    idx: usize,
+    score: u32,
+    highlight: LazyHighlight,
+}
+
+struct Finder {
+    corpus: Corpus,
+    prepared: PreparedCorpus,
+    matcher: Matcher,
+    limit: usize,
+    query: String,
+    /// The pattern the rows were ranked with, which highlights them.
+    pattern: Pattern,
+    field: usize,
+    rows: Vec<Row>,
+    matches: usize,
+    elapsed: Duration,
+    selected: ListState,
+}
+
+impl Finder {
+    /// Rescans the searched field for the current query.
+    fn search(&mut self) {
+        let start = Instant::now();
+        let field = FIELDS[self.field];
+        let column = self.prepared.column(field);
+        let pattern = Pattern::new(
+            &self.query,
+            CaseMatching::Ignore,
+            Normalization::Smart,
+            AtomKind::Fuzzy,
+        );
+        let mut buf = Vec::new();
+        let mut top = TopK::new(self.limit);
+        self.matches = 0;
+        if !self.query.trim().is_empty() {
+            for (idx, candidate) in column.iter().enumerate() {
+                let haystack = Utf32Str::new(candidate, &mut buf);
+                if let Some(score) = pattern.score(haystack, &mut self.matcher) {
+                    self.matches += 1;
+                    top.push(score, idx);
+                }
+            }
+        }
+        self.rows = top
+            .into_sorted()
+            .into_iter()
+            .map(|(score, idx)| Row {
+                idx,
+                score,
+                highlight: LazyHighlight::new(),
+            })
+            .collect();
+        self.pattern = pattern;
+        self.elapsed = start.elapsed();
+        self.selected.select((!self.rows.is_empty()).then_some(0));
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [input, status, results] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Length(1),
+            Constraint::Min(0),
+        ])
+        .areas(frame.area());
+
+        let field = FIELDS[self.field];
+        frame.render_widget(
+            Paragraph::new(self.query.as_str()).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!(" {} (Tab to switch) ", field)),
+            ),
+            input,
+        );
+        frame.set_cursor_position((input.x + 1 + self.query.chars().count() as u16, input.y + 1));
+        frame.render_widget(
+            Paragraph::new(format!(
+                " {} of {} match · {:.2} ms",
+                self.matches,
+                self.corpus.len(),
+                self.elapsed.as_secs_f64() * 1000.0
+            ))
+            .style(Style::default().fg(Color::DarkGray)),
+            status,
+        );
+
+        // The rows the list will show: it scrolls just far enough to keep
+        // the selection on screen
+        let height = results.height as usize;
+        let mut offset = self.selected.offset();
+        if let Some(selected) = self.selected.selected() {
+            offset = offset
+                .min(selected)
+                .max((selected + 1).saturating_sub(height));
+        }
+        let visible = offset..offset + height;
+
+        let marked = Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD);
+        let matcher = &mut self.matcher;
+        let items: Vec<ListItem> = self
+            .rows
+            .iter()
+            .enumerate()
+            .map(|(position, row)| {
+                let instrument = self.corpus.get(row.idx);
+                let text = self.corpus.text(row.idx, field);
+                let highlight = if visible.contains(&position) {
+                    row.highlight.get(
+                        &self.pattern,
+                        matcher,
+                        self.prepared.column(field).get(row.idx),
+                    )
+                } else {
+                    None
+                };
+                let mut spans: Vec<Span> = text
+                    .chars()
+                    .enumerate()
+                    .map(|(i, c)| {
+                        let style = if highlight
+                            .is_some_and(|ranges| ranges.iter().any(|r| r.contains(&i)))
+                        {
+                            marked
+                        } else {
+                            Style::default()
+                        };
+                        Span::styled(c.to_string(), style)
+                    })
+                    .collect();
+                let others: Vec<&str> = [
+                    ("symbol", instrument.symbol),
+                    ("name", instrument.name),
+                    ("isin", instrument.isin),
+                ]
+                .into_iter()
+                .filter(|&(other, value)| other != field && !value.is_empty())
+                .map(|(_, value)| value)
+                .collect();
+                spans.push(Span::styled(
+                    format!("  {}  [{}]", others.join(" · "), row.score),
+                    Style::default().fg(Color::DarkGray),
+                ));
+                ListItem::new(Line::from(spans))
+            })
+            .collect();
+        frame.render_stateful_widget(
+            List::new(items).highlight_style(Style::default().add_modifier(Modifier::REVERSED)),
+            results,
+            &mut self.selected,
+        );
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let tsv_path = flag_value(&args, "--tsv")
+        .map(str::to_string)
+        .unwrap_or_else(|| resource_path("instruments-export.tsv"));
+    let field_map: FieldMap = parsed_flag(&args, "--map", FieldMap::default());
+    let field = flag_value(&args, "--field").unwrap_or("name");
+    let field = FIELDS.iter().position(|&f| f == field).unwrap_or_else(|| {
+        panic!(
+            "Unknown field `{}` (supported: {})",
+            field,
+            FIELDS.join(", ")
+        )
+    });
+
+    let corpus = load_corpus_mapped(&tsv_path, &field_map).expect("Failed to read corpus");
+    let prepared = PreparedCorpus::new(&corpus, []);
+    let mut finder = Finder {
+        corpus,
+        prepared,
+        matcher: Matcher::new(Config::DEFAULT),
+        limit: parsed_flag(&args, "--limit", 50),
+        query: String::new(),
+        field,
+        rows: Vec::new(),
+        matches: 0,
+        elapsed: Duration::ZERO,
+        selected: ListState::default(),
+        pattern: Pattern::default(),
+    };
+
+    let mut terminal = ratatui::init();
+    let chosen = loop {
+        if let Err(e) = terminal.draw(|frame| finder.draw(frame)) {
+            ratatui::restore();
+            panic!("Failed to draw: {e}");
+        }
+        let Ok(Event::Key(key)) = event::read() else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        match key.code {
+            KeyCode::Esc => break None,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => break None,
+            KeyCode::Enter => break finder.selected.selected().map(|i| finder.rows[i].idx),
+            KeyCode::Up => finder.selected.select_previous(),
+            KeyCode::Down => finder.selected.select_next(),
+            KeyCode::Tab => {
+                finder.field = (finder.field + 1) % FIELDS.len();
+                finder.search();
+            }
+            KeyCode::Backspace => {
+                finder.query.pop();
+                finder.search();
+            }
+            KeyCode::Char(c) => {
+                finder.query.push(c);
+                finder.search();
+            }
+            _ => {}
+        }
+    };
+    ratatui::restore();
+
+    if let Some(idx) = chosen {
+        let row = finder.corpus.get(idx);
+        println!("{}\t{}\t{}", row.symbol, row.name, row.isin);
+    }
+}
@@ -0,0 +1,33 @@
+//! Parses arbitrary queries as nucleo patterns and as `--filter`
+//! expressions, and matches the patterns against arbitrary candidates.
+//!
+//! Neither parser may panic, and every highlight index a match reports
+//! must fall inside the candidate.
+
+#![no_main]
+
+use harness::filter::Filter;
+use libfuzzer_sys::fuzz_target;
+use nucleo_matcher::pattern::{CaseMatching, Normalization, Pattern};
+use nucleo_matcher::{Config, Matcher, Utf32Str};
+
+fuzz_target!(|input: (&str, &str)| {
+    let (query, candidate) = input;
+    let _ = query.parse::<Filter>();
+
+    let mut matcher = Matcher::new(Config::DEFAULT);
+    let pattern = Pattern::parse(query, CaseMatching::Ignore, Normalization::Smart);
+    let mut buf = Vec::new();
+    let haystack = Utf32Str::new(candidate, &mut buf);
+    let mut indices = Vec::new();
+    let highlighted = pattern.indices(haystack, &mut matcher, &mut indices);
+    assert_eq!(highlighted, pattern.score(haystack, &mut matcher));
+    for &idx in &indices {
+        assert!(
+            (idx as usize) < haystack.len(),
+            "index {} outside a {}-character candidate",
+            idx,
+            haystack.len()
+        );
+    }
+});
@@ -0,0 +1,62 @@
+//! Checks the invariants of fuzzy scoring on arbitrary input.
+//!
+//! - a candidate always matches a query of its own text;
+//! - a query that misses a candidate still misses it with a character
+//!   appended, as every character of the query has to be found in order;
+//! - on ASCII candidates, the character mask prefilter never rejects a
+//!   candidate nucleo matches.
+
+#![no_main]
+
+use harness::prefilter::CharMask;
+use libfuzzer_sys::fuzz_target;
+use nucleo_matcher::pattern::{AtomKind, CaseMatching, Normalization, Pattern};
+use nucleo_matcher::{Config, Matcher, Utf32Str};
+
+fn fuzzy(query: &str) -> Pattern {
+    Pattern::new(
+        query,
+        CaseMatching::Ignore,
+        Normalization::Smart,
+        AtomKind::Fuzzy,
+    )
+}
+
+fuzz_target!(|input: (&str, &str, char)| {
+    let (query, candidate, extra) = input;
+    let mut matcher = Matcher::new(Config::DEFAULT);
+    let mut buf = Vec::new();
+    let haystack = Utf32Str::new(candidate, &mut buf);
+
+    // Spaces separate atoms, so only a candidate without one is a single
+    // query.
+    if candidate.is_ascii() && !candidate.is_empty() && !candidate.contains(' ') {
+        assert!(
+            fuzzy(candidate).score(haystack, &mut matcher).is_some(),
+            "{:?} does not match itself",
+            candidate
+        );
+    }
+
+    let score = fuzzy(query).score(haystack, &mut matcher);
+    if score.is_none() && extra != ' ' {
+        let longer = format!("{}{}", query, extra);
+        assert_eq!(
+            fuzzy(&longer).score(haystack, &mut matcher),
+            None,
+            "{:?} matches {:?} though {:?} does not",
+            longer,
+            candidate,
+            query
+        );
+    }
+
+    if candidate.is_ascii() && score.is_some() {
+        assert!(
+            CharMask::of(candidate).may_contain(CharMask::of(query)),
+            "the mask rejects {:?} for {:?}",
+            candidate,
+            query
+        );
+    }
+});
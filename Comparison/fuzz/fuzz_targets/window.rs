@@ -0,0 +1,23 @@
+//! Searches arbitrary text for the best window of an arbitrary query.
+//!
+//! A window must lie inside the text's characters as given, whatever case
+//! folding does to them, and be no further from the query than the budget.
+
+#![no_main]
+
+use harness::window::{best_window, default_max_distance};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: (&str, &str)| {
+    let (query, text) = input;
+    // The search is quadratic; long inputs only slow the fuzzer down.
+    if query.len() > 64 || text.len() > 1024 {
+        return;
+    }
+    let max_distance = default_max_distance(query);
+    if let Some(window) = best_window(query, text, max_distance) {
+        assert!(window.start <= window.end);
+        assert!(window.end <= text.chars().count());
+        assert!(window.distance <= max_distance);
+    }
+});
@@ -0,0 +1,245 @@
+//! The comparison harness as a Python module, for ranking experiments in
+//! notebooks.
+//!
+//! `fuzzymatch_py.Corpus` loads and prepares a corpus the way the Rust
+//! binaries do and scores queries against it with the same nucleo pattern
+//! as `quality-nucleo`, so a notebook sees the rankings the harness
+//! reports. The metric functions are `harness::metrics` unchanged. Hits are
+//! returned as plain dicts and tuples, ready for pandas.
+
+use harness::corpus::{load_corpus_mapped, Corpus as HarnessCorpus, FieldMap};
+use harness::judgments::{load_judgments as read_judgments, Judgment};
+use harness::metrics;
+use harness::prepared::PreparedCorpus;
+use harness::results::{Hit, QueryResult};
+use harness::topk::TopK;
+use nucleo_matcher::pattern::{AtomKind, CaseMatching, Normalization, Pattern};
+use nucleo_matcher::{Config, Matcher, Utf32Str};
+use pyo3::exceptions::{PyIndexError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+fn pattern(query: &str) -> Pattern {
+    Pattern::new(
+        query,
+        CaseMatching::Ignore,
+        Normalization::Smart,
+        AtomKind::Fuzzy,
+    )
+}
+
+/// A loaded corpus with its searched fields prepared for scanning.
+#[pyclass(frozen)]
+struct Corpus {
+    corpus: HarnessCorpus,
+    prepared: PreparedCorpus,
+}
+
+#[pymethods]
+impl Corpus {
+    /// Loads a TSV or JSON Lines corpus, plain or compressed. `map` renames
+    /// columns as `--map` does, e.g. `"symbol=ticker,name=long_name"`.
+    #[new]
+    #[pyo3(signature = (path, map=None))]
+    fn new(path: &str, map: Option<&str>) -> PyResult<Corpus> {
+        let map: FieldMap = match map {
+            Some(map) => map.parse().map_err(PyValueError::new_err)?,
+            None => FieldMap::default(),
+        };
+        let corpus = load_corpus_mapped(path, &map)?;
+        let prepared = PreparedCorpus::new(&corpus, []);
+        Ok(Corpus { corpus, prepared })
+    }
+
+    fn __len__(&self) -> usize {
+        self.corpus.len()
+    }
+
+    /// Row `idx` as a dict of its symbol, name, ISIN, source line and
+    /// metadata columns.
+    fn row<'py>(&self, py: Python<'py>, idx: usize) -> PyResult<Bound<'py, PyDict>> {
+        if idx >= self.corpus.len() {
+            return Err(PyIndexError::new_err(format!(
+                "row {} of a {}-row corpus",
+                idx,
+                self.corpus.len()
+            )));
+        }
+        let row = self.corpus.get(idx);
+        let dict = PyDict::new(py);
+        dict.set_item("line", row.line)?;
+        dict.set_item("symbol", row.symbol)?;
+        dict.set_item("name", row.name)?;
+        dict.set_item("isin", row.isin)?;
+        let metadata = self.corpus.metadata(idx);
+        for (column, value) in self.corpus.metadata_columns().iter().zip(metadata.values()) {
+            dict.set_item(column, value)?;
+        }
+        Ok(dict)
+    }
+
+    /// The `limit` best rows for `query` in `field`, best first, each a
+    /// dict of its row index, score, symbol and name.
+    #[pyo3(signature = (query, field="name", limit=10))]
+    fn search<'py>(
+        &self,
+        py: Python<'py>,
+        query: &str,
+        field: &str,
+        limit: usize,
+    ) -> PyResult<Vec<Bound<'py, PyDict>>> {
+        let pattern = pattern(query);
+        let mut matcher = Matcher::new(Config::DEFAULT);
+        let mut buf = Vec::new();
+        let mut top = TopK::new(limit);
+        for (idx, candidate) in self.prepared.column(field).iter().enumerate() {
+            if let Some(score) = pattern.score(Utf32Str::new(candidate, &mut buf), &mut matcher) {
+                top.push(score, idx);
+            }
+        }
+        top.into_sorted()
+            .into_iter()
+            .map(|(score, idx)| {
+                let row = self.corpus.get(idx);
+                let dict = PyDict::new(py);
+                dict.set_item("row", idx)?;
+                dict.set_item("score", score)?;
+                dict.set_item("symbol", row.symbol)?;
+                dict.set_item("name", row.name)?;
+                Ok(dict)
+            })
+            .collect()
+    }
+
+    /// The score of every row for `query` in `field`, `None` where the row
+    /// does not match, in row order.
+    #[pyo3(signature = (query, field="name"))]
+    fn scores(&self, query: &str, field: &str) -> Vec<Option<u32>> {
+        let pattern = pattern(query);
+        let mut matcher = Matcher::new(Config::DEFAULT);
+        let mut buf = Vec::new();
+        self.prepared
+            .column(field)
+            .iter()
+            .map(|candidate| pattern.score(Utf32Str::new(candidate, &mut buf), &mut matcher))
+            .collect()
+    }
+}
+
+/// nucleo's score for `query` against one candidate, or `None` when it
+/// does not match.
+#[pyfunction]
+fn score(query: &str, candidate: &str) -> Option<u32> {
+    let mut buf = Vec::new();
+    pattern(query).score(
+        Utf32Str::new(candidate, &mut buf),
+        &mut Matcher::new(Config::DEFAULT),
+    )
+}
+
+/// The character positions of `candidate` a match of `query` highlights,
+/// ascending, or `None` when it does not match.
+#[pyfunction]
+fn indices(query: &str, candidate: &str) -> Option<Vec<u32>> {
+    let mut buf = Vec::new();
+    let mut indices = Vec::new();
+    pattern(query).indices(
+        Utf32Str::new(candidate, &mut buf),
+        &mut Matcher::new(Config::DEFAULT),
+        &mut indices,
+    )?;
+    indices.sort_unstable();
+    indices.dedup();
+    Some(indices)
+}
+
+#[pyfunction]
+fn dcg(gains: Vec<f64>) -> f64 {
+    metrics::dcg(&gains)
+}
+
+#[pyfunction]
+fn ndcg(gains: Vec<f64>, relevant: Vec<f64>, k: usize) -> Option<f64> {
+    metrics::ndcg(&gains, &relevant, k)
+}
+
+#[pyfunction]
+fn percentile(values: Vec<f64>, p: f64) -> Option<f64> {
+    metrics::percentile(&values, p)
+}
+
+/// NDCG@`k` of a ranked list of `(symbol, name)` hits, judged as the
+/// report judges a recorded query: by `judgments`, `(symbol, name,
+/// relevance)` tuples, when given, and otherwise by the first hit whose
+/// name contains `expected_name`.
+#[pyfunction]
+#[pyo3(signature = (hits, expected_name="", k=10, judgments=None))]
+fn ndcg_at(
+    hits: Vec<(String, String)>,
+    expected_name: &str,
+    k: usize,
+    judgments: Option<Vec<(String, String, u8)>>,
+) -> Option<f64> {
+    let result = QueryResult {
+        query: String::new(),
+        field: String::new(),
+        category: String::new(),
+        expected_name: expected_name.to_string(),
+        frequency: 1,
+        median_ms: None,
+        min_ms: None,
+        matches: None,
+        results: Some(
+            hits.into_iter()
+                .map(|(symbol, name)| Hit {
+                    symbol,
+                    name,
+                    score: None,
+                })
+                .collect(),
+        ),
+        recheck: None,
+        cost: None,
+        difficulty: None,
+        snapshot: None,
+    };
+    let judgments: Vec<Judgment> = judgments
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(symbol, name, relevance)| Judgment {
+            query: String::new(),
+            field: String::new(),
+            symbol,
+            name,
+            isin: String::new(),
+            relevance,
+        })
+        .collect();
+    let judged: Vec<&Judgment> = judgments.iter().collect();
+    metrics::ndcg_at(&result, &judged, k)
+}
+
+/// One judgments row: `(query, field, symbol, name, isin, relevance)`.
+type JudgmentRow = (String, String, String, String, String, u8);
+
+/// A judgments file, one tuple per row.
+#[pyfunction]
+fn load_judgments(path: &str) -> PyResult<Vec<JudgmentRow>> {
+    Ok(read_judgments(path)?
+        .into_iter()
+        .map(|j| (j.query, j.field, j.symbol, j.name, j.isin, j.relevance))
+        .collect())
+}
+
+#[pymodule]
+fn fuzzymatch_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<Corpus>()?;
+    m.add_function(wrap_pyfunction!(score, m)?)?;
+    m.add_function(wrap_pyfunction!(indices, m)?)?;
+    m.add_function(wrap_pyfunction!(dcg, m)?)?;
+    m.add_function(wrap_pyfunction!(ndcg, m)?)?;
+    m.add_function(wrap_pyfunction!(percentile, m)?)?;
+    m.add_function(wrap_pyfunction!(ndcg_at, m)?)?;
+    m.add_function(wrap_pyfunction!(load_judgments, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,135 @@
+//! Fails a CI job when a result file regresses against its baseline.
+//!
+//! Usage: gate --results RESULTS.json --baseline RESULTS.json [--config gate.toml]
+//!        gate --results RESULTS.json --db PATH [--baseline-run LABEL] [--config gate.toml]
+//!
+//! Compares the queries both runs contain against the limits in `--config`
+//! (see `harness::tolerance` for the format; built-in defaults without it)
+//! and exits with status 1, listing every violation, when any limit is
+//! broken or the runs share no queries. The baseline is either a result
+//! file or a run from a `report record` database: the latest run of the same
+//! backend labelled `--baseline-run`, or else the latest with a different
+//! run label from the results, so a run already recorded is not compared
+//! with itself.
+//!
+//! The change in total latency is printed with a bootstrap confidence
+//! interval over the shared queries (see `harness::significance`), so a
+//! small delta can be told from noise; `require_significance` in the config
+//! lets only significant latency changes fail the gate.
+//!
+//! Runs whose fingerprints differ in matcher configuration, or a current run
+//! built from an uncommitted tree, get a warning; neither fails the gate.
+
+use harness::cli::flag_value;
+use harness::results::ResultSet;
+use harness::store::{RunFilter, Store};
+use harness::tolerance::{self, Tolerances};
+use std::collections::BTreeSet;
+use std::env;
+use std::fs;
+use std::process;
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let results_path = flag_value(&args, "--results").expect("--results is required");
+    let current = ResultSet::read(results_path).expect("Failed to read results file");
+    let tolerances: Tolerances = match flag_value(&args, "--config") {
+        Some(path) => fs::read_to_string(path)
+            .expect("Failed to read gate config")
+            .parse()
+            .unwrap_or_else(|e| panic!("Invalid gate config {path}: {e}")),
+        None => Tolerances::default(),
+    };
+
+    let baseline = match (flag_value(&args, "--baseline"), flag_value(&args, "--db")) {
+        (Some(path), _) => ResultSet::read(path).expect("Failed to read baseline file"),
+        (None, Some(db)) => stored_baseline(db, &current, flag_value(&args, "--baseline-run")),
+        (None, None) => panic!("Either --baseline or --db is required"),
+    };
+
+    println!("Baseline: {}", baseline.label());
+    println!("Current:  {}", current.label());
+    if let (Some(a), Some(b)) = (&baseline.corpus_sha256, &current.corpus_sha256) {
+        if a != b {
+            println!("Warning: the runs used different corpora");
+        }
+    }
+    if let (Some(a), Some(b)) = (&baseline.fingerprint, &current.fingerprint) {
+        let keys: BTreeSet<&String> = a.config.keys().chain(b.config.keys()).collect();
+        let changed: Vec<&str> = keys
+            .into_iter()
+            .filter(|key| a.config.get(*key) != b.config.get(*key))
+            .map(String::as_str)
+            .collect();
+        if !changed.is_empty() {
+            println!(
+                "Warning: the runs used different matcher configurations ({})",
+                changed.join(", ")
+            );
+        }
+    }
+    if let Some(fingerprint) = &current.fingerprint {
+        if fingerprint.git_dirty == Some(true) {
+            println!(
+                "Warning: the current run was built from uncommitted changes ({})",
+                fingerprint.revision()
+            );
+        }
+    }
+
+    let verdict = tolerance::check(&tolerances, &baseline, &current);
+    if verdict.shared == 0 {
+        println!("FAIL: no queries in common with the baseline");
+        process::exit(1);
+    }
+    if let Some(change) = verdict.latency {
+        println!(
+            "Latency: {change}, {}",
+            if change.significant() {
+                "significant"
+            } else {
+                "within noise"
+            }
+        );
+    }
+    if verdict.passed() {
+        println!(
+            "PASS: {} checks over {} shared queries",
+            verdict.checks, verdict.shared
+        );
+        return;
+    }
+    println!(
+        "FAIL: {} of {} checks over {} shared queries",
+        verdict.violations.len(),
+        verdict.checks,
+        verdict.shared
+    );
+    for violation in &verdict.violations {
+        println!("  {violation}");
+    }
+    process::exit(1);
+}
+
+fn stored_baseline(db: &str, current: &ResultSet, run: Option<&str>) -> ResultSet {
+    let store = Store::open(db).expect("Failed to open results database");
+    let filter = RunFilter {
+        backend: Some(current.backend.clone()),
+        ..RunFilter::default()
+    };
+    let runs = store
+        .runs(&filter)
+        .expect("Failed to read results database");
+    let baseline = runs
+        .iter()
+        .rev()
+        .find(|r| match run {
+            Some(label) => r.run == label,
+            None => r.run != current.run,
+        })
+        .unwrap_or_else(|| panic!("No baseline run of {} in {db}", current.backend));
+    store
+        .load(baseline.id)
+        .expect("Failed to read results database")
+        .expect("Baseline run disappeared from the database")
+}
@@ -0,0 +1,97 @@
+//! Worst-case queries derived from a corpus.
+//!
+//! The other categories are the queries users type most often, so their
+//! times say what a typical search costs. Whether a matcher needs a
+//! prefilter is decided by the slowest searches instead, and
+//! [`queries`] writes the `adversarial` category to find them:
+//!
+//! - the letter found in the most names, and in the most symbols, on its
+//!   own. Nearly every row matches, so nothing is rejected early and every
+//!   match is ranked;
+//! - that letter repeated [`REPEATED`] times, which rows holding it many
+//!   times come close to matching, so they are scored in full before they
+//!   fail;
+//! - the words of the longest name repeated to twice its length, and to
+//!   at least [`LONG_QUERY`] characters, so that it is longer than any
+//!   name. Scoring matrices grow with the query;
+//! - the longest name and the longest symbol, which align every character
+//!   of the query against the largest candidate.
+//!
+//! None of them has one right answer, so their expected name is
+//! [`SKIP`]. They depend only on the corpus, never on a seed, so a corpus
+//! always gives the same set.
+
+use crate::corpus::Corpus;
+use crate::frequency::CharFrequency;
+use crate::queries::{Query, SKIP};
+use std::cmp::Reverse;
+
+/// The category the queries are written under.
+pub const CATEGORY: &str = "adversarial";
+
+/// Times the commonest letter is repeated.
+pub const REPEATED: usize = 16;
+
+/// Characters the long query reaches, however short the names.
+pub const LONG_QUERY: usize = 256;
+
+/// The adversarial queries for `corpus`, names first, or none for an
+/// empty corpus.
+pub fn queries(corpus: &Corpus) -> Vec<Query> {
+    let mut queries = Vec::new();
+    for field in ["name", "symbol"] {
+        let values = (0..corpus.len()).map(|row| (corpus.text(row, field).trim(), 1));
+        let frequency = CharFrequency::new(values.clone());
+        let letter = frequency
+            .chars()
+            .into_iter()
+            .filter(|(c, _)| c.is_alphabetic())
+            .max_by_key(|&(c, rows)| (rows, Reverse(c)))
+            .map(|(c, _)| c);
+        // The first of the longest values, counted in characters
+        let longest = values
+            .map(|(text, _)| text)
+            .filter(|text| !text.is_empty())
+            .min_by_key(|text| Reverse(text.chars().count()));
+
+        if let Some(letter) = letter {
+            queries.push(query(&letter.to_string(), field));
+            if field == "name" {
+                queries.push(query(&letter.to_string().repeat(REPEATED), field));
+            }
+        }
+        if let Some(longest) = longest {
+            if field == "name" {
+                let chars = LONG_QUERY.max(2 * longest.chars().count());
+                queries.push(query(&repeat_words(longest, chars), field));
+            }
+            queries.push(query(longest, field));
+        }
+    }
+    queries
+}
+
+/// The words of `text`, over and over, until they make `chars` characters.
+fn repeat_words(text: &str, chars: usize) -> String {
+    let mut out = String::new();
+    for word in text.split_whitespace().cycle() {
+        if out.chars().count() >= chars {
+            break;
+        }
+        if !out.is_empty() {
+            out.push(' ');
+        }
+        out.push_str(word);
+    }
+    out
+}
+
+fn query(text: &str, field: &str) -> Query {
+    Query {
+        text: text.to_string(),
+        field: field.to_string(),
+        category: CATEGORY.to_string(),
+        expected_name: SKIP.to_string(),
+        frequency: 1,
+    }
+}
@@ -0,0 +1,144 @@
+//! Pinning benchmark threads to cores on one NUMA node.
+//!
+//! Left to the scheduler, a benchmark thread can migrate between cores mid
+//! run, and on a multi-socket machine it can land on a different socket
+//! from the memory holding the corpus. Both show up as run-to-run swings
+//! that have nothing to do with the matcher. A [`Placement`] picks cores
+//! from a single NUMA node and pins threads to them in a fixed order, so
+//! every run places its threads the same way.
+//!
+//! Linux allocates a page on the node of the thread that first writes it, so
+//! pinning before the corpus is loaded keeps its memory on the same node as
+//! the threads that scan it.
+//!
+//! NUMA nodes are read from `/sys/devices/system/node`; where that is
+//! missing (other systems, containers that hide it) every allowed core is
+//! treated as one node. Cores outside the process's affinity mask, as set by
+//! `taskset` or a cgroup, are never chosen.
+
+use crate::cli::{flag_value, has_flag};
+use std::fs;
+
+/// Cores chosen for a run's threads, all on one NUMA node.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Placement {
+    pub node: usize,
+    /// Cores in the order threads are pinned to them.
+    pub cores: Vec<usize>,
+}
+
+impl Placement {
+    /// The placement asked for by `--pin-cores` and `--numa-node N`, or
+    /// `None` without `--pin-cores`.
+    pub fn from_args(args: &[String]) -> Result<Option<Placement>, String> {
+        if !has_flag(args, "--pin-cores") {
+            return Ok(None);
+        }
+        let node = flag_value(args, "--numa-node")
+            .map(|n| n.parse().map_err(|_| format!("invalid --numa-node `{n}`")))
+            .transpose()?;
+        Placement::on_node(node).map(Some)
+    }
+
+    /// Cores on `node`, or with `None` on the first node with any core the
+    /// process may run on.
+    pub fn on_node(node: Option<usize>) -> Result<Placement, String> {
+        let allowed: Vec<usize> = core_affinity::get_core_ids()
+            .ok_or("cannot read the cores this process may run on")?
+            .into_iter()
+            .map(|core| core.id)
+            .collect();
+        Placement::choose(&numa_nodes(&allowed), node)
+    }
+
+    /// The placement on `node` (or the first node with cores) among `nodes`,
+    /// given as each node's number and allowed cores.
+    ///
+    /// Core 0 goes last when the node has others, since it usually takes
+    /// more interrupts and housekeeping work than the rest.
+    pub fn choose(nodes: &[(usize, Vec<usize>)], node: Option<usize>) -> Result<Placement, String> {
+        let (node, cores) = match node {
+            Some(wanted) => nodes
+                .iter()
+                .find(|(n, _)| *n == wanted)
+                .filter(|(_, cores)| !cores.is_empty())
+                .ok_or_else(|| format!("NUMA node {wanted} has no cores this process may use"))?,
+            None => nodes
+                .iter()
+                .find(|(_, cores)| !cores.is_empty())
+                .ok_or("no cores to pin to")?,
+        };
+        let mut cores = cores.clone();
+        if cores.len() > 1 {
+            if let Some(zero) = cores.iter().position(|&c| c == 0) {
+                cores.remove(zero);
+                cores.push(0);
+            }
+        }
+        Ok(Placement { node: *node, cores })
+    }
+
+    /// The core thread `thread` (counting from 0) runs on. Threads beyond
+    /// the node's cores share them, starting again from the first.
+    pub fn core_for(&self, thread: usize) -> usize {
+        self.cores[thread % self.cores.len()]
+    }
+
+    /// Pins the calling thread as thread `thread` of the run and returns
+    /// the core it now runs on.
+    pub fn pin(&self, thread: usize) -> Result<usize, String> {
+        let core = self.core_for(thread);
+        if core_affinity::set_for_current(core_affinity::CoreId { id: core }) {
+            Ok(core)
+        } else {
+            Err(format!("cannot pin thread {thread} to core {core}"))
+        }
+    }
+}
+
+/// NUMA nodes with the cores on each that are in `allowed`, by node
+/// number. All of `allowed` as node 0 when the system does not list nodes.
+pub fn numa_nodes(allowed: &[usize]) -> Vec<(usize, Vec<usize>)> {
+    let mut nodes: Vec<(usize, Vec<usize>)> = Vec::new();
+    if let Ok(entries) = fs::read_dir("/sys/devices/system/node") {
+        for entry in entries.flatten() {
+            let file_name = entry.file_name();
+            let Some(node) = file_name
+                .to_str()
+                .and_then(|name| name.strip_prefix("node"))
+                .and_then(|n| n.parse().ok())
+            else {
+                continue;
+            };
+            let Ok(list) = fs::read_to_string(entry.path().join("cpulist")) else {
+                continue;
+            };
+            if let Ok(cores) = parse_cpu_list(&list) {
+                let cores = cores.into_iter().filter(|c| allowed.contains(c)).collect();
+                nodes.push((node, cores));
+            }
+        }
+    }
+    if nodes.iter().all(|(_, cores)| cores.is_empty()) {
+        return vec![(0, allowed.to_vec())];
+    }
+    nodes.sort();
+    nodes
+}
+
+/// Parses a kernel CPU list such as `0-3,8-11,16`.
+pub fn parse_cpu_list(list: &str) -> Result<Vec<usize>, String> {
+    let mut cores = Vec::new();
+    for part in list.trim().split(',').filter(|p| !p.is_empty()) {
+        let number = |s: &str| {
+            s.trim()
+                .parse::<usize>()
+                .map_err(|_| format!("invalid CPU list `{}`", list.trim()))
+        };
+        match part.split_once('-') {
+            Some((from, to)) => cores.extend(number(from)?..=number(to)?),
+            None => cores.push(number(part)?),
+        }
+    }
+    Ok(cores)
+}
@@ -0,0 +1,187 @@
+//! Keyed, consistent anonymization of instrument corpora.
+//!
+//! Every distinct token is replaced by one fake token of the same length,
+//! case pattern and vowel/consonant shape, with digits and punctuation
+//! replaced class for class. The mapping is consistent across the corpus, so
+//! word counts, shared words ("UCITS ETF" in thousands of fund names) and
+//! duplicate ISINs survive, which keeps prefilter and scoring costs close to
+//! the original. ISINs keep their country code and get a valid check digit.
+//!
+//! Fakes are drawn from an HMAC-SHA256 of the real value under a secret key,
+//! never from the value alone: anyone could run a list of tickers, issuer
+//! names or valid ISINs through a public function and reverse the mapping.
+
+use crate::isin;
+use crate::rng::Rng;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::collections::{HashMap, HashSet};
+
+const VOWELS: &[char] = &['a', 'e', 'i', 'o', 'u'];
+const CONSONANTS: &[char] = &[
+    'b', 'c', 'd', 'f', 'g', 'h', 'k', 'l', 'm', 'n', 'p', 'r', 's', 't', 'v', 'w', 'z',
+];
+const ACCENTED: &[char] = &['ä', 'ö', 'ü', 'é', 'è', 'à', 'ç', 'ñ', 'ø', 'å'];
+
+/// Shortest key a caller should accept from a user.
+pub const MIN_KEY_BYTES: usize = 16;
+
+/// Consistent token-to-fake mapping under one key.
+pub struct Anonymizer {
+    key: Vec<u8>,
+    tokens: HashMap<String, String>,
+    used: HashSet<String>,
+    isins: HashMap<String, String>,
+}
+
+impl Anonymizer {
+    /// A mapping keyed by `key`, which should be at least
+    /// [`MIN_KEY_BYTES`] of secret.
+    pub fn new(key: Vec<u8>) -> Self {
+        Anonymizer {
+            key,
+            tokens: HashMap::new(),
+            used: HashSet::new(),
+            isins: HashMap::new(),
+        }
+    }
+
+    /// Replaces each whitespace-separated token, keeping the separators.
+    pub fn text(&mut self, text: &str) -> String {
+        let mut out = String::with_capacity(text.len());
+        let mut token = String::new();
+        for c in text.chars() {
+            if c.is_whitespace() {
+                if !token.is_empty() {
+                    out.push_str(&self.token(&token));
+                    token.clear();
+                }
+                out.push(c);
+            } else {
+                token.push(c);
+            }
+        }
+        if !token.is_empty() {
+            out.push_str(&self.token(&token));
+        }
+        out
+    }
+
+    /// The fake for one token: same length, case pattern and
+    /// vowel/consonant shape, with digits and punctuation replaced class for
+    /// class.
+    pub fn token(&mut self, token: &str) -> String {
+        if let Some(fake) = self.tokens.get(token) {
+            return fake.clone();
+        }
+        // Retry on collisions so distinct tokens stay distinct. Tokens made
+        // only of punctuation map to themselves and may repeat.
+        let mut salt = 0;
+        let fake = loop {
+            let mut rng = self.rng(token, salt);
+            let fake: String = token.chars().map(|c| fake_char(c, &mut rng)).collect();
+            if fake == token || self.used.insert(fake.clone()) || salt > 100 {
+                break fake;
+            }
+            salt += 1;
+        };
+        self.tokens.insert(token.to_string(), fake.clone());
+        fake
+    }
+
+    /// The fake for an ISIN: same country code, a fresh body and a valid
+    /// check digit. Malformed values are rewritten as text.
+    pub fn isin(&mut self, value: &str) -> String {
+        if value.is_empty() {
+            return String::new();
+        }
+        if let Some(fake) = self.isins.get(value) {
+            return fake.clone();
+        }
+        if !value.is_ascii() || value.len() != 12 || isin::check_digit(&value[..11]).is_none() {
+            // Malformed source ISINs stay malformed, class for class.
+            let fake = self.text(value);
+            self.isins.insert(value.to_string(), fake.clone());
+            return fake;
+        }
+        // Like tokens, distinct ISINs must stay distinct so duplicate-ISIN
+        // structure is neither lost nor invented.
+        let mut salt = 0;
+        let fake = loop {
+            let mut rng = self.rng(value, salt);
+            let body: String = value[2..11]
+                .chars()
+                .map(|c| match c {
+                    '0'..='9' => (b'0' + rng.below(10) as u8) as char,
+                    _ => (b'A' + rng.below(26) as u8) as char,
+                })
+                .collect();
+            let partial = format!("{}{body}", &value[..2]);
+            let digit = isin::check_digit(&partial).expect("body is alphanumeric");
+            let fake = format!("{partial}{digit}");
+            if self.used.insert(fake.clone()) {
+                break fake;
+            }
+            salt += 1;
+        };
+        self.isins.insert(value.to_string(), fake.clone());
+        fake
+    }
+
+    /// Distinct tokens mapped so far.
+    pub fn tokens(&self) -> usize {
+        self.tokens.len()
+    }
+
+    /// Distinct ISINs mapped so far.
+    pub fn isins(&self) -> usize {
+        self.isins.len()
+    }
+
+    /// A generator seeded by the keyed hash of `value` and a retry count.
+    fn rng(&self, value: &str, salt: u64) -> Rng {
+        let mut mac = Hmac::<Sha256>::new_from_slice(&self.key).expect("HMAC takes any key length");
+        mac.update(&salt.to_le_bytes());
+        mac.update(value.as_bytes());
+        let digest = mac.finalize().into_bytes();
+        Rng::new(u64::from_le_bytes(digest[..8].try_into().unwrap()))
+    }
+}
+
+/// Replaces a character with a random one of the same class.
+fn fake_char(c: char, rng: &mut Rng) -> char {
+    let lower = c.to_lowercase().next().unwrap_or(c);
+    let replacement = if c.is_ascii_digit() {
+        (b'0' + rng.below(10) as u8) as char
+    } else if VOWELS.contains(&lower) {
+        *rng.choose(VOWELS).unwrap()
+    } else if lower.is_ascii_alphabetic() {
+        *rng.choose(CONSONANTS).unwrap()
+    } else if ACCENTED.contains(&lower) || ('\u{C0}'..='\u{17F}').contains(&lower) {
+        *rng.choose(ACCENTED).unwrap()
+    } else if let Some(block) = script_block(c) {
+        char::from_u32(block.0 + rng.below((block.1 - block.0) as usize) as u32).unwrap_or(c)
+    } else {
+        // Punctuation and symbols carry structure, not identity.
+        return c;
+    };
+    if c.is_uppercase() {
+        replacement.to_uppercase().next().unwrap_or(replacement)
+    } else {
+        replacement
+    }
+}
+
+/// Lowercase letter ranges for non-Latin scripts, so a Cyrillic or CJK name
+/// stays in its script.
+fn script_block(c: char) -> Option<(u32, u32)> {
+    match c as u32 {
+        0x0391..=0x03C9 => Some((0x03B1, 0x03C9)), // Greek
+        0x0410..=0x044F => Some((0x0430, 0x044F)), // Cyrillic
+        0x3041..=0x3096 => Some((0x3041, 0x3096)), // Hiragana
+        0x30A1..=0x30FA => Some((0x30A1, 0x30FA)), // Katakana
+        0x4E00..=0x9FFF => Some((0x4E00, 0x9FFF)), // CJK ideographs
+        0xAC00..=0xD7A3 => Some((0xAC00, 0xD7A3)), // Hangul syllables
+        _ => None,
+    }
+}
@@ -0,0 +1,127 @@
+//! Scans that stop when their time runs out, and what stopping costs.
+//!
+//! A time budget caps how long one query may scan. A budgeted scan checks
+//! its [`Deadline`] every [`CHECK_EVERY`] rows and, once the time is up,
+//! stops and ranks the matches it has found so far. Rows are scanned in
+//! corpus order, so a query cut off early never sees the rows at the end.
+//! That bounds its latency, but a better match further down is lost.
+//!
+//! [`BudgetStats`] sums up one budget over a set of scans: how many were
+//! cut off, how much of the column those saw, and how their top results
+//! compare with an unbudgeted scan's, so that a budget can be chosen
+//! knowing what it costs in quality.
+
+use crate::metrics::percentile;
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+/// Rows scanned between two looks at the clock.
+pub const CHECK_EVERY: usize = 1024;
+
+/// When a budgeted scan started and how long it may take.
+#[derive(Clone, Copy, Debug)]
+pub struct Deadline {
+    start: Instant,
+    limit: Duration,
+}
+
+impl Deadline {
+    /// A deadline `limit` from now.
+    pub fn new(limit: Duration) -> Deadline {
+        Deadline {
+            start: Instant::now(),
+            limit,
+        }
+    }
+
+    /// Whether a scan about to look at `row` should stop. The clock is
+    /// only read on every [`CHECK_EVERY`]th row, and never before the
+    /// first, so every scan sees at least that many rows.
+    #[inline]
+    pub fn expired(&self, row: usize) -> bool {
+        row > 0 && row.is_multiple_of(CHECK_EVERY) && self.start.elapsed() >= self.limit
+    }
+}
+
+/// How one budget fared over a set of scans.
+#[derive(Clone, Debug, Default)]
+pub struct BudgetStats {
+    scans: usize,
+    /// Share of the column each scan that was cut off saw.
+    coverage: Vec<f64>,
+    /// Share of the unbudgeted top results each scan found.
+    recall: Vec<f64>,
+    same_first: usize,
+    times_ms: Vec<f64>,
+}
+
+impl BudgetStats {
+    /// Records one budgeted scan that took `ms` and looked at `scanned`
+    /// of `rows` rows. `top` is its ranked top rows, and `unbudgeted` the
+    /// ranked top rows of the same query scanned in full.
+    pub fn record(
+        &mut self,
+        ms: f64,
+        scanned: usize,
+        rows: usize,
+        top: &[usize],
+        unbudgeted: &[usize],
+    ) {
+        self.scans += 1;
+        self.times_ms.push(ms);
+        if scanned < rows {
+            self.coverage.push(scanned as f64 / rows as f64);
+        }
+        if !unbudgeted.is_empty() {
+            let found: HashSet<usize> = top.iter().copied().collect();
+            let kept = unbudgeted.iter().filter(|row| found.contains(row)).count();
+            self.recall.push(kept as f64 / unbudgeted.len() as f64);
+        }
+        if top.first() == unbudgeted.first() {
+            self.same_first += 1;
+        }
+    }
+
+    pub fn scans(&self) -> usize {
+        self.scans
+    }
+
+    /// Scans stopped before the end of their column.
+    pub fn cut_off(&self) -> usize {
+        self.coverage.len()
+    }
+
+    /// Mean share of the column scanned by scans that were cut off, or 1
+    /// when none was.
+    pub fn mean_coverage(&self) -> f64 {
+        mean(&self.coverage).unwrap_or(1.0)
+    }
+
+    /// Smallest share of the column any scan saw.
+    pub fn min_coverage(&self) -> f64 {
+        self.coverage.iter().copied().fold(1.0, f64::min)
+    }
+
+    /// Mean share of the unbudgeted top results that were still found,
+    /// over scans whose unbudgeted scan found anything.
+    pub fn recall(&self) -> f64 {
+        mean(&self.recall).unwrap_or(1.0)
+    }
+
+    /// Share of scans whose first result was the unbudgeted first result.
+    pub fn same_first(&self) -> f64 {
+        if self.scans == 0 {
+            return 1.0;
+        }
+        self.same_first as f64 / self.scans as f64
+    }
+
+    /// The `p`th percentile of the scan times.
+    pub fn time_ms(&self, p: f64) -> f64 {
+        percentile(&self.times_ms, p).unwrap_or_default()
+    }
+}
+
+fn mean(values: &[f64]) -> Option<f64> {
+    (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+}
@@ -0,0 +1,51 @@
+//! Evicting the CPU caches between queries, to time them cold.
+//!
+//! The benchmark loop runs every query back to back, so the corpus text,
+//! the matcher and the query's own code stay in cache from one pass to the
+//! next. A user typing into a search field gets no such luck: between two
+//! keystrokes the app draws, handles events and runs other work, and the
+//! scan starts with much of its data evicted. [`CacheBuster`] walks a
+//! buffer larger than the last-level cache, writing to every line of it,
+//! which pushes out whatever the previous query left behind.
+
+use std::hint::black_box;
+
+/// Bytes in a cache line on every target the harness runs on.
+const LINE: usize = 64;
+
+/// A buffer that evicts the caches each time it is walked.
+pub struct CacheBuster {
+    lines: Vec<[u64; LINE / 8]>,
+    passes: u64,
+}
+
+impl CacheBuster {
+    /// Default size, comfortably over the last-level cache of desktop and
+    /// phone CPUs alike.
+    pub const DEFAULT_MB: usize = 64;
+
+    /// A buster of `mb` MiB, allocated and touched once so the first flush
+    /// does not also pay for page faults.
+    pub fn new(mb: usize) -> CacheBuster {
+        let mut buster = CacheBuster {
+            lines: vec![[0; LINE / 8]; (mb << 20) / LINE],
+            passes: 0,
+        };
+        buster.flush();
+        buster
+    }
+
+    pub fn bytes(&self) -> usize {
+        self.lines.len() * LINE
+    }
+
+    /// Writes one word of every line, so each line is brought in and
+    /// dirtied, displacing what the caches held before.
+    pub fn flush(&mut self) {
+        self.passes += 1;
+        for line in &mut self.lines {
+            line[0] = line[0].wrapping_add(self.passes);
+        }
+        black_box(&self.lines);
+    }
+}
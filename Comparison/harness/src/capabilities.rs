@@ -0,0 +1,144 @@
+//! What each matching backend can do, as this comparison runs it.
+//!
+//! The backends differ in more than speed and ranking. fzf's filter mode
+//! ranks without scores, only nucleo reports which characters matched, and
+//! RapidFuzz neither folds diacritics nor matches a query word by word. A
+//! mode that needs one of these used to fail late or quietly leave an empty
+//! column. [`Capabilities`] says so up front, so a runner can skip what a
+//! backend cannot do and a report can say why a cell is blank.
+//! `run-quality.py` keeps the same table for the backends it runs.
+
+use serde_json::{Map, Value};
+
+/// The capability names, in report order.
+pub const NAMES: [&str; 5] = [
+    "scores",
+    "indices",
+    "normalization",
+    "multi_atom",
+    "streaming",
+];
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Capabilities {
+    /// Scores each result, rather than only ranking them.
+    pub scores: bool,
+    /// Reports the matched character positions, for highlighting.
+    pub indices: bool,
+    /// Folds diacritics, so `societe` finds `Société`.
+    pub normalization: bool,
+    /// Matches each word of a query on its own, in any order.
+    pub multi_atom: bool,
+    /// Yields matches while the scan is still running.
+    pub streaming: bool,
+}
+
+impl Capabilities {
+    /// The capabilities of `backend`, by the name its results are recorded
+    /// under, or `None` for a backend this table does not know.
+    pub fn of(backend: &str) -> Option<Capabilities> {
+        let all = Capabilities {
+            scores: true,
+            indices: true,
+            normalization: true,
+            multi_atom: true,
+            streaming: false,
+        };
+        let scores_only = Capabilities {
+            scores: true,
+            ..Capabilities::default()
+        };
+        Some(match backend {
+            "nucleo" | "nucleo-http" => all,
+            // search-grpc sends each hit as the scan reaches it, without positions
+            "nucleo-grpc" => Capabilities {
+                indices: false,
+                streaming: true,
+                ..all
+            },
+            "fuzzymatch" => Capabilities {
+                normalization: true,
+                ..scores_only
+            },
+            // Smith-Waterman mode requires every word of the query
+            "fuzzymatch-sw" => Capabilities {
+                normalization: true,
+                multi_atom: true,
+                ..scores_only
+            },
+            "rapidfuzz-wratio" | "rapidfuzz-partial" | "ifrit" => scores_only,
+            // `fzf --filter` prints the matching lines, ranked, and nothing else
+            "fzf" => Capabilities {
+                normalization: true,
+                multi_atom: true,
+                ..Capabilities::default()
+            },
+            _ => return None,
+        })
+    }
+
+    /// Whether the backend has the capability `name` (one of [`NAMES`]).
+    pub fn supports(&self, name: &str) -> bool {
+        match name {
+            "scores" => self.scores,
+            "indices" => self.indices,
+            "normalization" => self.normalization,
+            "multi_atom" => self.multi_atom,
+            "streaming" => self.streaming,
+            _ => false,
+        }
+    }
+
+    /// Those of `required` the backend lacks.
+    pub fn missing<'a>(&self, required: &[&'a str]) -> Vec<&'a str> {
+        required
+            .iter()
+            .copied()
+            .filter(|name| !self.supports(name))
+            .collect()
+    }
+
+    /// The capabilities of [`NAMES`] the backend lacks, described for a
+    /// reader, such as `"match positions"`.
+    pub fn lacking(&self) -> Vec<&'static str> {
+        NAMES
+            .iter()
+            .filter(|name| !self.supports(name))
+            .map(|&name| describe(name))
+            .collect()
+    }
+
+    pub fn to_json(&self) -> Value {
+        NAMES
+            .iter()
+            .map(|&name| (name.to_string(), Value::Bool(self.supports(name))))
+            .collect::<Map<_, _>>()
+            .into()
+    }
+
+    /// Reads capabilities written by [`Capabilities::to_json`]; a missing
+    /// name counts as unsupported.
+    pub fn from_json(value: &Value) -> Option<Capabilities> {
+        let object = value.as_object()?;
+        let has = |name: &str| object.get(name).and_then(Value::as_bool).unwrap_or(false);
+        Some(Capabilities {
+            scores: has("scores"),
+            indices: has("indices"),
+            normalization: has("normalization"),
+            multi_atom: has("multi_atom"),
+            streaming: has("streaming"),
+        })
+    }
+}
+
+/// A capability's name as a report states it.
+pub fn describe(name: &str) -> &'static str {
+    match name {
+        "scores" => "scores",
+        "indices" => "match positions",
+        "normalization" => "diacritic folding",
+        "multi_atom" => "word-by-word matching",
+        "streaming" => "streaming",
+        _ => "unknown capability",
+    }
+}
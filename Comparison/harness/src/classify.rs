@@ -0,0 +1,210 @@
+//! Query category inference.
+//!
+//! Labels a query with the field it searches and one of the
+//! [`CATEGORY_ORDER`](crate::queries::CATEGORY_ORDER) categories by comparing
+//! it with the instrument it is known to target, e.g. the result a user
+//! clicked in a search log. Queries with no known target are labelled by a
+//! [`Classifier`], which compares them with the whole corpus.
+
+use crate::corpus::{Corpus, Instrument};
+
+/// The field and category inferred for a query.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Label {
+    pub field: &'static str,
+    pub category: &'static str,
+}
+
+impl Label {
+    const fn new(field: &'static str, category: &'static str) -> Self {
+        Label { field, category }
+    }
+}
+
+/// Infers how `query` relates to `target`, or `None` when no category fits.
+///
+/// Rules are tried from the most to the least specific, so a query that is
+/// both a prefix and a substring of the name is labelled `prefix`.
+pub fn infer(query: &str, target: &Instrument) -> Option<Label> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return None;
+    }
+    infer_lowercase(
+        &query,
+        &target.symbol.to_lowercase(),
+        &target.name.to_lowercase(),
+        &target.isin.to_lowercase(),
+    )
+}
+
+/// Labels queries that have no known target.
+///
+/// Holds a lowercased copy of the corpus so each query is compared with
+/// every instrument without re-folding case per comparison.
+pub struct Classifier {
+    lowercase: Corpus,
+}
+
+impl Classifier {
+    pub fn new(corpus: &Corpus) -> Self {
+        let mut lowercase = Corpus::with_capacity(corpus.len(), 0);
+        for inst in corpus {
+            lowercase.push(
+                inst.line,
+                &inst.symbol.to_lowercase(),
+                &inst.name.to_lowercase(),
+                &inst.isin.to_lowercase(),
+            );
+        }
+        Classifier { lowercase }
+    }
+
+    /// The most specific label `query` earns against any instrument, with
+    /// the index of the first instrument earning it.
+    ///
+    /// This scans the whole corpus, so callers should classify each
+    /// distinct query once.
+    pub fn classify(&self, query: &str) -> Option<(Label, usize)> {
+        let query = query.trim().to_lowercase();
+        if query.is_empty() {
+            return None;
+        }
+        let mut best: Option<(usize, Label, usize)> = None;
+        for (idx, inst) in self.lowercase.iter().enumerate() {
+            let Some(label) = infer_lowercase(&query, inst.symbol, inst.name, inst.isin) else {
+                continue;
+            };
+            let rank = specificity(label);
+            if best.is_none_or(|(best_rank, _, _)| rank < best_rank) {
+                best = Some((rank, label, idx));
+                if rank == 0 {
+                    break;
+                }
+            }
+        }
+        best.map(|(_, label, idx)| (label, idx))
+    }
+}
+
+/// Labels in the order [`infer_lowercase`] tries them.
+const RULES: [Label; 10] = [
+    Label::new("isin", "exact_isin"),
+    Label::new("symbol", "symbol_spaces"),
+    Label::new("symbol", "exact_symbol"),
+    Label::new("symbol", "prefix"),
+    Label::new("name", "exact_name"),
+    Label::new("name", "prefix"),
+    Label::new("name", "multi_word"),
+    Label::new("name", "substring"),
+    Label::new("name", "abbreviation"),
+    Label::new("name", "typo"),
+];
+
+/// Position of `label` in [`RULES`]; lower is more specific.
+fn specificity(label: Label) -> usize {
+    RULES.iter().position(|&rule| rule == label).unwrap()
+}
+
+/// [`infer`] on an already trimmed and lowercased query and target.
+fn infer_lowercase(query: &str, symbol: &str, name: &str, isin: &str) -> Option<Label> {
+    if !isin.is_empty() && (query == isin || (query.len() >= 6 && isin.starts_with(query))) {
+        return Some(Label::new("isin", "exact_isin"));
+    }
+    if !symbol.is_empty() {
+        if symbol.contains(' ')
+            && (query == symbol || (query.contains(' ') && symbol.starts_with(query)))
+        {
+            return Some(Label::new("symbol", "symbol_spaces"));
+        }
+        if query == symbol {
+            return Some(Label::new("symbol", "exact_symbol"));
+        }
+        if symbol.starts_with(query) {
+            return Some(Label::new("symbol", "prefix"));
+        }
+    }
+    if name.is_empty() {
+        return None;
+    }
+    if query == name {
+        return Some(Label::new("name", "exact_name"));
+    }
+    if name.starts_with(query) {
+        return Some(Label::new("name", "prefix"));
+    }
+
+    let query_words: Vec<&str> = query.split_whitespace().collect();
+    let name_words: Vec<&str> = name.split_whitespace().collect();
+    if query_words.len() > 1
+        && query_words
+            .iter()
+            .all(|q| name_words.iter().any(|w| w.starts_with(q)))
+    {
+        return Some(Label::new("name", "multi_word"));
+    }
+    if name.contains(query) {
+        return Some(Label::new("name", "substring"));
+    }
+    if is_abbreviation(query, name) {
+        return Some(Label::new("name", "abbreviation"));
+    }
+    if is_typo(&query_words, &name_words) {
+        return Some(Label::new("name", "typo"));
+    }
+    None
+}
+
+/// A short, single-token query whose letters appear in order in the name,
+/// starting with the name's first letter (`jnj` for `johnson & johnson`).
+fn is_abbreviation(query: &str, name: &str) -> bool {
+    let letters: Vec<char> = name.chars().filter(|c| c.is_alphanumeric()).collect();
+    if !(2..=6).contains(&query.chars().count())
+        || !query.chars().all(char::is_alphanumeric)
+        || query.chars().count() * 2 > letters.len()
+        || query.chars().next() != letters.first().copied()
+    {
+        return false;
+    }
+    let mut rest = letters.iter();
+    query.chars().all(|q| rest.any(|&c| c == q))
+}
+
+/// Every query word is within a small edit distance of some name word.
+fn is_typo(query_words: &[&str], name_words: &[&str]) -> bool {
+    query_words.iter().all(|q| {
+        let len = q.chars().count();
+        let budget = if len <= 4 { 1 } else { 2 };
+        // The distance is at least the length difference; skipping those
+        // words keeps corpus-wide classification affordable.
+        name_words
+            .iter()
+            .any(|w| w.chars().count().abs_diff(len) <= budget && edit_distance(q, w) <= budget)
+    })
+}
+
+/// Optimal string alignment distance: Levenshtein plus adjacent transpositions.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut rows = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in rows[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut d = (rows[i - 1][j] + 1)
+                .min(rows[i][j - 1] + 1)
+                .min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d = d.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = d;
+        }
+    }
+    rows[a.len()][b.len()]
+}
@@ -0,0 +1,47 @@
+//! Minimal flag parsing shared by the harness binaries.
+//!
+//! The harnesses take a handful of `--flag value` pairs, which is not enough
+//! to justify an argument-parsing dependency.
+
+/// Returns the value following `flag`, if the flag is present.
+///
+/// Panics when the flag is the last argument, since that is always a typo.
+pub fn flag_value<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+    let idx = args.iter().position(|a| a == flag)?;
+    let value = args
+        .get(idx + 1)
+        .unwrap_or_else(|| panic!("{flag} requires a value"));
+    Some(value.as_str())
+}
+
+/// Returns the values of every occurrence of a repeatable flag, in order.
+pub fn flag_values<'a>(args: &'a [String], flag: &str) -> Vec<&'a str> {
+    args.iter()
+        .enumerate()
+        .filter(|(_, a)| *a == flag)
+        .map(|(idx, _)| {
+            args.get(idx + 1)
+                .unwrap_or_else(|| panic!("{flag} requires a value"))
+                .as_str()
+        })
+        .collect()
+}
+
+/// Returns `true` when a bare switch such as `--sw` is present.
+pub fn has_flag(args: &[String], flag: &str) -> bool {
+    args.iter().any(|a| a == flag)
+}
+
+/// Parses the value following `flag`, falling back to `default` when absent.
+pub fn parsed_flag<T>(args: &[String], flag: &str, default: T) -> T
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    match flag_value(args, flag) {
+        Some(value) => value
+            .parse()
+            .unwrap_or_else(|e| panic!("{flag} has an invalid value: {value} ({e})")),
+        None => default,
+    }
+}
@@ -0,0 +1,576 @@
+//! Instrument corpus loading.
+//!
+//! The corpus is a TSV export with a header row. The symbol, name and ISIN
+//! columns are found by header name (case-insensitive), so exports with
+//! reordered or extra columns load unchanged; rows too short to hold all
+//! three are skipped. Gzip and zstd compressed exports are decompressed
+//! transparently.
+//!
+//! Corpora exported by other services can also be read as JSON Lines, one
+//! object per instrument. In both formats a [`FieldMap`] names the columns or
+//! keys that hold the symbol, name and ISIN.
+//!
+//! Columns or keys beyond the three mapped fields are kept as metadata
+//! (exchange, currency, asset class, ...), available per row through
+//! [`Corpus::metadata`].
+//!
+//! Lines that are not valid UTF-8 are repaired or skipped (see
+//! [`crate::encoding`]) rather than failing the load; the corpus keeps a
+//! record of them in [`Corpus::repairs`].
+//!
+//! Exports resaved from Excel keep their quoting: quoted cells are unquoted
+//! (see [`crate::tsv`]), and a byte order mark before the header is ignored.
+
+use crate::encoding::{self, Repairs};
+use crate::{input, tsv};
+use serde_json::Value;
+use std::borrow::Cow;
+use std::fs;
+use std::io::{self, BufRead};
+use std::ops::Range;
+use std::str::FromStr;
+
+/// One row of the instrument corpus, borrowed from its [`Corpus`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Instrument<'a> {
+    /// 1-based line number in the source file.
+    pub line: usize,
+    pub symbol: &'a str,
+    pub name: &'a str,
+    pub isin: &'a str,
+}
+
+impl<'a> Instrument<'a> {
+    /// The value searched for a query field (`symbol`, `isin`, anything else
+    /// searches `name`).
+    pub fn field(&self, field: &str) -> &'a str {
+        match field {
+            "symbol" => self.symbol,
+            "isin" => self.isin,
+            _ => self.name,
+        }
+    }
+}
+
+/// Offsets of one row's fields in the corpus arena. The symbol, name and
+/// ISIN are stored back to back starting at `start`.
+#[derive(Clone, Copy)]
+struct Row {
+    line: u32,
+    start: u32,
+    symbol_end: u32,
+    name_end: u32,
+    isin_end: u32,
+}
+
+/// A loaded corpus: every field of every row in one string arena.
+///
+/// One allocation for all text instead of three `String`s per row keeps a
+/// 270K-row export at roughly the size of the file itself. Metadata values
+/// follow a row's ISIN in the arena; their end offsets live in `meta_ends`,
+/// one per metadata column per row.
+#[derive(Default)]
+pub struct Corpus {
+    arena: String,
+    rows: Vec<Row>,
+    metadata_columns: Vec<String>,
+    meta_ends: Vec<u32>,
+    repairs: Repairs,
+}
+
+impl Corpus {
+    /// An empty corpus with room for `rows` rows and `bytes` bytes of text.
+    pub fn with_capacity(rows: usize, bytes: usize) -> Self {
+        Corpus {
+            arena: String::with_capacity(bytes),
+            rows: Vec::with_capacity(rows),
+            ..Corpus::default()
+        }
+    }
+
+    /// Names the metadata columns rows carry. Must be called before the
+    /// first row is pushed.
+    pub fn set_metadata_columns(&mut self, columns: Vec<String>) {
+        assert!(
+            self.is_empty(),
+            "metadata columns set on a non-empty corpus"
+        );
+        self.metadata_columns = columns;
+    }
+
+    /// Appends a row with empty metadata. Offsets are 32-bit, so the arena is
+    /// capped at 4 GiB.
+    pub fn push(&mut self, line: usize, symbol: &str, name: &str, isin: &str) {
+        self.push_with_metadata(line, symbol, name, isin, &[]);
+    }
+
+    /// Appends a row. `metadata` holds values for the leading metadata
+    /// columns; columns past its end are left empty.
+    pub fn push_with_metadata(
+        &mut self,
+        line: usize,
+        symbol: &str,
+        name: &str,
+        isin: &str,
+        metadata: &[&str],
+    ) {
+        let line = u32::try_from(line).expect("corpus exceeds 4G lines");
+        let offset = |len: usize| u32::try_from(len).expect("corpus text exceeds 4 GiB");
+        let start = offset(self.arena.len());
+        self.arena.push_str(symbol);
+        let symbol_end = offset(self.arena.len());
+        self.arena.push_str(name);
+        let name_end = offset(self.arena.len());
+        self.arena.push_str(isin);
+        let isin_end = offset(self.arena.len());
+        for column in 0..self.metadata_columns.len() {
+            self.arena
+                .push_str(metadata.get(column).copied().unwrap_or(""));
+            self.meta_ends.push(offset(self.arena.len()));
+        }
+        self.rows.push(Row {
+            line,
+            start,
+            symbol_end,
+            name_end,
+            isin_end,
+        });
+    }
+
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Keeps the first `rows` rows and releases the text of the rest.
+    pub fn truncate(&mut self, rows: usize) {
+        if rows >= self.rows.len() {
+            return;
+        }
+        let columns = self.metadata_columns.len();
+        self.rows.truncate(rows);
+        self.meta_ends.truncate(rows * columns);
+        let end = match (self.rows.last(), self.meta_ends.last()) {
+            (None, _) => 0,
+            (Some(_), Some(&meta_end)) => meta_end,
+            (Some(row), None) => row.isin_end,
+        };
+        self.arena.truncate(end as usize);
+        self.arena.shrink_to_fit();
+        self.rows.shrink_to_fit();
+        self.meta_ends.shrink_to_fit();
+    }
+
+    /// The row at `idx`. Panics when out of bounds, like slice indexing.
+    pub fn get(&self, idx: usize) -> Instrument<'_> {
+        let row = self.rows[idx];
+        let text = |from: u32, to: u32| &self.arena[from as usize..to as usize];
+        Instrument {
+            line: row.line as usize,
+            symbol: text(row.start, row.symbol_end),
+            name: text(row.symbol_end, row.name_end),
+            isin: text(row.name_end, row.isin_end),
+        }
+    }
+
+    /// Names of the metadata columns, in source order.
+    pub fn metadata_columns(&self) -> &[String] {
+        &self.metadata_columns
+    }
+
+    /// Index of a metadata column, matched case-insensitively.
+    pub fn metadata_column(&self, name: &str) -> Option<usize> {
+        self.metadata_columns
+            .iter()
+            .position(|column| column.eq_ignore_ascii_case(name))
+    }
+
+    /// Metadata of the row at `idx`. Panics when out of bounds.
+    pub fn metadata(&self, idx: usize) -> Metadata<'_> {
+        Metadata {
+            corpus: self,
+            start: self.rows[idx].isin_end,
+            ends: &self.meta_ends[idx * self.metadata_columns.len()..]
+                [..self.metadata_columns.len()],
+        }
+    }
+
+    /// The text a query on `field` searches in row `idx`: the symbol, name
+    /// or ISIN, or a metadata column of that name, such as a long
+    /// description. Unknown fields search the name, as in
+    /// [`Instrument::field`].
+    pub fn text(&self, idx: usize, field: &str) -> &str {
+        match field {
+            "symbol" | "isin" | "name" => self.get(idx).field(field),
+            _ => match self.metadata_column(field) {
+                Some(column) => self.metadata(idx).value(column),
+                None => self.get(idx).name,
+            },
+        }
+    }
+
+    /// Lines repaired or dropped for invalid UTF-8 while loading.
+    pub fn repairs(&self) -> &Repairs {
+        &self.repairs
+    }
+
+    /// Records load-time repairs, e.g. when copying a loaded corpus.
+    pub fn set_repairs(&mut self, repairs: Repairs) {
+        self.repairs = repairs;
+    }
+
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            corpus: self,
+            range: 0..self.rows.len(),
+        }
+    }
+}
+
+/// One row's metadata values, borrowed from its [`Corpus`].
+#[derive(Clone, Copy)]
+pub struct Metadata<'a> {
+    corpus: &'a Corpus,
+    start: u32,
+    ends: &'a [u32],
+}
+
+impl<'a> Metadata<'a> {
+    /// The value in metadata column `column` (see [`Corpus::metadata_column`]).
+    pub fn value(&self, column: usize) -> &'a str {
+        let from = if column == 0 {
+            self.start
+        } else {
+            self.ends[column - 1]
+        };
+        &self.corpus.arena[from as usize..self.ends[column] as usize]
+    }
+
+    /// The value in the column named `name`, if the corpus has one.
+    pub fn get(&self, name: &str) -> Option<&'a str> {
+        self.corpus
+            .metadata_column(name)
+            .map(|column| self.value(column))
+    }
+
+    /// Values in column order.
+    pub fn values(&self) -> impl Iterator<Item = &'a str> + '_ {
+        (0..self.ends.len()).map(|column| self.value(column))
+    }
+}
+
+impl<'a> IntoIterator for &'a Corpus {
+    type Item = Instrument<'a>;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Iter<'a> {
+        self.iter()
+    }
+}
+
+/// Iterator over the rows of a [`Corpus`], in file order.
+pub struct Iter<'a> {
+    corpus: &'a Corpus,
+    range: Range<usize>,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = Instrument<'a>;
+
+    fn next(&mut self) -> Option<Instrument<'a>> {
+        self.range.next().map(|idx| self.corpus.get(idx))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.range.size_hint()
+    }
+}
+
+impl ExactSizeIterator for Iter<'_> {}
+
+/// Which source columns or keys hold each instrument field.
+///
+/// Parsed from `--map symbol=ticker,name=long_name,isin=isin`. Fields left
+/// out of the flag keep their own name as the key. TSV header names are
+/// matched case-insensitively, JSON keys exactly.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FieldMap {
+    pub symbol: String,
+    pub name: String,
+    pub isin: String,
+}
+
+impl Default for FieldMap {
+    fn default() -> Self {
+        FieldMap {
+            symbol: "symbol".to_string(),
+            name: "name".to_string(),
+            isin: "isin".to_string(),
+        }
+    }
+}
+
+impl FromStr for FieldMap {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut map = FieldMap::default();
+        for pair in s.split(',').filter(|p| !p.trim().is_empty()) {
+            let (field, key) = pair
+                .split_once('=')
+                .ok_or_else(|| format!("expected field=key, got `{pair}`"))?;
+            let key = key.trim().to_string();
+            match field.trim() {
+                "symbol" => map.symbol = key,
+                "name" => map.name = key,
+                "isin" => map.isin = key,
+                other => return Err(format!("unknown field `{other}`")),
+            }
+        }
+        Ok(map)
+    }
+}
+
+/// Loads the corpus at `path` with the default [`FieldMap`].
+///
+/// `path` may point to a plain, `.tsv.gz` or `.tsv.zst` file, or to a JSON
+/// Lines file.
+pub fn load_corpus(path: &str) -> io::Result<Corpus> {
+    load_corpus_mapped(path, &FieldMap::default())
+}
+
+/// Loads a corpus, resolving TSV columns or JSON Lines keys (`.jsonl` or
+/// `.ndjson`, possibly compressed) through `map`.
+///
+/// The file is streamed line by line, never held in memory as a whole.
+/// Fails with [`io::ErrorKind::InvalidData`] when a TSV header lacks a mapped
+/// column.
+pub fn load_corpus_mapped(path: &str, map: &FieldMap) -> io::Result<Corpus> {
+    load_corpus_progress(path, map, usize::MAX, &mut |_| {})
+}
+
+/// Loads a corpus as [`load_corpus_mapped`] does, handing the rows loaded
+/// so far to `on_progress` after every `every` rows, for searching a corpus
+/// before it has finished loading.
+pub fn load_corpus_progress(
+    path: &str,
+    map: &FieldMap,
+    every: usize,
+    on_progress: &mut dyn FnMut(&Corpus),
+) -> io::Result<Corpus> {
+    let every = every.max(1);
+    let mut on_row = |corpus: &Corpus| {
+        if corpus.len().is_multiple_of(every) {
+            on_progress(corpus);
+        }
+    };
+    // The on-disk size is a good arena estimate for plain files and an
+    // underestimate for compressed ones, which then grow as needed.
+    let bytes = fs::metadata(path)?.len() as usize;
+    let mut corpus = Corpus::with_capacity(bytes / 48, bytes);
+    let mut reader = input::open(path)?;
+    if is_json_lines(path) {
+        read_jsonl(&mut reader, map, &mut corpus, &mut on_row)?;
+    } else {
+        read_tsv(&mut reader, map, &mut corpus, &mut on_row)?;
+    }
+    Ok(corpus)
+}
+
+/// Reads a TSV corpus that is already in memory or arriving over a stream
+/// rather than sitting in a file, such as one uploaded to the browser demo.
+pub fn read_tsv_corpus(reader: &mut dyn BufRead, map: &FieldMap) -> io::Result<Corpus> {
+    let mut corpus = Corpus::default();
+    read_tsv(reader, map, &mut corpus, &mut |_| {})?;
+    Ok(corpus)
+}
+
+/// Whether `path` names a JSON Lines corpus, judged by extension.
+pub fn is_json_lines(path: &str) -> bool {
+    let path = path
+        .strip_suffix(".gz")
+        .or_else(|| path.strip_suffix(".zst"))
+        .unwrap_or(path);
+    path.ends_with(".jsonl") || path.ends_with(".ndjson")
+}
+
+/// Column indices of the symbol, name and ISIN in a TSV header row.
+pub fn tsv_columns(header: &str, map: &FieldMap) -> io::Result<[usize; 3]> {
+    let names: Vec<String> = tsv::fields(header)
+        .map(|name| name.trim().to_lowercase())
+        .collect();
+    let column = |key: &str| {
+        names
+            .iter()
+            .position(|name| *name == key.to_lowercase())
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("header has no `{key}` column (columns: {header:?}); use --map"),
+                )
+            })
+    };
+    Ok([column(&map.symbol)?, column(&map.name)?, column(&map.isin)?])
+}
+
+/// Reads the next line into `buf` without its line terminator, returning
+/// `false` at end of input.
+fn next_line(reader: &mut dyn BufRead, buf: &mut Vec<u8>) -> io::Result<bool> {
+    buf.clear();
+    if reader.read_until(b'\n', buf)? == 0 {
+        return Ok(false);
+    }
+    if buf.ends_with(b"\n") {
+        buf.pop();
+        if buf.ends_with(b"\r") {
+            buf.pop();
+        }
+    }
+    Ok(true)
+}
+
+/// Decodes line `number`, recording a repair or drop in `repairs`.
+fn decode<'b>(bytes: &'b [u8], number: usize, repairs: &mut Repairs) -> Option<Cow<'b, str>> {
+    let line = encoding::decode_line(bytes);
+    match &line {
+        None => repairs.dropped.push(number),
+        Some(Cow::Owned(_)) => repairs.repaired.push(number),
+        Some(Cow::Borrowed(_)) => {}
+    }
+    line
+}
+
+/// Reads rows into `corpus`, calling `on_row` after each one is added.
+fn read_tsv(
+    reader: &mut dyn BufRead,
+    map: &FieldMap,
+    corpus: &mut Corpus,
+    on_row: &mut dyn FnMut(&Corpus),
+) -> io::Result<()> {
+    let mut bytes = Vec::new();
+    if !next_line(reader, &mut bytes)? {
+        return Ok(());
+    }
+    let header = String::from_utf8_lossy(&bytes)
+        .trim_start_matches('\u{feff}')
+        .to_string();
+    let [symbol, name, isin] = tsv_columns(&header, map)?;
+    let width = symbol.max(name).max(isin) + 1;
+
+    // Every other named column is metadata; `slots` maps each source column
+    // to its metadata index.
+    let mut slots: Vec<Option<usize>> = Vec::new();
+    let mut metadata_columns = Vec::new();
+    for (i, column) in tsv::fields(&header).enumerate() {
+        let column = column.trim();
+        if [symbol, name, isin].contains(&i) || column.is_empty() {
+            slots.push(None);
+        } else {
+            slots.push(Some(metadata_columns.len()));
+            metadata_columns.push(column.to_string());
+        }
+    }
+    let metadata_count = metadata_columns.len();
+    corpus.set_metadata_columns(metadata_columns);
+
+    let mut number = 1;
+    while next_line(reader, &mut bytes)? {
+        number += 1;
+        let Some(line) = decode(&bytes, number, &mut corpus.repairs) else {
+            continue;
+        };
+        let mut fields = [Cow::Borrowed(""), Cow::Borrowed(""), Cow::Borrowed("")];
+        let mut metadata = vec![Cow::Borrowed(""); metadata_count];
+        let mut count = 0;
+        for (i, col) in tsv::fields(&line).enumerate() {
+            for (field, &idx) in fields.iter_mut().zip(&[symbol, name, isin]) {
+                if idx == i {
+                    *field = col.clone();
+                }
+            }
+            if let Some(&Some(slot)) = slots.get(i) {
+                metadata[slot] = col;
+            }
+            count = i + 1;
+        }
+        if count >= width {
+            let metadata: Vec<&str> = metadata.iter().map(|m| m.as_ref()).collect();
+            corpus.push_with_metadata(number, &fields[0], &fields[1], &fields[2], &metadata);
+            on_row(corpus);
+        }
+    }
+    Ok(())
+}
+
+fn read_jsonl(
+    reader: &mut dyn BufRead,
+    map: &FieldMap,
+    corpus: &mut Corpus,
+    on_row: &mut dyn FnMut(&Corpus),
+) -> io::Result<()> {
+    let mut bytes = Vec::new();
+    let mut number = 0;
+    while next_line(reader, &mut bytes)? {
+        number += 1;
+        let Some(line) = decode(&bytes, number, &mut corpus.repairs) else {
+            continue;
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let object: Value = serde_json::from_str(&line).map_err(|e| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("line {number}: {e}"))
+        })?;
+        if corpus.is_empty() {
+            corpus.set_metadata_columns(metadata_keys(&object, map));
+        }
+        let metadata: Vec<Cow<str>> = corpus
+            .metadata_columns()
+            .iter()
+            .map(|key| json_field(&object, key))
+            .collect();
+        let metadata: Vec<&str> = metadata.iter().map(|value| value.as_ref()).collect();
+        corpus.push_with_metadata(
+            number,
+            &json_field(&object, &map.symbol),
+            &json_field(&object, &map.name),
+            &json_field(&object, &map.isin),
+            &metadata,
+        );
+        on_row(corpus);
+    }
+    Ok(())
+}
+
+/// Metadata columns of a JSON Lines corpus: the first object's scalar keys
+/// other than the mapped fields. Later objects missing a key read as empty.
+fn metadata_keys(object: &Value, map: &FieldMap) -> Vec<String> {
+    let Some(object) = object.as_object() else {
+        return Vec::new();
+    };
+    object
+        .iter()
+        .filter(|(key, value)| {
+            ![&map.symbol, &map.name, &map.isin].contains(key)
+                && !matches!(value, Value::Object(_) | Value::Array(_))
+        })
+        .map(|(key, _)| key.clone())
+        .collect()
+}
+
+/// Looks up `key` literally, then as a dotted path into nested objects
+/// (`listing.ticker`). Missing and null values read as empty strings.
+fn json_field<'v>(object: &'v Value, key: &str) -> Cow<'v, str> {
+    let value = object.get(key).or_else(|| {
+        let pointer = format!("/{}", key.replace('.', "/"));
+        object.pointer(&pointer)
+    });
+    match value {
+        None | Some(Value::Null) => Cow::Borrowed(""),
+        Some(Value::String(s)) => Cow::Borrowed(s),
+        Some(other) => Cow::Owned(other.to_string()),
+    }
+}
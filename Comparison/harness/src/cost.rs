@@ -0,0 +1,186 @@
+//! Counting the work a scan does for one query.
+//!
+//! A latency says how long a query took but not what the time went on.
+//! [`QueryCost`] counts the steps of a scan: candidates visited, candidates
+//! set aside before scoring by a metadata filter or by the
+//! [`CharMask`](crate::prefilter::CharMask) prefilter, full scores, matches,
+//! and insertions into the result set. Next to the timings the counts show
+//! whether a prefilter really skips work, and [`CostModel`] turns a query
+//! set's counts and timings into a per-step cost that predicts a query's
+//! latency from its shape.
+
+use serde_json::{json, Value};
+use std::ops::AddAssign;
+
+/// What one scan of a query did.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct QueryCost {
+    /// Candidates the scan visited.
+    pub scanned: usize,
+    /// Candidates a metadata filter excluded before scoring.
+    pub filtered: usize,
+    /// Candidates the character-mask prefilter rejected before scoring.
+    pub prefiltered: usize,
+    /// Candidates handed to the matcher.
+    pub scored: usize,
+    /// Scored candidates that matched.
+    pub matched: usize,
+    /// Matches added to the result set: pushes a top-K heap kept, or every
+    /// match where a harness sorts them all.
+    pub inserted: usize,
+}
+
+impl QueryCost {
+    /// Share of visited candidates that were never scored.
+    pub fn skip_ratio(&self) -> f64 {
+        if self.scanned == 0 {
+            return 0.0;
+        }
+        (self.filtered + self.prefiltered) as f64 / self.scanned as f64
+    }
+
+    pub(crate) fn to_json(self) -> Value {
+        json!({
+            "scanned": self.scanned,
+            "filtered": self.filtered,
+            "prefiltered": self.prefiltered,
+            "scored": self.scored,
+            "matched": self.matched,
+            "inserted": self.inserted,
+        })
+    }
+
+    pub(crate) fn from_json(value: &Value) -> Option<QueryCost> {
+        let count = |key: &str| value.get(key).and_then(Value::as_u64).map(|n| n as usize);
+        Some(QueryCost {
+            scanned: count("scanned")?,
+            filtered: count("filtered").unwrap_or(0),
+            prefiltered: count("prefiltered").unwrap_or(0),
+            scored: count("scored")?,
+            matched: count("matched")?,
+            inserted: count("inserted")?,
+        })
+    }
+
+    /// The counts [`CostModel`] weighs, in its order.
+    fn terms(&self) -> [f64; 4] {
+        [
+            self.scanned as f64,
+            self.scored as f64,
+            self.matched as f64,
+            self.inserted as f64,
+        ]
+    }
+}
+
+impl AddAssign for QueryCost {
+    fn add_assign(&mut self, other: QueryCost) {
+        self.scanned += other.scanned;
+        self.filtered += other.filtered;
+        self.prefiltered += other.prefiltered;
+        self.scored += other.scored;
+        self.matched += other.matched;
+        self.inserted += other.inserted;
+    }
+}
+
+/// Latency as a sum of per-step costs, fitted to a query set by least
+/// squares.
+///
+/// A step whose count cannot be told apart from the steps before it gets
+/// no cost of its own. Without a prefilter every visited candidate is
+/// scored, so the cost of a score is folded into the cost of a visit.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CostModel {
+    pub ns_per_scanned: f64,
+    pub ns_per_scored: f64,
+    pub ns_per_matched: f64,
+    pub ns_per_inserted: f64,
+    /// Share of the variance in latency the model explains.
+    pub r_squared: f64,
+}
+
+impl CostModel {
+    /// Fits the model to `(cost, latency in ms)` pairs, or `None` with
+    /// fewer than two queries or nothing visited.
+    pub fn fit(samples: &[(QueryCost, f64)]) -> Option<CostModel> {
+        if samples.len() < 2 {
+            return None;
+        }
+        // Normal equations, solved by elimination that drops any term
+        // whose pivot vanishes against its own scale
+        let mut gram = [[0.0f64; 4]; 4];
+        let mut rhs = [0.0f64; 4];
+        for (cost, ms) in samples {
+            let x = cost.terms();
+            for i in 0..4 {
+                for j in 0..4 {
+                    gram[i][j] += x[i] * x[j];
+                }
+                rhs[i] += x[i] * ms;
+            }
+        }
+        let scale: Vec<f64> = (0..4).map(|i| gram[i][i]).collect();
+        let mut kept = [false; 4];
+        for p in 0..4 {
+            if gram[p][p] <= scale[p] * 1e-9 {
+                continue;
+            }
+            kept[p] = true;
+            let pivot = gram[p];
+            for r in (p + 1)..4 {
+                let factor = gram[r][p] / pivot[p];
+                for (value, above) in gram[r].iter_mut().zip(pivot).skip(p) {
+                    *value -= factor * above;
+                }
+                rhs[r] -= factor * rhs[p];
+            }
+        }
+        if !kept[0] {
+            return None;
+        }
+        let mut coef = [0.0f64; 4];
+        for p in (0..4).rev().filter(|&p| kept[p]) {
+            let known: f64 = ((p + 1)..4).map(|c| gram[p][c] * coef[c]).sum();
+            coef[p] = (rhs[p] - known) / gram[p][p];
+        }
+
+        let mut model = CostModel {
+            ns_per_scanned: coef[0] * 1e6,
+            ns_per_scored: coef[1] * 1e6,
+            ns_per_matched: coef[2] * 1e6,
+            ns_per_inserted: coef[3] * 1e6,
+            r_squared: 0.0,
+        };
+        model.r_squared = model.explained(samples);
+        Some(model)
+    }
+
+    /// Share of the variance in `samples`' latencies that predictions from
+    /// their counts explain. The counts need not be the ones the model was
+    /// fitted to: estimated counts show how well latency can be told before
+    /// a scan runs. Negative when the predictions do worse than the mean.
+    pub fn explained(&self, samples: &[(QueryCost, f64)]) -> f64 {
+        let mean = samples.iter().map(|(_, ms)| ms).sum::<f64>() / samples.len().max(1) as f64;
+        let total: f64 = samples.iter().map(|(_, ms)| (ms - mean).powi(2)).sum();
+        let residual: f64 = samples
+            .iter()
+            .map(|(cost, ms)| (ms - self.predict_ms(cost)).powi(2))
+            .sum();
+        if total > 0.0 {
+            1.0 - residual / total
+        } else {
+            1.0
+        }
+    }
+
+    /// Predicted latency of a scan with `cost`'s counts.
+    pub fn predict_ms(&self, cost: &QueryCost) -> f64 {
+        let [scanned, scored, matched, inserted] = cost.terms();
+        (scanned * self.ns_per_scanned
+            + scored * self.ns_per_scored
+            + matched * self.ns_per_matched
+            + inserted * self.ns_per_inserted)
+            / 1e6
+    }
+}
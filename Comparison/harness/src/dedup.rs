@@ -0,0 +1,74 @@
+//! Duplicate detection and removal at load time.
+//!
+//! Exact duplicate rows are matched and scored once per copy, which inflates
+//! match counts and lets one instrument take several top-10 slots. ISINs
+//! shared by rows with different names are reported but kept, since they are
+//! usually different listings of the same security.
+
+use crate::corpus::Corpus;
+use std::collections::{HashMap, HashSet};
+
+/// Duplicates found in a corpus.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Duplicates {
+    /// Indices of rows identical in symbol, name and ISIN to an earlier row.
+    pub exact: Vec<usize>,
+    /// Number of distinct ISINs carried by rows with more than one name.
+    pub conflicting_isins: usize,
+}
+
+impl Duplicates {
+    pub fn find(corpus: &Corpus) -> Duplicates {
+        let mut seen: HashSet<(&str, &str, &str)> = HashSet::with_capacity(corpus.len());
+        let mut names_by_isin: HashMap<&str, HashSet<&str>> = HashMap::new();
+        let mut exact = Vec::new();
+        for (idx, inst) in corpus.iter().enumerate() {
+            if !seen.insert((inst.symbol, inst.name, inst.isin)) {
+                exact.push(idx);
+            }
+            if !inst.isin.is_empty() {
+                names_by_isin
+                    .entry(inst.isin)
+                    .or_default()
+                    .insert(inst.name);
+            }
+        }
+        let conflicting_isins = names_by_isin.values().filter(|n| n.len() > 1).count();
+        Duplicates {
+            exact,
+            conflicting_isins,
+        }
+    }
+
+    /// A copy of `corpus` without the exact duplicate rows, keeping the first
+    /// occurrence, its metadata, the original line numbers and the load-time
+    /// repairs.
+    pub fn remove_from(&self, corpus: &Corpus) -> Corpus {
+        let drop: HashSet<usize> = self.exact.iter().copied().collect();
+        let mut deduplicated = Corpus::with_capacity(corpus.len() - drop.len(), 0);
+        deduplicated.set_metadata_columns(corpus.metadata_columns().to_vec());
+        for (idx, inst) in corpus.iter().enumerate() {
+            if !drop.contains(&idx) {
+                let metadata: Vec<&str> = corpus.metadata(idx).values().collect();
+                deduplicated.push_with_metadata(
+                    inst.line,
+                    inst.symbol,
+                    inst.name,
+                    inst.isin,
+                    &metadata,
+                );
+            }
+        }
+        deduplicated.set_repairs(corpus.repairs().clone());
+        deduplicated
+    }
+
+    /// One-line summary for harness output.
+    pub fn summary(&self) -> String {
+        format!(
+            "{} exact duplicate rows, {} ISINs with differing names",
+            self.exact.len(),
+            self.conflicting_isins
+        )
+    }
+}
@@ -0,0 +1,114 @@
+//! How hard a query is, estimated from the corpus alone.
+//!
+//! A hit rate averages easy and hard queries alike, so a set heavy on exact
+//! tickers flatters every backend and a regression on the hard queries is
+//! diluted. [`Difficulty`] rates a query on three things, none of which
+//! depends on the backend:
+//!
+//! - how common its characters are: the share of rows holding every
+//!   character of the query, as [`CharFrequency::estimate`] bounds it. The
+//!   more rows a query can match at all, the more a matcher has to rank
+//!   correctly.
+//! - its lookalikes: rows whose searched text starts with the query but
+//!   whose name the expected name does not accept. They look the same as
+//!   the answer to someone who has typed only that much, and a matcher has
+//!   to rank every one of them lower.
+//! - how ambiguous its expected answer is: the rows whose name contains the
+//!   expected name, any of which the ground-truth check accepts.
+//!
+//! [`Difficulty::score`] folds the first two into one number from 0 (easy)
+//! to 1 (hard), for weighting aggregates and sorting queries for review.
+//! Ambiguity is kept out of it because it cuts the other way: an expected
+//! name hundreds of rows contain is easy to find, and a hit on it says
+//! little. It is reported for reviewers to discount such queries.
+
+use crate::corpus::Corpus;
+use crate::frequency::CharFrequency;
+use crate::queries::{Query, SKIP};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// Lookalike rows at which that half of the score is 0.5.
+const HALF_SCORE_ROWS: f64 = 10.0;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Difficulty {
+    /// Estimated share of rows holding every character of the query.
+    pub char_share: f64,
+    /// Rows whose searched text starts with the query, in either case, and
+    /// whose name does not contain the expected name.
+    pub lookalikes: usize,
+    /// Rows whose name contains the expected name, or `None` when the
+    /// query has none.
+    pub accepted: Option<usize>,
+}
+
+impl Difficulty {
+    /// The mean of the character share and the lookalikes, scaled to
+    /// 0–1 as `n / (n + 10)`.
+    pub fn score(&self) -> f64 {
+        let lookalikes = self.lookalikes as f64;
+        (self.char_share + lookalikes / (lookalikes + HALF_SCORE_ROWS)) / 2.0
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "score": self.score(),
+            "char_share": self.char_share,
+            "lookalikes": self.lookalikes,
+            "accepted": self.accepted,
+        })
+    }
+
+    /// Reads back [`Difficulty::to_json`]; the score is recomputed.
+    pub fn from_json(value: &Value) -> Option<Difficulty> {
+        Some(Difficulty {
+            char_share: value.get("char_share")?.as_f64()?,
+            lookalikes: value.get("lookalikes")?.as_u64()? as usize,
+            accepted: value
+                .get("accepted")
+                .and_then(Value::as_u64)
+                .map(|n| n as usize),
+        })
+    }
+}
+
+/// Rates queries against one corpus. Each searched column is case-folded
+/// and counted once, when a query first searches it.
+pub struct Estimator<'a> {
+    corpus: &'a Corpus,
+    columns: HashMap<String, (Vec<String>, CharFrequency)>,
+    names: Vec<String>,
+}
+
+impl<'a> Estimator<'a> {
+    pub fn new(corpus: &'a Corpus) -> Estimator<'a> {
+        Estimator {
+            corpus,
+            columns: HashMap::new(),
+            names: corpus.iter().map(|row| row.name.to_lowercase()).collect(),
+        }
+    }
+
+    pub fn estimate(&mut self, query: &Query) -> Difficulty {
+        let corpus = self.corpus;
+        let (texts, frequency) = self.columns.entry(query.field.clone()).or_insert_with(|| {
+            let texts: Vec<String> = (0..corpus.len())
+                .map(|idx| corpus.text(idx, &query.field).to_lowercase())
+                .collect();
+            let frequency = CharFrequency::new(texts.iter().map(|t| (t.as_str(), 1)));
+            (texts, frequency)
+        });
+        let text = query.text.trim().to_lowercase();
+        let expected = query.expected_name.to_lowercase();
+        let judged = !expected.is_empty() && query.expected_name != SKIP;
+        let accepts = |idx: usize| judged && self.names[idx].contains(&expected);
+        Difficulty {
+            char_share: frequency.estimate(&text),
+            lookalikes: (0..texts.len())
+                .filter(|&idx| texts[idx].starts_with(&text) && !accepts(idx))
+                .count(),
+            accepted: judged.then(|| (0..texts.len()).filter(|&idx| accepts(idx)).count()),
+        }
+    }
+}
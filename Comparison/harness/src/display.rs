@@ -0,0 +1,92 @@
+//! Query and candidate text laid out in fixed-width console tables.
+//!
+//! `{:<32}` pads by characters, but a terminal draws CJK and other wide
+//! characters two columns wide, so a row holding them spills past its
+//! column and pushes the rest of the row out of line. Shortening text by
+//! slicing it at a byte count is worse: it panics when the cut lands
+//! inside a multi-byte character. [`truncate`] shortens text to a display
+//! width at character boundaries and [`pad`] pads it to one, so tables line
+//! up whatever script the queries are in.
+//!
+//! Text is cut between grapheme clusters, the characters a reader sees,
+//! rather than between `char`s: an accent written as a combining mark, a
+//! flag, or an emoji joined from several code points stays whole or goes
+//! whole. Anything that shortens query or candidate text for a person to
+//! read goes through here, so no report or log slices strings by hand.
+
+use std::borrow::Cow;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Marks text that [`truncate`] shortened.
+pub const ELLIPSIS: &str = "...";
+
+/// Columns a terminal takes to draw `text`.
+pub fn width(text: &str) -> usize {
+    text.width()
+}
+
+/// `text`, or as much of its start as fits in `width` columns followed by
+/// [`ELLIPSIS`], which counts toward the width.
+pub fn truncate(text: &str, width: usize) -> Cow<'_, str> {
+    if self::width(text) <= width {
+        return Cow::Borrowed(text);
+    }
+    let end =
+        fitting(text.grapheme_indices(true), width).map_or(0, |(at, grapheme)| at + grapheme.len());
+    let mut short = text[..end].to_string();
+    short.push_str(&ELLIPSIS[..width.min(ELLIPSIS.len())]);
+    Cow::Owned(short)
+}
+
+/// `text`, or as much of its end as fits in `width` columns after
+/// [`ELLIPSIS`], for text whose end tells it apart, such as a file name.
+pub fn truncate_start(text: &str, width: usize) -> Cow<'_, str> {
+    if self::width(text) <= width {
+        return Cow::Borrowed(text);
+    }
+    let start = fitting(text.grapheme_indices(true).rev(), width).map_or(text.len(), |(at, _)| at);
+    let mut short = ELLIPSIS[..width.min(ELLIPSIS.len())].to_string();
+    short.push_str(&text[start..]);
+    Cow::Owned(short)
+}
+
+/// `text` followed by spaces to fill `width` columns.
+pub fn pad(text: &str, width: usize) -> String {
+    let fill = width.saturating_sub(self::width(text));
+    let mut padded = String::with_capacity(text.len() + fill);
+    padded.push_str(text);
+    padded.extend(std::iter::repeat_n(' ', fill));
+    padded
+}
+
+/// Spaces to fill `width` columns followed by `text`, for a right-aligned
+/// cell.
+pub fn pad_start(text: &str, width: usize) -> String {
+    let fill = width.saturating_sub(self::width(text));
+    let mut padded: String = std::iter::repeat_n(' ', fill).collect();
+    padded.push_str(text);
+    padded
+}
+
+/// `text` shortened and padded to exactly `width` columns, for a
+/// left-aligned table cell.
+pub fn cell(text: &str, width: usize) -> String {
+    pad(&truncate(text, width), width)
+}
+
+/// The last of `graphemes` that fits, with those before it, in `width`
+/// columns less the ellipsis.
+fn fitting<'a>(
+    graphemes: impl Iterator<Item = (usize, &'a str)>,
+    width: usize,
+) -> Option<(usize, &'a str)> {
+    let room = width.saturating_sub(ELLIPSIS.len());
+    let mut used = 0;
+    graphemes
+        .take_while(|(_, grapheme)| {
+            used += grapheme.width();
+            used <= room
+        })
+        .last()
+}
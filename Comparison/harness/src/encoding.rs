@@ -0,0 +1,96 @@
+//! Lenient decoding of corpus lines.
+//!
+//! Exports are meant to be UTF-8, but rows pasted in from older systems
+//! sometimes carry Windows-1252 bytes (`M\xfcnchener`). Such a line is
+//! repaired by decoding each invalid byte as Windows-1252, leaving the valid
+//! UTF-8 around it untouched. Bytes that Windows-1252 leaves undefined mean
+//! the line is neither encoding, and it is dropped.
+
+use std::borrow::Cow;
+
+/// Windows-1252 characters for bytes 0x80–0x9F; `None` marks the five bytes
+/// the code page leaves undefined. Bytes 0xA0–0xFF match Latin-1.
+const CP1252_HIGH: [Option<char>; 32] = [
+    Some('€'),
+    None,
+    Some('‚'),
+    Some('ƒ'),
+    Some('„'),
+    Some('…'),
+    Some('†'),
+    Some('‡'),
+    Some('ˆ'),
+    Some('‰'),
+    Some('Š'),
+    Some('‹'),
+    Some('Œ'),
+    None,
+    Some('Ž'),
+    None,
+    None,
+    Some('‘'),
+    Some('’'),
+    Some('“'),
+    Some('”'),
+    Some('•'),
+    Some('–'),
+    Some('—'),
+    Some('˜'),
+    Some('™'),
+    Some('š'),
+    Some('›'),
+    Some('œ'),
+    None,
+    Some('ž'),
+    Some('Ÿ'),
+];
+
+/// Decodes one line, repairing Windows-1252 bytes.
+///
+/// Returns borrowed text for valid UTF-8, owned text for a repaired line and
+/// `None` for a line that cannot be decoded.
+pub fn decode_line(bytes: &[u8]) -> Option<Cow<'_, str>> {
+    if let Ok(text) = std::str::from_utf8(bytes) {
+        return Some(Cow::Borrowed(text));
+    }
+    let mut text = String::with_capacity(bytes.len() + 8);
+    for chunk in bytes.utf8_chunks() {
+        text.push_str(chunk.valid());
+        for &byte in chunk.invalid() {
+            text.push(cp1252(byte)?);
+        }
+    }
+    Some(Cow::Owned(text))
+}
+
+/// The character a single byte stands for in Windows-1252.
+fn cp1252(byte: u8) -> Option<char> {
+    match byte {
+        0x80..=0x9F => CP1252_HIGH[(byte - 0x80) as usize],
+        _ => Some(byte as char),
+    }
+}
+
+/// Lines changed or skipped by [`decode_line`] while loading a corpus.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Repairs {
+    /// 1-based line numbers decoded as Windows-1252.
+    pub repaired: Vec<usize>,
+    /// 1-based line numbers that could not be decoded and were skipped.
+    pub dropped: Vec<usize>,
+}
+
+impl Repairs {
+    pub fn is_empty(&self) -> bool {
+        self.repaired.is_empty() && self.dropped.is_empty()
+    }
+
+    /// One-line summary for harness output.
+    pub fn summary(&self) -> String {
+        format!(
+            "{} rows repaired from Windows-1252, {} undecodable rows dropped",
+            self.repaired.len(),
+            self.dropped.len()
+        )
+    }
+}
@@ -0,0 +1,311 @@
+//! The benchmark as a library, for applications that embed a matcher.
+//!
+//! Teams embedding a matcher in their own services often cannot share
+//! their corpora, but their numbers are only useful to us when they were
+//! measured the way ours are. [`Evaluation`] is the timing loop of
+//! `bench-nucleo` and `run-scenarios` behind a small API: it loads a corpus
+//! and a query set, warms a [`MatcherBackend`] up, times every query over
+//! a number of passes, and returns a [`ResultSet`] in the unified schema.
+//! Written with [`ResultSet::write`], that file reads in `report` and
+//! `gate` beside our own runs, and its fingerprint records the settings it
+//! was measured with.
+//!
+//! [`NucleoBackend`] runs nucleo under a [`Preset`], as `bench-nucleo
+//! --preset` does. An application measures its own matcher by
+//! implementing [`MatcherBackend`] for it.
+//!
+//! ```no_run
+//! use harness::evaluation::{Evaluation, NucleoBackend};
+//! use harness::preset::Preset;
+//!
+//! let mut backend = NucleoBackend::new(Preset::load("autocomplete").unwrap());
+//! let results = Evaluation::new("private-corpus.tsv.gz", "queries.tsv")
+//!     .iterations(10)
+//!     .run_label("acme")
+//!     .run(&mut backend)
+//!     .unwrap();
+//! results.write("acme.json").unwrap();
+//! ```
+
+use crate::corpus::{load_corpus_mapped, Corpus, FieldMap};
+use crate::fingerprint::Fingerprint;
+use crate::manifest::Manifest;
+use crate::prepared::PreparedCorpus;
+use crate::preset::Preset;
+use crate::queries::{load_queries, Query};
+use crate::results::{Hit, QueryResult, ResultSet, Startup, SteadyState};
+use crate::topk::TopK;
+use crate::warmup::Warmup;
+use nucleo_matcher::{Matcher, Utf32Str};
+use std::collections::BTreeMap;
+use std::io;
+use std::path::Path;
+use std::time::Instant;
+
+/// Results kept per query, as `bench-nucleo` records them.
+pub const RECORDED_RESULTS: usize = 10;
+
+/// What a backend found for one query.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Search {
+    /// Rows that matched.
+    pub matches: usize,
+    /// The best rows, best first, as corpus row indices with their scores
+    /// on the backend's own scale, if it has one.
+    pub top: Vec<(usize, Option<f64>)>,
+}
+
+/// A matcher [`Evaluation`] can time.
+pub trait MatcherBackend {
+    /// The name results are recorded under.
+    fn name(&self) -> &str;
+
+    /// Settings that change what the backend returns or how fast, for the
+    /// results' fingerprint.
+    fn config(&self) -> BTreeMap<String, String>;
+
+    /// Builds whatever the backend searches, before any query is timed.
+    /// `queries` are the queries to come, whose fields may need indexing.
+    fn prepare(&mut self, corpus: &Corpus, queries: &[Query]);
+
+    /// Reads what [`prepare`](MatcherBackend::prepare) built through once,
+    /// for a [`Warmup`] with `index` set.
+    fn touch(&mut self) {}
+
+    /// Searches `query`'s field for `query`, returning up to `limit` rows.
+    fn search(&mut self, query: &Query, limit: usize) -> Search;
+}
+
+/// nucleo under a [`Preset`], scanning prepared columns as `bench-nucleo`
+/// does.
+pub struct NucleoBackend {
+    preset: Preset,
+    matcher: Matcher,
+    columns: Option<PreparedCorpus>,
+    buf: Vec<char>,
+}
+
+impl NucleoBackend {
+    /// Matches keep a top list this long before ranking, as in
+    /// `bench-nucleo`.
+    const TOP_K: usize = 100;
+
+    pub fn new(preset: Preset) -> NucleoBackend {
+        NucleoBackend {
+            matcher: Matcher::new(preset.config()),
+            preset,
+            columns: None,
+            buf: Vec::new(),
+        }
+    }
+}
+
+impl MatcherBackend for NucleoBackend {
+    fn name(&self) -> &str {
+        "nucleo"
+    }
+
+    fn config(&self) -> BTreeMap<String, String> {
+        let mut config = self.preset.entries();
+        config.insert("matcher".to_string(), "nucleo-matcher 0.3".to_string());
+        config.insert("top_k".to_string(), Self::TOP_K.to_string());
+        config
+    }
+
+    fn prepare(&mut self, corpus: &Corpus, queries: &[Query]) {
+        let columns = PreparedCorpus::builder(corpus)
+            .fields(queries.iter().map(|q| q.field.as_str()))
+            .build();
+        self.columns = Some(columns);
+    }
+
+    fn touch(&mut self) {
+        if let Some(columns) = &self.columns {
+            for field in columns.fields() {
+                columns.column(field).touch();
+            }
+        }
+    }
+
+    fn search(&mut self, query: &Query, limit: usize) -> Search {
+        let column = self
+            .columns
+            .as_ref()
+            .expect("search before prepare")
+            .column(&query.field);
+        let pattern = self.preset.pattern(&query.text);
+        let mut matches = 0;
+        let mut top = TopK::new(Self::TOP_K.max(limit));
+        for (row, candidate) in column.iter().enumerate() {
+            self.buf.clear();
+            if let Some(score) =
+                pattern.score(Utf32Str::new(candidate, &mut self.buf), &mut self.matcher)
+            {
+                matches += 1;
+                top.push(score, row);
+            }
+        }
+        let mut top = top.into_sorted();
+        self.preset.rank(&mut top, |row| column.get(row));
+        top.truncate(limit);
+        Search {
+            matches,
+            top: top
+                .into_iter()
+                .map(|(score, row)| (row, Some(f64::from(score))))
+                .collect(),
+        }
+    }
+}
+
+/// One benchmark run of a corpus and query set, configured by its builder
+/// methods.
+pub struct Evaluation {
+    corpus_path: String,
+    queries_path: String,
+    map: FieldMap,
+    iterations: usize,
+    warmup: Warmup,
+    run: String,
+}
+
+impl Evaluation {
+    /// An evaluation of the corpus at `corpus` (TSV or JSON Lines, possibly
+    /// compressed) with the query set at `queries`, over 5 passes after one
+    /// untimed pass, as `bench-nucleo` runs by default.
+    pub fn new(corpus: &str, queries: &str) -> Evaluation {
+        Evaluation {
+            corpus_path: corpus.to_string(),
+            queries_path: queries.to_string(),
+            map: FieldMap::default(),
+            iterations: 5,
+            warmup: Warmup::IN_PROCESS,
+            run: String::new(),
+        }
+    }
+
+    /// The corpus columns or keys holding the symbol, name and ISIN.
+    pub fn map(mut self, map: FieldMap) -> Self {
+        self.map = map;
+        self
+    }
+
+    /// Timed passes over the query set; each query's median is over these.
+    pub fn iterations(mut self, iterations: usize) -> Self {
+        self.iterations = iterations.max(1);
+        self
+    }
+
+    pub fn warmup(mut self, warmup: Warmup) -> Self {
+        self.warmup = warmup;
+        self
+    }
+
+    /// The results' run label, such as a team or release name.
+    pub fn run_label(mut self, run: &str) -> Self {
+        self.run = run.to_string();
+        self
+    }
+
+    /// Loads the corpus and queries, then prepares, warms up and times
+    /// `backend`. Loading and preparing are recorded as the `load` and
+    /// `prepare` startup phases.
+    pub fn run(&self, backend: &mut dyn MatcherBackend) -> io::Result<ResultSet> {
+        let mut startup = Startup::default();
+        let start = Instant::now();
+        let corpus = load_corpus_mapped(&self.corpus_path, &self.map)?;
+        startup.push("load", start.elapsed().as_secs_f64() * 1000.0);
+        let queries = load_queries(&self.queries_path)?;
+        let start = Instant::now();
+        backend.prepare(&corpus, &queries);
+        startup.push("prepare", start.elapsed().as_secs_f64() * 1000.0);
+
+        if self.warmup.index {
+            backend.touch();
+        }
+        for q in queries.iter().take(self.warmup.query_count(queries.len())) {
+            backend.search(q, RECORDED_RESULTS);
+        }
+
+        // A discarded pass is timed like the rest but not kept, and the
+        // first pass kept keeps results
+        let passes = self.warmup.timed_passes(self.iterations);
+        let discarded = passes - self.iterations;
+        let mut records: Vec<QueryResult> = queries.iter().map(QueryResult::new).collect();
+        let mut timings: Vec<Vec<f64>> = vec![Vec::with_capacity(self.iterations); queries.len()];
+        let mut pass_totals = Vec::with_capacity(self.iterations);
+        for pass in 0..passes {
+            let pass_start = Instant::now();
+            for (qi, q) in queries.iter().enumerate() {
+                let start = Instant::now();
+                let search = backend.search(q, RECORDED_RESULTS);
+                let elapsed_ms = start.elapsed().as_secs_f64() * 1000.0;
+                if pass < discarded {
+                    continue;
+                }
+                timings[qi].push(elapsed_ms);
+                if pass == discarded {
+                    records[qi].matches = Some(search.matches);
+                    records[qi].results = Some(
+                        search
+                            .top
+                            .iter()
+                            .map(|&(row, score)| {
+                                let instrument = corpus.get(row);
+                                Hit {
+                                    symbol: instrument.symbol.to_string(),
+                                    name: instrument.name.to_string(),
+                                    score,
+                                }
+                            })
+                            .collect(),
+                    );
+                }
+            }
+            if pass >= discarded {
+                pass_totals.push(pass_start.elapsed().as_secs_f64() * 1000.0);
+            }
+        }
+        for (record, times) in records.iter_mut().zip(&mut timings) {
+            times.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            record.median_ms = Some(times[times.len() / 2]);
+            record.min_ms = Some(times[0]);
+        }
+
+        let mut config = backend.config();
+        config.insert("iterations".to_string(), self.iterations.to_string());
+        config.insert("map".to_string(), self.map_spec());
+        config.extend(self.warmup.entries(&Warmup::IN_PROCESS));
+        Ok(ResultSet {
+            backend: backend.name().to_string(),
+            run: self.run.clone(),
+            corpus: Path::new(&self.corpus_path)
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string()),
+            corpus_sha256: Manifest::read_for(&self.corpus_path)
+                .ok()
+                .flatten()
+                .map(|m| m.sha256),
+            fingerprint: Some(Fingerprint::capture(&[], config)),
+            startup: Some(startup),
+            steady_state: SteadyState::from_passes(&pass_totals, queries.len()),
+            memory: Vec::new(),
+            queries: records,
+        })
+    }
+
+    /// The field map as `--map` would spell it, empty for the default.
+    fn map_spec(&self) -> String {
+        let default = FieldMap::default();
+        [
+            ("symbol", &self.map.symbol, &default.symbol),
+            ("name", &self.map.name, &default.name),
+            ("isin", &self.map.isin, &default.isin),
+        ]
+        .iter()
+        .filter(|(_, value, default)| value != default)
+        .map(|(field, value, _)| format!("{field}={value}"))
+        .collect::<Vec<_>>()
+        .join(",")
+    }
+}
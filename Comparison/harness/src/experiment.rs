@@ -0,0 +1,245 @@
+//! A/B experiments: ranking a share of live searches with another preset.
+//!
+//! Offline metrics say which preset ranks the judged queries better; an
+//! experiment says whether users notice. An [`Experiment`] splits searches
+//! between a control and a variant [`Preset`] by hashing a unit key, a user
+//! or session id where the client sends one, so the same user keeps seeing
+//! the same ranking for as long as the experiment runs. Which arm a unit
+//! lands in depends only on the experiment's name and the key, not on the
+//! server or the order requests arrive in.
+//!
+//! An [`EventLog`] records every search and every click as a JSON line.
+//! Search events name the arm, its preset and the SHA-256 of the preset's
+//! settings (the hash a results file's fingerprint gives the same preset),
+//! and clicks refer to the search by request id. [`summarize`] joins the
+//! two into per-arm click-through rates; the preset hash joins those with
+//! the offline quality of each arm.
+
+use crate::cli::{flag_value, parsed_flag};
+use crate::fingerprint::Fingerprint;
+use crate::preset::Preset;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, Write};
+use std::sync::Mutex;
+
+/// Share of units given the variant when `--variant-share` is not set.
+pub const DEFAULT_SHARE: f64 = 0.1;
+
+/// One side of an experiment.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Arm {
+    /// `control` or `variant`.
+    pub label: &'static str,
+    pub preset: Preset,
+    /// SHA-256 of the preset's settings, see [`preset_sha256`].
+    pub config_sha256: String,
+}
+
+impl Arm {
+    pub fn new(label: &'static str, preset: Preset) -> Arm {
+        let config_sha256 = preset_sha256(&preset);
+        Arm {
+            label,
+            preset,
+            config_sha256,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Experiment {
+    pub name: String,
+    /// Fraction of units ranked by the variant, 0.0 to 1.0.
+    pub share: f64,
+    pub control: Arm,
+    pub variant: Arm,
+}
+
+impl Experiment {
+    pub fn new(
+        name: &str,
+        control: Preset,
+        variant: Preset,
+        share: f64,
+    ) -> Result<Experiment, String> {
+        if !(0.0..=1.0).contains(&share) {
+            return Err(format!("variant share must be 0 to 1, not {share}"));
+        }
+        Ok(Experiment {
+            name: name.to_string(),
+            share,
+            control: Arm::new("control", control),
+            variant: Arm::new("variant", variant),
+        })
+    }
+
+    /// The experiment `--variant PRESET` asks for, if any: `--preset` (or
+    /// the default) is the control, `--variant-share` the variant's share
+    /// and `--experiment NAME` its name, `CONTROL-vs-VARIANT` by default.
+    pub fn from_args(args: &[String]) -> Result<Option<Experiment>, String> {
+        let Some(variant) = flag_value(args, "--variant") else {
+            return Ok(None);
+        };
+        let control = Preset::from_args(args)?;
+        let variant = Preset::load(variant)?;
+        let name = flag_value(args, "--experiment")
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("{}-vs-{}", control.name, variant.name));
+        let share = parsed_flag(args, "--variant-share", DEFAULT_SHARE);
+        Experiment::new(&name, control, variant, share).map(Some)
+    }
+
+    /// The arm the unit `key` is assigned to.
+    pub fn assign(&self, key: &str) -> &Arm {
+        if bucket(&self.name, key) < self.share {
+            &self.variant
+        } else {
+            &self.control
+        }
+    }
+}
+
+/// Where `key` falls in `[0, 1)` for the experiment `name`: a hash of
+/// both, so a unit's bucket is stable across restarts and servers, and
+/// differs between experiments.
+pub fn bucket(name: &str, key: &str) -> f64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in name.bytes().chain([0]).chain(key.bytes()) {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    // FNV-1a leaves the high bits poorly mixed for keys differing in their
+    // last bytes, such as numbered user ids; finish as SplitMix64 does
+    hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    hash ^= hash >> 31;
+    (hash >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// SHA-256 of a preset's settings, equal to the configuration hash of a
+/// fingerprint holding only [`Preset::entries`].
+pub fn preset_sha256(preset: &Preset) -> String {
+    Fingerprint {
+        config: preset.entries(),
+        ..Fingerprint::default()
+    }
+    .config_sha256()
+}
+
+/// Search and click events appended to a file, one JSON object per line.
+pub struct EventLog {
+    file: Mutex<File>,
+}
+
+impl EventLog {
+    /// Opens `path` for appending, creating it if needed, so a restarted
+    /// server adds to the events of its previous run.
+    pub fn open(path: &str) -> io::Result<EventLog> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(EventLog {
+            file: Mutex::new(file),
+        })
+    }
+
+    /// Appends `event` as one line, written whole so concurrent requests
+    /// never interleave.
+    pub fn record(&self, event: &Value) -> io::Result<()> {
+        let mut line = event.to_string();
+        line.push('\n');
+        self.file.lock().unwrap().write_all(line.as_bytes())
+    }
+}
+
+/// Click-through of one arm of an experiment.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ArmSummary {
+    pub experiment: String,
+    pub arm: String,
+    pub preset: String,
+    pub config_sha256: String,
+    pub searches: usize,
+    /// Searches with at least one click.
+    pub clicked: usize,
+    /// Sum of 1 / rank of each clicked search's best-ranked click, ranks
+    /// counting from 1.
+    pub reciprocal_ranks: f64,
+}
+
+impl ArmSummary {
+    /// Share of searches with a click.
+    pub fn click_through(&self) -> f64 {
+        if self.searches == 0 {
+            0.0
+        } else {
+            self.clicked as f64 / self.searches as f64
+        }
+    }
+
+    /// Mean reciprocal rank of the first click, counting searches without
+    /// one as 0.
+    pub fn mrr(&self) -> f64 {
+        if self.searches == 0 {
+            0.0
+        } else {
+            self.reciprocal_ranks / self.searches as f64
+        }
+    }
+}
+
+/// Joins the clicks in an event log to their searches and totals them by
+/// experiment and arm, in the order arms first appear. Clicks on a search
+/// the log does not hold, and lines that are not events, are skipped.
+pub fn summarize(log: impl BufRead) -> io::Result<Vec<ArmSummary>> {
+    let mut arms: Vec<ArmSummary> = Vec::new();
+    // request id -> (arm index, best clicked rank)
+    let mut searches: HashMap<String, (usize, Option<u64>)> = HashMap::new();
+    for line in log.lines() {
+        let Ok(event) = serde_json::from_str::<Value>(&line?) else {
+            continue;
+        };
+        let text = |key: &str| event[key].as_str().unwrap_or_default().to_string();
+        let Some(request_id) = event["request_id"].as_str() else {
+            continue;
+        };
+        match event["event"].as_str() {
+            Some("search") => {
+                let (experiment, arm) = (text("experiment"), text("arm"));
+                let index = match arms
+                    .iter()
+                    .position(|a| a.experiment == experiment && a.arm == arm)
+                {
+                    Some(index) => index,
+                    None => {
+                        arms.push(ArmSummary {
+                            experiment,
+                            arm,
+                            preset: text("preset"),
+                            config_sha256: text("config_sha256"),
+                            ..ArmSummary::default()
+                        });
+                        arms.len() - 1
+                    }
+                };
+                arms[index].searches += 1;
+                searches.insert(request_id.to_string(), (index, None));
+            }
+            Some("click") => {
+                if let (Some(search), Some(rank)) =
+                    (searches.get_mut(request_id), event["rank"].as_u64())
+                {
+                    search.1 = Some(search.1.map_or(rank, |best| best.min(rank)));
+                }
+            }
+            _ => {}
+        }
+    }
+    for (index, best) in searches.into_values() {
+        if let Some(rank) = best {
+            arms[index].clicked += 1;
+            arms[index].reciprocal_ranks += 1.0 / rank.max(1) as f64;
+        }
+    }
+    Ok(arms)
+}
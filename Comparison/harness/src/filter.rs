@@ -0,0 +1,111 @@
+//! Metadata filters applied alongside fuzzy search.
+//!
+//! A filter is a list of `column=value` clauses separated by whitespace,
+//! e.g. `exchange=XNAS currency=USD`. Every clause must hold; `|` separates
+//! alternative values (`exchange=XNAS|XNYS`). Double quotes protect spaces
+//! in column names and values (`"Product Class"="Equity Share"`). Columns
+//! and values are compared case-insensitively against the corpus metadata
+//! (see [`Corpus::metadata`]).
+
+use crate::corpus::{Corpus, Metadata};
+use std::str::FromStr;
+
+/// A parsed filter, not yet tied to a corpus.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Filter {
+    clauses: Vec<(String, Vec<String>)>,
+}
+
+impl FromStr for Filter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut clauses = Vec::new();
+        for token in tokens(s)? {
+            let (column, values) = token
+                .split_once('=')
+                .ok_or_else(|| format!("expected column=value, got `{token}`"))?;
+            let column = column.trim();
+            if column.is_empty() {
+                return Err(format!("missing column in `{token}`"));
+            }
+            let values = values.split('|').map(|v| v.trim().to_string()).collect();
+            clauses.push((column.to_string(), values));
+        }
+        Ok(Filter { clauses })
+    }
+}
+
+/// Splits on whitespace outside double quotes, dropping the quotes.
+fn tokens(s: &str) -> Result<Vec<String>, String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    for c in s.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if quoted {
+        return Err(format!("unterminated quote in `{s}`"));
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    Ok(tokens)
+}
+
+impl Filter {
+    /// Whether the filter has no clauses and so accepts every row.
+    pub fn is_empty(&self) -> bool {
+        self.clauses.is_empty()
+    }
+
+    /// Resolves column names against `corpus`. Naming a column the corpus
+    /// lacks is an error, since it would otherwise silently match nothing.
+    pub fn compile(&self, corpus: &Corpus) -> Result<CompiledFilter, String> {
+        let clauses = self
+            .clauses
+            .iter()
+            .map(|(column, values)| {
+                let index = corpus.metadata_column(column).ok_or_else(|| {
+                    format!(
+                        "corpus has no metadata column `{column}` (columns: {:?})",
+                        corpus.metadata_columns()
+                    )
+                })?;
+                Ok((index, values.clone()))
+            })
+            .collect::<Result<_, String>>()?;
+        Ok(CompiledFilter { clauses })
+    }
+}
+
+/// A [`Filter`] with columns resolved to metadata indices.
+#[derive(Clone, Debug)]
+pub struct CompiledFilter {
+    clauses: Vec<(usize, Vec<String>)>,
+}
+
+impl CompiledFilter {
+    /// Whether a row's metadata satisfies every clause.
+    pub fn matches(&self, metadata: &Metadata) -> bool {
+        self.clauses.iter().all(|(column, values)| {
+            let value = metadata.value(*column);
+            values.iter().any(|v| v.eq_ignore_ascii_case(value))
+        })
+    }
+
+    /// Indices of the rows of `corpus` that pass, in corpus order.
+    pub fn select(&self, corpus: &Corpus) -> Vec<usize> {
+        (0..corpus.len())
+            .filter(|&idx| self.matches(&corpus.metadata(idx)))
+            .collect()
+    }
+}
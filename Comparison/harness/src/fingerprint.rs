@@ -0,0 +1,136 @@
+//! What produced a result file: the source revision, the build's cargo
+//! features and the effective matcher configuration.
+//!
+//! Two result files are only comparable when the difference between them
+//! is the one under test. Recording the revision (and whether the tree had
+//! uncommitted changes), the features, and a hash of every setting that
+//! affects matching lets a report say so, instead of leaving it to whoever
+//! remembers which flags a run used.
+
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::process::Command;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Fingerprint {
+    /// Commit the binary was run from, when it ran inside a git checkout.
+    pub git_sha: Option<String>,
+    /// Whether the checkout had uncommitted changes.
+    pub git_dirty: Option<bool>,
+    /// Cargo features the binary was built with, including the harness's
+    /// own as `harness/<feature>`.
+    pub features: Vec<String>,
+    /// Every setting that affects matching, by name.
+    pub config: BTreeMap<String, String>,
+}
+
+impl Fingerprint {
+    /// Fingerprints the current checkout with the calling binary's build
+    /// features and matcher configuration. Git details are left unset when `git` is
+    /// unavailable or the harness is not inside a checkout.
+    pub fn capture(features: &[&str], config: BTreeMap<String, String>) -> Fingerprint {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let git = |args: &[&str]| -> Option<String> {
+            let output = Command::new("git")
+                .arg("-C")
+                .arg(dir)
+                .args(args)
+                .output()
+                .ok()?;
+            output
+                .status
+                .success()
+                .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        };
+        let git_sha = git(&["rev-parse", "HEAD"]);
+        let git_dirty = git_sha
+            .as_ref()
+            .and_then(|_| git(&["status", "--porcelain"]))
+            .map(|status| !status.is_empty());
+        let mut features: Vec<String> = features.iter().map(|f| f.to_string()).collect();
+        if cfg!(feature = "store") {
+            features.push("harness/store".to_string());
+        }
+        features.sort();
+        Fingerprint {
+            git_sha,
+            git_dirty,
+            features,
+            config,
+        }
+    }
+
+    /// Hex SHA-256 of the configuration, one `key=value` line per setting
+    /// in key order, so equal settings always hash equally.
+    pub fn config_sha256(&self) -> String {
+        let mut hasher = Sha256::new();
+        for (key, value) in &self.config {
+            hasher.update(format!("{key}={value}\n"));
+        }
+        hasher
+            .finalize()
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect()
+    }
+
+    /// Short commit, suffixed `-dirty` for uncommitted changes, or
+    /// `unknown`.
+    pub fn revision(&self) -> String {
+        match &self.git_sha {
+            Some(sha) => {
+                let dirty = if self.git_dirty == Some(true) {
+                    "-dirty"
+                } else {
+                    ""
+                };
+                format!("{}{dirty}", &sha[..sha.len().min(10)])
+            }
+            None => "unknown".to_string(),
+        }
+    }
+
+    pub(crate) fn to_json(&self) -> Value {
+        json!({
+            "git_sha": self.git_sha,
+            "git_dirty": self.git_dirty,
+            "features": self.features,
+            "config": self.config,
+            "config_sha256": self.config_sha256(),
+        })
+    }
+
+    pub(crate) fn from_json(value: &Value) -> Option<Fingerprint> {
+        Some(Fingerprint {
+            git_sha: value
+                .get("git_sha")
+                .and_then(Value::as_str)
+                .map(String::from),
+            git_dirty: value.get("git_dirty").and_then(Value::as_bool),
+            features: value
+                .get("features")
+                .and_then(Value::as_array)
+                .map(|features| {
+                    features
+                        .iter()
+                        .filter_map(|f| f.as_str().map(String::from))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            config: value
+                .get("config")?
+                .as_object()?
+                .iter()
+                .map(|(key, value)| {
+                    let value = match value {
+                        Value::String(s) => s.clone(),
+                        other => other.to_string(),
+                    };
+                    (key.clone(), value)
+                })
+                .collect(),
+        })
+    }
+}
@@ -0,0 +1,190 @@
+//! How many rows of a column contain each character and each pair of
+//! adjacent characters, for estimating how selective a query is before
+//! scanning.
+//!
+//! nucleo scores a multi-word query atom by atom and gives up on a
+//! candidate at the first atom it fails, so putting the atom that fails
+//! most candidates first saves scoring the others. A fuzzy atom can only
+//! match a row holding every one of its characters, so the share of rows
+//! holding its rarest character bounds the share it matches. Substring,
+//! prefix, postfix and exact atoms also need each adjacent pair of their
+//! characters, which bounds them much more tightly. The table is
+//! case-folded, as the default patterns are.
+
+use nucleo_matcher::pattern::{Atom, AtomKind, Pattern};
+use std::collections::HashMap;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CharFrequency {
+    rows: usize,
+    chars: HashMap<char, u32>,
+    bigrams: HashMap<(char, char), u32>,
+}
+
+impl CharFrequency {
+    /// Counts the characters of each distinct value, paired with the number
+    /// of rows that hold it.
+    pub fn new<'a>(values: impl IntoIterator<Item = (&'a str, usize)>) -> CharFrequency {
+        let mut frequency = CharFrequency::default();
+        let mut chars: Vec<char> = Vec::new();
+        let mut bigrams: Vec<(char, char)> = Vec::new();
+        for (text, rows) in values {
+            frequency.rows += rows;
+            chars.clear();
+            chars.extend(text.chars().map(fold));
+            bigrams.clear();
+            bigrams.extend(chars.windows(2).map(|pair| (pair[0], pair[1])));
+            chars.sort_unstable();
+            chars.dedup();
+            bigrams.sort_unstable();
+            bigrams.dedup();
+            for &c in &chars {
+                *frequency.chars.entry(c).or_default() += rows as u32;
+            }
+            for &pair in &bigrams {
+                *frequency.bigrams.entry(pair).or_default() += rows as u32;
+            }
+        }
+        frequency
+    }
+
+    /// A table from its parts, as read back from a serialized index.
+    pub fn from_counts(
+        rows: usize,
+        chars: HashMap<char, u32>,
+        bigrams: HashMap<(char, char), u32>,
+    ) -> CharFrequency {
+        CharFrequency {
+            rows,
+            chars,
+            bigrams,
+        }
+    }
+
+    /// Adds the counts of a table built over other values of the same
+    /// column, as if both sets of values had been counted together.
+    pub fn merge(&mut self, other: &CharFrequency) {
+        self.rows += other.rows;
+        for (&c, &rows) in &other.chars {
+            *self.chars.entry(c).or_default() += rows;
+        }
+        for (&pair, &rows) in &other.bigrams {
+            *self.bigrams.entry(pair).or_default() += rows;
+        }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Rows containing `c`, in either case.
+    pub fn count(&self, c: char) -> u32 {
+        self.chars.get(&fold(c)).copied().unwrap_or(0)
+    }
+
+    /// Rows containing `a` directly followed by `b`, in either case.
+    pub fn bigram_count(&self, a: char, b: char) -> u32 {
+        self.bigrams.get(&(fold(a), fold(b))).copied().unwrap_or(0)
+    }
+
+    /// Every character counted, with its rows, in character order.
+    pub fn chars(&self) -> Vec<(char, u32)> {
+        let mut chars: Vec<(char, u32)> = self.chars.iter().map(|(&c, &n)| (c, n)).collect();
+        chars.sort_unstable();
+        chars
+    }
+
+    /// Every pair of adjacent characters counted, with its rows, in order.
+    pub fn bigrams(&self) -> Vec<((char, char), u32)> {
+        let mut bigrams: Vec<((char, char), u32)> =
+            self.bigrams.iter().map(|(&pair, &n)| (pair, n)).collect();
+        bigrams.sort_unstable();
+        bigrams
+    }
+
+    /// Estimated share of rows a fuzzy atom with `needle` matches: the
+    /// share holding its rarest character, or every row for an empty
+    /// needle.
+    pub fn estimate(&self, needle: &str) -> f64 {
+        let needle: Vec<char> = needle.chars().filter(|c| !c.is_whitespace()).collect();
+        self.share(needle.iter().map(|&c| self.count(c)))
+    }
+
+    /// Estimated share of rows `atom` matches. Atoms other than fuzzy ones
+    /// match their needle contiguously, so their rarest adjacent pair
+    /// counts too. A negative atom's estimate is still the share its
+    /// needle matches, which are the rows it rejects.
+    pub fn estimate_atom(&self, atom: &Atom) -> f64 {
+        let needle = atom.needle_text().to_string();
+        if atom.kind == AtomKind::Fuzzy {
+            return self.estimate(&needle);
+        }
+        let needle: Vec<char> = needle.chars().collect();
+        let chars = needle.iter().map(|&c| self.count(c));
+        let pairs = needle
+            .windows(2)
+            .map(|pair| self.bigram_count(pair[0], pair[1]));
+        self.share(chars.chain(pairs))
+    }
+
+    /// Estimated share of rows the whole pattern matches: the rarest of its
+    /// atoms, less the rows its negative atoms reject.
+    pub fn estimate_pattern(&self, pattern: &Pattern) -> f64 {
+        let positive = pattern
+            .atoms
+            .iter()
+            .filter(|atom| !atom.negative)
+            .map(|atom| self.estimate_atom(atom))
+            .fold(1.0, f64::min);
+        pattern
+            .atoms
+            .iter()
+            .filter(|atom| atom.negative)
+            .fold(positive, |share, atom| {
+                share * (1.0 - self.estimate_atom(atom))
+            })
+    }
+
+    /// Reorders `pattern`'s atoms so the one estimated to let the fewest
+    /// rows through comes first. A negative atom lets through the rows it
+    /// does not match. Scores are a sum over atoms, so the order changes
+    /// only how soon a failing candidate is given up on.
+    pub fn order_atoms(&self, pattern: &mut Pattern) {
+        if pattern.atoms.len() < 2 {
+            return;
+        }
+        let passing: Vec<f64> = pattern
+            .atoms
+            .iter()
+            .map(|atom| {
+                let share = self.estimate_atom(atom);
+                if atom.negative {
+                    1.0 - share
+                } else {
+                    share
+                }
+            })
+            .collect();
+        let mut order: Vec<usize> = (0..pattern.atoms.len()).collect();
+        order.sort_by(|&a, &b| passing[a].total_cmp(&passing[b]));
+        pattern.atoms = order
+            .into_iter()
+            .map(|i| pattern.atoms[i].clone())
+            .collect();
+    }
+
+    /// The smallest of `counts` as a share of the rows, or every row when
+    /// there are none.
+    fn share(&self, counts: impl Iterator<Item = u32>) -> f64 {
+        if self.rows == 0 {
+            return 0.0;
+        }
+        counts
+            .min()
+            .map_or(1.0, |count| count as f64 / self.rows as f64)
+    }
+}
+
+fn fold(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
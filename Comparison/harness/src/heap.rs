@@ -0,0 +1,200 @@
+//! Heap profiling of a scoring loop with dhat.
+//!
+//! A binary built with the `heap-profile` feature installs
+//! [`Alloc`] as its global allocator and wraps the code to measure in a
+//! [`HeapProfile`]. Finishing the profile writes dhat's JSON file, which
+//! `dh_view.html` from Valgrind opens, and returns a [`HeapReport`]: how
+//! many allocations the loop made, its peak heap, and the call sites that
+//! allocated the most bytes.
+//!
+//! Only allocations made while the profile runs are counted, so the corpus
+//! and query set loaded beforehand do not appear in the peak. dhat tracks a
+//! backtrace per allocation, which slows allocation-heavy code badly: take
+//! timings from a run without the profile.
+//!
+//! Without the feature, [`HeapProfile::start`] warns and returns `None`,
+//! and binaries need no `cfg` of their own beyond the allocator.
+
+use serde_json::Value;
+use std::cmp::Reverse;
+use std::fmt;
+#[cfg(feature = "heap-profile")]
+use std::fs;
+
+#[cfg(feature = "heap-profile")]
+pub use dhat::Alloc;
+
+/// Call sites shown in a [`HeapReport`].
+pub const TOP_SITES: usize = 10;
+
+/// A running heap profile; profiling stops when it is finished.
+pub struct HeapProfile {
+    path: String,
+    #[cfg(feature = "heap-profile")]
+    profiler: dhat::Profiler,
+}
+
+impl HeapProfile {
+    /// Starts profiling, to be written to `path` when finished.
+    #[cfg(feature = "heap-profile")]
+    pub fn start(path: &str) -> Option<HeapProfile> {
+        Some(HeapProfile {
+            path: path.to_string(),
+            profiler: dhat::Profiler::builder().file_name(path).build(),
+        })
+    }
+
+    #[cfg(not(feature = "heap-profile"))]
+    pub fn start(path: &str) -> Option<HeapProfile> {
+        eprintln!("Warning: no heap profile for {path}: built without the `heap-profile` feature");
+        None
+    }
+
+    /// Stops profiling, writes the dhat file and summarises it.
+    #[cfg(feature = "heap-profile")]
+    pub fn finish(self) -> Result<HeapReport, String> {
+        let stats = dhat::HeapStats::get();
+        drop(self.profiler);
+        let json = fs::read_to_string(&self.path).map_err(|e| format!("{}: {e}", self.path))?;
+        Ok(HeapReport {
+            sites: top_sites(&json, TOP_SITES)?,
+            path: self.path,
+            total_blocks: stats.total_blocks,
+            total_bytes: stats.total_bytes,
+            max_blocks: stats.max_blocks as u64,
+            max_bytes: stats.max_bytes as u64,
+        })
+    }
+
+    #[cfg(not(feature = "heap-profile"))]
+    pub fn finish(self) -> Result<HeapReport, String> {
+        Err(format!(
+            "{}: built without the `heap-profile` feature",
+            self.path
+        ))
+    }
+}
+
+/// What a profiled stretch of code allocated.
+#[derive(Debug, PartialEq)]
+pub struct HeapReport {
+    /// The dhat file written.
+    pub path: String,
+    pub total_blocks: u64,
+    pub total_bytes: u64,
+    /// Blocks and bytes live at the peak.
+    pub max_blocks: u64,
+    pub max_bytes: u64,
+    /// Call sites by total bytes allocated, most first.
+    pub sites: Vec<HeapSite>,
+}
+
+/// One allocating call site.
+#[derive(Debug, PartialEq)]
+pub struct HeapSite {
+    /// The innermost frame outside the allocator and standard library, as
+    /// `function (file:line:column)`.
+    pub frame: String,
+    pub total_blocks: u64,
+    pub total_bytes: u64,
+}
+
+/// The `n` call sites in a dhat JSON file that allocated the most bytes.
+///
+/// dhat records a backtrace per program point. Program points whose
+/// innermost frames outside `alloc`, `core`, `std` and dhat itself agree
+/// are folded into one site, since they are the same line of our code
+/// reached through different allocator paths.
+pub fn top_sites(json: &str, n: usize) -> Result<Vec<HeapSite>, String> {
+    let value: Value = serde_json::from_str(json).map_err(|e| e.to_string())?;
+    let frames: Vec<&str> = value
+        .get("ftbl")
+        .and_then(Value::as_array)
+        .ok_or("dhat file has no frame table")?
+        .iter()
+        .map(|f| f.as_str().unwrap_or_default())
+        .collect();
+    let points = value
+        .get("pps")
+        .and_then(Value::as_array)
+        .ok_or("dhat file has no program points")?;
+
+    let mut sites: Vec<HeapSite> = Vec::new();
+    for point in points {
+        let count = |key: &str| point.get(key).and_then(Value::as_u64).unwrap_or(0);
+        let frame = point
+            .get("fs")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|i| frames.get(i.as_u64()? as usize).copied())
+            .map(|f| f.split_once(": ").map_or(f, |(_, frame)| frame))
+            .find(|f| !is_allocator_frame(f))
+            .unwrap_or("[unknown]");
+        match sites.iter_mut().find(|s| s.frame == frame) {
+            Some(site) => {
+                site.total_blocks += count("tbk");
+                site.total_bytes += count("tb");
+            }
+            None => sites.push(HeapSite {
+                frame: frame.to_string(),
+                total_blocks: count("tbk"),
+                total_bytes: count("tb"),
+            }),
+        }
+    }
+    sites.sort_by_key(|s| Reverse(s.total_bytes));
+    sites.truncate(n);
+    Ok(sites)
+}
+
+/// Whether `frame` is allocator or standard library code, including trait
+/// impls such as `<T as alloc::slice::…>::to_vec`.
+fn is_allocator_frame(frame: &str) -> bool {
+    let name = frame.trim_start_matches('<');
+    frame == "[root]"
+        || ["alloc::", "core::", "std::", "dhat::", "__rust"]
+            .iter()
+            .any(|prefix| name.starts_with(prefix) || name.contains(&format!(" as {prefix}")))
+}
+
+impl fmt::Display for HeapReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "Allocations: {} blocks, {}",
+            self.total_blocks,
+            bytes(self.total_bytes)
+        )?;
+        writeln!(
+            f,
+            "Peak heap:   {} blocks, {}",
+            self.max_blocks,
+            bytes(self.max_bytes)
+        )?;
+        if !self.sites.is_empty() {
+            writeln!(f)?;
+            writeln!(f, "{:>10} {:>10}  Allocation site", "Bytes", "Blocks")?;
+            writeln!(f, "{}", "-".repeat(80))?;
+            for site in &self.sites {
+                writeln!(
+                    f,
+                    "{:>10} {:>10}  {}",
+                    bytes(site.total_bytes),
+                    site.total_blocks,
+                    site.frame
+                )?;
+            }
+        }
+        write!(f, "Full profile: {} (open with dh_view.html)", self.path)
+    }
+}
+
+fn bytes(n: u64) -> String {
+    match n {
+        n if n >= 1 << 30 => format!("{:.1} GiB", n as f64 / (1u64 << 30) as f64),
+        n if n >= 1 << 20 => format!("{:.1} MiB", n as f64 / (1u64 << 20) as f64),
+        n if n >= 1 << 10 => format!("{:.1} KiB", n as f64 / (1u64 << 10) as f64),
+        n => format!("{n} B"),
+    }
+}
@@ -0,0 +1,151 @@
+//! Highlighted runs of a result's fields, for drawing a match.
+//!
+//! nucleo reports a match as the character positions it matched, one per
+//! character, out of order when a pattern has several atoms and repeated
+//! where their matches overlap. A UI draws runs of characters, so
+//! [`ranges`] sorts the positions, merges adjacent ones into half-open
+//! character ranges and clips them to the text shown. [`Highlights`] keeps
+//! the ranges of every field a multi-field search matched, so a result
+//! carries what it takes to draw its symbol and its name without matching
+//! either again.
+//!
+//! Finding the positions costs a second, slower pass over the row than
+//! scoring it, and a list showing 10 of its 100 best rows need not pay it
+//! for the other 90. A [`LazyHighlight`] runs it the first time a row is
+//! drawn and keeps the ranges for later redraws; the row keeps only its
+//! index, and the caller the pattern, which is all it takes to rerun it.
+
+use nucleo_matcher::pattern::Pattern;
+use nucleo_matcher::{Matcher, Utf32Str};
+use std::cell::OnceCell;
+use std::ops::Range;
+
+/// Matched character `indices` as sorted, merged ranges, less any past
+/// `len` characters.
+pub fn ranges(indices: &[u32], len: usize) -> Vec<Range<usize>> {
+    let mut indices: Vec<usize> = indices
+        .iter()
+        .map(|&i| i as usize)
+        .filter(|&i| i < len)
+        .collect();
+    indices.sort_unstable();
+    indices.dedup();
+    let mut ranges: Vec<Range<usize>> = Vec::new();
+    for i in indices {
+        match ranges.last_mut() {
+            Some(last) if last.end == i => last.end += 1,
+            _ => ranges.push(i..i + 1),
+        }
+    }
+    ranges
+}
+
+/// The ranges of `text` that `pattern` highlights, or `None` when it does
+/// not match.
+pub fn highlight(
+    pattern: &Pattern,
+    matcher: &mut Matcher,
+    text: &str,
+) -> Option<Vec<Range<usize>>> {
+    let mut buf = Vec::new();
+    let mut indices = Vec::new();
+    pattern.indices(Utf32Str::new(text, &mut buf), matcher, &mut indices)?;
+    Some(ranges(&indices, text.chars().count()))
+}
+
+/// Highlighted ranges per field of one result, in the order the fields
+/// were searched. Fields the query did not match are left out.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Highlights {
+    fields: Vec<(String, Vec<Range<usize>>)>,
+}
+
+impl Highlights {
+    /// Highlights `pattern` in each of `fields`, given as `(field, text)`.
+    pub fn new<'a>(
+        pattern: &Pattern,
+        matcher: &mut Matcher,
+        fields: impl IntoIterator<Item = (&'a str, &'a str)>,
+    ) -> Highlights {
+        let mut highlights = Highlights::default();
+        for (field, text) in fields {
+            if let Some(ranges) = highlight(pattern, matcher, text) {
+                highlights.insert(field, ranges);
+            }
+        }
+        highlights
+    }
+
+    /// Records `ranges` for `field`, merged with any already recorded.
+    pub fn insert(&mut self, field: &str, ranges: Vec<Range<usize>>) {
+        match self.fields.iter_mut().find(|(have, _)| have == field) {
+            Some((_, have)) => {
+                let indices: Vec<u32> = have
+                    .iter()
+                    .chain(&ranges)
+                    .flat_map(|range| range.clone().map(|i| i as u32))
+                    .collect();
+                let len = indices.iter().max().map_or(0, |&i| i as usize + 1);
+                *have = self::ranges(&indices, len);
+            }
+            None => self.fields.push((field.to_string(), ranges)),
+        }
+    }
+
+    /// The ranges highlighted in `field`; empty when it did not match.
+    pub fn get(&self, field: &str) -> &[Range<usize>] {
+        self.fields
+            .iter()
+            .find(|(have, _)| have == field)
+            .map_or(&[], |(_, ranges)| ranges)
+    }
+
+    /// Each matched field with its ranges.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &[Range<usize>])> {
+        self.fields
+            .iter()
+            .map(|(field, ranges)| (field.as_str(), ranges.as_slice()))
+    }
+
+    /// The highlights as a JSON object of `[start, end)` pairs per field.
+    pub fn to_json(&self) -> serde_json::Value {
+        self.iter()
+            .map(|(field, ranges)| {
+                let ranges: Vec<[usize; 2]> = ranges.iter().map(|r| [r.start, r.end]).collect();
+                (field.to_string(), serde_json::json!(ranges))
+            })
+            .collect::<serde_json::Map<_, _>>()
+            .into()
+    }
+}
+
+/// A row's highlight ranges, computed when first asked for.
+#[derive(Clone, Debug, Default)]
+pub struct LazyHighlight {
+    ranges: OnceCell<Option<Vec<Range<usize>>>>,
+}
+
+impl LazyHighlight {
+    pub fn new() -> LazyHighlight {
+        LazyHighlight::default()
+    }
+
+    /// The ranges `pattern` highlights in `text`, or `None` when it does
+    /// not match. Only the first call matches; later ones return its
+    /// ranges, so every call must pass the row's own pattern and text.
+    pub fn get(
+        &self,
+        pattern: &Pattern,
+        matcher: &mut Matcher,
+        text: &str,
+    ) -> Option<&[Range<usize>]> {
+        self.ranges
+            .get_or_init(|| highlight(pattern, matcher, text))
+            .as_deref()
+    }
+
+    /// Whether the ranges have been computed yet.
+    pub fn is_computed(&self) -> bool {
+        self.ranges.get().is_some()
+    }
+}
@@ -0,0 +1,127 @@
+//! Score distributions, for putting backends' scores on a common scale.
+//!
+//! Every backend scores on its own scale: nucleo in unbounded integer
+//! points, FuzzyMatch between 0 and 1, RapidFuzz in percent. A threshold
+//! such as "hide results scoring under 0.3" means nothing for another
+//! backend until its scores are mapped onto a shared scale, and fitting
+//! that mapping needs each backend's distribution of scores. A
+//! [`ScoreHistogram`] counts every match's score in fixed-width bins, and
+//! separately the scores of the matches that were the expected instrument,
+//! so a calibration curve can be fitted from the share of relevant matches
+//! per bin, or scores compared by [`ScoreHistogram::percentile_rank`].
+
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+
+/// Counts of scores in bins of equal width. Only bins with a score are
+/// stored, so the scale does not need to be known in advance.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScoreHistogram {
+    bin_width: f64,
+    /// Bin index (lower edge over the width) to all and relevant counts.
+    bins: BTreeMap<i64, (u64, u64)>,
+}
+
+/// One non-empty bin, covering scores from `lower` up to `upper`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Bin {
+    pub lower: f64,
+    pub upper: f64,
+    /// Matches scoring in the bin.
+    pub count: u64,
+    /// Of those, matches that were the expected instrument.
+    pub relevant: u64,
+}
+
+impl ScoreHistogram {
+    /// An empty histogram; `bin_width` must be positive.
+    pub fn new(bin_width: f64) -> ScoreHistogram {
+        assert!(bin_width > 0.0, "bin width must be positive");
+        ScoreHistogram {
+            bin_width,
+            bins: BTreeMap::new(),
+        }
+    }
+
+    pub fn bin_width(&self) -> f64 {
+        self.bin_width
+    }
+
+    /// Counts one match's score.
+    pub fn add(&mut self, score: f64, relevant: bool) {
+        let bin = self
+            .bins
+            .entry((score / self.bin_width).floor() as i64)
+            .or_default();
+        bin.0 += 1;
+        bin.1 += relevant as u64;
+    }
+
+    /// Adds another histogram's counts; the bin widths must match.
+    pub fn merge(&mut self, other: &ScoreHistogram) {
+        assert_eq!(self.bin_width, other.bin_width, "bin widths differ");
+        for (&index, &(count, relevant)) in &other.bins {
+            let bin = self.bins.entry(index).or_default();
+            bin.0 += count;
+            bin.1 += relevant;
+        }
+    }
+
+    /// Scores counted.
+    pub fn total(&self) -> u64 {
+        self.bins.values().map(|&(count, _)| count).sum()
+    }
+
+    /// The non-empty bins, lowest first.
+    pub fn bins(&self) -> impl Iterator<Item = Bin> + '_ {
+        self.bins.iter().map(|(&index, &(count, relevant))| Bin {
+            lower: index as f64 * self.bin_width,
+            upper: (index + 1) as f64 * self.bin_width,
+            count,
+            relevant,
+        })
+    }
+
+    /// Share of counted scores in bins below the one holding `score`,
+    /// from 0 to 1: the score's position on a scale every backend shares.
+    /// `None` for an empty histogram.
+    pub fn percentile_rank(&self, score: f64) -> Option<f64> {
+        let total = self.total();
+        if total == 0 {
+            return None;
+        }
+        let index = (score / self.bin_width).floor() as i64;
+        let below: u64 = self.bins.range(..index).map(|(_, &(count, _))| count).sum();
+        Some(below as f64 / total as f64)
+    }
+
+    /// The width and non-empty bins, as `[index, count, relevant]` triples,
+    /// for saving a histogram part way through a run.
+    pub fn to_json(&self) -> Value {
+        json!({
+            "bin_width": self.bin_width,
+            "bins": self
+                .bins
+                .iter()
+                .map(|(&index, &(count, relevant))| json!([index, count, relevant]))
+                .collect::<Vec<_>>(),
+        })
+    }
+
+    pub fn from_json(value: &Value) -> Option<ScoreHistogram> {
+        let bin_width = value.get("bin_width")?.as_f64().filter(|w| *w > 0.0)?;
+        let bins = value
+            .get("bins")?
+            .as_array()?
+            .iter()
+            .map(|bin| {
+                let bin = bin.as_array()?;
+                Some((
+                    bin.first()?.as_i64()?,
+                    (bin.get(1)?.as_u64()?, bin.get(2)?.as_u64()?),
+                ))
+            })
+            .collect::<Option<_>>()?;
+        Some(ScoreHistogram { bin_width, bins })
+    }
+}
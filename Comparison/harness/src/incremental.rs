@@ -0,0 +1,115 @@
+//! Incremental runs: re-time only the queries that changed since a
+//! baseline run and carry the baseline's results over for the rest.
+//!
+//! Queries are matched on text and field. One whose category, expected name
+//! and frequency also match, and that the baseline timed, is unchanged and
+//! keeps its recorded result. One with the same text and field but different
+//! columns is changed, one the baseline never saw is added, and both are run
+//! again. Baseline queries no longer in the set are dropped.
+//!
+//! Reusing a result is only sound when the baseline matched the same corpus
+//! with the same settings; [`Incremental::against`] leaves that check to the
+//! caller, which knows what the current run's fingerprint and corpus are.
+
+use crate::queries::Query;
+use crate::results::{QueryResult, ResultSet};
+use std::collections::HashMap;
+
+/// How a query set differs from a baseline run, and the baseline results
+/// that can be reused.
+pub struct Incremental {
+    /// Queries to run again, in query-set order: added and changed ones.
+    pub stale: Vec<Query>,
+    pub added: usize,
+    pub changed: usize,
+    /// Baseline queries missing from the current set.
+    pub removed: usize,
+    /// The baseline result for each query in the current set, or `None`
+    /// where it is stale.
+    cached: Vec<Option<QueryResult>>,
+}
+
+impl Incremental {
+    /// Diffs `queries` against what `baseline` recorded.
+    pub fn against(baseline: &ResultSet, queries: &[Query]) -> Incremental {
+        let recorded: HashMap<(&str, &str), &QueryResult> = baseline
+            .queries
+            .iter()
+            .map(|r| ((r.query.as_str(), r.field.as_str()), r))
+            .collect();
+        let mut incremental = Incremental {
+            stale: Vec::new(),
+            added: 0,
+            changed: 0,
+            removed: 0,
+            cached: Vec::with_capacity(queries.len()),
+        };
+        for q in queries {
+            match recorded.get(&(q.text.as_str(), q.field.as_str())) {
+                Some(r) if unchanged(r, q) => incremental.cached.push(Some((*r).clone())),
+                Some(_) => {
+                    incremental.changed += 1;
+                    incremental.stale.push(q.clone());
+                    incremental.cached.push(None);
+                }
+                None => {
+                    incremental.added += 1;
+                    incremental.stale.push(q.clone());
+                    incremental.cached.push(None);
+                }
+            }
+        }
+        incremental.removed = recorded
+            .keys()
+            .filter(|(text, field)| !queries.iter().any(|q| q.text == *text && q.field == *field))
+            .count();
+        incremental
+    }
+
+    /// Queries whose baseline result is reused.
+    pub fn unchanged(&self) -> usize {
+        self.cached.len() - self.stale.len()
+    }
+
+    /// One line describing the diff, for the run log.
+    pub fn summary(&self) -> String {
+        format!(
+            "{} unchanged, {} added, {} changed, {} removed",
+            self.unchanged(),
+            self.added,
+            self.changed,
+            self.removed
+        )
+    }
+
+    /// Results for the whole current query set, in its order: the reused
+    /// baseline results with `fresh`, one per [`stale`](Self::stale) query
+    /// in the same order, filling the gaps.
+    ///
+    /// # Panics
+    ///
+    /// If `fresh` holds a different number of results than there are stale
+    /// queries.
+    pub fn merge(&self, fresh: Vec<QueryResult>) -> Vec<QueryResult> {
+        assert_eq!(
+            fresh.len(),
+            self.stale.len(),
+            "one fresh result is needed per stale query"
+        );
+        let mut fresh = fresh.into_iter();
+        self.cached
+            .iter()
+            .map(|cached| match cached {
+                Some(result) => result.clone(),
+                None => fresh.next().unwrap(),
+            })
+            .collect()
+    }
+}
+
+fn unchanged(recorded: &QueryResult, query: &Query) -> bool {
+    recorded.median_ms.is_some()
+        && recorded.category == query.category
+        && recorded.expected_name == query.expected_name
+        && recorded.frequency == query.frequency
+}
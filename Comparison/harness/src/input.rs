@@ -0,0 +1,107 @@
+//! Transparent decompression of input files.
+//!
+//! Corpus exports are shipped as `.tsv.gz` or `.tsv.zst`. The format is
+//! detected from the file's magic bytes, so a misnamed file still loads and
+//! uncompressed files pass straight through.
+//!
+//! Files saved by Windows tools are normalized here too, so no loader has
+//! to know about them. Excel's "CSV UTF-8" and Notepad prefix a UTF-8 byte
+//! order mark, which [`open`] drops. Excel's "Unicode Text" is UTF-16 with a
+//! byte order mark, which [`open`] transcodes to UTF-8. Paths written on
+//! Windows go through [`native_path`].
+
+use flate2::read::MultiGzDecoder;
+use ruzstd::decoding::StreamingDecoder;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Cursor, Read};
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+const UTF8_BOM: [u8; 3] = [0xef, 0xbb, 0xbf];
+const UTF16LE_BOM: [u8; 2] = [0xff, 0xfe];
+const UTF16BE_BOM: [u8; 2] = [0xfe, 0xff];
+
+/// Compression formats recognised by [`open`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    /// Identifies the format from the first bytes of a file.
+    pub fn detect(header: &[u8]) -> Compression {
+        if header.starts_with(&GZIP_MAGIC) {
+            Compression::Gzip
+        } else if header.starts_with(&ZSTD_MAGIC) {
+            Compression::Zstd
+        } else {
+            Compression::None
+        }
+    }
+}
+
+/// Opens `path` for buffered reading, decompressing gzip and zstd on the fly
+/// and dropping or transcoding a byte order mark (see [`unmark`]).
+pub fn open(path: &str) -> io::Result<Box<dyn BufRead>> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let reader: Box<dyn BufRead> = match Compression::detect(reader.fill_buf()?) {
+        Compression::None => Box::new(reader),
+        Compression::Gzip => Box::new(BufReader::new(MultiGzDecoder::new(reader))),
+        Compression::Zstd => {
+            let decoder = StreamingDecoder::new(reader)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+            Box::new(BufReader::new(decoder))
+        }
+    };
+    unmark(reader)
+}
+
+/// Drops a UTF-8 byte order mark from the front of `reader`. Text behind a
+/// UTF-16 mark is read in full and transcoded to UTF-8; an unpaired
+/// surrogate in it is an [`io::ErrorKind::InvalidData`] error.
+pub fn unmark(mut reader: Box<dyn BufRead>) -> io::Result<Box<dyn BufRead>> {
+    let head = reader.fill_buf()?;
+    if head.starts_with(&UTF8_BOM) {
+        reader.consume(UTF8_BOM.len());
+        return Ok(reader);
+    }
+    let from_bytes = if head.starts_with(&UTF16LE_BOM) {
+        u16::from_le_bytes
+    } else if head.starts_with(&UTF16BE_BOM) {
+        u16::from_be_bytes
+    } else {
+        return Ok(reader);
+    };
+    reader.consume(2);
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes)?;
+    let units = bytes
+        .chunks_exact(2)
+        .map(|pair| from_bytes([pair[0], pair[1]]));
+    let text: String = char::decode_utf16(units)
+        .collect::<Result<_, _>>()
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+    Ok(Box::new(Cursor::new(text.into_bytes())))
+}
+
+/// `path` as written in a list or config file, with Windows separators
+/// made native. Corpora lists and campaign files written on Windows name
+/// files as `data\nasdaq.tsv`; off Windows each backslash becomes a `/`,
+/// so the file resolves the same everywhere. On Windows both separators
+/// already work and the path is returned as is.
+pub fn native_path(path: &str) -> String {
+    if cfg!(windows) {
+        path.to_string()
+    } else {
+        path.replace('\\', "/")
+    }
+}
+
+/// Reads the whole (possibly compressed) file at `path` as UTF-8.
+pub fn read_to_string(path: &str) -> io::Result<String> {
+    let mut content = String::new();
+    open(path)?.read_to_string(&mut content)?;
+    Ok(content)
+}
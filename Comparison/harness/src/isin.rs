@@ -0,0 +1,51 @@
+//! ISIN validation (ISO 6166).
+//!
+//! An ISIN is a two-letter country code, a nine-character alphanumeric
+//! security identifier and a Luhn check digit computed over the letters
+//! expanded to two-digit numbers (`A` = 10 … `Z` = 35).
+
+/// Returns `true` for a well-formed ISIN with a correct check digit.
+pub fn is_valid(isin: &str) -> bool {
+    let bytes = isin.as_bytes();
+    bytes.len() == 12
+        && bytes[..2].iter().all(u8::is_ascii_uppercase)
+        && bytes[2..11]
+            .iter()
+            .all(|b| b.is_ascii_uppercase() || b.is_ascii_digit())
+        && bytes[11].is_ascii_digit()
+        && check_digit(&isin[..11]) == Some(bytes[11] - b'0')
+}
+
+/// Computes the check digit for the first eleven characters of an ISIN, or
+/// `None` if `body` contains anything other than digits and uppercase ASCII
+/// letters.
+pub fn check_digit(body: &str) -> Option<u8> {
+    let mut digits: Vec<u32> = Vec::with_capacity(body.len() * 2);
+    for c in body.chars() {
+        match c {
+            '0'..='9' => digits.push(c as u32 - '0' as u32),
+            'A'..='Z' => {
+                let value = c as u32 - 'A' as u32 + 10;
+                digits.push(value / 10);
+                digits.push(value % 10);
+            }
+            _ => return None,
+        }
+    }
+    // Luhn: double every second digit starting from the rightmost one, since
+    // the check digit will be appended to the right.
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &d)| {
+            if i % 2 == 0 {
+                let doubled = d * 2;
+                doubled / 10 + doubled % 10
+            } else {
+                d
+            }
+        })
+        .sum();
+    Some(((10 - sum % 10) % 10) as u8)
+}
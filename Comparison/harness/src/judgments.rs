@@ -0,0 +1,85 @@
+//! Relevance judgments for queries.
+//!
+//! A judgments file has no header and uses the columns
+//! `query \t field \t symbol \t name \t isin \t relevance`, one row per
+//! (query, relevant instrument) pair. Instruments are identified by all three
+//! fields because none of them is unique on its own in the export.
+//!
+//! Relevance is graded from 0 to [`MAX_GRADE`], named in [`GRADES`]. Many
+//! instrument searches have results that are acceptable but not ideal, such
+//! as a fund's other share class or a delisted predecessor, and a grade
+//! lets NDCG reward them less than the instrument meant without calling
+//! them wrong. Binary files with only `0` and `1` are valid graded files:
+//! NDCG is normalized per query, so they score as they always did.
+
+use crate::{input, tsv};
+use std::borrow::Cow;
+use std::io::{self, Write};
+
+/// One judged (query, instrument) pair.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Judgment {
+    pub query: String,
+    pub field: String,
+    pub symbol: String,
+    pub name: String,
+    pub isin: String,
+    /// Grade from `0`, judged not relevant, to [`MAX_GRADE`], the ideal
+    /// result.
+    pub relevance: u8,
+}
+
+/// The highest relevance grade.
+pub const MAX_GRADE: u8 = 3;
+
+/// Name of each grade, indexed by grade.
+pub const GRADES: [&str; MAX_GRADE as usize + 1] = ["irrelevant", "acceptable", "good", "ideal"];
+
+/// Loads a judgments TSV. Lines with fewer than six columns are skipped, and
+/// a relevance that is not a grade is an error.
+pub fn load_judgments(path: &str) -> io::Result<Vec<Judgment>> {
+    let content = input::read_to_string(path)?;
+    let mut judgments = Vec::new();
+    for (i, line) in content.lines().enumerate() {
+        let cols: Vec<Cow<str>> = tsv::fields(line).collect();
+        if cols.len() < 6 {
+            continue;
+        }
+        let relevance = cols[5]
+            .trim()
+            .parse()
+            .map_err(|e| format!("{e}"))
+            .and_then(|grade: u8| {
+                (grade <= MAX_GRADE)
+                    .then_some(grade)
+                    .ok_or_else(|| format!("grades run from 0 to {MAX_GRADE}"))
+            })
+            .map_err(|e| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("line {}: relevance {:?}: {e}", i + 1, cols[5]),
+                )
+            })?;
+        judgments.push(Judgment {
+            query: cols[0].to_string(),
+            field: cols[1].to_string(),
+            symbol: cols[2].to_string(),
+            name: cols[3].to_string(),
+            isin: cols[4].to_string(),
+            relevance,
+        });
+    }
+    Ok(judgments)
+}
+
+/// Writes judgments in the six-column format.
+pub fn write_judgments<W: Write>(out: &mut W, judgments: &[Judgment]) -> io::Result<()> {
+    for j in judgments {
+        writeln!(
+            out,
+            "{}\t{}\t{}\t{}\t{}\t{}",
+            j.query, j.field, j.symbol, j.name, j.isin, j.relevance
+        )?;
+    }
+    Ok(())
+}
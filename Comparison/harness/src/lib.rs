@@ -0,0 +1,78 @@
+//! Shared building blocks for the Rust comparison harnesses.
+//!
+//! Every binary in the comparison workspace reads the same instrument corpus
+//! and query set, so loading, argument handling and seeded randomness live
+//! here instead of being copied into each `main.rs`.
+
+pub mod adversarial;
+pub mod affinity;
+pub mod anonymize;
+pub mod budget;
+pub mod cache;
+pub mod capabilities;
+pub mod classify;
+pub mod cli;
+pub mod corpus;
+pub mod cost;
+pub mod dedup;
+pub mod difficulty;
+pub mod display;
+pub mod encoding;
+pub mod evaluation;
+pub mod experiment;
+pub mod filter;
+pub mod fingerprint;
+pub mod frequency;
+pub mod heap;
+pub mod highlight;
+pub mod histogram;
+pub mod incremental;
+pub mod input;
+pub mod isin;
+pub mod judgments;
+pub mod limits;
+pub mod logging;
+pub mod manifest;
+pub mod memory;
+pub mod metrics;
+pub mod mutable;
+pub mod near_duplicates;
+pub mod parallel;
+pub mod perturb;
+pub mod prefilter;
+pub mod prepared;
+pub mod preset;
+pub mod queries;
+pub mod registry;
+pub mod replay;
+pub mod results;
+pub mod rng;
+pub mod sample;
+pub mod schedule;
+pub mod scratch;
+pub mod significance;
+pub mod split;
+#[cfg(feature = "store")]
+pub mod store;
+pub mod strata;
+pub mod subprocess;
+pub mod telemetry;
+pub mod tolerance;
+pub mod topk;
+pub mod tsv;
+pub mod typing;
+pub mod typos;
+pub mod warmup;
+pub mod window;
+
+use std::path::PathBuf;
+
+/// Path to a file in the repository's `Resources/` directory.
+pub fn resource_path(file_name: &str) -> String {
+    let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    manifest_dir
+        .join("../../Resources")
+        .join(file_name)
+        .to_string_lossy()
+        .to_string()
+}
@@ -0,0 +1,98 @@
+//! Time and memory limits on one benchmark run, so a backend that hangs or
+//! grows without bound is stopped and reported instead of stalling or
+//! killing everything scheduled after it.
+//!
+//! A run checks its limits between queries with [`Limits::check`], so a
+//! single query that never returns is not caught, and the memory read is
+//! the whole process's resident set, which includes whatever it loaded
+//! before the run began.
+
+use crate::memory;
+use serde_json::{json, Value};
+use std::fmt;
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Limits {
+    /// Wall-clock time the run may take, from its start.
+    pub time: Option<Duration>,
+    /// Resident set the process may reach, in bytes.
+    pub memory_bytes: Option<u64>,
+}
+
+/// The limit a run went past.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Exceeded {
+    Time { limit: Duration },
+    Memory { limit: u64, resident: u64 },
+}
+
+impl Exceeded {
+    pub fn to_json(&self) -> Value {
+        match self {
+            Exceeded::Time { limit } => json!({ "time_limit_ms": limit.as_millis() as u64 }),
+            Exceeded::Memory { limit, resident } => {
+                json!({ "memory_limit_bytes": limit, "resident_bytes": resident })
+            }
+        }
+    }
+
+    pub fn from_json(value: &Value) -> Option<Exceeded> {
+        let number = |key: &str| value.get(key).and_then(Value::as_u64);
+        match number("time_limit_ms") {
+            Some(ms) => Some(Exceeded::Time {
+                limit: Duration::from_millis(ms),
+            }),
+            None => Some(Exceeded::Memory {
+                limit: number("memory_limit_bytes")?,
+                resident: number("resident_bytes")?,
+            }),
+        }
+    }
+}
+
+impl fmt::Display for Exceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Exceeded::Time { limit } => {
+                write!(f, "ran past its {}s time limit", limit.as_secs_f64())
+            }
+            Exceeded::Memory { limit, resident } => write!(
+                f,
+                "reached {} MiB resident, past its {} MiB memory limit",
+                resident >> 20,
+                limit >> 20
+            ),
+        }
+    }
+}
+
+impl Limits {
+    pub fn is_unlimited(&self) -> bool {
+        self.time.is_none() && self.memory_bytes.is_none()
+    }
+
+    /// The limit a run that has taken `elapsed` with `resident` bytes
+    /// resident has gone past, if any; an unknown resident set passes.
+    pub fn exceeded(&self, elapsed: Duration, resident: Option<u64>) -> Option<Exceeded> {
+        if let Some(limit) = self.time.filter(|limit| elapsed > *limit) {
+            return Some(Exceeded::Time { limit });
+        }
+        match (self.memory_bytes, resident) {
+            (Some(limit), Some(resident)) if resident > limit => {
+                Some(Exceeded::Memory { limit, resident })
+            }
+            _ => None,
+        }
+    }
+
+    /// Checks a run that began at `start`, reading the resident set only
+    /// when memory is limited.
+    pub fn check(&self, start: Instant) -> Result<(), Exceeded> {
+        let resident = self.memory_bytes.and_then(|_| memory::resident_bytes());
+        match self.exceeded(start.elapsed(), resident) {
+            Some(exceeded) => Err(exceeded),
+            None => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,75 @@
+//! Structured logs of a run, for auditing runs on shared machines.
+//!
+//! The harnesses' printed tables summarise a run; a log records what it did
+//! as it did it. Binaries open a `tracing` span per corpus or scenario and
+//! per query, with the query's text, field, category and backend as span
+//! fields, and log each query's duration inside it. [`init`] installs a
+//! subscriber for `--log-format text|json`, writing to stderr or, with
+//! `--log-file PATH`, appending to a file, so logs of many runs on one
+//! machine accumulate in one place. JSON lines carry the enclosing spans'
+//! fields, so each line can be filtered on its own.
+//!
+//! Without `--log-format` nothing is installed and the spans cost next to
+//! nothing, so the printed output of a run is unchanged.
+
+use std::fs::OpenOptions;
+use std::io::IsTerminal;
+use std::str::FromStr;
+use std::sync::Mutex;
+use tracing_subscriber::fmt;
+
+/// How log lines are written.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LogFormat {
+    /// One human-readable line per event.
+    Text,
+    /// One JSON object per event, with its spans' fields.
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(LogFormat::Text),
+            "json" => Ok(LogFormat::Json),
+            other => Err(format!(
+                "unknown log format `{other}` (supported: text, json)"
+            )),
+        }
+    }
+}
+
+/// Installs the subscriber `--log-format` and `--log-file` ask for, and
+/// returns the format, or `None` when logging was not asked for.
+///
+/// Fails on an unknown format, a file that cannot be opened, or when a
+/// subscriber is already installed.
+pub fn init(args: &[String]) -> Result<Option<LogFormat>, String> {
+    let Some(format) = crate::cli::flag_value(args, "--log-format") else {
+        return Ok(None);
+    };
+    let format: LogFormat = format.parse()?;
+    let file = match crate::cli::flag_value(args, "--log-file") {
+        Some(path) => Some(
+            OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .map_err(|e| format!("cannot open log file {path}: {e}"))?,
+        ),
+        None => None,
+    };
+
+    let ansi = file.is_none() && std::io::stderr().is_terminal();
+    let builder = fmt().with_target(false).with_ansi(ansi);
+    let installed = match (format, file) {
+        (LogFormat::Text, None) => builder.with_writer(std::io::stderr).try_init(),
+        (LogFormat::Text, Some(file)) => builder.with_writer(Mutex::new(file)).try_init(),
+        (LogFormat::Json, None) => builder.json().with_writer(std::io::stderr).try_init(),
+        (LogFormat::Json, Some(file)) => builder.json().with_writer(Mutex::new(file)).try_init(),
+    };
+    installed.map_err(|e| format!("cannot install logger: {e}"))?;
+    Ok(Some(format))
+}
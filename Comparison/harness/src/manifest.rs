@@ -0,0 +1,228 @@
+//! Dataset manifests.
+//!
+//! A generated corpus or query set is accompanied by a JSON sidecar,
+//! `<file>.manifest.json`, recording its row count, SHA-256, schema, and the
+//! tool and seed that produced it. Harnesses verify the sidecar before a run,
+//! so two results claiming the same dataset can be checked to really share
+//! one.
+
+use crate::input;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::fs::{self, File};
+use std::io::{self, BufRead, Read};
+use std::path::Path;
+
+/// Layout of a dataset file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Schema {
+    pub format: String,
+    /// Whether the first line is a header rather than a row.
+    pub header: bool,
+    pub columns: Vec<String>,
+}
+
+impl Schema {
+    /// Instrument corpora as written by the generators.
+    pub fn instruments() -> Schema {
+        Schema {
+            format: "tsv".to_string(),
+            header: true,
+            columns: vec!["Symbol".into(), "Name".into(), "ISIN".into()],
+        }
+    }
+
+    /// A corpus carrying metadata columns after the three instrument
+    /// fields.
+    pub fn instruments_with_metadata(metadata_columns: &[String]) -> Schema {
+        let mut schema = Schema::instruments();
+        schema.columns.extend(metadata_columns.iter().cloned());
+        schema
+    }
+
+    /// `queries.tsv`.
+    pub fn queries() -> Schema {
+        Schema {
+            format: "tsv".to_string(),
+            header: false,
+            columns: ["query", "field", "category", "expected_name", "frequency"]
+                .map(String::from)
+                .to_vec(),
+        }
+    }
+
+    /// Judgments files (see [`crate::judgments`]).
+    pub fn judgments() -> Schema {
+        Schema {
+            format: "tsv".to_string(),
+            header: false,
+            columns: ["query", "field", "symbol", "name", "isin", "relevance"]
+                .map(String::from)
+                .to_vec(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Manifest {
+    /// Tool that wrote the file.
+    pub generator: String,
+    pub seed: Option<u64>,
+    pub schema: Schema,
+    /// Data rows, excluding any header and blank lines.
+    pub rows: usize,
+    /// Hex SHA-256 of the file bytes as stored (compressed, if it is).
+    pub sha256: String,
+}
+
+/// Sidecar manifest path for a data file.
+pub fn manifest_path(data_path: &str) -> String {
+    format!("{data_path}.manifest.json")
+}
+
+impl Manifest {
+    /// Describes the file at `data_path` as it is now.
+    pub fn describe(
+        data_path: &str,
+        generator: &str,
+        seed: Option<u64>,
+        schema: Schema,
+    ) -> io::Result<Manifest> {
+        Ok(Manifest {
+            generator: generator.to_string(),
+            seed,
+            rows: count_rows(data_path, schema.header)?,
+            sha256: sha256_file(data_path)?,
+            schema,
+        })
+    }
+
+    /// Describes `data_path` and writes its sidecar manifest.
+    pub fn write_for(
+        data_path: &str,
+        generator: &str,
+        seed: Option<u64>,
+        schema: Schema,
+    ) -> io::Result<Manifest> {
+        let manifest = Manifest::describe(data_path, generator, seed, schema)?;
+        let text = serde_json::to_string_pretty(&manifest.to_json())?;
+        fs::write(manifest_path(data_path), text + "\n")?;
+        Ok(manifest)
+    }
+
+    /// Reads the sidecar manifest of `data_path`, if there is one.
+    pub fn read_for(data_path: &str) -> io::Result<Option<Manifest>> {
+        let path = manifest_path(data_path);
+        if !Path::new(&path).exists() {
+            return Ok(None);
+        }
+        let value: Value = serde_json::from_str(&fs::read_to_string(&path)?)?;
+        Manifest::from_json(&value)
+            .map(Some)
+            .ok_or_else(|| invalid(format!("{path}: not a dataset manifest")))
+    }
+
+    /// One-line description for harness output.
+    pub fn summary(&self) -> String {
+        let seed = self.seed.map(|s| format!(", seed {s}")).unwrap_or_default();
+        format!(
+            "{} rows, sha256 {}..., written by {}{seed}",
+            self.rows,
+            &self.sha256[..12.min(self.sha256.len())],
+            self.generator
+        )
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "generator": self.generator,
+            "seed": self.seed,
+            "schema": {
+                "format": self.schema.format,
+                "header": self.schema.header,
+                "columns": self.schema.columns,
+            },
+            "rows": self.rows,
+            "sha256": self.sha256,
+        })
+    }
+
+    fn from_json(value: &Value) -> Option<Manifest> {
+        let schema = value.get("schema")?;
+        Some(Manifest {
+            generator: value.get("generator")?.as_str()?.to_string(),
+            seed: value.get("seed").and_then(Value::as_u64),
+            schema: Schema {
+                format: schema.get("format")?.as_str()?.to_string(),
+                header: schema.get("header")?.as_bool()?,
+                columns: schema
+                    .get("columns")?
+                    .as_array()?
+                    .iter()
+                    .map(|c| c.as_str().map(String::from))
+                    .collect::<Option<_>>()?,
+            },
+            rows: value.get("rows")?.as_u64()? as usize,
+            sha256: value.get("sha256")?.as_str()?.to_string(),
+        })
+    }
+}
+
+/// Checks `data_path` against its sidecar manifest.
+///
+/// Returns `Ok(None)` when there is no manifest and the verified manifest
+/// otherwise. A checksum or row count mismatch is an
+/// [`io::ErrorKind::InvalidData`] error.
+pub fn verify(data_path: &str) -> io::Result<Option<Manifest>> {
+    let Some(manifest) = Manifest::read_for(data_path)? else {
+        return Ok(None);
+    };
+    let sha256 = sha256_file(data_path)?;
+    if sha256 != manifest.sha256 {
+        return Err(invalid(format!(
+            "{data_path}: sha256 {sha256} does not match manifest {}",
+            manifest.sha256
+        )));
+    }
+    let rows = count_rows(data_path, manifest.schema.header)?;
+    if rows != manifest.rows {
+        return Err(invalid(format!(
+            "{data_path}: {rows} rows, manifest says {}",
+            manifest.rows
+        )));
+    }
+    Ok(Some(manifest))
+}
+
+/// Hex SHA-256 of a file's raw bytes.
+pub fn sha256_file(path: &str) -> io::Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; 64 * 1024];
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect())
+}
+
+fn count_rows(path: &str, header: bool) -> io::Result<usize> {
+    let mut rows: usize = 0;
+    for line in input::open(path)?.lines() {
+        if !line?.trim().is_empty() {
+            rows += 1;
+        }
+    }
+    Ok(if header { rows.saturating_sub(1) } else { rows })
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
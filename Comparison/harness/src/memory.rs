@@ -0,0 +1,85 @@
+//! Measuring the memory a data structure holds.
+//!
+//! A binary that installs [`CountingAlloc`] as its global allocator keeps a
+//! running count of live heap bytes, so the heap a structure holds is the
+//! change in [`live_bytes`] across building it: exact, and unaffected by
+//! what the allocator does with freed memory. [`resident_bytes`] is the
+//! operating system's view instead, the pages the process has resident,
+//! which also counts memory the allocator kept after a free and pages
+//! touched while building. The heap figure says how big a representation
+//! is; the resident one says what a device has to spare for it.
+//!
+//! The resident set is read from `/proc/self/status`, so it is only known
+//! on Linux.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fs;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static LIVE: AtomicUsize = AtomicUsize::new(0);
+
+/// The system allocator, counting the bytes it has handed out and not yet
+/// had back.
+pub struct CountingAlloc;
+
+// SAFETY: every call is forwarded to `System` unchanged; only the counter
+// is updated alongside
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            LIVE.fetch_add(layout.size(), Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            LIVE.fetch_add(layout.size(), Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        LIVE.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new = System.realloc(ptr, layout, new_size);
+        if !new.is_null() {
+            LIVE.fetch_add(new_size, Ordering::Relaxed);
+            LIVE.fetch_sub(layout.size(), Ordering::Relaxed);
+        }
+        new
+    }
+}
+
+/// Heap bytes currently allocated through [`CountingAlloc`]; always 0 when
+/// it is not the global allocator.
+pub fn live_bytes() -> usize {
+    LIVE.load(Ordering::Relaxed)
+}
+
+/// The process's resident set in bytes, where the system reports it.
+pub fn resident_bytes() -> Option<u64> {
+    parse_vm_rss(&fs::read_to_string("/proc/self/status").ok()?)
+}
+
+/// The `VmRSS` line of a `/proc/<pid>/status` file, in bytes.
+pub fn parse_vm_rss(status: &str) -> Option<u64> {
+    status_bytes(status, "VmRSS:")
+}
+
+/// The `VmHWM` line of a `/proc/<pid>/status` file, the peak resident set
+/// since the process last called `exec`, in bytes.
+pub fn parse_vm_hwm(status: &str) -> Option<u64> {
+    status_bytes(status, "VmHWM:")
+}
+
+fn status_bytes(status: &str, key: &str) -> Option<u64> {
+    let line = status.lines().find_map(|l| l.strip_prefix(key))?;
+    let kib: u64 = line.trim().strip_suffix("kB")?.trim().parse().ok()?;
+    Some(kib * 1024)
+}
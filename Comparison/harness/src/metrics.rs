@@ -0,0 +1,123 @@
+//! Ranking-quality and latency metrics over recorded results.
+//!
+//! NDCG compares a result list's discounted gain with that of the ideal
+//! ordering, so 1.0 means the best possible ranking and 0.0 that nothing
+//! relevant was returned in the cutoff. Gains come from a judgments file
+//! where one covers the query, and otherwise from the query's expected name,
+//! which makes the first result containing it the single relevant one.
+//!
+//! Judgment grades are the gains, so a result graded 3 counts three times
+//! one graded 1. NDCG folds every grade into one number; [`GradeConfusion`]
+//! keeps them apart, counting where each grade's judged results landed.
+
+use crate::judgments::{Judgment, MAX_GRADE};
+use crate::queries::SKIP;
+use crate::results::QueryResult;
+
+/// Discounted cumulative gain of `gains` in rank order.
+pub fn dcg(gains: &[f64]) -> f64 {
+    gains
+        .iter()
+        .enumerate()
+        .map(|(i, gain)| gain / (i as f64 + 2.0).log2())
+        .sum()
+}
+
+/// NDCG of the first `k` of `gains`, against the best ordering of
+/// `relevant`. `None` when nothing is relevant, since any ranking is then
+/// as good as any other.
+pub fn ndcg(gains: &[f64], relevant: &[f64], k: usize) -> Option<f64> {
+    let mut ideal = relevant.to_vec();
+    ideal.sort_by(|a, b| b.partial_cmp(a).unwrap());
+    ideal.truncate(k);
+    let ideal = dcg(&ideal);
+    (ideal > 0.0).then(|| dcg(&gains[..gains.len().min(k)]) / ideal)
+}
+
+/// NDCG@`k` of a query's recorded results. `judged` are the judgments for
+/// this query, if any; without them the expected name stands in. `None`
+/// when the run recorded no results or the query has nothing to judge by.
+pub fn ndcg_at(result: &QueryResult, judged: &[&Judgment], k: usize) -> Option<f64> {
+    let hits = result.results.as_ref()?;
+    if !judged.is_empty() {
+        let gain = |symbol: &str, name: &str| {
+            judged
+                .iter()
+                .find(|j| j.symbol == symbol && j.name == name)
+                .map_or(0.0, |j| f64::from(j.relevance))
+        };
+        let gains: Vec<f64> = hits.iter().map(|h| gain(&h.symbol, &h.name)).collect();
+        let relevant: Vec<f64> = judged.iter().map(|j| f64::from(j.relevance)).collect();
+        return ndcg(&gains, &relevant, k);
+    }
+    if result.expected_name.is_empty() || result.expected_name == SKIP {
+        return None;
+    }
+    let expected = result.expected_name.to_lowercase();
+    let mut gains = vec![0.0; hits.len()];
+    if let Some(rank) = hits
+        .iter()
+        .position(|h| h.name.to_lowercase().contains(&expected))
+    {
+        gains[rank] = 1.0;
+    }
+    ndcg(&gains, &[1.0], k)
+}
+
+/// Where judged results were ranked, by grade, over any number of queries.
+///
+/// Each judged (query, instrument) pair lands in one column: ranked first,
+/// elsewhere in the top `k`, returned below the cutoff, or not returned.
+/// Ideal results that are rarely first while acceptable ones often are
+/// point at a ranking problem NDCG alone would only hint at.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GradeConfusion {
+    /// Per grade, judged results ranked first.
+    pub first: [usize; MAX_GRADE as usize + 1],
+    /// Per grade, judged results ranked 2 to `k`.
+    pub top_k: [usize; MAX_GRADE as usize + 1],
+    /// Per grade, judged results returned below the cutoff.
+    pub below_k: [usize; MAX_GRADE as usize + 1],
+    /// Per grade, judged results not returned at all.
+    pub missed: [usize; MAX_GRADE as usize + 1],
+}
+
+impl GradeConfusion {
+    /// Counts `judged`, the judgments of one query, against its recorded
+    /// results. Queries whose run recorded no results are not counted.
+    pub fn add(&mut self, result: &QueryResult, judged: &[&Judgment], k: usize) {
+        let Some(hits) = &result.results else {
+            return;
+        };
+        for j in judged {
+            let grade = usize::from(j.relevance.min(MAX_GRADE));
+            match hits
+                .iter()
+                .position(|h| h.symbol == j.symbol && h.name == j.name)
+            {
+                Some(0) if k > 0 => self.first[grade] += 1,
+                Some(rank) if rank < k => self.top_k[grade] += 1,
+                Some(_) => self.below_k[grade] += 1,
+                None => self.missed[grade] += 1,
+            }
+        }
+    }
+
+    /// Judged results of `grade`, wherever they landed.
+    pub fn judged(&self, grade: u8) -> usize {
+        let g = usize::from(grade);
+        self.first[g] + self.top_k[g] + self.below_k[g] + self.missed[g]
+    }
+}
+
+/// The `p`th percentile (0–100) of `values` by nearest rank, or `None` for
+/// no values.
+pub fn percentile(values: &[f64], p: f64) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    Some(sorted[rank.clamp(1, sorted.len()) - 1])
+}
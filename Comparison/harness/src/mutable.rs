@@ -0,0 +1,218 @@
+//! A prepared corpus that rows can be deleted from while it serves.
+//!
+//! Building the columns of a [`PreparedCorpus`] takes a pass over every row,
+//! which a server cannot afford each time an instrument is delisted. A
+//! [`MutableCorpus`] deletes softly instead: a deleted row gets a tombstone,
+//! scans skip rows with one, and the columns are left as they are, so a
+//! delisted instrument stops appearing in results as soon as it is deleted.
+//!
+//! Tombstoned rows still cost their place in every scan, so once they make
+//! up more than `compact_at` of the rows the corpus is compacted: the live
+//! rows are copied into a new corpus, keeping their line numbers and
+//! metadata, and its columns are built again. Compaction renumbers rows, so
+//! row indices held across a deletion are only valid while
+//! [`MutableCorpus::compactions`] is unchanged; line numbers stay valid.
+//!
+//! Every deletion that removes a row starts a new generation. An answer
+//! tagged with its [`Snapshot`], the corpus file's checksum and the
+//! generation it was computed against, can be traced back to the exact
+//! rows it saw, so a stale result reported by a user can be told apart
+//! from one that was right when it was given.
+
+use crate::corpus::Corpus;
+use crate::prepared::PreparedCorpus;
+use serde_json::{json, Value};
+use std::fmt;
+
+/// Default share of tombstoned rows past which the corpus is compacted.
+pub const COMPACT_AT: f64 = 0.25;
+
+/// Which state of which corpus an answer was computed against.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Snapshot {
+    /// SHA-256 of the file the corpus was loaded from, when known.
+    pub corpus_sha256: Option<String>,
+    /// Deletions that removed rows since it was loaded.
+    pub generation: u64,
+}
+
+impl Snapshot {
+    pub fn to_json(&self) -> Value {
+        json!({
+            "corpus_sha256": self.corpus_sha256,
+            "generation": self.generation,
+        })
+    }
+
+    pub fn from_json(value: &Value) -> Option<Snapshot> {
+        Some(Snapshot {
+            corpus_sha256: value
+                .get("corpus_sha256")
+                .and_then(Value::as_str)
+                .map(String::from),
+            generation: value.get("generation")?.as_u64()?,
+        })
+    }
+}
+
+/// `3f2a9c01d4e7@2`: the first 12 characters of the checksum, or `unknown`,
+/// and the generation.
+impl fmt::Display for Snapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.corpus_sha256 {
+            Some(sha) => write!(f, "{}@{}", &sha[..sha.len().min(12)], self.generation),
+            None => write!(f, "unknown@{}", self.generation),
+        }
+    }
+}
+
+pub struct MutableCorpus {
+    corpus: Corpus,
+    corpus_sha256: Option<String>,
+    prepared: PreparedCorpus,
+    /// Fields the columns were prepared for, to prepare them again.
+    fields: Vec<String>,
+    deleted: Vec<bool>,
+    tombstones: usize,
+    compact_at: f64,
+    compactions: usize,
+    generation: u64,
+}
+
+impl MutableCorpus {
+    /// Prepares `fields` of `corpus` on every core, to be compacted when
+    /// more than `compact_at` of its rows are tombstoned.
+    pub fn new(corpus: Corpus, fields: &[&str], compact_at: f64) -> MutableCorpus {
+        let prepared = PreparedCorpus::builder(&corpus)
+            .fields(fields.iter().copied())
+            .build();
+        MutableCorpus::with_prepared(corpus, prepared, fields, compact_at)
+    }
+
+    /// Serves columns prepared beforehand, such as a mapped index, which
+    /// must hold `fields` of `corpus` (see [`PreparedCorpus::check`]).
+    /// Compaction builds them again in memory.
+    pub fn with_prepared(
+        corpus: Corpus,
+        prepared: PreparedCorpus,
+        fields: &[&str],
+        compact_at: f64,
+    ) -> MutableCorpus {
+        MutableCorpus {
+            deleted: vec![false; corpus.len()],
+            corpus,
+            corpus_sha256: None,
+            prepared,
+            fields: fields.iter().map(|f| f.to_string()).collect(),
+            tombstones: 0,
+            compact_at,
+            compactions: 0,
+            generation: 0,
+        }
+    }
+
+    /// Records the checksum of the file the corpus was loaded from, for
+    /// its snapshots.
+    pub fn set_corpus_sha256(&mut self, sha256: Option<String>) {
+        self.corpus_sha256 = sha256;
+    }
+
+    /// The state answers computed now are computed against.
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            corpus_sha256: self.corpus_sha256.clone(),
+            generation: self.generation,
+        }
+    }
+
+    /// Every row, tombstoned ones included; check [`MutableCorpus::is_deleted`]
+    /// before using one.
+    pub fn corpus(&self) -> &Corpus {
+        &self.corpus
+    }
+
+    pub fn prepared(&self) -> &PreparedCorpus {
+        &self.prepared
+    }
+
+    pub fn is_deleted(&self, row: usize) -> bool {
+        self.deleted[row]
+    }
+
+    /// Rows without a tombstone, in corpus order.
+    pub fn live_rows(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.deleted.len()).filter(|&row| !self.deleted[row])
+    }
+
+    /// Rows without a tombstone.
+    pub fn len(&self) -> usize {
+        self.deleted.len() - self.tombstones
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Tombstoned rows not yet compacted away.
+    pub fn tombstones(&self) -> usize {
+        self.tombstones
+    }
+
+    /// Share of the rows scanned that are tombstoned.
+    pub fn tombstone_fraction(&self) -> f64 {
+        if self.deleted.is_empty() {
+            0.0
+        } else {
+            self.tombstones as f64 / self.deleted.len() as f64
+        }
+    }
+
+    /// Times the corpus has been compacted, and its rows renumbered.
+    pub fn compactions(&self) -> usize {
+        self.compactions
+    }
+
+    /// Tombstones every live row whose `field` is `value`, as
+    /// [`Corpus::text`] reads it, and compacts if that takes the tombstones
+    /// past the limit. Gives the rows deleted.
+    pub fn delete(&mut self, field: &str, value: &str) -> usize {
+        let mut deleted = 0;
+        for row in 0..self.corpus.len() {
+            if !self.deleted[row] && self.corpus.text(row, field) == value {
+                self.deleted[row] = true;
+                deleted += 1;
+            }
+        }
+        self.tombstones += deleted;
+        if deleted > 0 {
+            self.generation += 1;
+        }
+        if self.tombstone_fraction() > self.compact_at {
+            self.compact();
+        }
+        deleted
+    }
+
+    /// Drops the tombstoned rows and prepares the columns again.
+    pub fn compact(&mut self) {
+        if self.tombstones == 0 {
+            return;
+        }
+        let corpus = &self.corpus;
+        let mut live = Corpus::with_capacity(self.len(), 0);
+        live.set_metadata_columns(corpus.metadata_columns().to_vec());
+        for row in self.live_rows() {
+            let inst = corpus.get(row);
+            let metadata: Vec<&str> = corpus.metadata(row).values().collect();
+            live.push_with_metadata(inst.line, inst.symbol, inst.name, inst.isin, &metadata);
+        }
+        live.set_repairs(corpus.repairs().clone());
+        self.prepared = PreparedCorpus::builder(&live)
+            .fields(self.fields.iter().map(String::as_str))
+            .build();
+        self.deleted = vec![false; live.len()];
+        self.corpus = live;
+        self.tombstones = 0;
+        self.compactions += 1;
+    }
+}
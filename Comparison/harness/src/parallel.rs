@@ -0,0 +1,57 @@
+//! Scoring rows on several threads with the same results as one.
+//!
+//! Quality runs are gated by comparing their output with a snapshot byte
+//! for byte, so scoring a query on more threads must not change a line of
+//! it. [`score_rows`] gives each matcher one contiguous run of the rows and
+//! joins the runs' matches back in order, so it returns exactly the
+//! `(score, row)` list a single matcher would, in the same order, however
+//! the threads are scheduled. A row's score depends only on the pattern
+//! and its text: a nucleo [`Matcher`] carries nothing but scratch space
+//! between calls, so which matcher scored a row, and what it scored
+//! before, makes no difference. Ranking afterwards with
+//! [`Preset::rank`](crate::preset::Preset::rank) or
+//! [`TopK`](crate::topk::TopK) breaks ties by row, never by arrival, so
+//! the ranked output is identical too.
+
+use nucleo_matcher::pattern::Pattern;
+use nucleo_matcher::{Matcher, Utf32Str};
+use std::thread;
+
+/// Scores the text of each of `rows` against `pattern`, one contiguous run
+/// of rows per matcher, and returns the matches in the order of `rows`.
+/// With a single matcher it scores on the calling thread. `matchers` must
+/// not be empty.
+pub fn score_rows<'a>(
+    pattern: &Pattern,
+    rows: &[usize],
+    text: impl Fn(usize) -> &'a str + Sync,
+    matchers: &mut [Matcher],
+) -> Vec<(u32, usize)> {
+    assert!(!matchers.is_empty(), "scoring needs at least one matcher");
+    let score = |rows: &[usize], matcher: &mut Matcher| -> Vec<(u32, usize)> {
+        let mut buf = Vec::new();
+        rows.iter()
+            .filter_map(|&row| {
+                buf.clear();
+                let score = pattern.score(Utf32Str::new(text(row), &mut buf), matcher)?;
+                Some((score, row))
+            })
+            .collect()
+    };
+    if let [matcher] = matchers {
+        return score(rows, matcher);
+    }
+    let run = rows.len().div_ceil(matchers.len()).max(1);
+    let score = &score;
+    thread::scope(|scope| {
+        let handles: Vec<_> = rows
+            .chunks(run)
+            .zip(matchers.iter_mut())
+            .map(|(rows, matcher)| scope.spawn(move || score(rows, matcher)))
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().expect("Scoring thread panicked"))
+            .collect()
+    })
+}
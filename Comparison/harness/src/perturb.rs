@@ -0,0 +1,65 @@
+//! Trivial edits to a query that should not change its ranking.
+//!
+//! A user who types a trailing space, holds shift, or types a letter and
+//! deletes it expects the same results as before. When the top few change
+//! anyway the list seems to jump about for no reason. Each
+//! [`Perturbation`] lists the queries to scan in order, the last one's
+//! results being compared with the original query's. A harness that keeps
+//! state between scans is exercised by the retyped character, where the
+//! final query is the original itself.
+
+/// One kind of trivial edit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Perturbation {
+    /// A space typed after the query.
+    TrailingSpace,
+    /// Every letter's case swapped.
+    CaseFlip,
+    /// A character typed after the query and then deleted.
+    RetypedCharacter,
+}
+
+impl Perturbation {
+    pub const ALL: [Perturbation; 3] = [
+        Perturbation::TrailingSpace,
+        Perturbation::CaseFlip,
+        Perturbation::RetypedCharacter,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Perturbation::TrailingSpace => "trailing_space",
+            Perturbation::CaseFlip => "case_flip",
+            Perturbation::RetypedCharacter => "retyped_character",
+        }
+    }
+
+    /// The queries to scan, in order, to apply this edit to `query`; the
+    /// results of the last are the perturbed ranking. `None` when the edit
+    /// cannot apply, as flipping the case of a query without letters.
+    pub fn steps(self, query: &str) -> Option<Vec<String>> {
+        match self {
+            Perturbation::TrailingSpace => Some(vec![format!("{query} ")]),
+            Perturbation::CaseFlip => {
+                let flipped: String = query
+                    .chars()
+                    .flat_map(|c| {
+                        if c.is_lowercase() {
+                            c.to_uppercase().collect::<Vec<_>>()
+                        } else {
+                            c.to_lowercase().collect()
+                        }
+                    })
+                    .collect();
+                (flipped != query).then(|| vec![flipped])
+            }
+            Perturbation::RetypedCharacter => Some(vec![format!("{query}x"), query.to_string()]),
+        }
+    }
+}
+
+/// Whether the first `k` entries of two rankings are the same, in the same
+/// order.
+pub fn same_top<T: PartialEq>(original: &[T], perturbed: &[T], k: usize) -> bool {
+    original.iter().take(k).eq(perturbed.iter().take(k))
+}
@@ -0,0 +1,46 @@
+//! A character-set bitmask for rejecting candidates before scoring.
+//!
+//! The Swift matcher's second prefilter stage summarises a string as 37
+//! bits: one per ASCII letter (case-folded), one per digit, and one shared
+//! by everything else. A candidate lacking a letter or digit the query
+//! needs cannot contain it as a subsequence, so one AND and compare rejects
+//! it without touching the text. The Rust harnesses port it here so its
+//! cost and its effect on the scan can be measured next to nucleo, which
+//! has no such stage of its own.
+//!
+//! The shared bit only records that some other character is present, so a
+//! query's non-ASCII letters never cause a rejection. The reverse is not
+//! covered: "Société" has no `e` bit, so a query for "societe" rejects it,
+//! though nucleo's `Normalization::Smart` matches `e` to `é`. Against
+//! nucleo the mask is only exact on ASCII candidates.
+
+/// Bits 0-25 are `a`-`z`, 26-35 `0`-`9`, and 36 any other character
+/// except whitespace.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CharMask(pub u64);
+
+const OTHER: u64 = 1 << 36;
+
+impl CharMask {
+    pub fn of(text: &str) -> CharMask {
+        let mut mask = 0u64;
+        for byte in text.bytes() {
+            mask |= match byte {
+                b'a'..=b'z' => 1 << (byte - b'a'),
+                b'A'..=b'Z' => 1 << (byte - b'A'),
+                b'0'..=b'9' => 1 << (26 + byte - b'0'),
+                b' ' | b'\t' => 0,
+                _ => OTHER,
+            };
+        }
+        CharMask(mask)
+    }
+
+    /// Whether a candidate with this mask can hold every ASCII letter and
+    /// digit of a query with `query`'s.
+    #[inline]
+    pub fn may_contain(self, query: CharMask) -> bool {
+        let needed = query.0 & !OTHER;
+        self.0 & needed == needed
+    }
+}
@@ -0,0 +1,968 @@
+//! Corpus columns laid out for scanning.
+//!
+//! A [`Corpus`] keeps each row's symbol, name, ISIN and metadata back to
+//! back, which suits loading and row lookups. A scan reads one field of
+//! every row, though, so walking the loaded arena drags the other fields of
+//! each row (long descriptions included) through the cache as well.
+//! [`PreparedCorpus`] copies each searched field into an arena of its own,
+//! with an `(offset, len)` span per row, so a scan reads one contiguous run
+//! of text.
+//!
+//! Identical values are interned: rows sharing a name or exchange all point
+//! at one copy, which shrinks columns with many repeats (the same fund name
+//! listed on several venues) well below the sum of their rows. Each column
+//! also counts the rows holding each character and each adjacent pair of
+//! characters ([`CharFrequency`]), so a scan can estimate how selective a
+//! query is before running it.
+//!
+//! [`PreparedCorpus::to_bytes`] writes the columns out as they sit in
+//! memory, so a prepared corpus can be saved as an index and read back with
+//! [`PreparedCorpus::from_bytes`] without loading the corpus again. Every
+//! number is a little-endian `u32`:
+//!
+//! ```text
+//! "FMPI", format version, body length (a u64), CRC-32 of the body, 0
+//! body: column count,
+//!       per column: field length, field, arena length, arena,
+//!                   row count, then (offset, length) per row,
+//!                   character count, then (character, rows) per character,
+//!                   pair count, then (first, second, rows) per pair
+//! ```
+//!
+//! Characters are stored as their scalar values. The frequency table is
+//! stored rather than rebuilt, since counting pairs costs a pass over every
+//! distinct value. The field and arena are padded with zero bytes to a
+//! multiple of four, so every number sits on a four-byte boundary.
+//!
+//! That alignment lets [`PreparedCorpus::open`] map an index read-only and
+//! scan its arenas and spans where they lie in the file, with nothing
+//! copied. Only the frequency tables, a few thousand entries, are read
+//! into memory. Every process that opens the same index shares one copy of
+//! it in the page cache, so a server and several harness runs on one
+//! machine cost one corpus's worth of memory between them. Opening checks
+//! the whole file as [`PreparedCorpus::from_bytes`] does, which reads it
+//! through once. A mapped index must not be rewritten in place while it is
+//! open; write a new file and rename it over the old one.
+//!
+//! An index outlives the build that wrote it, so it starts with its format
+//! version, [`FORMAT_VERSION`], and a build only reads its own format: an
+//! older or newer index is refused with an error naming both versions,
+//! rather than misread. Format 1 had no header and no padding, and format
+//! 2 no length or checksum. The `reindex` binary rewrites an older index in
+//! the current format, using [`PreparedCorpus::upgrade`], which reads every
+//! earlier format.
+//!
+//! A copy cut short or a flipped bit in an arena would still parse, and
+//! rank against the wrong text without a word. So the header records the
+//! body's length, which catches a partly written file, and its CRC-32,
+//! which catches corruption; reading compares both. The checksum costs a
+//! pass over the file, about a tenth of the time it takes to open, which
+//! [`PreparedCorpus::open_trusted`] skips for an index known to be intact.
+//!
+//! [`PreparedCorpus::new`] prepares on the calling thread, which takes over
+//! a second for a million rows. [`PreparedCorpus::builder`] spreads the
+//! work over threads: each interns a run of rows on its own, the runs are
+//! merged in row order into one arena, and the distinct values are split
+//! again to be counted. The arena comes out in the order a single thread
+//! would lay it down, so both give the same bytes. The builder can also
+//! compute each row's [`CharMask`] for the prefilter, and reports its
+//! progress to a callback on the calling thread.
+
+use crate::corpus::Corpus;
+use crate::frequency::CharFrequency;
+use crate::prefilter::CharMask;
+use memmap2::Mmap;
+use std::collections::HashMap;
+use std::fs::File;
+use std::ops::Range;
+use std::sync::{mpsc, Arc};
+use std::thread;
+
+/// The first bytes of every prepared index.
+pub const MAGIC: [u8; 4] = *b"FMPI";
+
+/// The index format [`PreparedCorpus::to_bytes`] writes and
+/// [`PreparedCorpus::from_bytes`] reads. Bump it with any change to the
+/// layout, and teach [`PreparedCorpus::upgrade`] the old one.
+pub const FORMAT_VERSION: u32 = 3;
+
+/// The magic, version, body length, checksum and padding.
+const HEADER_LEN: usize = 24;
+
+/// The header of format 2, the magic and the version.
+const HEADER_LEN_2: usize = 8;
+
+/// Where one row's value sits in a [`Column`] arena. Laid out as in an
+/// index, so a mapped index's spans can be read in place.
+#[derive(Clone, Copy, Debug)]
+#[repr(C)]
+struct Span {
+    offset: u32,
+    len: u32,
+}
+
+/// Where a column's text and spans are held.
+enum Backing {
+    Owned {
+        arena: String,
+        spans: Vec<Span>,
+    },
+    /// Ranges of a mapped index, checked when it was opened.
+    Mapped {
+        index: Arc<Mmap>,
+        arena: Range<usize>,
+        spans: Range<usize>,
+    },
+}
+
+/// One field of every row, in row order, in a single interned arena.
+pub struct Column {
+    backing: Backing,
+    frequency: CharFrequency,
+    /// One per row, when built by a [`Builder`] asked for them.
+    masks: Option<Vec<CharMask>>,
+}
+
+impl Column {
+    /// The values of `field` for every row of `corpus`, as
+    /// [`Corpus::text`] reads them.
+    pub fn new(corpus: &Corpus, field: &str) -> Column {
+        let mut arena = String::new();
+        let mut spans = Vec::with_capacity(corpus.len());
+        let mut interned: HashMap<&str, Span> = HashMap::new();
+        for idx in 0..corpus.len() {
+            let text = corpus.text(idx, field);
+            let span = *interned.entry(text).or_insert_with(|| {
+                let offset = u32::try_from(arena.len()).expect("column exceeds 4 GiB");
+                arena.push_str(text);
+                Span {
+                    offset,
+                    len: text.len() as u32,
+                }
+            });
+            spans.push(span);
+        }
+        arena.shrink_to_fit();
+        let mut column = Column {
+            backing: Backing::Owned { arena, spans },
+            frequency: CharFrequency::default(),
+            masks: None,
+        };
+        column.count_chars();
+        column
+    }
+
+    fn arena(&self) -> &str {
+        match &self.backing {
+            Backing::Owned { arena, .. } => arena,
+            // SAFETY: opening the index checked that this range is UTF-8
+            Backing::Mapped { index, arena, .. } => unsafe {
+                std::str::from_utf8_unchecked(&index[arena.clone()])
+            },
+        }
+    }
+
+    fn spans(&self) -> &[Span] {
+        match &self.backing {
+            Backing::Owned { spans, .. } => spans,
+            // SAFETY: opening the index checked that this range starts on a
+            // four-byte boundary, holds whole spans, each inside the arena,
+            // and that the target is little-endian like the file
+            Backing::Mapped { index, spans, .. } => unsafe {
+                std::slice::from_raw_parts(
+                    index[spans.clone()].as_ptr().cast::<Span>(),
+                    spans.len() / std::mem::size_of::<Span>(),
+                )
+            },
+        }
+    }
+
+    /// Builds the character table once per interned value, weighted by the
+    /// rows sharing it.
+    fn count_chars(&mut self) {
+        let mut rows: HashMap<u32, (u32, usize)> = HashMap::new();
+        for span in self.spans() {
+            rows.entry(span.offset).or_insert((span.len, 0)).1 += 1;
+        }
+        let arena = self.arena();
+        let frequency =
+            CharFrequency::new(rows.into_iter().map(|(offset, (len, rows))| {
+                (&arena[offset as usize..(offset + len) as usize], rows)
+            }));
+        self.frequency = frequency;
+    }
+
+    /// How many rows contain each character.
+    pub fn frequency(&self) -> &CharFrequency {
+        &self.frequency
+    }
+
+    /// Each row's prefilter mask, when the column was built with them.
+    pub fn masks(&self) -> Option<&[CharMask]> {
+        self.masks.as_deref()
+    }
+
+    /// The value in row `idx`. Panics when out of bounds, like slice
+    /// indexing.
+    pub fn get(&self, idx: usize) -> &str {
+        let span = self.spans()[idx];
+        &self.arena()[span.offset as usize..(span.offset + span.len) as usize]
+    }
+
+    pub fn len(&self) -> usize {
+        self.spans().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.spans().is_empty()
+    }
+
+    /// Bytes of text held, after interning.
+    pub fn arena_bytes(&self) -> usize {
+        self.arena().len()
+    }
+
+    /// Bytes the rows' values would take stored separately.
+    pub fn text_bytes(&self) -> usize {
+        self.spans().iter().map(|span| span.len as usize).sum()
+    }
+
+    /// Whether the text and spans are read from a mapped index.
+    pub fn is_mapped(&self) -> bool {
+        matches!(self.backing, Backing::Mapped { .. })
+    }
+
+    /// Asks the CPU to start loading the text of rows `rows` into cache,
+    /// so a scan can prefetch one batch while it scores the one before.
+    /// Only a hint: rows past the end are ignored, and on targets without a
+    /// prefetch instruction it does nothing.
+    pub fn prefetch(&self, rows: Range<usize>) {
+        let (arena, spans) = (self.arena(), self.spans());
+        let rows = rows.start.min(spans.len())..rows.end.min(spans.len());
+        for span in &spans[rows] {
+            let text = &arena.as_bytes()[span.offset as usize..][..span.len as usize];
+            for line in text.chunks(CACHE_LINE) {
+                prefetch_line(line.as_ptr());
+            }
+        }
+    }
+
+    /// Reads one byte of every cache line of the text and one span in
+    /// every cache line of the spans, so their pages are mapped and in
+    /// cache before the first scan rather than faulted in by it. Gives the
+    /// bytes the column occupies.
+    pub fn touch(&self) -> usize {
+        let (arena, spans) = (self.arena(), self.spans());
+        let mut sum = 0u32;
+        for line in arena.as_bytes().chunks(CACHE_LINE) {
+            sum = sum.wrapping_add(u32::from(line[0]));
+        }
+        let per_line = CACHE_LINE / std::mem::size_of::<Span>();
+        for span in spans.iter().step_by(per_line) {
+            sum = sum.wrapping_add(span.offset);
+        }
+        std::hint::black_box(sum);
+        arena.len() + std::mem::size_of_val(spans)
+    }
+
+    pub fn iter(&self) -> ColumnIter<'_> {
+        ColumnIter {
+            arena: self.arena(),
+            spans: self.spans().iter(),
+        }
+    }
+}
+
+const CACHE_LINE: usize = 64;
+
+#[cfg(target_arch = "x86_64")]
+fn prefetch_line(ptr: *const u8) {
+    use std::arch::x86_64::{_mm_prefetch, _MM_HINT_T0};
+    // SAFETY: prefetching is a hint that never faults, and SSE is part of
+    // the x86_64 baseline
+    unsafe { _mm_prefetch(ptr as *const i8, _MM_HINT_T0) }
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+fn prefetch_line(_ptr: *const u8) {}
+
+impl<'a> IntoIterator for &'a Column {
+    type Item = &'a str;
+    type IntoIter = ColumnIter<'a>;
+
+    fn into_iter(self) -> ColumnIter<'a> {
+        self.iter()
+    }
+}
+
+/// Iterator over a [`Column`]'s values, in row order.
+pub struct ColumnIter<'a> {
+    arena: &'a str,
+    spans: std::slice::Iter<'a, Span>,
+}
+
+impl<'a> Iterator for ColumnIter<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        let span = self.spans.next()?;
+        Some(&self.arena[span.offset as usize..(span.offset + span.len) as usize])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.spans.size_hint()
+    }
+}
+
+impl ExactSizeIterator for ColumnIter<'_> {}
+
+/// The searched fields of a corpus, one [`Column`] each. Row indices match
+/// the corpus, so a hit in a column is looked up with [`Corpus::get`].
+pub struct PreparedCorpus {
+    columns: Vec<(String, Column)>,
+}
+
+impl PreparedCorpus {
+    /// Columns for `fields`, plus the symbol, name and ISIN, which every
+    /// query set may search. Repeated fields are prepared once.
+    pub fn new<'a>(corpus: &Corpus, fields: impl IntoIterator<Item = &'a str>) -> PreparedCorpus {
+        let columns = column_keys(corpus, fields)
+            .into_iter()
+            .map(|key| {
+                let column = Column::new(corpus, &key);
+                (key, column)
+            })
+            .collect();
+        PreparedCorpus { columns }
+    }
+
+    /// Prepares `corpus` across threads; see [`Builder`].
+    pub fn builder(corpus: &Corpus) -> Builder<'_> {
+        Builder {
+            corpus,
+            fields: Vec::new(),
+            threads: thread::available_parallelism().map_or(1, |n| n.get()),
+            masks: false,
+            progress: None,
+        }
+    }
+
+    /// The columns in the layout described in the module docs.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(
+            HEADER_LEN
+                + 4
+                + self
+                    .columns
+                    .iter()
+                    .map(|(field, c)| 26 + field.len() + c.arena_bytes() + 8 * c.len())
+                    .sum::<usize>(),
+        );
+        let put = |bytes: &mut Vec<u8>, n: usize| {
+            let n = u32::try_from(n).expect("prepared column exceeds 4 GiB");
+            bytes.extend_from_slice(&n.to_le_bytes());
+        };
+        let put_text = |bytes: &mut Vec<u8>, text: &str| {
+            put(bytes, text.len());
+            bytes.extend_from_slice(text.as_bytes());
+            bytes.resize(bytes.len().next_multiple_of(4), 0);
+        };
+        bytes.extend_from_slice(&MAGIC);
+        put(&mut bytes, FORMAT_VERSION as usize);
+        // Length and checksum, filled in once the body is written
+        bytes.resize(HEADER_LEN, 0);
+        put(&mut bytes, self.columns.len());
+        for (field, column) in &self.columns {
+            put_text(&mut bytes, field);
+            put_text(&mut bytes, column.arena());
+            put(&mut bytes, column.len());
+            for span in column.spans() {
+                put(&mut bytes, span.offset as usize);
+                put(&mut bytes, span.len as usize);
+            }
+            let chars = column.frequency.chars();
+            put(&mut bytes, chars.len());
+            for (c, rows) in chars {
+                put(&mut bytes, c as usize);
+                put(&mut bytes, rows as usize);
+            }
+            let bigrams = column.frequency.bigrams();
+            put(&mut bytes, bigrams.len());
+            for ((a, b), rows) in bigrams {
+                put(&mut bytes, a as usize);
+                put(&mut bytes, b as usize);
+                put(&mut bytes, rows as usize);
+            }
+        }
+        let body = &bytes[HEADER_LEN..];
+        let (length, checksum) = (body.len() as u64, crc32fast::hash(body));
+        bytes[8..16].copy_from_slice(&length.to_le_bytes());
+        bytes[16..20].copy_from_slice(&checksum.to_le_bytes());
+        bytes
+    }
+
+    /// Reads columns written by [`PreparedCorpus::to_bytes`], checking the
+    /// length and checksum, and that every span lies inside its arena on
+    /// character boundaries. Indexes in another format are refused; see
+    /// [`PreparedCorpus::upgrade`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<PreparedCorpus, String> {
+        read_columns(bytes, None, true)
+    }
+
+    /// Reads an index in the current format or any earlier one, and gives
+    /// the format it was in. [`PreparedCorpus::to_bytes`] then writes it
+    /// out in the current format.
+    pub fn upgrade(bytes: &[u8]) -> Result<(PreparedCorpus, u32), String> {
+        if bytes.starts_with(&MAGIC) {
+            // Format 2 had the magic and version, then the body
+            if bytes.get(4..8) == Some(&2u32.to_le_bytes()[..]) {
+                return read_body(Reader::new(bytes, HEADER_LEN_2, true), None).map(|p| (p, 2));
+            }
+            return PreparedCorpus::from_bytes(bytes).map(|p| (p, FORMAT_VERSION));
+        }
+        // Format 1 had no header. Its strings were unpadded, except in
+        // indexes from the build that padded them before the header came
+        let unpadded = read_body(Reader::new(bytes, 0, false), None);
+        unpadded
+            .or_else(|e| read_body(Reader::new(bytes, 0, true), None).map_err(|_| e))
+            .map(|p| (p, 1))
+            .map_err(|e| format!("not a prepared index in any known format: {e}"))
+    }
+
+    /// Maps the index at `path` read-only and reads its columns in place,
+    /// checking it as [`PreparedCorpus::from_bytes`] does.
+    pub fn open(path: &str) -> Result<PreparedCorpus, String> {
+        PreparedCorpus::map(path, true)
+    }
+
+    /// Opens an index as [`PreparedCorpus::open`] does without comparing
+    /// its checksum, for one known to be intact, such as one this machine
+    /// has just written and opened before. Its length and structure are
+    /// still checked, so a damaged index can rank wrongly but not crash.
+    pub fn open_trusted(path: &str) -> Result<PreparedCorpus, String> {
+        PreparedCorpus::map(path, false)
+    }
+
+    fn map(path: &str, verify: bool) -> Result<PreparedCorpus, String> {
+        if cfg!(target_endian = "big") {
+            return Err("mapped indexes need a little-endian target".to_string());
+        }
+        let file = File::open(path).map_err(|e| format!("{path}: {e}"))?;
+        // SAFETY: the map is read-only, and the module docs ask that an
+        // open index be replaced by renaming rather than rewritten
+        let index = unsafe { Mmap::map(&file) }.map_err(|e| format!("{path}: {e}"))?;
+        let index = Arc::new(index);
+        read_columns(&index, Some(&index), verify).map_err(|e| format!("{path}: {e}"))
+    }
+
+    /// The column a query on `field` searches. Fields that were not
+    /// prepared search the name, as in [`Corpus::text`].
+    pub fn column(&self, field: &str) -> &Column {
+        let found = |key: &str| self.columns.iter().find(|(have, _)| have == key);
+        found(field)
+            .or_else(|| {
+                self.columns
+                    .iter()
+                    .find(|(have, _)| have.eq_ignore_ascii_case(field))
+            })
+            .or_else(|| found("name"))
+            .map(|(_, column)| column)
+            .expect("name column is always prepared")
+    }
+
+    /// Prepared field names, in preparation order.
+    pub fn fields(&self) -> impl Iterator<Item = &str> {
+        self.columns.iter().map(|(field, _)| field.as_str())
+    }
+
+    /// Bytes held across all columns, after interning.
+    pub fn arena_bytes(&self) -> usize {
+        self.columns.iter().map(|(_, c)| c.arena_bytes()).sum()
+    }
+
+    /// One line on the columns' size, for run logs.
+    pub fn summary(&self) -> String {
+        let text: usize = self.columns.iter().map(|(_, c)| c.text_bytes()).sum();
+        let saved = if text == 0 {
+            0.0
+        } else {
+            100.0 * (1.0 - self.arena_bytes() as f64 / text as f64)
+        };
+        let mapped = if self.columns.iter().any(|(_, c)| c.is_mapped()) {
+            ", mapped"
+        } else {
+            ""
+        };
+        format!(
+            "{} columns, {} KiB of text ({:.0}% saved by interning{})",
+            self.columns.len(),
+            self.arena_bytes() / 1024,
+            saved,
+            mapped
+        )
+    }
+
+    /// Checks that an index read back suits `corpus` and queries on
+    /// `fields`: each field has a column of its own, rather than falling
+    /// back to the name, and every column has the corpus's rows, with the
+    /// corpus's values in its first and last row. The rows between are not
+    /// compared, which would read the whole index.
+    pub fn check<'a>(
+        &self,
+        corpus: &Corpus,
+        fields: impl IntoIterator<Item = &'a str>,
+    ) -> Result<(), String> {
+        for key in column_keys(corpus, fields) {
+            if self.columns.iter().all(|(have, _)| *have != key) {
+                return Err(format!("the index has no `{key}` column"));
+            }
+        }
+        for (field, column) in &self.columns {
+            if column.len() != corpus.len() {
+                return Err(format!(
+                    "column `{field}` has {} rows, the corpus {}",
+                    column.len(),
+                    corpus.len()
+                ));
+            }
+            for row in [0, corpus.len().saturating_sub(1)] {
+                if row < corpus.len() && column.get(row) != corpus.text(row, field) {
+                    return Err(format!(
+                        "row {row} of column `{field}` differs from the corpus"
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// What a [`Builder`] is doing when it reports progress.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Stage {
+    /// Copying a column's values into its arena, interning repeats.
+    Interning,
+    /// Counting the characters and pairs of the distinct values.
+    Counting,
+    /// Computing each row's prefilter mask.
+    Masking,
+}
+
+/// How far a [`Builder`] has got with one stage of one column.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Progress<'a> {
+    pub field: &'a str,
+    pub stage: Stage,
+    /// Rows done, or distinct values when counting.
+    pub done: usize,
+    pub total: usize,
+}
+
+/// A [`Builder`]'s progress callback.
+type ProgressFn<'a> = Box<dyn FnMut(Progress<'_>) + 'a>;
+
+/// Items a thread works through between progress reports.
+const PROGRESS_STEP: usize = 1 << 16;
+
+/// Prepares a [`PreparedCorpus`] across threads, giving the same columns
+/// as [`PreparedCorpus::new`].
+pub struct Builder<'a> {
+    corpus: &'a Corpus,
+    fields: Vec<String>,
+    threads: usize,
+    masks: bool,
+    progress: Option<ProgressFn<'a>>,
+}
+
+impl<'a> Builder<'a> {
+    /// Fields to prepare besides the symbol, name and ISIN.
+    pub fn fields<'f>(mut self, fields: impl IntoIterator<Item = &'f str>) -> Self {
+        self.fields.extend(fields.into_iter().map(String::from));
+        self
+    }
+
+    /// Threads to spread each stage over, by default one per core.
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// Whether to compute every row's [`CharMask`], for
+    /// [`Column::masks`].
+    pub fn masks(mut self, masks: bool) -> Self {
+        self.masks = masks;
+        self
+    }
+
+    /// Called on the building thread as the stages of each column advance,
+    /// and once more when each finishes, with `done == total`.
+    pub fn progress(mut self, progress: impl FnMut(Progress<'_>) + 'a) -> Self {
+        self.progress = Some(Box::new(progress));
+        self
+    }
+
+    pub fn build(mut self) -> PreparedCorpus {
+        let keys = column_keys(self.corpus, self.fields.iter().map(String::as_str));
+        let columns = keys
+            .into_iter()
+            .map(|key| {
+                let column = self.column(&key);
+                (key, column)
+            })
+            .collect();
+        PreparedCorpus { columns }
+    }
+
+    fn column(&mut self, field: &str) -> Column {
+        let corpus = self.corpus;
+        let rows = corpus.len();
+
+        // Each thread interns its own run of rows, numbering its distinct
+        // values in the order it meets them
+        let runs = self.in_parallel(field, Stage::Interning, rows, |range, done| {
+            let mut interned: HashMap<&str, u32> = HashMap::new();
+            let mut distinct: Vec<&str> = Vec::new();
+            let mut ids = Vec::with_capacity(range.len());
+            for block in blocks(range) {
+                for row in block.clone() {
+                    let text = corpus.text(row, field);
+                    let id = *interned.entry(text).or_insert_with(|| {
+                        distinct.push(text);
+                        distinct.len() as u32 - 1
+                    });
+                    ids.push(id);
+                }
+                done(block.len());
+            }
+            (distinct, ids)
+        });
+
+        // Merging the runs in row order meets each value first where a
+        // single thread would, so the arena is laid out as it would be
+        let mut arena = String::new();
+        let mut spans = Vec::with_capacity(rows);
+        let mut distinct: Vec<(Span, usize)> = Vec::new();
+        let mut interned: HashMap<&str, u32> = HashMap::new();
+        for (values, ids) in &runs {
+            let global: Vec<u32> = values
+                .iter()
+                .map(|&text| {
+                    *interned.entry(text).or_insert_with(|| {
+                        let offset = u32::try_from(arena.len()).expect("column exceeds 4 GiB");
+                        arena.push_str(text);
+                        let span = Span {
+                            offset,
+                            len: text.len() as u32,
+                        };
+                        distinct.push((span, 0));
+                        distinct.len() as u32 - 1
+                    })
+                })
+                .collect();
+            for &id in ids {
+                let value = &mut distinct[global[id as usize] as usize];
+                value.1 += 1;
+                spans.push(value.0);
+            }
+        }
+        drop(runs);
+        arena.shrink_to_fit();
+
+        let text = |span: Span| &arena[span.offset as usize..(span.offset + span.len) as usize];
+        let counts = self.in_parallel(field, Stage::Counting, distinct.len(), |range, done| {
+            let mut frequency = CharFrequency::default();
+            for block in blocks(range) {
+                let values = distinct[block.clone()]
+                    .iter()
+                    .map(|&(span, rows)| (text(span), rows));
+                frequency.merge(&CharFrequency::new(values));
+                done(block.len());
+            }
+            frequency
+        });
+        let mut frequency = CharFrequency::default();
+        for counted in &counts {
+            frequency.merge(counted);
+        }
+
+        let mut column = Column {
+            backing: Backing::Owned { arena, spans },
+            frequency,
+            masks: None,
+        };
+        if self.masks {
+            let masks = self.in_parallel(field, Stage::Masking, rows, |range, done| {
+                let mut masks = Vec::with_capacity(range.len());
+                for block in blocks(range) {
+                    masks.extend(block.clone().map(|row| CharMask::of(column.get(row))));
+                    done(block.len());
+                }
+                masks
+            });
+            column.masks = Some(masks.concat());
+        }
+        column
+    }
+
+    /// Splits `0..total` into one run per thread, runs `work` on each, and
+    /// gives the results in run order. `work` reports the items it has
+    /// finished through its second argument, and the callback hears of
+    /// them here, on the building thread.
+    fn in_parallel<T: Send>(
+        &mut self,
+        field: &str,
+        stage: Stage,
+        total: usize,
+        work: impl Fn(Range<usize>, &mut dyn FnMut(usize)) -> T + Sync,
+    ) -> Vec<T> {
+        let run = total.div_ceil(self.threads).max(1);
+        let (sender, receiver) = mpsc::channel();
+        let results = thread::scope(|scope| {
+            let work = &work;
+            let workers: Vec<_> = (0..total)
+                .step_by(run)
+                .map(|start| {
+                    let sender = sender.clone();
+                    scope.spawn(move || {
+                        work(start..(start + run).min(total), &mut |n| {
+                            let _ = sender.send(n);
+                        })
+                    })
+                })
+                .collect();
+            drop(sender);
+            let mut done = 0;
+            for n in receiver {
+                done += n;
+                if done < total {
+                    self.report(field, stage, done, total);
+                }
+            }
+            workers
+                .into_iter()
+                .map(|worker| worker.join().expect("preparation thread panicked"))
+                .collect()
+        });
+        self.report(field, stage, total, total);
+        results
+    }
+
+    fn report(&mut self, field: &str, stage: Stage, done: usize, total: usize) {
+        if let Some(progress) = &mut self.progress {
+            progress(Progress {
+                field,
+                stage,
+                done,
+                total,
+            });
+        }
+    }
+}
+
+/// `range` in runs of [`PROGRESS_STEP`].
+fn blocks(range: Range<usize>) -> impl Iterator<Item = Range<usize>> {
+    range
+        .clone()
+        .step_by(PROGRESS_STEP)
+        .map(move |start| start..(start + PROGRESS_STEP).min(range.end))
+}
+
+/// Reads the columns of an index, copying each arena and its spans, or
+/// with `mapped`, the map `bytes` lies in, pointing into it.
+fn read_columns(
+    bytes: &[u8],
+    mapped: Option<&Arc<Mmap>>,
+    verify: bool,
+) -> Result<PreparedCorpus, String> {
+    if !bytes.starts_with(&MAGIC) {
+        return Err(format!(
+            "no format header, so written before format {FORMAT_VERSION} or not an index; \
+             `reindex` rewrites an older index"
+        ));
+    }
+    let mut reader = Reader::new(bytes, MAGIC.len(), true);
+    let version = reader.u32()?;
+    if version != FORMAT_VERSION {
+        let fix = if version < FORMAT_VERSION {
+            "`reindex` rewrites it"
+        } else {
+            "read it with a newer build, or prepare it again"
+        };
+        return Err(format!(
+            "index format {version}, but this build reads format {FORMAT_VERSION}; {fix}"
+        ));
+    }
+    let length = u64::from(reader.u32()?) | u64::from(reader.u32()?) << 32;
+    let checksum = reader.u32()?;
+    reader.u32()?;
+    let body = &bytes[HEADER_LEN..];
+    if body.len() as u64 != length {
+        return Err(format!(
+            "index holds {} bytes after its header, but was written with {length}; \
+             it was truncated or added to",
+            body.len()
+        ));
+    }
+    if verify {
+        let found = crc32fast::hash(body);
+        if found != checksum {
+            return Err(format!(
+                "index checksum is {found:08x}, but was written as {checksum:08x}; \
+                 it is corrupt"
+            ));
+        }
+    }
+    read_body(reader, mapped)
+}
+
+/// Reads the columns after the header, or from the start of a format 1
+/// index.
+fn read_body(mut reader: Reader, mapped: Option<&Arc<Mmap>>) -> Result<PreparedCorpus, String> {
+    let bytes = reader.bytes;
+    let mut columns = Vec::new();
+    for _ in 0..reader.u32()? {
+        let field = reader.text()?.to_string();
+        let arena = reader.text()?;
+        let arena_range = reader.at_text(arena);
+        let rows = reader.u32()? as usize;
+        let spans_start = reader.at;
+        let mut spans = Vec::new();
+        if mapped.is_none() {
+            spans.reserve(rows.min(bytes.len() / 8));
+        }
+        for row in 0..rows {
+            let span = Span {
+                offset: reader.u32()?,
+                len: reader.u32()?,
+            };
+            let (start, end) = (
+                span.offset as usize,
+                span.offset as usize + span.len as usize,
+            );
+            if end > arena.len() || !arena.is_char_boundary(start) || !arena.is_char_boundary(end) {
+                return Err(format!(
+                    "row {row} of column `{field}` lies outside its text"
+                ));
+            }
+            if mapped.is_none() {
+                spans.push(span);
+            }
+        }
+        let mut chars = HashMap::new();
+        for _ in 0..reader.u32()? {
+            let c = reader.char()?;
+            chars.insert(c, reader.u32()?);
+        }
+        let mut bigrams = HashMap::new();
+        for _ in 0..reader.u32()? {
+            let pair = (reader.char()?, reader.char()?);
+            bigrams.insert(pair, reader.u32()?);
+        }
+        let backing = match mapped {
+            Some(index) => {
+                let spans = spans_start..spans_start + rows * std::mem::size_of::<Span>();
+                let at = bytes.as_ptr() as usize + spans.start;
+                if !at.is_multiple_of(std::mem::align_of::<Span>()) {
+                    return Err(format!("spans of column `{field}` are not aligned"));
+                }
+                Backing::Mapped {
+                    index: Arc::clone(index),
+                    arena: arena_range,
+                    spans,
+                }
+            }
+            None => Backing::Owned {
+                arena: arena.to_string(),
+                spans,
+            },
+        };
+        let column = Column {
+            backing,
+            frequency: CharFrequency::from_counts(rows, chars, bigrams),
+            masks: None,
+        };
+        columns.push((field, column));
+    }
+    if columns.iter().all(|(field, _)| field != "name") {
+        return Err("prepared corpus has no name column".to_string());
+    }
+    if reader.at != bytes.len() {
+        return Err(format!("{} trailing bytes", bytes.len() - reader.at));
+    }
+    Ok(PreparedCorpus { columns })
+}
+
+/// Reads the numbers and strings of [`PreparedCorpus::to_bytes`] in turn.
+struct Reader<'a> {
+    bytes: &'a [u8],
+    at: usize,
+    /// Whether strings are padded to a four-byte boundary.
+    padded: bool,
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8], at: usize, padded: bool) -> Reader<'a> {
+        Reader { bytes, at, padded }
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let taken = self
+            .bytes
+            .get(self.at..self.at.saturating_add(len))
+            .ok_or_else(|| format!("prepared corpus truncated at byte {}", self.at))?;
+        self.at += len;
+        Ok(taken)
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn char(&mut self) -> Result<char, String> {
+        let at = self.at;
+        char::from_u32(self.u32()?).ok_or_else(|| format!("invalid character at byte {at}"))
+    }
+
+    /// A string and any padding after it.
+    fn text(&mut self) -> Result<&'a str, String> {
+        let len = self.u32()? as usize;
+        let at = self.at;
+        let bytes = self.take(len)?;
+        if self.padded {
+            self.take(len.next_multiple_of(4) - len)?;
+        }
+        std::str::from_utf8(bytes).map_err(|_| format!("invalid UTF-8 at byte {at}"))
+    }
+
+    /// Where `text`, just read, lies in the bytes.
+    fn at_text(&self, text: &str) -> Range<usize> {
+        let start = text.as_ptr() as usize - self.bytes.as_ptr() as usize;
+        start..start + text.len()
+    }
+}
+
+/// The columns to prepare for `fields`: the symbol, name and ISIN, then
+/// each field's column once.
+fn column_keys<'a>(corpus: &Corpus, fields: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    let mut keys: Vec<String> = Vec::new();
+    for field in ["symbol", "name", "isin"].into_iter().chain(fields) {
+        let key = column_key(corpus, field);
+        if !keys.contains(&key) {
+            keys.push(key);
+        }
+    }
+    keys
+}
+
+/// `field` as it names its column: the built-in fields as given, and
+/// metadata columns and unknown fields by the text they resolve to.
+fn column_key(corpus: &Corpus, field: &str) -> String {
+    match field {
+        "symbol" | "name" | "isin" => field.to_string(),
+        _ => match corpus.metadata_column(field) {
+            Some(column) => corpus.metadata_columns()[column].clone(),
+            None => "name".to_string(),
+        },
+    }
+}
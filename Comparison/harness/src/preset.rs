@@ -0,0 +1,230 @@
+//! Scoring presets: a complete matcher configuration saved as TOML.
+//!
+//! A preset names every setting that changes how nucleo ranks candidates,
+//! so a past configuration can be run again by name instead of by memory.
+//! Presets live in `Comparison/presets/NAME.toml`; `--preset NAME` loads
+//! one (or a preset file at any path), and `--save-preset PATH` writes the
+//! one in effect. Every key is optional and falls back to the default shown,
+//! which is what the harnesses run without a preset:
+//!
+//! ```toml
+//! name = "default"         # defaults to the file name
+//! case = "ignore"          # ignore | smart | respect
+//! normalization = "smart"  # smart | never
+//! atom = "fuzzy"           # fuzzy | substring | prefix | postfix | exact
+//! prefer_prefix = false    # small bonus for matches near the start
+//! bonuses = "default"      # default | paths
+//! tie_break = "row"        # row | shorter
+//! ```
+//!
+//! `bonuses = "paths"` swaps nucleo's word-boundary bonuses for the ones it
+//! tunes for file paths; nucleo exposes no other weights. `tie_break` orders
+//! equal scores: by corpus row, or shorter candidates first. Unknown keys
+//! and values are errors, since a misspelt setting would otherwise run the
+//! default unnoticed.
+
+use crate::cli::flag_value;
+use nucleo_matcher::pattern::{AtomKind, CaseMatching, Normalization, Pattern};
+use nucleo_matcher::Config;
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::{fs, io};
+use toml::{Table, Value};
+
+/// Allowed values of each choice, the default first.
+const CHOICES: [(&str, &[&str]); 5] = [
+    ("case", &["ignore", "smart", "respect"]),
+    ("normalization", &["smart", "never"]),
+    (
+        "atom",
+        &["fuzzy", "substring", "prefix", "postfix", "exact"],
+    ),
+    ("bonuses", &["default", "paths"]),
+    ("tie_break", &["row", "shorter"]),
+];
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Preset {
+    pub name: String,
+    pub case: String,
+    pub normalization: String,
+    pub atom: String,
+    pub prefer_prefix: bool,
+    pub bonuses: String,
+    pub tie_break: String,
+}
+
+impl Default for Preset {
+    fn default() -> Self {
+        Preset {
+            name: "default".to_string(),
+            case: "ignore".to_string(),
+            normalization: "smart".to_string(),
+            atom: "fuzzy".to_string(),
+            prefer_prefix: false,
+            bonuses: "default".to_string(),
+            tie_break: "row".to_string(),
+        }
+    }
+}
+
+impl FromStr for Preset {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let table: Table = s.parse().map_err(|e: toml::de::Error| e.to_string())?;
+        let mut preset = Preset::default();
+        for (key, value) in &table {
+            match key.as_str() {
+                "name" => {
+                    preset.name = value
+                        .as_str()
+                        .ok_or_else(|| format!("{key} must be a string"))?
+                        .to_string()
+                }
+                "prefer_prefix" => {
+                    preset.prefer_prefix = value
+                        .as_bool()
+                        .ok_or_else(|| format!("{key} must be true or false"))?
+                }
+                _ => {
+                    let (_, allowed) = CHOICES
+                        .iter()
+                        .find(|(choice, _)| choice == key)
+                        .ok_or_else(|| format!("unknown key `{key}`"))?;
+                    let chosen = match value {
+                        Value::String(s) if allowed.contains(&s.as_str()) => s.clone(),
+                        _ => return Err(format!("{key} must be one of {}", allowed.join(", "))),
+                    };
+                    *preset.choice_mut(key) = chosen;
+                }
+            }
+        }
+        Ok(preset)
+    }
+}
+
+impl Preset {
+    /// Loads the preset `name` from `Comparison/presets`, or from the file
+    /// `name` when it is a path to one.
+    pub fn load(name: &str) -> Result<Preset, String> {
+        let path = if Path::new(name).is_file() {
+            PathBuf::from(name)
+        } else {
+            presets_dir().join(format!("{name}.toml"))
+        };
+        let text = fs::read_to_string(&path).map_err(|e| format!("{}: {e}", path.display()))?;
+        let mut preset: Preset = text
+            .parse()
+            .map_err(|e| format!("{}: {e}", path.display()))?;
+        if !text.parse::<Table>().is_ok_and(|t| t.contains_key("name")) {
+            preset.name = path
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default();
+        }
+        Ok(preset)
+    }
+
+    /// The preset named by `--preset`, or the default.
+    pub fn from_args(args: &[String]) -> Result<Preset, String> {
+        flag_value(args, "--preset").map_or_else(|| Ok(Preset::default()), Preset::load)
+    }
+
+    /// Writes the preset as TOML, every key spelt out.
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        fs::write(path, self.to_toml())
+    }
+
+    pub fn to_toml(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "name = {}", Value::from(self.name.as_str())).unwrap();
+        for (key, _) in CHOICES {
+            writeln!(out, "{key} = {}", Value::from(self.choice(key))).unwrap();
+        }
+        writeln!(out, "prefer_prefix = {}", self.prefer_prefix).unwrap();
+        out
+    }
+
+    /// The settings as fingerprint entries, with the preset's name.
+    pub fn entries(&self) -> BTreeMap<String, String> {
+        let mut entries: BTreeMap<String, String> = CHOICES
+            .iter()
+            .map(|(key, _)| (key.to_string(), self.choice(key).to_string()))
+            .collect();
+        entries.insert("prefer_prefix".to_string(), self.prefer_prefix.to_string());
+        entries.insert("preset".to_string(), self.name.clone());
+        entries
+    }
+
+    /// The matcher configuration for this preset.
+    pub fn config(&self) -> Config {
+        let mut config = Config::DEFAULT;
+        if self.bonuses == "paths" {
+            config.set_match_paths();
+        }
+        config.prefer_prefix = self.prefer_prefix;
+        config
+    }
+
+    /// `query` parsed with this preset's case, normalization and atom kind.
+    pub fn pattern(&self, query: &str) -> Pattern {
+        let case = match self.case.as_str() {
+            "smart" => CaseMatching::Smart,
+            "respect" => CaseMatching::Respect,
+            _ => CaseMatching::Ignore,
+        };
+        let normalization = match self.normalization.as_str() {
+            "never" => Normalization::Never,
+            _ => Normalization::Smart,
+        };
+        let atom = match self.atom.as_str() {
+            "substring" => AtomKind::Substring,
+            "prefix" => AtomKind::Prefix,
+            "postfix" => AtomKind::Postfix,
+            "exact" => AtomKind::Exact,
+            _ => AtomKind::Fuzzy,
+        };
+        Pattern::new(query, case, normalization, atom)
+    }
+
+    /// Sorts `(score, row)` matches best first, breaking ties as the preset
+    /// says; `text` gives a row's candidate text.
+    pub fn rank<'a>(&self, matches: &mut [(u32, usize)], text: impl Fn(usize) -> &'a str) {
+        if self.tie_break == "shorter" {
+            matches.sort_by_cached_key(|&(score, row)| {
+                (Reverse(score), text(row).chars().count(), row)
+            });
+        } else {
+            matches.sort_unstable_by_key(|&(score, row)| (Reverse(score), row));
+        }
+    }
+
+    fn choice(&self, key: &str) -> &str {
+        match key {
+            "case" => &self.case,
+            "normalization" => &self.normalization,
+            "atom" => &self.atom,
+            "bonuses" => &self.bonuses,
+            _ => &self.tie_break,
+        }
+    }
+
+    fn choice_mut(&mut self, key: &str) -> &mut String {
+        match key {
+            "case" => &mut self.case,
+            "normalization" => &mut self.normalization,
+            "atom" => &mut self.atom,
+            "bonuses" => &mut self.bonuses,
+            _ => &mut self.tie_break,
+        }
+    }
+}
+
+/// `Comparison/presets`, where presets are looked up by name.
+pub fn presets_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../presets")
+}
@@ -0,0 +1,106 @@
+//! Query set loading and writing.
+//!
+//! `Resources/queries.tsv` has no header and uses the columns
+//! `query \t field \t category \t expected_name \t frequency`. The fourth
+//! column is optional and defaults to [`SKIP`]. The fifth, how often the
+//! query is searched for in real traffic, defaults to 1 so that unweighted
+//! query sets count every query once.
+//!
+//! Files saved from Excel load as written: byte order marks, CRLF line
+//! endings and quoted cells are handled by [`input`] and [`tsv`].
+
+use crate::{input, tsv};
+use std::borrow::Cow;
+use std::io::{self, Write};
+
+/// Placeholder `expected_name` for queries without a definitive answer.
+pub const SKIP: &str = "_SKIP_";
+
+/// Categories in the order every report prints them.
+pub const CATEGORY_ORDER: [&str; 11] = [
+    "exact_symbol",
+    "exact_name",
+    "exact_isin",
+    "prefix",
+    "typo",
+    "substring",
+    "multi_word",
+    "symbol_spaces",
+    "abbreviation",
+    "long_text",
+    "adversarial",
+];
+
+/// One benchmark/quality query.
+#[derive(Clone, Debug)]
+pub struct Query {
+    pub text: String,
+    /// `symbol`, `name`, `isin`, or for `long_text` queries the metadata
+    /// column holding the long text (see [`Corpus::text`](crate::corpus::Corpus::text)).
+    pub field: String,
+    pub category: String,
+    pub expected_name: String,
+    /// Relative number of searches for this query; at least 1.
+    pub frequency: u64,
+}
+
+/// Loads a queries TSV. Lines with fewer than three columns are skipped.
+///
+/// An empty frequency counts as 1, and any other that is not a positive
+/// integer is an [`io::ErrorKind::InvalidData`] error.
+pub fn load_queries(path: &str) -> io::Result<Vec<Query>> {
+    let content = input::read_to_string(path)?;
+    let mut queries = Vec::new();
+    for (i, line) in content.lines().enumerate() {
+        if line.is_empty() {
+            continue;
+        }
+        let cols: Vec<Cow<str>> = tsv::fields(line).collect();
+        if cols.len() >= 3 {
+            // Spreadsheets pad short rows with empty cells
+            let frequency = match cols.get(4).filter(|value| !value.trim().is_empty()) {
+                None => 1,
+                Some(value) => value
+                    .trim()
+                    .parse()
+                    .ok()
+                    .filter(|&f| f > 0)
+                    .ok_or_else(|| {
+                        io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            format!(
+                                "line {}: frequency {value:?} is not a positive integer",
+                                i + 1
+                            ),
+                        )
+                    })?,
+            };
+            queries.push(Query {
+                text: cols[0].to_string(),
+                field: cols[1].to_string(),
+                category: cols[2].to_string(),
+                expected_name: cols.get(3).map_or(SKIP, |c| c.as_ref()).to_string(),
+                frequency,
+            });
+        }
+    }
+    Ok(queries)
+}
+
+/// Writes queries in the `queries.tsv` format. The frequency column is
+/// only written for queries searched more than once, so unweighted query
+/// sets keep the original four columns.
+pub fn write_queries<W: Write>(out: &mut W, queries: &[Query]) -> io::Result<()> {
+    for q in queries {
+        write!(
+            out,
+            "{}\t{}\t{}\t{}",
+            q.text, q.field, q.category, q.expected_name
+        )?;
+        if q.frequency != 1 {
+            write!(out, "\t{}", q.frequency)?;
+        }
+        writeln!(out)?;
+    }
+    Ok(())
+}
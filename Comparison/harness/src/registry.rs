@@ -0,0 +1,256 @@
+//! Backends registered by name, for binaries built outside this repository.
+//!
+//! A team comparing its internal matcher with ours should not have to fork
+//! the workspace to do it. Its own crate depends on `harness`, implements
+//! [`MatcherBackend`](crate::evaluation::MatcherBackend), and builds a
+//! [`Registry`] holding that backend beside the built-in ones. The
+//! registry's [`main`](Registry::main) is then the whole binary:
+//!
+//! ```text
+//! acme-bench list
+//! acme-bench bench BACKEND [--tsv PATH] [--queries PATH] [--preset NAME]
+//!                          [--map SPEC] [--iterations N] [--run LABEL]
+//!                          [--warmup-queries N|all] [--warmup-index]
+//!                          [--discard-first-pass] [--results PATH]
+//! acme-bench serve BACKEND [--preset NAME] [--map SPEC] CORPUS
+//! ```
+//!
+//! `bench` times the backend with an [`Evaluation`] and writes its results
+//! in the unified schema. `serve` speaks the quality harnesses' line
+//! protocol (see [`subprocess`](crate::subprocess)). A `run-scenarios`
+//! campaign names the binary and the backend, checks the name against
+//! `list`, and runs `serve` like any external backend:
+//!
+//! ```toml
+//! [[registered]]
+//! bin = "../../acme-bench/target/release/acme-bench"
+//! backend = "acme"
+//! ```
+//!
+//! ```no_run
+//! use harness::evaluation::NucleoBackend;
+//! use harness::preset::Preset;
+//! use harness::registry::Registry;
+//!
+//! Registry::new()
+//!     .register("nucleo-autocomplete", |_| {
+//!         Box::new(NucleoBackend::new(Preset::load("autocomplete").unwrap()))
+//!     })
+//!     .main();
+//! ```
+
+use crate::cli::{flag_value, has_flag, parsed_flag};
+use crate::corpus::{load_corpus_mapped, Corpus, FieldMap};
+use crate::evaluation::{Evaluation, MatcherBackend, NucleoBackend, RECORDED_RESULTS};
+use crate::preset::Preset;
+use crate::queries::{Query, SKIP};
+use crate::resource_path;
+use crate::warmup::Warmup;
+use std::env;
+use std::io::{self, Read, Write};
+
+/// Builds a backend for the preset given on the command line, which a
+/// backend that has no use for nucleo's settings ignores.
+pub type Factory = Box<dyn Fn(&Preset) -> Box<dyn MatcherBackend>>;
+
+/// Backends by name, in the order they were registered.
+pub struct Registry {
+    backends: Vec<(String, Factory)>,
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        Registry::new()
+    }
+}
+
+impl Registry {
+    /// The backends built into the harness: `nucleo`.
+    pub fn new() -> Registry {
+        Registry {
+            backends: Vec::new(),
+        }
+        .register("nucleo", |preset| {
+            Box::new(NucleoBackend::new(preset.clone()))
+        })
+    }
+
+    /// Adds `factory` under `name`.
+    ///
+    /// # Panics
+    ///
+    /// When `name` is already registered, built-in backends included, so a
+    /// result file's backend always names the same matcher.
+    pub fn register(
+        mut self,
+        name: &str,
+        factory: impl Fn(&Preset) -> Box<dyn MatcherBackend> + 'static,
+    ) -> Self {
+        assert!(
+            !self.backends.iter().any(|(n, _)| n == name),
+            "Backend {name} is already registered"
+        );
+        self.backends.push((name.to_string(), Box::new(factory)));
+        self
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.backends
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect()
+    }
+
+    /// A new instance of the backend `name` under `preset`.
+    pub fn create(&self, name: &str, preset: &Preset) -> Result<Box<dyn MatcherBackend>, String> {
+        self.backends
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, factory)| factory(preset))
+            .ok_or_else(|| {
+                format!(
+                    "unknown backend {name}; registered: {}",
+                    self.names().join(", ")
+                )
+            })
+    }
+
+    /// Runs the command line of the process, panicking on a usage error or
+    /// one the command ran into.
+    pub fn main(&self) {
+        let args: Vec<String> = env::args().collect();
+        if let Err(e) = self.run(&args) {
+            panic!("{e}");
+        }
+    }
+
+    /// Runs `args`, the program name first, as [`main`](Registry::main)
+    /// would, with the protocol read from stdin and written to stdout.
+    pub fn run(&self, args: &[String]) -> Result<(), String> {
+        let usage = || {
+            format!(
+                "Usage: {} list | bench BACKEND [flags] | serve BACKEND [flags] CORPUS",
+                args.first().map_or("registry", String::as_str)
+            )
+        };
+        let command = args.get(1).ok_or_else(usage)?;
+        if command == "list" {
+            for name in self.names() {
+                println!("{name}");
+            }
+            return Ok(());
+        }
+        let name = args
+            .get(2)
+            .filter(|a| !a.starts_with("--"))
+            .ok_or_else(usage)?;
+        let preset = Preset::from_args(args).map_err(|e| format!("Invalid preset: {e}"))?;
+        let mut backend = self.create(name, &preset)?;
+        let map: FieldMap = parsed_flag(args, "--map", FieldMap::default());
+        match command.as_str() {
+            "bench" => bench(backend.as_mut(), args, map),
+            "serve" => {
+                let corpus_path = args.last().filter(|_| args.len() > 3).ok_or_else(usage)?;
+                let corpus = load_corpus_mapped(corpus_path, &map)
+                    .map_err(|e| format!("Failed to read corpus {corpus_path}: {e}"))?;
+                let mut input = String::new();
+                io::stdin()
+                    .read_to_string(&mut input)
+                    .map_err(|e| format!("Failed to read queries: {e}"))?;
+                serve(backend.as_mut(), &corpus, &input, &mut io::stdout().lock())
+                    .map_err(|e| format!("Failed to write results: {e}"))
+            }
+            _ => Err(usage()),
+        }
+    }
+}
+
+/// Times `backend` as `bench-nucleo` times nucleo, printing a summary and
+/// writing the results to `--results` when given.
+fn bench(backend: &mut dyn MatcherBackend, args: &[String], map: FieldMap) -> Result<(), String> {
+    let corpus = flag_value(args, "--tsv")
+        .map(str::to_string)
+        .unwrap_or_else(|| resource_path("instruments-export.tsv"));
+    let queries = flag_value(args, "--queries")
+        .map(str::to_string)
+        .unwrap_or_else(|| resource_path("queries.tsv"));
+    let warmup = Warmup {
+        queries: match flag_value(args, "--warmup-queries") {
+            Some(n) => Warmup::parse_queries(n)
+                .map_err(|e| format!("--warmup-queries has an invalid value: {e}"))?,
+            None => Warmup::IN_PROCESS.queries,
+        },
+        index: has_flag(args, "--warmup-index"),
+        discard_first: has_flag(args, "--discard-first-pass"),
+    };
+    let results = Evaluation::new(&corpus, &queries)
+        .map(map)
+        .iterations(parsed_flag(args, "--iterations", 5))
+        .warmup(warmup)
+        .run_label(flag_value(args, "--run").unwrap_or(""))
+        .run(backend)
+        .map_err(|e| format!("Failed to run {}: {e}", backend.name()))?;
+    if let Some(steady) = &results.steady_state {
+        println!(
+            "{}: {} queries, median pass {:.2} ms over {} passes",
+            results.label(),
+            steady.queries,
+            steady.pass_median_ms,
+            steady.iterations
+        );
+    }
+    if let Some(path) = flag_value(args, "--results") {
+        results
+            .write(path)
+            .map_err(|e| format!("Failed to write results to {path}: {e}"))?;
+        println!("Results written to {path}");
+    }
+    Ok(())
+}
+
+/// Answers the `query<TAB>field` lines of `input` with `backend`, writing
+/// each query's results as the quality harnesses print them. The whole
+/// query set is read before the backend is prepared, since preparing may
+/// depend on the fields queried. A third, filter field is ignored: the
+/// registry has no way to hand it to a backend.
+pub fn serve(
+    backend: &mut dyn MatcherBackend,
+    corpus: &Corpus,
+    input: &str,
+    out: &mut dyn Write,
+) -> io::Result<()> {
+    let queries: Vec<Query> = input
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.trim_end_matches('\r').splitn(3, '\t');
+            let (text, field) = (parts.next()?, parts.next()?);
+            Some(Query {
+                text: text.to_string(),
+                field: field.to_string(),
+                category: String::new(),
+                expected_name: SKIP.to_string(),
+                frequency: 1,
+            })
+        })
+        .collect();
+    backend.prepare(corpus, &queries);
+    for query in &queries {
+        let search = backend.search(query, RECORDED_RESULTS);
+        for (rank, (row, score)) in search.top.iter().enumerate() {
+            let instrument = corpus.get(*row);
+            let score = score.map_or_else(|| "-".to_string(), |s| s.to_string());
+            writeln!(
+                out,
+                "{}\t{}\t{}\t{}\t{}\t{}\tline={}",
+                query.text,
+                query.field,
+                rank + 1,
+                score,
+                instrument.symbol,
+                instrument.name,
+                instrument.line
+            )?;
+        }
+    }
+    out.flush()
+}
@@ -0,0 +1,118 @@
+//! Search logs read back as a stream of timed arrivals.
+//!
+//! A benchmark that sends queries back to back measures a saturated
+//! server. Real traffic comes in bursts and lulls, and a request that
+//! arrives in a burst waits behind the others. Replaying a log at its
+//! recorded pace reproduces that. [`load_log`] reads the log `query-import`
+//! reads (a CSV with a header row and the columns `timestamp, raw query`,
+//! then anything else) and returns each search with its arrival time,
+//! measured from the first search in the log.
+//!
+//! Timestamps may be Unix seconds, with or without a fraction, Unix
+//! milliseconds (13 digits or more), or ISO 8601 date-times such as
+//! `2024-03-01T09:30:00.250Z` or `2024-03-01 09:30:00+01:00`. A date-time
+//! with no offset is taken as UTC. The log need not be sorted.
+
+use crate::input;
+use std::time::Duration;
+
+/// One search of a log.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Arrival {
+    /// Time since the log's first search.
+    pub at: Duration,
+    pub text: String,
+}
+
+/// Seconds since the Unix epoch, or `None` for a timestamp in none of the
+/// accepted forms.
+pub fn parse_timestamp(text: &str) -> Option<f64> {
+    let text = text.trim();
+    if let Ok(number) = text.parse::<f64>() {
+        let digits = text.split('.').next().unwrap_or("").len();
+        return Some(if digits >= 13 {
+            number / 1000.0
+        } else {
+            number
+        });
+    }
+    let (date, time) = text.split_once(['T', ' '])?;
+    let mut parts = date.splitn(3, '-').map(str::parse::<i64>);
+    let (year, month, day) = (
+        parts.next()?.ok()?,
+        parts.next()?.ok()?,
+        parts.next()?.ok()?,
+    );
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    let (clock, offset_s) = split_offset(time)?;
+    let mut fields = clock.splitn(3, ':');
+    let hours: f64 = fields.next()?.parse().ok()?;
+    let minutes: f64 = fields.next()?.parse().ok()?;
+    let seconds: f64 = fields.next().map_or(Some(0.0), |s| s.parse().ok())?;
+    let days = days_from_civil(year, month, day) as f64;
+    Some(days * 86_400.0 + hours * 3600.0 + minutes * 60.0 + seconds - offset_s)
+}
+
+/// The searches in the log at `path`, by arrival. Rows with an empty query
+/// are skipped, and so is the log's header row.
+pub fn load_log(path: &str) -> Result<Vec<Arrival>, String> {
+    let log = input::open(path).map_err(|e| format!("{path}: {e}"))?;
+    let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(log);
+    let mut timed: Vec<(f64, String)> = Vec::new();
+    for (row, record) in reader.records().enumerate() {
+        let record = record.map_err(|e| format!("{path}: {e}"))?;
+        let text = record
+            .get(1)
+            .unwrap_or("")
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        if text.is_empty() {
+            continue;
+        }
+        let stamp = record.get(0).unwrap_or("");
+        let seconds = parse_timestamp(stamp)
+            .ok_or_else(|| format!("{path}: line {}: cannot read timestamp {stamp:?}", row + 2))?;
+        timed.push((seconds, text));
+    }
+    timed.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let first = timed.first().map_or(0.0, |(seconds, _)| *seconds);
+    Ok(timed
+        .into_iter()
+        .map(|(seconds, text)| Arrival {
+            at: Duration::from_secs_f64(seconds - first),
+            text,
+        })
+        .collect())
+}
+
+/// The clock time and the UTC offset in seconds of an ISO 8601 time.
+fn split_offset(time: &str) -> Option<(&str, f64)> {
+    if let Some(clock) = time.strip_suffix(['Z', 'z']) {
+        return Some((clock, 0.0));
+    }
+    let Some(at) = time.rfind(['+', '-']) else {
+        return Some((time, 0.0));
+    };
+    let (clock, offset) = time.split_at(at);
+    let sign = if offset.starts_with('-') { -1.0 } else { 1.0 };
+    let offset = offset[1..].replace(':', "");
+    if offset.len() != 4 {
+        return None;
+    }
+    let hours: f64 = offset[..2].parse().ok()?;
+    let minutes: f64 = offset[2..].parse().ok()?;
+    Some((clock, sign * (hours * 3600.0 + minutes * 60.0)))
+}
+
+/// Days from 1970-01-01 to a proleptic Gregorian date.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
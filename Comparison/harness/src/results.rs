@@ -0,0 +1,518 @@
+//! Per-query results in one schema shared by every backend.
+//!
+//! A harness run saves what it measured as a single JSON document: the
+//! backend, a run label (a branch or commit, say), the corpus, and for each
+//! query its latency and top results where the run recorded them. Benchmark
+//! runs fill in the timings, quality runs the result lists, and some runs
+//! both. The `report` binary compares any number of these files.
+//!
+//! The optional `fingerprint` records what produced the run (see
+//! [`Fingerprint`]); files written before it existed simply lack it.
+//!
+//! Benchmark runs also split their cost in two. `startup` holds the one-off
+//! phases paid before the first query (loading the corpus, preparing it,
+//! building an index), and `steady_state` the time of whole passes over the
+//! query set once warmed up. A backend that builds a heavy index to answer
+//! queries quickly looks very different in each.
+//!
+//! Memory runs have no queries; `memory` lists the [`Footprint`] of each
+//! representation of the candidates they measured, at each corpus size.
+//!
+//! ```json
+//! {
+//!   "schema_version": 1,
+//!   "backend": "nucleo",
+//!   "run": "main",
+//!   "corpus": "instruments-export.tsv",
+//!   "corpus_sha256": "…",
+//!   "fingerprint": {"git_sha": "…", "git_dirty": false, "features": ["store"],
+//!                   "config": {"case": "smart", …}, "config_sha256": "…"},
+//!   "startup": {"phases": [{"name": "load", "ms": 812.0},
+//!                          {"name": "prepare", "ms": 40.1}], "total_ms": 852.1},
+//!   "steady_state": {"iterations": 5, "queries": 197, "pass_median_ms": 3047.7,
+//!                    "pass_min_ms": 3009.2, "per_query_ms": 15.47},
+//!   "memory": [{"representation": "arena", "candidates": 100000,
+//!               "heap_bytes": 4518912, "resident_bytes": 4603904}],
+//!   "queries": [
+//!     {"query": "aple", "field": "name", "category": "typo",
+//!      "expected_name": "Apple", "frequency": 1,
+//!      "median_ms": 4.2, "min_ms": 4.0, "matches": 812,
+//!      "results": [{"symbol": "AAPL", "name": "Apple Inc.", "score": 140}],
+//!      "recheck": {"iterations": 100, "median_ms": 3.9, "p99_ms": 4.4, …},
+//!      "cost": {"scanned": 52000, "prefiltered": 48210, "scored": 3790, …},
+//!      "difficulty": {"score": 0.41, "char_share": 0.62, "lookalikes": 3, "accepted": 2},
+//!      "snapshot": {"corpus_sha256": "…", "generation": 2}}
+//!   ]
+//! }
+//! ```
+
+use crate::cost::QueryCost;
+use crate::difficulty::Difficulty;
+use crate::fingerprint::Fingerprint;
+use crate::metrics::percentile;
+use crate::mutable::Snapshot;
+use crate::queries::{Query, SKIP};
+use serde_json::{json, Map, Value};
+use std::fs;
+use std::io;
+
+pub const SCHEMA_VERSION: u64 = 1;
+
+/// One ranked result of a query.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Hit {
+    pub symbol: String,
+    pub name: String,
+    /// Backend-specific score; not comparable across backends.
+    pub score: Option<f64>,
+}
+
+/// What a run recorded for one query.
+#[derive(Clone, Debug, PartialEq)]
+pub struct QueryResult {
+    pub query: String,
+    pub field: String,
+    pub category: String,
+    pub expected_name: String,
+    pub frequency: u64,
+    pub median_ms: Option<f64>,
+    pub min_ms: Option<f64>,
+    /// Candidates the backend matched at all.
+    pub matches: Option<usize>,
+    /// Top results, best first, or `None` when the run did not record them.
+    pub results: Option<Vec<Hit>>,
+    /// Refined timing, for the slow queries a run re-checked.
+    pub recheck: Option<Recheck>,
+    /// Work the scan did, when the run counted it.
+    pub cost: Option<QueryCost>,
+    /// How hard the query is on this corpus, when the run estimated it.
+    pub difficulty: Option<Difficulty>,
+    /// The corpus state a server answered from, for runs against one whose
+    /// rows change while it serves.
+    pub snapshot: Option<Snapshot>,
+}
+
+impl QueryResult {
+    /// An empty record for `query`, to be filled in by the run.
+    pub fn new(query: &Query) -> QueryResult {
+        QueryResult {
+            query: query.text.clone(),
+            field: query.field.clone(),
+            category: query.category.clone(),
+            expected_name: query.expected_name.clone(),
+            frequency: query.frequency,
+            median_ms: None,
+            min_ms: None,
+            matches: None,
+            results: None,
+            recheck: None,
+            cost: None,
+            difficulty: None,
+            snapshot: None,
+        }
+    }
+
+    /// Whether the expected name appears, as a case-insensitive substring,
+    /// within the first [`ground_truth_depth`] results.
+    ///
+    /// `None` when the query has no expected name or the run recorded no
+    /// results.
+    pub fn found(&self) -> Option<bool> {
+        if self.expected_name.is_empty() || self.expected_name == SKIP {
+            return None;
+        }
+        let expected = self.expected_name.to_lowercase();
+        let results = self.results.as_ref()?;
+        Some(
+            results
+                .iter()
+                .take(ground_truth_depth(&self.category))
+                .any(|hit| hit.name.to_lowercase().contains(&expected)),
+        )
+    }
+
+    fn to_json(&self) -> Value {
+        let mut object = Map::new();
+        object.insert("query".into(), json!(self.query));
+        object.insert("field".into(), json!(self.field));
+        object.insert("category".into(), json!(self.category));
+        object.insert("expected_name".into(), json!(self.expected_name));
+        object.insert("frequency".into(), json!(self.frequency));
+        if let Some(ms) = self.median_ms {
+            object.insert("median_ms".into(), json!(ms));
+        }
+        if let Some(ms) = self.min_ms {
+            object.insert("min_ms".into(), json!(ms));
+        }
+        if let Some(matches) = self.matches {
+            object.insert("matches".into(), json!(matches));
+        }
+        if let Some(results) = &self.results {
+            object.insert("results".into(), hits_to_json(results));
+        }
+        if let Some(recheck) = &self.recheck {
+            object.insert("recheck".into(), recheck.to_json());
+        }
+        if let Some(cost) = self.cost {
+            object.insert("cost".into(), cost.to_json());
+        }
+        if let Some(difficulty) = &self.difficulty {
+            object.insert("difficulty".into(), difficulty.to_json());
+        }
+        if let Some(snapshot) = &self.snapshot {
+            object.insert("snapshot".into(), snapshot.to_json());
+        }
+        Value::Object(object)
+    }
+
+    fn from_json(value: &Value) -> Option<QueryResult> {
+        let text = |key: &str| value.get(key).and_then(Value::as_str).map(String::from);
+        let results = match value.get("results") {
+            Some(hits) => Some(hits_from_json(hits)?),
+            None => None,
+        };
+        Some(QueryResult {
+            query: text("query")?,
+            field: text("field")?,
+            category: text("category").unwrap_or_else(|| "other".to_string()),
+            expected_name: text("expected_name").unwrap_or_else(|| SKIP.to_string()),
+            frequency: value.get("frequency").and_then(Value::as_u64).unwrap_or(1),
+            median_ms: value.get("median_ms").and_then(Value::as_f64),
+            min_ms: value.get("min_ms").and_then(Value::as_f64),
+            matches: value
+                .get("matches")
+                .and_then(Value::as_u64)
+                .map(|m| m as usize),
+            results,
+            recheck: value.get("recheck").and_then(Recheck::from_json),
+            cost: value.get("cost").and_then(QueryCost::from_json),
+            difficulty: value.get("difficulty").and_then(Difficulty::from_json),
+            snapshot: value.get("snapshot").and_then(Snapshot::from_json),
+        })
+    }
+}
+
+/// Timing of one query re-run on its own, many more times than the main
+/// pass, to tell a genuinely slow query from one that met a noisy moment.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Recheck {
+    pub iterations: usize,
+    pub min_ms: f64,
+    pub median_ms: f64,
+    pub p90_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+    pub stddev_ms: f64,
+    /// Path of a flamegraph SVG of the re-run, when one was captured.
+    pub flamegraph: Option<String>,
+}
+
+impl Recheck {
+    /// Summarises per-iteration timings; `None` for no timings.
+    pub fn from_timings(timings_ms: &[f64]) -> Option<Recheck> {
+        let n = timings_ms.len() as f64;
+        let mean = timings_ms.iter().sum::<f64>() / n;
+        let variance = timings_ms.iter().map(|t| (t - mean).powi(2)).sum::<f64>() / n;
+        Some(Recheck {
+            iterations: timings_ms.len(),
+            min_ms: percentile(timings_ms, 0.0)?,
+            median_ms: percentile(timings_ms, 50.0)?,
+            p90_ms: percentile(timings_ms, 90.0)?,
+            p99_ms: percentile(timings_ms, 99.0)?,
+            max_ms: percentile(timings_ms, 100.0)?,
+            stddev_ms: variance.sqrt(),
+            flamegraph: None,
+        })
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "iterations": self.iterations,
+            "min_ms": self.min_ms,
+            "median_ms": self.median_ms,
+            "p90_ms": self.p90_ms,
+            "p99_ms": self.p99_ms,
+            "max_ms": self.max_ms,
+            "stddev_ms": self.stddev_ms,
+            "flamegraph": self.flamegraph,
+        })
+    }
+
+    fn from_json(value: &Value) -> Option<Recheck> {
+        let ms = |key: &str| value.get(key).and_then(Value::as_f64);
+        Some(Recheck {
+            iterations: value.get("iterations")?.as_u64()? as usize,
+            min_ms: ms("min_ms")?,
+            median_ms: ms("median_ms")?,
+            p90_ms: ms("p90_ms")?,
+            p99_ms: ms("p99_ms")?,
+            max_ms: ms("max_ms")?,
+            stddev_ms: ms("stddev_ms")?,
+            flamegraph: value
+                .get("flamegraph")
+                .and_then(Value::as_str)
+                .map(String::from),
+        })
+    }
+}
+
+/// What a run spent before it could answer its first query, phase by
+/// phase in the order they ran.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Startup {
+    /// Phase names (`load`, `prepare`, `index`, ...) with their time in
+    /// milliseconds.
+    pub phases: Vec<(String, f64)>,
+}
+
+impl Startup {
+    /// Records `ms` for `phase`, after those already recorded.
+    pub fn push(&mut self, phase: &str, ms: f64) {
+        self.phases.push((phase.to_string(), ms));
+    }
+
+    /// Time spent in `phase`, if the run had one.
+    pub fn phase_ms(&self, phase: &str) -> Option<f64> {
+        self.phases
+            .iter()
+            .find(|(name, _)| name == phase)
+            .map(|(_, ms)| *ms)
+    }
+
+    pub fn total_ms(&self) -> f64 {
+        self.phases.iter().map(|(_, ms)| ms).sum()
+    }
+
+    pub(crate) fn to_json(&self) -> Value {
+        json!({
+            "phases": self
+                .phases
+                .iter()
+                .map(|(name, ms)| json!({"name": name, "ms": ms}))
+                .collect::<Vec<_>>(),
+            "total_ms": self.total_ms(),
+        })
+    }
+
+    pub(crate) fn from_json(value: &Value) -> Option<Startup> {
+        let phases = value
+            .get("phases")?
+            .as_array()?
+            .iter()
+            .map(|phase| {
+                Some((
+                    phase.get("name")?.as_str()?.to_string(),
+                    phase.get("ms")?.as_f64()?,
+                ))
+            })
+            .collect::<Option<_>>()?;
+        Some(Startup { phases })
+    }
+}
+
+/// Timed passes over the whole query set, after warm-up. Unlike the sum of
+/// per-query medians, a pass total includes whatever a backend does
+/// between queries.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SteadyState {
+    pub iterations: usize,
+    pub queries: usize,
+    pub pass_median_ms: f64,
+    pub pass_min_ms: f64,
+}
+
+impl SteadyState {
+    /// Summarises the totals of each timed pass over `queries` queries;
+    /// `None` for no passes.
+    pub fn from_passes(pass_totals_ms: &[f64], queries: usize) -> Option<SteadyState> {
+        Some(SteadyState {
+            iterations: pass_totals_ms.len(),
+            queries,
+            pass_median_ms: percentile(pass_totals_ms, 50.0)?,
+            pass_min_ms: percentile(pass_totals_ms, 0.0)?,
+        })
+    }
+
+    /// Median cost of one query in a steady pass.
+    pub fn per_query_ms(&self) -> f64 {
+        self.pass_median_ms / self.queries.max(1) as f64
+    }
+
+    pub(crate) fn to_json(&self) -> Value {
+        json!({
+            "iterations": self.iterations,
+            "queries": self.queries,
+            "pass_median_ms": self.pass_median_ms,
+            "pass_min_ms": self.pass_min_ms,
+            "per_query_ms": self.per_query_ms(),
+        })
+    }
+
+    pub(crate) fn from_json(value: &Value) -> Option<SteadyState> {
+        Some(SteadyState {
+            iterations: value.get("iterations")?.as_u64()? as usize,
+            queries: value.get("queries")?.as_u64()? as usize,
+            pass_median_ms: value.get("pass_median_ms")?.as_f64()?,
+            pass_min_ms: value.get("pass_min_ms")?.as_f64()?,
+        })
+    }
+}
+
+/// Memory one representation of a corpus's candidates holds.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Footprint {
+    /// `strings`, `arena`, `utf32`, `serialized`, ...
+    pub representation: String,
+    pub candidates: usize,
+    /// Live heap bytes held once built.
+    pub heap_bytes: u64,
+    /// Growth of the resident set while building, where the system
+    /// reports it.
+    pub resident_bytes: Option<u64>,
+}
+
+impl Footprint {
+    pub fn heap_bytes_per_candidate(&self) -> f64 {
+        self.heap_bytes as f64 / self.candidates.max(1) as f64
+    }
+
+    pub(crate) fn to_json(&self) -> Value {
+        json!({
+            "representation": self.representation,
+            "candidates": self.candidates,
+            "heap_bytes": self.heap_bytes,
+            "resident_bytes": self.resident_bytes,
+        })
+    }
+
+    pub(crate) fn from_json(value: &Value) -> Option<Footprint> {
+        Some(Footprint {
+            representation: value.get("representation")?.as_str()?.to_string(),
+            candidates: value.get("candidates")?.as_u64()? as usize,
+            heap_bytes: value.get("heap_bytes")?.as_u64()?,
+            resident_bytes: value.get("resident_bytes").and_then(Value::as_u64),
+        })
+    }
+}
+
+pub(crate) fn hits_to_json(hits: &[Hit]) -> Value {
+    hits.iter()
+        .map(|hit| json!({"symbol": hit.symbol, "name": hit.name, "score": hit.score}))
+        .collect()
+}
+
+pub(crate) fn hits_from_json(value: &Value) -> Option<Vec<Hit>> {
+    value
+        .as_array()?
+        .iter()
+        .map(|hit| {
+            Some(Hit {
+                symbol: hit.get("symbol")?.as_str()?.to_string(),
+                name: hit.get("name")?.as_str()?.to_string(),
+                score: hit.get("score").and_then(Value::as_f64),
+            })
+        })
+        .collect()
+}
+
+/// How many results a category's ground-truth check looks at: typo,
+/// prefix and abbreviation queries are ambiguous enough that the expected
+/// instrument counts anywhere in the top 5, every other category needs it
+/// first. Matches `run-quality.py`.
+pub fn ground_truth_depth(category: &str) -> usize {
+    match category {
+        "typo" | "prefix" | "abbreviation" => 5,
+        _ => 1,
+    }
+}
+
+/// Everything one run of one backend recorded.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ResultSet {
+    pub backend: String,
+    /// Free-form run label, such as a branch name or commit.
+    pub run: String,
+    pub corpus: Option<String>,
+    pub corpus_sha256: Option<String>,
+    /// Revision, features and matcher configuration of the run, when known.
+    pub fingerprint: Option<Fingerprint>,
+    /// One-off costs before the first query, for benchmark runs.
+    pub startup: Option<Startup>,
+    /// Cost of warmed-up passes over the whole query set, for benchmark
+    /// runs that timed every query in the file.
+    pub steady_state: Option<SteadyState>,
+    /// Footprints measured by memory runs; empty for other runs.
+    pub memory: Vec<Footprint>,
+    pub queries: Vec<QueryResult>,
+}
+
+impl ResultSet {
+    /// `backend` or `backend (run)`, for report headings.
+    pub fn label(&self) -> String {
+        if self.run.is_empty() {
+            self.backend.clone()
+        } else {
+            format!("{} ({})", self.backend, self.run)
+        }
+    }
+
+    pub fn write(&self, path: &str) -> io::Result<()> {
+        let value = json!({
+            "schema_version": SCHEMA_VERSION,
+            "backend": self.backend,
+            "run": self.run,
+            "corpus": self.corpus,
+            "corpus_sha256": self.corpus_sha256,
+            "fingerprint": self.fingerprint.as_ref().map(Fingerprint::to_json),
+            "startup": self.startup.as_ref().map(Startup::to_json),
+            "steady_state": self.steady_state.as_ref().map(SteadyState::to_json),
+            "memory": self.memory.iter().map(Footprint::to_json).collect::<Vec<_>>(),
+            "queries": self.queries.iter().map(QueryResult::to_json).collect::<Vec<_>>(),
+        });
+        fs::write(path, serde_json::to_string_pretty(&value)? + "\n")
+    }
+
+    /// Reads a results file. A missing field or a newer schema version is
+    /// an [`io::ErrorKind::InvalidData`] error.
+    pub fn read(path: &str) -> io::Result<ResultSet> {
+        let value: Value = serde_json::from_str(&fs::read_to_string(path)?)?;
+        let version = value.get("schema_version").and_then(Value::as_u64);
+        if version.is_some_and(|v| v > SCHEMA_VERSION) {
+            return Err(invalid(format!(
+                "{path}: schema version {} is newer than this harness supports ({SCHEMA_VERSION})",
+                version.unwrap()
+            )));
+        }
+        ResultSet::from_json(&value).ok_or_else(|| invalid(format!("{path}: not a results file")))
+    }
+
+    fn from_json(value: &Value) -> Option<ResultSet> {
+        let text = |key: &str| value.get(key).and_then(Value::as_str).map(String::from);
+        Some(ResultSet {
+            backend: text("backend")?,
+            run: text("run").unwrap_or_default(),
+            corpus: text("corpus"),
+            corpus_sha256: text("corpus_sha256"),
+            fingerprint: value.get("fingerprint").and_then(Fingerprint::from_json),
+            startup: value.get("startup").and_then(Startup::from_json),
+            steady_state: value.get("steady_state").and_then(SteadyState::from_json),
+            memory: match value.get("memory").and_then(Value::as_array) {
+                Some(footprints) => footprints
+                    .iter()
+                    .map(Footprint::from_json)
+                    .collect::<Option<_>>()?,
+                None => Vec::new(),
+            },
+            queries: value
+                .get("queries")?
+                .as_array()?
+                .iter()
+                .map(QueryResult::from_json)
+                .collect::<Option<_>>()?,
+        })
+    }
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
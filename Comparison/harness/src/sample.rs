@@ -0,0 +1,127 @@
+//! Stratified corpus sampling.
+//!
+//! Prefilter and scoring costs depend mostly on how long candidates are and
+//! which scripts they use, so a uniform random subset of a corpus dominated
+//! by short option codes can lose the few long fund names or CJK names that
+//! drive the tail. Sampling within strata keeps every combination at its
+//! share of the full corpus, and keeps at least one row of each.
+
+use crate::corpus::{Corpus, Instrument};
+use crate::rng::Rng;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Coarse script class of a candidate string.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Script {
+    Ascii,
+    /// Latin with diacritics (`Münchener`, `Société`).
+    Latin,
+    /// Kana, CJK ideographs and Hangul.
+    Cjk,
+    Other,
+}
+
+impl Script {
+    /// The most "exotic" class any character of `text` falls into.
+    pub fn of(text: &str) -> Script {
+        text.chars()
+            .map(Script::of_char)
+            .max()
+            .unwrap_or(Script::Ascii)
+    }
+
+    fn of_char(c: char) -> Script {
+        match c as u32 {
+            0..=0x7F => Script::Ascii,
+            0x80..=0x024F | 0x1E00..=0x1EFF => Script::Latin,
+            0x3040..=0x30FF | 0x4E00..=0x9FFF | 0xAC00..=0xD7A3 => Script::Cjk,
+            _ => Script::Other,
+        }
+    }
+}
+
+/// Upper bounds, in characters, of the candidate length buckets.
+pub const LENGTH_BUCKETS: [usize; 5] = [4, 8, 16, 32, usize::MAX];
+
+/// Index into [`LENGTH_BUCKETS`] for a string of `len` characters.
+pub fn length_bucket(len: usize) -> usize {
+    LENGTH_BUCKETS.iter().position(|&max| len <= max).unwrap()
+}
+
+/// The stratum an instrument is sampled in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Stratum {
+    pub symbol_length: usize,
+    pub name_length: usize,
+    pub script: Script,
+    pub has_isin: bool,
+}
+
+impl Stratum {
+    pub fn of(inst: &Instrument) -> Stratum {
+        Stratum {
+            symbol_length: length_bucket(inst.symbol.chars().count()),
+            name_length: length_bucket(inst.name.chars().count()),
+            script: Script::of(inst.name).max(Script::of(inst.symbol)),
+            has_isin: !inst.isin.is_empty(),
+        }
+    }
+}
+
+impl fmt::Display for Stratum {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bucket = |b: usize| match b {
+            0 => "0-4".to_string(),
+            b if b + 1 == LENGTH_BUCKETS.len() => format!("{}+", LENGTH_BUCKETS[b - 1] + 1),
+            b => format!("{}-{}", LENGTH_BUCKETS[b - 1] + 1, LENGTH_BUCKETS[b]),
+        };
+        write!(
+            f,
+            "symbol {} name {} {:?}{}",
+            bucket(self.symbol_length),
+            bucket(self.name_length),
+            self.script,
+            if self.has_isin { "" } else { " no-isin" }
+        )
+    }
+}
+
+/// Groups instrument indices by stratum.
+pub fn strata(corpus: &Corpus) -> BTreeMap<Stratum, Vec<usize>> {
+    let mut strata: BTreeMap<Stratum, Vec<usize>> = BTreeMap::new();
+    for (idx, inst) in corpus.iter().enumerate() {
+        strata.entry(Stratum::of(&inst)).or_default().push(idx);
+    }
+    strata
+}
+
+/// Picks about `n` instrument indices, allocated to strata in proportion to
+/// their size (largest remainder) with at least one per stratum.
+///
+/// Indices are returned in corpus order. With fewer rows than `n` the whole
+/// corpus is returned; with more strata than `n` the result has one row per
+/// stratum.
+pub fn stratified_sample(corpus: &Corpus, n: usize, rng: &mut Rng) -> Vec<usize> {
+    if n >= corpus.len() {
+        return (0..corpus.len()).collect();
+    }
+    let strata: Vec<Vec<usize>> = strata(corpus).into_values().collect();
+    let total = corpus.len();
+
+    let mut quotas: Vec<usize> = strata.iter().map(|s| s.len() * n / total).collect();
+    let mut order: Vec<usize> = (0..strata.len()).collect();
+    order.sort_by_key(|&i| std::cmp::Reverse(strata[i].len() * n % total));
+    let assigned: usize = quotas.iter().sum();
+    for &i in order.iter().take(n - assigned) {
+        quotas[i] += 1;
+    }
+
+    let mut picked = Vec::with_capacity(n);
+    for (mut members, quota) in strata.into_iter().zip(quotas) {
+        rng.shuffle(&mut members);
+        picked.extend(members.into_iter().take(quota.max(1)));
+    }
+    picked.sort_unstable();
+    picked
+}
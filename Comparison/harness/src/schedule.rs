@@ -0,0 +1,91 @@
+//! Running independent benchmark tasks side by side within a CPU and memory
+//! budget.
+//!
+//! A [`Scheduler`] hands out slots, at most one per job the budget allows.
+//! A task keeps its slot from start to finish, and the slot number is the
+//! core it is pinned to (see [`crate::affinity`]), so no two running tasks
+//! share a core and each one's timings are its own. Each task reserves the
+//! memory it is expected to need. A task starts only when its reservation,
+//! the reservations of the tasks running and the memory already in use all
+//! fit the budget. Tasks that do not fit wait, and smaller ones behind
+//! them may go first. A task too big for the budget on its own still runs
+//! once nothing else is running, so a tight budget slows a campaign down
+//! but never stalls it.
+
+/// How much of the machine tasks may use at once.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Budget {
+    /// Tasks running at once, each on a core of its own.
+    pub jobs: usize,
+    /// Memory for the tasks' reservations and what is already in use, or
+    /// `None` for no memory budget.
+    pub memory_bytes: Option<u64>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Scheduler {
+    budget: Budget,
+    /// The reservation of the task in each slot, `None` when it is free.
+    slots: Vec<Option<u64>>,
+}
+
+impl Scheduler {
+    pub fn new(budget: Budget) -> Scheduler {
+        Scheduler {
+            slots: vec![None; budget.jobs.max(1)],
+            budget,
+        }
+    }
+
+    /// Which of the `waiting` tasks, given as their reservations, to start
+    /// next, with `in_use` bytes already taken outside the running tasks'
+    /// reservations. `None` when every slot is busy or nothing waiting fits.
+    pub fn next(&self, waiting: &[u64], in_use: u64) -> Option<usize> {
+        if waiting.is_empty() || self.slots.iter().all(Option::is_some) {
+            return None;
+        }
+        if self.is_idle() {
+            // Something must run, or a task bigger than the budget would
+            // wait forever
+            return Some(self.first_fitting(waiting, in_use).unwrap_or(0));
+        }
+        self.first_fitting(waiting, in_use)
+    }
+
+    /// Takes the lowest free slot for a task reserving `reservation` bytes
+    /// and returns it.
+    pub fn start(&mut self, reservation: u64) -> usize {
+        let slot = self
+            .slots
+            .iter()
+            .position(Option::is_none)
+            .expect("started a task with no free slot");
+        self.slots[slot] = Some(reservation);
+        slot
+    }
+
+    /// Frees the slot of a finished task.
+    pub fn finish(&mut self, slot: usize) {
+        self.slots[slot] = None;
+    }
+
+    /// Whether no task is running.
+    pub fn is_idle(&self) -> bool {
+        self.slots.iter().all(Option::is_none)
+    }
+
+    /// Bytes reserved by the running tasks.
+    pub fn reserved(&self) -> u64 {
+        self.slots.iter().flatten().sum()
+    }
+
+    fn first_fitting(&self, waiting: &[u64], in_use: u64) -> Option<usize> {
+        let Some(budget) = self.budget.memory_bytes else {
+            return Some(0);
+        };
+        let taken = self.reserved() + in_use;
+        waiting
+            .iter()
+            .position(|&reservation| taken + reservation <= budget)
+    }
+}
@@ -0,0 +1,46 @@
+//! Scratch memory a query's scan needs, for sizing preallocated buffers.
+//!
+//! A scan reuses two growable buffers: the UTF-32 copy of each non-ASCII
+//! candidate that nucleo scores, and the [`TopK`](crate::topk::TopK) heap
+//! of the best matches so far. An app that must not allocate while the
+//! user types, as on mobile, preallocates both, and their high-water marks
+//! over a query set say how large. nucleo's matcher also holds a slab of
+//! fixed size, allocated once with the matcher, which is not counted here.
+
+use std::mem::size_of;
+
+/// The most a scan used of each scratch buffer.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Scratch {
+    /// Longest candidate converted to UTF-32; ASCII candidates are scored
+    /// in place and never use the buffer.
+    pub utf32_chars: usize,
+    /// Entries held in the top-K heap at once.
+    pub heap_entries: usize,
+}
+
+impl Scratch {
+    /// Bytes one heap entry takes: a score and a row.
+    pub const HEAP_ENTRY_BYTES: usize = size_of::<(u32, usize)>();
+
+    pub fn utf32_bytes(&self) -> usize {
+        self.utf32_chars * size_of::<char>()
+    }
+
+    pub fn heap_bytes(&self) -> usize {
+        self.heap_entries * Self::HEAP_ENTRY_BYTES
+    }
+
+    /// Both buffers together.
+    pub fn bytes(&self) -> usize {
+        self.utf32_bytes() + self.heap_bytes()
+    }
+
+    /// The larger of each buffer's high-water mark.
+    pub fn max(self, other: Scratch) -> Scratch {
+        Scratch {
+            utf32_chars: self.utf32_chars.max(other.utf32_chars),
+            heap_entries: self.heap_entries.max(other.heap_entries),
+        }
+    }
+}
@@ -0,0 +1,97 @@
+//! Whether a latency difference between two runs is more than noise.
+//!
+//! Two runs of the same build differ by a few percent from scheduling,
+//! frequency scaling and cache state alone, so a delta on its own says
+//! little. [`Change::of`] takes each shared query's median in both runs
+//! and puts a bootstrap confidence interval on the ratio of their sums:
+//! the queries are resampled with replacement [`RESAMPLES`] times, keeping
+//! each query's two medians together, and the interval is the middle
+//! [`CONFIDENCE`] of the resampled ratios. A change is significant when
+//! that interval leaves out 1, meaning no change.
+//!
+//! Pairing the medians by query matters. Queries differ from each other
+//! far more than one query does between runs, so an unpaired test would
+//! call almost nothing significant. The resampling is seeded, so a report
+//! run twice on the same files says the same thing.
+
+use crate::metrics::percentile;
+use crate::rng::Rng;
+use std::fmt;
+
+/// Bootstrap resamples per interval.
+pub const RESAMPLES: usize = 2000;
+
+/// Share of the resampled ratios inside the interval.
+pub const CONFIDENCE: f64 = 0.95;
+
+const SEED: u64 = 0x5EED;
+
+/// The ratio of current to baseline latency, with its confidence interval.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Change {
+    pub ratio: f64,
+    pub low: f64,
+    pub high: f64,
+}
+
+impl Change {
+    /// The change from the baseline to the current medians of `pairs`,
+    /// given as `(baseline, current)` per query. `None` for fewer than two
+    /// queries, where there is nothing to resample, or a zero baseline.
+    pub fn of(pairs: &[(f64, f64)]) -> Option<Change> {
+        Change::bootstrap(pairs, RESAMPLES, SEED)
+    }
+
+    /// [`Change::of`] with `resamples` resamples drawn from `seed`.
+    pub fn bootstrap(pairs: &[(f64, f64)], resamples: usize, seed: u64) -> Option<Change> {
+        let ratio = ratio_of(pairs.iter())?;
+        if pairs.len() < 2 {
+            return None;
+        }
+        let mut rng = Rng::new(seed);
+        let ratios: Vec<f64> = (0..resamples)
+            .filter_map(|_| ratio_of((0..pairs.len()).map(|_| &pairs[rng.below(pairs.len())])))
+            .collect();
+        let tail = (1.0 - CONFIDENCE) / 2.0 * 100.0;
+        Some(Change {
+            ratio,
+            low: percentile(&ratios, tail)?,
+            high: percentile(&ratios, 100.0 - tail)?,
+        })
+    }
+
+    /// Whether the interval leaves out no change.
+    pub fn significant(&self) -> bool {
+        self.low > 1.0 || self.high < 1.0
+    }
+
+    /// The interval alone, as `95% CI +3.1% to +20.4%`.
+    pub fn interval(&self) -> String {
+        let pct = |ratio: f64| (ratio - 1.0) * 100.0;
+        format!(
+            "{:.0}% CI {:+.1}% to {:+.1}%",
+            CONFIDENCE * 100.0,
+            pct(self.low),
+            pct(self.high)
+        )
+    }
+
+    /// The change in percent, positive when the current run is slower.
+    pub fn percent(&self) -> f64 {
+        (self.ratio - 1.0) * 100.0
+    }
+}
+
+/// `+12.0% (95% CI +3.1% to +20.4%)`.
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:+.1}% ({})", self.percent(), self.interval())
+    }
+}
+
+/// Summed current over summed baseline, or `None` when the baseline sums
+/// to zero.
+fn ratio_of<'a>(pairs: impl Iterator<Item = &'a (f64, f64)>) -> Option<f64> {
+    let (old, new) = pairs.fold((0.0, 0.0), |(old, new), (a, b)| (old + a, new + b));
+    (old > 0.0).then(|| new / old)
+}
@@ -0,0 +1,71 @@
+//! Train/validation splits of query sets.
+//!
+//! Anything tuned against the query set (field weights, thresholds) must be
+//! evaluated on queries it never saw. Splitting within each category keeps
+//! both halves' category mix equal to the full set's, so a validation score
+//! is not skewed by, say, drawing most typo queries into training.
+
+use crate::judgments::Judgment;
+use crate::queries::Query;
+use crate::rng::Rng;
+use std::collections::{BTreeMap, HashSet};
+
+/// Queries assigned to each side of a split.
+pub struct Split {
+    pub train: Vec<Query>,
+    pub validation: Vec<Query>,
+}
+
+impl Split {
+    /// Puts `ratio` of each category's queries, rounded, into validation.
+    ///
+    /// Output keeps the input order within each side. The same seed and
+    /// input always produce the same split.
+    pub fn stratified(queries: &[Query], ratio: f64, rng: &mut Rng) -> Split {
+        let mut by_category: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+        for (idx, q) in queries.iter().enumerate() {
+            by_category.entry(&q.category).or_default().push(idx);
+        }
+        let mut validation_idx: HashSet<usize> = HashSet::new();
+        for members in by_category.values_mut() {
+            rng.shuffle(members);
+            let take = (members.len() as f64 * ratio).round() as usize;
+            validation_idx.extend(members.iter().take(take));
+        }
+
+        let mut split = Split {
+            train: Vec::new(),
+            validation: Vec::new(),
+        };
+        for (idx, q) in queries.iter().enumerate() {
+            if validation_idx.contains(&idx) {
+                split.validation.push(q.clone());
+            } else {
+                split.train.push(q.clone());
+            }
+        }
+        split
+    }
+
+    /// Partitions judgments to follow their query, matched on text and
+    /// field. Judgments for queries in neither side are dropped.
+    pub fn judgments(&self, judgments: &[Judgment]) -> (Vec<Judgment>, Vec<Judgment>) {
+        let keys = |queries: &[Query]| -> HashSet<(String, String)> {
+            queries
+                .iter()
+                .map(|q| (q.text.clone(), q.field.clone()))
+                .collect()
+        };
+        let (train, validation) = (keys(&self.train), keys(&self.validation));
+        let mut out = (Vec::new(), Vec::new());
+        for j in judgments {
+            let key = (j.query.clone(), j.field.clone());
+            if validation.contains(&key) {
+                out.1.push(j.clone());
+            } else if train.contains(&key) {
+                out.0.push(j.clone());
+            }
+        }
+        out
+    }
+}
@@ -0,0 +1,357 @@
+//! A SQLite history of result sets, for spotting slow drifts.
+//!
+//! A single comparison shows what one change did; a regression that creeps
+//! in a few percent at a time only shows up against weeks of runs. The
+//! store keeps every recorded [`ResultSet`] with the time it was recorded,
+//! and summarises runs per category for trend reports.
+//!
+//! Requires the `store` feature.
+
+use crate::fingerprint::Fingerprint;
+use crate::results::{
+    hits_from_json, hits_to_json, Footprint, Hit, QueryResult, ResultSet, Startup, SteadyState,
+};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde_json::Value;
+use std::io;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS runs (
+    id INTEGER PRIMARY KEY,
+    recorded_at INTEGER NOT NULL,
+    backend TEXT NOT NULL,
+    run TEXT NOT NULL,
+    corpus TEXT,
+    corpus_sha256 TEXT,
+    fingerprint TEXT,
+    startup TEXT,
+    steady_state TEXT,
+    memory TEXT
+);
+CREATE TABLE IF NOT EXISTS query_results (
+    run_id INTEGER NOT NULL REFERENCES runs(id),
+    query TEXT NOT NULL,
+    field TEXT NOT NULL,
+    category TEXT NOT NULL,
+    expected_name TEXT NOT NULL,
+    frequency INTEGER NOT NULL,
+    median_ms REAL,
+    min_ms REAL,
+    matches INTEGER,
+    found INTEGER,
+    results TEXT
+);
+CREATE INDEX IF NOT EXISTS query_results_run ON query_results(run_id);
+";
+
+/// A run as listed by the store, without its queries.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StoredRun {
+    pub id: i64,
+    /// Unix seconds.
+    pub recorded_at: u64,
+    pub backend: String,
+    pub run: String,
+    pub corpus: Option<String>,
+    pub corpus_sha256: Option<String>,
+}
+
+impl StoredRun {
+    /// `backend` or `backend (run)`, as [`ResultSet::label`].
+    pub fn label(&self) -> String {
+        if self.run.is_empty() {
+            self.backend.clone()
+        } else {
+            format!("{} ({})", self.backend, self.run)
+        }
+    }
+}
+
+/// One category of one stored run.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CategoryPoint {
+    pub run_id: i64,
+    pub category: String,
+    pub queries: usize,
+    /// Queries with a ground-truth verdict, and how many passed.
+    pub judged: usize,
+    pub found: usize,
+    /// Summed median latency, when any query was timed.
+    pub latency_ms: Option<f64>,
+}
+
+impl CategoryPoint {
+    pub fn hit_rate(&self) -> Option<f64> {
+        (self.judged > 0).then(|| self.found as f64 / self.judged as f64)
+    }
+}
+
+/// Which runs a query over the store covers.
+#[derive(Clone, Debug, Default)]
+pub struct RunFilter {
+    pub backend: Option<String>,
+    /// Matches either the corpus name or its checksum.
+    pub corpus: Option<String>,
+    /// Only the most recent this many runs.
+    pub last: Option<usize>,
+}
+
+pub struct Store {
+    conn: Connection,
+}
+
+impl Store {
+    /// Opens the store at `path`, creating the file and tables if needed.
+    pub fn open(path: &str) -> io::Result<Store> {
+        let conn = Connection::open(path).map_err(sql)?;
+        conn.execute_batch(SCHEMA).map_err(sql)?;
+        // Stores created before these were recorded lack their columns.
+        for column in ["fingerprint", "startup", "steady_state", "memory"] {
+            let has_column: bool = conn
+                .query_row(
+                    "SELECT COUNT(*) > 0 FROM pragma_table_info('runs') WHERE name = ?1",
+                    params![column],
+                    |row| row.get(0),
+                )
+                .map_err(sql)?;
+            if !has_column {
+                conn.execute_batch(&format!("ALTER TABLE runs ADD COLUMN {column} TEXT"))
+                    .map_err(sql)?;
+            }
+        }
+        Ok(Store { conn })
+    }
+
+    /// Appends `results` as recorded at `recorded_at` (Unix seconds) and
+    /// returns the new run's id.
+    pub fn append(&mut self, results: &ResultSet, recorded_at: u64) -> io::Result<i64> {
+        let tx = self.conn.transaction().map_err(sql)?;
+        tx.execute(
+            "INSERT INTO runs (recorded_at, backend, run, corpus, corpus_sha256, fingerprint,
+                               startup, steady_state, memory)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                recorded_at as i64,
+                results.backend,
+                results.run,
+                results.corpus,
+                results.corpus_sha256,
+                results
+                    .fingerprint
+                    .as_ref()
+                    .map(|f| f.to_json().to_string()),
+                results.startup.as_ref().map(|s| s.to_json().to_string()),
+                results
+                    .steady_state
+                    .as_ref()
+                    .map(|s| s.to_json().to_string()),
+                (!results.memory.is_empty()).then(|| {
+                    Value::from_iter(results.memory.iter().map(Footprint::to_json)).to_string()
+                })
+            ],
+        )
+        .map_err(sql)?;
+        let run_id = tx.last_insert_rowid();
+        {
+            let mut insert = tx
+                .prepare(
+                    "INSERT INTO query_results (run_id, query, field, category, expected_name,
+                         frequency, median_ms, min_ms, matches, found, results)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+                )
+                .map_err(sql)?;
+            for q in &results.queries {
+                let hits = q
+                    .results
+                    .as_deref()
+                    .map(|hits| hits_to_json(hits).to_string());
+                insert
+                    .execute(params![
+                        run_id,
+                        q.query,
+                        q.field,
+                        q.category,
+                        q.expected_name,
+                        q.frequency as i64,
+                        q.median_ms,
+                        q.min_ms,
+                        q.matches.map(|m| m as i64),
+                        q.found(),
+                        hits
+                    ])
+                    .map_err(sql)?;
+            }
+        }
+        tx.commit().map_err(sql)?;
+        Ok(run_id)
+    }
+
+    /// Runs matching `filter`, oldest first.
+    pub fn runs(&self, filter: &RunFilter) -> io::Result<Vec<StoredRun>> {
+        let mut statement = self
+            .conn
+            .prepare(
+                "SELECT id, recorded_at, backend, run, corpus, corpus_sha256 FROM runs
+                 WHERE (?1 IS NULL OR backend = ?1)
+                   AND (?2 IS NULL OR corpus = ?2 OR corpus_sha256 = ?2)
+                 ORDER BY recorded_at DESC, id DESC
+                 LIMIT ?3",
+            )
+            .map_err(sql)?;
+        let limit = filter.last.map_or(-1, |n| n as i64);
+        let mut runs = statement
+            .query_map(params![filter.backend, filter.corpus, limit], |row| {
+                Ok(StoredRun {
+                    id: row.get(0)?,
+                    recorded_at: row.get::<_, i64>(1)? as u64,
+                    backend: row.get(2)?,
+                    run: row.get(3)?,
+                    corpus: row.get(4)?,
+                    corpus_sha256: row.get(5)?,
+                })
+            })
+            .map_err(sql)?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(sql)?;
+        runs.reverse();
+        Ok(runs)
+    }
+
+    /// Per-category aggregates of one run.
+    pub fn category_points(&self, run_id: i64) -> io::Result<Vec<CategoryPoint>> {
+        let mut statement = self
+            .conn
+            .prepare(
+                "SELECT category, COUNT(*), COUNT(found), COALESCE(SUM(found), 0),
+                        CASE WHEN COUNT(median_ms) > 0 THEN SUM(median_ms) END
+                 FROM query_results WHERE run_id = ?1
+                 GROUP BY category",
+            )
+            .map_err(sql)?;
+        let points = statement
+            .query_map(params![run_id], |row| {
+                Ok(CategoryPoint {
+                    run_id,
+                    category: row.get(0)?,
+                    queries: row.get::<_, i64>(1)? as usize,
+                    judged: row.get::<_, i64>(2)? as usize,
+                    found: row.get::<_, i64>(3)? as usize,
+                    latency_ms: row.get(4)?,
+                })
+            })
+            .map_err(sql)?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(sql)?;
+        Ok(points)
+    }
+
+    /// Reads a stored run back as a result set.
+    pub fn load(&self, run_id: i64) -> io::Result<Option<ResultSet>> {
+        let run = self
+            .conn
+            .query_row(
+                "SELECT backend, run, corpus, corpus_sha256, fingerprint, startup, steady_state,
+                        memory
+                 FROM runs WHERE id = ?1",
+                params![run_id],
+                |row| {
+                    Ok((
+                        row.get(0)?,
+                        row.get(1)?,
+                        row.get(2)?,
+                        row.get(3)?,
+                        row.get::<_, Option<String>>(4)?,
+                        row.get::<_, Option<String>>(5)?,
+                        row.get::<_, Option<String>>(6)?,
+                        row.get::<_, Option<String>>(7)?,
+                    ))
+                },
+            )
+            .optional()
+            .map_err(sql)?;
+        let Some((backend, run, corpus, corpus_sha256, fingerprint, startup, steady_state, memory)) =
+            run
+        else {
+            return Ok(None);
+        };
+        let mut statement = self
+            .conn
+            .prepare(
+                "SELECT query, field, category, expected_name, frequency, median_ms, min_ms,
+                        matches, results
+                 FROM query_results WHERE run_id = ?1 ORDER BY rowid",
+            )
+            .map_err(sql)?;
+        let queries = statement
+            .query_map(params![run_id], |row| {
+                let results: Option<String> = row.get(8)?;
+                Ok(QueryResult {
+                    query: row.get(0)?,
+                    field: row.get(1)?,
+                    category: row.get(2)?,
+                    expected_name: row.get(3)?,
+                    frequency: row.get::<_, i64>(4)? as u64,
+                    median_ms: row.get(5)?,
+                    min_ms: row.get(6)?,
+                    matches: row.get::<_, Option<i64>>(7)?.map(|m| m as usize),
+                    results: results
+                        .as_deref()
+                        .map(parse_hits)
+                        .transpose()
+                        .map_err(|e| {
+                            rusqlite::Error::FromSqlConversionFailure(
+                                8,
+                                rusqlite::types::Type::Text,
+                                e,
+                            )
+                        })?,
+                    // The history keeps the main pass only
+                    recheck: None,
+                    cost: None,
+                    difficulty: None,
+                    snapshot: None,
+                })
+            })
+            .map_err(sql)?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(sql)?;
+        Ok(Some(ResultSet {
+            backend,
+            run,
+            corpus,
+            corpus_sha256,
+            fingerprint: json_column(fingerprint).and_then(|value| Fingerprint::from_json(&value)),
+            startup: json_column(startup).and_then(|value| Startup::from_json(&value)),
+            steady_state: json_column(steady_state)
+                .and_then(|value| SteadyState::from_json(&value)),
+            memory: json_column(memory)
+                .and_then(|value| value.as_array()?.iter().map(Footprint::from_json).collect())
+                .unwrap_or_default(),
+            queries,
+        }))
+    }
+}
+
+/// A JSON column's value, when set and well formed.
+fn json_column(text: Option<String>) -> Option<Value> {
+    text.and_then(|text| serde_json::from_str(&text).ok())
+}
+
+/// Seconds since the Unix epoch, for [`Store::append`].
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Hits as stored by [`Store::append`].
+fn parse_hits(text: &str) -> Result<Vec<Hit>, Box<dyn std::error::Error + Send + Sync>> {
+    let value: Value = serde_json::from_str(text)?;
+    hits_from_json(&value).ok_or_else(|| "not a result list".into())
+}
+
+fn sql(e: rusqlite::Error) -> io::Error {
+    io::Error::other(e)
+}
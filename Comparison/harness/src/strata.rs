@@ -0,0 +1,172 @@
+//! How well a query set covers the kinds of rows a corpus holds.
+//!
+//! A query set drawn by hand or from logs follows whatever its author or
+//! users happened to search for, so whole kinds of names can go untested:
+//! a corpus that is 8% Cyrillic with no Cyrillic queries, or long fund
+//! names that no query targets. [`Coverage`] splits the corpus and the
+//! queries into strata along each [`DIMENSIONS`] entry, compares each
+//! stratum's share of the queries with its share of the corpus rows, and
+//! suggests how many queries to add to it.
+//!
+//! A corpus row falls in the strata of its name. A query falls in the
+//! script stratum of its own text, since that is what the matcher sees,
+//! and in the length stratum of its expected name, which is the row it
+//! targets. Queries with no expected name have no length stratum.
+//!
+//! A stratum should hold at least its share of the corpus in queries, and
+//! at least `min_per_stratum` queries so that its hit rate means something.
+//! A stratum with fewer rows than that asks for one query per row.
+
+use crate::corpus::Corpus;
+use crate::queries::{Query, SKIP};
+use std::cmp::Reverse;
+
+/// The ways rows and queries are stratified.
+pub const DIMENSIONS: [&str; 2] = ["script", "name_length"];
+
+/// Names up to this many characters are `short`.
+pub const SHORT_NAME: usize = 12;
+
+/// Names over this many characters are `long`.
+pub const LONG_NAME: usize = 32;
+
+/// The script stratum of `text`: the first non-Latin script among its
+/// letters, else `latin_accented` when any letter is accented Latin,
+/// `latin` for plain ASCII letters, and `no_letters` for digits and
+/// punctuation alone.
+pub fn script(text: &str) -> &'static str {
+    let mut accented = false;
+    let mut letters = false;
+    for c in text.chars().filter(|c| c.is_alphabetic()) {
+        letters = true;
+        match c as u32 {
+            0x0370..=0x03FF | 0x1F00..=0x1FFF => return "greek",
+            0x0400..=0x052F => return "cyrillic",
+            0x0590..=0x05FF => return "hebrew",
+            0x0600..=0x06FF => return "arabic",
+            0x3040..=0x30FF | 0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xAC00..=0xD7AF => return "cjk",
+            0x00C0..=0x024F | 0x1E00..=0x1EFF => accented = true,
+            _ if c.is_ascii() => {}
+            _ => return "other",
+        }
+    }
+    match (letters, accented) {
+        (false, _) => "no_letters",
+        (true, true) => "latin_accented",
+        (true, false) => "latin",
+    }
+}
+
+/// The length stratum of a name, by its characters.
+pub fn name_length(name: &str) -> &'static str {
+    match name.trim().chars().count() {
+        n if n <= SHORT_NAME => "short",
+        n if n <= LONG_NAME => "medium",
+        _ => "long",
+    }
+}
+
+/// One stratum of one dimension, with what the corpus and query set hold.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Stratum {
+    pub dimension: &'static str,
+    pub name: &'static str,
+    pub rows: usize,
+    pub queries: usize,
+    /// Queries to add for the stratum to be covered.
+    pub suggested: usize,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Coverage {
+    /// Strata with any rows or queries, by dimension, most rows first.
+    pub strata: Vec<Stratum>,
+}
+
+impl Coverage {
+    pub fn new(corpus: &Corpus, queries: &[Query], min_per_stratum: usize) -> Coverage {
+        let names: Vec<&str> = corpus.iter().map(|row| row.name).collect();
+        let judged: Vec<&str> = queries
+            .iter()
+            .map(|q| q.expected_name.as_str())
+            .filter(|name| !name.is_empty() && *name != SKIP)
+            .collect();
+        let mut strata = Vec::new();
+        for dimension in DIMENSIONS {
+            let (rows, placed): (Vec<&str>, Vec<&str>) = match dimension {
+                "script" => (
+                    names.iter().map(|name| script(name)).collect(),
+                    queries.iter().map(|q| script(&q.text)).collect(),
+                ),
+                _ => (
+                    names.iter().map(|name| name_length(name)).collect(),
+                    judged.iter().map(|name| name_length(name)).collect(),
+                ),
+            };
+            strata.extend(stratify(dimension, &rows, &placed, min_per_stratum));
+        }
+        Coverage { strata }
+    }
+
+    /// Strata that need more queries, most needed first.
+    pub fn under_covered(&self) -> Vec<&Stratum> {
+        let mut strata: Vec<&Stratum> = self.strata.iter().filter(|s| s.suggested > 0).collect();
+        strata.sort_by_key(|s| Reverse(s.suggested));
+        strata
+    }
+
+    /// Queries to add over every dimension's strata, counting each query
+    /// once in the dimension that needs the most.
+    pub fn suggested(&self) -> usize {
+        DIMENSIONS
+            .iter()
+            .map(|dimension| {
+                self.strata
+                    .iter()
+                    .filter(|s| s.dimension == *dimension)
+                    .map(|s| s.suggested)
+                    .sum::<usize>()
+            })
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// The strata of one dimension, given the stratum of every row and of every
+/// query placed in it.
+fn stratify(
+    dimension: &'static str,
+    rows: &[&'static str],
+    queries: &[&'static str],
+    min_per_stratum: usize,
+) -> Vec<Stratum> {
+    let mut names: Vec<&'static str> = Vec::new();
+    for name in rows.iter().chain(queries) {
+        if !names.contains(name) {
+            names.push(name);
+        }
+    }
+    let count = |all: &[&str], name: &str| all.iter().filter(|n| **n == name).count();
+    let mut strata: Vec<Stratum> = names
+        .into_iter()
+        .map(|name| {
+            let (in_rows, in_queries) = (count(rows, name), count(queries, name));
+            let share = if rows.is_empty() {
+                0.0
+            } else {
+                in_rows as f64 / rows.len() as f64
+            };
+            let wanted =
+                ((share * queries.len() as f64).ceil() as usize).max(min_per_stratum.min(in_rows));
+            Stratum {
+                dimension,
+                name,
+                rows: in_rows,
+                queries: in_queries,
+                suggested: wanted.saturating_sub(in_queries),
+            }
+        })
+        .collect();
+    strata.sort_by(|a, b| b.rows.cmp(&a.rows).then(b.queries.cmp(&a.queries)));
+    strata
+}
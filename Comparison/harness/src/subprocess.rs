@@ -0,0 +1,259 @@
+//! Running an external backend in a child process: its output captured,
+//! its limits enforced from outside, and its resource use measured.
+//!
+//! The quality harnesses of the other backends (`quality-fuzzymatch`,
+//! `quality-rapidfuzz`, ...) share a line protocol: they take the corpus
+//! path as their first argument, read `query<TAB>field` lines on stdin and
+//! print each query's ranked results as `query, field, rank, score, [kind,]
+//! symbol, name` fields, optionally followed by `line=N` provenance.
+//! [`ranked_hits`] reads that back.
+//!
+//! A child cannot stall or exhaust the process that runs it. [`run`] polls
+//! its `/proc/<pid>/status` and kills it once it goes past its [`Limits`]:
+//! wall-clock time, or its resident set. The child leads a process group of
+//! its own and the whole group is killed, since anything it started (a
+//! shell's commands, a wrapper's interpreter) would otherwise live on and
+//! hold its output pipes open. The child is reaped with `wait4`,
+//! whose `rusage` gives its CPU time whether it finished or was killed.
+//! Its peak resident set is the last `VmHWM` polled: Linux's `ru_maxrss`
+//! also counts the pages of the parent it was forked from, which for a
+//! runner holding a corpus dwarfs a small backend. Only where `/proc` is
+//! missing, or the child exits before the first poll, is `ru_maxrss` used.
+//! Its stderr is kept whole, so a failure can be diagnosed from the bundle
+//! rather than by rerunning it.
+
+use crate::limits::{Exceeded, Limits};
+use crate::memory;
+use crate::results::Hit;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::os::unix::process::CommandExt;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How often a running child is checked against its limits.
+const POLL: Duration = Duration::from_millis(10);
+
+/// Resources a child used, from its `rusage` and the wall clock.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Usage {
+    pub wall_ms: f64,
+    pub user_ms: f64,
+    pub system_ms: f64,
+    /// Peak resident set.
+    pub max_rss_bytes: u64,
+}
+
+/// How a child ended, with everything it printed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Outcome {
+    pub stdout: String,
+    pub stderr: String,
+    /// Exit code, or `None` when a signal ended it.
+    pub code: Option<i32>,
+    pub signal: Option<i32>,
+    /// The limit it was killed for going past, if any.
+    pub stopped: Option<Exceeded>,
+    pub usage: Usage,
+}
+
+impl Outcome {
+    pub fn success(&self) -> bool {
+        self.stopped.is_none() && self.code == Some(0)
+    }
+
+    /// Why the child failed, with the last line of its stderr, or `None`
+    /// when it succeeded.
+    pub fn failure(&self) -> Option<String> {
+        let reason = match (self.stopped, self.code, self.signal) {
+            (Some(exceeded), _, _) => exceeded.to_string(),
+            (None, Some(0), _) => return None,
+            (None, Some(code), _) => format!("exited with status {code}"),
+            (None, None, signal) => format!("killed by signal {}", signal.unwrap_or(0)),
+        };
+        match self
+            .stderr
+            .lines()
+            .rev()
+            .find(|line| !line.trim().is_empty())
+        {
+            Some(line) => Some(format!("{reason}: {}", line.trim())),
+            None => Some(reason),
+        }
+    }
+
+    /// Exit status and usage, for a bundle index; the output is left out.
+    pub fn to_json(&self) -> Value {
+        json!({
+            "exit_code": self.code,
+            "signal": self.signal,
+            "stopped": self.stopped.as_ref().map(Exceeded::to_json),
+            "wall_ms": self.usage.wall_ms,
+            "user_ms": self.usage.user_ms,
+            "system_ms": self.usage.system_ms,
+            "max_rss_bytes": self.usage.max_rss_bytes,
+        })
+    }
+
+    /// Reads back [`Outcome::to_json`], with empty output.
+    pub fn from_json(value: &Value) -> Option<Outcome> {
+        let number = |key: &str| value.get(key).and_then(Value::as_f64);
+        let code = |key: &str| value.get(key).and_then(Value::as_i64).map(|n| n as i32);
+        Some(Outcome {
+            stdout: String::new(),
+            stderr: String::new(),
+            code: code("exit_code"),
+            signal: code("signal"),
+            stopped: match value.get("stopped") {
+                Some(Value::Null) | None => None,
+                Some(stopped) => Some(Exceeded::from_json(stopped)?),
+            },
+            usage: Usage {
+                wall_ms: number("wall_ms")?,
+                user_ms: number("user_ms")?,
+                system_ms: number("system_ms")?,
+                max_rss_bytes: value.get("max_rss_bytes")?.as_u64()?,
+            },
+        })
+    }
+}
+
+/// Runs `command` with `input` on its stdin until it exits or goes past
+/// `limits`. Errors only when it cannot be started or waited for; a child
+/// that fails is an [`Outcome`] that says so.
+pub fn run(command: &[String], input: &str, limits: &Limits) -> io::Result<Outcome> {
+    let (program, args) = command
+        .split_first()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "empty command"))?;
+    let start = Instant::now();
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .process_group(0)
+        .spawn()?;
+    let pid = child.id() as libc::pid_t;
+
+    // Fed and drained on threads of their own, so a child that fills one
+    // pipe while the other waits cannot deadlock against us. A child that
+    // exits without reading all its input is not an error here.
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let input = input.to_string();
+    let feeder = thread::spawn(move || {
+        let _ = stdin.write_all(input.as_bytes());
+    });
+    let stdout = drain(child.stdout.take().expect("stdout is piped"));
+    let stderr = drain(child.stderr.take().expect("stderr is piped"));
+
+    let mut stopped = None;
+    let mut peak = None;
+    let (status, usage) = loop {
+        if let Some(reaped) = wait4(pid, libc::WNOHANG)? {
+            break reaped;
+        }
+        if stopped.is_none() {
+            let status = fs::read_to_string(format!("/proc/{pid}/status")).ok();
+            let status = status.as_deref().unwrap_or_default();
+            peak = memory::parse_vm_hwm(status)
+                .filter(|bytes| *bytes > 0)
+                .max(peak);
+            if let Some(exceeded) = limits.exceeded(start.elapsed(), memory::parse_vm_rss(status)) {
+                stopped = Some(exceeded);
+                kill_group(pid)?;
+            }
+        }
+        thread::sleep(POLL);
+    };
+    let wall_ms = start.elapsed().as_secs_f64() * 1000.0;
+    let _ = feeder.join();
+
+    let millis = |time: libc::timeval| time.tv_sec as f64 * 1000.0 + time.tv_usec as f64 / 1000.0;
+    Ok(Outcome {
+        stdout: stdout.join().expect("stdout reader panicked")?,
+        stderr: stderr.join().expect("stderr reader panicked")?,
+        code: libc::WIFEXITED(status).then(|| libc::WEXITSTATUS(status)),
+        signal: libc::WIFSIGNALED(status).then(|| libc::WTERMSIG(status)),
+        stopped,
+        usage: Usage {
+            wall_ms,
+            user_ms: millis(usage.ru_utime),
+            system_ms: millis(usage.ru_stime),
+            // Linux reports kibibytes, macOS bytes
+            max_rss_bytes: peak.unwrap_or(
+                usage.ru_maxrss.max(0) as u64 * if cfg!(target_os = "macos") { 1 } else { 1024 },
+            ),
+        },
+    })
+}
+
+/// Reads all of `pipe` on a thread of its own, replacing invalid UTF-8.
+fn drain(mut pipe: impl Read + Send + 'static) -> thread::JoinHandle<io::Result<String>> {
+    thread::spawn(move || {
+        let mut bytes = Vec::new();
+        pipe.read_to_end(&mut bytes)?;
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    })
+}
+
+/// Kills every process in the group child `pid` leads. The child is not
+/// reaped yet, so the group cannot have been reused.
+fn kill_group(pid: libc::pid_t) -> io::Result<()> {
+    // SAFETY: killpg takes no pointers
+    if unsafe { libc::killpg(pid, libc::SIGKILL) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Reaps child `pid`, with its status and resource use, or `None` when
+/// `options` has `WNOHANG` and it is still running.
+fn wait4(
+    pid: libc::pid_t,
+    options: libc::c_int,
+) -> io::Result<Option<(libc::c_int, libc::rusage)>> {
+    let mut status = 0;
+    // SAFETY: rusage is plain integers and timevals, for which all zeroes
+    // is a valid value
+    let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+    loop {
+        // SAFETY: both pointers are to live locals that outlast the call
+        match unsafe { libc::wait4(pid, &mut status, options, &mut usage) } {
+            0 => return Ok(None),
+            -1 => {
+                let error = io::Error::last_os_error();
+                if error.kind() != io::ErrorKind::Interrupted {
+                    return Err(error);
+                }
+            }
+            _ => return Ok(Some((status, usage))),
+        }
+    }
+}
+
+/// The results a quality harness printed, by query and field, in the order
+/// it ranked them. Lines with too few fields are skipped, and a score that
+/// is not a number is kept as `None`.
+pub fn ranked_hits(stdout: &str) -> HashMap<(String, String), Vec<Hit>> {
+    let mut hits: HashMap<(String, String), Vec<Hit>> = HashMap::new();
+    for line in stdout.lines() {
+        let mut fields: Vec<&str> = line.split('\t').collect();
+        if let Some(provenance) = fields.iter().skip(6).position(|f| f.starts_with("line=")) {
+            fields.truncate(6 + provenance);
+        }
+        if fields.len() < 6 {
+            continue;
+        }
+        hits.entry((fields[0].to_string(), fields[1].to_string()))
+            .or_default()
+            .push(Hit {
+                symbol: fields[fields.len() - 2].to_string(),
+                name: fields[fields.len() - 1].to_string(),
+                score: fields[3].parse().ok(),
+            });
+    }
+    hits
+}
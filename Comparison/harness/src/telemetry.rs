@@ -0,0 +1,179 @@
+//! Request metrics for the search services, in the Prometheus text format.
+//!
+//! `load-test` measures a server from outside; a server under real traffic
+//! has to report on itself. [`SearchMetrics`] counts every search a service
+//! answers, with histograms of its latency, the rows it scanned, the rows
+//! that matched and the results returned, and [`SearchMetrics::render`]
+//! writes them in the text exposition format (version 0.0.4) that
+//! Prometheus scrapes, as does an OpenTelemetry collector through its
+//! `prometheus` receiver. Observing is a few relaxed atomic additions, so
+//! requests never wait on each other to record.
+
+use std::fmt::Write as _;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Upper bounds of the latency buckets, in seconds: 0.5 ms to 2.5 s.
+pub const LATENCY_BUCKETS: [f64; 12] = [
+    0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5,
+];
+
+/// Upper bounds of the row count buckets, in decades up to ten million.
+pub const COUNT_BUCKETS: [f64; 8] = [0.0, 1.0, 10.0, 100.0, 1e3, 1e4, 1e5, 1e7];
+
+/// The content type of [`SearchMetrics::render`]'s output.
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Observations counted into buckets of fixed upper bounds.
+pub struct Histogram {
+    bounds: &'static [f64],
+    /// One count per bound, then the observations above every bound.
+    counts: Vec<AtomicU64>,
+    /// Sum of the observations, in millionths.
+    sum_micros: AtomicU64,
+}
+
+impl Histogram {
+    pub fn new(bounds: &'static [f64]) -> Histogram {
+        Histogram {
+            bounds,
+            counts: (0..=bounds.len()).map(|_| AtomicU64::new(0)).collect(),
+            sum_micros: AtomicU64::new(0),
+        }
+    }
+
+    pub fn observe(&self, value: f64) {
+        let bucket = self.bounds.partition_point(|&bound| bound < value);
+        self.counts[bucket].fetch_add(1, Ordering::Relaxed);
+        self.sum_micros
+            .fetch_add((value.max(0.0) * 1e6).round() as u64, Ordering::Relaxed);
+    }
+
+    /// Number of observations.
+    pub fn count(&self) -> u64 {
+        self.counts.iter().map(|c| c.load(Ordering::Relaxed)).sum()
+    }
+
+    pub fn sum(&self) -> f64 {
+        self.sum_micros.load(Ordering::Relaxed) as f64 / 1e6
+    }
+
+    /// Writes the histogram's `_bucket`, `_sum` and `_count` series, the
+    /// buckets cumulative as Prometheus expects.
+    fn render(&self, out: &mut String, name: &str, help: &str) {
+        writeln!(out, "# HELP {name} {help}").unwrap();
+        writeln!(out, "# TYPE {name} histogram").unwrap();
+        let mut cumulative = 0;
+        for (bound, count) in self.bounds.iter().zip(&self.counts) {
+            cumulative += count.load(Ordering::Relaxed);
+            writeln!(out, "{name}_bucket{{le=\"{bound}\"}} {cumulative}").unwrap();
+        }
+        cumulative += self.counts[self.bounds.len()].load(Ordering::Relaxed);
+        writeln!(out, "{name}_bucket{{le=\"+Inf\"}} {cumulative}").unwrap();
+        writeln!(out, "{name}_sum {}", self.sum()).unwrap();
+        writeln!(out, "{name}_count {cumulative}").unwrap();
+    }
+}
+
+/// What a service observed while answering one search.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SearchObservation {
+    /// From receiving the request to having the answer ready.
+    pub latency: Duration,
+    /// Rows the scan scored.
+    pub scanned: usize,
+    /// Rows that matched.
+    pub matched: usize,
+    /// Results returned to the client.
+    pub returned: usize,
+}
+
+/// The metrics one search service exports.
+pub struct SearchMetrics {
+    /// Prefixes every metric name, such as `search_server`.
+    namespace: String,
+    searches: AtomicU64,
+    rejected: AtomicU64,
+    latency: Histogram,
+    scanned: Histogram,
+    matched: Histogram,
+    returned: Histogram,
+}
+
+impl SearchMetrics {
+    pub fn new(namespace: &str) -> SearchMetrics {
+        SearchMetrics {
+            namespace: namespace.to_string(),
+            searches: AtomicU64::new(0),
+            rejected: AtomicU64::new(0),
+            latency: Histogram::new(&LATENCY_BUCKETS),
+            scanned: Histogram::new(&COUNT_BUCKETS),
+            matched: Histogram::new(&COUNT_BUCKETS),
+            returned: Histogram::new(&COUNT_BUCKETS),
+        }
+    }
+
+    /// Records a search the service answered.
+    pub fn observe(&self, search: &SearchObservation) {
+        self.searches.fetch_add(1, Ordering::Relaxed);
+        self.latency.observe(search.latency.as_secs_f64());
+        self.scanned.observe(search.scanned as f64);
+        self.matched.observe(search.matched as f64);
+        self.returned.observe(search.returned as f64);
+    }
+
+    /// Records a request refused as invalid.
+    pub fn reject(&self) {
+        self.rejected.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn searches(&self) -> u64 {
+        self.searches.load(Ordering::Relaxed)
+    }
+
+    /// Every metric in the text exposition format, followed by `gauges`:
+    /// `(name, help, value)` triples for state the service reads at scrape
+    /// time, such as the rows it holds.
+    pub fn render(&self, gauges: &[(&str, &str, f64)]) -> String {
+        let ns = &self.namespace;
+        let mut out = String::new();
+        for (name, help, value) in [
+            ("searches_total", "Searches answered.", &self.searches),
+            (
+                "rejected_total",
+                "Requests refused as invalid.",
+                &self.rejected,
+            ),
+        ] {
+            writeln!(out, "# HELP {ns}_{name} {help}").unwrap();
+            writeln!(out, "# TYPE {ns}_{name} counter").unwrap();
+            writeln!(out, "{ns}_{name} {}", value.load(Ordering::Relaxed)).unwrap();
+        }
+        self.latency.render(
+            &mut out,
+            &format!("{ns}_search_duration_seconds"),
+            "Time from request to answer.",
+        );
+        self.scanned.render(
+            &mut out,
+            &format!("{ns}_scanned_rows"),
+            "Rows scored per search.",
+        );
+        self.matched.render(
+            &mut out,
+            &format!("{ns}_matched_rows"),
+            "Rows matching per search.",
+        );
+        self.returned.render(
+            &mut out,
+            &format!("{ns}_returned_results"),
+            "Results returned per search.",
+        );
+        for (name, help, value) in gauges {
+            writeln!(out, "# HELP {ns}_{name} {help}").unwrap();
+            writeln!(out, "# TYPE {ns}_{name} gauge").unwrap();
+            writeln!(out, "{ns}_{name} {value}").unwrap();
+        }
+        out
+    }
+}
@@ -0,0 +1,356 @@
+//! Regression tolerances for gating a run against its baseline.
+//!
+//! Tolerances are read from TOML. Every key is optional and falls back to
+//! the default shown:
+//!
+//! ```toml
+//! # Latency changes smaller than this never count, whatever the percentage.
+//! min_delta_ms = 0.05
+//! # Queries allowed to lose their expected result.
+//! lost_queries = 0
+//! # Let a latency limit fire only on a significant change.
+//! require_significance = false
+//!
+//! [total]             # all shared queries together
+//! latency_pct = 5.0
+//! hit_rate_drop_pp = 0.0
+//!
+//! [category]          # each category on its own
+//! latency_pct = 10.0
+//! hit_rate_drop_pp = 0.0
+//!
+//! [categories.typo]   # overrides for one category
+//! latency_pct = 20.0
+//!
+//! [query]             # each query on its own; off unless set
+//! latency_pct = 50.0
+//! ```
+//!
+//! Latency limits are percentages of the baseline's summed median time;
+//! hit-rate limits are percentage points of ground-truth hits. A latency
+//! violation over two or more queries gives the change's confidence
+//! interval (see `harness::significance`). With `require_significance` a
+//! change whose interval includes no change passes whatever its size; a
+//! single query has no interval, so its limit fires as usual. Unknown keys
+//! are errors, since a misspelt limit would otherwise quietly never fire.
+
+use crate::results::{QueryResult, ResultSet};
+use crate::significance::Change;
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use toml::{Table, Value};
+
+/// Limits for one scope: the whole run, a category, or a query.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Limits {
+    /// Largest allowed latency growth, in percent.
+    pub latency_pct: Option<f64>,
+    /// Largest allowed hit-rate drop, in percentage points.
+    pub hit_rate_drop_pp: Option<f64>,
+}
+
+impl Limits {
+    /// `self`, with any limit it leaves unset taken from `fallback`.
+    fn or(self, fallback: Limits) -> Limits {
+        Limits {
+            latency_pct: self.latency_pct.or(fallback.latency_pct),
+            hit_rate_drop_pp: self.hit_rate_drop_pp.or(fallback.hit_rate_drop_pp),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tolerances {
+    pub min_delta_ms: f64,
+    pub lost_queries: Option<usize>,
+    pub require_significance: bool,
+    pub total: Limits,
+    pub category: Limits,
+    /// Per-category overrides of [`Tolerances::category`].
+    pub categories: HashMap<String, Limits>,
+    pub query: Limits,
+}
+
+impl Default for Tolerances {
+    fn default() -> Self {
+        Tolerances {
+            min_delta_ms: 0.05,
+            lost_queries: Some(0),
+            require_significance: false,
+            total: Limits {
+                latency_pct: Some(5.0),
+                hit_rate_drop_pp: Some(0.0),
+            },
+            category: Limits {
+                latency_pct: Some(10.0),
+                hit_rate_drop_pp: Some(0.0),
+            },
+            categories: HashMap::new(),
+            query: Limits::default(),
+        }
+    }
+}
+
+impl FromStr for Tolerances {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let table: Table = s.parse().map_err(|e: toml::de::Error| e.to_string())?;
+        let defaults = Tolerances::default();
+        let mut tolerances = defaults.clone();
+        for (key, value) in &table {
+            match key.as_str() {
+                "min_delta_ms" => tolerances.min_delta_ms = number(key, value)?,
+                "lost_queries" => {
+                    tolerances.lost_queries = Some(
+                        value
+                            .as_integer()
+                            .and_then(|n| usize::try_from(n).ok())
+                            .ok_or_else(|| format!("{key} must be a non-negative integer"))?,
+                    )
+                }
+                "require_significance" => {
+                    tolerances.require_significance = value
+                        .as_bool()
+                        .ok_or_else(|| format!("{key} must be true or false"))?
+                }
+                "total" => tolerances.total = limits(key, value)?.or(defaults.total),
+                "category" => tolerances.category = limits(key, value)?.or(defaults.category),
+                "query" => {
+                    tolerances.query = limits(key, value)?;
+                    if tolerances.query.hit_rate_drop_pp.is_some() {
+                        return Err(
+                            "query.hit_rate_drop_pp is not supported; use lost_queries".to_string()
+                        );
+                    }
+                }
+                "categories" => {
+                    let categories = value
+                        .as_table()
+                        .ok_or_else(|| format!("{key} must be a table of categories"))?;
+                    for (category, value) in categories {
+                        let path = format!("categories.{category}");
+                        tolerances
+                            .categories
+                            .insert(category.clone(), limits(&path, value)?);
+                    }
+                }
+                _ => return Err(format!("unknown key `{key}`")),
+            }
+        }
+        Ok(tolerances)
+    }
+}
+
+fn limits(path: &str, value: &Value) -> Result<Limits, String> {
+    let table = value
+        .as_table()
+        .ok_or_else(|| format!("[{path}] must be a table"))?;
+    let mut limits = Limits::default();
+    for (key, value) in table {
+        let n = number(&format!("{path}.{key}"), value)?;
+        match key.as_str() {
+            "latency_pct" => limits.latency_pct = Some(n),
+            "hit_rate_drop_pp" => limits.hit_rate_drop_pp = Some(n),
+            _ => return Err(format!("unknown key `{path}.{key}`")),
+        }
+    }
+    Ok(limits)
+}
+
+fn number(key: &str, value: &Value) -> Result<f64, String> {
+    match value {
+        Value::Float(f) => Ok(*f),
+        Value::Integer(i) => Ok(*i as f64),
+        _ => Err(format!("{key} must be a number")),
+    }
+}
+
+/// A limit the current run broke.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Violation {
+    /// `total`, `category typo`, or the query text and field.
+    pub scope: String,
+    pub message: String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.scope, self.message)
+    }
+}
+
+/// Outcome of [`check`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Verdict {
+    /// Queries present in both runs; only these are compared.
+    pub shared: usize,
+    /// Limits evaluated, including those that passed.
+    pub checks: usize,
+    pub violations: Vec<Violation>,
+    /// The change in summed median latency over the shared queries, when
+    /// at least two were timed in both runs.
+    pub latency: Option<Change>,
+}
+
+impl Verdict {
+    pub fn passed(&self) -> bool {
+        self.shared > 0 && self.violations.is_empty()
+    }
+
+    fn check_scope(
+        &mut self,
+        tolerances: &Tolerances,
+        scope: &str,
+        limits: Limits,
+        pairs: &[(&QueryResult, &QueryResult)],
+    ) {
+        if let Some(limit) = limits.latency_pct {
+            let timed: Vec<_> = pairs
+                .iter()
+                .filter_map(|(old, new)| Some((old.median_ms?, new.median_ms?)))
+                .collect();
+            if !timed.is_empty() {
+                self.checks += 1;
+                let old: f64 = timed.iter().map(|t| t.0).sum();
+                let new: f64 = timed.iter().map(|t| t.1).sum();
+                let change = Change::of(&timed);
+                let noise = tolerances.require_significance
+                    && change.is_some_and(|change| !change.significant());
+                if new - old >= tolerances.min_delta_ms
+                    && new > old * (1.0 + limit / 100.0)
+                    && !noise
+                {
+                    let interval = change
+                        .map(|change| format!(", {}", change.interval()))
+                        .unwrap_or_default();
+                    self.violations.push(Violation {
+                        scope: scope.to_string(),
+                        message: format!(
+                            "latency {old:.2}ms → {new:.2}ms ({:+.1}%{interval}, limit +{limit}%)",
+                            (new / old - 1.0) * 100.0
+                        ),
+                    });
+                }
+            }
+        }
+        if let Some(limit) = limits.hit_rate_drop_pp {
+            let judged: Vec<_> = pairs
+                .iter()
+                .filter_map(|(old, new)| Some((old.found()?, new.found()?)))
+                .collect();
+            if !judged.is_empty() {
+                self.checks += 1;
+                let rate = |hits: usize| hits as f64 * 100.0 / judged.len() as f64;
+                let old = rate(judged.iter().filter(|j| j.0).count());
+                let new = rate(judged.iter().filter(|j| j.1).count());
+                if old - new > limit + 1e-9 {
+                    self.violations.push(Violation {
+                        scope: scope.to_string(),
+                        message: format!(
+                            "hit rate {old:.1}% → {new:.1}% ({:+.1} pp, limit -{limit} pp)",
+                            new - old
+                        ),
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// Checks `current` against `baseline` on the queries both contain,
+/// matched by text and field.
+pub fn check(tolerances: &Tolerances, baseline: &ResultSet, current: &ResultSet) -> Verdict {
+    let before: HashMap<(&str, &str), &QueryResult> = baseline
+        .queries
+        .iter()
+        .map(|q| ((q.query.as_str(), q.field.as_str()), q))
+        .collect();
+    let pairs: Vec<(&QueryResult, &QueryResult)> = current
+        .queries
+        .iter()
+        .filter_map(|q| {
+            before
+                .get(&(q.query.as_str(), q.field.as_str()))
+                .map(|b| (*b, q))
+        })
+        .collect();
+    let timed: Vec<(f64, f64)> = pairs
+        .iter()
+        .filter_map(|(old, new)| Some((old.median_ms?, new.median_ms?)))
+        .collect();
+    let mut verdict = Verdict {
+        shared: pairs.len(),
+        latency: Change::of(&timed),
+        ..Verdict::default()
+    };
+
+    verdict.check_scope(tolerances, "total", tolerances.total, &pairs);
+    let mut categories: Vec<&str> = Vec::new();
+    for (_, q) in &pairs {
+        if !categories.contains(&q.category.as_str()) {
+            categories.push(&q.category);
+        }
+    }
+    for category in categories {
+        let limits = tolerances
+            .categories
+            .get(category)
+            .copied()
+            .unwrap_or_default()
+            .or(tolerances.category);
+        let in_category: Vec<_> = pairs
+            .iter()
+            .filter(|(_, q)| q.category == category)
+            .copied()
+            .collect();
+        verdict.check_scope(
+            tolerances,
+            &format!("category {category}"),
+            limits,
+            &in_category,
+        );
+    }
+    if tolerances.query.latency_pct.is_some() {
+        for pair in &pairs {
+            let scope = format!("query `{}` ({})", pair.1.query, pair.1.field);
+            verdict.check_scope(
+                tolerances,
+                &scope,
+                tolerances.query,
+                std::slice::from_ref(pair),
+            );
+        }
+    }
+
+    if let Some(allowed) = tolerances.lost_queries {
+        verdict.checks += 1;
+        let lost: Vec<&QueryResult> = pairs
+            .iter()
+            .filter(|(old, new)| old.found() == Some(true) && new.found() == Some(false))
+            .map(|(_, new)| *new)
+            .collect();
+        if lost.len() > allowed {
+            let examples: Vec<String> = lost
+                .iter()
+                .take(5)
+                .map(|q| format!("`{}`", q.query))
+                .collect();
+            verdict.violations.push(Violation {
+                scope: "total".to_string(),
+                message: format!(
+                    "{} queries lost their expected result (limit {allowed}): {}{}",
+                    lost.len(),
+                    examples.join(", "),
+                    if lost.len() > examples.len() {
+                        ", …"
+                    } else {
+                        ""
+                    }
+                ),
+            });
+        }
+    }
+    verdict
+}
@@ -0,0 +1,58 @@
+//! Keeping the best-scored candidates of a scan.
+//!
+//! A scan scores every row but only reports the first few. [`TopK`] holds
+//! the `k` best `(score, row)` pairs seen so far in a min-heap. Once it is
+//! full, a match is compared with the weakest entry and only replaces it
+//! when it beats it, so most matches of a broad query cost one comparison,
+//! whatever the number of matches.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+/// The `k` highest-scoring rows pushed so far.
+pub struct TopK {
+    k: usize,
+    heap: BinaryHeap<Reverse<(u32, usize)>>,
+}
+
+impl TopK {
+    pub fn new(k: usize) -> TopK {
+        TopK {
+            k,
+            heap: BinaryHeap::with_capacity(k + 1),
+        }
+    }
+
+    /// Offers row `idx` with `score`, and returns whether it was kept.
+    /// Among equal scores the later row is kept, as rows are pushed in
+    /// order.
+    #[inline]
+    pub fn push(&mut self, score: u32, idx: usize) -> bool {
+        if self.heap.len() == self.k {
+            match self.heap.peek() {
+                Some(&Reverse(weakest)) if (score, idx) > weakest => {
+                    self.heap.pop();
+                }
+                _ => return false,
+            }
+        }
+        self.heap.push(Reverse((score, idx)));
+        true
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// The kept `(score, row)` pairs, highest score first and rows in
+    /// order within a score.
+    pub fn into_sorted(self) -> Vec<(u32, usize)> {
+        let mut top: Vec<(u32, usize)> = self.heap.into_iter().map(|Reverse(x)| x).collect();
+        top.sort_unstable_by_key(|&(score, idx)| (Reverse(score), idx));
+        top
+    }
+}
@@ -0,0 +1,78 @@
+//! Splitting tab-separated lines as spreadsheets write them.
+//!
+//! Query sets, judgments and corpora are often edited in Excel and saved
+//! back as tab-delimited text. Excel quotes any cell holding a quote or a
+//! tab, doubling the quotes inside: `Berkshire Hathaway "B"` is saved as
+//! `"Berkshire Hathaway ""B"""`. Splitting such a line on every tab keeps
+//! the quotes and cuts quoted tabs apart. [`fields`] undoes the quoting
+//! instead. A field counts as quoted only when it opens with a quote and
+//! its closing quote ends the field, so a stray quote in an unquoted value
+//! is kept as written.
+//!
+//! Line endings are the caller's: split with [`str::lines`] or strip a
+//! trailing `\r`, so CRLF files read like LF ones.
+
+use std::borrow::Cow;
+
+/// The fields of one line, unquoted. Fields are borrowed from `line` unless
+/// a doubled quote had to be collapsed.
+pub fn fields(line: &str) -> Fields<'_> {
+    Fields { rest: Some(line) }
+}
+
+/// Iterator returned by [`fields`].
+pub struct Fields<'a> {
+    /// The unread part of the line, `None` after the last field.
+    rest: Option<&'a str>,
+}
+
+impl<'a> Iterator for Fields<'a> {
+    type Item = Cow<'a, str>;
+
+    fn next(&mut self) -> Option<Cow<'a, str>> {
+        let rest = self.rest?;
+        if let Some((value, tail)) = rest.strip_prefix('"').and_then(quoted) {
+            self.rest = tail;
+            return Some(value);
+        }
+        match rest.split_once('\t') {
+            Some((field, tail)) => {
+                self.rest = Some(tail);
+                Some(Cow::Borrowed(field))
+            }
+            None => {
+                self.rest = None;
+                Some(Cow::Borrowed(rest))
+            }
+        }
+    }
+}
+
+/// Reads a quoted field from just after its opening quote: its value, and
+/// the rest of the line after the tab that ends it. `None` when the field
+/// does not end at its closing quote.
+fn quoted(text: &str) -> Option<(Cow<'_, str>, Option<&str>)> {
+    let mut value = String::new();
+    let mut start = 0;
+    loop {
+        let quote = start + text[start..].find('"')?;
+        let after = &text[quote + 1..];
+        if let Some(after) = after.strip_prefix('"') {
+            value.push_str(&text[start..=quote]);
+            start = text.len() - after.len();
+            continue;
+        }
+        let tail = match after.strip_prefix('\t') {
+            Some(tail) => Some(tail),
+            None if after.is_empty() => None,
+            None => return None,
+        };
+        let value = if value.is_empty() {
+            Cow::Borrowed(&text[..quote])
+        } else {
+            value.push_str(&text[start..quote]);
+            Cow::Owned(value)
+        };
+        return Some((value, tail));
+    }
+}
@@ -0,0 +1,181 @@
+//! Replaying a query as it is typed, to weigh debouncing and cancellation.
+//!
+//! A search field scans on every keystroke, but people type faster than a
+//! large corpus can be scanned, so the app has to decide which scans run at
+//! all. [`simulate`] replays one query's keystrokes against the measured
+//! cost of scanning each prefix, on a single search worker, with a debounce
+//! window and a [`Strategy`] for a scan still running when the next one is
+//! asked for. It counts the scans that started, finished, were cancelled or
+//! never ran, and the latency the user sees: from each keystroke to the
+//! first results that reflect it.
+
+use crate::rng::Rng;
+use std::collections::VecDeque;
+use std::str::FromStr;
+
+/// What the worker does with scans asked for while it is busy.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Strategy {
+    /// Every scan runs to the end, in the order asked for.
+    Queue,
+    /// The running scan finishes; then only the newest request runs.
+    Latest,
+    /// A new request aborts the running scan.
+    Cancel,
+}
+
+impl Strategy {
+    pub const ALL: [Strategy; 3] = [Strategy::Queue, Strategy::Latest, Strategy::Cancel];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Strategy::Queue => "queue",
+            Strategy::Latest => "latest",
+            Strategy::Cancel => "cancel",
+        }
+    }
+}
+
+impl FromStr for Strategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Strategy::ALL
+            .into_iter()
+            .find(|strategy| strategy.name() == s)
+            .ok_or_else(|| format!("unknown strategy `{s}` (supported: queue, latest, cancel)"))
+    }
+}
+
+/// How one typed query played out.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Session {
+    /// Keystrokes followed by another within the debounce window, so no
+    /// scan was asked for.
+    pub debounced: usize,
+    /// Scans asked for but overtaken by a newer request before starting.
+    pub skipped: usize,
+    pub started: usize,
+    pub completed: usize,
+    /// Scans aborted by a newer request.
+    pub cancelled: usize,
+    /// Worker time spent on scans that were cancelled.
+    pub wasted_ms: f64,
+    /// From each keystroke to the first results for its prefix or a longer
+    /// one, in keystroke order.
+    pub keystroke_ms: Vec<f64>,
+}
+
+impl Session {
+    /// From the last keystroke to the results for the whole query.
+    pub fn final_ms(&self) -> f64 {
+        self.keystroke_ms.last().copied().unwrap_or_default()
+    }
+}
+
+/// Times of `keys` keystrokes, the first at 0, each `delay_ms` after the
+/// last give or take up to `jitter_ms`.
+pub fn keystroke_times(keys: usize, delay_ms: f64, jitter_ms: f64, rng: &mut Rng) -> Vec<f64> {
+    let mut time = 0.0;
+    (0..keys)
+        .map(|key| {
+            if key > 0 {
+                time += (delay_ms + jitter_ms * (2.0 * rng.unit() - 1.0)).max(0.0);
+            }
+            time
+        })
+        .collect()
+}
+
+/// Replays keystrokes at `times` (in ms), where a scan for the prefix
+/// ending at keystroke `i` takes `costs[i]` ms.
+///
+/// A keystroke asks for a scan `debounce_ms` after it, unless the next
+/// keystroke comes first. Under [`Strategy::Cancel`] an aborted scan keeps
+/// the worker for up to `cancel_ms` more, the time it takes to notice.
+pub fn simulate(
+    times: &[f64],
+    costs: &[f64],
+    debounce_ms: f64,
+    strategy: Strategy,
+    cancel_ms: f64,
+) -> Session {
+    assert_eq!(times.len(), costs.len(), "one scan cost per keystroke");
+    let mut session = Session::default();
+    let requests: Vec<(f64, usize)> = (0..times.len())
+        .filter(|&key| {
+            let asked = times[key] + debounce_ms;
+            let debounced = times.get(key + 1).is_some_and(|&next| next < asked);
+            session.debounced += debounced as usize;
+            !debounced
+        })
+        .map(|key| (times[key] + debounce_ms, key))
+        .collect();
+
+    // The running scan's prefix, start, end and whether it was aborted
+    let mut running: Option<(usize, f64, f64, bool)> = None;
+    let mut waiting: VecDeque<usize> = VecDeque::new();
+    let mut shown: Vec<(f64, usize)> = Vec::new();
+    let mut next = 0;
+    let mut clock = 0.0;
+    loop {
+        if running.is_none() {
+            let pick = match strategy {
+                Strategy::Queue => waiting.pop_front(),
+                Strategy::Latest | Strategy::Cancel => {
+                    let newest = waiting.pop_back();
+                    session.skipped += waiting.len();
+                    waiting.clear();
+                    newest
+                }
+            };
+            if let Some(key) = pick {
+                session.started += 1;
+                running = Some((key, clock, clock + costs[key], false));
+            }
+        }
+        // Whichever comes first: the next request, or the running scan's end
+        let end = running.map(|(_, _, end, _)| end);
+        let request = requests
+            .get(next)
+            .filter(|&&(asked, _)| end.is_none_or(|end| asked < end));
+        match (request, end) {
+            (Some(&(asked, key)), _) => {
+                clock = asked;
+                next += 1;
+                if strategy == Strategy::Cancel {
+                    if let Some((_, _, end, aborted)) = &mut running {
+                        *end = end.min(clock + cancel_ms);
+                        *aborted = true;
+                    }
+                }
+                waiting.push_back(key);
+            }
+            (None, Some(end)) => {
+                clock = end;
+                let (key, start, _, aborted) = running.take().unwrap();
+                if aborted {
+                    session.cancelled += 1;
+                    session.wasted_ms += end - start;
+                } else {
+                    session.completed += 1;
+                    shown.push((end, key));
+                }
+            }
+            (None, None) => break,
+        }
+    }
+
+    session.keystroke_ms = times
+        .iter()
+        .enumerate()
+        .map(|(key, typed)| {
+            shown
+                .iter()
+                .find(|&&(_, prefix)| prefix >= key)
+                .map(|(at, _)| at - typed)
+                .expect("the last keystroke's scan always completes")
+        })
+        .collect();
+    session
+}
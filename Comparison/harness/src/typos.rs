@@ -0,0 +1,164 @@
+//! Realistic typo injection.
+//!
+//! Substitutions use physically adjacent keys on a configurable keyboard
+//! layout, so generated typos look like the ones users actually make
+//! (`Voeing` for `Boeing`) rather than random letter noise.
+
+use crate::rng::Rng;
+use std::str::FromStr;
+
+/// Keyboard layout used for adjacent-key substitutions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Layout {
+    Qwerty,
+    Qwertz,
+    Azerty,
+}
+
+impl Layout {
+    /// Key rows from the digit row down. Each row is offset to the right of
+    /// the one above it, as on a physical staggered keyboard.
+    fn rows(self) -> [&'static str; 4] {
+        match self {
+            Layout::Qwerty => ["1234567890", "qwertyuiop", "asdfghjkl", "zxcvbnm"],
+            Layout::Qwertz => ["1234567890", "qwertzuiopü", "asdfghjklöä", "yxcvbnm"],
+            Layout::Azerty => ["1234567890", "azertyuiop", "qsdfghjklm", "wxcvbn"],
+        }
+    }
+}
+
+impl FromStr for Layout {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "qwerty" => Ok(Layout::Qwerty),
+            "qwertz" => Ok(Layout::Qwertz),
+            "azerty" => Ok(Layout::Azerty),
+            _ => Err(format!("unknown keyboard layout: {s}")),
+        }
+    }
+}
+
+/// The kinds of mistake the injector can make.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TypoKind {
+    /// A neighbouring key is hit instead (`Boeing` → `Voeing`).
+    AdjacentKey,
+    /// Two neighbouring letters swap places (`Goldman` → `Goldamn`).
+    Transposition,
+    /// A letter is dropped (`Blackstone` → `Blakstone`).
+    Deletion,
+    /// A letter is typed twice (`Goldman` → `Gooldman`).
+    DoubledLetter,
+}
+
+impl TypoKind {
+    pub const ALL: [TypoKind; 4] = [
+        TypoKind::AdjacentKey,
+        TypoKind::Transposition,
+        TypoKind::Deletion,
+        TypoKind::DoubledLetter,
+    ];
+}
+
+/// Applies single-edit typos to words.
+pub struct TypoInjector {
+    pub layout: Layout,
+    /// Mistakes to choose from, uniformly.
+    pub kinds: Vec<TypoKind>,
+    /// Leave the first character alone; users rarely mistype it.
+    pub keep_first: bool,
+}
+
+impl TypoInjector {
+    pub fn new(layout: Layout) -> Self {
+        TypoInjector {
+            layout,
+            kinds: TypoKind::ALL.to_vec(),
+            keep_first: true,
+        }
+    }
+
+    /// Keys physically adjacent to `c`, in the same case as `c`.
+    pub fn neighbors(&self, c: char) -> Vec<char> {
+        let lower = c.to_lowercase().next().unwrap_or(c);
+        let rows: Vec<Vec<char>> = self
+            .layout
+            .rows()
+            .iter()
+            .map(|r| r.chars().collect())
+            .collect();
+        let Some((r, col)) = rows
+            .iter()
+            .enumerate()
+            .find_map(|(r, row)| row.iter().position(|&k| k == lower).map(|col| (r, col)))
+        else {
+            return Vec::new();
+        };
+
+        let mut keys: Vec<char> = Vec::new();
+        let mut push = |row: usize, col: Option<usize>| {
+            if let Some(&k) = col.and_then(|col| rows.get(row).and_then(|keys| keys.get(col))) {
+                keys.push(k);
+            }
+        };
+        push(r, col.checked_sub(1));
+        push(r, Some(col + 1));
+        if r > 0 {
+            push(r - 1, Some(col));
+            push(r - 1, Some(col + 1));
+        }
+        push(r + 1, col.checked_sub(1));
+        push(r + 1, Some(col));
+
+        // The digit row is only reachable from digits; a letter slipping onto
+        // a digit reads as noise rather than a plausible misspelling.
+        keys.retain(|k| k.is_ascii_digit() == lower.is_ascii_digit());
+        if c.is_uppercase() {
+            keys.iter().flat_map(|k| k.to_uppercase()).collect()
+        } else {
+            keys
+        }
+    }
+
+    /// Applies one randomly chosen typo. Returns `word` unchanged when it is
+    /// too short for any enabled kind to apply.
+    pub fn inject(&self, word: &str, rng: &mut Rng) -> String {
+        let mut kinds = self.kinds.clone();
+        rng.shuffle(&mut kinds);
+        for kind in kinds {
+            if let Some(typo) = self.inject_kind(word, kind, rng) {
+                return typo;
+            }
+        }
+        word.to_string()
+    }
+
+    /// Applies a typo of the given kind at a random position, or `None` when
+    /// the word offers no position where it would change the text.
+    pub fn inject_kind(&self, word: &str, kind: TypoKind, rng: &mut Rng) -> Option<String> {
+        let chars: Vec<char> = word.chars().collect();
+        let first = usize::from(self.keep_first);
+        let positions: Vec<usize> = (first..chars.len())
+            .filter(|&i| match kind {
+                TypoKind::AdjacentKey => !self.neighbors(chars[i]).is_empty(),
+                TypoKind::Transposition => i + 1 < chars.len() && chars[i] != chars[i + 1],
+                TypoKind::Deletion => chars.len() > 2,
+                TypoKind::DoubledLetter => chars[i].is_alphanumeric(),
+            })
+            .collect();
+        let pos = *rng.choose(&positions)?;
+
+        let mut typo = chars;
+        match kind {
+            TypoKind::AdjacentKey => typo[pos] = *rng.choose(&self.neighbors(typo[pos]))?,
+            TypoKind::Transposition => typo.swap(pos, pos + 1),
+            TypoKind::Deletion => {
+                typo.remove(pos);
+            }
+            TypoKind::DoubledLetter => typo.insert(pos, typo[pos]),
+        }
+        Some(typo.into_iter().collect())
+    }
+}
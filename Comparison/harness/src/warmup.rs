@@ -0,0 +1,116 @@
+//! How a backend is warmed up before it is timed.
+//!
+//! Backends differ in what a first query pays for. nucleo runs in the
+//! harness's own process with no JIT, so one untimed pass over the queries
+//! brings the columns and the matcher's tables into cache and the timed
+//! passes are steady from the start. A backend run as a child process pays
+//! its start-up and corpus load on every run, and much of that is the
+//! corpus file coming off disk, so what it needs is the file in the page
+//! cache. A backend with a JIT or lazily built index may need its whole
+//! first timed pass thrown away. [`Warmup`] says which of these a run does:
+//!
+//! - `queries`: how many queries, from the start of the set, are run
+//!   untimed before the first timed pass. `None` runs the whole set.
+//! - `index`: whether what the backend searches is read through once
+//!   before any query: the prepared columns of an in-process backend, the
+//!   corpus file of a child process.
+//! - `discard_first`: whether one more timed pass is run first and its
+//!   times and results thrown away. For a child process that is a whole
+//!   extra run.
+//!
+//! A run records its policy with [`Warmup::entries`], which lists only the
+//! settings that differ from its backend's default so that runs with the
+//! default keep their config hash.
+
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, Read};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Warmup {
+    /// Queries run untimed first, or `None` for all of them.
+    pub queries: Option<usize>,
+    pub index: bool,
+    pub discard_first: bool,
+}
+
+impl Warmup {
+    /// One untimed pass over the whole query set.
+    pub const IN_PROCESS: Warmup = Warmup {
+        queries: None,
+        index: false,
+        discard_first: false,
+    };
+
+    /// The corpus file read into the page cache, and no untimed queries,
+    /// since a warm-up run would pay the start-up it is meant to absorb.
+    pub const SUBPROCESS: Warmup = Warmup {
+        queries: Some(0),
+        index: true,
+        discard_first: false,
+    };
+
+    /// Parses a warm-up query count: a number, or `all`.
+    pub fn parse_queries(text: &str) -> Result<Option<usize>, String> {
+        match text.trim() {
+            "all" => Ok(None),
+            n => n
+                .parse()
+                .map(Some)
+                .map_err(|_| format!("expected a number of queries or `all`, got `{text}`")),
+        }
+    }
+
+    /// Queries the warm-up runs out of a set of `total`.
+    pub fn query_count(&self, total: usize) -> usize {
+        self.queries.map_or(total, |n| n.min(total))
+    }
+
+    /// Passes to run after the warm-up queries for `iterations` kept ones.
+    pub fn timed_passes(&self, iterations: usize) -> usize {
+        iterations + usize::from(self.discard_first)
+    }
+
+    /// The settings that differ from `default`, as fingerprint config
+    /// entries.
+    pub fn entries(&self, default: &Warmup) -> BTreeMap<String, String> {
+        let mut entries = BTreeMap::new();
+        if self.queries != default.queries {
+            let queries = self.queries.map_or("all".to_string(), |n| n.to_string());
+            entries.insert("warmup_queries".to_string(), queries);
+        }
+        if self.index != default.index {
+            entries.insert("warmup_index".to_string(), self.index.to_string());
+        }
+        if self.discard_first != default.discard_first {
+            entries.insert(
+                "warmup_discard_first".to_string(),
+                self.discard_first.to_string(),
+            );
+        }
+        entries
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "queries": self.queries,
+            "index": self.index,
+            "discard_first": self.discard_first,
+        })
+    }
+}
+
+/// Reads the file at `path` to the end and drops it, so that a process
+/// opening it next finds it in the page cache. Gives the bytes read.
+pub fn read_through(path: &str) -> io::Result<u64> {
+    let mut file = File::open(path)?;
+    let mut buf = vec![0; 1 << 20];
+    let mut total = 0;
+    loop {
+        match file.read(&mut buf)? {
+            0 => return Ok(total),
+            n => total += n as u64,
+        }
+    }
+}
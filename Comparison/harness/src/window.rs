@@ -0,0 +1,76 @@
+//! Best-window substring matching for long text.
+//!
+//! Fuzzy subsequence matchers degrade on long descriptions: almost any short
+//! query's letters appear in order somewhere in a 200-character prospectus
+//! title. The best window instead finds the substring of the text closest
+//! to the whole query by edit distance, so a match has to be local.
+
+/// The text window closest to a query.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Window {
+    /// Edit distance between the query and the window.
+    pub distance: usize,
+    /// Character range of the window in the text as given, before case
+    /// folding.
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Finds the window of `text` with the smallest edit distance to `query`,
+/// compared case-insensitively. Ties go to the leftmost window.
+///
+/// Returns `None` for an empty query, or when even the best window is more
+/// than `max_distance` edits away.
+pub fn best_window(query: &str, text: &str, max_distance: usize) -> Option<Window> {
+    let query: Vec<char> = query.chars().flat_map(char::to_lowercase).collect();
+    // A character can lower-case to several ("İ" to "i̇"), so each folded
+    // character remembers which of the original ones it came from.
+    let (text, origin): (Vec<char>, Vec<usize>) = text
+        .chars()
+        .enumerate()
+        .flat_map(|(idx, c)| c.to_lowercase().map(move |lower| (lower, idx)))
+        .unzip();
+    let text_len = origin.last().map_or(0, |&last| last + 1);
+    if query.is_empty() {
+        return None;
+    }
+
+    // Semi-global alignment: the window may start anywhere, so row 0 is all
+    // zeros. Each cell carries the text position its alignment started at.
+    let mut prev: Vec<(usize, usize)> = (0..=text.len()).map(|j| (0, j)).collect();
+    let mut row = vec![(0, 0); text.len() + 1];
+    for (i, &q) in query.iter().enumerate() {
+        row[0] = (i + 1, 0);
+        for (j, &t) in text.iter().enumerate() {
+            let diagonal = (prev[j].0 + usize::from(q != t), prev[j].1);
+            let up = (prev[j + 1].0 + 1, prev[j + 1].1);
+            let left = (row[j].0 + 1, row[j].1);
+            row[j + 1] = [diagonal, up, left]
+                .into_iter()
+                .min_by_key(|&(distance, _)| distance)
+                .unwrap();
+        }
+        std::mem::swap(&mut prev, &mut row);
+    }
+
+    let (end, &(distance, start)) = prev
+        .iter()
+        .enumerate()
+        .min_by_key(|&(end, &(distance, _))| (distance, end))?;
+    let start_char = origin.get(start).copied().unwrap_or(text_len);
+    let end_char = if end > start {
+        origin[end - 1] + 1
+    } else {
+        start_char
+    };
+    (distance <= max_distance).then_some(Window {
+        distance,
+        start: start_char,
+        end: end_char,
+    })
+}
+
+/// The default edit budget for a query: a quarter of its length, at least 1.
+pub fn default_max_distance(query: &str) -> usize {
+    (query.chars().count() / 4).max(1)
+}
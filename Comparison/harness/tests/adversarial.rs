@@ -0,0 +1,32 @@
+use harness::adversarial::{queries, CATEGORY, LONG_QUERY, REPEATED};
+use harness::corpus::Corpus;
+use harness::queries::SKIP;
+
+#[test]
+fn derives_worst_cases_from_the_corpus() {
+    let mut corpus = Corpus::with_capacity(3, 128);
+    corpus.push(1, "AAPL", "Apple Inc", "US0378331005");
+    corpus.push(2, "BRK A", "Berkshire Hathaway Class A", "US0846701086");
+    corpus.push(3, "AIR", "Airbus SE", "NL0000235190");
+
+    let found = queries(&corpus);
+    let texts: Vec<(&str, &str)> = found
+        .iter()
+        .map(|q| (q.text.as_str(), q.field.as_str()))
+        .collect();
+    let long = "berkshire hathaway class a ".repeat(10);
+    let long = long.trim_end();
+    assert_eq!(found.len(), 6);
+    assert_eq!(texts[0], ("a", "name"));
+    assert_eq!(texts[1], ("a".repeat(REPEATED).as_str(), "name"));
+    assert!(texts[2].0.len() >= LONG_QUERY);
+    assert!(long.starts_with(&texts[2].0.to_lowercase()));
+    assert_eq!(texts[3], ("Berkshire Hathaway Class A", "name"));
+    assert_eq!(texts[4], ("a", "symbol"));
+    assert_eq!(texts[5], ("BRK A", "symbol"));
+    assert!(found
+        .iter()
+        .all(|q| q.category == CATEGORY && q.expected_name == SKIP));
+
+    assert!(queries(&Corpus::with_capacity(0, 0)).is_empty());
+}
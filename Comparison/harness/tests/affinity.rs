@@ -0,0 +1,30 @@
+use harness::affinity::{parse_cpu_list, Placement};
+
+#[test]
+fn parses_kernel_cpu_lists() {
+    assert_eq!(
+        parse_cpu_list("0-3,8-9,16\n").unwrap(),
+        [0, 1, 2, 3, 8, 9, 16]
+    );
+    assert_eq!(parse_cpu_list("").unwrap(), Vec::<usize>::new());
+    assert!(parse_cpu_list("0-x").is_err());
+}
+
+#[test]
+fn places_threads_on_one_node() {
+    let nodes = vec![(0, vec![0, 1, 2, 3]), (1, vec![4, 5, 6, 7])];
+    let first = Placement::choose(&nodes, None).unwrap();
+    assert_eq!(first.node, 0);
+    assert_eq!(first.cores, [1, 2, 3, 0]);
+    let second = Placement::choose(&nodes, Some(1)).unwrap();
+    let cores: Vec<usize> = (0..6).map(|t| second.core_for(t)).collect();
+    assert_eq!(cores, [4, 5, 6, 7, 4, 5]);
+}
+
+#[test]
+fn skips_nodes_without_allowed_cores() {
+    let nodes = vec![(0, vec![]), (1, vec![0])];
+    assert_eq!(Placement::choose(&nodes, None).unwrap().node, 1);
+    assert!(Placement::choose(&nodes, Some(0)).is_err());
+    assert!(Placement::choose(&nodes, Some(2)).is_err());
+}
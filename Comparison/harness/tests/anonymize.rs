@@ -0,0 +1,90 @@
+use harness::anonymize::Anonymizer;
+use harness::isin::is_valid;
+
+const KEY: &[u8] = b"0123456789abcdef0123456789abcdef";
+const OTHER_KEY: &[u8] = b"fedcba9876543210fedcba9876543210";
+
+/// (symbol, name, ISIN) rows with the shared words, duplicate ISIN and
+/// non-Latin script the mapping has to carry over.
+const ROWS: &[(&str, &str, &str)] = &[
+    ("AAPL", "Apple Inc.", "US0378331005"),
+    ("APLE", "Apple Hospitality REIT", "US03784Y2000"),
+    ("IWDA", "iShares Core MSCI World UCITS ETF", "IE00B4L5Y983"),
+    ("SWDA", "iShares Core MSCI World UCITS ETF", "IE00B4L5Y983"),
+    ("SBER", "Сбербанк России", "RU0009029540"),
+];
+
+fn anonymize(key: &[u8]) -> Vec<(String, String, String)> {
+    let mut anonymizer = Anonymizer::new(key.to_vec());
+    ROWS.iter()
+        .map(|(symbol, name, isin)| {
+            (
+                anonymizer.text(symbol),
+                anonymizer.text(name),
+                anonymizer.isin(isin),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn the_same_key_gives_the_same_output() {
+    assert_eq!(anonymize(KEY), anonymize(KEY));
+}
+
+#[test]
+fn a_different_key_gives_different_output() {
+    let (ours, theirs) = (anonymize(KEY), anonymize(OTHER_KEY));
+    for (a, b) in ours.iter().zip(&theirs) {
+        assert_ne!(a.0, b.0);
+        assert_ne!(a.1, b.1);
+        assert_ne!(a.2, b.2);
+    }
+}
+
+#[test]
+fn fake_isins_are_valid_and_keep_their_country() {
+    let rows = anonymize(KEY);
+    for ((_, _, real), (_, _, fake)) in ROWS.iter().zip(&rows) {
+        assert!(is_valid(fake), "{fake}");
+        assert_eq!(fake[..2], real[..2]);
+    }
+    // The duplicate ISIN stays a duplicate
+    assert_eq!(rows[2].2, rows[3].2);
+    assert_ne!(rows[0].2, rows[1].2);
+}
+
+#[test]
+fn no_input_value_appears_in_the_output() {
+    let rows = anonymize(KEY);
+    let output: String = rows
+        .iter()
+        .map(|(symbol, name, isin)| format!("{symbol}\t{name}\t{isin}\n"))
+        .collect();
+    for (symbol, name, isin) in ROWS {
+        for value in [*symbol, *name, *isin] {
+            assert!(!output.contains(value), "{value} in\n{output}");
+        }
+        for word in name.split_whitespace() {
+            assert!(!output.contains(word), "{word} in\n{output}");
+        }
+    }
+}
+
+#[test]
+fn keeps_shape_and_shared_words() {
+    let rows = anonymize(KEY);
+    for ((symbol, name, _), (fake_symbol, fake_name, _)) in ROWS.iter().zip(&rows) {
+        assert_eq!(fake_symbol.chars().count(), symbol.chars().count());
+        assert_eq!(fake_name.chars().count(), name.chars().count());
+        assert_eq!(
+            fake_name.split_whitespace().count(),
+            name.split_whitespace().count()
+        );
+    }
+    assert_eq!(rows[2].1, rows[3].1);
+    assert!(rows[4]
+        .1
+        .chars()
+        .all(|c| c == ' ' || ('а'..='я').contains(&c) || ('А'..='Я').contains(&c)));
+}
@@ -0,0 +1,36 @@
+use harness::budget::{BudgetStats, Deadline, CHECK_EVERY};
+use std::time::Duration;
+
+#[test]
+fn looks_at_the_clock_every_few_rows() {
+    let spent = Deadline::new(Duration::ZERO);
+    assert!(!spent.expired(0));
+    assert!(!spent.expired(CHECK_EVERY - 1));
+    assert!(spent.expired(CHECK_EVERY));
+    assert!(!spent.expired(CHECK_EVERY + 1));
+
+    let generous = Deadline::new(Duration::from_secs(3600));
+    assert!(!generous.expired(CHECK_EVERY));
+}
+
+#[test]
+fn sums_up_cut_offs_coverage_and_quality() {
+    let mut stats = BudgetStats::default();
+    assert_eq!(stats.mean_coverage(), 1.0);
+    assert_eq!(stats.same_first(), 1.0);
+
+    // In full: the same results
+    stats.record(2.0, 100, 100, &[3, 1, 2], &[3, 1, 2]);
+    // Cut off at a quarter: one of three found, and not the first
+    stats.record(5.0, 25, 100, &[1, 8], &[3, 1, 2]);
+    // Cut off at half, for a query nothing matches
+    stats.record(5.0, 50, 100, &[], &[]);
+
+    assert_eq!(stats.scans(), 3);
+    assert_eq!(stats.cut_off(), 2);
+    assert_eq!(stats.mean_coverage(), 0.375);
+    assert_eq!(stats.min_coverage(), 0.25);
+    assert!((stats.recall() - 2.0 / 3.0).abs() < 1e-12);
+    assert!((stats.same_first() - 2.0 / 3.0).abs() < 1e-12);
+    assert_eq!(stats.time_ms(100.0), 5.0);
+}
@@ -0,0 +1,9 @@
+use harness::cache::CacheBuster;
+
+#[test]
+fn sizes_the_buffer_in_mebibytes() {
+    let mut buster = CacheBuster::new(2);
+    assert_eq!(buster.bytes(), 2 << 20);
+    buster.flush();
+    assert_eq!(CacheBuster::new(0).bytes(), 0);
+}
@@ -0,0 +1,37 @@
+use harness::capabilities::Capabilities;
+
+#[test]
+fn describes_known_backends_and_what_they_lack() {
+    let nucleo = Capabilities::of("nucleo").unwrap();
+    assert!(nucleo.scores && nucleo.indices && nucleo.multi_atom);
+    assert_eq!(Capabilities::of("nucleo-http"), Some(nucleo));
+    assert!(Capabilities::of("nucleo-grpc").unwrap().streaming);
+
+    let fzf = Capabilities::of("fzf").unwrap();
+    assert!(!fzf.supports("scores"));
+    assert_eq!(
+        fzf.missing(&["scores", "multi_atom", "indices"]),
+        ["scores", "indices"]
+    );
+    assert_eq!(fzf.lacking(), ["scores", "match positions", "streaming"]);
+
+    let wratio = Capabilities::of("rapidfuzz-wratio").unwrap();
+    assert!(wratio.scores && !wratio.normalization && !wratio.supports("no-such-capability"));
+    assert_eq!(Capabilities::of("no-such-backend"), None);
+}
+
+#[test]
+fn round_trips_through_json() {
+    let fuzzymatch_sw = Capabilities::of("fuzzymatch-sw").unwrap();
+    let json = fuzzymatch_sw.to_json();
+    assert_eq!(json["multi_atom"], true);
+    assert_eq!(json["indices"], false);
+    assert_eq!(Capabilities::from_json(&json), Some(fuzzymatch_sw));
+
+    let partial = serde_json::json!({ "scores": true });
+    assert_eq!(
+        Capabilities::from_json(&partial),
+        Capabilities::of("rapidfuzz-partial")
+    );
+    assert_eq!(Capabilities::from_json(&serde_json::json!(1)), None);
+}
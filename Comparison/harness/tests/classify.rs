@@ -0,0 +1,69 @@
+use harness::classify::{infer, Classifier, Label};
+use harness::corpus::{Corpus, Instrument};
+
+fn instrument<'a>(symbol: &'a str, name: &'a str, isin: &'a str) -> Instrument<'a> {
+    Instrument {
+        line: 2,
+        symbol,
+        name,
+        isin,
+    }
+}
+
+fn label(query: &str, target: &Instrument) -> Option<(&'static str, &'static str)> {
+    infer(query, target).map(|Label { field, category }| (field, category))
+}
+
+#[test]
+fn labels_identifier_queries() {
+    let apple = instrument("AAPL", "Apple Inc.", "US0378331005");
+    assert_eq!(label("US0378331005", &apple), Some(("isin", "exact_isin")));
+    assert_eq!(label("us037833", &apple), Some(("isin", "exact_isin")));
+    assert_eq!(label("aapl", &apple), Some(("symbol", "exact_symbol")));
+    assert_eq!(label("AA", &apple), Some(("symbol", "prefix")));
+
+    let future = instrument("WKL U6 28", "Wolters Kluwer Future", "");
+    assert_eq!(label("WKL U6", &future), Some(("symbol", "symbol_spaces")));
+}
+
+#[test]
+fn labels_name_queries() {
+    let goldman = instrument("GS", "Goldman Sachs Group", "US38141G1040");
+    assert_eq!(
+        label("Goldman Sachs Group", &goldman),
+        Some(("name", "exact_name"))
+    );
+    assert_eq!(label("Goldman Sa", &goldman), Some(("name", "prefix")));
+    assert_eq!(
+        label("sachs goldman", &goldman),
+        Some(("name", "multi_word"))
+    );
+    assert_eq!(label("Sachs", &goldman), Some(("name", "substring")));
+    assert_eq!(label("Goldamn Sachs", &goldman), Some(("name", "typo")));
+
+    let jnj = instrument("J", "Johnson & Johnson", "US4781601046");
+    assert_eq!(label("jnj", &jnj), Some(("name", "abbreviation")));
+}
+
+#[test]
+fn leaves_unrelated_queries_unlabelled() {
+    let apple = instrument("AAPL", "Apple Inc.", "US0378331005");
+    assert_eq!(label("microsoft", &apple), None);
+    assert_eq!(label("  ", &apple), None);
+}
+
+#[test]
+fn classifier_picks_the_most_specific_label_in_the_corpus() {
+    let mut corpus = Corpus::default();
+    corpus.push(2, "AAPLX", "Apple Growth Fund", "");
+    corpus.push(3, "AAPL", "Apple Inc.", "US0378331005");
+    corpus.push(4, "MSFT", "Microsoft Corp", "US5949181045");
+    let classifier = Classifier::new(&corpus);
+    let classify = |query| classifier.classify(query).map(|(l, idx)| (l.category, idx));
+
+    assert_eq!(classify("aapl"), Some(("exact_symbol", 1)));
+    assert_eq!(classify("AAP"), Some(("prefix", 0)));
+    assert_eq!(classify("growth fund"), Some(("multi_word", 0)));
+    assert_eq!(classify("microsfot"), Some(("typo", 2)));
+    assert_eq!(classify("nvidia"), None);
+}
@@ -0,0 +1,9 @@
+use std::fs;
+use std::path::PathBuf;
+
+/// Writes `bytes` to a file in Cargo's per-test-target temp directory.
+pub fn temp_file(name: &str, bytes: &[u8]) -> String {
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+    fs::write(&path, bytes).unwrap();
+    path.to_string_lossy().to_string()
+}
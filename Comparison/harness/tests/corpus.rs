@@ -0,0 +1,229 @@
+mod common;
+
+use common::temp_file;
+use harness::corpus::{
+    load_corpus, load_corpus_mapped, load_corpus_progress, read_tsv_corpus, Corpus, FieldMap,
+};
+
+#[test]
+fn parses_field_map_flag() {
+    let map: FieldMap = "symbol=ticker,name=long_name".parse().unwrap();
+    assert_eq!(map.symbol, "ticker");
+    assert_eq!(map.name, "long_name");
+    assert_eq!(map.isin, "isin");
+    assert!("ticker=symbol".parse::<FieldMap>().is_err());
+    assert!("symbol".parse::<FieldMap>().is_err());
+}
+
+#[test]
+fn loads_jsonl_through_field_map() {
+    let path = temp_file(
+        "mapped.jsonl",
+        br#"{"ticker": "AAPL", "long_name": "Apple Inc.", "ids": {"isin": "US0378331005"}}
+
+{"ticker": 7203, "long_name": "Toyota", "ids": {"isin": null}}
+"#,
+    );
+    let map: FieldMap = "symbol=ticker,name=long_name,isin=ids.isin"
+        .parse()
+        .unwrap();
+    let corpus = load_corpus_mapped(&path, &map).unwrap();
+    assert_eq!(corpus.len(), 2);
+    assert_eq!(corpus.get(0).symbol, "AAPL");
+    assert_eq!(corpus.get(0).name, "Apple Inc.");
+    assert_eq!(corpus.get(0).isin, "US0378331005");
+    assert_eq!(corpus.get(1).symbol, "7203");
+    assert_eq!(corpus.get(1).isin, "");
+}
+
+#[test]
+fn reports_the_line_of_invalid_jsonl() {
+    let path = temp_file("broken.jsonl", b"{\"symbol\": \"A\"}\n{oops\n");
+    let err = load_corpus(&path).err().unwrap();
+    assert!(err.to_string().contains("line 2"), "{err}");
+}
+
+#[test]
+fn resolves_tsv_columns_by_header() {
+    let path = temp_file(
+        "reordered.tsv",
+        b"ISIN\tExchange\tName\tSymbol\nUS0378331005\tXNAS\tApple Inc.\tAAPL\nshort\trow\n",
+    );
+    let corpus = load_corpus(&path).unwrap();
+    assert_eq!(corpus.len(), 1);
+    assert_eq!(corpus.get(0).line, 2);
+    assert_eq!(corpus.get(0).symbol, "AAPL");
+    assert_eq!(corpus.get(0).name, "Apple Inc.");
+    assert_eq!(corpus.get(0).isin, "US0378331005");
+}
+
+#[test]
+fn maps_renamed_tsv_columns() {
+    let path = temp_file(
+        "renamed.tsv",
+        b"ticker\tlong_name\tisin\nAAPL\tApple Inc.\tUS0378331005\n",
+    );
+    let err = load_corpus(&path).err().unwrap();
+    assert!(err.to_string().contains("no `symbol` column"));
+
+    let map: FieldMap = "symbol=Ticker,name=long_name".parse().unwrap();
+    let corpus = load_corpus_mapped(&path, &map).unwrap();
+    assert_eq!(corpus.get(0).symbol, "AAPL");
+    assert_eq!(corpus.get(0).name, "Apple Inc.");
+}
+
+#[test]
+fn streams_rows_into_the_arena() {
+    let path = temp_file(
+        "crlf.tsv",
+        b"Symbol\tName\tISIN\r\nAAPL\tApple Inc.\tUS0378331005\r\n\r\nMSFT\tMicrosoft\t\r\n",
+    );
+    let corpus = load_corpus(&path).unwrap();
+    let rows: Vec<(usize, &str, &str, &str)> = corpus
+        .iter()
+        .map(|i| (i.line, i.symbol, i.name, i.isin))
+        .collect();
+    assert_eq!(
+        rows,
+        [
+            (2, "AAPL", "Apple Inc.", "US0378331005"),
+            (4, "MSFT", "Microsoft", ""),
+        ]
+    );
+    assert_eq!((&corpus).into_iter().len(), 2);
+}
+
+#[test]
+fn truncates_to_leading_rows() {
+    let mut corpus = Corpus::default();
+    corpus.set_metadata_columns(vec!["Exchange".to_string()]);
+    corpus.push_with_metadata(2, "AAPL", "Apple Inc.", "US0378331005", &["XNAS"]);
+    corpus.push_with_metadata(3, "SAP", "SAP SE", "DE0007164600", &["XETR"]);
+    corpus.push_with_metadata(4, "MSFT", "Microsoft", "", &["XNAS"]);
+    corpus.truncate(2);
+    assert_eq!(corpus.len(), 2);
+    assert_eq!(corpus.get(1).name, "SAP SE");
+    assert_eq!(corpus.metadata(1).get("exchange"), Some("XETR"));
+    corpus.truncate(5);
+    assert_eq!(corpus.len(), 2);
+    corpus.truncate(0);
+    assert!(corpus.is_empty());
+}
+
+#[test]
+fn repairs_or_drops_rows_that_are_not_utf8() {
+    let path = temp_file(
+        "latin1.tsv",
+        b"Symbol\tName\tISIN\nMUV2\tM\xfcnchener R\xfcck\tDE0008430026\nBAD\t\x8f\t\nSAP\tSAP SE\t\n",
+    );
+    let corpus = load_corpus(&path).unwrap();
+    let names: Vec<&str> = corpus.iter().map(|i| i.name).collect();
+    assert_eq!(names, ["Münchener Rück", "SAP SE"]);
+    assert_eq!(corpus.repairs().repaired, [2]);
+    assert_eq!(corpus.repairs().dropped, [3]);
+}
+
+#[test]
+fn keeps_extra_tsv_columns_as_metadata() {
+    let path = temp_file(
+        "metadata.tsv",
+        b"Exchange\tSymbol\tName\tISIN\tCurrency\nXNAS\tAAPL\tApple Inc.\tUS0378331005\tUSD\nXETR\tSAP\tSAP SE\tDE0007164600\n",
+    );
+    let corpus = load_corpus(&path).unwrap();
+    assert_eq!(corpus.metadata_columns(), ["Exchange", "Currency"]);
+    assert_eq!(corpus.metadata_column("currency"), Some(1));
+    let apple = corpus.metadata(0);
+    assert_eq!(apple.get("exchange"), Some("XNAS"));
+    assert_eq!(apple.values().collect::<Vec<_>>(), ["XNAS", "USD"]);
+    // A short row still loads; its missing metadata reads as empty.
+    assert_eq!(corpus.get(1).name, "SAP SE");
+    assert_eq!(
+        corpus.metadata(1).values().collect::<Vec<_>>(),
+        ["XETR", ""]
+    );
+    assert_eq!(corpus.metadata(1).get("sector"), None);
+}
+
+#[test]
+fn keeps_scalar_jsonl_keys_as_metadata() {
+    let path = temp_file(
+        "metadata.jsonl",
+        br#"{"ticker":"AAPL","name":"Apple Inc.","isin":"US0378331005","mic":"XNAS","lot":1,"ids":{"cusip":"037833100"}}
+{"ticker":"SAP","name":"SAP SE","isin":"DE0007164600","lot":5}
+"#,
+    );
+    let map: FieldMap = "symbol=ticker".parse().unwrap();
+    let corpus = load_corpus_mapped(&path, &map).unwrap();
+    assert_eq!(corpus.metadata_columns(), ["lot", "mic"]);
+    assert_eq!(
+        corpus.metadata(0).values().collect::<Vec<_>>(),
+        ["1", "XNAS"]
+    );
+    assert_eq!(corpus.metadata(1).values().collect::<Vec<_>>(), ["5", ""]);
+}
+
+#[test]
+fn text_searches_long_text_columns() {
+    let path = temp_file(
+        "long-text.tsv",
+        b"Symbol\tName\tISIN\tDescription\nIWDA\tiShares Core MSCI World\tIE00B4L5Y983\tAccumulating share class tracking developed market equities\n",
+    );
+    let corpus = load_corpus(&path).unwrap();
+    assert_eq!(corpus.text(0, "symbol"), "IWDA");
+    assert_eq!(
+        corpus.text(0, "description"),
+        "Accumulating share class tracking developed market equities"
+    );
+    assert_eq!(corpus.text(0, "unknown"), "iShares Core MSCI World");
+}
+
+#[test]
+fn reads_tsv_from_memory() {
+    let tsv =
+        "symbol\tname\tisin\tmic\nAAPL\tApple Inc.\tUS0378331005\tXNAS\nVOD\tVodafone\t\tXLON\n";
+    let corpus = read_tsv_corpus(&mut tsv.as_bytes(), &FieldMap::default()).unwrap();
+    assert_eq!(corpus.len(), 2);
+    assert_eq!(corpus.get(1).name, "Vodafone");
+    assert_eq!(corpus.metadata(1).get("mic"), Some("XLON"));
+    assert!(read_tsv_corpus(&mut "ticker\tname\n".as_bytes(), &FieldMap::default()).is_err());
+}
+
+#[test]
+fn reports_progress_while_loading() {
+    let mut tsv = String::from("symbol\tname\tisin\n");
+    for i in 0..7 {
+        tsv.push_str(&format!("S{i}\tName {i}\t\n"));
+    }
+    let path = temp_file("progress.tsv", tsv.as_bytes());
+    let mut seen = Vec::new();
+    let corpus = load_corpus_progress(&path, &FieldMap::default(), 3, &mut |partial| {
+        seen.push((
+            partial.len(),
+            partial.get(partial.len() - 1).symbol.to_string(),
+        ));
+    })
+    .unwrap();
+    assert_eq!(corpus.len(), 7);
+    assert_eq!(seen, vec![(3, "S2".to_string()), (6, "S5".to_string())]);
+}
+
+#[test]
+fn loads_excel_exports() {
+    let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/");
+    // Tab-delimited text (UTF-8 with a byte order mark) and "Unicode Text"
+    // (UTF-16), both with CRLF line endings and quoted cells
+    for name in ["excel-corpus.tsv", "excel-corpus-unicode.txt"] {
+        let corpus = load_corpus(&format!("{dir}{name}")).unwrap();
+        assert_eq!(corpus.len(), 3, "{name}");
+        assert_eq!(corpus.get(0).symbol, "AAPL", "{name}");
+        assert_eq!(corpus.get(1).name, "Berkshire Hathaway \"B\"", "{name}");
+        assert_eq!(corpus.get(2).isin, "DE0008430026", "{name}");
+        assert_eq!(corpus.metadata_columns(), ["Exchange"], "{name}");
+        assert_eq!(corpus.metadata(0).get("exchange"), Some("XNAS"), "{name}");
+        assert_eq!(
+            corpus.metadata(2).get("exchange"),
+            Some("XETR\tXFRA"),
+            "{name}"
+        );
+    }
+}
@@ -0,0 +1,75 @@
+use harness::cost::{CostModel, QueryCost};
+
+fn cost(scanned: usize, prefiltered: usize, matched: usize) -> QueryCost {
+    QueryCost {
+        scanned,
+        filtered: 0,
+        prefiltered,
+        scored: scanned - prefiltered,
+        matched,
+        inserted: matched.min(10),
+    }
+}
+
+#[test]
+fn fits_per_step_costs() {
+    // 2 ns a visit, 30 ns a score, 100 ns a match, 500 ns an insertion
+    let ms = |c: &QueryCost| {
+        (2.0 * c.scanned as f64
+            + 30.0 * c.scored as f64
+            + 100.0 * c.matched as f64
+            + 500.0 * c.inserted as f64)
+            / 1e6
+    };
+    let samples: Vec<(QueryCost, f64)> = [
+        cost(50_000, 0, 900),
+        cost(50_000, 48_000, 4),
+        cost(50_000, 40_000, 60),
+        cost(20_000, 15_000, 7),
+        cost(20_000, 2_000, 3_000),
+    ]
+    .into_iter()
+    .map(|c| (c, ms(&c)))
+    .collect();
+    let model = CostModel::fit(&samples).unwrap();
+    assert!((model.ns_per_scanned - 2.0).abs() < 1e-3);
+    assert!((model.ns_per_scored - 30.0).abs() < 1e-3);
+    assert!((model.ns_per_matched - 100.0).abs() < 1e-3);
+    assert!((model.ns_per_inserted - 500.0).abs() < 1e-3);
+    assert!(model.r_squared > 0.999_999);
+}
+
+#[test]
+fn folds_scores_into_visits_without_a_prefilter() {
+    let samples = [
+        (cost(50_000, 0, 0), 1.0),
+        (cost(100_000, 0, 0), 2.0),
+        (cost(20_000, 0, 0), 0.4),
+    ];
+    let model = CostModel::fit(&samples).unwrap();
+    assert!((model.ns_per_scanned - 20.0).abs() < 1e-6);
+    assert_eq!(model.ns_per_scored, 0.0);
+    assert!((model.predict_ms(&cost(10_000, 0, 0)) - 0.2).abs() < 1e-9);
+    assert!(CostModel::fit(&samples[..1]).is_none());
+
+    // Counts off by the same scale for every query no longer fit
+    let halved: Vec<(QueryCost, f64)> = samples
+        .iter()
+        .map(|(c, ms)| (cost(c.scanned / 2, 0, 0), *ms))
+        .collect();
+    assert!((model.explained(&samples) - 1.0).abs() < 1e-9);
+    assert!(model.explained(&halved) < 0.5);
+}
+
+#[test]
+fn counts_skipped_candidates() {
+    let mut total = cost(100, 60, 5);
+    total += QueryCost {
+        scanned: 100,
+        filtered: 100,
+        ..QueryCost::default()
+    };
+    assert_eq!(total.scanned, 200);
+    assert_eq!(total.skip_ratio(), 0.8);
+    assert_eq!(QueryCost::default().skip_ratio(), 0.0);
+}
@@ -0,0 +1,50 @@
+use harness::corpus::Corpus;
+use harness::dedup::Duplicates;
+
+fn corpus(rows: &[(&str, &str, &str)]) -> Corpus {
+    let mut corpus = Corpus::default();
+    for (i, (symbol, name, isin)) in rows.iter().enumerate() {
+        corpus.push(i + 2, symbol, name, isin);
+    }
+    corpus
+}
+
+#[test]
+fn finds_exact_duplicates_and_isin_conflicts() {
+    let corpus = corpus(&[
+        ("SAP", "SAP SE", "DE0007164600"),
+        ("SAP", "SAP SE", "DE0007164600"),
+        ("SAP", "SAP SE ADS", "DE0007164600"),
+        ("BMW", "BMW AG", "DE0005190003"),
+        ("X", "No ISIN", ""),
+        ("X", "No ISIN", ""),
+    ]);
+    let duplicates = Duplicates::find(&corpus);
+    assert_eq!(duplicates.exact, [1, 5]);
+    assert_eq!(duplicates.conflicting_isins, 1);
+}
+
+#[test]
+fn removes_later_copies_keeping_line_numbers() {
+    let corpus = corpus(&[
+        ("A", "Alpha", "US0000000001"),
+        ("A", "Alpha", "US0000000001"),
+        ("B", "Beta", "US0000000002"),
+    ]);
+    let deduplicated = Duplicates::find(&corpus).remove_from(&corpus);
+    let lines: Vec<usize> = deduplicated.iter().map(|i| i.line).collect();
+    assert_eq!(lines, [2, 4]);
+    assert_eq!(deduplicated.get(1).name, "Beta");
+}
+
+#[test]
+fn removal_keeps_metadata() {
+    let mut corpus = Corpus::default();
+    corpus.set_metadata_columns(vec!["Exchange".to_string()]);
+    corpus.push_with_metadata(2, "SAP", "SAP SE", "DE0007164600", &["XETR"]);
+    corpus.push_with_metadata(3, "SAP", "SAP SE", "DE0007164600", &["XETR"]);
+    corpus.push_with_metadata(4, "BMW", "BMW AG", "DE0005190003", &["XFRA"]);
+    let deduplicated = Duplicates::find(&corpus).remove_from(&corpus);
+    assert_eq!(deduplicated.metadata_columns(), ["Exchange"]);
+    assert_eq!(deduplicated.metadata(1).get("Exchange"), Some("XFRA"));
+}
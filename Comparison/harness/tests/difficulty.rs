@@ -0,0 +1,58 @@
+use harness::corpus::Corpus;
+use harness::difficulty::{Difficulty, Estimator};
+use harness::queries::{Query, SKIP};
+
+fn corpus() -> Corpus {
+    let mut corpus = Corpus::with_capacity(4, 128);
+    corpus.push(1, "AAPL", "Apple Inc.", "US0378331005");
+    corpus.push(2, "APLE", "Apple Hospitality REIT", "US03784Y2000");
+    corpus.push(3, "MSFT", "Microsoft Corp.", "US5949181045");
+    corpus.push(4, "XOM", "Exxon Mobil Corp.", "US30231G1022");
+    corpus
+}
+
+fn query(text: &str, field: &str, expected_name: &str) -> Query {
+    Query {
+        text: text.to_string(),
+        field: field.to_string(),
+        category: "exact_symbol".to_string(),
+        expected_name: expected_name.to_string(),
+        frequency: 1,
+    }
+}
+
+#[test]
+fn rates_a_query_against_the_corpus() {
+    let corpus = corpus();
+    let mut estimator = Estimator::new(&corpus);
+
+    let apple = estimator.estimate(&query("Apple", "name", "Apple Inc"));
+    assert_eq!(apple.lookalikes, 1);
+    assert_eq!(apple.accepted, Some(1));
+    assert!((apple.char_share - 0.5).abs() < 1e-9);
+
+    // Both Apples are acceptable, so neither is a lookalike
+    let either = estimator.estimate(&query("Apple", "name", "apple"));
+    assert_eq!((either.lookalikes, either.accepted), (0, Some(2)));
+    assert!(either.score() < apple.score());
+
+    let unjudged = estimator.estimate(&query("a", "symbol", SKIP));
+    assert_eq!((unjudged.lookalikes, unjudged.accepted), (2, None));
+}
+
+#[test]
+fn scores_stay_between_zero_and_one_and_round_trip() {
+    let easy = Difficulty {
+        char_share: 0.0,
+        lookalikes: 0,
+        accepted: Some(400),
+    };
+    assert_eq!(easy.score(), 0.0);
+    let hard = Difficulty {
+        char_share: 1.0,
+        lookalikes: 10_000,
+        accepted: None,
+    };
+    assert!(hard.score() > 0.99 && hard.score() <= 1.0);
+    assert_eq!(Difficulty::from_json(&hard.to_json()), Some(hard));
+}
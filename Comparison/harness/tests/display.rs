@@ -0,0 +1,64 @@
+use harness::display::{cell, pad, pad_start, truncate, truncate_start, width};
+
+#[test]
+fn measures_wide_characters_as_two_columns() {
+    assert_eq!(width("apple"), 5);
+    assert_eq!(width("日本"), 4);
+    assert_eq!(width("Société"), 7);
+    assert_eq!(width(""), 0);
+}
+
+#[test]
+fn truncates_at_character_boundaries_by_width() {
+    assert_eq!(truncate("apple", 5), "apple");
+    assert_eq!(truncate("apple inc", 8), "apple...");
+    // A cut inside a multi-byte character would panic on a byte slice
+    assert_eq!(truncate("Société Générale", 10), "Société...");
+    // A wide character that would straddle the limit is left out
+    assert_eq!(truncate("日本電信電話", 8), "日本...");
+    assert_eq!(width(&truncate("日本電信電話", 8)), 7);
+    assert_eq!(truncate("apple", 2), "..");
+    assert_eq!(truncate("apple", 0), "");
+}
+
+#[test]
+fn pads_to_display_width() {
+    assert_eq!(pad("ab", 4), "ab  ");
+    assert_eq!(pad("日本", 6), "日本  ");
+    assert_eq!(pad("toolong", 3), "toolong");
+    assert_eq!(pad_start("日本", 6), "  日本");
+    for text in [
+        "Deutsche Bank",
+        "三菱UFJフィナンシャル・グループ",
+        "Crédit Agricole Société Anonyme",
+    ] {
+        assert_eq!(width(&cell(text, 16)), 16);
+    }
+}
+
+#[test]
+fn keeps_grapheme_clusters_whole() {
+    // "e" and a combining acute accent are one character to the reader
+    let decomposed = "Socie\u{301}te\u{301} Ge\u{301}ne\u{301}rale";
+    assert_eq!(truncate(decomposed, 8), "Socie\u{301}...");
+    // A family emoji is four people joined into one two-column glyph
+    let family = "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}\u{200D}\u{1F466} fund";
+    assert_eq!(
+        truncate(family, 6),
+        "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}\u{200D}\u{1F466} ..."
+    );
+    assert_eq!(
+        truncate("\u{1F1E9}\u{1F1EA}\u{1F1EB}\u{1F1F7} flags", 6),
+        "\u{1F1E9}\u{1F1EA}..."
+    );
+}
+
+#[test]
+fn truncates_from_the_start_to_keep_the_end() {
+    assert_eq!(truncate_start("instruments-export", 12), "...ts-export");
+    assert_eq!(width(&truncate_start("instruments-export", 11)), 11);
+    assert_eq!(truncate_start("short", 12), "short");
+    assert_eq!(truncate_start("日本電信電話", 8), "...電話");
+    assert_eq!(truncate_start("xanne\u{301}e", 4), "...e");
+    assert_eq!(truncate_start("xanne\u{301}e", 5), "...e\u{301}e");
+}
@@ -0,0 +1,24 @@
+use harness::encoding::decode_line;
+use std::borrow::Cow;
+
+#[test]
+fn borrows_valid_utf8() {
+    assert!(matches!(
+        decode_line("Münchener Rück".as_bytes()),
+        Some(Cow::Borrowed("Münchener Rück"))
+    ));
+}
+
+#[test]
+fn repairs_windows_1252_bytes_around_valid_utf8() {
+    assert_eq!(
+        decode_line(b"M\xfcnchener R\xfcck \x96 Caf\xc3\xa9").as_deref(),
+        Some("Münchener Rück – Café")
+    );
+    assert_eq!(decode_line(b"\x80 100").as_deref(), Some("€ 100"));
+}
+
+#[test]
+fn rejects_bytes_undefined_in_windows_1252() {
+    assert_eq!(decode_line(b"bad \x81 byte"), None);
+}
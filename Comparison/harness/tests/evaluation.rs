@@ -0,0 +1,132 @@
+mod common;
+
+use common::temp_file;
+use harness::corpus::Corpus;
+use harness::evaluation::{Evaluation, MatcherBackend, NucleoBackend, Search};
+use harness::preset::Preset;
+use harness::queries::Query;
+use harness::results::ResultSet;
+use harness::warmup::Warmup;
+use std::collections::BTreeMap;
+
+const CORPUS: &str = "symbol\tname\tisin\n\
+                      AAPL\tApple Inc.\tUS0378331005\n\
+                      APLE\tApple Hospitality REIT\tUS03784Y2000\n\
+                      MSFT\tMicrosoft Corp.\tUS5949181045\n";
+const QUERIES: &str = "aapl\tsymbol\texact_symbol\tApple Inc.\n\
+                       apple\tname\tprefix\tApple Inc.\n\
+                       zzzz\tname\ttypo\t_SKIP_\n";
+
+/// Matches rows whose field equals the query, counting its calls.
+#[derive(Default)]
+struct Exact {
+    rows: Vec<(String, String)>,
+    searches: usize,
+}
+
+impl MatcherBackend for Exact {
+    fn name(&self) -> &str {
+        "exact"
+    }
+
+    fn config(&self) -> BTreeMap<String, String> {
+        BTreeMap::from([("matcher".to_string(), "exact".to_string())])
+    }
+
+    fn prepare(&mut self, corpus: &Corpus, _queries: &[Query]) {
+        self.rows = corpus
+            .iter()
+            .map(|row| (row.symbol.to_lowercase(), row.name.to_lowercase()))
+            .collect();
+    }
+
+    fn search(&mut self, query: &Query, limit: usize) -> Search {
+        self.searches += 1;
+        let top: Vec<(usize, Option<f64>)> = self
+            .rows
+            .iter()
+            .enumerate()
+            .filter(|(_, (symbol, name))| {
+                let text = if query.field == "symbol" {
+                    symbol
+                } else {
+                    name
+                };
+                *text == query.text
+            })
+            .map(|(row, _)| (row, None))
+            .collect();
+        Search {
+            matches: top.len(),
+            top: top.into_iter().take(limit).collect(),
+        }
+    }
+}
+
+fn paths(name: &str) -> (String, String) {
+    (
+        temp_file(&format!("{name}-corpus.tsv"), CORPUS.as_bytes()),
+        temp_file(&format!("{name}-queries.tsv"), QUERIES.as_bytes()),
+    )
+}
+
+#[test]
+fn times_nucleo_into_a_unified_result_set() {
+    let (corpus, queries) = paths("evaluation-nucleo");
+    let mut backend = NucleoBackend::new(Preset::default());
+    let results = Evaluation::new(&corpus, &queries)
+        .iterations(3)
+        .run_label("acme")
+        .run(&mut backend)
+        .unwrap();
+    assert_eq!(results.label(), "nucleo (acme)");
+    assert_eq!(
+        results.corpus.as_deref(),
+        Some("evaluation-nucleo-corpus.tsv")
+    );
+    assert_eq!(results.queries.len(), 3);
+    let apple = &results.queries[1];
+    assert_eq!(apple.matches, Some(2));
+    assert_eq!(apple.results.as_ref().unwrap()[0].symbol, "AAPL");
+    assert_eq!(apple.found(), Some(true));
+    assert!(apple.median_ms.is_some());
+    assert_eq!(results.queries[2].results.as_deref(), Some(&[][..]));
+    assert_eq!(results.steady_state.as_ref().unwrap().iterations, 3);
+    let startup = results.startup.as_ref().unwrap();
+    assert!(startup.phase_ms("load").is_some() && startup.phase_ms("prepare").is_some());
+    let fingerprint = results.fingerprint.as_ref().unwrap();
+    assert_eq!(fingerprint.config["iterations"], "3");
+    assert_eq!(fingerprint.config["preset"], "default");
+
+    // The file reads back like any other run's
+    let path = temp_file("evaluation-nucleo.json", b"");
+    results.write(&path).unwrap();
+    assert_eq!(ResultSet::read(&path).unwrap(), results);
+}
+
+#[test]
+fn runs_any_backend_with_its_warmup() {
+    let (corpus, queries) = paths("evaluation-exact");
+    let mut backend = Exact::default();
+    let warmup = Warmup {
+        queries: Some(1),
+        index: false,
+        discard_first: true,
+    };
+    let results = Evaluation::new(&corpus, &queries)
+        .iterations(2)
+        .warmup(warmup)
+        .run(&mut backend)
+        .unwrap();
+    // One warm-up query, then a discarded pass and two kept ones
+    assert_eq!(backend.searches, 1 + 3 * 3);
+    assert_eq!(results.backend, "exact");
+    assert_eq!(
+        results.queries[0].results.as_ref().unwrap()[0].name,
+        "Apple Inc."
+    );
+    assert_eq!(results.queries[0].results.as_ref().unwrap()[0].score, None);
+    let config = &results.fingerprint.as_ref().unwrap().config;
+    assert_eq!(config["matcher"], "exact");
+    assert!(config.contains_key("warmup_discard_first"), "{config:?}");
+}
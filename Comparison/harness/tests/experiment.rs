@@ -0,0 +1,112 @@
+mod common;
+
+use common::temp_file;
+use harness::experiment::{bucket, preset_sha256, summarize, EventLog, Experiment};
+use harness::fingerprint::Fingerprint;
+use harness::preset::Preset;
+use serde_json::json;
+use std::fs;
+use std::io::Cursor;
+
+fn autocomplete() -> Preset {
+    Preset {
+        name: "autocomplete".to_string(),
+        tie_break: "shorter".to_string(),
+        prefer_prefix: true,
+        ..Preset::default()
+    }
+}
+
+#[test]
+fn assignment_is_sticky_and_near_the_share() {
+    let experiment = Experiment::new("prefix", Preset::default(), autocomplete(), 0.2).unwrap();
+    let keys: Vec<String> = (0..10_000).map(|i| format!("user-{i}")).collect();
+    let variant = keys
+        .iter()
+        .filter(|k| experiment.assign(k).label == "variant")
+        .count();
+    assert!((1_800..2_200).contains(&variant), "{variant}");
+    assert_eq!(experiment.assign("user-7"), experiment.assign("user-7"));
+
+    // Another experiment splits the same users differently
+    let moved = keys
+        .iter()
+        .filter(|k| (bucket("prefix", k) < 0.2) != (bucket("other", k) < 0.2))
+        .count();
+    assert!(moved > 1_000, "{moved}");
+    assert!(keys
+        .iter()
+        .all(|k| (0.0..1.0).contains(&bucket("prefix", k))));
+}
+
+#[test]
+fn shares_outside_zero_to_one_are_rejected() {
+    assert!(Experiment::new("x", Preset::default(), autocomplete(), 1.5).is_err());
+    let none = Experiment::new("x", Preset::default(), autocomplete(), 0.0).unwrap();
+    assert_eq!(none.assign("anyone").label, "control");
+    let all = Experiment::new("x", Preset::default(), autocomplete(), 1.0).unwrap();
+    assert_eq!(all.assign("anyone").preset.name, "autocomplete");
+}
+
+#[test]
+fn reads_the_experiment_from_flags() {
+    let args: Vec<String> = [
+        "server",
+        "--variant",
+        "autocomplete",
+        "--variant-share",
+        "0.5",
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect();
+    let experiment = Experiment::from_args(&args).unwrap().unwrap();
+    assert_eq!(experiment.name, "default-vs-autocomplete");
+    assert_eq!(experiment.share, 0.5);
+    assert_eq!(experiment.variant.preset.tie_break, "shorter");
+    assert!(Experiment::from_args(&args[..1]).unwrap().is_none());
+}
+
+#[test]
+fn arms_hash_like_a_fingerprint_of_the_preset() {
+    let preset = autocomplete();
+    let fingerprint = Fingerprint::capture(&[], preset.entries());
+    assert_eq!(preset_sha256(&preset), fingerprint.config_sha256());
+    assert_ne!(preset_sha256(&preset), preset_sha256(&Preset::default()));
+}
+
+#[test]
+fn summarizes_click_through_by_arm() {
+    let path = temp_file("experiment-events.jsonl", b"");
+    let log = EventLog::open(&path).unwrap();
+    let search = |id: &str, arm: &str| {
+        json!({"event": "search", "request_id": id, "experiment": "prefix",
+               "arm": arm, "preset": arm, "config_sha256": "abc"})
+    };
+    let click = |id: &str, rank: u64| json!({"event": "click", "request_id": id, "rank": rank});
+    for event in [
+        search("1", "control"),
+        search("2", "variant"),
+        search("3", "control"),
+        click("1", 3),
+        click("1", 2),
+        click("2", 1),
+        click("missing", 1),
+    ] {
+        log.record(&event).unwrap();
+    }
+    let text = fs::read_to_string(&path).unwrap() + "not json\n";
+
+    let arms = summarize(Cursor::new(text)).unwrap();
+    assert_eq!(arms.len(), 2);
+    let (control, variant) = (&arms[0], &arms[1]);
+    assert_eq!(
+        (control.arm.as_str(), control.searches, control.clicked),
+        ("control", 2, 1)
+    );
+    assert_eq!(control.click_through(), 0.5);
+    assert_eq!(control.mrr(), 0.25);
+    assert_eq!((variant.searches, variant.clicked), (1, 1));
+    assert_eq!(variant.mrr(), 1.0);
+    assert_eq!(variant.config_sha256, "abc");
+}
@@ -0,0 +1,58 @@
+use harness::corpus::Corpus;
+use harness::filter::Filter;
+
+fn corpus() -> Corpus {
+    let mut corpus = Corpus::default();
+    corpus.set_metadata_columns(vec![
+        "Exchange".into(),
+        "Currency".into(),
+        "Product Class".into(),
+    ]);
+    corpus.push_with_metadata(
+        2,
+        "AAPL",
+        "Apple Inc.",
+        "",
+        &["XNAS", "USD", "Equity Share"],
+    );
+    corpus.push_with_metadata(3, "APC", "Apple Inc.", "", &["XETR", "EUR", "Equity Share"]);
+    corpus.push_with_metadata(4, "IBM", "IBM", "", &["XNYS", "USD", "Equity Share"]);
+    corpus.push_with_metadata(5, "QQQ", "Invesco QQQ", "", &["XNAS", "USD", "ETF"]);
+    corpus
+}
+
+fn select(filter: &str) -> Vec<usize> {
+    let corpus = corpus();
+    let filter: Filter = filter.parse().unwrap();
+    filter.compile(&corpus).unwrap().select(&corpus)
+}
+
+#[test]
+fn all_clauses_must_hold() {
+    assert_eq!(select("exchange=XNAS currency=USD"), [0, 3]);
+    assert_eq!(
+        select("exchange=xnas currency=usd \"product class\"=etf"),
+        [3]
+    );
+    assert_eq!(select("currency=GBP"), Vec::<usize>::new());
+}
+
+#[test]
+fn alternatives_and_quoted_values() {
+    assert_eq!(select("exchange=XNAS|XNYS"), [0, 2, 3]);
+    assert_eq!(
+        select("\"Product Class\"=\"Equity Share\" currency=USD"),
+        [0, 2]
+    );
+}
+
+#[test]
+fn rejects_malformed_filters_and_unknown_columns() {
+    assert!("exchange".parse::<Filter>().is_err());
+    assert!("=XNAS".parse::<Filter>().is_err());
+    assert!("\"Product Class=ETF".parse::<Filter>().is_err());
+    let filter: Filter = "sector=Tech".parse().unwrap();
+    let err = filter.compile(&corpus()).unwrap_err();
+    assert!(err.contains("sector"));
+    assert!("".parse::<Filter>().unwrap().is_empty());
+}
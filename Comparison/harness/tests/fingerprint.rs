@@ -0,0 +1,86 @@
+mod common;
+
+use common::temp_file;
+use harness::fingerprint::Fingerprint;
+use harness::results::ResultSet;
+use std::collections::BTreeMap;
+
+fn config(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+    pairs
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+#[test]
+fn config_hash_ignores_insertion_order() {
+    let a = Fingerprint {
+        config: config(&[("case", "ignore"), ("iterations", "5")]),
+        ..Fingerprint::default()
+    };
+    let b = Fingerprint {
+        config: config(&[("iterations", "5"), ("case", "ignore")]),
+        ..Fingerprint::default()
+    };
+    assert_eq!(a.config_sha256(), b.config_sha256());
+    assert_eq!(a.config_sha256().len(), 64);
+
+    let c = Fingerprint {
+        config: config(&[("case", "ignore"), ("iterations", "10")]),
+        ..Fingerprint::default()
+    };
+    assert_ne!(a.config_sha256(), c.config_sha256());
+}
+
+#[test]
+fn revision_marks_dirty_trees() {
+    let mut fingerprint = Fingerprint {
+        git_sha: Some("0123456789abcdef0123".to_string()),
+        git_dirty: Some(false),
+        ..Fingerprint::default()
+    };
+    assert_eq!(fingerprint.revision(), "0123456789");
+    fingerprint.git_dirty = Some(true);
+    assert_eq!(fingerprint.revision(), "0123456789-dirty");
+    assert_eq!(Fingerprint::default().revision(), "unknown");
+}
+
+#[test]
+fn capture_sorts_features() {
+    let fingerprint = Fingerprint::capture(&["simd", "arena"], config(&[("atom", "fuzzy")]));
+    assert!(fingerprint.features.starts_with(&["arena".to_string()]));
+    assert!(fingerprint.features.windows(2).all(|w| w[0] <= w[1]));
+    assert_eq!(fingerprint.config, config(&[("atom", "fuzzy")]));
+}
+
+#[test]
+fn round_trips_through_results_files() {
+    let set = ResultSet {
+        backend: "nucleo".to_string(),
+        run: "main".to_string(),
+        corpus: None,
+        corpus_sha256: None,
+        fingerprint: Some(Fingerprint {
+            git_sha: Some("abc123".to_string()),
+            git_dirty: Some(true),
+            features: vec!["harness/store".to_string()],
+            config: config(&[("case", "ignore"), ("top_k", "100")]),
+        }),
+        startup: None,
+        steady_state: None,
+        memory: Vec::new(),
+        queries: Vec::new(),
+    };
+    let path = temp_file("fingerprint-round-trip.json", b"");
+    set.write(&path).unwrap();
+    assert_eq!(ResultSet::read(&path).unwrap(), set);
+}
+
+#[test]
+fn files_without_a_fingerprint_still_read() {
+    let path = temp_file(
+        "fingerprint-absent.json",
+        br#"{"schema_version": 1, "backend": "nucleo", "queries": []}"#,
+    );
+    assert_eq!(ResultSet::read(&path).unwrap().fingerprint, None);
+}
@@ -0,0 +1,44 @@
+use harness::heap::top_sites;
+
+const DHAT_JSON: &str = r#"{
+  "dhatFileVersion": 2,
+  "mode": "rust-heap",
+  "pps": [
+    {"tb": 100, "tbk": 2, "fs": [1, 2, 4]},
+    {"tb": 300, "tbk": 3, "fs": [1, 3, 4]},
+    {"tb": 50, "tbk": 5, "fs": [1, 5]},
+    {"tb": 10, "tbk": 1, "fs": [0]}
+  ],
+  "ftbl": [
+    "[root]",
+    "0x1: alloc::alloc::alloc (alloc/src/alloc.rs:95:14)",
+    "0x2: alloc::raw_vec::finish_grow (alloc/src/raw_vec.rs:470:13)",
+    "0x3: <T as alloc::slice::<impl [T]>::to_vec_in::ConvertVec>::to_vec (alloc/src/slice.rs:448:29)",
+    "0x4: bench_nucleo::bench_corpus (bench-nucleo/src/main.rs:360:63)",
+    "0x5: nucleo_matcher::pattern::Pattern::new (nucleo-matcher-0.3.1/src/pattern.rs:436:14)"
+  ]
+}"#;
+
+#[test]
+fn folds_program_points_into_our_call_sites() {
+    let sites = top_sites(DHAT_JSON, 10).unwrap();
+    let summary: Vec<(&str, u64, u64)> = sites
+        .iter()
+        .map(|s| (s.frame.as_str(), s.total_bytes, s.total_blocks))
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            ("bench_nucleo::bench_corpus (bench-nucleo/src/main.rs:360:63)", 400, 5),
+            ("nucleo_matcher::pattern::Pattern::new (nucleo-matcher-0.3.1/src/pattern.rs:436:14)", 50, 5),
+            ("[unknown]", 10, 1),
+        ]
+    );
+    assert_eq!(top_sites(DHAT_JSON, 1).unwrap().len(), 1);
+}
+
+#[test]
+fn rejects_files_that_are_not_dhat_output() {
+    assert!(top_sites("{}", 10).is_err());
+    assert!(top_sites("not json", 10).is_err());
+}
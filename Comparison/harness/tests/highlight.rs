@@ -0,0 +1,79 @@
+use harness::highlight::{highlight, ranges, Highlights, LazyHighlight};
+use nucleo_matcher::pattern::{AtomKind, CaseMatching, Normalization, Pattern};
+use nucleo_matcher::{Config, Matcher};
+
+fn pattern(query: &str) -> Pattern {
+    Pattern::new(
+        query,
+        CaseMatching::Ignore,
+        Normalization::Smart,
+        AtomKind::Fuzzy,
+    )
+}
+
+#[test]
+fn merges_sorts_and_clips_indices() {
+    assert_eq!(ranges(&[4, 0, 1, 2, 1, 6, 9], 8), vec![0..3, 4..5, 6..7]);
+    assert_eq!(ranges(&[3, 4], 3), vec![]);
+    assert!(ranges(&[], 10).is_empty());
+}
+
+#[test]
+fn highlights_every_matched_field() {
+    let mut matcher = Matcher::new(Config::DEFAULT);
+    assert_eq!(highlight(&pattern(""), &mut matcher, "DBK"), Some(vec![]));
+    assert_eq!(
+        highlight(&pattern("dbk deutsche"), &mut matcher, "MSFT"),
+        None
+    );
+
+    let highlights = Highlights::new(
+        &pattern("deu bank"),
+        &mut matcher,
+        [("symbol", "DBK.DE"), ("name", "Deutsche Bank AG")],
+    );
+    assert_eq!(highlights.get("symbol"), &[]);
+    assert_eq!(highlights.get("name"), &[0..3, 9..13]);
+    assert_eq!(
+        highlights
+            .iter()
+            .map(|(field, _)| field)
+            .collect::<Vec<_>>(),
+        ["name"]
+    );
+    assert_eq!(
+        highlights.to_json(),
+        serde_json::json!({ "name": [[0, 3], [9, 13]] })
+    );
+}
+
+#[test]
+fn merges_ranges_recorded_twice_for_a_field() {
+    let mut highlights = Highlights::default();
+    highlights.insert("name", vec![0..2, 6..8]);
+    highlights.insert("name", vec![2..3, 7..9]);
+    highlights.insert("symbol", vec![0..1, 2..3]);
+    assert_eq!(highlights.get("name"), &[0..3, 6..9]);
+    assert_eq!(highlights.get("symbol"), &[0..1, 2..3]);
+}
+
+#[test]
+fn lazy_highlights_match_once() {
+    let mut matcher = Matcher::new(Config::DEFAULT);
+    let lazy = LazyHighlight::new();
+    assert!(!lazy.is_computed());
+    let ranges = lazy
+        .get(&pattern("deu bank"), &mut matcher, "Deutsche Bank AG")
+        .map(<[_]>::to_vec);
+    assert_eq!(ranges, Some(vec![0..3, 9..13]));
+    assert!(lazy.is_computed());
+    // Later calls reuse the first answer rather than matching again
+    assert_eq!(
+        lazy.get(&pattern("msft"), &mut matcher, "Microsoft"),
+        Some(&[0..3, 9..13][..])
+    );
+
+    let unmatched = LazyHighlight::new();
+    assert_eq!(unmatched.get(&pattern("msft"), &mut matcher, "Apple"), None);
+    assert!(unmatched.is_computed());
+}
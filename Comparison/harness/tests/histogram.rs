@@ -0,0 +1,73 @@
+use harness::histogram::{Bin, ScoreHistogram};
+
+#[test]
+fn counts_scores_and_relevant_matches_per_bin() {
+    let mut histogram = ScoreHistogram::new(10.0);
+    for score in [3.0, 9.9, 10.0, 57.0, -0.5] {
+        histogram.add(score, score == 57.0);
+    }
+    let bins: Vec<Bin> = histogram.bins().collect();
+    assert_eq!(
+        bins,
+        vec![
+            Bin {
+                lower: -10.0,
+                upper: 0.0,
+                count: 1,
+                relevant: 0
+            },
+            Bin {
+                lower: 0.0,
+                upper: 10.0,
+                count: 2,
+                relevant: 0
+            },
+            Bin {
+                lower: 10.0,
+                upper: 20.0,
+                count: 1,
+                relevant: 0
+            },
+            Bin {
+                lower: 50.0,
+                upper: 60.0,
+                count: 1,
+                relevant: 1
+            },
+        ]
+    );
+    assert_eq!(histogram.total(), 5);
+}
+
+#[test]
+fn merges_and_ranks_scores() {
+    let mut a = ScoreHistogram::new(0.25);
+    a.add(0.1, false);
+    a.add(0.9, true);
+    let mut b = ScoreHistogram::new(0.25);
+    b.add(0.3, false);
+    b.add(0.95, true);
+    a.merge(&b);
+
+    assert_eq!(a.total(), 4);
+    assert_eq!(a.percentile_rank(0.0), Some(0.0));
+    assert_eq!(a.percentile_rank(0.3), Some(0.25));
+    assert_eq!(a.percentile_rank(0.8), Some(0.5));
+    assert_eq!(a.percentile_rank(2.0), Some(1.0));
+    assert_eq!(ScoreHistogram::new(1.0).percentile_rank(5.0), None);
+}
+
+#[test]
+fn round_trips_through_json() {
+    let mut histogram = ScoreHistogram::new(0.25);
+    for score in [0.1, 0.2, 0.9, -0.3] {
+        histogram.add(score, score > 0.5);
+    }
+    let json = histogram.to_json();
+    assert_eq!(json["bins"][0], serde_json::json!([-2, 1, 0]));
+    assert_eq!(ScoreHistogram::from_json(&json), Some(histogram));
+    assert_eq!(
+        ScoreHistogram::from_json(&serde_json::json!({"bin_width": 0, "bins": []})),
+        None
+    );
+}
@@ -0,0 +1,88 @@
+use harness::incremental::Incremental;
+use harness::queries::Query;
+use harness::results::{QueryResult, ResultSet};
+
+fn query(text: &str, category: &str) -> Query {
+    Query {
+        text: text.to_string(),
+        field: "name".to_string(),
+        category: category.to_string(),
+        expected_name: "Apple".to_string(),
+        frequency: 1,
+    }
+}
+
+fn timed(query: &Query, median_ms: f64) -> QueryResult {
+    let mut result = QueryResult::new(query);
+    result.median_ms = Some(median_ms);
+    result
+}
+
+fn baseline(queries: Vec<QueryResult>) -> ResultSet {
+    ResultSet {
+        backend: "nucleo".to_string(),
+        run: "main".to_string(),
+        corpus: None,
+        corpus_sha256: None,
+        fingerprint: None,
+        startup: None,
+        steady_state: None,
+        memory: Vec::new(),
+        queries,
+    }
+}
+
+#[test]
+fn reruns_added_and_changed_queries_only() {
+    let base = baseline(vec![
+        timed(&query("apple", "exact_name"), 1.0),
+        timed(&query("aple", "typo"), 2.0),
+        timed(&query("gone", "typo"), 3.0),
+    ]);
+    let current = vec![
+        query("apple", "exact_name"),
+        query("aple", "substring"),
+        query("appl", "prefix"),
+    ];
+    let incremental = Incremental::against(&base, &current);
+    assert_eq!(incremental.unchanged(), 1);
+    assert_eq!(
+        (incremental.added, incremental.changed, incremental.removed),
+        (1, 1, 1)
+    );
+    let stale: Vec<&str> = incremental.stale.iter().map(|q| q.text.as_str()).collect();
+    assert_eq!(stale, ["aple", "appl"]);
+}
+
+#[test]
+fn reruns_queries_the_baseline_did_not_time() {
+    let apple = query("apple", "exact_name");
+    let base = baseline(vec![QueryResult::new(&apple)]);
+    let incremental = Incremental::against(&base, &[apple]);
+    assert_eq!((incremental.unchanged(), incremental.changed), (0, 1));
+}
+
+#[test]
+fn merges_fresh_results_in_query_set_order() {
+    let base = baseline(vec![timed(&query("apple", "exact_name"), 1.0)]);
+    let current = vec![
+        query("appl", "prefix"),
+        query("apple", "exact_name"),
+        query("aple", "typo"),
+    ];
+    let incremental = Incremental::against(&base, &current);
+    let fresh = incremental.stale.iter().map(|q| timed(q, 9.0)).collect();
+    let merged = incremental.merge(fresh);
+    let order: Vec<(&str, Option<f64>)> = merged
+        .iter()
+        .map(|r| (r.query.as_str(), r.median_ms))
+        .collect();
+    assert_eq!(
+        order,
+        [
+            ("appl", Some(9.0)),
+            ("apple", Some(1.0)),
+            ("aple", Some(9.0))
+        ]
+    );
+}
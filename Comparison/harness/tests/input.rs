@@ -0,0 +1,94 @@
+mod common;
+
+use common::temp_file;
+use flate2::write::GzEncoder;
+use harness::corpus::load_corpus;
+use harness::input::{self, Compression};
+use ruzstd::encoding::{compress_to_vec, CompressionLevel};
+use std::io::Write;
+
+const CORPUS: &str = "Symbol\tName\tISIN\n\
+                      AAPL\tApple Inc.\tUS0378331005\n\
+                      MÜV2\tMünchener Rück\tDE0008430026\n";
+
+fn gzip(bytes: &[u8]) -> Vec<u8> {
+    let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(bytes).unwrap();
+    encoder.finish().unwrap()
+}
+
+#[test]
+fn detects_formats_from_magic_bytes() {
+    assert_eq!(Compression::detect(&gzip(b"x")), Compression::Gzip);
+    let zstd = compress_to_vec(&b"x"[..], CompressionLevel::Fastest);
+    assert_eq!(Compression::detect(&zstd), Compression::Zstd);
+    assert_eq!(Compression::detect(b"Symbol\tName"), Compression::None);
+    assert_eq!(Compression::detect(b""), Compression::None);
+}
+
+#[test]
+fn loads_plain_gzip_and_zstd_corpora_identically() {
+    let paths = [
+        temp_file("corpus.tsv", CORPUS.as_bytes()),
+        temp_file("corpus.tsv.gz", &gzip(CORPUS.as_bytes())),
+        temp_file(
+            "corpus.tsv.zst",
+            &compress_to_vec(CORPUS.as_bytes(), CompressionLevel::Fastest),
+        ),
+        // Detection does not depend on the extension.
+        temp_file("misnamed.tsv", &gzip(CORPUS.as_bytes())),
+    ];
+    for path in &paths {
+        let corpus = load_corpus(path).unwrap();
+        assert_eq!(corpus.len(), 2, "{path}");
+        assert_eq!(corpus.get(1).name, "Münchener Rück", "{path}");
+        assert_eq!(corpus.get(1).isin, "DE0008430026", "{path}");
+    }
+}
+
+#[test]
+fn reads_concatenated_gzip_members() {
+    let mut bytes = gzip(b"first ");
+    bytes.extend(gzip(b"second"));
+    let path = temp_file("members.gz", &bytes);
+    assert_eq!(input::read_to_string(&path).unwrap(), "first second");
+}
+
+#[test]
+fn drops_byte_order_marks_and_transcodes_utf16() {
+    let text = "Symbol\tName\r\nMÜV2\tMünchener Rück\r\n";
+    let utf8 = [&[0xef, 0xbb, 0xbf][..], text.as_bytes()].concat();
+    let le: Vec<u8> = [0xff, 0xfe]
+        .into_iter()
+        .chain(text.encode_utf16().flat_map(u16::to_le_bytes))
+        .collect();
+    let be: Vec<u8> = [0xfe, 0xff]
+        .into_iter()
+        .chain(text.encode_utf16().flat_map(u16::to_be_bytes))
+        .collect();
+    for (name, bytes) in [
+        ("bom.tsv", utf8.clone()),
+        ("bom.tsv.gz", gzip(&utf8)),
+        ("utf16le.txt", le),
+        ("utf16be.txt", be),
+    ] {
+        let path = temp_file(name, &bytes);
+        assert_eq!(input::read_to_string(&path).unwrap(), text, "{name}");
+    }
+
+    let unpaired = temp_file("unpaired.txt", &[0xff, 0xfe, 0x00, 0xd8, 0x41, 0x00]);
+    assert!(input::read_to_string(&unpaired).is_err());
+}
+
+#[test]
+fn makes_windows_separators_native() {
+    assert_eq!(input::native_path("data/nasdaq.tsv"), "data/nasdaq.tsv");
+    if cfg!(windows) {
+        assert_eq!(input::native_path("data\\nasdaq.tsv"), "data\\nasdaq.tsv");
+    } else {
+        assert_eq!(
+            input::native_path("..\\data\\nasdaq.tsv"),
+            "../data/nasdaq.tsv"
+        );
+    }
+}
@@ -0,0 +1,32 @@
+use harness::isin::{check_digit, is_valid};
+
+#[test]
+fn accepts_real_isins() {
+    for isin in [
+        "US0378331005",
+        "DE0008430026",
+        "IE00BKBF6616",
+        "LU1834983634",
+        "NL0000852564",
+        "GB0002634946",
+    ] {
+        assert!(is_valid(isin), "{isin}");
+    }
+}
+
+#[test]
+fn rejects_bad_check_digits_and_shapes() {
+    assert!(!is_valid("US0378331006"));
+    assert!(!is_valid("US037833100"));
+    assert!(!is_valid("us0378331005"));
+    assert!(!is_valid("1S0378331005"));
+    assert!(!is_valid("US03783310O5"));
+    assert!(!is_valid(""));
+}
+
+#[test]
+fn computes_check_digits() {
+    assert_eq!(check_digit("US037833100"), Some(5));
+    assert_eq!(check_digit("IE00BKBF661"), Some(6));
+    assert_eq!(check_digit("US03783310-"), None);
+}
@@ -0,0 +1,53 @@
+mod common;
+
+use common::temp_file;
+use harness::judgments::{load_judgments, write_judgments, Judgment};
+
+#[test]
+fn round_trips_judgments() {
+    let judgments = vec![Judgment {
+        query: "apple".to_string(),
+        field: "name".to_string(),
+        symbol: "AAPL".to_string(),
+        name: "Apple Inc.".to_string(),
+        isin: "US0378331005".to_string(),
+        relevance: 1,
+    }];
+    let mut bytes = Vec::new();
+    write_judgments(&mut bytes, &judgments).unwrap();
+    let path = temp_file("judgments.tsv", &bytes);
+    assert_eq!(load_judgments(&path).unwrap(), judgments);
+}
+
+#[test]
+fn rejects_non_numeric_relevance() {
+    let path = temp_file("bad-judgments.tsv", b"apple\tname\tAAPL\tApple\t\thigh\n");
+    let err = load_judgments(&path).unwrap_err();
+    assert!(err.to_string().contains("line 1"));
+}
+
+#[test]
+fn rejects_grades_above_the_scale() {
+    let path = temp_file(
+        "graded-judgments.tsv",
+        b"apple\tname\tAAPL\tApple\t\t3\napple\tname\tAPLE\tApple Hospitality\t\t4\n",
+    );
+    let err = load_judgments(&path).unwrap_err();
+    assert!(err.to_string().contains("line 2"), "{err}");
+    assert!(err.to_string().contains("0 to 3"), "{err}");
+}
+
+#[test]
+fn loads_judgments_saved_from_excel() {
+    let path = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/excel-judgments.tsv"
+    );
+    let judgments = load_judgments(path).unwrap();
+    assert_eq!(judgments.len(), 2);
+    assert_eq!(judgments[0].query, "aapl");
+    assert_eq!(judgments[0].relevance, 3);
+    assert_eq!(judgments[1].query, "berkshire \"b\"");
+    assert_eq!(judgments[1].name, "Berkshire Hathaway \"B\"");
+    assert_eq!(judgments[1].relevance, 2);
+}
@@ -0,0 +1,49 @@
+use harness::limits::{Exceeded, Limits};
+use std::time::{Duration, Instant};
+
+#[test]
+fn reports_the_first_limit_passed() {
+    let limits = Limits {
+        time: Some(Duration::from_secs(60)),
+        memory_bytes: Some(512 << 20),
+    };
+    assert_eq!(
+        limits.exceeded(Duration::from_secs(59), Some(100 << 20)),
+        None
+    );
+    assert_eq!(
+        limits.exceeded(Duration::from_secs(61), Some(600 << 20)),
+        Some(Exceeded::Time {
+            limit: Duration::from_secs(60)
+        })
+    );
+    let memory = limits
+        .exceeded(Duration::from_secs(1), Some(600 << 20))
+        .unwrap();
+    assert_eq!(
+        memory.to_string(),
+        "reached 600 MiB resident, past its 512 MiB memory limit"
+    );
+    assert_eq!(limits.exceeded(Duration::from_secs(1), None), None);
+}
+
+#[test]
+fn unlimited_runs_always_pass() {
+    let limits = Limits::default();
+    assert!(limits.is_unlimited());
+    assert_eq!(limits.exceeded(Duration::MAX, Some(u64::MAX)), None);
+    assert_eq!(limits.check(Instant::now()), Ok(()));
+
+    let timed = Limits {
+        time: Some(Duration::ZERO),
+        ..Limits::default()
+    };
+    assert!(!timed.is_unlimited());
+    assert_eq!(
+        timed
+            .check(Instant::now() - Duration::from_millis(1))
+            .unwrap_err()
+            .to_string(),
+        "ran past its 0s time limit"
+    );
+}
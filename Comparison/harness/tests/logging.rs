@@ -0,0 +1,40 @@
+mod common;
+
+use common::temp_file;
+use harness::logging::{init, LogFormat};
+use std::fs;
+
+#[test]
+fn parses_log_formats() {
+    assert_eq!("json".parse(), Ok(LogFormat::Json));
+    assert_eq!("text".parse(), Ok(LogFormat::Text));
+    assert!("yaml".parse::<LogFormat>().is_err());
+}
+
+// Only one test may install the global subscriber.
+#[test]
+fn writes_json_lines_with_span_fields() {
+    let args = |flags: &[&str]| -> Vec<String> {
+        std::iter::once("bench")
+            .chain(flags.iter().copied())
+            .map(String::from)
+            .collect()
+    };
+    assert_eq!(init(&args(&[])), Ok(None));
+    assert!(init(&args(&["--log-format", "xml"])).is_err());
+
+    let path = temp_file("run.log", b"");
+    let format = init(&args(&["--log-format", "json", "--log-file", &path])).unwrap();
+    assert_eq!(format, Some(LogFormat::Json));
+    {
+        let _span = tracing::info_span!("query", query = "AAPL", field = "symbol").entered();
+        tracing::info!(duration_ms = 0.25, "timed");
+    }
+    let log = fs::read_to_string(&path).unwrap();
+    let line: serde_json::Value = serde_json::from_str(log.lines().last().unwrap()).unwrap();
+    assert_eq!(line["fields"]["message"], "timed");
+    assert_eq!(line["fields"]["duration_ms"], 0.25);
+    assert_eq!(line["span"]["query"], "AAPL");
+    assert_eq!(line["span"]["field"], "symbol");
+    assert!(init(&args(&["--log-format", "text"])).is_err());
+}
@@ -0,0 +1,43 @@
+mod common;
+
+use common::temp_file;
+use harness::manifest::{self, manifest_path, Manifest, Schema};
+use std::fs;
+
+#[test]
+fn writes_and_verifies_a_manifest() {
+    let path = temp_file(
+        "manifested.tsv",
+        b"Symbol\tName\tISIN\nAAPL\tApple Inc.\tUS0378331005\n\nMSFT\tMicrosoft\t\n",
+    );
+    let written = Manifest::write_for(&path, "test", Some(7), Schema::instruments()).unwrap();
+    assert_eq!(written.rows, 2);
+    assert_eq!(written.sha256.len(), 64);
+    assert_eq!(Manifest::read_for(&path).unwrap(), Some(written.clone()));
+    assert_eq!(manifest::verify(&path).unwrap(), Some(written));
+}
+
+#[test]
+fn rejects_a_modified_file() {
+    let path = temp_file("tampered.tsv", b"a\tname\tcat\n");
+    Manifest::write_for(&path, "test", None, Schema::queries()).unwrap();
+    fs::write(&path, b"b\tname\tcat\n").unwrap();
+    let err = manifest::verify(&path).unwrap_err();
+    assert!(err.to_string().contains("does not match manifest"), "{err}");
+}
+
+#[test]
+fn skips_files_without_a_manifest() {
+    let path = temp_file("unmanifested.tsv", b"a\tname\tcat\n");
+    let _ = fs::remove_file(manifest_path(&path));
+    assert_eq!(manifest::verify(&path).unwrap(), None);
+}
+
+#[test]
+fn hashes_known_input() {
+    let path = temp_file("abc.txt", b"abc");
+    assert_eq!(
+        manifest::sha256_file(&path).unwrap(),
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+}
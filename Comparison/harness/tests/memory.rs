@@ -0,0 +1,25 @@
+use harness::memory::{live_bytes, parse_vm_hwm, parse_vm_rss, CountingAlloc};
+
+#[global_allocator]
+static ALLOC: CountingAlloc = CountingAlloc;
+
+#[test]
+fn counts_live_heap_bytes() {
+    let before = live_bytes();
+    let mut block: Vec<u8> = Vec::with_capacity(1 << 20);
+    block.push(1);
+    assert!(live_bytes() >= before + (1 << 20));
+    block.reserve_exact(1 << 21);
+    assert!(live_bytes() >= before + (1 << 21));
+    let grown = live_bytes();
+    drop(block);
+    assert!(live_bytes() <= grown - (1 << 21));
+}
+
+#[test]
+fn reads_resident_set_from_proc_status() {
+    let status = "Name:\tbench-memory\nVmPeak:\t  20480 kB\nVmHWM:\t  12288 kB\nVmRSS:\t   8192 kB\nThreads:\t1\n";
+    assert_eq!(parse_vm_rss(status), Some(8 << 20));
+    assert_eq!(parse_vm_hwm(status), Some(12 << 20));
+    assert_eq!(parse_vm_rss("Name:\tkthreadd\n"), None);
+}
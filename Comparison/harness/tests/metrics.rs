@@ -0,0 +1,107 @@
+use harness::judgments::Judgment;
+use harness::metrics::{dcg, ndcg, ndcg_at, percentile, GradeConfusion};
+use harness::queries::Query;
+use harness::results::{Hit, QueryResult};
+
+fn result(expected_name: &str, names: &[&str]) -> QueryResult {
+    let mut result = QueryResult::new(&Query {
+        text: "aple".to_string(),
+        field: "name".to_string(),
+        category: "typo".to_string(),
+        expected_name: expected_name.to_string(),
+        frequency: 1,
+    });
+    result.results = Some(
+        names
+            .iter()
+            .map(|name| Hit {
+                symbol: name.to_uppercase(),
+                name: name.to_string(),
+                score: None,
+            })
+            .collect(),
+    );
+    result
+}
+
+fn judgment(name: &str, relevance: u8) -> Judgment {
+    Judgment {
+        query: "aple".to_string(),
+        field: "name".to_string(),
+        symbol: name.to_uppercase(),
+        name: name.to_string(),
+        isin: String::new(),
+        relevance,
+    }
+}
+
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() < 1e-9
+}
+
+#[test]
+fn ndcg_rewards_ideal_order_and_discounts_rank() {
+    assert!(close(dcg(&[1.0, 1.0]), 1.0 + 1.0 / 3f64.log2()));
+    assert_eq!(ndcg(&[2.0, 1.0, 0.0], &[1.0, 2.0], 10), Some(1.0));
+    let swapped = ndcg(&[1.0, 2.0], &[1.0, 2.0], 10).unwrap();
+    assert!(swapped < 1.0 && swapped > 0.0);
+    assert_eq!(ndcg(&[0.0, 0.0], &[], 10), None);
+    // A relevant result past the cutoff earns nothing
+    assert_eq!(ndcg(&[0.0, 1.0], &[1.0], 1), Some(0.0));
+}
+
+#[test]
+fn expected_name_counts_its_first_match_only() {
+    let second = result(
+        "apple",
+        &["Applied Materials", "Apple Inc.", "Apple Hospitality"],
+    );
+    assert!(close(ndcg_at(&second, &[], 10).unwrap(), 1.0 / 3f64.log2()));
+    let missing = result("apple", &["Microsoft"]);
+    assert_eq!(ndcg_at(&missing, &[], 10), Some(0.0));
+    assert_eq!(ndcg_at(&result("_SKIP_", &["Apple Inc."]), &[], 10), None);
+}
+
+#[test]
+fn judgments_take_precedence_over_the_expected_name() {
+    let hits = result("microsoft", &["Apple Hospitality", "Apple Inc."]);
+    let best = judgment("Apple Inc.", 3);
+    let partial = judgment("Apple Hospitality", 1);
+    let score = ndcg_at(&hits, &[&best, &partial], 10).unwrap();
+    let expected = dcg(&[1.0, 3.0]) / dcg(&[3.0, 1.0]);
+    assert!(close(score, expected));
+}
+
+#[test]
+fn grade_confusion_counts_where_each_grade_landed() {
+    let hits = result(
+        "apple",
+        &["Apple Hospitality", "Apple Inc.", "Applied Materials"],
+    );
+    let ideal = judgment("Apple Inc.", 3);
+    let acceptable = judgment("Apple Hospitality", 1);
+    let below = judgment("Applied Materials", 2);
+    let missed = judgment("Apple Bank", 3);
+    let mut confusion = GradeConfusion::default();
+    confusion.add(&hits, &[&ideal, &acceptable, &below, &missed], 2);
+    assert_eq!(confusion.first, [0, 1, 0, 0]);
+    assert_eq!(confusion.top_k, [0, 0, 0, 1]);
+    assert_eq!(confusion.below_k, [0, 0, 1, 0]);
+    assert_eq!(confusion.missed, [0, 0, 0, 1]);
+    assert_eq!(confusion.judged(3), 2);
+
+    // A run without result lists has nothing to place
+    let mut untimed = hits.clone();
+    untimed.results = None;
+    confusion.add(&untimed, &[&ideal], 2);
+    assert_eq!(confusion.judged(3), 2);
+}
+
+#[test]
+fn percentile_uses_nearest_rank() {
+    let values: Vec<f64> = (1..=20).map(f64::from).collect();
+    assert_eq!(percentile(&values, 95.0), Some(19.0));
+    assert_eq!(percentile(&values, 100.0), Some(20.0));
+    assert_eq!(percentile(&values, 0.0), Some(1.0));
+    assert_eq!(percentile(&[], 50.0), None);
+}
@@ -0,0 +1,95 @@
+use harness::corpus::Corpus;
+use harness::mutable::{MutableCorpus, Snapshot};
+
+fn corpus() -> Corpus {
+    let mut corpus = Corpus::default();
+    corpus.set_metadata_columns(vec!["Exchange".to_string()]);
+    for (line, (symbol, name, isin, exchange)) in [
+        ("VWCE", "Vanguard FTSE All-World", "IE00BK5BQT80", "XETR"),
+        ("VWRL", "Vanguard FTSE All-World", "IE00B3RBWM25", "XLON"),
+        ("AAPL", "Apple Inc.", "US0378331005", "XNAS"),
+        ("APC", "Apple Inc.", "US0378331005", "XETR"),
+        ("MSFT", "Microsoft Corp.", "US5949181045", "XNAS"),
+        ("SAP", "SAP SE", "DE0007164600", "XETR"),
+        ("NESN", "Nestle SA", "CH0038863350", "XSWX"),
+        ("ASML", "ASML Holding NV", "NL0010273215", "XAMS"),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        corpus.push_with_metadata(line + 2, symbol, name, isin, &[exchange]);
+    }
+    corpus
+}
+
+fn live_symbols(corpus: &MutableCorpus) -> Vec<&str> {
+    corpus
+        .live_rows()
+        .map(|row| corpus.prepared().column("symbol").get(row))
+        .collect()
+}
+
+#[test]
+fn tombstones_rows_without_rebuilding_the_columns() {
+    let mut corpus = MutableCorpus::new(corpus(), &["name"], 0.5);
+    assert_eq!(corpus.delete("isin", "US0378331005"), 2);
+    assert_eq!(corpus.delete("isin", "US0378331005"), 0);
+    assert_eq!(corpus.delete("symbol", "NOPE"), 0);
+    assert!(corpus.is_deleted(2) && corpus.is_deleted(3));
+    assert_eq!(corpus.len(), 6);
+    assert_eq!(corpus.tombstones(), 2);
+    assert_eq!(corpus.tombstone_fraction(), 0.25);
+    assert_eq!(corpus.compactions(), 0);
+    // The rows are still there to scan past, but not live
+    assert_eq!(corpus.corpus().len(), 8);
+    assert_eq!(
+        live_symbols(&corpus),
+        ["VWCE", "VWRL", "MSFT", "SAP", "NESN", "ASML"]
+    );
+}
+
+#[test]
+fn compacts_past_the_tombstone_fraction_keeping_lines_and_metadata() {
+    let mut corpus = MutableCorpus::new(corpus(), &["name"], 0.3);
+    corpus.delete("isin", "US0378331005");
+    assert_eq!(corpus.compactions(), 0);
+    corpus.delete("name", "Nestle SA");
+    assert_eq!(corpus.compactions(), 1);
+    assert_eq!(corpus.tombstones(), 0);
+    assert_eq!(corpus.corpus().len(), 5);
+    assert_eq!(corpus.prepared().column("name").len(), 5);
+    assert_eq!(
+        live_symbols(&corpus),
+        ["VWCE", "VWRL", "MSFT", "SAP", "ASML"]
+    );
+    let sap = corpus.corpus().get(3);
+    assert_eq!((sap.symbol, sap.line), ("SAP", 7));
+    assert_eq!(corpus.corpus().metadata(3).get("Exchange"), Some("XETR"));
+
+    corpus.delete("symbol", "VWCE");
+    corpus.compact();
+    assert_eq!(corpus.compactions(), 2);
+    assert_eq!(live_symbols(&corpus), ["VWRL", "MSFT", "SAP", "ASML"]);
+    // Nothing to drop
+    corpus.compact();
+    assert_eq!(corpus.compactions(), 2);
+}
+
+#[test]
+fn starts_a_generation_with_each_deletion_that_removes_rows() {
+    let mut corpus = MutableCorpus::new(corpus(), &[], 0.3);
+    assert_eq!(corpus.snapshot().to_string(), "unknown@0");
+    corpus.set_corpus_sha256(Some("3f2a9c01d4e7b8a6c5d4".to_string()));
+    corpus.delete("symbol", "NOPE");
+    assert_eq!(corpus.snapshot().generation, 0);
+    corpus.delete("symbol", "AAPL");
+    corpus.delete("symbol", "AAPL");
+    assert_eq!(corpus.snapshot().to_string(), "3f2a9c01d4e7@1");
+    // Compaction renumbers rows but leaves the same ones live
+    corpus.delete("isin", "US0378331005");
+    corpus.delete("symbol", "SAP");
+    assert_eq!(corpus.compactions(), 1);
+    let snapshot = corpus.snapshot();
+    assert_eq!(snapshot.generation, 3);
+    assert_eq!(Snapshot::from_json(&snapshot.to_json()), Some(snapshot));
+}
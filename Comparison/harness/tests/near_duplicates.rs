@@ -0,0 +1,58 @@
+use harness::corpus::Corpus;
+use harness::near_duplicates::{normalize, normalized_distance, NearDuplicates, THRESHOLD};
+
+fn corpus(names: &[&str]) -> Corpus {
+    let mut corpus = Corpus::default();
+    for (i, name) in names.iter().enumerate() {
+        corpus.push(i + 2, "X", name, "");
+    }
+    corpus
+}
+
+#[test]
+fn measures_distance_on_normalized_names() {
+    assert_eq!(
+        normalize("  Alphabet, Inc. (Class A) "),
+        "alphabet inc class a"
+    );
+    assert_eq!(
+        normalized_distance("alphabet inc class a", "alphabet inc class c"),
+        0.05
+    );
+    assert_eq!(normalized_distance("", ""), 0.0);
+    assert_eq!(normalized_distance("abc", "xyz"), 1.0);
+}
+
+#[test]
+fn clusters_near_duplicates_around_a_leader() {
+    let corpus = corpus(&[
+        "Alphabet Inc. Class A",
+        "Microsoft Corp.",
+        "Alphabet Inc. Class C",
+        "ALPHABET INC CLASS A",
+        "Microsoft Corporation",
+        "Apple Inc.",
+    ]);
+    let near = NearDuplicates::find(&corpus, THRESHOLD, 10);
+    assert_eq!(near.clusters.len(), 1);
+    assert_eq!(near.clusters[0].rows, [0, 2, 3]);
+    assert_eq!(near.rows(), 3);
+    let leaders = near.leaders(corpus.len());
+    assert_eq!(leaders[3], Some(0));
+    assert_eq!(leaders[1], None);
+}
+
+#[test]
+fn does_not_chain_names_that_are_only_near_each_other() {
+    // Each strike is one digit from the next, but the last is three from
+    // the first
+    let corpus = corpus(&[
+        "POUS1 2612I200300C",
+        "POUS1 2612I200310C",
+        "POUS1 2612I200311C",
+        "POUS1 2612I200411C",
+    ]);
+    let near = NearDuplicates::find(&corpus, 0.1, 10);
+    assert!(near.clusters.iter().all(|c| c.rows.len() < corpus.len()));
+    assert_eq!(near.clusters[0].leader(), 0);
+}
@@ -0,0 +1,162 @@
+use harness::parallel::score_rows;
+use harness::preset::Preset;
+use harness::rng::Rng;
+use harness::topk::TopK;
+use nucleo_matcher::{Matcher, Utf32Str};
+use std::env;
+use std::fmt::Write;
+
+/// Words shared by many rows, so that most queries tie on score.
+const WORDS: [&str; 12] = [
+    "alpha",
+    "global",
+    "fund",
+    "bond",
+    "tech",
+    "x-trackers",
+    "db",
+    "ishares",
+    "msci",
+    "world",
+    "acc",
+    "2030",
+];
+
+/// Rows built from a handful of words, with every tenth one repeated.
+fn stress_rows(rng: &mut Rng, count: usize) -> Vec<String> {
+    let mut rows: Vec<String> = Vec::with_capacity(count);
+    for _ in 0..count {
+        if !rows.is_empty() && rng.chance(0.1) {
+            let copy = rng.choose(&rows).unwrap().clone();
+            rows.push(copy);
+            continue;
+        }
+        let words: Vec<&str> = (0..rng.range(1, 4))
+            .map(|_| *rng.choose(&WORDS).unwrap())
+            .collect();
+        rows.push(words.join(" "));
+    }
+    rows
+}
+
+/// A short query: a single letter, part of a word, or two fragments.
+fn stress_query(rng: &mut Rng) -> String {
+    let word = rng.choose(&WORDS).unwrap();
+    match rng.below(3) {
+        0 => word[..1].to_string(),
+        1 => word[..rng.range(1, word.len())].to_string(),
+        _ => format!(
+            "{} {}",
+            &word[..2.min(word.len())],
+            rng.choose(&WORDS).unwrap()
+        ),
+    }
+}
+
+/// Ranked matches as the lines a quality run prints, to compare as bytes.
+fn render(ranked: &[(u32, usize)], rows: &[String]) -> String {
+    let mut out = String::new();
+    for (rank, (score, row)) in ranked.iter().enumerate() {
+        writeln!(out, "{}\t{}\t{}\t{}", rank + 1, score, row, rows[*row]).unwrap();
+    }
+    out
+}
+
+/// Rounds of the stress test; `PARALLEL_STRESS_ROUNDS` runs more.
+fn rounds() -> u64 {
+    env::var("PARALLEL_STRESS_ROUNDS")
+        .ok()
+        .and_then(|n| n.parse().ok())
+        .unwrap_or(12)
+}
+
+#[test]
+fn scores_rows_in_the_order_one_matcher_does() {
+    let preset = Preset::default();
+    let rows = ["db x-trackers msci", "ishares", "x-trackers world", "bond"];
+    let all: Vec<usize> = (0..rows.len()).collect();
+    let pattern = preset.pattern("xtr");
+
+    let mut one = vec![Matcher::new(preset.config())];
+    let serial = score_rows(&pattern, &all, |row| rows[row], &mut one);
+    assert_eq!(
+        serial.iter().map(|&(_, row)| row).collect::<Vec<_>>(),
+        [0, 2]
+    );
+
+    let mut many: Vec<Matcher> = (0..8).map(|_| Matcher::new(preset.config())).collect();
+    assert_eq!(
+        score_rows(&pattern, &all, |row| rows[row], &mut many),
+        serial
+    );
+    assert!(score_rows(&pattern, &[], |row| rows[row], &mut many).is_empty());
+    assert_eq!(
+        score_rows(&pattern, &[2, 0], |row| rows[row], &mut many),
+        [serial[1], serial[0]]
+    );
+}
+
+#[test]
+fn ranks_byte_identically_on_any_number_of_threads() {
+    let shorter = Preset {
+        tie_break: "shorter".to_string(),
+        ..Preset::default()
+    };
+    for seed in 0..rounds() {
+        let mut rng = Rng::new(seed);
+        let count = rng.range(1, 3000);
+        let rows = stress_rows(&mut rng, count);
+        let all: Vec<usize> = (0..rows.len()).collect();
+        // Some rows left out, as a filter or the prefilter would
+        let some: Vec<usize> = all.iter().copied().filter(|_| rng.chance(0.7)).collect();
+        let query = stress_query(&mut rng);
+
+        for preset in [&Preset::default(), &shorter] {
+            let pattern = preset.pattern(&query);
+            for subset in [&all, &some] {
+                // The plain loop a single-threaded scan runs
+                let mut matcher = Matcher::new(preset.config());
+                let mut buf = Vec::new();
+                let mut expected: Vec<(u32, usize)> = subset
+                    .iter()
+                    .filter_map(|&row| {
+                        buf.clear();
+                        let haystack = Utf32Str::new(&rows[row], &mut buf);
+                        Some((pattern.score(haystack, &mut matcher)?, row))
+                    })
+                    .collect();
+                preset.rank(&mut expected, |row| &rows[row]);
+                let expected_text = render(&expected, &rows);
+                let mut expected_top = TopK::new(10);
+                for &(score, row) in &expected {
+                    expected_top.push(score, row);
+                }
+                let expected_top = expected_top.into_sorted();
+
+                for threads in [1, 2, 3, 4, 7, 16] {
+                    let mut matchers: Vec<Matcher> = (0..threads)
+                        .map(|_| Matcher::new(preset.config()))
+                        .collect();
+                    let mut found = score_rows(&pattern, subset, |row| &rows[row], &mut matchers);
+                    let mut top = TopK::new(10);
+                    for &(score, row) in &found {
+                        top.push(score, row);
+                    }
+                    assert_eq!(
+                        top.into_sorted(),
+                        expected_top,
+                        "seed {seed}, {threads} threads"
+                    );
+
+                    preset.rank(&mut found, |row| &rows[row]);
+                    assert_eq!(
+                        render(&found, &rows),
+                        expected_text,
+                        "seed {seed}, query {query:?}, {threads} threads, tie break {}",
+                        preset.tie_break
+                    );
+                }
+            }
+        }
+    }
+}
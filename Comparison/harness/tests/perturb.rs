@@ -0,0 +1,30 @@
+use harness::perturb::{same_top, Perturbation};
+
+#[test]
+fn builds_the_scans_for_each_edit() {
+    assert_eq!(
+        Perturbation::TrailingSpace.steps("apple"),
+        Some(vec!["apple ".to_string()])
+    );
+    assert_eq!(
+        Perturbation::CaseFlip.steps("Deutsche bANK"),
+        Some(vec!["dEUTSCHE Bank".to_string()])
+    );
+    assert_eq!(
+        Perturbation::CaseFlip.steps("straße"),
+        Some(vec!["STRASSE".to_string()])
+    );
+    assert_eq!(Perturbation::CaseFlip.steps("123 4"), None);
+    assert_eq!(
+        Perturbation::RetypedCharacter.steps("msft"),
+        Some(vec!["msftx".to_string(), "msft".to_string()])
+    );
+}
+
+#[test]
+fn compares_only_the_top_of_each_ranking() {
+    assert!(same_top(&[1, 2, 3, 4], &[1, 2, 3, 9], 3));
+    assert!(!same_top(&[1, 2, 3], &[1, 3, 2], 3));
+    assert!(!same_top(&[1, 2, 3], &[1, 2], 3));
+    assert!(same_top::<u32>(&[], &[], 3));
+}
@@ -0,0 +1,20 @@
+use harness::prefilter::CharMask;
+
+#[test]
+fn folds_case_and_ignores_spaces() {
+    assert_eq!(CharMask::of("ABC"), CharMask::of("cab"));
+    assert_eq!(CharMask::of("a b"), CharMask::of("ab"));
+    assert_eq!(CharMask::of("7"), CharMask(1 << 33));
+    assert_eq!(CharMask::of("é"), CharMask(1 << 36));
+}
+
+#[test]
+fn rejects_candidates_missing_a_needed_character() {
+    let candidate = CharMask::of("Deutsche Bank AG");
+    assert!(candidate.may_contain(CharMask::of("dbk")));
+    assert!(candidate.may_contain(CharMask::of("")));
+    assert!(!candidate.may_contain(CharMask::of("db x-trackers 7")));
+    // Other characters in the query never reject
+    assert!(candidate.may_contain(CharMask::of("bänk")));
+    assert!(!CharMask::of("Société").may_contain(CharMask::of("societe")));
+}
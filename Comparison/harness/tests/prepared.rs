@@ -0,0 +1,352 @@
+mod common;
+
+use common::temp_file;
+use harness::corpus::Corpus;
+use harness::prefilter::CharMask;
+use harness::prepared::{Column, PreparedCorpus, Stage, FORMAT_VERSION, MAGIC};
+use nucleo_matcher::pattern::{Atom, AtomKind, CaseMatching, Normalization, Pattern};
+
+fn corpus() -> Corpus {
+    let mut corpus = Corpus::default();
+    corpus.set_metadata_columns(vec!["Exchange".to_string()]);
+    corpus.push_with_metadata(
+        2,
+        "VWCE",
+        "Vanguard FTSE All-World",
+        "IE00BK5BQT80",
+        &["XETR"],
+    );
+    corpus.push_with_metadata(
+        3,
+        "VWRL",
+        "Vanguard FTSE All-World",
+        "IE00B3RBWM25",
+        &["XLON"],
+    );
+    corpus.push_with_metadata(4, "AAPL", "Apple Inc.", "US0378331005", &["XETR"]);
+    corpus
+}
+
+#[test]
+fn interns_repeated_values() {
+    let corpus = corpus();
+    let names = Column::new(&corpus, "name");
+    let values: Vec<&str> = names.iter().collect();
+    assert_eq!(
+        values,
+        [
+            "Vanguard FTSE All-World",
+            "Vanguard FTSE All-World",
+            "Apple Inc."
+        ]
+    );
+    assert_eq!(
+        names.arena_bytes(),
+        "Vanguard FTSE All-WorldApple Inc.".len()
+    );
+    assert_eq!(names.text_bytes(), 2 * 23 + 10);
+    // The text plus an (offset, len) pair of u32s per row
+    assert_eq!(names.touch(), names.arena_bytes() + 3 * 8);
+}
+
+#[test]
+fn resolves_fields_as_the_corpus_does() {
+    let corpus = corpus();
+    let prepared = PreparedCorpus::new(&corpus, ["exchange", "Exchange", "unknown"]);
+    let fields: Vec<&str> = prepared.fields().collect();
+    assert_eq!(fields, ["symbol", "name", "isin", "Exchange"]);
+    assert_eq!(prepared.column("symbol").get(2), "AAPL");
+    assert_eq!(prepared.column("exchange").get(1), "XLON");
+    assert_eq!(prepared.column("unknown").get(2), "Apple Inc.");
+    assert_eq!(prepared.column("isin").len(), corpus.len());
+}
+
+#[test]
+fn round_trips_through_bytes() {
+    let prepared = PreparedCorpus::new(&corpus(), ["Exchange"]);
+    let bytes = prepared.to_bytes();
+    let read = PreparedCorpus::from_bytes(&bytes).unwrap();
+    assert_eq!(
+        read.fields().collect::<Vec<_>>(),
+        prepared.fields().collect::<Vec<_>>()
+    );
+    assert_eq!(read.arena_bytes(), prepared.arena_bytes());
+    for field in ["symbol", "name", "isin", "exchange"] {
+        let values: Vec<&str> = read.column(field).iter().collect();
+        assert_eq!(values, prepared.column(field).iter().collect::<Vec<_>>());
+    }
+
+    let truncated = PreparedCorpus::from_bytes(&bytes[..bytes.len() - 1]).err();
+    assert!(truncated.unwrap().contains("truncated"));
+    let mut trailing = bytes.clone();
+    trailing.push(0);
+    assert!(PreparedCorpus::from_bytes(&trailing).is_err());
+}
+
+#[test]
+fn opens_an_index_mapped_in_place() {
+    let prepared = PreparedCorpus::new(&corpus(), ["Exchange"]);
+    let path = temp_file("prepared-mapped.idx", &prepared.to_bytes());
+    let mapped = PreparedCorpus::open(&path).unwrap();
+    assert_eq!(mapped.to_bytes(), prepared.to_bytes());
+    for field in ["symbol", "name", "isin", "exchange"] {
+        let column = mapped.column(field);
+        assert!(column.is_mapped());
+        let values: Vec<&str> = column.iter().collect();
+        assert_eq!(values, prepared.column(field).iter().collect::<Vec<_>>());
+        assert_eq!(column.get(1), prepared.column(field).get(1));
+    }
+    assert_eq!(
+        mapped.column("name").touch(),
+        prepared.column("name").touch()
+    );
+    assert!(!prepared.column("name").is_mapped());
+    assert!(mapped.summary().ends_with("interning, mapped)"));
+    assert_eq!(mapped.check(&corpus(), ["exchange"]), Ok(()));
+    let err = PreparedCorpus::new(&corpus(), []).check(&corpus(), ["exchange"]);
+    assert_eq!(err.unwrap_err(), "the index has no `Exchange` column");
+    let mut longer = corpus();
+    longer.push(5, "MSFT", "Microsoft", "US5949181045");
+    let err = mapped.check(&longer, []).err().unwrap();
+    assert_eq!(err, "column `symbol` has 3 rows, the corpus 4");
+
+    let truncated = temp_file("prepared-truncated.idx", &prepared.to_bytes()[..40]);
+    let err = PreparedCorpus::open(&truncated).err().unwrap();
+    assert!(
+        err.starts_with(&truncated) && err.contains("truncated"),
+        "{err}"
+    );
+}
+
+#[test]
+fn rejects_spans_outside_their_arena() {
+    let mut bytes = Vec::new();
+    for n in [1, 4] {
+        bytes.extend_from_slice(&u32::to_le_bytes(n));
+    }
+    bytes.extend_from_slice(b"name");
+    bytes.extend_from_slice(&u32::to_le_bytes(2));
+    bytes.extend_from_slice("é".as_bytes());
+    // Padding to a four-byte boundary
+    bytes.extend_from_slice(&[0, 0]);
+    for n in [1, 0, 1] {
+        bytes.extend_from_slice(&u32::to_le_bytes(n));
+    }
+    let err = PreparedCorpus::from_bytes(&with_header(&bytes)).err();
+    assert_eq!(err.unwrap(), "row 0 of column `name` lies outside its text");
+}
+
+/// `body` behind a current header with its length and checksum.
+fn with_header(body: &[u8]) -> Vec<u8> {
+    let mut bytes = MAGIC.to_vec();
+    bytes.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+    bytes.extend_from_slice(&(body.len() as u64).to_le_bytes());
+    bytes.extend_from_slice(&crc32fast::hash(body).to_le_bytes());
+    bytes.extend_from_slice(&[0; 4]);
+    bytes.extend_from_slice(body);
+    bytes
+}
+
+#[test]
+fn refuses_indexes_cut_short_or_corrupt() {
+    let bytes = PreparedCorpus::new(&corpus(), []).to_bytes();
+    let err = PreparedCorpus::from_bytes(&bytes[..bytes.len() - 4]).err();
+    assert!(err.unwrap().contains("written with"));
+
+    // A flipped letter still parses, so only the checksum catches it
+    let apple = bytes.windows(5).position(|w| w == b"Apple").unwrap();
+    let mut corrupt = bytes.clone();
+    corrupt[apple] = b'B';
+    let err = PreparedCorpus::from_bytes(&corrupt).err().unwrap();
+    assert!(err.contains("corrupt"), "{err}");
+    let path = temp_file("prepared-corrupt.idx", &corrupt);
+    assert!(PreparedCorpus::open(&path)
+        .err()
+        .unwrap()
+        .contains("corrupt"));
+    let trusted = PreparedCorpus::open_trusted(&path).unwrap();
+    assert_eq!(trusted.column("name").get(2), "Bpple Inc.");
+}
+
+#[test]
+fn refuses_other_formats_and_upgrades_older_ones() {
+    // Format 1: no header, and strings unpadded
+    let mut legacy = Vec::new();
+    for n in [1, 4] {
+        legacy.extend_from_slice(&u32::to_le_bytes(n));
+    }
+    legacy.extend_from_slice(b"name");
+    legacy.extend_from_slice(&u32::to_le_bytes(5));
+    legacy.extend_from_slice(b"Apple");
+    for n in [1, 0, 5, 0, 0] {
+        legacy.extend_from_slice(&u32::to_le_bytes(n));
+    }
+    let err = PreparedCorpus::from_bytes(&legacy).err().unwrap();
+    assert!(
+        err.starts_with("no format header") && err.contains("`reindex`"),
+        "{err}"
+    );
+    let path = temp_file("prepared-legacy.idx", &legacy);
+    assert!(PreparedCorpus::open(&path)
+        .err()
+        .unwrap()
+        .contains("`reindex`"));
+
+    let (upgraded, from) = PreparedCorpus::upgrade(&legacy).unwrap();
+    assert_eq!(from, 1);
+    assert_eq!(upgraded.column("name").get(0), "Apple");
+    let rewritten = upgraded.to_bytes();
+    assert_eq!(rewritten[..4], MAGIC);
+    assert_eq!(
+        PreparedCorpus::upgrade(&rewritten).unwrap().1,
+        FORMAT_VERSION
+    );
+    assert_eq!(
+        PreparedCorpus::from_bytes(&rewritten)
+            .unwrap()
+            .column("name")
+            .get(0),
+        "Apple"
+    );
+
+    // Format 2: the magic and version, then the body
+    let mut format_2 = MAGIC.to_vec();
+    format_2.extend_from_slice(&2u32.to_le_bytes());
+    format_2.extend_from_slice(&rewritten[24..]);
+    let err = PreparedCorpus::from_bytes(&format_2).err().unwrap();
+    assert!(err.starts_with("index format 2,") && err.ends_with("`reindex` rewrites it"));
+    let (upgraded, from) = PreparedCorpus::upgrade(&format_2).unwrap();
+    assert_eq!((upgraded.column("name").get(0), from), ("Apple", 2));
+
+    let mut newer = rewritten.clone();
+    newer[4..8].copy_from_slice(&u32::to_le_bytes(FORMAT_VERSION + 1));
+    let err = PreparedCorpus::from_bytes(&newer).err().unwrap();
+    assert_eq!(
+        err,
+        format!(
+            "index format {}, but this build reads format {FORMAT_VERSION}; \
+             read it with a newer build, or prepare it again",
+            FORMAT_VERSION + 1
+        )
+    );
+    assert!(PreparedCorpus::upgrade(&newer).is_err());
+    assert!(PreparedCorpus::upgrade(b"not an index").is_err());
+}
+
+#[test]
+fn counts_rows_per_character_and_pair_and_orders_atoms_by_them() {
+    let corpus = corpus();
+    let names = Column::new(&corpus, "name");
+    let frequency = names.frequency();
+    assert_eq!(frequency.rows(), 3);
+    assert_eq!(frequency.count('v'), 2);
+    assert_eq!(frequency.count('P'), 1);
+    assert_eq!(frequency.count('z'), 0);
+    assert_eq!(frequency.estimate("vanguard"), 2.0 / 3.0);
+    assert_eq!(frequency.estimate("apple"), 1.0 / 3.0);
+    assert_eq!(frequency.bigram_count('V', 'a'), 2);
+    assert_eq!(frequency.bigram_count('a', 'v'), 0);
+
+    // Fuzzy atoms need only the characters, substrings each adjacent pair
+    let atom = |kind| {
+        Atom::new(
+            "ld",
+            CaseMatching::Ignore,
+            Normalization::Smart,
+            kind,
+            false,
+        )
+    };
+    assert_eq!(frequency.estimate_atom(&atom(AtomKind::Fuzzy)), 2.0 / 3.0);
+    assert_eq!(
+        frequency.estimate_atom(&atom(AtomKind::Substring)),
+        2.0 / 3.0
+    );
+    let atom = |kind| {
+        Atom::new(
+            "nv",
+            CaseMatching::Ignore,
+            Normalization::Smart,
+            kind,
+            false,
+        )
+    };
+    assert_eq!(frequency.estimate_atom(&atom(AtomKind::Fuzzy)), 2.0 / 3.0);
+    assert_eq!(frequency.estimate_atom(&atom(AtomKind::Substring)), 0.0);
+
+    let mut pattern = Pattern::new(
+        "vanguard apple",
+        CaseMatching::Ignore,
+        Normalization::Smart,
+        AtomKind::Fuzzy,
+    );
+    frequency.order_atoms(&mut pattern);
+    let needles: Vec<String> = pattern
+        .atoms
+        .iter()
+        .map(|atom| atom.needle_text().to_string())
+        .collect();
+    assert_eq!(needles, ["apple", "vanguard"]);
+
+    let restored =
+        PreparedCorpus::from_bytes(&PreparedCorpus::new(&corpus, []).to_bytes()).unwrap();
+    assert_eq!(restored.column("name").frequency(), frequency);
+}
+
+#[test]
+fn builds_across_threads_what_a_single_thread_builds() {
+    let mut corpus = Corpus::default();
+    corpus.set_metadata_columns(vec!["Exchange".to_string()]);
+    for row in 0..1000 {
+        let name = format!("Fund {} Acc", row % 37);
+        let exchange = ["XETR", "XLON", "XPAR"][row % 3];
+        corpus.push_with_metadata(row + 2, &format!("F{row}"), &name, "", &[exchange]);
+    }
+    let single = PreparedCorpus::new(&corpus, ["Exchange"]);
+    for threads in [1, 3, 8] {
+        let built = PreparedCorpus::builder(&corpus)
+            .fields(["Exchange"])
+            .threads(threads)
+            .build();
+        assert_eq!(built.to_bytes(), single.to_bytes(), "{threads} threads");
+        assert!(built.column("name").masks().is_none());
+    }
+
+    let empty = PreparedCorpus::builder(&Corpus::default())
+        .threads(4)
+        .build();
+    assert_eq!(
+        empty.to_bytes(),
+        PreparedCorpus::new(&Corpus::default(), []).to_bytes()
+    );
+}
+
+#[test]
+fn builds_masks_and_reports_progress() {
+    let corpus = corpus();
+    let mut reports = Vec::new();
+    let prepared = PreparedCorpus::builder(&corpus)
+        .threads(2)
+        .masks(true)
+        .progress(|p| reports.push((p.field.to_string(), p.stage, p.done, p.total)))
+        .build();
+    let masks: Vec<CharMask> = corpus.iter().map(|inst| CharMask::of(inst.name)).collect();
+    assert_eq!(prepared.column("name").masks(), Some(&masks[..]));
+    assert!(Column::new(&corpus, "name").masks().is_none());
+
+    // Each stage of each column ends with a report of all of it
+    let finished: Vec<_> = reports
+        .iter()
+        .filter(|(_, _, done, total)| done == total)
+        .map(|(field, stage, _, total)| (field.as_str(), *stage, *total))
+        .collect();
+    assert_eq!(
+        finished[3..6],
+        [
+            ("name", Stage::Interning, 3),
+            ("name", Stage::Counting, 2),
+            ("name", Stage::Masking, 3),
+        ]
+    );
+    assert_eq!(finished.len(), 9);
+}
@@ -0,0 +1,55 @@
+mod common;
+
+use common::temp_file;
+use harness::preset::Preset;
+
+#[test]
+fn parses_presets_and_rejects_unknown_settings() {
+    let preset: Preset = "case = \"smart\"\ntie_break = \"shorter\"\nprefer_prefix = true\n"
+        .parse()
+        .unwrap();
+    assert_eq!(preset.case, "smart");
+    assert_eq!(preset.tie_break, "shorter");
+    assert!(preset.prefer_prefix);
+    assert_eq!(preset.atom, "fuzzy");
+    assert_eq!("".parse::<Preset>().unwrap(), Preset::default());
+
+    assert!("weights = 2".parse::<Preset>().is_err());
+    assert!("case = \"upper\"".parse::<Preset>().is_err());
+    assert!("prefer_prefix = \"yes\"".parse::<Preset>().is_err());
+}
+
+#[test]
+fn saves_and_loads_by_path_and_name() {
+    let preset = Preset {
+        name: "shipped-2.3".to_string(),
+        atom: "substring".to_string(),
+        bonuses: "paths".to_string(),
+        ..Preset::default()
+    };
+    let path = temp_file("shipped.toml", preset.to_toml().as_bytes());
+    assert_eq!(Preset::load(&path).unwrap(), preset);
+
+    let unnamed = temp_file("unnamed.toml", b"case = \"respect\"\n");
+    assert_eq!(Preset::load(&unnamed).unwrap().name, "unnamed");
+
+    assert_eq!(Preset::load("default").unwrap(), Preset::default());
+    assert!(Preset::load("no-such-preset").is_err());
+}
+
+#[test]
+fn breaks_ties_by_row_or_length() {
+    let texts = ["Apple Inc. Class A", "Apple", "Apple Hospitality"];
+    let mut matches = vec![(10, 2), (12, 0), (10, 1)];
+
+    Preset::default().rank(&mut matches, |row| texts[row]);
+    assert_eq!(matches, vec![(12, 0), (10, 1), (10, 2)]);
+
+    let shorter = Preset {
+        tie_break: "shorter".to_string(),
+        ..Preset::default()
+    };
+    let mut matches = vec![(10, 2), (10, 0), (10, 1)];
+    shorter.rank(&mut matches, |row| texts[row]);
+    assert_eq!(matches, vec![(10, 1), (10, 2), (10, 0)]);
+}
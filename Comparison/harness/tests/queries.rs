@@ -0,0 +1,63 @@
+mod common;
+
+use common::temp_file;
+use harness::queries::{load_queries, write_queries, SKIP};
+use std::io::ErrorKind;
+
+#[test]
+fn frequency_defaults_to_one() {
+    let path = temp_file("queries-frequency.tsv", b"aapl\tsymbol\texact_symbol\n");
+    let queries = load_queries(&path).unwrap();
+    assert_eq!(queries[0].expected_name, SKIP);
+    assert_eq!(queries[0].frequency, 1);
+}
+
+#[test]
+fn frequency_column_round_trips() {
+    let path = temp_file(
+        "queries-frequency-round-trip.tsv",
+        b"aapl\tsymbol\texact_symbol\tApple Inc\t120\nappl\tsymbol\ttypo\tApple Inc\n",
+    );
+    let queries = load_queries(&path).unwrap();
+    assert_eq!(queries[0].frequency, 120);
+    assert_eq!(queries[1].frequency, 1);
+
+    let mut out = Vec::new();
+    write_queries(&mut out, &queries).unwrap();
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "aapl\tsymbol\texact_symbol\tApple Inc\t120\nappl\tsymbol\ttypo\tApple Inc\n"
+    );
+}
+
+#[test]
+fn invalid_frequency_is_an_error() {
+    for (name, value) in [
+        ("queries-frequency-zero.tsv", "0"),
+        ("queries-frequency-text.tsv", "often"),
+    ] {
+        let path = temp_file(
+            name,
+            format!("aapl\tsymbol\texact_symbol\tApple Inc\t{value}\n").as_bytes(),
+        );
+        let err = load_queries(&path).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert!(err.to_string().contains("line 1"));
+    }
+}
+
+#[test]
+fn loads_queries_saved_from_excel() {
+    let path = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/excel-queries.tsv"
+    );
+    let queries = load_queries(path).unwrap();
+    assert_eq!(queries.len(), 3);
+    assert_eq!(queries[0].text, "berkshire \"b\"");
+    assert_eq!(queries[0].expected_name, "Berkshire Hathaway \"B\"");
+    assert_eq!(queries[0].frequency, 3);
+    assert_eq!(queries[1].category, "exact_symbol");
+    assert_eq!(queries[1].expected_name, "Apple Inc.");
+    assert_eq!(queries[2].text, "münchener");
+}
@@ -0,0 +1,150 @@
+mod common;
+
+use common::temp_file;
+use harness::corpus::{load_corpus, Corpus};
+use harness::evaluation::{MatcherBackend, Search};
+use harness::preset::Preset;
+use harness::queries::Query;
+use harness::registry::{serve, Registry};
+use harness::results::ResultSet;
+use harness::subprocess::ranked_hits;
+use std::collections::BTreeMap;
+
+const CORPUS: &str = "symbol\tname\tisin\n\
+                      AAPL\tApple Inc.\tUS0378331005\n\
+                      APLE\tApple Hospitality REIT\tUS03784Y2000\n\
+                      MSFT\tMicrosoft Corp.\tUS5949181045\n";
+
+/// Returns every row, last first, without scores.
+struct Reversed {
+    rows: usize,
+}
+
+impl MatcherBackend for Reversed {
+    fn name(&self) -> &str {
+        "reversed"
+    }
+
+    fn config(&self) -> BTreeMap<String, String> {
+        BTreeMap::new()
+    }
+
+    fn prepare(&mut self, corpus: &Corpus, _queries: &[Query]) {
+        self.rows = corpus.len();
+    }
+
+    fn search(&mut self, _query: &Query, limit: usize) -> Search {
+        Search {
+            matches: self.rows,
+            top: (0..self.rows)
+                .rev()
+                .take(limit)
+                .map(|row| (row, None))
+                .collect(),
+        }
+    }
+}
+
+fn registry() -> Registry {
+    Registry::new().register("reversed", |_| Box::new(Reversed { rows: 0 }))
+}
+
+#[test]
+fn registers_backends_beside_the_built_ins() {
+    let registry = registry();
+    assert_eq!(registry.names(), ["nucleo", "reversed"]);
+    let backend = registry.create("reversed", &Preset::default()).unwrap();
+    assert_eq!(backend.name(), "reversed");
+    let nucleo = registry.create("nucleo", &Preset::default()).unwrap();
+    assert_eq!(nucleo.config()["preset"], "default");
+    let err = registry.create("acme", &Preset::default()).err().unwrap();
+    assert!(err.contains("nucleo, reversed"), "{err}");
+}
+
+#[test]
+#[should_panic(expected = "Backend nucleo is already registered")]
+fn rejects_a_name_taken_by_a_built_in() {
+    let _ = Registry::new().register("nucleo", |_| Box::new(Reversed { rows: 0 }));
+}
+
+#[test]
+fn serves_the_quality_line_protocol() {
+    let corpus = load_corpus(&temp_file("registry-serve.tsv", CORPUS.as_bytes())).unwrap();
+    let mut out = Vec::new();
+    let mut backend = registry().create("reversed", &Preset::default()).unwrap();
+    serve(
+        backend.as_mut(),
+        &corpus,
+        "apple\tname\r\nmsft\tsymbol\tcountry=US\nmalformed\n",
+        &mut out,
+    )
+    .unwrap();
+    let out = String::from_utf8(out).unwrap();
+    assert!(
+        out.starts_with("apple\tname\t1\t-\tMSFT\tMicrosoft Corp.\tline=4\n"),
+        "{out}"
+    );
+
+    // Read back as run-scenarios reads an external backend's output
+    let hits = ranked_hits(&out);
+    assert_eq!(hits.len(), 2);
+    let msft = &hits[&("msft".to_string(), "symbol".to_string())];
+    assert_eq!(
+        msft.iter().map(|h| h.symbol.as_str()).collect::<Vec<_>>(),
+        ["MSFT", "APLE", "AAPL"]
+    );
+    assert_eq!(msft[0].score, None);
+
+    let mut out = Vec::new();
+    let mut nucleo = registry().create("nucleo", &Preset::default()).unwrap();
+    serve(nucleo.as_mut(), &corpus, "aapl\tsymbol\n", &mut out).unwrap();
+    let hits = ranked_hits(&String::from_utf8(out).unwrap());
+    let aapl = &hits[&("aapl".to_string(), "symbol".to_string())];
+    assert_eq!(aapl[0].symbol, "AAPL");
+    assert!(aapl[0].score.is_some());
+}
+
+#[test]
+fn benches_a_registered_backend_into_results() {
+    let corpus = temp_file("registry-bench-corpus.tsv", CORPUS.as_bytes());
+    let queries = temp_file(
+        "registry-bench-queries.tsv",
+        b"apple\tname\tprefix\tApple Inc.\n",
+    );
+    let results = temp_file("registry-bench.json", b"");
+    let args: Vec<String> = [
+        "acme-bench",
+        "bench",
+        "reversed",
+        "--tsv",
+        &corpus,
+        "--queries",
+        &queries,
+        "--iterations",
+        "2",
+        "--run",
+        "acme",
+        "--results",
+        &results,
+    ]
+    .iter()
+    .map(|a| a.to_string())
+    .collect();
+    registry().run(&args).unwrap();
+    let results = ResultSet::read(&results).unwrap();
+    assert_eq!(results.label(), "reversed (acme)");
+    assert_eq!(
+        results.queries[0].results.as_ref().unwrap()[0].symbol,
+        "MSFT"
+    );
+    assert_eq!(results.fingerprint.unwrap().config["iterations"], "2");
+
+    let unknown: Vec<String> = ["acme-bench", "bench", "acme"]
+        .iter()
+        .map(|a| a.to_string())
+        .collect();
+    assert!(registry()
+        .run(&unknown)
+        .unwrap_err()
+        .starts_with("unknown backend acme"));
+}
@@ -0,0 +1,45 @@
+mod common;
+
+use common::temp_file;
+use harness::replay::{load_log, parse_timestamp};
+use std::time::Duration;
+
+#[test]
+fn reads_unix_and_iso_timestamps() {
+    assert_eq!(parse_timestamp("1709285400"), Some(1_709_285_400.0));
+    assert_eq!(parse_timestamp("1709285400250"), Some(1_709_285_400.25));
+    assert_eq!(
+        parse_timestamp("2024-03-01T09:30:00Z"),
+        Some(1_709_285_400.0)
+    );
+    assert_eq!(
+        parse_timestamp("2024-03-01 10:30:00.5+01:00"),
+        Some(1_709_285_400.5)
+    );
+    assert_eq!(
+        parse_timestamp("2024-03-01T04:30:00-0500"),
+        Some(1_709_285_400.0)
+    );
+    assert_eq!(parse_timestamp("1970-01-01T00:00"), Some(0.0));
+    assert_eq!(parse_timestamp("yesterday"), None);
+    assert_eq!(parse_timestamp("2024-13-01T00:00:00Z"), None);
+}
+
+#[test]
+fn orders_searches_from_the_first_arrival() {
+    let path = temp_file(
+        "searches.csv",
+        b"timestamp,query,selected\n\
+          2024-03-01T09:30:01.5Z,\"apple, inc\",US0378331005\n\
+          2024-03-01T09:30:00Z,msft,\n\
+          2024-03-01T09:30:02Z,,\n",
+    );
+    let arrivals = load_log(&path).unwrap();
+    let texts: Vec<&str> = arrivals.iter().map(|a| a.text.as_str()).collect();
+    assert_eq!(texts, ["msft", "apple, inc"]);
+    assert_eq!(arrivals[0].at, Duration::ZERO);
+    assert_eq!(arrivals[1].at, Duration::from_millis(1500));
+
+    let bad = temp_file("bad-searches.csv", b"timestamp,query\nsoon,msft\n");
+    assert!(load_log(&bad).unwrap_err().contains("line 2"));
+}
@@ -0,0 +1,129 @@
+mod common;
+
+use common::temp_file;
+use harness::cost::QueryCost;
+use harness::mutable::Snapshot;
+use harness::queries::Query;
+use harness::results::{Footprint, Hit, QueryResult, Recheck, ResultSet, Startup, SteadyState};
+
+fn query(text: &str, category: &str, expected_name: &str) -> Query {
+    Query {
+        text: text.to_string(),
+        field: "name".to_string(),
+        category: category.to_string(),
+        expected_name: expected_name.to_string(),
+        frequency: 1,
+    }
+}
+
+fn hit(name: &str) -> Hit {
+    Hit {
+        symbol: name[..3].to_uppercase(),
+        name: name.to_string(),
+        score: Some(100.0),
+    }
+}
+
+#[test]
+fn round_trips_through_json() {
+    let mut timed = QueryResult::new(&query("aple", "typo", "Apple"));
+    timed.median_ms = Some(4.5);
+    timed.min_ms = Some(4.0);
+    timed.matches = Some(12);
+    timed.results = Some(vec![hit("Apple Inc."), hit("Applied Materials")]);
+    let mut recheck = Recheck::from_timings(&[4.2, 4.4, 4.3]).unwrap();
+    recheck.flamegraph = Some("flamegraphs/01-aple.svg".to_string());
+    timed.recheck = Some(recheck);
+    timed.cost = Some(QueryCost {
+        scanned: 5000,
+        filtered: 0,
+        prefiltered: 4800,
+        scored: 200,
+        matched: 12,
+        inserted: 12,
+    });
+    timed.snapshot = Some(Snapshot {
+        corpus_sha256: Some("3f2a9c01d4e7".to_string()),
+        generation: 2,
+    });
+    let untimed = QueryResult::new(&query("msft", "exact_symbol", "_SKIP_"));
+    let set = ResultSet {
+        backend: "nucleo".to_string(),
+        run: "main".to_string(),
+        corpus: Some("instruments-export.tsv".to_string()),
+        corpus_sha256: None,
+        fingerprint: None,
+        startup: Some(Startup {
+            phases: vec![("load".to_string(), 812.0), ("prepare".to_string(), 40.5)],
+        }),
+        steady_state: SteadyState::from_passes(&[9.0, 8.5, 9.5], 2),
+        memory: vec![Footprint {
+            representation: "utf32".to_string(),
+            candidates: 500_000,
+            heap_bytes: 31_000_000,
+            resident_bytes: Some(33_554_432),
+        }],
+        queries: vec![timed, untimed],
+    };
+
+    let path = temp_file("results-round-trip.json", b"");
+    set.write(&path).unwrap();
+    assert_eq!(ResultSet::read(&path).unwrap(), set);
+}
+
+#[test]
+fn summarises_recheck_timings() {
+    assert_eq!(Recheck::from_timings(&[]), None);
+    let recheck = Recheck::from_timings(&[3.0, 1.0, 2.0, 4.0, 10.0]).unwrap();
+    assert_eq!(recheck.iterations, 5);
+    assert_eq!(recheck.min_ms, 1.0);
+    assert_eq!(recheck.median_ms, 3.0);
+    assert_eq!(recheck.p90_ms, 10.0);
+    assert_eq!(recheck.max_ms, 10.0);
+    assert!((recheck.stddev_ms - 3.1623).abs() < 1e-4);
+    assert_eq!(recheck.flamegraph, None);
+}
+
+#[test]
+fn splits_startup_from_steady_state() {
+    let mut startup = Startup::default();
+    startup.push("load", 800.0);
+    startup.push("index", 200.0);
+    assert_eq!(startup.total_ms(), 1000.0);
+    assert_eq!(startup.phase_ms("index"), Some(200.0));
+    assert_eq!(startup.phase_ms("prepare"), None);
+
+    assert_eq!(SteadyState::from_passes(&[], 10), None);
+    let steady = SteadyState::from_passes(&[30.0, 20.0, 40.0], 10).unwrap();
+    assert_eq!((steady.iterations, steady.pass_min_ms), (3, 20.0));
+    assert_eq!(steady.pass_median_ms, 30.0);
+    assert_eq!(steady.per_query_ms(), 3.0);
+}
+
+#[test]
+fn ground_truth_depends_on_category() {
+    let results = Some(vec![hit("Applied Materials"), hit("Apple Inc.")]);
+    let mut typo = QueryResult::new(&query("aple", "typo", "apple inc"));
+    typo.results = results.clone();
+    assert_eq!(typo.found(), Some(true));
+
+    let mut exact = QueryResult::new(&query("apple", "exact_name", "Apple Inc"));
+    exact.results = results;
+    assert_eq!(exact.found(), Some(false));
+
+    let unrecorded = QueryResult::new(&query("apple", "exact_name", "Apple Inc"));
+    assert_eq!(unrecorded.found(), None);
+    let mut skipped = QueryResult::new(&query("apple", "exact_name", "_SKIP_"));
+    skipped.results = Some(Vec::new());
+    assert_eq!(skipped.found(), None);
+}
+
+#[test]
+fn rejects_newer_schema_versions() {
+    let path = temp_file(
+        "results-future.json",
+        br#"{"schema_version": 99, "backend": "nucleo", "queries": []}"#,
+    );
+    let err = ResultSet::read(&path).unwrap_err();
+    assert!(err.to_string().contains("newer"), "{err}");
+}
@@ -0,0 +1,64 @@
+use harness::corpus::Corpus;
+use harness::rng::Rng;
+use harness::sample::{length_bucket, stratified_sample, Script, Stratum};
+
+fn push(corpus: &mut Corpus, symbol: &str, name: &str) {
+    corpus.push(corpus.len() + 2, symbol, name, "US0378331005");
+}
+
+#[test]
+fn classifies_scripts() {
+    assert_eq!(Script::of("Apple Inc."), Script::Ascii);
+    assert_eq!(Script::of("Münchener Rück"), Script::Latin);
+    assert_eq!(Script::of("トヨタ自動車"), Script::Cjk);
+    assert_eq!(Script::of("Газпром"), Script::Other);
+    assert_eq!(Script::of(""), Script::Ascii);
+}
+
+#[test]
+fn buckets_lengths() {
+    assert_eq!(length_bucket(0), 0);
+    assert_eq!(length_bucket(4), 0);
+    assert_eq!(length_bucket(5), 1);
+    assert_eq!(length_bucket(33), 4);
+    assert_eq!(length_bucket(500), 4);
+}
+
+#[test]
+fn keeps_stratum_proportions_and_rare_strata() {
+    let mut corpus = Corpus::default();
+    for _ in 0..900 {
+        push(&mut corpus, "AB", "Short");
+    }
+    for _ in 0..99 {
+        push(&mut corpus, "ABCDEFGH", "A much longer fund name here");
+    }
+    push(&mut corpus, "7203", "トヨタ自動車");
+
+    let picked = stratified_sample(&corpus, 100, &mut Rng::new(7));
+    let count = |stratum: Stratum| {
+        picked
+            .iter()
+            .filter(|&&i| Stratum::of(&corpus.get(i)) == stratum)
+            .count()
+    };
+    assert_eq!(count(Stratum::of(&corpus.get(0))), 90);
+    assert_eq!(count(Stratum::of(&corpus.get(900))), 10);
+    assert_eq!(count(Stratum::of(&corpus.get(999))), 1);
+    assert!(picked.windows(2).all(|w| w[0] < w[1]));
+}
+
+#[test]
+fn is_deterministic_for_a_seed() {
+    let mut corpus = Corpus::default();
+    for i in 0..500 {
+        push(&mut corpus, &format!("S{i}"), &"x".repeat(i % 40 + 1));
+    }
+    let a = stratified_sample(&corpus, 50, &mut Rng::new(1));
+    let b = stratified_sample(&corpus, 50, &mut Rng::new(1));
+    assert_eq!(a, b);
+    assert_eq!(
+        stratified_sample(&corpus, 1000, &mut Rng::new(1)).len(),
+        500
+    );
+}
@@ -0,0 +1,48 @@
+use harness::schedule::{Budget, Scheduler};
+
+#[test]
+fn fills_free_slots_within_the_memory_budget() {
+    let mut scheduler = Scheduler::new(Budget {
+        jobs: 2,
+        memory_bytes: Some(100),
+    });
+    assert!(scheduler.is_idle());
+    assert_eq!(scheduler.next(&[60, 30], 10), Some(0));
+    assert_eq!(scheduler.start(60), 0);
+    // 60 reserved and 10 in use leave room for 30, not 40
+    assert_eq!(scheduler.next(&[40, 30], 10), Some(1));
+    assert_eq!(scheduler.next(&[40], 10), None);
+    assert_eq!(scheduler.start(30), 1);
+    assert_eq!(scheduler.reserved(), 90);
+    assert_eq!(scheduler.next(&[0], 0), None, "both slots are busy");
+
+    scheduler.finish(0);
+    assert_eq!(scheduler.next(&[40], 10), Some(0));
+    assert_eq!(scheduler.start(40), 0);
+    scheduler.finish(0);
+    scheduler.finish(1);
+    assert!(scheduler.is_idle());
+}
+
+#[test]
+fn runs_a_task_over_budget_alone() {
+    let mut scheduler = Scheduler::new(Budget {
+        jobs: 4,
+        memory_bytes: Some(100),
+    });
+    assert_eq!(scheduler.next(&[500, 20], 0), Some(1));
+    assert_eq!(scheduler.next(&[500], 0), Some(0));
+    scheduler.start(500);
+    assert_eq!(scheduler.next(&[500], 0), None);
+}
+
+#[test]
+fn without_a_memory_budget_only_slots_count() {
+    let mut scheduler = Scheduler::new(Budget {
+        jobs: 0,
+        memory_bytes: None,
+    });
+    assert_eq!(scheduler.next(&[u64::MAX / 2], 0), Some(0));
+    assert_eq!(scheduler.start(u64::MAX / 2), 0);
+    assert_eq!(scheduler.next(&[1], 0), None, "zero jobs still runs one");
+}
@@ -0,0 +1,24 @@
+use harness::scratch::Scratch;
+
+#[test]
+fn sums_both_buffers_and_keeps_the_high_water_mark() {
+    let a = Scratch {
+        utf32_chars: 40,
+        heap_entries: 3,
+    };
+    let b = Scratch {
+        utf32_chars: 12,
+        heap_entries: 100,
+    };
+    assert_eq!(a.utf32_bytes(), 160);
+    assert_eq!(a.heap_bytes(), 3 * Scratch::HEAP_ENTRY_BYTES);
+    assert_eq!(a.bytes(), 160 + 3 * Scratch::HEAP_ENTRY_BYTES);
+    assert_eq!(
+        a.max(b),
+        Scratch {
+            utf32_chars: 40,
+            heap_entries: 100
+        }
+    );
+    assert_eq!(Scratch::default().max(a), a);
+}
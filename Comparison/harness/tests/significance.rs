@@ -0,0 +1,38 @@
+use harness::significance::Change;
+
+#[test]
+fn a_consistent_slowdown_is_significant() {
+    let pairs: Vec<(f64, f64)> = (1..=50)
+        .map(|i| (i as f64, i as f64 * 1.2 + (i % 3) as f64 * 0.01))
+        .collect();
+    let change = Change::of(&pairs).unwrap();
+    assert!((change.percent() - 20.0).abs() < 0.5, "{change}");
+    assert!(change.low > 1.15 && change.high < 1.25, "{change}");
+    assert!(change.significant());
+    assert!(
+        change.to_string().starts_with("+20.0% (95% CI +"),
+        "{change}"
+    );
+}
+
+#[test]
+fn noise_in_both_directions_is_not_significant() {
+    // Every other query 10% slower, the rest 10% faster
+    let pairs: Vec<(f64, f64)> = (0..40)
+        .map(|i| {
+            let base = 1.0 + (i % 7) as f64;
+            (base, if i % 2 == 0 { base * 1.1 } else { base * 0.9 })
+        })
+        .collect();
+    let change = Change::of(&pairs).unwrap();
+    assert!(change.low < 1.0 && change.high > 1.0, "{change}");
+    assert!(!change.significant());
+    assert_eq!(Change::of(&pairs), Some(change), "seeded, so repeatable");
+}
+
+#[test]
+fn needs_two_queries_and_a_baseline() {
+    assert_eq!(Change::of(&[]), None);
+    assert_eq!(Change::of(&[(1.0, 2.0)]), None);
+    assert_eq!(Change::of(&[(0.0, 1.0), (0.0, 2.0)]), None);
+}
@@ -0,0 +1,60 @@
+use harness::judgments::Judgment;
+use harness::queries::Query;
+use harness::rng::Rng;
+use harness::split::Split;
+
+fn query(text: &str, category: &str) -> Query {
+    Query {
+        text: text.to_string(),
+        field: "name".to_string(),
+        category: category.to_string(),
+        expected_name: "_SKIP_".to_string(),
+        frequency: 1,
+    }
+}
+
+fn queries() -> Vec<Query> {
+    let mut queries: Vec<Query> = (0..10).map(|i| query(&format!("t{i}"), "typo")).collect();
+    queries.extend((0..5).map(|i| query(&format!("p{i}"), "prefix")));
+    queries
+}
+
+#[test]
+fn splits_each_category_by_ratio() {
+    let split = Split::stratified(&queries(), 0.2, &mut Rng::new(1));
+    let count = |qs: &[Query], c: &str| qs.iter().filter(|q| q.category == c).count();
+    assert_eq!(count(&split.validation, "typo"), 2);
+    assert_eq!(count(&split.validation, "prefix"), 1);
+    assert_eq!(split.train.len(), 12);
+}
+
+#[test]
+fn is_deterministic_for_a_seed() {
+    let texts =
+        |split: Split| -> Vec<String> { split.validation.into_iter().map(|q| q.text).collect() };
+    let a = texts(Split::stratified(&queries(), 0.4, &mut Rng::new(9)));
+    let b = texts(Split::stratified(&queries(), 0.4, &mut Rng::new(9)));
+    assert_eq!(a, b);
+}
+
+#[test]
+fn judgments_follow_their_query() {
+    let split = Split::stratified(&queries(), 0.2, &mut Rng::new(1));
+    let judgment = |q: &Query| Judgment {
+        query: q.text.clone(),
+        field: q.field.clone(),
+        symbol: String::new(),
+        name: "Target".to_string(),
+        isin: String::new(),
+        relevance: 1,
+    };
+    let mut judgments: Vec<Judgment> = queries().iter().map(judgment).collect();
+    judgments.push(judgment(&query("unknown", "typo")));
+
+    let (train, validation) = split.judgments(&judgments);
+    assert_eq!(train.len(), 12);
+    assert_eq!(validation.len(), 3);
+    assert!(validation
+        .iter()
+        .all(|j| split.validation.iter().any(|q| q.text == j.query)));
+}
@@ -0,0 +1,117 @@
+#![cfg(feature = "store")]
+
+mod common;
+
+use common::temp_file;
+use harness::fingerprint::Fingerprint;
+use harness::queries::Query;
+use harness::results::{Footprint, Hit, QueryResult, ResultSet, Startup, SteadyState};
+use harness::store::{RunFilter, Store};
+
+fn results(run: &str, median_ms: f64, top_name: &str) -> ResultSet {
+    let query = Query {
+        text: "aple".to_string(),
+        field: "name".to_string(),
+        category: "typo".to_string(),
+        expected_name: "Apple".to_string(),
+        frequency: 3,
+    };
+    let mut timed = QueryResult::new(&query);
+    timed.median_ms = Some(median_ms);
+    timed.matches = Some(40);
+    timed.results = Some(vec![Hit {
+        symbol: "AAPL".to_string(),
+        name: top_name.to_string(),
+        score: Some(120.0),
+    }]);
+    ResultSet {
+        backend: "nucleo".to_string(),
+        run: run.to_string(),
+        corpus: Some("export.tsv".to_string()),
+        corpus_sha256: Some("abc".to_string()),
+        fingerprint: Some(Fingerprint {
+            git_sha: Some("0123456789abcdef".to_string()),
+            git_dirty: Some(false),
+            features: vec!["harness/store".to_string()],
+            config: [("iterations".to_string(), "5".to_string())].into(),
+        }),
+        startup: Some(Startup {
+            phases: vec![("load".to_string(), 812.5), ("prepare".to_string(), 40.0)],
+        }),
+        steady_state: SteadyState::from_passes(&[median_ms, median_ms + 1.0], 1),
+        memory: vec![Footprint {
+            representation: "arena".to_string(),
+            candidates: 100_000,
+            heap_bytes: 4_518_912,
+            resident_bytes: None,
+        }],
+        queries: vec![timed],
+    }
+}
+
+fn open_empty(name: &str) -> Store {
+    Store::open(&temp_file(name, b"")).unwrap()
+}
+
+#[test]
+fn appended_runs_load_back() {
+    let mut store = open_empty("store-round-trip.db");
+    let set = results("main", 2.5, "Apple Inc.");
+    let id = store.append(&set, 1_700_000_000).unwrap();
+    assert_eq!(store.load(id).unwrap(), Some(set));
+    assert_eq!(store.load(id + 1).unwrap(), None);
+}
+
+#[test]
+fn lists_runs_oldest_first_within_the_window() {
+    let mut store = open_empty("store-window.db");
+    store
+        .append(&results("week-2", 2.0, "Apple Inc."), 200)
+        .unwrap();
+    store
+        .append(&results("week-1", 1.0, "Apple Inc."), 100)
+        .unwrap();
+    store
+        .append(&results("week-3", 3.0, "Applied Materials"), 300)
+        .unwrap();
+
+    let all = store.runs(&RunFilter::default()).unwrap();
+    let labels: Vec<&str> = all.iter().map(|r| r.run.as_str()).collect();
+    assert_eq!(labels, ["week-1", "week-2", "week-3"]);
+
+    let last_two = RunFilter {
+        last: Some(2),
+        ..RunFilter::default()
+    };
+    let labels: Vec<String> = store
+        .runs(&last_two)
+        .unwrap()
+        .into_iter()
+        .map(|r| r.run)
+        .collect();
+    assert_eq!(labels, ["week-2", "week-3"]);
+
+    let other_backend = RunFilter {
+        backend: Some("fzf".to_string()),
+        ..RunFilter::default()
+    };
+    assert!(store.runs(&other_backend).unwrap().is_empty());
+}
+
+#[test]
+fn summarises_categories_per_run() {
+    let mut store = open_empty("store-points.db");
+    let hit = store.append(&results("a", 2.0, "Apple Inc."), 100).unwrap();
+    let miss = store
+        .append(&results("b", 3.0, "Applied Materials"), 200)
+        .unwrap();
+
+    let points = store.category_points(hit).unwrap();
+    assert_eq!(points.len(), 1);
+    assert_eq!(points[0].category, "typo");
+    assert_eq!((points[0].judged, points[0].found), (1, 1));
+    assert_eq!(points[0].latency_ms, Some(2.0));
+
+    let points = store.category_points(miss).unwrap();
+    assert_eq!(points[0].hit_rate(), Some(0.0));
+}
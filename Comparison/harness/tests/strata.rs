@@ -0,0 +1,68 @@
+use harness::corpus::Corpus;
+use harness::queries::{Query, SKIP};
+use harness::strata::{name_length, script, Coverage};
+
+fn query(text: &str, expected_name: &str) -> Query {
+    Query {
+        text: text.to_string(),
+        field: "name".to_string(),
+        category: "exact_name".to_string(),
+        expected_name: expected_name.to_string(),
+        frequency: 1,
+    }
+}
+
+#[test]
+fn classifies_scripts_and_name_lengths() {
+    assert_eq!(script("Apple Inc."), "latin");
+    assert_eq!(script("Nestlé"), "latin_accented");
+    assert_eq!(script("Газпром"), "cyrillic");
+    assert_eq!(script("Alpha Ωmega"), "greek");
+    assert_eq!(script("トヨタ自動車"), "cjk");
+    assert_eq!(script("700"), "no_letters");
+    assert_eq!(name_length("Apple Inc."), "short");
+    assert_eq!(name_length("Microsoft Corporation"), "medium");
+    assert_eq!(
+        name_length("iShares Core MSCI World UCITS ETF USD (Acc)"),
+        "long"
+    );
+}
+
+#[test]
+fn suggests_queries_for_strata_the_set_misses() {
+    let mut corpus = Corpus::with_capacity(20, 0);
+    for i in 0..16 {
+        corpus.push(i + 2, "X", "Apple Inc.", "");
+    }
+    for i in 16..20 {
+        corpus.push(i + 2, "Y", "Газпром", "");
+    }
+    let queries: Vec<Query> = (0..10)
+        .map(|_| query("apple", "Apple Inc."))
+        .chain([query("700", SKIP)])
+        .collect();
+    let coverage = Coverage::new(&corpus, &queries, 3);
+
+    let cyrillic = coverage
+        .strata
+        .iter()
+        .find(|s| s.name == "cyrillic")
+        .unwrap();
+    assert_eq!((cyrillic.rows, cyrillic.queries), (4, 0));
+    // A fifth of the rows, so a fifth of 11 queries, rounded up
+    assert_eq!(cyrillic.suggested, 3);
+    let digits = coverage
+        .strata
+        .iter()
+        .find(|s| s.name == "no_letters")
+        .unwrap();
+    assert_eq!((digits.rows, digits.suggested), (0, 0));
+    // Every judged query targets a short name, as every row has one
+    assert!(coverage
+        .strata
+        .iter()
+        .filter(|s| s.dimension == "name_length")
+        .all(|s| s.suggested == 0));
+    assert_eq!(coverage.under_covered(), vec![cyrillic]);
+    assert_eq!(coverage.suggested(), 3);
+}
@@ -0,0 +1,105 @@
+use harness::limits::Limits;
+use harness::subprocess::{ranked_hits, run, Outcome};
+use std::time::{Duration, Instant};
+
+fn sh(script: &str) -> Vec<String> {
+    vec!["sh".to_string(), "-c".to_string(), script.to_string()]
+}
+
+#[test]
+fn captures_output_and_exit_status() {
+    let outcome = run(
+        &sh("cat; echo oops >&2"),
+        "aple\tname\n",
+        &Limits::default(),
+    )
+    .unwrap();
+    assert!(outcome.success());
+    assert_eq!(outcome.stdout, "aple\tname\n");
+    assert_eq!(outcome.stderr, "oops\n");
+    assert_eq!(outcome.failure(), None);
+    assert!(outcome.usage.wall_ms > 0.0);
+    assert!(outcome.usage.max_rss_bytes > 0);
+
+    let failed = run(
+        &sh("echo 'no corpus at x' >&2; exit 3"),
+        "",
+        &Limits::default(),
+    )
+    .unwrap();
+    assert_eq!(failed.code, Some(3));
+    assert_eq!(
+        failed.failure().as_deref(),
+        Some("exited with status 3: no corpus at x")
+    );
+    assert!(run(&[], "", &Limits::default()).is_err());
+}
+
+#[test]
+fn kills_a_child_past_its_time_limit() {
+    let limits = Limits {
+        time: Some(Duration::from_millis(100)),
+        ..Limits::default()
+    };
+    let outcome = run(&sh("exec sleep 10"), "", &limits).unwrap();
+    assert!(!outcome.success());
+    assert_eq!(outcome.code, None);
+    assert_eq!(outcome.signal, Some(9));
+    assert!(outcome.usage.wall_ms < 5000.0);
+    assert_eq!(
+        outcome.failure().as_deref(),
+        Some("ran past its 0.1s time limit")
+    );
+    assert_eq!(outcome.to_json()["signal"], 9);
+    assert_eq!(outcome.to_json()["stopped"]["time_limit_ms"], 100);
+    assert_eq!(
+        Outcome::from_json(&outcome.to_json()),
+        Some(Outcome {
+            stdout: String::new(),
+            stderr: String::new(),
+            ..outcome
+        })
+    );
+}
+
+#[test]
+fn kills_what_the_child_started() {
+    let limits = Limits {
+        time: Some(Duration::from_millis(100)),
+        ..Limits::default()
+    };
+    // `sleep` runs in a process of its own that inherits the output pipes
+    let start = Instant::now();
+    let outcome = run(&sh("sleep 10; true"), "", &limits).unwrap();
+    assert!(
+        start.elapsed() < Duration::from_secs(5),
+        "{:?}",
+        start.elapsed()
+    );
+    assert_eq!(outcome.signal, Some(9));
+    assert_eq!(
+        outcome.failure().as_deref(),
+        Some("ran past its 0.1s time limit")
+    );
+}
+
+#[test]
+fn reads_ranked_results_with_or_without_kind_and_provenance() {
+    let stdout = "aple\tname\t1\t140\tAAPL\tApple Inc.\tline=12\tExchange=XNAS\n\
+                  aple\tname\t2\t98\tAPLE\tApple Hospitality REIT\tline=40\n\
+                  msft\tsymbol\t1\t0.93\tprefix\tMSFT\tMicrosoft Corp\n\
+                  too\tfew\tfields\n";
+    let hits = ranked_hits(stdout);
+    assert_eq!(hits.len(), 2);
+    let apple = &hits[&("aple".to_string(), "name".to_string())];
+    assert_eq!(
+        apple.iter().map(|h| h.symbol.as_str()).collect::<Vec<_>>(),
+        ["AAPL", "APLE"]
+    );
+    assert_eq!(apple[0].name, "Apple Inc.");
+    assert_eq!(apple[0].score, Some(140.0));
+    let microsoft = &hits[&("msft".to_string(), "symbol".to_string())][0];
+    assert_eq!(microsoft.symbol, "MSFT");
+    assert_eq!(microsoft.name, "Microsoft Corp");
+    assert_eq!(microsoft.score, Some(0.93));
+}
@@ -0,0 +1,53 @@
+use harness::telemetry::{Histogram, SearchMetrics, SearchObservation, LATENCY_BUCKETS};
+use std::time::Duration;
+
+#[test]
+fn histograms_count_into_the_first_bucket_that_holds_the_value() {
+    let histogram = Histogram::new(&LATENCY_BUCKETS);
+    for seconds in [0.0001, 0.0005, 0.003, 9.0] {
+        histogram.observe(seconds);
+    }
+    assert_eq!(histogram.count(), 4);
+    assert!((histogram.sum() - 9.0036).abs() < 1e-9);
+}
+
+#[test]
+fn renders_cumulative_buckets_and_gauges() {
+    let metrics = SearchMetrics::new("search_server");
+    metrics.observe(&SearchObservation {
+        latency: Duration::from_millis(3),
+        scanned: 271_625,
+        matched: 430,
+        returned: 10,
+    });
+    metrics.observe(&SearchObservation {
+        latency: Duration::from_secs(4),
+        ..SearchObservation::default()
+    });
+    metrics.reject();
+    let text = metrics.render(&[("live_rows", "Rows searches can match.", 271_625.0)]);
+
+    assert_eq!(metrics.searches(), 2);
+    for line in [
+        "# TYPE search_server_searches_total counter",
+        "search_server_searches_total 2",
+        "search_server_rejected_total 1",
+        "# TYPE search_server_search_duration_seconds histogram",
+        "search_server_search_duration_seconds_bucket{le=\"0.0025\"} 0",
+        "search_server_search_duration_seconds_bucket{le=\"0.005\"} 1",
+        "search_server_search_duration_seconds_bucket{le=\"2.5\"} 1",
+        "search_server_search_duration_seconds_bucket{le=\"+Inf\"} 2",
+        "search_server_search_duration_seconds_count 2",
+        "search_server_scanned_rows_bucket{le=\"0\"} 1",
+        "search_server_scanned_rows_bucket{le=\"100000\"} 1",
+        "search_server_scanned_rows_bucket{le=\"10000000\"} 2",
+        "search_server_returned_results_sum 10",
+        "# TYPE search_server_live_rows gauge",
+        "search_server_live_rows 271625",
+    ] {
+        assert!(
+            text.lines().any(|l| l == line),
+            "missing {line:?} in\n{text}"
+        );
+    }
+}
@@ -0,0 +1,136 @@
+use harness::queries::Query;
+use harness::results::{Hit, QueryResult, ResultSet};
+use harness::tolerance::{check, Limits, Tolerances};
+
+fn result(text: &str, category: &str, median_ms: f64, top_name: &str) -> QueryResult {
+    let query = Query {
+        text: text.to_string(),
+        field: "name".to_string(),
+        category: category.to_string(),
+        expected_name: "Apple".to_string(),
+        frequency: 1,
+    };
+    let mut result = QueryResult::new(&query);
+    result.median_ms = Some(median_ms);
+    result.results = Some(vec![Hit {
+        symbol: "X".to_string(),
+        name: top_name.to_string(),
+        score: None,
+    }]);
+    result
+}
+
+fn run(queries: Vec<QueryResult>) -> ResultSet {
+    ResultSet {
+        backend: "nucleo".to_string(),
+        run: String::new(),
+        corpus: None,
+        corpus_sha256: None,
+        fingerprint: None,
+        startup: None,
+        steady_state: None,
+        memory: Vec::new(),
+        queries,
+    }
+}
+
+#[test]
+fn parses_overrides_and_keeps_defaults() {
+    let tolerances: Tolerances = "
+        lost_queries = 2
+        [total]
+        latency_pct = 8
+        [categories.typo]
+        latency_pct = 25.5
+    "
+    .parse()
+    .unwrap();
+    assert_eq!(tolerances.lost_queries, Some(2));
+    assert_eq!(tolerances.total.latency_pct, Some(8.0));
+    assert_eq!(tolerances.total.hit_rate_drop_pp, Some(0.0));
+    assert_eq!(
+        tolerances.categories["typo"],
+        Limits {
+            latency_pct: Some(25.5),
+            hit_rate_drop_pp: None
+        }
+    );
+}
+
+#[test]
+fn rejects_unknown_keys() {
+    let err = "[total]\nlatency_percent = 5"
+        .parse::<Tolerances>()
+        .unwrap_err();
+    assert!(err.contains("total.latency_percent"), "{err}");
+}
+
+#[test]
+fn passes_within_tolerance() {
+    let baseline = run(vec![result("aple", "typo", 10.0, "Apple Inc.")]);
+    let current = run(vec![result("aple", "typo", 10.4, "Apple Inc.")]);
+    let verdict = check(&Tolerances::default(), &baseline, &current);
+    assert!(verdict.passed(), "{:?}", verdict.violations);
+    assert_eq!(verdict.shared, 1);
+}
+
+#[test]
+fn reports_latency_and_quality_violations() {
+    let baseline = run(vec![
+        result("aple", "typo", 10.0, "Apple Inc."),
+        result("apple", "exact_name", 1.0, "Apple Inc."),
+    ]);
+    let current = run(vec![
+        result("aple", "typo", 12.0, "Apple Inc."),
+        result("apple", "exact_name", 1.0, "Applied Materials"),
+    ]);
+    let verdict = check(&Tolerances::default(), &baseline, &current);
+    let scopes: Vec<&str> = verdict
+        .violations
+        .iter()
+        .map(|v| v.scope.as_str())
+        .collect();
+    assert!(!verdict.passed());
+    assert!(scopes.contains(&"category typo"), "{scopes:?}");
+    assert!(scopes.contains(&"category exact_name"), "{scopes:?}");
+
+    let lenient: Tolerances = "
+        lost_queries = 1
+        [total]
+        latency_pct = 50
+        hit_rate_drop_pp = 100
+        [category]
+        latency_pct = 50
+        hit_rate_drop_pp = 100
+    "
+    .parse()
+    .unwrap();
+    assert!(check(&lenient, &baseline, &current).passed());
+}
+
+#[test]
+fn can_require_a_significant_latency_change() {
+    // 10% slower in all, past the 5% total limit, but half the queries got
+    // faster
+    let baseline = run((0..20)
+        .map(|i| result(&format!("q{i}"), "typo", 10.0, "Apple Inc."))
+        .collect());
+    let current = run((0..20)
+        .map(|i| {
+            let ms = if i % 2 == 0 { 4.0 } else { 18.0 };
+            result(&format!("q{i}"), "typo", ms, "Apple Inc.")
+        })
+        .collect());
+    let verdict = check(&Tolerances::default(), &baseline, &current);
+    let total = verdict
+        .violations
+        .iter()
+        .find(|v| v.scope == "total")
+        .expect("the total latency grew 10%");
+    assert!(total.message.contains("95% CI"), "{total}");
+
+    let strict: Tolerances = "require_significance = true".parse().unwrap();
+    assert!(strict.require_significance);
+    assert!(check(&strict, &baseline, &current).passed());
+    assert!("require_significance = 1".parse::<Tolerances>().is_err());
+}
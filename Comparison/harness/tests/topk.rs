@@ -0,0 +1,33 @@
+use harness::topk::TopK;
+
+#[test]
+fn keeps_the_highest_scores_in_order() {
+    let mut top = TopK::new(3);
+    for (idx, score) in [5, 1, 9, 7, 3, 9].into_iter().enumerate() {
+        top.push(score, idx);
+    }
+    assert_eq!(top.len(), 3);
+    assert_eq!(top.into_sorted(), [(9, 2), (9, 5), (7, 3)]);
+}
+
+#[test]
+fn keeps_later_rows_on_tied_scores() {
+    let mut top = TopK::new(2);
+    for idx in 0..4 {
+        top.push(10, idx);
+    }
+    assert_eq!(top.into_sorted(), [(10, 2), (10, 3)]);
+    assert!(TopK::new(0).is_empty());
+}
+
+#[test]
+fn reports_whether_a_push_was_kept() {
+    let mut top = TopK::new(2);
+    assert!(top.push(5, 0));
+    assert!(top.push(3, 1));
+    assert!(!top.push(2, 2));
+    assert!(top.push(3, 3));
+    assert!(!top.push(3, 1));
+    assert_eq!(top.into_sorted(), [(5, 0), (3, 3)]);
+    assert!(!TopK::new(0).push(9, 0));
+}
@@ -0,0 +1,32 @@
+use harness::tsv::fields;
+use std::borrow::Cow;
+
+fn split(line: &str) -> Vec<Cow<'_, str>> {
+    fields(line).collect()
+}
+
+#[test]
+fn splits_plain_lines_like_split() {
+    assert_eq!(split("AAPL\tApple Inc.\t"), ["AAPL", "Apple Inc.", ""]);
+    assert_eq!(split(""), [""]);
+    assert!(split("a\tb").iter().all(|f| matches!(f, Cow::Borrowed(_))));
+}
+
+#[test]
+fn unquotes_excel_cells() {
+    assert_eq!(
+        split("BRK.B\t\"Berkshire Hathaway \"\"B\"\"\"\tUS0846707026"),
+        ["BRK.B", "Berkshire Hathaway \"B\"", "US0846707026"]
+    );
+    // A quoted tab stays inside its cell
+    assert_eq!(split("\"XETR\tXFRA\"\tEUR"), ["XETR\tXFRA", "EUR"]);
+    assert_eq!(split("\"\"\t\"x\""), ["", "x"]);
+    assert!(matches!(split("\"plain\"")[0], Cow::Borrowed("plain")));
+}
+
+#[test]
+fn keeps_quotes_that_are_not_excel_quoting() {
+    assert_eq!(split("Berkshire \"B\"\tx"), ["Berkshire \"B\"", "x"]);
+    assert_eq!(split("\"B\" shares\tx"), ["\"B\" shares", "x"]);
+    assert_eq!(split("\"unclosed\tx"), ["\"unclosed", "x"]);
+}
@@ -0,0 +1,83 @@
+use harness::rng::Rng;
+use harness::typing::{keystroke_times, simulate, Strategy};
+
+#[test]
+fn queued_scans_all_run_in_order() {
+    let session = simulate(
+        &[0.0, 50.0, 100.0, 150.0],
+        &[200.0; 4],
+        0.0,
+        Strategy::Queue,
+        0.0,
+    );
+    assert_eq!(
+        (session.started, session.completed, session.cancelled),
+        (4, 4, 0)
+    );
+    assert_eq!(session.keystroke_ms, [200.0, 350.0, 500.0, 650.0]);
+    assert_eq!(session.final_ms(), 650.0);
+}
+
+#[test]
+fn latest_skips_requests_overtaken_while_busy() {
+    let session = simulate(
+        &[0.0, 50.0, 100.0, 150.0],
+        &[200.0; 4],
+        0.0,
+        Strategy::Latest,
+        0.0,
+    );
+    assert_eq!(
+        (session.started, session.completed, session.skipped),
+        (2, 2, 2)
+    );
+    assert_eq!(session.keystroke_ms, [200.0, 350.0, 300.0, 250.0]);
+}
+
+#[test]
+fn cancel_aborts_the_running_scan_after_the_cancel_latency() {
+    let session = simulate(
+        &[0.0, 50.0, 100.0, 150.0],
+        &[200.0; 4],
+        0.0,
+        Strategy::Cancel,
+        1.0,
+    );
+    assert_eq!(
+        (session.started, session.completed, session.cancelled),
+        (4, 1, 3)
+    );
+    assert_eq!(session.wasted_ms, 151.0);
+    assert_eq!(session.final_ms(), 201.0);
+    assert_eq!(session.keystroke_ms[0], 351.0);
+}
+
+#[test]
+fn debounce_drops_keystrokes_followed_within_the_window() {
+    let session = simulate(
+        &[0.0, 50.0, 100.0, 300.0],
+        &[10.0; 4],
+        80.0,
+        Strategy::Queue,
+        0.0,
+    );
+    assert_eq!(session.debounced, 2);
+    assert_eq!(session.started, 2);
+    assert_eq!(session.keystroke_ms, [190.0, 140.0, 90.0, 90.0]);
+}
+
+#[test]
+fn keystroke_times_stay_ordered_around_the_delay() {
+    assert_eq!(
+        keystroke_times(3, 120.0, 0.0, &mut Rng::new(1)),
+        [0.0, 120.0, 240.0]
+    );
+    let times = keystroke_times(50, 100.0, 40.0, &mut Rng::new(7));
+    for pair in times.windows(2) {
+        let gap = pair[1] - pair[0];
+        assert!((60.0..=140.0).contains(&gap), "gap {gap}");
+    }
+    assert_eq!(times, keystroke_times(50, 100.0, 40.0, &mut Rng::new(7)));
+    assert_eq!("cancel".parse::<Strategy>(), Ok(Strategy::Cancel));
+    assert!("abort".parse::<Strategy>().is_err());
+}
@@ -0,0 +1,70 @@
+use harness::rng::Rng;
+use harness::typos::{Layout, TypoInjector, TypoKind};
+
+fn sorted(mut keys: Vec<char>) -> Vec<char> {
+    keys.sort();
+    keys
+}
+
+#[test]
+fn qwerty_neighbors_follow_the_staggered_rows() {
+    let typos = TypoInjector::new(Layout::Qwerty);
+    assert_eq!(
+        sorted(typos.neighbors('g')),
+        vec!['b', 'f', 'h', 't', 'v', 'y']
+    );
+    assert_eq!(sorted(typos.neighbors('q')), vec!['a', 'w']);
+    assert_eq!(
+        sorted(typos.neighbors('S')),
+        vec!['A', 'D', 'E', 'W', 'X', 'Z']
+    );
+}
+
+#[test]
+fn layouts_move_keys() {
+    let qwertz = TypoInjector::new(Layout::Qwertz);
+    assert!(qwertz.neighbors('z').contains(&'t'));
+    let azerty = TypoInjector::new(Layout::Azerty);
+    assert!(azerty.neighbors('a').contains(&'z'));
+    assert!("dvorak".parse::<Layout>().is_err());
+}
+
+#[test]
+fn each_kind_makes_a_single_edit() {
+    let typos = TypoInjector::new(Layout::Qwerty);
+    let mut rng = Rng::new(1);
+    for _ in 0..100 {
+        let adjacent = typos
+            .inject_kind("boeing", TypoKind::AdjacentKey, &mut rng)
+            .unwrap();
+        assert_eq!(adjacent.chars().count(), 6);
+        assert!(adjacent.starts_with('b'));
+        assert_ne!(adjacent, "boeing");
+
+        let swapped = typos
+            .inject_kind("goldman", TypoKind::Transposition, &mut rng)
+            .unwrap();
+        assert_eq!(
+            sorted(swapped.chars().collect()),
+            sorted("goldman".chars().collect())
+        );
+        assert_ne!(swapped, "goldman");
+
+        let deleted = typos
+            .inject_kind("blackstone", TypoKind::Deletion, &mut rng)
+            .unwrap();
+        assert_eq!(deleted.len(), 9);
+
+        let doubled = typos
+            .inject_kind("goldman", TypoKind::DoubledLetter, &mut rng)
+            .unwrap();
+        assert_eq!(doubled.len(), 8);
+    }
+}
+
+#[test]
+fn short_words_are_left_alone() {
+    let typos = TypoInjector::new(Layout::Qwerty);
+    let mut rng = Rng::new(3);
+    assert_eq!(typos.inject("a", &mut rng), "a");
+}
@@ -0,0 +1,51 @@
+mod common;
+
+use common::temp_file;
+use harness::warmup::{read_through, Warmup};
+
+#[test]
+fn counts_warm_up_queries_and_timed_passes() {
+    assert_eq!(Warmup::IN_PROCESS.query_count(40), 40);
+    assert_eq!(Warmup::SUBPROCESS.query_count(40), 0);
+    let partial = Warmup {
+        queries: Some(10),
+        index: false,
+        discard_first: true,
+    };
+    assert_eq!(partial.query_count(40), 10);
+    assert_eq!(partial.query_count(4), 4);
+    assert_eq!(partial.timed_passes(5), 6);
+    assert_eq!(Warmup::IN_PROCESS.timed_passes(5), 5);
+
+    assert_eq!(Warmup::parse_queries("all"), Ok(None));
+    assert_eq!(Warmup::parse_queries("25"), Ok(Some(25)));
+    assert!(Warmup::parse_queries("some").is_err());
+}
+
+#[test]
+fn records_only_settings_that_differ_from_the_default() {
+    assert!(Warmup::IN_PROCESS.entries(&Warmup::IN_PROCESS).is_empty());
+    let custom = Warmup {
+        queries: Some(0),
+        index: true,
+        discard_first: false,
+    };
+    let entries = custom.entries(&Warmup::IN_PROCESS);
+    assert_eq!(entries.get("warmup_queries").map(String::as_str), Some("0"));
+    assert_eq!(
+        entries.get("warmup_index").map(String::as_str),
+        Some("true")
+    );
+    assert!(!entries.contains_key("warmup_discard_first"));
+    assert!(custom.entries(&Warmup::SUBPROCESS).is_empty());
+    assert_eq!(
+        Warmup::IN_PROCESS.entries(&Warmup::SUBPROCESS)["warmup_queries"],
+        "all"
+    );
+}
+
+#[test]
+fn reads_a_file_through() {
+    let path = temp_file("warmup.tsv", &vec![b'x'; 3 << 20]);
+    assert_eq!(read_through(&path).unwrap(), 3 << 20);
+}
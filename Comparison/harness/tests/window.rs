@@ -0,0 +1,46 @@
+use harness::window::{best_window, Window};
+
+const TEXT: &str = "Accumulating share class of a fund tracking global semiconductor equities";
+
+#[test]
+fn finds_an_exact_window() {
+    let window = best_window("Semiconductor", TEXT, 0).unwrap();
+    assert_eq!(window.distance, 0);
+    assert_eq!(&TEXT[window.start..window.end], "semiconductor");
+}
+
+#[test]
+fn tolerates_typos_within_the_budget() {
+    let window = best_window("semiconducter equites", TEXT, 3).unwrap();
+    assert_eq!(window.distance, 2);
+    assert_eq!(&TEXT[window.start..window.end], "semiconductor equities");
+    assert_eq!(best_window("semiconducter equites", TEXT, 1), None);
+}
+
+#[test]
+fn scattered_letters_do_not_match() {
+    // The letters of "sgeqty" appear in order, but far apart.
+    assert!(best_window("sgeqty", TEXT, 1).is_none());
+    assert_eq!(best_window("", TEXT, 5), None);
+    assert_eq!(
+        best_window("ab", "xxab", 0),
+        Some(Window {
+            distance: 0,
+            start: 2,
+            end: 4
+        })
+    );
+}
+
+#[test]
+fn ranges_count_characters_before_folding() {
+    // "İ" lower-cases to two characters, which must not shift the window.
+    let text = "İSTANBUL İHRACAT";
+    let window = best_window("ihracat", text, 1).unwrap();
+    let chars: Vec<char> = text.chars().collect();
+    assert_eq!((window.start, window.end), (9, 16));
+    assert_eq!(
+        chars[window.start..window.end].iter().collect::<String>(),
+        "İHRACAT"
+    );
+}
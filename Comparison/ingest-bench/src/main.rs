@@ -0,0 +1,214 @@
+//! Measures how usable search is while the corpus is still loading.
+//!
+//! Usage: ingest-bench [--tsv PATH] [--queries PATH] [--map symbol=KEY,...]
+//!                     [--checkpoints 20] [--top 10]
+//!
+//! The app can start answering queries over the instruments loaded so far
+//! instead of waiting for the whole universe. This loads the corpus once to
+//! know every query's final top results, then loads it again, pausing at
+//! `--checkpoints` evenly spaced row counts to run the whole query set over
+//! the rows loaded so far. Rows are appended in file order, so a result
+//! already loaded keeps its row index.
+//!
+//! For each checkpoint the table gives the time spent loading up to it
+//! (searching is not counted, as if it ran on another core), the query
+//! latency over the partial corpus, and how complete the answers are
+//! against the final ones: the share of each query's final top `--top`
+//! already in its results, the share of queries whose best result is
+//! already the final one, and the share whose whole top `--top` is final.
+//! Below it, the loading time after which queries keep their final top
+//! results for good.
+
+use harness::cli::{flag_value, parsed_flag};
+use harness::corpus::{load_corpus_mapped, load_corpus_progress, Corpus, FieldMap};
+use harness::metrics::percentile;
+use harness::queries::{load_queries, Query};
+use harness::resource_path;
+use harness::topk::TopK;
+use nucleo_matcher::pattern::{AtomKind, CaseMatching, Normalization, Pattern};
+use nucleo_matcher::{Config, Matcher, Utf32Str};
+use std::env;
+use std::time::Instant;
+
+/// The query set run over the rows loaded at one point during the load.
+struct Checkpoint {
+    rows: usize,
+    load_ms: f64,
+    /// Per query: the scan time and the rows of its top results.
+    answers: Vec<(f64, Vec<usize>)>,
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let tsv_path = flag_value(&args, "--tsv")
+        .map(str::to_string)
+        .unwrap_or_else(|| resource_path("instruments-export.tsv"));
+    let queries_path = flag_value(&args, "--queries")
+        .map(str::to_string)
+        .unwrap_or_else(|| resource_path("queries.tsv"));
+    let field_map: FieldMap = parsed_flag(&args, "--map", FieldMap::default());
+    let checkpoints: usize = parsed_flag(&args, "--checkpoints", 20);
+    let top: usize = parsed_flag(&args, "--top", 10);
+
+    let queries = load_queries(&queries_path).expect("Failed to read queries TSV file");
+    let patterns: Vec<Pattern> = queries
+        .iter()
+        .map(|q| {
+            Pattern::new(
+                &q.text,
+                CaseMatching::Ignore,
+                Normalization::Smart,
+                AtomKind::Fuzzy,
+            )
+        })
+        .collect();
+    let mut matcher = Matcher::new(Config::DEFAULT);
+    let mut buf = Vec::new();
+
+    let start = Instant::now();
+    let corpus = load_corpus_mapped(&tsv_path, &field_map).expect("Failed to read corpus");
+    let full_load_ms = start.elapsed().as_secs_f64() * 1000.0;
+    println!(
+        "Loaded {} instruments in {:.1}ms, {} queries",
+        corpus.len(),
+        full_load_ms,
+        queries.len()
+    );
+    let every = corpus.len().div_ceil(checkpoints.max(1));
+    drop(corpus);
+
+    // Time spent searching at checkpoints, taken off the loading clock
+    let mut searching_ms = 0.0;
+    let mut timeline: Vec<Checkpoint> = Vec::new();
+    let start = Instant::now();
+    let corpus = load_corpus_progress(&tsv_path, &field_map, every, &mut |partial| {
+        let paused = Instant::now();
+        let load_ms = (paused - start).as_secs_f64() * 1000.0 - searching_ms;
+        timeline.push(search(
+            partial,
+            load_ms,
+            &queries,
+            &patterns,
+            top,
+            &mut matcher,
+            &mut buf,
+        ));
+        searching_ms += paused.elapsed().as_secs_f64() * 1000.0;
+    })
+    .expect("Failed to read corpus");
+    if timeline.last().is_none_or(|c| c.rows < corpus.len()) {
+        let load_ms = start.elapsed().as_secs_f64() * 1000.0 - searching_ms;
+        timeline.push(search(
+            &corpus,
+            load_ms,
+            &queries,
+            &patterns,
+            top,
+            &mut matcher,
+            &mut buf,
+        ));
+    }
+
+    let last = timeline.last().expect("at least one checkpoint");
+    let finals: Vec<&[usize]> = last
+        .answers
+        .iter()
+        .map(|(_, rows)| rows.as_slice())
+        .collect();
+    let total_ms = last.load_ms;
+
+    println!();
+    println!("=== Search while loading (top {top}) ===");
+    println!();
+    println!(
+        "{:>10} {:>7} {:>10} {:>10} {:>10} {:>9} {:>9} {:>9}",
+        "Rows", "Loaded", "Load (ms)", "Query p50", "Query p95", "Recall", "Top-1", "Complete"
+    );
+    println!("{}", "-".repeat(82));
+    for checkpoint in &timeline {
+        let times: Vec<f64> = checkpoint.answers.iter().map(|(ms, _)| *ms).collect();
+        let mut recall = 0.0;
+        let mut best = 0;
+        let mut complete = 0;
+        for ((_, rows), final_rows) in checkpoint.answers.iter().zip(&finals) {
+            recall += if final_rows.is_empty() {
+                1.0
+            } else {
+                final_rows.iter().filter(|row| rows.contains(row)).count() as f64
+                    / final_rows.len() as f64
+            };
+            best += (rows.first() == final_rows.first()) as usize;
+            complete += (rows.as_slice() == *final_rows) as usize;
+        }
+        let share = |n: f64| n * 100.0 / queries.len().max(1) as f64;
+        println!(
+            "{:>10} {:>6.1}% {:>10.1} {:>8.3}ms {:>8.3}ms {:>8.1}% {:>8.1}% {:>8.1}%",
+            checkpoint.rows,
+            checkpoint.rows as f64 * 100.0 / corpus.len().max(1) as f64,
+            checkpoint.load_ms,
+            percentile(&times, 50.0).unwrap_or_default(),
+            percentile(&times, 95.0).unwrap_or_default(),
+            share(recall),
+            share(best as f64),
+            share(complete as f64)
+        );
+    }
+
+    // A query is settled from the checkpoint after which its answer never
+    // changes again
+    let settled: Vec<f64> = (0..queries.len())
+        .map(|qi| {
+            let unsettled = timeline
+                .iter()
+                .rposition(|c| c.answers[qi].1 != finals[qi])
+                .map_or(0, |i| i + 1);
+            timeline[unsettled].load_ms
+        })
+        .collect();
+    println!();
+    println!(
+        "First answers after {:.1}ms of {:.1}ms loading ({:.1}ms in one go)",
+        timeline[0].load_ms, total_ms, full_load_ms
+    );
+    println!(
+        "Final top {} reached after: p50 {:.1}ms, p90 {:.1}ms, all {:.1}ms",
+        top,
+        percentile(&settled, 50.0).unwrap_or_default(),
+        percentile(&settled, 90.0).unwrap_or_default(),
+        percentile(&settled, 100.0).unwrap_or_default()
+    );
+}
+
+/// Runs every query over the rows of `partial`.
+fn search(
+    partial: &Corpus,
+    load_ms: f64,
+    queries: &[Query],
+    patterns: &[Pattern],
+    top: usize,
+    matcher: &mut Matcher,
+    buf: &mut Vec<char>,
+) -> Checkpoint {
+    let answers = queries
+        .iter()
+        .zip(patterns)
+        .map(|(q, pattern)| {
+            let start = Instant::now();
+            let mut best = TopK::new(top);
+            for idx in 0..partial.len() {
+                buf.clear();
+                let haystack = Utf32Str::new(partial.text(idx, &q.field), buf);
+                if let Some(score) = pattern.score(haystack, matcher) {
+                    best.push(score, idx);
+                }
+            }
+            let rows = best.into_sorted().into_iter().map(|(_, idx)| idx).collect();
+            (start.elapsed().as_secs_f64() * 1000.0, rows)
+        })
+        .collect();
+    Checkpoint {
+        rows: partial.len(),
+        load_ms,
+        answers,
+    }
+}
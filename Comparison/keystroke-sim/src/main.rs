@@ -0,0 +1,187 @@
+//! Replays the query set as typed keystrokes, to compare debounce windows
+//! and cancellation strategies before the app commits to one.
+//!
+//! Usage: keystroke-sim [--tsv PATH] [--queries PATH] [--map symbol=KEY,...]
+//!                      [--key-delay-ms 150] [--jitter-ms 50]
+//!                      [--debounce-ms 0,50,100,200] [--cancel-ms 0.5]
+//!                      [--iterations 3] [--seed 42]
+//!
+//! Each query is typed one character at a time. The scan for every prefix
+//! is timed for real, as in `bench-nucleo` (the median of `--iterations`
+//! scans over the prepared column), and the keystrokes are spaced
+//! `--key-delay-ms` apart, give or take `--jitter-ms`, with the same
+//! seeded timings for every configuration. The typing is then replayed
+//! (`harness::typing`) on one search worker for each debounce window and
+//! each strategy:
+//!
+//! - `queue` runs every scan asked for, in order;
+//! - `latest` lets the running scan finish, then runs only the newest
+//!   request;
+//! - `cancel` aborts the running scan when a new one is asked for, holding
+//!   the worker `--cancel-ms` longer while the abort is noticed.
+//!
+//! For each pair the table gives the scans per query that were debounced,
+//! skipped, cancelled and completed, the scan time per query thrown away on
+//! cancelled scans, and the latency the user sees: from the last keystroke
+//! to the whole query's results, and from every keystroke to the first
+//! results that reflect it.
+
+use harness::cli::{flag_value, parsed_flag};
+use harness::corpus::{load_corpus_mapped, FieldMap};
+use harness::metrics::percentile;
+use harness::prepared::PreparedCorpus;
+use harness::queries::load_queries;
+use harness::resource_path;
+use harness::rng::Rng;
+use harness::topk::TopK;
+use harness::typing::{keystroke_times, simulate, Session, Strategy};
+use nucleo_matcher::pattern::{AtomKind, CaseMatching, Normalization, Pattern};
+use nucleo_matcher::{Config, Matcher, Utf32Str};
+use std::collections::HashMap;
+use std::env;
+use std::time::Instant;
+
+/// Results a scan keeps, as in the app's result list.
+const TOP_K: usize = 100;
+
+const DEFAULT_DEBOUNCE: &str = "0,50,100,200";
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let tsv_path = flag_value(&args, "--tsv")
+        .map(str::to_string)
+        .unwrap_or_else(|| resource_path("instruments-export.tsv"));
+    let queries_path = flag_value(&args, "--queries")
+        .map(str::to_string)
+        .unwrap_or_else(|| resource_path("queries.tsv"));
+    let field_map: FieldMap = parsed_flag(&args, "--map", FieldMap::default());
+    let key_delay_ms: f64 = parsed_flag(&args, "--key-delay-ms", 150.0);
+    let jitter_ms: f64 = parsed_flag(&args, "--jitter-ms", 50.0);
+    let cancel_ms: f64 = parsed_flag(&args, "--cancel-ms", 0.5);
+    let iterations: usize = parsed_flag(&args, "--iterations", 3);
+    let seed: u64 = parsed_flag(&args, "--seed", 42);
+    let debounces: Vec<f64> = flag_value(&args, "--debounce-ms")
+        .unwrap_or(DEFAULT_DEBOUNCE)
+        .split(',')
+        .map(|ms| {
+            ms.trim()
+                .parse()
+                .unwrap_or_else(|_| panic!("Invalid --debounce-ms entry `{ms}`"))
+        })
+        .collect();
+
+    let corpus = load_corpus_mapped(&tsv_path, &field_map).expect("Failed to read corpus");
+    let queries = load_queries(&queries_path).expect("Failed to read queries TSV file");
+    let prepared = PreparedCorpus::new(&corpus, queries.iter().map(|q| q.field.as_str()));
+    println!(
+        "Loaded {} instruments, {} queries",
+        corpus.len(),
+        queries.len()
+    );
+
+    // Time every prefix once; queries sharing a prefix on a field share it
+    let mut matcher = Matcher::new(Config::DEFAULT);
+    let mut buf = Vec::new();
+    let mut prefix_ms: HashMap<(String, String), f64> = HashMap::new();
+    let mut typed: Vec<Vec<f64>> = Vec::new();
+    for q in &queries {
+        let chars: Vec<char> = q.text.chars().collect();
+        let costs = (1..=chars.len())
+            .map(|len| {
+                let prefix: String = chars[..len].iter().collect();
+                *prefix_ms
+                    .entry((q.field.clone(), prefix))
+                    .or_insert_with_key(|(field, prefix)| {
+                        let column = prepared.column(field);
+                        let pattern = Pattern::new(
+                            prefix,
+                            CaseMatching::Ignore,
+                            Normalization::Smart,
+                            AtomKind::Fuzzy,
+                        );
+                        let mut times: Vec<f64> = (0..iterations.max(1))
+                            .map(|_| {
+                                let start = Instant::now();
+                                let mut top = TopK::new(TOP_K);
+                                for (ci, candidate) in column.iter().enumerate() {
+                                    buf.clear();
+                                    if let Some(score) = pattern
+                                        .score(Utf32Str::new(candidate, &mut buf), &mut matcher)
+                                    {
+                                        top.push(score, ci);
+                                    }
+                                }
+                                std::hint::black_box(top.into_sorted());
+                                start.elapsed().as_secs_f64() * 1000.0
+                            })
+                            .collect();
+                        times.sort_by(|a, b| a.partial_cmp(b).unwrap());
+                        times[times.len() / 2]
+                    })
+            })
+            .collect();
+        typed.push(costs);
+    }
+    println!(
+        "Timed {} distinct prefixes; keystrokes every {:.0}ms ± {:.0}ms",
+        prefix_ms.len(),
+        key_delay_ms,
+        jitter_ms
+    );
+
+    let mut rng = Rng::new(seed);
+    let times: Vec<Vec<f64>> = typed
+        .iter()
+        .map(|costs| keystroke_times(costs.len(), key_delay_ms, jitter_ms, &mut rng))
+        .collect();
+
+    println!();
+    println!("=== Typed queries (per query: scans, wasted time; latency in ms) ===");
+    println!();
+    println!(
+        "{:>8} {:<8} {:>9} {:>8} {:>9} {:>9} {:>9} {:>9} {:>9} {:>9} {:>9}",
+        "Debounce",
+        "Strategy",
+        "Debounced",
+        "Skipped",
+        "Cancelled",
+        "Completed",
+        "Wasted",
+        "Final p50",
+        "Final p95",
+        "Key p50",
+        "Key p95"
+    );
+    println!("{}", "-".repeat(108));
+    for &debounce_ms in &debounces {
+        for strategy in Strategy::ALL {
+            let sessions: Vec<Session> = times
+                .iter()
+                .zip(&typed)
+                .map(|(times, costs)| simulate(times, costs, debounce_ms, strategy, cancel_ms))
+                .collect();
+            let per_query = |count: fn(&Session) -> f64| {
+                sessions.iter().map(count).sum::<f64>() / sessions.len().max(1) as f64
+            };
+            let finals: Vec<f64> = sessions.iter().map(Session::final_ms).collect();
+            let keys: Vec<f64> = sessions
+                .iter()
+                .flat_map(|session| session.keystroke_ms.iter().copied())
+                .collect();
+            println!(
+                "{:>6.0}ms {:<8} {:>9.2} {:>8.2} {:>9.2} {:>9.2} {:>7.2}ms {:>9.1} {:>9.1} {:>9.1} {:>9.1}",
+                debounce_ms,
+                strategy.name(),
+                per_query(|s| s.debounced as f64),
+                per_query(|s| s.skipped as f64),
+                per_query(|s| s.cancelled as f64),
+                per_query(|s| s.completed as f64),
+                per_query(|s| s.wasted_ms),
+                percentile(&finals, 50.0).unwrap_or_default(),
+                percentile(&finals, 95.0).unwrap_or_default(),
+                percentile(&keys, 50.0).unwrap_or_default(),
+                percentile(&keys, 95.0).unwrap_or_default()
+            );
+        }
+    }
+}
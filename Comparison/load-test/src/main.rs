@@ -0,0 +1,442 @@
+//! Measures search latency end to end against a running `search-server`.
+//!
+//! Usage: load-test [--url http://127.0.0.1:8080] [--queries PATH]
+//!                  [--concurrency 1,4,16] [--iterations 5]
+//!                  [--results PATH] [--run LABEL] [--db PATH]
+//!        load-test --replay LOG.csv [--url ...] [--speed X] [--field symbol,name]
+//!                  [--clients N] [--window-s S]
+//!
+//! Each concurrency level runs that many client threads, each with its own
+//! keep-alive connection, which between them send every query
+//! `--iterations` times. A request's end-to-end time covers the round trip:
+//! routing, the scan, JSON encoding and decoding, and the network. The
+//! server reports its scan time in each response, so the table shows what
+//! the round trip adds on top of the scan. Throughput is completed
+//! requests over the level's wall time.
+//!
+//! The results file records each query's end-to-end median and minimum at
+//! the first level listed, as backend `nucleo-http`, with the top results
+//! from the server's answer, so the report can put it next to in-process
+//! runs. Each query also keeps the corpus snapshot the server answered it
+//! from (see `harness::mutable`). When the corpus changed during a level,
+//! say because instruments were delisted while the test ran, the table is
+//! followed by the range of generations its answers came from.
+//!
+//! `--replay` sends a search log's queries at the pace they were recorded
+//! instead (see `harness::replay`), `--speed` times faster, each searching
+//! `--field` (default `symbol,name`, as a search box would). Requests are
+//! sent open loop: a query goes out at its arrival time whether or not the
+//! earlier ones have come back, from up to `--clients` (default 64)
+//! connections. An arrival finding every connection busy waits for one. Its
+//! response time is counted from its arrival, not from when it was sent,
+//! so queueing in a burst shows up in the percentiles rather than slowing
+//! the replay down. The output gives the response, service and scan time
+//! percentiles overall, then per `--window-s` (default 60) of replay time
+//! for the busiest windows, to set latency against the arrival rate. A
+//! replay that ran while the corpus changed says which generations it was
+//! answered from.
+
+use harness::cli::{flag_value, parsed_flag};
+use harness::fingerprint::Fingerprint;
+use harness::metrics::percentile;
+use harness::mutable::Snapshot;
+use harness::queries::{load_queries, Query, SKIP};
+use harness::replay::{load_log, Arrival};
+use harness::resource_path;
+use harness::results::{Hit, QueryResult, ResultSet};
+use harness::store::{self, Store};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::env;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Results kept per query in the results file.
+const RECORDED_RESULTS: usize = 10;
+
+/// Windows listed in a replay's output, the busiest first.
+const BUSIEST_WINDOWS: usize = 10;
+
+/// One completed request.
+struct Sample {
+    query: usize,
+    end_to_end_ms: f64,
+    scan_ms: f64,
+    /// The corpus generation the server answered from.
+    generation: Option<u64>,
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let url = flag_value(&args, "--url")
+        .unwrap_or("http://127.0.0.1:8080")
+        .trim_end_matches('/')
+        .to_string();
+    let queries_path = flag_value(&args, "--queries")
+        .map(str::to_string)
+        .unwrap_or_else(|| resource_path("queries.tsv"));
+    if let Some(log) = flag_value(&args, "--replay") {
+        replay(&args, &url, log);
+        return;
+    }
+    let iterations: usize = parsed_flag(&args, "--iterations", 5);
+    let levels: Vec<usize> = flag_value(&args, "--concurrency")
+        .unwrap_or("1,4,16")
+        .split(',')
+        .map(|n| {
+            n.trim()
+                .parse()
+                .ok()
+                .filter(|&n| n > 0)
+                .unwrap_or_else(|| panic!("Invalid --concurrency entry `{n}`"))
+        })
+        .collect();
+
+    let queries = load_queries(&queries_path).expect("Failed to read queries");
+    let agent = ureq::AgentBuilder::new()
+        .timeout(Duration::from_secs(30))
+        .build();
+    agent
+        .get(&format!("{url}/health"))
+        .call()
+        .unwrap_or_else(|e| panic!("search-server is not answering at {url}: {e}"));
+
+    // Warm-up pass, which also keeps each query's answer for the results
+    let answers: Vec<Value> = queries.iter().map(|q| request(&agent, &url, q).0).collect();
+
+    println!(
+        "=== Load test: {} queries x {} iterations against {} ===",
+        queries.len(),
+        iterations,
+        url
+    );
+    println!();
+    println!(
+        "{:>7} {:>9} {:>10} {:>10} {:>10} {:>10} {:>10}",
+        "Clients", "Req/s", "p50 ms", "p95 ms", "p99 ms", "Scan p50", "Added p50"
+    );
+    println!("{}", "-".repeat(72));
+
+    let mut first_level = Vec::new();
+    let mut changed = Vec::new();
+    for (level, &clients) in levels.iter().enumerate() {
+        let (samples, wall) = run_level(&url, &queries, iterations, clients);
+        let end_to_end: Vec<f64> = samples.iter().map(|s| s.end_to_end_ms).collect();
+        let scan: Vec<f64> = samples.iter().map(|s| s.scan_ms).collect();
+        let added: Vec<f64> = samples
+            .iter()
+            .map(|s| s.end_to_end_ms - s.scan_ms)
+            .collect();
+        let ms = |values: &[f64], p: f64| percentile(values, p).unwrap_or(0.0);
+        println!(
+            "{:>7} {:>9.0} {:>10.2} {:>10.2} {:>10.2} {:>10.2} {:>10.2}",
+            clients,
+            samples.len() as f64 / wall.as_secs_f64(),
+            ms(&end_to_end, 50.0),
+            ms(&end_to_end, 95.0),
+            ms(&end_to_end, 99.0),
+            ms(&scan, 50.0),
+            ms(&added, 50.0)
+        );
+        let generations = samples.iter().filter_map(|s| s.generation);
+        if let (Some(first), Some(last)) = (generations.clone().min(), generations.max()) {
+            if first != last {
+                changed.push((clients, first, last));
+            }
+        }
+        if level == 0 {
+            first_level = samples;
+        }
+    }
+    for (clients, first, last) in changed {
+        println!("Corpus changed during the {clients}-client level: answered from generations {first} to {last}");
+    }
+
+    let results_path = flag_value(&args, "--results");
+    let db_path = flag_value(&args, "--db");
+    if results_path.is_none() && db_path.is_none() {
+        return;
+    }
+    let mut timings: Vec<Vec<f64>> = vec![Vec::new(); queries.len()];
+    for sample in &first_level {
+        timings[sample.query].push(sample.end_to_end_ms);
+    }
+    let records = queries
+        .iter()
+        .zip(&answers)
+        .zip(&mut timings)
+        .map(|((q, answer), times)| {
+            let mut record = QueryResult::new(q);
+            times.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            record.median_ms = times.get(times.len() / 2).copied();
+            record.min_ms = times.first().copied();
+            record.matches = answer["matches"].as_u64().map(|m| m as usize);
+            record.snapshot = Snapshot::from_json(&answer["snapshot"]);
+            record.results = answer["results"].as_array().map(|hits| {
+                hits.iter()
+                    .take(RECORDED_RESULTS)
+                    .map(|hit| Hit {
+                        symbol: hit["symbol"].as_str().unwrap_or_default().to_string(),
+                        name: hit["name"].as_str().unwrap_or_default().to_string(),
+                        score: hit["score"].as_f64(),
+                    })
+                    .collect()
+            });
+            record
+        })
+        .collect();
+    let config: BTreeMap<String, String> = [
+        ("url", url.clone()),
+        ("concurrency", levels[0].to_string()),
+        ("iterations", iterations.to_string()),
+    ]
+    .into_iter()
+    .map(|(key, value)| (key.to_string(), value))
+    .collect();
+    let results = ResultSet {
+        backend: "nucleo-http".to_string(),
+        run: flag_value(&args, "--run").unwrap_or_default().to_string(),
+        corpus: None,
+        corpus_sha256: answers
+            .iter()
+            .find_map(|answer| answer["snapshot"]["corpus_sha256"].as_str())
+            .map(String::from),
+        fingerprint: Some(Fingerprint::capture(&[], config)),
+        startup: None,
+        steady_state: None,
+        memory: Vec::new(),
+        queries: records,
+    };
+    println!();
+    if let Some(path) = results_path {
+        results.write(path).expect("Failed to write results file");
+        println!("Results written to {}", path);
+    }
+    if let Some(path) = db_path {
+        let mut db = Store::open(path).expect("Failed to open results database");
+        let id = db
+            .append(&results, store::now())
+            .expect("Failed to record results");
+        println!("Recorded as run {} in {}", id, path);
+    }
+}
+
+/// Sends every query `iterations` times from `clients` threads, returning
+/// the samples and the wall time they took.
+fn run_level(
+    url: &str,
+    queries: &[Query],
+    iterations: usize,
+    clients: usize,
+) -> (Vec<Sample>, Duration) {
+    let total = queries.len() * iterations;
+    let next = AtomicUsize::new(0);
+    let samples = Mutex::new(Vec::with_capacity(total));
+    let start = Instant::now();
+    thread::scope(|scope| {
+        for _ in 0..clients {
+            scope.spawn(|| {
+                let agent = ureq::AgentBuilder::new()
+                    .timeout(Duration::from_secs(30))
+                    .build();
+                let mut mine = Vec::new();
+                loop {
+                    let i = next.fetch_add(1, Ordering::Relaxed);
+                    if i >= total {
+                        break;
+                    }
+                    let query = i % queries.len();
+                    let (answer, end_to_end_ms) = request(&agent, url, &queries[query]);
+                    mine.push(Sample {
+                        query,
+                        end_to_end_ms,
+                        scan_ms: answer["scan_ms"].as_f64().unwrap_or(0.0),
+                        generation: answer["snapshot"]["generation"].as_u64(),
+                    });
+                }
+                samples.lock().unwrap().extend(mine);
+            });
+        }
+    });
+    (samples.into_inner().unwrap(), start.elapsed())
+}
+
+/// One search round trip: the decoded answer and its end-to-end time.
+fn request(agent: &ureq::Agent, url: &str, query: &Query) -> (Value, f64) {
+    let start = Instant::now();
+    let response = agent
+        .get(&format!("{url}/search"))
+        .query("q", &query.text)
+        .query("field", &query.field)
+        .query("limit", &RECORDED_RESULTS.to_string())
+        .call()
+        .unwrap_or_else(|e| panic!("Search for {:?} failed: {e}", query.text));
+    let body = response
+        .into_string()
+        .expect("Failed to read search response");
+    let answer: Value = serde_json::from_str(&body).expect("Search response is not JSON");
+    (answer, start.elapsed().as_secs_f64() * 1000.0)
+}
+
+/// One replayed search.
+struct Replayed {
+    arrival: usize,
+    /// From the search's arrival to its answer, including any wait for a
+    /// free connection.
+    response_ms: f64,
+    /// From sending the request to its answer.
+    service_ms: f64,
+    scan_ms: f64,
+    generation: Option<u64>,
+}
+
+fn replay(args: &[String], url: &str, log: &str) {
+    let speed: f64 = parsed_flag(args, "--speed", 1.0);
+    assert!(speed > 0.0, "--speed must be positive");
+    let clients: usize = parsed_flag(args, "--clients", 64);
+    let window_s: f64 = parsed_flag(args, "--window-s", 60.0);
+    let field = flag_value(args, "--field").unwrap_or("symbol,name");
+    let arrivals: Vec<Arrival> = load_log(log)
+        .unwrap_or_else(|e| panic!("Failed to read search log: {e}"))
+        .into_iter()
+        .map(|a| Arrival {
+            at: a.at.div_f64(speed),
+            ..a
+        })
+        .collect();
+    assert!(!arrivals.is_empty(), "{log} holds no searches");
+    let queries: Vec<Query> = arrivals
+        .iter()
+        .map(|a| Query {
+            text: a.text.clone(),
+            field: field.to_string(),
+            category: "other".to_string(),
+            expected_name: SKIP.to_string(),
+            frequency: 1,
+        })
+        .collect();
+    ureq::get(&format!("{url}/health"))
+        .call()
+        .unwrap_or_else(|e| panic!("search-server is not answering at {url}: {e}"));
+
+    let span = arrivals.last().unwrap().at.as_secs_f64();
+    println!(
+        "=== Replay: {} searches over {:.1}s ({}x recorded pace) against {} ===",
+        arrivals.len(),
+        span,
+        speed,
+        url
+    );
+    let samples = run_replay(url, &arrivals, &queries, clients.max(1));
+
+    let column = |pick: fn(&Replayed) -> f64| -> Vec<f64> { samples.iter().map(pick).collect() };
+    let response = column(|s| s.response_ms);
+    println!();
+    println!(
+        "{:<12} {:>10} {:>10} {:>10} {:>10}",
+        "", "p50 ms", "p95 ms", "p99 ms", "max ms"
+    );
+    println!("{}", "-".repeat(56));
+    for (name, values) in [
+        ("Response", response.clone()),
+        ("Service", column(|s| s.service_ms)),
+        ("Scan", column(|s| s.scan_ms)),
+    ] {
+        let ms = |p: f64| percentile(&values, p).unwrap_or(0.0);
+        println!(
+            "{:<12} {:>10.2} {:>10.2} {:>10.2} {:>10.2}",
+            name,
+            ms(50.0),
+            ms(95.0),
+            ms(99.0),
+            ms(100.0)
+        );
+    }
+    let generations = samples.iter().filter_map(|s| s.generation);
+    if let (Some(first), Some(last)) = (generations.clone().min(), generations.max()) {
+        if first != last {
+            println!(
+                "Corpus changed during the replay: answered from generations {first} to {last}"
+            );
+        }
+    }
+
+    // Response times by the window their search arrived in
+    let mut windows: BTreeMap<u64, Vec<f64>> = BTreeMap::new();
+    for sample in &samples {
+        let at = arrivals[sample.arrival].at.as_secs_f64();
+        let window = (at / window_s.max(f64::MIN_POSITIVE)) as u64;
+        windows.entry(window).or_default().push(sample.response_ms);
+    }
+    let mut busiest: Vec<(u64, Vec<f64>)> = windows.into_iter().collect();
+    let mean_rate = arrivals.len() as f64 / span.max(window_s);
+    busiest.sort_by_key(|(window, times)| (std::cmp::Reverse(times.len()), *window));
+    busiest.truncate(BUSIEST_WINDOWS);
+    busiest.sort_by_key(|(window, _)| *window);
+    println!();
+    println!(
+        "Busiest {}s windows (mean {:.1} req/s over the replay):",
+        window_s, mean_rate
+    );
+    println!(
+        "{:>10} {:>9} {:>9} {:>10} {:>10} {:>10}",
+        "Start s", "Searches", "Req/s", "p50 ms", "p99 ms", "max ms"
+    );
+    println!("{}", "-".repeat(63));
+    for (window, times) in &busiest {
+        let ms = |p: f64| percentile(times, p).unwrap_or(0.0);
+        println!(
+            "{:>10.0} {:>9} {:>9.1} {:>10.2} {:>10.2} {:>10.2}",
+            *window as f64 * window_s,
+            times.len(),
+            times.len() as f64 / window_s,
+            ms(50.0),
+            ms(99.0),
+            ms(100.0)
+        );
+    }
+}
+
+/// Sends each query at its arrival time from up to `clients` connections,
+/// returning one sample per arrival.
+fn run_replay(url: &str, arrivals: &[Arrival], queries: &[Query], clients: usize) -> Vec<Replayed> {
+    let (send, receive) = mpsc::channel::<(usize, Instant)>();
+    let receive = Mutex::new(receive);
+    let samples = Mutex::new(Vec::with_capacity(arrivals.len()));
+    let start = Instant::now();
+    thread::scope(|scope| {
+        for _ in 0..clients {
+            scope.spawn(|| {
+                let agent = ureq::AgentBuilder::new()
+                    .timeout(Duration::from_secs(30))
+                    .build();
+                let mut mine = Vec::new();
+                loop {
+                    let next = receive.lock().unwrap().recv();
+                    let Ok((arrival, arrived)) = next else {
+                        break;
+                    };
+                    let (answer, service_ms) = request(&agent, url, &queries[arrival]);
+                    mine.push(Replayed {
+                        arrival,
+                        response_ms: arrived.elapsed().as_secs_f64() * 1000.0,
+                        service_ms,
+                        scan_ms: answer["scan_ms"].as_f64().unwrap_or(0.0),
+                        generation: answer["snapshot"]["generation"].as_u64(),
+                    });
+                }
+                samples.lock().unwrap().extend(mine);
+            });
+        }
+        for (i, arrival) in arrivals.iter().enumerate() {
+            let due = start + arrival.at;
+            thread::sleep(due.saturating_duration_since(Instant::now()));
+            // Timed from when it was due, so a late send counts against it
+            send.send((i, due)).expect("Replay clients stopped");
+        }
+        drop(send);
+    });
+    samples.into_inner().unwrap()
+}
@@ -0,0 +1,126 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use harness::prefilter::CharMask;
+use harness::rng::Rng;
+use harness::topk::TopK;
+use microbench::{candidates, LENGTHS};
+use nucleo_matcher::chars::{normalize, to_lower_case};
+use nucleo_matcher::pattern::{AtomKind, CaseMatching, Normalization, Pattern};
+use nucleo_matcher::{Config, Matcher, Utf32Str, Utf32String};
+use std::hint::black_box;
+
+/// Candidates per benchmark iteration, so one iteration is long enough to
+/// time and short enough to stay in cache.
+const BATCH: usize = 64;
+
+/// Queries scored against each candidate length: a short abbreviation, a
+/// two-word prefix query, and one with an accent to normalise.
+const QUERIES: [&str; 3] = ["dbk", "ishares core", "societe gen"];
+
+fn folding(c: &mut Criterion) {
+    let mut group = c.benchmark_group("fold");
+    for len in LENGTHS {
+        let texts = candidates(len, BATCH, 1);
+        let bytes: usize = texts.iter().map(String::len).sum();
+        group.throughput(Throughput::Bytes(bytes as u64));
+        group.bench_with_input(
+            BenchmarkId::new("normalize_lowercase", len),
+            &texts,
+            |b, texts| {
+                b.iter(|| {
+                    for text in texts {
+                        for c in text.chars() {
+                            black_box(to_lower_case(normalize(c)));
+                        }
+                    }
+                })
+            },
+        );
+        group.bench_with_input(BenchmarkId::new("utf32_string", len), &texts, |b, texts| {
+            b.iter(|| {
+                for text in texts {
+                    black_box(Utf32String::from(text.as_str()));
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+fn prefilter(c: &mut Criterion) {
+    let mut group = c.benchmark_group("char_mask");
+    for len in LENGTHS {
+        let texts = candidates(len, BATCH, 2);
+        group.throughput(Throughput::Elements(BATCH as u64));
+        group.bench_with_input(BenchmarkId::new("build", len), &texts, |b, texts| {
+            b.iter(|| {
+                for text in texts {
+                    black_box(CharMask::of(text));
+                }
+            })
+        });
+    }
+    let masks: Vec<CharMask> = candidates(32, 4096, 3)
+        .iter()
+        .map(|text| CharMask::of(text))
+        .collect();
+    let query = CharMask::of("ishares core");
+    group.throughput(Throughput::Elements(masks.len() as u64));
+    group.bench_function("may_contain", |b| {
+        b.iter(|| {
+            masks
+                .iter()
+                .filter(|mask| mask.may_contain(black_box(query)))
+                .count()
+        })
+    });
+    group.finish();
+}
+
+fn scoring(c: &mut Criterion) {
+    let mut group = c.benchmark_group("score");
+    let mut matcher = Matcher::new(Config::DEFAULT);
+    let mut buf = Vec::new();
+    for query in QUERIES {
+        let pattern = Pattern::new(
+            query,
+            CaseMatching::Ignore,
+            Normalization::Smart,
+            AtomKind::Fuzzy,
+        );
+        for len in LENGTHS {
+            let texts = candidates(len, BATCH, 4);
+            group.throughput(Throughput::Elements(BATCH as u64));
+            group.bench_with_input(BenchmarkId::new(query, len), &texts, |b, texts| {
+                b.iter(|| {
+                    for text in texts {
+                        buf.clear();
+                        black_box(pattern.score(Utf32Str::new(text, &mut buf), &mut matcher));
+                    }
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+fn top_k(c: &mut Criterion) {
+    let mut group = c.benchmark_group("top_k");
+    let mut rng = Rng::new(5);
+    let scores: Vec<u32> = (0..10_000).map(|_| rng.below(400) as u32).collect();
+    group.throughput(Throughput::Elements(scores.len() as u64));
+    for k in [10, 100, 1000] {
+        group.bench_with_input(BenchmarkId::new("push", k), &scores, |b, scores| {
+            b.iter(|| {
+                let mut top = TopK::new(k);
+                for (idx, &score) in scores.iter().enumerate() {
+                    top.push(score, idx);
+                }
+                top.into_sorted()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, folding, prefilter, scoring, top_k);
+criterion_main!(benches);
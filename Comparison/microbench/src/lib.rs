@@ -0,0 +1,65 @@
+//! Inputs for the criterion micro-benchmarks in `benches/`.
+//!
+//! Usage: cargo bench --manifest-path Comparison/microbench/Cargo.toml
+//!        [-- FILTER] [--save-baseline NAME | --baseline NAME]
+//!
+//! The full harnesses time whole scans of a corpus, where a slower kernel
+//! is one effect among loading, caching and the query mix. These benches
+//! time the primitives of a scan on their own (folding, the character
+//! mask prefilter, scoring one candidate, top-K insertion), so a change
+//! that slows one shows up directly. Candidates are built from instrument
+//! name words with a fixed seed, so every run scores the same text.
+
+use harness::rng::Rng;
+
+/// Candidate lengths in characters: a ticker, a short name, a long fund
+/// name, and a description-sized text.
+pub const LENGTHS: [usize; 4] = [8, 32, 128, 512];
+
+/// Words candidates are made of, accented ones included so folding has
+/// work to do.
+const WORDS: &[&str] = &[
+    "Deutsche",
+    "Bank",
+    "iShares",
+    "Core",
+    "MSCI",
+    "World",
+    "UCITS",
+    "ETF",
+    "Société",
+    "Générale",
+    "Nestlé",
+    "Holding",
+    "AG",
+    "Registered",
+    "Shares",
+    "Class",
+    "A",
+    "Münchener",
+    "Rück",
+    "Vanguard",
+    "FTSE",
+    "All-World",
+    "2.125%",
+    "Notes",
+    "2031",
+    "Accumulating",
+];
+
+/// `count` candidates of `len` characters each.
+pub fn candidates(len: usize, count: usize, seed: u64) -> Vec<String> {
+    let mut rng = Rng::new(seed);
+    (0..count)
+        .map(|_| {
+            let mut text = String::new();
+            while text.chars().count() < len {
+                if !text.is_empty() {
+                    text.push(' ');
+                }
+                text.push_str(rng.choose(WORDS).expect("WORDS is not empty"));
+            }
+            text.chars().take(len).collect()
+        })
+        .collect()
+}
@@ -1,82 +1,328 @@
-use nucleo_matcher::pattern::{AtomKind, CaseMatching, Normalization, Pattern};
-use nucleo_matcher::{Config, Matcher, Utf32Str};
+use harness::cli::{flag_value, has_flag, parsed_flag};
+use harness::corpus::{load_corpus_mapped, Corpus, FieldMap};
+use harness::cost::QueryCost;
+use harness::dedup::Duplicates;
+use harness::filter::{CompiledFilter, Filter};
+use harness::heap::HeapProfile;
+use harness::logging;
+use harness::manifest;
+use harness::parallel::score_rows;
+use harness::perturb::{same_top, Perturbation};
+use harness::prefilter::CharMask;
+use harness::preset::Preset;
+use nucleo_matcher::Matcher;
+use std::collections::{BTreeMap, HashMap};
 use std::env;
-use std::fs;
-use std::io::{self, BufRead};
+use std::fs::File;
+use std::io::{self, BufRead, BufWriter, Write};
+use std::time::Instant;
+use tracing::{info, info_span};
 
-struct Instrument {
-    symbol: String,
-    name: String,
-    isin: String,
-}
+#[cfg(feature = "heap-profile")]
+#[global_allocator]
+static ALLOC: harness::heap::Alloc = harness::heap::Alloc;
+
+/// Leading results that must survive a trivial edit to the query.
+const STABLE_TOP: usize = 3;
 
 fn main() {
     let args: Vec<String> = env::args().collect();
+    logging::init(&args).unwrap_or_else(|e| panic!("Invalid logging flags: {e}"));
     let tsv_path = &args[1];
+    let field_map: FieldMap = parsed_flag(&args, "--map", FieldMap::default());
 
-    let content = fs::read_to_string(tsv_path).expect("Failed to read TSV file");
-    let mut instruments: Vec<Instrument> = Vec::new();
+    if let Some(manifest) = manifest::verify(tsv_path).expect("Corpus does not match its manifest")
+    {
+        eprintln!("Verified {}: {}", tsv_path, manifest.summary());
+    }
+    let mut instruments = load_corpus_mapped(tsv_path, &field_map).expect("Failed to read corpus");
+    if !instruments.repairs().is_empty() {
+        eprintln!("Encoding: {}", instruments.repairs().summary());
+    }
+    let duplicates = Duplicates::find(&instruments);
+    eprintln!("Duplicates: {}", duplicates.summary());
+    if has_flag(&args, "--dedup") {
+        instruments = duplicates.remove_from(&instruments);
+    }
 
-    for (i, line) in content.lines().enumerate() {
-        if i == 0 {
-            continue; // skip header
-        }
-        let cols: Vec<&str> = line.split('\t').collect();
-        if cols.len() >= 3 {
-            instruments.push(Instrument {
-                symbol: cols[0].to_string(),
-                name: cols[1].to_string(),
-                isin: cols[2].to_string(),
-            });
-        }
+    // A preset replaces the default matcher settings; --save-preset writes
+    // the settings in effect, to name a configuration worth keeping
+    let preset = Preset::from_args(&args).unwrap_or_else(|e| panic!("Invalid preset: {e}"));
+    if flag_value(&args, "--preset").is_some() {
+        eprintln!("Preset: {}", preset.name);
     }
+    if let Some(path) = flag_value(&args, "--save-preset") {
+        preset.save(path).expect("Failed to write preset");
+    }
+
+    // A filter given on the command line applies to every query; a third
+    // input column overrides it for that query
+    let default_filter: Filter = parsed_flag(&args, "--filter", Filter::default());
+    let compile = |filter: &Filter| -> Option<CompiledFilter> {
+        if filter.is_empty() {
+            return None;
+        }
+        Some(
+            filter
+                .compile(&instruments)
+                .unwrap_or_else(|e| panic!("Invalid filter: {e}")),
+        )
+    };
+    let default_filter = compile(&default_filter);
+
+    // The profile goes to stderr, keeping stdout to results
+    let heap_profile = match flag_value(&args, "--profile") {
+        Some("heap") => HeapProfile::start("dhat-heap.json"),
+        Some(other) => panic!("Unknown profile `{}` (supported: heap)", other),
+        None => None,
+    };
+
+    // With --prefilter, candidates lacking a letter or digit of the query
+    // are rejected by their character mask before scoring, as in bench-nucleo.
+    // Each field's masks are computed when a query first searches it
+    let prefilter = has_flag(&args, "--prefilter");
+    let mut masks: HashMap<String, Vec<CharMask>> = HashMap::new();
+    // With --perturb, each query is also scanned after trivial edits, and
+    // whether its top 3 survived each edit goes to a TSV of its own
+    let mut perturb = flag_value(&args, "--perturb").map(|path| {
+        let mut out =
+            BufWriter::new(File::create(path).expect("Failed to create perturbation file"));
+        writeln!(
+            out,
+            "query\tfield\tperturbation\tstable\ttop3\tperturbed_top3"
+        )
+        .expect("Failed to write perturbation file");
+        out
+    });
+    let mut unstable: BTreeMap<&str, (usize, usize)> = BTreeMap::new();
+    // With --costs, the work each query's scan did goes to a TSV of its own,
+    // keeping stdout to results
+    let mut costs = flag_value(&args, "--costs").map(|path| {
+        let mut out = BufWriter::new(File::create(path).expect("Failed to create costs file"));
+        writeln!(
+            out,
+            "query\tfield\tscanned\tfiltered\tprefiltered\tscored\tmatched\tinserted"
+        )
+        .expect("Failed to write costs file");
+        out
+    });
 
-    let mut matcher = Matcher::new(Config::DEFAULT);
+    // With --threads, each query's rows are scored on that many threads;
+    // the results are the same lines in the same order as on one
+    let threads: usize = parsed_flag(&args, "--threads", 1);
+    let mut matchers: Vec<Matcher> = (0..threads.max(1))
+        .map(|_| Matcher::new(preset.config()))
+        .collect();
+
+    let _run = info_span!("run", backend = "nucleo", corpus = %tsv_path).entered();
     let stdin = io::stdin();
 
     for line in stdin.lock().lines() {
         let line = line.expect("Failed to read line");
-        let parts: Vec<&str> = line.splitn(2, '\t').collect();
-        if parts.len() != 2 {
+        let parts: Vec<&str> = line.splitn(3, '\t').collect();
+        if parts.len() < 2 {
             continue;
         }
         let query = parts[0];
         let field = parts[1];
+        let query_filter = match parts.get(2).filter(|f| !f.trim().is_empty()) {
+            Some(expr) => compile(
+                &expr
+                    .parse()
+                    .unwrap_or_else(|e| panic!("Invalid filter: {e}")),
+            ),
+            None => None,
+        };
+        let filter = query_filter.as_ref().or(default_filter.as_ref());
+        let _query = info_span!("query", query, field).entered();
 
-        let pattern = Pattern::new(query, CaseMatching::Ignore, Normalization::Smart, AtomKind::Fuzzy);
-
-        let mut results: Vec<(u32, usize)> = Vec::new();
-        let mut buf = Vec::new();
-
-        for (idx, inst) in instruments.iter().enumerate() {
-            let candidate = if field == "symbol" {
-                &inst.symbol
-            } else if field == "isin" {
-                &inst.isin
-            } else {
-                &inst.name
-            };
+        let field_masks = prefilter.then(|| {
+            &*masks.entry(field.to_string()).or_insert_with(|| {
+                (0..instruments.len())
+                    .map(|idx| CharMask::of(instruments.text(idx, field)))
+                    .collect()
+            })
+        });
+        let start = Instant::now();
+        let (results, cost) = scan(
+            &instruments,
+            query,
+            field,
+            filter,
+            field_masks,
+            &preset,
+            &mut matchers,
+        );
+        info!(
+            duration_ms = start.elapsed().as_secs_f64() * 1000.0,
+            matches = results.len(),
+            scored = cost.scored,
+            prefiltered = cost.prefiltered,
+            "scored"
+        );
+        if let Some(out) = &mut costs {
+            writeln!(
+                out,
+                "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+                query,
+                field,
+                cost.scanned,
+                cost.filtered,
+                cost.prefiltered,
+                cost.scored,
+                cost.matched,
+                cost.inserted
+            )
+            .expect("Failed to write costs file");
+        }
 
-            buf.clear();
-            let haystack = Utf32Str::new(candidate, &mut buf);
-            if let Some(score) = pattern.score(haystack, &mut matcher) {
-                results.push((score, idx));
+        if let Some(out) = &mut perturb {
+            let top: Vec<usize> = results
+                .iter()
+                .map(|&(_, idx)| idx)
+                .take(STABLE_TOP)
+                .collect();
+            for perturbation in Perturbation::ALL {
+                let Some(steps) = perturbation.steps(query) else {
+                    continue;
+                };
+                let mut perturbed = Vec::new();
+                for step in &steps {
+                    (perturbed, _) = scan(
+                        &instruments,
+                        step,
+                        field,
+                        filter,
+                        field_masks,
+                        &preset,
+                        &mut matchers,
+                    );
+                }
+                let perturbed: Vec<usize> = perturbed
+                    .iter()
+                    .map(|&(_, idx)| idx)
+                    .take(STABLE_TOP)
+                    .collect();
+                let stable = same_top(&top, &perturbed, STABLE_TOP);
+                let counts = unstable.entry(perturbation.name()).or_default();
+                counts.0 += !stable as usize;
+                counts.1 += 1;
+                let symbols = |rows: &[usize]| -> String {
+                    rows.iter()
+                        .map(|&idx| instruments.get(idx).symbol)
+                        .collect::<Vec<_>>()
+                        .join(",")
+                };
+                writeln!(
+                    out,
+                    "{}\t{}\t{}\t{}\t{}\t{}",
+                    query,
+                    field,
+                    perturbation.name(),
+                    stable,
+                    symbols(&top),
+                    symbols(&perturbed)
+                )
+                .expect("Failed to write perturbation file");
             }
         }
 
-        results.sort_by(|a, b| b.0.cmp(&a.0));
-
         for (rank, (score, idx)) in results.iter().take(10).enumerate() {
-            let inst = &instruments[*idx];
+            let inst = instruments.get(*idx);
             println!(
-                "{}\t{}\t{}\t{}\t{}\t{}",
+                "{}\t{}\t{}\t{}\t{}\t{}\tline={}{}",
                 query,
                 field,
                 rank + 1,
                 score,
                 inst.symbol,
-                inst.name
+                inst.name,
+                inst.line,
+                provenance(&instruments, *idx)
             );
         }
     }
+
+    if let Some(mut out) = costs {
+        out.flush().expect("Failed to write costs file");
+    }
+    if let Some(mut out) = perturb {
+        out.flush().expect("Failed to write perturbation file");
+        for perturbation in Perturbation::ALL {
+            let (changed, tried) = unstable
+                .get(perturbation.name())
+                .copied()
+                .unwrap_or_default();
+            eprintln!(
+                "Ranking instability, {}: {} of {} queries changed their top {}",
+                perturbation.name(),
+                changed,
+                tried,
+                STABLE_TOP
+            );
+        }
+    }
+
+    if let Some(profile) = heap_profile {
+        let report = profile.finish().expect("Failed to read heap profile");
+        eprintln!("Heap profile of the scoring loop:");
+        eprintln!("{}", report);
+    }
+}
+
+/// Scores every row's `field` against `query` as `preset` says, skipping
+/// rows the filter or the character masks reject, and returns the matches
+/// best first with the work done. The rows are shared out between the
+/// matchers (see `harness::parallel`).
+fn scan(
+    instruments: &Corpus,
+    query: &str,
+    field: &str,
+    filter: Option<&CompiledFilter>,
+    field_masks: Option<&Vec<CharMask>>,
+    preset: &Preset,
+    matchers: &mut [Matcher],
+) -> (Vec<(u32, usize)>, QueryCost) {
+    let pattern = preset.pattern(query);
+    let query_mask = CharMask::of(query);
+    let mut rows: Vec<usize> = Vec::new();
+    let mut cost = QueryCost {
+        scanned: instruments.len(),
+        ..QueryCost::default()
+    };
+    for idx in 0..instruments.len() {
+        if filter.is_some_and(|f| !f.matches(&instruments.metadata(idx))) {
+            cost.filtered += 1;
+            continue;
+        }
+        if field_masks.is_some_and(|masks| !masks[idx].may_contain(query_mask)) {
+            cost.prefiltered += 1;
+            continue;
+        }
+        rows.push(idx);
+    }
+    let mut results = score_rows(
+        &pattern,
+        &rows,
+        |idx| instruments.text(idx, field),
+        matchers,
+    );
+    // Every match is kept and sorted, so each one is an insertion
+    cost.scored = cost.scanned - cost.filtered - cost.prefiltered;
+    cost.matched = results.len();
+    cost.inserted = results.len();
+
+    preset.rank(&mut results, |idx| instruments.text(idx, field));
+    (results, cost)
+}
+
+/// The row's metadata as trailing `column=value` fields, so a result can be
+/// traced to its corpus line without searching the file.
+fn provenance(instruments: &Corpus, idx: usize) -> String {
+    instruments
+        .metadata_columns()
+        .iter()
+        .zip(instruments.metadata(idx).values())
+        .map(|(column, value)| format!("\t{column}={value}"))
+        .collect()
 }
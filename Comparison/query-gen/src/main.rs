@@ -0,0 +1,433 @@
+//! Derives a `queries.tsv` from any instrument corpus.
+//!
+//! Usage: query-gen [--tsv PATH] [--out PATH] [--seed N] [--per-category N]
+//!                  [--layout qwerty|qwertz|azerty] [--map symbol=KEY,name=KEY,isin=KEY]
+//!                  [--long-text-column NAME]
+//!        query-gen --coverage QUERIES.tsv [--tsv PATH] [--map ...] [--min-per-stratum N]
+//!        query-gen --difficulty QUERIES.tsv [--tsv PATH] [--map ...] [--out PATH]
+//!
+//! Every category in `Agents/ADDING_TEST_QUERIES.md` is generated from
+//! sampled corpus rows, and the same seed always produces the same file.
+//! `long_text` queries are only generated when the corpus has the long-text
+//! column (default `Description`); their field is that column's name.
+//! `adversarial` queries are not sampled but derived from the whole corpus
+//! (see `harness::adversarial`), so every seed writes the same ones.
+//!
+//! `--coverage` generates nothing. It compares an existing query set with
+//! the corpus by script and name length (see `harness::strata`), and prints
+//! each stratum's share of rows and of queries and how many queries to add
+//! to it, at least `--min-per-stratum` (default 10) per stratum.
+//!
+//! `--difficulty` generates nothing either. It rates each query of an
+//! existing set against the corpus (see `harness::difficulty`) and writes a
+//! TSV of query, field, category, the three parts of the rating and the
+//! score, in the query set's order.
+
+use harness::adversarial;
+use harness::cli::{flag_value, parsed_flag};
+use harness::corpus::{load_corpus_mapped, FieldMap, Instrument};
+use harness::difficulty::Estimator;
+use harness::manifest::{Manifest, Schema};
+use harness::queries::{load_queries, write_queries, Query, CATEGORY_ORDER, SKIP};
+use harness::resource_path;
+use harness::rng::Rng;
+use harness::strata::{Coverage, DIMENSIONS};
+use harness::typos::{Layout, TypoInjector};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::fs::File;
+use std::hash::{Hash, Hasher};
+use std::io::{self, BufWriter, Write};
+
+/// Candidate pools, built once so every category samples from rows that can
+/// actually produce a meaningful query for it.
+struct Pools<'a> {
+    /// Rows whose name reads like words rather than a derivative code.
+    named: Vec<Instrument<'a>>,
+    /// Named rows with at least three alphabetic words.
+    long_named: Vec<Instrument<'a>>,
+    /// Derivative-style symbols containing spaces.
+    spaced_symbols: Vec<Instrument<'a>>,
+    /// Name of the long-text column, as spelled in the corpus.
+    long_text_column: &'a str,
+    /// Rows with at least eight words of long text, and that text.
+    long_texts: Vec<(Instrument<'a>, &'a str)>,
+    /// How many long texts contain each three-word window, by hash, so a
+    /// query can be cut from a window only one row contains.
+    window_counts: HashMap<u64, usize>,
+}
+
+/// Words of a long-text window, as a query types them.
+const WINDOW_WORDS: usize = 3;
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let tsv_path = flag_value(&args, "--tsv")
+        .map(str::to_string)
+        .unwrap_or_else(|| resource_path("instruments-export.tsv"));
+    let seed: u64 = parsed_flag(&args, "--seed", 42);
+    let per_category: usize = parsed_flag(&args, "--per-category", 20);
+    let layout: Layout = parsed_flag(&args, "--layout", Layout::Qwerty);
+    let typos = TypoInjector::new(layout);
+    let field_map: FieldMap = parsed_flag(&args, "--map", FieldMap::default());
+    let long_text_column = flag_value(&args, "--long-text-column").unwrap_or("Description");
+
+    let instruments = load_corpus_mapped(&tsv_path, &field_map).expect("Failed to read corpus");
+    if let Some(path) = flag_value(&args, "--coverage") {
+        let queries = load_queries(path).expect("Failed to read queries");
+        let min_per_stratum: usize = parsed_flag(&args, "--min-per-stratum", 10);
+        print_coverage(&Coverage::new(&instruments, &queries, min_per_stratum));
+        return;
+    }
+    if let Some(path) = flag_value(&args, "--difficulty") {
+        let queries = load_queries(path).expect("Failed to read queries");
+        let mut out = output(&args);
+        write_difficulty(&mut out, &mut Estimator::new(&instruments), &queries)
+            .and_then(|_| out.flush())
+            .expect("Failed to write difficulty");
+        return;
+    }
+    let long_text_column = instruments.metadata_column(long_text_column);
+    let long_texts: Vec<(Instrument, &str)> = match long_text_column {
+        Some(column) => (0..instruments.len())
+            .map(|idx| {
+                (
+                    instruments.get(idx),
+                    instruments.metadata(idx).value(column),
+                )
+            })
+            .filter(|(_, text)| text.split_whitespace().count() >= 8)
+            .collect(),
+        None => Vec::new(),
+    };
+    let mut window_counts: HashMap<u64, usize> = HashMap::new();
+    for (_, text) in &long_texts {
+        let ws: Vec<&str> = text.split_whitespace().collect();
+        let mut seen = HashSet::new();
+        for window in ws.windows(WINDOW_WORDS) {
+            let hash = window_hash(window);
+            if seen.insert(hash) {
+                *window_counts.entry(hash).or_default() += 1;
+            }
+        }
+    }
+    let pools = Pools {
+        named: instruments
+            .iter()
+            .filter(|i| is_descriptive_name(i.name))
+            .collect(),
+        long_named: instruments
+            .iter()
+            .filter(|i| is_descriptive_name(i.name) && words(i.name).len() >= 3)
+            .collect(),
+        spaced_symbols: instruments
+            .iter()
+            .filter(|i| i.symbol.trim().contains(' '))
+            .collect(),
+        long_text_column: long_text_column
+            .map(|column| instruments.metadata_columns()[column].as_str())
+            .unwrap_or(""),
+        long_texts,
+        window_counts,
+    };
+    eprintln!(
+        "Loaded {} instruments ({} with descriptive names)",
+        instruments.len(),
+        pools.named.len()
+    );
+
+    let mut rng = Rng::new(seed);
+    let mut queries: Vec<Query> = Vec::new();
+    let mut seen: HashSet<(String, String)> = HashSet::new();
+
+    for category in CATEGORY_ORDER {
+        if category == adversarial::CATEGORY {
+            let before = queries.len();
+            for q in adversarial::queries(&instruments) {
+                if seen.insert((q.text.to_lowercase(), q.field.clone())) {
+                    queries.push(q);
+                }
+            }
+            eprintln!("{:<16} {:>4} queries", category, queries.len() - before);
+            continue;
+        }
+        let mut generated = 0;
+        // Small pools can run out of distinct queries; cap the attempts.
+        for _ in 0..per_category * 50 {
+            if generated == per_category {
+                break;
+            }
+            let Some(q) = generate(category, &pools, &typos, &mut rng) else {
+                continue;
+            };
+            if q.text.trim().is_empty() {
+                continue;
+            }
+            if seen.insert((q.text.to_lowercase(), q.field.clone())) {
+                queries.push(q);
+                generated += 1;
+            }
+        }
+        eprintln!("{:<16} {:>4} queries", category, generated);
+    }
+
+    let mut out = output(&args);
+    write_queries(&mut out, &queries).expect("Failed to write queries");
+    out.flush().expect("Failed to write queries");
+    if let Some(path) = flag_value(&args, "--out") {
+        Manifest::write_for(path, "query-gen", Some(seed), Schema::queries())
+            .expect("Failed to write manifest");
+    }
+}
+
+/// The `--out` file, or stdout.
+fn output(args: &[String]) -> Box<dyn Write> {
+    match flag_value(args, "--out") {
+        Some(path) => Box::new(BufWriter::new(
+            File::create(path).expect("Failed to create output file"),
+        )),
+        None => Box::new(BufWriter::new(io::stdout().lock())),
+    }
+}
+
+/// One row per query, with `accepted` empty for queries with no expected
+/// name.
+fn write_difficulty(
+    out: &mut dyn Write,
+    estimator: &mut Estimator,
+    queries: &[Query],
+) -> io::Result<()> {
+    writeln!(
+        out,
+        "query\tfield\tcategory\tchar_share\tlookalikes\taccepted\tdifficulty"
+    )?;
+    for query in queries {
+        let difficulty = estimator.estimate(query);
+        writeln!(
+            out,
+            "{}\t{}\t{}\t{:.4}\t{}\t{}\t{:.4}",
+            query.text,
+            query.field,
+            query.category,
+            difficulty.char_share,
+            difficulty.lookalikes,
+            difficulty
+                .accepted
+                .map(|n| n.to_string())
+                .unwrap_or_default(),
+            difficulty.score()
+        )?;
+    }
+    Ok(())
+}
+
+/// A table per dimension, then the strata that need queries.
+fn print_coverage(coverage: &Coverage) {
+    for dimension in DIMENSIONS {
+        let strata: Vec<_> = coverage
+            .strata
+            .iter()
+            .filter(|s| s.dimension == dimension)
+            .collect();
+        let rows: usize = strata.iter().map(|s| s.rows).sum();
+        let queries: usize = strata.iter().map(|s| s.queries).sum();
+        let share = |n: usize, total: usize| n as f64 * 100.0 / total.max(1) as f64;
+        println!("By {}:", dimension.replace('_', " "));
+        println!(
+            "  {:<16} {:>9} {:>7} {:>8} {:>7} {:>5}",
+            "Stratum", "Rows", "%", "Queries", "%", "Add"
+        );
+        for s in strata {
+            println!(
+                "  {:<16} {:>9} {:>6.1}% {:>8} {:>6.1}% {:>5}",
+                s.name,
+                s.rows,
+                share(s.rows, rows),
+                s.queries,
+                share(s.queries, queries),
+                s.suggested
+            );
+        }
+        println!();
+    }
+    let under = coverage.under_covered();
+    if under.is_empty() {
+        println!("Every stratum is covered.");
+        return;
+    }
+    println!(
+        "Add about {} queries to balance the set:",
+        coverage.suggested()
+    );
+    for s in under {
+        println!(
+            "  {} {}: {} more ({} now, {} rows)",
+            s.dimension.replace('_', " "),
+            s.name,
+            s.suggested,
+            s.queries,
+            s.rows
+        );
+    }
+}
+
+fn generate(category: &str, pools: &Pools, typos: &TypoInjector, rng: &mut Rng) -> Option<Query> {
+    match category {
+        "exact_symbol" => {
+            let inst = rng.choose(&pools.named)?;
+            if inst.symbol.is_empty() || inst.symbol.contains(' ') {
+                return None;
+            }
+            Some(query(inst.symbol, "symbol", category, SKIP))
+        }
+        "exact_name" => {
+            let inst = rng.choose(&pools.named)?;
+            if words(inst.name).len() > 4 || inst.name.len() > 32 {
+                return None;
+            }
+            let text = if rng.chance(0.5) {
+                inst.name.to_lowercase()
+            } else {
+                inst.name.to_string()
+            };
+            Some(query(&text, "name", category, inst.name))
+        }
+        "exact_isin" => {
+            let inst = rng.choose(&pools.named)?;
+            // Sliced by byte below, so only ASCII ISINs qualify
+            if inst.isin.len() != 12 || !inst.isin.is_ascii() {
+                return None;
+            }
+            // Mostly full ISINs, some partial prefixes as typed mid-lookup.
+            let text = if rng.chance(0.7) {
+                inst.isin.to_string()
+            } else {
+                inst.isin[..rng.range(7, 10)].to_string()
+            };
+            Some(query(&text, "isin", category, inst.name))
+        }
+        "prefix" => {
+            if rng.chance(0.2) {
+                // Very short symbol prefixes have no single right answer.
+                let inst = rng.choose(&pools.named)?;
+                let prefix: String = inst.symbol.chars().take(rng.range(1, 2)).collect();
+                return Some(query(&prefix, "symbol", category, SKIP));
+            }
+            let inst = rng.choose(&pools.named)?;
+            let first = *words(inst.name).first()?;
+            let len = first.chars().count();
+            if len < 4 {
+                return None;
+            }
+            let prefix: String = first.chars().take(rng.range(3, (len - 1).min(6))).collect();
+            Some(query(&prefix.to_lowercase(), "name", category, first))
+        }
+        "typo" => {
+            let inst = rng.choose(&pools.named)?;
+            let ws = words(inst.name);
+            let target = ws.iter().position(|w| w.chars().count() >= 4)?;
+            let mut typed: Vec<String> = ws
+                .iter()
+                .take(2.max(target + 1))
+                .map(|w| w.to_string())
+                .collect();
+            typed[target] = typos.inject(ws[target], rng);
+            // `words` skips codes and punctuation, so only the mistyped word
+            // itself is guaranteed to be a substring of the expected name.
+            Some(query(&typed.join(" "), "name", category, ws[target]))
+        }
+        "substring" => {
+            let inst = rng.choose(&pools.named)?;
+            let ws = words(inst.name);
+            let inner: Vec<&str> = ws
+                .iter()
+                .skip(1)
+                .filter(|w| w.len() >= 3)
+                .copied()
+                .collect();
+            let word = *rng.choose(&inner)?;
+            Some(query(word, "name", category, word))
+        }
+        "multi_word" => {
+            let inst = rng.choose(&pools.long_named)?;
+            let ws = words(inst.name);
+            let take = rng.range(2, 3.min(ws.len()));
+            let start = rng.below(ws.len() - take + 1);
+            let mut picked: Vec<String> = ws[start..start + take]
+                .iter()
+                .map(|w| w.to_lowercase())
+                .collect();
+            if rng.chance(0.3) {
+                rng.shuffle(&mut picked);
+            }
+            Some(query(&picked.join(" "), "name", category, ws[start]))
+        }
+        "symbol_spaces" => {
+            let inst = rng.choose(&pools.spaced_symbols)?;
+            Some(query(inst.symbol.trim(), "symbol", category, SKIP))
+        }
+        "abbreviation" => {
+            let inst = rng.choose(&pools.long_named)?;
+            let ws = words(inst.name);
+            // Nobody abbreviates a ten-word fund name letter by letter.
+            if ws.len() > 5 {
+                return None;
+            }
+            let initials: String = ws.iter().filter_map(|w| w.chars().next()).collect();
+            Some(query(&initials.to_lowercase(), "name", category, inst.name))
+        }
+        "long_text" => {
+            let (inst, text) = rng.choose(&pools.long_texts)?;
+            let ws: Vec<&str> = text.split_whitespace().collect();
+            // Only windows unique to this row have a single right answer.
+            let unique: Vec<usize> = (0..=ws.len() - WINDOW_WORDS)
+                .filter(|&i| {
+                    pools
+                        .window_counts
+                        .get(&window_hash(&ws[i..i + WINDOW_WORDS]))
+                        == Some(&1)
+                })
+                .collect();
+            let start = *rng.choose(&unique)?;
+            let text = ws[start..start + WINDOW_WORDS].join(" ").to_lowercase();
+            Some(query(&text, pools.long_text_column, category, inst.name))
+        }
+        _ => None,
+    }
+}
+
+fn query(text: &str, field: &str, category: &str, expected_name: &str) -> Query {
+    Query {
+        text: text.to_string(),
+        field: field.to_string(),
+        category: category.to_string(),
+        expected_name: expected_name.to_string(),
+        frequency: 1,
+    }
+}
+
+/// Hash of a word window, case-insensitive.
+fn window_hash(words: &[&str]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for word in words {
+        word.to_lowercase().hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+/// Alphabetic words of a name, ignoring numbers, punctuation and codes.
+fn words(name: &str) -> Vec<&str> {
+    name.split_whitespace()
+        .filter(|w| w.chars().count() >= 2 && w.chars().all(char::is_alphabetic))
+        .collect()
+}
+
+/// Rejects derivative codes such as `AOWKL 2609I202800P`, where a token mixes
+/// letters and digits, and names without any real word.
+fn is_descriptive_name(name: &str) -> bool {
+    let mixed_token = name
+        .split_whitespace()
+        .any(|t| t.chars().any(|c| c.is_ascii_digit()) && t.chars().any(char::is_alphabetic));
+    !mixed_token && words(name).iter().any(|w| w.chars().count() >= 3)
+}
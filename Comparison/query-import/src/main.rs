@@ -0,0 +1,263 @@
+//! Turns a production search log into a query set and relevance judgments.
+//!
+//! Usage: query-import --log PATH [--tsv PATH] [--map symbol=KEY,name=KEY,isin=KEY]
+//!                     [--out PATH] [--judgments PATH] [--classify-unclicked]
+//!
+//! The log is a CSV with a header row and the columns
+//! `timestamp, raw query, selected result`, where the selected result is the
+//! ISIN, symbol or name of the clicked instrument. Each distinct query becomes
+//! one `queries.tsv` row whose field and category are inferred from the
+//! most-clicked result, and every clicked instrument is written to the
+//! judgments file as relevant. The query's frequency column counts its
+//! clicked searches. Searches without a click are skipped unless
+//! `--classify-unclicked` is given, in which case each distinct unclicked
+//! query is labelled against the whole corpus and written without an
+//! expected name or judgments.
+
+use harness::classify::{self, Classifier, Label};
+use harness::cli::{flag_value, has_flag, parsed_flag};
+use harness::corpus::{load_corpus_mapped, Corpus, FieldMap};
+use harness::input;
+use harness::judgments::{write_judgments, Judgment};
+use harness::manifest::{Manifest, Schema};
+use harness::queries::{write_queries, Query, CATEGORY_ORDER, SKIP};
+use harness::resource_path;
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+/// Clicks for one distinct (query, field) pair, in first-seen order.
+struct Imported {
+    text: String,
+    label: Label,
+    clicks: Vec<(usize, usize)>,
+}
+
+/// Finds the instrument a log's "selected result" refers to.
+struct Resolver {
+    by_isin: HashMap<String, usize>,
+    by_symbol: HashMap<String, usize>,
+    by_name: HashMap<String, usize>,
+}
+
+impl Resolver {
+    fn new(corpus: &Corpus) -> Self {
+        let mut resolver = Resolver {
+            by_isin: HashMap::new(),
+            by_symbol: HashMap::new(),
+            by_name: HashMap::new(),
+        };
+        for (idx, inst) in corpus.iter().enumerate() {
+            for (map, value) in [
+                (&mut resolver.by_isin, inst.isin),
+                (&mut resolver.by_symbol, inst.symbol),
+                (&mut resolver.by_name, inst.name),
+            ] {
+                if !value.is_empty() {
+                    map.entry(value.to_lowercase()).or_insert(idx);
+                }
+            }
+        }
+        resolver
+    }
+
+    fn resolve(&self, selected: &str) -> Option<usize> {
+        let key = selected.trim().to_lowercase();
+        self.by_isin
+            .get(&key)
+            .or_else(|| self.by_symbol.get(&key))
+            .or_else(|| self.by_name.get(&key))
+            .copied()
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let log_path = flag_value(&args, "--log").expect("--log is required");
+    let tsv_path = flag_value(&args, "--tsv")
+        .map(str::to_string)
+        .unwrap_or_else(|| resource_path("instruments-export.tsv"));
+    let field_map: FieldMap = parsed_flag(&args, "--map", FieldMap::default());
+    let out_path = flag_value(&args, "--out").unwrap_or("imported-queries.tsv");
+    let judgments_path = flag_value(&args, "--judgments").unwrap_or("imported-judgments.tsv");
+    let classify_unclicked = has_flag(&args, "--classify-unclicked");
+
+    let corpus = load_corpus_mapped(&tsv_path, &field_map).expect("Failed to read corpus");
+    let resolver = Resolver::new(&corpus);
+
+    let log = input::open(log_path).expect("Failed to open search log");
+    let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(log);
+
+    let mut imported: Vec<Imported> = Vec::new();
+    let mut index: HashMap<(String, &str), usize> = HashMap::new();
+    // Distinct unclicked queries and their search counts, in first-seen order.
+    let mut unclicked: Vec<(String, u64)> = Vec::new();
+    let mut unclicked_index: HashMap<String, usize> = HashMap::new();
+    let (mut searches, mut no_click, mut unresolved, mut unclassified) = (0, 0, 0, 0);
+
+    for record in reader.records() {
+        let record = record.expect("Failed to parse search log");
+        searches += 1;
+        // Tabs and newlines would break the TSV outputs.
+        let text = record
+            .get(1)
+            .unwrap_or("")
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        let selected = record.get(2).unwrap_or("").trim();
+        if text.is_empty() || selected.is_empty() {
+            no_click += 1;
+            if classify_unclicked && !text.is_empty() {
+                let entry = *unclicked_index
+                    .entry(text.to_lowercase())
+                    .or_insert_with(|| {
+                        unclicked.push((text.clone(), 0));
+                        unclicked.len() - 1
+                    });
+                unclicked[entry].1 += 1;
+            }
+            continue;
+        }
+        let Some(idx) = resolver.resolve(selected) else {
+            unresolved += 1;
+            continue;
+        };
+        let Some(label) = classify::infer(&text, &corpus.get(idx)) else {
+            unclassified += 1;
+            continue;
+        };
+
+        let key = (text.to_lowercase(), label.field);
+        let entry = *index.entry(key).or_insert_with(|| {
+            imported.push(Imported {
+                text: text.clone(),
+                label,
+                clicks: Vec::new(),
+            });
+            imported.len() - 1
+        });
+        let clicks = &mut imported[entry].clicks;
+        match clicks.iter_mut().find(|(i, _)| *i == idx) {
+            Some((_, count)) => *count += 1,
+            None => clicks.push((idx, 1)),
+        }
+    }
+
+    let mut queries: Vec<Query> = Vec::with_capacity(imported.len());
+    let mut judgments = Vec::new();
+    // Rows are keyed again on the final, relabelled (query, field), so the
+    // query set never holds the same pair twice whatever field the top click
+    // settles on. A merged row sums the frequencies and takes the category
+    // and expected name of the most-clicked top result.
+    let mut rows: HashMap<(String, &str), (usize, usize)> = HashMap::new();
+    let mut judged: HashSet<(usize, usize)> = HashSet::new();
+    for entry in &imported {
+        // The first of the most-clicked results decides the category.
+        let &(top, top_clicks) = entry
+            .clicks
+            .iter()
+            .rev()
+            .max_by_key(|(_, count)| *count)
+            .unwrap();
+        let label = classify::infer(&entry.text, &corpus.get(top)).unwrap_or(entry.label);
+        let frequency: u64 = entry.clicks.iter().map(|&(_, count)| count as u64).sum();
+        let row = match rows.get_mut(&(entry.text.to_lowercase(), label.field)) {
+            Some((row, best)) => {
+                let query = &mut queries[*row];
+                query.frequency += frequency;
+                if top_clicks > *best {
+                    *best = top_clicks;
+                    query.category = label.category.to_string();
+                    query.expected_name = corpus.get(top).name.to_string();
+                }
+                *row
+            }
+            None => {
+                queries.push(Query {
+                    text: entry.text.clone(),
+                    field: label.field.to_string(),
+                    category: label.category.to_string(),
+                    expected_name: corpus.get(top).name.to_string(),
+                    frequency,
+                });
+                rows.insert(
+                    (entry.text.to_lowercase(), label.field),
+                    (queries.len() - 1, top_clicks),
+                );
+                queries.len() - 1
+            }
+        };
+        for &(idx, _) in &entry.clicks {
+            if !judged.insert((row, idx)) {
+                continue;
+            }
+            let inst = corpus.get(idx);
+            judgments.push(Judgment {
+                query: queries[row].text.clone(),
+                field: label.field.to_string(),
+                symbol: inst.symbol.to_string(),
+                name: inst.name.to_string(),
+                isin: inst.isin.to_string(),
+                relevance: 1,
+            });
+        }
+    }
+
+    if classify_unclicked {
+        // Unclicked searches for a query that also got clicks add to its
+        // frequency; the rest need the corpus to find a category.
+        let clicked: HashMap<String, usize> = queries
+            .iter()
+            .enumerate()
+            .map(|(i, q)| (q.text.to_lowercase(), i))
+            .collect();
+        let classifier = Classifier::new(&corpus);
+        let mut labelled = 0;
+        for (text, count) in unclicked {
+            if let Some(&i) = clicked.get(&text.to_lowercase()) {
+                queries[i].frequency += count;
+                continue;
+            }
+            let Some((label, _)) = classifier.classify(&text) else {
+                unclassified += count;
+                continue;
+            };
+            labelled += 1;
+            queries.push(Query {
+                text,
+                field: label.field.to_string(),
+                category: label.category.to_string(),
+                expected_name: SKIP.to_string(),
+                frequency: count,
+            });
+        }
+        eprintln!("Classified {labelled} unclicked queries against the corpus");
+    }
+
+    let mut out = BufWriter::new(File::create(out_path).expect("Failed to create query file"));
+    write_queries(&mut out, &queries).expect("Failed to write queries");
+    out.flush().expect("Failed to write queries");
+    let mut out =
+        BufWriter::new(File::create(judgments_path).expect("Failed to create judgments file"));
+    write_judgments(&mut out, &judgments).expect("Failed to write judgments");
+    out.flush().expect("Failed to write judgments");
+    Manifest::write_for(out_path, "query-import", None, Schema::queries())
+        .expect("Failed to write manifest");
+    Manifest::write_for(judgments_path, "query-import", None, Schema::judgments())
+        .expect("Failed to write manifest");
+
+    eprintln!(
+        "Read {searches} searches: {no_click} without a click, {unresolved} with an unknown result, {unclassified} unclassified"
+    );
+    for category in CATEGORY_ORDER {
+        let count = queries.iter().filter(|q| q.category == category).count();
+        eprintln!("  {category:<15} {count:>6}");
+    }
+    eprintln!(
+        "Wrote {} queries to {out_path} and {} judgments to {judgments_path}",
+        queries.len(),
+        judgments.len()
+    );
+}
@@ -0,0 +1,85 @@
+//! Splits a query set (and its judgments) into train and validation sets.
+//!
+//! Usage: query-split [--queries PATH] [--judgments PATH] [--out-dir DIR]
+//!                    [--validation RATIO] [--seed N]
+//!
+//! Each category is split separately, so both sets keep the full set's
+//! category mix. Writes `train-queries.tsv` and `validation-queries.tsv`
+//! (plus `train-judgments.tsv` and `validation-judgments.tsv` when judgments
+//! are given) to `--out-dir`, each with a manifest recording the seed.
+
+use harness::cli::{flag_value, parsed_flag};
+use harness::judgments::{load_judgments, write_judgments, Judgment};
+use harness::manifest::{Manifest, Schema};
+use harness::queries::{load_queries, write_queries, Query, CATEGORY_ORDER};
+use harness::resource_path;
+use harness::rng::Rng;
+use harness::split::Split;
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let queries_path = flag_value(&args, "--queries")
+        .map(str::to_string)
+        .unwrap_or_else(|| resource_path("queries.tsv"));
+    let out_dir = flag_value(&args, "--out-dir").unwrap_or(".");
+    let ratio: f64 = parsed_flag(&args, "--validation", 0.2);
+    let seed: u64 = parsed_flag(&args, "--seed", 42);
+    assert!(
+        (0.0..=1.0).contains(&ratio),
+        "--validation must be between 0 and 1"
+    );
+
+    let queries = load_queries(&queries_path).expect("Failed to read queries TSV file");
+    let split = Split::stratified(&queries, ratio, &mut Rng::new(seed));
+    fs::create_dir_all(out_dir).expect("Failed to create output directory");
+
+    let out = |name: &str| Path::new(out_dir).join(name).to_string_lossy().to_string();
+    write(&out("train-queries.tsv"), &split.train, seed);
+    write(&out("validation-queries.tsv"), &split.validation, seed);
+
+    if let Some(path) = flag_value(&args, "--judgments") {
+        let judgments = load_judgments(path).expect("Failed to read judgments");
+        let (train, validation) = split.judgments(&judgments);
+        write_judgment_file(&out("train-judgments.tsv"), &train, seed);
+        write_judgment_file(&out("validation-judgments.tsv"), &validation, seed);
+        eprintln!(
+            "Judgments: {} train, {} validation, {} without a query",
+            train.len(),
+            validation.len(),
+            judgments.len() - train.len() - validation.len()
+        );
+    }
+
+    eprintln!("{:<16} {:>6} {:>11}", "Category", "Train", "Validation");
+    for category in CATEGORY_ORDER {
+        let count = |qs: &[Query]| qs.iter().filter(|q| q.category == category).count();
+        eprintln!(
+            "{:<16} {:>6} {:>11}",
+            category,
+            count(&split.train),
+            count(&split.validation)
+        );
+    }
+}
+
+fn write(path: &str, queries: &[Query], seed: u64) {
+    write_file(path, |out| write_queries(out, queries));
+    Manifest::write_for(path, "query-split", Some(seed), Schema::queries())
+        .expect("Failed to write manifest");
+}
+
+fn write_judgment_file(path: &str, judgments: &[Judgment], seed: u64) {
+    write_file(path, |out| write_judgments(out, judgments));
+    Manifest::write_for(path, "query-split", Some(seed), Schema::judgments())
+        .expect("Failed to write manifest");
+}
+
+fn write_file(path: &str, body: impl FnOnce(&mut BufWriter<File>) -> io::Result<()>) {
+    let mut out = BufWriter::new(File::create(path).expect("Failed to create output file"));
+    body(&mut out).expect("Failed to write output file");
+    out.flush().expect("Failed to write output file");
+}
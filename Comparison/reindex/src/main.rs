@@ -0,0 +1,72 @@
+//! Rewrites a saved prepared index in the format this build reads.
+//!
+//! Usage: reindex --index PATH [--out PATH]
+//!        reindex --index PATH --tsv PATH [--map symbol=KEY,name=KEY,isin=KEY]
+//!                [--fields FIELD,...] [--out PATH]
+//!
+//! An index written by an older build is refused by `bench-nucleo`,
+//! `search-server` and `bench-memory` (see `harness::prepared`). Given
+//! only `--index`, this reads it in whichever earlier format it is in and
+//! writes the same columns in the current one. With `--tsv`, it prepares
+//! the columns again from the corpus instead, for an index too old or too
+//! damaged to read: the symbol, name and ISIN, plus `--fields`, or the
+//! fields of the old index when it can still be read.
+//!
+//! The new index is written next to the old one and renamed over it (or
+//! over `--out`), so a process that has the old one mapped keeps reading
+//! it intact. An index already in the current format is left alone unless
+//! it is being prepared again.
+
+use harness::cli::{flag_value, parsed_flag};
+use harness::corpus::{load_corpus_mapped, FieldMap};
+use harness::prepared::{PreparedCorpus, FORMAT_VERSION};
+use std::env;
+use std::fs;
+use std::process;
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let index_path = flag_value(&args, "--index").unwrap_or_else(|| {
+        eprintln!("Usage: reindex --index PATH [--tsv PATH [--fields FIELD,...]] [--out PATH]");
+        process::exit(2);
+    });
+    let out_path = flag_value(&args, "--out").unwrap_or(index_path);
+    let old = fs::read(index_path)
+        .map_err(|e| e.to_string())
+        .and_then(|bytes| PreparedCorpus::upgrade(&bytes));
+
+    let (prepared, from) = match flag_value(&args, "--tsv") {
+        Some(tsv_path) => {
+            let field_map: FieldMap = parsed_flag(&args, "--map", FieldMap::default());
+            let corpus = load_corpus_mapped(tsv_path, &field_map).expect("Failed to read corpus");
+            let fields: Vec<String> = match (flag_value(&args, "--fields"), &old) {
+                (Some(fields), _) => fields.split(',').map(|f| f.trim().to_string()).collect(),
+                (None, Ok((old, _))) => old.fields().map(String::from).collect(),
+                (None, Err(_)) => Vec::new(),
+            };
+            let prepared = PreparedCorpus::builder(&corpus)
+                .fields(fields.iter().map(String::as_str))
+                .build();
+            (prepared, tsv_path.to_string())
+        }
+        None => match old {
+            Ok((_, version)) if version == FORMAT_VERSION && out_path == index_path => {
+                println!("{index_path} is already in format {FORMAT_VERSION}");
+                return;
+            }
+            Ok((prepared, version)) => (prepared, format!("format {version}")),
+            Err(e) => panic!("Failed to read index {index_path}: {e}; prepare it again with --tsv"),
+        },
+    };
+
+    let temp_path = format!("{out_path}.reindex");
+    fs::write(&temp_path, prepared.to_bytes()).expect("Failed to write index");
+    fs::rename(&temp_path, out_path).expect("Failed to replace index");
+    println!(
+        "Wrote {} in format {} from {}: {}",
+        out_path,
+        FORMAT_VERSION,
+        from,
+        prepared.summary()
+    );
+}
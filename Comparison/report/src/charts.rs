@@ -0,0 +1,229 @@
+//! SVG charts drawn with plotters: throughput per category and the
+//! distribution of per-query latency, one colour per run.
+//!
+//! The HTML report inlines them; `--charts DIR` also writes them to files
+//! that the Markdown report links, so they render in a PR description once
+//! the directory is committed or uploaded alongside it.
+
+use crate::html::PALETTE;
+use crate::{categories_of, Stats};
+use harness::results::ResultSet;
+use plotters::prelude::*;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+const SIZE: (u32, u32) = (720, 360);
+
+/// A chart and the file name it is written under.
+pub struct Chart {
+    pub file_name: &'static str,
+    pub title: &'static str,
+    pub svg: String,
+}
+
+/// Every chart the runs have data for; runs without timings have none.
+pub fn render(runs: &[ResultSet]) -> Vec<Chart> {
+    let mut charts = Vec::new();
+    if let Some(svg) = throughput_by_category(runs) {
+        charts.push(Chart {
+            file_name: "throughput.svg",
+            title: "Throughput by category",
+            svg,
+        });
+    }
+    if let Some(svg) = latency_distribution(runs) {
+        charts.push(Chart {
+            file_name: "latency.svg",
+            title: "Per-query latency distribution",
+            svg,
+        });
+    }
+    charts
+}
+
+/// Writes `charts` into `dir`, creating it if needed.
+pub fn write(charts: &[Chart], dir: &str) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+    for chart in charts {
+        fs::write(Path::new(dir).join(chart.file_name), &chart.svg)?;
+    }
+    Ok(())
+}
+
+fn colour(run: usize) -> RGBColor {
+    let hex = PALETTE[run % PALETTE.len()].trim_start_matches('#');
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).unwrap();
+    RGBColor(channel(0), channel(2), channel(4))
+}
+
+/// Queries per second in each category: timed queries over their summed
+/// median latency. Grouped bars, one group per category.
+fn throughput_by_category(runs: &[ResultSet]) -> Option<String> {
+    let categories = categories_of(runs);
+    let throughput: Vec<Vec<Option<f64>>> = runs
+        .iter()
+        .map(|run| {
+            categories
+                .iter()
+                .map(|c| {
+                    let timed = run
+                        .queries
+                        .iter()
+                        .filter(|q| q.category == *c && q.median_ms.is_some());
+                    let stats = Stats::of(timed);
+                    stats
+                        .latency_ms
+                        .filter(|ms| *ms > 0.0)
+                        .map(|ms| stats.queries as f64 * 1000.0 / ms)
+                })
+                .collect()
+        })
+        .collect();
+    let max = throughput
+        .iter()
+        .flatten()
+        .flatten()
+        .copied()
+        .fold(0.0, f64::max);
+    if max == 0.0 {
+        return None;
+    }
+
+    let bars = runs.len() as f64;
+    let mut svg = String::new();
+    {
+        let root = SVGBackend::with_string(&mut svg, SIZE).into_drawing_area();
+        root.fill(&WHITE).ok()?;
+        let mut chart = ChartBuilder::on(&root)
+            .caption("Throughput by category (queries/s)", ("sans-serif", 16))
+            .margin(12)
+            .x_label_area_size(40)
+            .y_label_area_size(60)
+            .build_cartesian_2d(
+                (0..categories.len() as i32 - 1).nested_coord(|_| 0.0..bars + 1.0),
+                0.0..max * 1.1,
+            )
+            .ok()?;
+        chart
+            .configure_mesh()
+            .disable_x_mesh()
+            .x_labels(categories.len())
+            .x_label_formatter(&|x| match x {
+                NestedValue::Category(i) => categories[*i as usize].clone(),
+                NestedValue::Value(..) => String::new(),
+            })
+            .y_label_formatter(&|qps| format!("{qps:.0}"))
+            .y_desc("queries/s")
+            .draw()
+            .ok()?;
+        for (r, series) in throughput.iter().enumerate() {
+            let style = colour(r).filled();
+            let left = r as f64 + 0.55;
+            chart
+                .draw_series(series.iter().enumerate().filter_map(|(c, qps)| {
+                    let c = c as i32;
+                    Some(Rectangle::new(
+                        [
+                            (NestedValue::Value(c, left), 0.0),
+                            (NestedValue::Value(c, left + 0.9), (*qps)?),
+                        ],
+                        style,
+                    ))
+                }))
+                .ok()?
+                .label(runs[r].label())
+                .legend(move |(x, y)| Rectangle::new([(x, y - 5), (x + 10, y + 5)], style));
+        }
+        chart
+            .configure_series_labels()
+            .background_style(WHITE.mix(0.8))
+            .border_style(BLACK.mix(0.3))
+            .draw()
+            .ok()?;
+        root.present().ok()?;
+    }
+    Some(svg)
+}
+
+/// Cumulative share of queries answered within each latency, on a log
+/// scale, one line per run. A run that is faster overall sits to the left;
+/// a long tail shows as a line that flattens late.
+fn latency_distribution(runs: &[ResultSet]) -> Option<String> {
+    let latencies: Vec<Vec<f64>> = runs
+        .iter()
+        .map(|run| {
+            let mut ms: Vec<f64> = run
+                .queries
+                .iter()
+                .filter_map(|q| q.median_ms)
+                .filter(|ms| *ms > 0.0)
+                .collect();
+            ms.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            ms
+        })
+        .collect();
+    let all = latencies.iter().flatten().copied();
+    let low = all.clone().fold(f64::INFINITY, f64::min);
+    let high = all.fold(0.0, f64::max);
+    if !low.is_finite() {
+        return None;
+    }
+    let (low, high) = if high > low {
+        (low, high)
+    } else {
+        (low / 2.0, high * 2.0)
+    };
+
+    let mut svg = String::new();
+    {
+        let root = SVGBackend::with_string(&mut svg, SIZE).into_drawing_area();
+        root.fill(&WHITE).ok()?;
+        let mut chart = ChartBuilder::on(&root)
+            .caption("Per-query median latency", ("sans-serif", 16))
+            .margin(12)
+            .x_label_area_size(40)
+            .y_label_area_size(60)
+            .build_cartesian_2d((low..high).log_scale(), 0.0..100.0)
+            .ok()?;
+        chart
+            .configure_mesh()
+            .x_label_formatter(&|ms| {
+                let label = format!("{ms:.3}");
+                label
+                    .trim_end_matches('0')
+                    .trim_end_matches('.')
+                    .to_string()
+            })
+            .y_label_formatter(&|pct| format!("{pct:.0}%"))
+            .x_desc("ms (log scale)")
+            .y_desc("queries answered within")
+            .draw()
+            .ok()?;
+        for (r, ms) in latencies.iter().enumerate() {
+            if ms.is_empty() {
+                continue;
+            }
+            let style = colour(r).stroke_width(2);
+            let count = ms.len() as f64;
+            let points = ms
+                .iter()
+                .enumerate()
+                .map(|(i, ms)| (*ms, (i + 1) as f64 * 100.0 / count));
+            chart
+                .draw_series(LineSeries::new(points, style))
+                .ok()?
+                .label(runs[r].label())
+                .legend(move |(x, y)| PathElement::new([(x, y), (x + 12, y)], style));
+        }
+        chart
+            .configure_series_labels()
+            .position(SeriesLabelPosition::LowerRight)
+            .background_style(WHITE.mix(0.8))
+            .border_style(BLACK.mix(0.3))
+            .draw()
+            .ok()?;
+        root.present().ok()?;
+    }
+    Some(svg)
+}
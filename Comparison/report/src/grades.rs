@@ -0,0 +1,81 @@
+//! `report grades`: where each relevance grade's judged results landed.
+//!
+//! NDCG says how good a ranking is but not what is wrong with it: a run
+//! that ranks acceptable results above ideal ones and a run that misses
+//! ideal results altogether can score alike. This writes a CSV with one
+//! row per run and grade (see `harness::judgments::GRADES`), counting that
+//! grade's judged results ranked first, elsewhere in the top `--k`, below
+//! the cutoff and not returned. Only queries the judgments cover count.
+
+use crate::{positional, read};
+use harness::cli::{flag_value, parsed_flag};
+use harness::judgments::{load_judgments, Judgment, GRADES, MAX_GRADE};
+use harness::metrics::GradeConfusion;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, Write};
+
+const HEADER: [&str; 11] = [
+    "backend", "run", "config", "revision", "grade", "label", "judged", "first", "top_k",
+    "below_k", "missed",
+];
+
+pub fn run(args: &[String]) {
+    let k: usize = parsed_flag(args, "--k", 10);
+    let paths = positional(args, 2, &["--judgments", "--k", "--out"]);
+    let judgments_path = flag_value(args, "--judgments");
+    assert!(
+        !paths.is_empty() && judgments_path.is_some(),
+        "Usage: report grades RESULTS.json... --judgments PATH [--k N] [--out PATH]"
+    );
+    let judgments = load_judgments(judgments_path.unwrap()).expect("Failed to read judgments");
+    let mut by_query: HashMap<(&str, &str), Vec<&Judgment>> = HashMap::new();
+    for j in &judgments {
+        by_query
+            .entry((j.query.as_str(), j.field.as_str()))
+            .or_default()
+            .push(j);
+    }
+
+    let out: Box<dyn Write> = match flag_value(args, "--out") {
+        Some(path) => Box::new(File::create(path).expect("Failed to create output file")),
+        None => Box::new(io::stdout()),
+    };
+    let mut writer = csv::Writer::from_writer(out);
+    writer.write_record(HEADER).expect("Failed to write table");
+    for path in paths {
+        let set = read(path);
+        let (config, revision) = match &set.fingerprint {
+            Some(fingerprint) => (
+                fingerprint.config_sha256()[..8].to_string(),
+                fingerprint.revision(),
+            ),
+            None => (String::new(), String::new()),
+        };
+        let mut confusion = GradeConfusion::default();
+        for q in &set.queries {
+            if let Some(judged) = by_query.get(&(q.query.as_str(), q.field.as_str())) {
+                confusion.add(q, judged, k);
+            }
+        }
+        for grade in (0..=MAX_GRADE).rev() {
+            let g = usize::from(grade);
+            writer
+                .write_record([
+                    set.backend.clone(),
+                    set.run.clone(),
+                    config.clone(),
+                    revision.clone(),
+                    grade.to_string(),
+                    GRADES[g].to_string(),
+                    confusion.judged(grade).to_string(),
+                    confusion.first[g].to_string(),
+                    confusion.top_k[g].to_string(),
+                    confusion.below_k[g].to_string(),
+                    confusion.missed[g].to_string(),
+                ])
+                .expect("Failed to write table");
+        }
+    }
+    writer.flush().expect("Failed to write table");
+}
@@ -0,0 +1,780 @@
+//! Self-contained HTML rendering: inline styles, script and SVG charts, so
+//! the page works offline and can be attached to a PR or CI run as is.
+
+use crate::charts::Chart;
+use crate::wins::{attribute, tally};
+use crate::{
+    capability_notes, categories_of, footprints, mib, mixed_configs, mixed_corpora, provenance,
+    rechecked, startup_phases, winner, Options, Regressions, Stats,
+};
+use harness::results::{QueryResult, ResultSet};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+
+/// Bar colours, one per run, reused when there are more runs.
+pub(crate) const PALETTE: [&str; 8] = [
+    "#4e79a7", "#f28e2b", "#59a14f", "#e15759", "#76b7b2", "#edc948", "#b07aa1", "#9c755f",
+];
+
+const STYLE: &str = "
+body { font: 14px/1.4 -apple-system, system-ui, sans-serif; margin: 2em; color: #222; }
+table { border-collapse: collapse; margin: 1em 0; }
+th, td { border: 1px solid #ddd; padding: 4px 8px; text-align: left; vertical-align: top; }
+th { background: #f4f4f4; }
+table.sortable th { cursor: pointer; user-select: none; }
+table.sortable th:after { content: ' ⇅'; color: #aaa; }
+td.num { text-align: right; font-variant-numeric: tabular-nums; }
+td.hit { color: #2a7d2a; } td.miss { color: #c0392b; }
+.winner { font-weight: bold; }
+.note { background: #fff6d5; padding: 0.5em 1em; border-left: 4px solid #e6b800; }
+.legend span { display: inline-block; margin-right: 1em; }
+.legend i { display: inline-block; width: 12px; height: 12px; margin-right: 4px; vertical-align: middle; }
+.charts svg { margin: 0 2em 1em 0; }
+details ol { margin: 0.3em 0; padding-left: 1.5em; }
+details .run { margin: 0.3em 0 0.3em 0.5em; }
+li.expected { background: #e3f4e3; }
+mark { background: #ffe08a; padding: 0; }
+.score { color: #888; font-size: 90%; }
+";
+
+/// Sorts a table by the clicked column: numerically when every value
+/// parses, as text otherwise. Cells may carry a `data-sort` value.
+const SCRIPT: &str = "
+document.querySelectorAll('table.sortable th').forEach(function (th, col) {
+  th.addEventListener('click', function () {
+    var body = th.closest('table').tBodies[0];
+    var rows = Array.from(body.rows);
+    var key = function (row) {
+      var cell = row.cells[col];
+      return cell.dataset.sort !== undefined ? cell.dataset.sort : cell.textContent.trim();
+    };
+    var numeric = rows.every(function (r) { var k = key(r); return k === '' || !isNaN(parseFloat(k)); });
+    var dir = th.dataset.dir === 'asc' ? -1 : 1;
+    th.dataset.dir = dir === 1 ? 'asc' : 'desc';
+    rows.sort(function (a, b) {
+      var x = key(a), y = key(b);
+      if (numeric) { x = x === '' ? -Infinity : parseFloat(x); y = y === '' ? -Infinity : parseFloat(y); }
+      return x < y ? -dir : x > y ? dir : 0;
+    });
+    rows.forEach(function (r) { body.appendChild(r); });
+  });
+});
+";
+
+pub fn render(runs: &[ResultSet], charts: &[Chart], options: &Options) -> String {
+    let mut out = String::new();
+    if mixed_corpora(runs) {
+        writeln!(out, "<p class=\"note\">The runs used different corpora, so their numbers are not directly comparable.</p>").unwrap();
+    }
+    if mixed_configs(runs) {
+        writeln!(out, "<p class=\"note\">The runs used different matcher configurations (see the Config column).</p>").unwrap();
+    }
+
+    let notes = capability_notes(runs);
+    if !notes.is_empty() {
+        writeln!(
+            out,
+            "<p class=\"note\">Not every backend supports every mode: {}.</p>",
+            escape(&notes.join("; "))
+        )
+        .unwrap();
+    }
+
+    render_summary(&mut out, runs);
+    render_startup(&mut out, runs);
+    render_memory(&mut out, runs);
+    render_charts(&mut out, runs);
+    if !charts.is_empty() {
+        writeln!(out, "<h2>Latency</h2>").unwrap();
+        writeln!(out, "<div class=\"charts\">").unwrap();
+        for chart in charts {
+            out.push_str(&chart.svg);
+            out.push('\n');
+        }
+        writeln!(out, "</div>").unwrap();
+    }
+    if runs.len() > 1 {
+        render_winners(&mut out, runs);
+        render_wins(&mut out, runs);
+        render_regressions(&mut out, runs, options);
+    }
+    render_rechecks(&mut out, runs);
+    render_queries(&mut out, runs);
+    page("Fuzzy matching comparison", &out)
+}
+
+/// Wraps `body` in a complete page with the shared styles and script.
+pub fn page(title: &str, body: &str) -> String {
+    let title = escape(title);
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\"><head><meta charset=\"utf-8\">\n<title>{title}</title>\n<style>{STYLE}</style></head><body>\n<h1>{title}</h1>\n{body}<script>{SCRIPT}</script>\n</body></html>\n"
+    )
+}
+
+/// A colour key for `labels`, matching the chart colours by position.
+pub fn legend(labels: &[String]) -> String {
+    let mut html = String::from("<p class=\"legend\">");
+    for (i, label) in labels.iter().enumerate() {
+        write!(
+            html,
+            "<span><i style=\"background:{}\"></i>{}</span>",
+            PALETTE[i % PALETTE.len()],
+            escape(label)
+        )
+        .unwrap();
+    }
+    html.push_str("</p>\n");
+    html
+}
+
+fn render_summary(out: &mut String, runs: &[ResultSet]) {
+    writeln!(out, "<h2>Summary</h2>").unwrap();
+    writeln!(out, "<table class=\"sortable\"><thead><tr><th>Run</th><th>Corpus</th><th>Revision</th><th>Config</th><th>Queries</th><th>Ground truth</th><th>Total median (ms)</th></tr></thead><tbody>").unwrap();
+    for run in runs {
+        let stats = Stats::of(&run.queries);
+        let (revision, config) = provenance(run);
+        writeln!(
+            out,
+            "<tr><td>{}</td><td>{}</td><td><code>{}</code></td><td><code>{}</code></td><td class=\"num\">{}</td><td class=\"num\" data-sort=\"{}\">{}</td><td class=\"num\">{}</td></tr>",
+            escape(&run.label()),
+            escape(run.corpus.as_deref().unwrap_or("—")),
+            escape(&revision),
+            config,
+            stats.queries,
+            sort_key(stats.hit_rate()),
+            stats.hit_rate_cell(),
+            stats.latency_cell()
+        )
+        .unwrap();
+    }
+    writeln!(out, "</tbody></table>").unwrap();
+}
+
+fn render_startup(out: &mut String, runs: &[ResultSet]) {
+    let phases = startup_phases(runs);
+    if phases.is_empty() {
+        return;
+    }
+    writeln!(out, "<h2>Startup and steady state</h2>").unwrap();
+    let mut header = "<table><thead><tr><th>Run</th>".to_string();
+    for phase in &phases {
+        write!(header, "<th>{} (ms)</th>", escape(phase)).unwrap();
+    }
+    writeln!(out, "{header}<th>Startup (ms)</th><th>Steady pass (ms)</th><th>Per query (ms)</th></tr></thead><tbody>").unwrap();
+    let cell = |value: Option<String>| {
+        format!(
+            "<td class=\"num\">{}</td>",
+            value.unwrap_or_else(|| "—".to_string())
+        )
+    };
+    for run in runs {
+        let mut row = format!("<tr><td>{}</td>", escape(&run.label()));
+        for phase in &phases {
+            let ms = run.startup.as_ref().and_then(|s| s.phase_ms(phase));
+            row.push_str(&cell(ms.map(|ms| format!("{ms:.1}"))));
+        }
+        row.push_str(&cell(
+            run.startup.as_ref().map(|s| format!("{:.1}", s.total_ms())),
+        ));
+        let steady = run.steady_state.as_ref();
+        row.push_str(&cell(steady.map(|s| format!("{:.1}", s.pass_median_ms))));
+        row.push_str(&cell(steady.map(|s| format!("{:.3}", s.per_query_ms()))));
+        writeln!(out, "{row}</tr>").unwrap();
+    }
+    writeln!(out, "</tbody></table>").unwrap();
+}
+
+fn render_memory(out: &mut String, runs: &[ResultSet]) {
+    let rows = footprints(runs);
+    if rows.is_empty() {
+        return;
+    }
+    writeln!(out, "<h2>Memory footprint</h2>").unwrap();
+    writeln!(out, "<table><thead><tr><th>Run</th><th>Candidates</th><th>Held as</th><th>Heap (MiB)</th><th>Resident (MiB)</th><th>Heap per candidate (B)</th></tr></thead><tbody>").unwrap();
+    for (run, footprint) in rows {
+        writeln!(
+            out,
+            "<tr><td>{}</td><td class=\"num\">{}</td><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{:.1}</td></tr>",
+            escape(&run.label()),
+            footprint.candidates,
+            escape(&footprint.representation),
+            mib(footprint.heap_bytes),
+            footprint.resident_bytes.map_or("—".to_string(), mib),
+            footprint.heap_bytes_per_candidate()
+        )
+        .unwrap();
+    }
+    writeln!(out, "</tbody></table>").unwrap();
+}
+
+fn render_charts(out: &mut String, runs: &[ResultSet]) {
+    let categories = categories_of(runs);
+    let stats: Vec<Vec<Stats>> = runs
+        .iter()
+        .map(|run| {
+            categories
+                .iter()
+                .map(|c| Stats::of_category(run, c))
+                .collect()
+        })
+        .collect();
+    let latency: Vec<Vec<Option<f64>>> = stats
+        .iter()
+        .map(|s| s.iter().map(|s| s.latency_ms).collect())
+        .collect();
+    let hit_rate: Vec<Vec<Option<f64>>> = stats
+        .iter()
+        .map(|s| s.iter().map(|s| s.hit_rate().map(|r| r * 100.0)).collect())
+        .collect();
+    let recorded = |series: &[Vec<Option<f64>>]| series.iter().flatten().any(Option::is_some);
+    if !recorded(&latency) && !recorded(&hit_rate) {
+        return;
+    }
+
+    writeln!(out, "<h2>By category</h2>").unwrap();
+    let labels: Vec<String> = runs.iter().map(ResultSet::label).collect();
+    out.push_str(&legend(&labels));
+    writeln!(out, "<div class=\"charts\">").unwrap();
+    if recorded(&latency) {
+        out.push_str(&bar_chart(
+            "Summed median latency (ms)",
+            &categories,
+            &latency,
+            None,
+        ));
+    }
+    if recorded(&hit_rate) {
+        out.push_str(&bar_chart(
+            "Ground-truth hit rate (%)",
+            &categories,
+            &hit_rate,
+            Some(100.0),
+        ));
+    }
+    writeln!(out, "</div>").unwrap();
+}
+
+/// A grouped vertical bar chart: one group per category, one bar per run.
+/// The y axis runs from 0 to `fixed_max`, so percentages keep a 0–100
+/// scale, or else to a round number above the largest value.
+fn bar_chart(
+    title: &str,
+    categories: &[String],
+    series: &[Vec<Option<f64>>],
+    fixed_max: Option<f64>,
+) -> String {
+    const PLOT_HEIGHT: f64 = 180.0;
+    const BAR_WIDTH: f64 = 12.0;
+    const GROUP_GAP: f64 = 14.0;
+    const LEFT: f64 = 48.0;
+    const TOP: f64 = 28.0;
+    const BOTTOM: f64 = 90.0;
+
+    let scale_max = fixed_max.unwrap_or_else(|| {
+        nice_ceiling(
+            series
+                .iter()
+                .flatten()
+                .flatten()
+                .copied()
+                .fold(0.0, f64::max),
+        )
+    });
+    let group_width = series.len() as f64 * BAR_WIDTH + GROUP_GAP;
+    let width = LEFT + categories.len() as f64 * group_width + 10.0;
+    let height = TOP + PLOT_HEIGHT + BOTTOM;
+    let y = |value: f64| TOP + PLOT_HEIGHT * (1.0 - value / scale_max);
+
+    let mut svg = String::new();
+    writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width:.0}\" height=\"{height:.0}\" font-size=\"11\">"
+    )
+    .unwrap();
+    writeln!(
+        svg,
+        "<text x=\"{LEFT}\" y=\"16\" font-weight=\"bold\">{}</text>",
+        escape(title)
+    )
+    .unwrap();
+    for step in 0..=4 {
+        let value = scale_max * step as f64 / 4.0;
+        let y = y(value);
+        writeln!(
+            svg,
+            "<line x1=\"{LEFT}\" x2=\"{:.1}\" y1=\"{y:.1}\" y2=\"{y:.1}\" stroke=\"#e5e5e5\"/><text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"end\">{}</text>",
+            width - 10.0,
+            LEFT - 4.0,
+            y + 4.0,
+            format_axis(value)
+        )
+        .unwrap();
+    }
+    for (ci, category) in categories.iter().enumerate() {
+        let group_x = LEFT + ci as f64 * group_width + GROUP_GAP / 2.0;
+        for (ri, values) in series.iter().enumerate() {
+            let Some(value) = values[ci] else { continue };
+            let top = y(value);
+            writeln!(
+                svg,
+                "<rect x=\"{:.1}\" y=\"{top:.1}\" width=\"{BAR_WIDTH}\" height=\"{:.1}\" fill=\"{}\"><title>{}: {value:.2}</title></rect>",
+                group_x + ri as f64 * BAR_WIDTH,
+                TOP + PLOT_HEIGHT - top,
+                PALETTE[ri % PALETTE.len()],
+                escape(category)
+            )
+            .unwrap();
+        }
+        let label_x = group_x + series.len() as f64 * BAR_WIDTH / 2.0;
+        let label_y = TOP + PLOT_HEIGHT + 12.0;
+        writeln!(
+            svg,
+            "<text x=\"{label_x:.1}\" y=\"{label_y:.1}\" text-anchor=\"end\" transform=\"rotate(-40 {label_x:.1} {label_y:.1})\">{}</text>",
+            escape(category)
+        )
+        .unwrap();
+    }
+    writeln!(svg, "</svg>").unwrap();
+    svg
+}
+
+/// A line chart with one line per series over shared x labels, such as
+/// one line per category across runs. Gaps in a series break its line.
+pub fn line_chart(
+    title: &str,
+    x_labels: &[String],
+    series: &[Vec<Option<f64>>],
+    fixed_max: Option<f64>,
+) -> String {
+    const PLOT_HEIGHT: f64 = 200.0;
+    const STEP: f64 = 48.0;
+    const LEFT: f64 = 48.0;
+    const TOP: f64 = 28.0;
+    const BOTTOM: f64 = 90.0;
+
+    let scale_max = fixed_max.unwrap_or_else(|| {
+        nice_ceiling(
+            series
+                .iter()
+                .flatten()
+                .flatten()
+                .copied()
+                .fold(0.0, f64::max),
+        )
+    });
+    let width = LEFT + x_labels.len().max(2) as f64 * STEP;
+    let height = TOP + PLOT_HEIGHT + BOTTOM;
+    let x = |i: usize| LEFT + STEP / 2.0 + i as f64 * STEP;
+    let y = |value: f64| TOP + PLOT_HEIGHT * (1.0 - value / scale_max);
+
+    let mut svg = String::new();
+    writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width:.0}\" height=\"{height:.0}\" font-size=\"11\">"
+    )
+    .unwrap();
+    writeln!(
+        svg,
+        "<text x=\"{LEFT}\" y=\"16\" font-weight=\"bold\">{}</text>",
+        escape(title)
+    )
+    .unwrap();
+    for step in 0..=4 {
+        let value = scale_max * step as f64 / 4.0;
+        let y = y(value);
+        writeln!(
+            svg,
+            "<line x1=\"{LEFT}\" x2=\"{width:.1}\" y1=\"{y:.1}\" y2=\"{y:.1}\" stroke=\"#e5e5e5\"/><text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"end\">{}</text>",
+            LEFT - 4.0,
+            y + 4.0,
+            format_axis(value)
+        )
+        .unwrap();
+    }
+    for (i, label) in x_labels.iter().enumerate() {
+        let (label_x, label_y) = (x(i), TOP + PLOT_HEIGHT + 12.0);
+        writeln!(
+            svg,
+            "<text x=\"{label_x:.1}\" y=\"{label_y:.1}\" text-anchor=\"end\" transform=\"rotate(-40 {label_x:.1} {label_y:.1})\">{}</text>",
+            escape(label)
+        )
+        .unwrap();
+    }
+    for (si, values) in series.iter().enumerate() {
+        let colour = PALETTE[si % PALETTE.len()];
+        let mut segment: Vec<String> = Vec::new();
+        let mut segments = Vec::new();
+        for (i, value) in values.iter().enumerate() {
+            match value {
+                Some(value) => {
+                    segment.push(format!("{:.1},{:.1}", x(i), y(*value)));
+                    writeln!(
+                        svg,
+                        "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"3\" fill=\"{colour}\"><title>{}: {value:.2}</title></circle>",
+                        x(i),
+                        y(*value),
+                        escape(&x_labels[i])
+                    )
+                    .unwrap();
+                }
+                None => segments.push(std::mem::take(&mut segment)),
+            }
+        }
+        segments.push(segment);
+        for points in segments.iter().filter(|p| p.len() > 1) {
+            writeln!(
+                svg,
+                "<polyline points=\"{}\" fill=\"none\" stroke=\"{colour}\" stroke-width=\"2\"/>",
+                points.join(" ")
+            )
+            .unwrap();
+        }
+    }
+    writeln!(svg, "</svg>").unwrap();
+    svg
+}
+
+/// The next 1, 2 or 5 times a power of ten at or above `value`.
+fn nice_ceiling(value: f64) -> f64 {
+    if value <= 0.0 {
+        return 1.0;
+    }
+    let magnitude = 10f64.powf(value.log10().floor());
+    [1.0, 2.0, 5.0, 10.0]
+        .into_iter()
+        .map(|m| m * magnitude)
+        .find(|&v| v >= value)
+        .unwrap()
+}
+
+fn format_axis(value: f64) -> String {
+    if value >= 10.0 || value == 0.0 {
+        format!("{value:.0}")
+    } else {
+        format!("{value:.2}")
+    }
+}
+
+fn render_winners(out: &mut String, runs: &[ResultSet]) {
+    writeln!(out, "<h2>Per-category winners</h2>").unwrap();
+    write!(out, "<table><thead><tr><th>Category</th>").unwrap();
+    for run in runs {
+        write!(out, "<th>{}</th>", escape(&run.label())).unwrap();
+    }
+    writeln!(out, "<th>Winner</th></tr></thead><tbody>").unwrap();
+    for category in categories_of(runs) {
+        let stats: Vec<Stats> = runs
+            .iter()
+            .map(|run| Stats::of_category(run, &category))
+            .collect();
+        let best = winner(&stats);
+        write!(out, "<tr><td>{}</td>", escape(&category)).unwrap();
+        for (i, s) in stats.iter().enumerate() {
+            let class = if best == Some(i) { "num winner" } else { "num" };
+            write!(out, "<td class=\"{class}\">{}</td>", s.cell()).unwrap();
+        }
+        let name = best
+            .map(|i| escape(&runs[i].label()))
+            .unwrap_or_else(|| "—".to_string());
+        writeln!(out, "<td class=\"winner\">{name}</td></tr>").unwrap();
+    }
+    writeln!(out, "</tbody></table>").unwrap();
+}
+
+fn render_wins(out: &mut String, runs: &[ResultSet]) {
+    let tallies = tally(runs, &attribute(runs));
+    if tallies.is_empty() {
+        return;
+    }
+    writeln!(out, "<h2>Queries won (quality / latency)</h2>").unwrap();
+    write!(out, "<table><thead><tr><th>Category</th>").unwrap();
+    for run in runs {
+        write!(out, "<th>{}</th>", escape(&run.label())).unwrap();
+    }
+    writeln!(out, "<th>Ties</th></tr></thead><tbody>").unwrap();
+    for t in &tallies {
+        write!(out, "<tr><td>{}</td>", escape(&t.category)).unwrap();
+        for i in 0..runs.len() {
+            write!(out, "<td class=\"num\">{}</td>", t.cell(i)).unwrap();
+        }
+        writeln!(out, "<td class=\"num\">{}</td></tr>", t.ties_cell()).unwrap();
+    }
+    writeln!(out, "</tbody></table>").unwrap();
+}
+
+fn render_rechecks(out: &mut String, runs: &[ResultSet]) {
+    let rows = rechecked(runs);
+    if rows.is_empty() {
+        return;
+    }
+    writeln!(out, "<h2>Slow queries, re-checked</h2>").unwrap();
+    writeln!(out, "<table><thead><tr><th>Run</th><th>Query</th><th>Field</th><th>Category</th><th>First pass (ms)</th><th>Re-checked (ms)</th><th>p90 (ms)</th><th>p99 (ms)</th><th>Std dev (ms)</th><th>Flamegraph</th></tr></thead><tbody>").unwrap();
+    for (run, q, recheck) in rows {
+        let first = q.median_ms.map_or("—".to_string(), |ms| format!("{ms:.3}"));
+        let flamegraph = recheck.flamegraph.as_ref().map_or("—".to_string(), |path| {
+            format!("<a href=\"{}\">svg</a>", escape(path))
+        });
+        writeln!(
+            out,
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{:.3}</td><td class=\"num\">{:.3}</td><td class=\"num\">{:.3}</td><td class=\"num\">{:.3}</td><td>{}</td></tr>",
+            escape(&run.label()),
+            escape(&q.query),
+            escape(&q.field),
+            escape(&q.category),
+            first,
+            recheck.median_ms,
+            recheck.p90_ms,
+            recheck.p99_ms,
+            recheck.stddev_ms,
+            flamegraph
+        )
+        .unwrap();
+    }
+    writeln!(out, "</tbody></table>").unwrap();
+}
+
+fn render_regressions(out: &mut String, runs: &[ResultSet], options: &Options) {
+    let baseline = &runs[0];
+    writeln!(
+        out,
+        "<h2>Regressions against {}</h2>",
+        escape(&baseline.label())
+    )
+    .unwrap();
+    for run in &runs[1..] {
+        writeln!(out, "<h3>{}</h3>", escape(&run.label())).unwrap();
+        let regressions = Regressions::find(baseline, run, options.threshold);
+        if regressions.shared == 0 {
+            writeln!(out, "<p>No queries in common with the baseline.</p>").unwrap();
+            continue;
+        }
+        if let Some(line) = regressions.latency_line() {
+            writeln!(out, "<p>{}</p>", escape(&line)).unwrap();
+        }
+        if regressions.is_empty() {
+            writeln!(
+                out,
+                "<p>No regressions across {} shared queries.</p>",
+                regressions.shared
+            )
+            .unwrap();
+            continue;
+        }
+        if !regressions.categories.is_empty() {
+            writeln!(
+                out,
+                "<table><thead><tr><th>Category</th><th>Regression</th></tr></thead><tbody>"
+            )
+            .unwrap();
+            for (category, problems) in &regressions.categories {
+                writeln!(
+                    out,
+                    "<tr><td>{}</td><td>{}</td></tr>",
+                    escape(category),
+                    escape(&problems.join("; "))
+                )
+                .unwrap();
+            }
+            writeln!(out, "</tbody></table>").unwrap();
+        }
+        if !regressions.lost.is_empty() {
+            writeln!(
+                out,
+                "<p>Queries that no longer find their expected result ({}):</p><ul>",
+                regressions.lost.len()
+            )
+            .unwrap();
+            for q in regressions.lost.iter().take(options.max_queries) {
+                writeln!(
+                    out,
+                    "<li><code>{}</code> ({}, {}): expected <code>{}</code></li>",
+                    escape(&q.query),
+                    escape(&q.field),
+                    escape(&q.category),
+                    escape(&q.expected_name)
+                )
+                .unwrap();
+            }
+            writeln!(out, "</ul>").unwrap();
+        }
+        if !regressions.slowed.is_empty() {
+            writeln!(
+                out,
+                "<p>Queries more than {}% slower ({}):</p><ul>",
+                options.threshold,
+                regressions.slowed.len()
+            )
+            .unwrap();
+            for (q, a, b) in regressions.slowed.iter().take(options.max_queries) {
+                writeln!(
+                    out,
+                    "<li><code>{}</code> ({}, {}): {a:.2}ms → {b:.2}ms</li>",
+                    escape(&q.query),
+                    escape(&q.field),
+                    escape(&q.category)
+                )
+                .unwrap();
+            }
+            writeln!(out, "</ul>").unwrap();
+        }
+    }
+}
+
+/// Every query once, with each run's latency and verdict, and its result
+/// lists folded into the query cell.
+fn render_queries(out: &mut String, runs: &[ResultSet]) {
+    let mut keys: Vec<(&str, &str)> = Vec::new();
+    let mut by_run: Vec<HashMap<(&str, &str), &QueryResult>> = Vec::new();
+    for run in runs {
+        let mut index = HashMap::new();
+        for q in &run.queries {
+            let key = (q.query.as_str(), q.field.as_str());
+            if index.insert(key, q).is_none() && !keys.contains(&key) {
+                keys.push(key);
+            }
+        }
+        by_run.push(index);
+    }
+
+    writeln!(out, "<h2>Queries</h2>").unwrap();
+    write!(out, "<table class=\"sortable\"><thead><tr><th>Query</th><th>Field</th><th>Category</th><th>Expected</th>").unwrap();
+    for run in runs {
+        let label = escape(&run.label());
+        write!(out, "<th>{label} ms</th><th>{label} found</th>").unwrap();
+    }
+    writeln!(out, "</tr></thead><tbody>").unwrap();
+
+    for key in keys {
+        let first = by_run.iter().find_map(|index| index.get(&key)).unwrap();
+        write!(
+            out,
+            "<tr><td data-sort=\"{}\"><details><summary>{}</summary>",
+            escape(&first.query),
+            escape(&first.query)
+        )
+        .unwrap();
+        for (run, index) in runs.iter().zip(&by_run) {
+            let Some(results) = index.get(&key).and_then(|q| q.results.as_ref()) else {
+                continue;
+            };
+            write!(
+                out,
+                "<div class=\"run\"><strong>{}</strong><ol>",
+                escape(&run.label())
+            )
+            .unwrap();
+            let expected = first.expected_name.to_lowercase();
+            for hit in results {
+                let is_expected =
+                    first.found().is_some() && hit.name.to_lowercase().contains(&expected);
+                let (symbol, name) = if first.field == "symbol" {
+                    (highlight(&first.query, &hit.symbol), escape(&hit.name))
+                } else {
+                    (escape(&hit.symbol), highlight(&first.query, &hit.name))
+                };
+                let score = hit
+                    .score
+                    .map(|s| format!(" <span class=\"score\">{s}</span>"))
+                    .unwrap_or_default();
+                write!(
+                    out,
+                    "<li{}>{symbol} — {name}{score}</li>",
+                    if is_expected {
+                        " class=\"expected\""
+                    } else {
+                        ""
+                    }
+                )
+                .unwrap();
+            }
+            write!(out, "</ol></div>").unwrap();
+        }
+        write!(
+            out,
+            "</details></td><td>{}</td><td>{}</td><td>{}</td>",
+            escape(&first.field),
+            escape(&first.category),
+            escape(&first.expected_name)
+        )
+        .unwrap();
+        for index in &by_run {
+            let q = index.get(&key);
+            let ms = q.and_then(|q| q.median_ms);
+            let (class, verdict) = match q.and_then(|q| q.found()) {
+                Some(true) => ("hit", "✓"),
+                Some(false) => ("miss", "✗"),
+                None => ("", ""),
+            };
+            write!(
+                out,
+                "<td class=\"num\" data-sort=\"{}\">{}</td><td class=\"{class}\">{verdict}</td>",
+                sort_key(ms),
+                ms.map(|ms| format!("{ms:.2}")).unwrap_or_default()
+            )
+            .unwrap();
+        }
+        writeln!(out, "</tr>").unwrap();
+    }
+    writeln!(out, "</tbody></table>").unwrap();
+}
+
+/// Marks where `query` occurs in `text`: as a case-insensitive substring
+/// when it does, otherwise as the first subsequence of its non-space
+/// characters, as a fuzzy matcher would pick them. Unmatched text is left
+/// plain.
+fn highlight(query: &str, text: &str) -> String {
+    let fold = |c: char| c.to_lowercase().next().unwrap_or(c);
+    let text_chars: Vec<char> = text.chars().collect();
+    let needle: Vec<char> = query.chars().map(fold).collect();
+    let mut marked = vec![false; text_chars.len()];
+
+    let substring = (needle.len() <= text_chars.len())
+        .then(|| {
+            (0..=text_chars.len() - needle.len()).find(|&start| {
+                needle
+                    .iter()
+                    .enumerate()
+                    .all(|(i, &c)| fold(text_chars[start + i]) == c)
+            })
+        })
+        .flatten();
+    if let Some(start) = substring.filter(|_| !needle.is_empty()) {
+        marked[start..start + needle.len()].fill(true);
+    } else {
+        let mut pending = needle.iter().filter(|c| !c.is_whitespace()).peekable();
+        for (i, &c) in text_chars.iter().enumerate() {
+            if pending.peek().is_some_and(|&&q| q == fold(c)) {
+                marked[i] = true;
+                pending.next();
+            }
+        }
+        if pending.peek().is_some() {
+            marked.fill(false);
+        }
+    }
+
+    let mut html = String::new();
+    let mut open = false;
+    for (c, mark) in text_chars.iter().zip(marked) {
+        if mark != open {
+            html.push_str(if mark { "<mark>" } else { "</mark>" });
+            open = mark;
+        }
+        html.push_str(&escape(&c.to_string()));
+    }
+    if open {
+        html.push_str("</mark>");
+    }
+    html
+}
+
+fn sort_key(value: Option<f64>) -> String {
+    value.map(|v| format!("{v}")).unwrap_or_default()
+}
+
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
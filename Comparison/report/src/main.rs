@@ -0,0 +1,509 @@
+//! Renders a comparison of result files, for pasting into a PR or sharing.
+//!
+//! Usage: report RESULTS.json... [--baseline RESULTS.json] [--threshold PCT]
+//!               [--max-queries N] [--html] [--charts DIR] [--out PATH]
+//!        report record --db PATH RESULTS.json... [--at UNIX_SECONDS]
+//!        report trends --db PATH [--backend NAME] [--corpus NAME|SHA256]
+//!                      [--last N] [--threshold PCT] [--html] [--out PATH]
+//!        report tradeoff RESULTS.json... [--judgments PATH] [--k N] [--out PATH]
+//!        report tidy RESULTS.json... [--judgments PATH] [--k N] [--out PATH]
+//!        report grades RESULTS.json... --judgments PATH [--k N] [--out PATH]
+//!        report winners RESULTS.json RESULTS.json... [--out PATH]
+//!
+//! Reads files in the unified results schema (see `harness::results`), as
+//! written by `bench-nucleo --results` and `run-quality.py --results-dir`,
+//! and prints a summary table per run, the winner of each query category,
+//! and what regressed against the baseline: categories whose latency grew
+//! by more than `--threshold` percent (default 10) or whose ground-truth
+//! hit rate fell, and the individual queries behind them. Each category
+//! also counts the queries each run won outright, on quality and on
+//! latency (see `wins`). Latency changes
+//! carry a bootstrap confidence interval over the shared queries (see
+//! `harness::significance`), and those within noise say so. The baseline is
+//! `--baseline` when given and the first file otherwise. Runs that carry a
+//! fingerprint show their revision and config hash, and the report warns
+//! when the configurations differ. Backends lacking a capability the
+//! numbers depend on, such as fzf's missing scores, are noted up front so
+//! their blank cells and misses read as expected.
+//!
+//! The report is Markdown by default. `--html` writes a self-contained page
+//! instead, adding latency and hit-rate charts and a sortable table of every
+//! query with its result lists. Both formats include throughput-by-category
+//! and latency-distribution charts when the runs were timed: the page
+//! inlines them, and `--charts DIR` writes them as SVG files that the
+//! Markdown links to.
+//!
+//! `record` appends result files to a SQLite history (see `harness::store`),
+//! stamped with the current time or `--at`. `trends` reads the last `--last`
+//! runs (default 30) back and tabulates, or with `--html` charts, each
+//! category's latency and hit rate over time, flagging categories that
+//! drifted by more than `--threshold` across the window.
+//!
+//! `tradeoff` joins benchmark and quality files of the same backend and run
+//! label into a CSV of p95 latency against mean NDCG@`--k` (default 10) per
+//! category, graded by `--judgments` where given and by expected names
+//! otherwise.
+//!
+//! `tidy` writes every per-query number of the given files as long-format
+//! CSV, one row per backend, run, query and metric, for analysis tools.
+//!
+//! `grades` counts, per run and relevance grade, the judged results ranked
+//! first, in the top `--k`, below it and not returned.
+//!
+//! `winners` writes one CSV row per query of the first file, naming the run
+//! that won it on quality and the one that won it on latency, with the
+//! expected result's rank in each run.
+
+mod charts;
+mod grades;
+mod html;
+mod markdown;
+mod tidy;
+mod tradeoff;
+mod trends;
+mod wins;
+
+use harness::capabilities::{self, Capabilities};
+use harness::cli::{flag_value, has_flag, parsed_flag};
+use harness::queries::CATEGORY_ORDER;
+use harness::results::{Footprint, QueryResult, Recheck, ResultSet};
+use harness::significance::Change;
+use harness::store::{self, Store};
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+
+/// Latency changes below this are timer noise, whatever the percentage.
+const MIN_LATENCY_DELTA_MS: f64 = 0.05;
+
+/// Aggregates over a set of query results.
+#[derive(Default)]
+struct Stats {
+    queries: usize,
+    /// Queries with a ground-truth verdict, and how many of those passed.
+    judged: usize,
+    found: usize,
+    /// Summed median latency, when any query was timed.
+    latency_ms: Option<f64>,
+}
+
+impl Stats {
+    fn of<'a>(results: impl IntoIterator<Item = &'a QueryResult>) -> Stats {
+        let mut stats = Stats::default();
+        for result in results {
+            stats.queries += 1;
+            if let Some(found) = result.found() {
+                stats.judged += 1;
+                stats.found += usize::from(found);
+            }
+            if let Some(ms) = result.median_ms {
+                *stats.latency_ms.get_or_insert(0.0) += ms;
+            }
+        }
+        stats
+    }
+
+    /// `run`'s queries in `category`.
+    fn of_category(run: &ResultSet, category: &str) -> Stats {
+        Stats::of(run.queries.iter().filter(|q| q.category == category))
+    }
+
+    fn hit_rate(&self) -> Option<f64> {
+        (self.judged > 0).then(|| self.found as f64 / self.judged as f64)
+    }
+
+    fn hit_rate_cell(&self) -> String {
+        match self.hit_rate() {
+            Some(rate) => format!("{:.1}% ({}/{})", rate * 100.0, self.found, self.judged),
+            None => "—".to_string(),
+        }
+    }
+
+    fn latency_cell(&self) -> String {
+        self.latency_ms
+            .map(|ms| format!("{ms:.2}"))
+            .unwrap_or_else(|| "—".to_string())
+    }
+
+    /// Hit rate and latency together, or whichever was recorded.
+    fn cell(&self) -> String {
+        match (self.hit_rate(), self.latency_ms) {
+            (Some(_), Some(_)) => format!("{} · {}ms", self.hit_rate_cell(), self.latency_cell()),
+            (Some(_), None) => self.hit_rate_cell(),
+            (None, Some(_)) => format!("{}ms", self.latency_cell()),
+            (None, None) => "—".to_string(),
+        }
+    }
+}
+
+/// Index of the best of `stats`: the highest hit rate, with latency
+/// breaking ties. Stats without either are skipped.
+fn winner(stats: &[Stats]) -> Option<usize> {
+    stats
+        .iter()
+        .enumerate()
+        .filter(|(_, s)| s.hit_rate().is_some() || s.latency_ms.is_some())
+        .max_by(|(_, a), (_, b)| {
+            let key = |s: &Stats| {
+                (
+                    s.hit_rate().unwrap_or(-1.0),
+                    -s.latency_ms.unwrap_or(f64::INFINITY),
+                )
+            };
+            key(a).partial_cmp(&key(b)).unwrap()
+        })
+        .map(|(i, _)| i)
+}
+
+/// How one run fared against the baseline on the queries both contain.
+struct Regressions<'a> {
+    shared: usize,
+    /// Categories that regressed, each with its problems described.
+    categories: Vec<(String, Vec<String>)>,
+    /// Queries that found their expected result in the baseline only.
+    lost: Vec<&'a QueryResult>,
+    /// Slower queries with their baseline and current median, worst first.
+    slowed: Vec<(&'a QueryResult, f64, f64)>,
+    /// The change in summed median latency, when two or more shared
+    /// queries were timed in both runs.
+    latency: Option<Change>,
+}
+
+impl<'a> Regressions<'a> {
+    fn find(baseline: &'a ResultSet, run: &'a ResultSet, threshold: f64) -> Regressions<'a> {
+        let before: HashMap<(&str, &str), &QueryResult> = baseline
+            .queries
+            .iter()
+            .map(|q| ((q.query.as_str(), q.field.as_str()), q))
+            .collect();
+        let pairs: Vec<(&QueryResult, &QueryResult)> = run
+            .queries
+            .iter()
+            .filter_map(|q| {
+                before
+                    .get(&(q.query.as_str(), q.field.as_str()))
+                    .map(|b| (*b, q))
+            })
+            .collect();
+        let slower = |old: f64, new: f64| {
+            new - old >= MIN_LATENCY_DELTA_MS && new > old * (1.0 + threshold / 100.0)
+        };
+
+        let mut categories = Vec::new();
+        for category in categories_of(std::slice::from_ref(run)) {
+            let (old, new): (Vec<_>, Vec<_>) = pairs
+                .iter()
+                .filter(|(_, q)| q.category == category)
+                .copied()
+                .unzip();
+            let (old, new) = (Stats::of(old), Stats::of(new));
+            let mut problems = Vec::new();
+            if let (Some(a), Some(b)) = (old.hit_rate(), new.hit_rate()) {
+                if b < a {
+                    problems.push(format!(
+                        "ground truth {} → {}",
+                        old.hit_rate_cell(),
+                        new.hit_rate_cell()
+                    ));
+                }
+            }
+            if let (Some(a), Some(b)) = (old.latency_ms, new.latency_ms) {
+                if slower(a, b) {
+                    let timed = timed_pairs(
+                        pairs
+                            .iter()
+                            .filter(|(_, q)| q.category == category)
+                            .copied(),
+                    );
+                    let interval = match Change::of(&timed) {
+                        Some(change) if change.significant() => format!(", {}", change.interval()),
+                        Some(change) => format!(", {}, within noise", change.interval()),
+                        None => String::new(),
+                    };
+                    problems.push(format!(
+                        "latency {a:.2}ms → {b:.2}ms (+{:.0}%{interval})",
+                        (b / a - 1.0) * 100.0
+                    ));
+                }
+            }
+            if !problems.is_empty() {
+                categories.push((category, problems));
+            }
+        }
+
+        let mut lost = Vec::new();
+        let mut slowed = Vec::new();
+        for (old, new) in &pairs {
+            if old.found() == Some(true) && new.found() == Some(false) {
+                lost.push(*new);
+            }
+            if let (Some(a), Some(b)) = (old.median_ms, new.median_ms) {
+                if slower(a, b) {
+                    slowed.push((*new, a, b));
+                }
+            }
+        }
+        slowed.sort_by(|x, y| (y.2 - y.1).partial_cmp(&(x.2 - x.1)).unwrap());
+
+        Regressions {
+            shared: pairs.len(),
+            categories,
+            lost,
+            slowed,
+            latency: Change::of(&timed_pairs(pairs.iter().copied())),
+        }
+    }
+
+    /// The total latency change as a sentence, such as `Latency over 120
+    /// shared queries: +2.1% (95% CI -1.0% to +5.3%), within noise.`
+    fn latency_line(&self) -> Option<String> {
+        let change = self.latency?;
+        Some(format!(
+            "Latency over {} shared queries: {change}, {}.",
+            self.shared,
+            if change.significant() {
+                "significant"
+            } else {
+                "within noise"
+            }
+        ))
+    }
+
+    fn is_empty(&self) -> bool {
+        self.categories.is_empty() && self.lost.is_empty() && self.slowed.is_empty()
+    }
+}
+
+/// Baseline and current medians of the pairs timed in both runs.
+fn timed_pairs<'a>(
+    pairs: impl Iterator<Item = (&'a QueryResult, &'a QueryResult)>,
+) -> Vec<(f64, f64)> {
+    pairs
+        .filter_map(|(old, new)| Some((old.median_ms?, new.median_ms?)))
+        .collect()
+}
+
+/// Report settings shared by both formats.
+struct Options {
+    threshold: f64,
+    max_queries: usize,
+    /// Directory the SVG charts are written to, when asked for.
+    charts_dir: Option<String>,
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    match args.get(1).map(String::as_str) {
+        Some("record") => record(&args),
+        Some("trends") => trends::run(&args),
+        Some("tradeoff") => tradeoff::run(&args),
+        Some("tidy") => tidy::run(&args),
+        Some("grades") => grades::run(&args),
+        Some("winners") => wins::run(&args),
+        _ => compare(&args),
+    }
+}
+
+fn compare(args: &[String]) {
+    let options = Options {
+        threshold: parsed_flag(args, "--threshold", 10.0),
+        max_queries: parsed_flag(args, "--max-queries", 20),
+        charts_dir: flag_value(args, "--charts").map(String::from),
+    };
+
+    let mut paths = positional(
+        args,
+        1,
+        &[
+            "--baseline",
+            "--threshold",
+            "--max-queries",
+            "--out",
+            "--charts",
+        ],
+    );
+    if let Some(path) = flag_value(args, "--baseline") {
+        paths.retain(|p| *p != path);
+        paths.insert(0, path);
+    }
+    assert!(
+        !paths.is_empty(),
+        "Usage: report RESULTS.json... [--baseline RESULTS.json]"
+    );
+    let runs: Vec<ResultSet> = paths.iter().map(|path| read(path)).collect();
+    let charts = charts::render(&runs);
+    if let Some(dir) = &options.charts_dir {
+        charts::write(&charts, dir).expect("Failed to write charts");
+    }
+
+    let report = if has_flag(args, "--html") {
+        html::render(&runs, &charts, &options)
+    } else {
+        markdown::render(&runs, &charts, &options)
+    };
+    match flag_value(args, "--out") {
+        Some(path) => fs::write(path, report).expect("Failed to write report"),
+        None => print!("{report}"),
+    }
+}
+
+fn record(args: &[String]) {
+    let db = flag_value(args, "--db").expect("record requires --db PATH");
+    let recorded_at: u64 = parsed_flag(args, "--at", store::now());
+    let paths = positional(args, 2, &["--db", "--at"]);
+    assert!(
+        !paths.is_empty(),
+        "Usage: report record --db PATH RESULTS.json..."
+    );
+
+    let mut store = Store::open(db).expect("Failed to open results database");
+    for path in paths {
+        let results = read(path);
+        let id = store
+            .append(&results, recorded_at)
+            .expect("Failed to record results");
+        eprintln!("Recorded {} from {} as run {}", results.label(), path, id);
+    }
+}
+
+/// Arguments from `first` on that are neither flags nor flag values.
+fn positional<'a>(args: &'a [String], first: usize, value_flags: &[&str]) -> Vec<&'a str> {
+    args.iter()
+        .enumerate()
+        .skip(first)
+        .filter(|(i, a)| !a.starts_with("--") && !value_flags.contains(&args[i - 1].as_str()))
+        .map(|(_, a)| a.as_str())
+        .collect()
+}
+
+fn read(path: &str) -> ResultSet {
+    ResultSet::read(path).unwrap_or_else(|e| panic!("Failed to read results file {path}: {e}"))
+}
+
+/// Whether the runs name different corpus checksums.
+fn mixed_corpora(runs: &[ResultSet]) -> bool {
+    let checksums: Vec<&str> = runs
+        .iter()
+        .filter_map(|r| r.corpus_sha256.as_deref())
+        .collect();
+    checksums.windows(2).any(|w| w[0] != w[1])
+}
+
+/// Whether the runs record different matcher configurations.
+fn mixed_configs(runs: &[ResultSet]) -> bool {
+    let hashes: Vec<String> = runs
+        .iter()
+        .filter_map(|r| r.fingerprint.as_ref().map(|f| f.config_sha256()))
+        .collect();
+    hashes.windows(2).any(|w| w[0] != w[1])
+}
+
+/// Capabilities a comparison of result lists depends on: without scores a
+/// backend's lists show rank alone, and without the others it misses
+/// accented or reordered queries by design.
+const REPORTED_CAPABILITIES: [&str; 3] = ["scores", "normalization", "multi_atom"];
+
+/// One line per backend lacking any of [`REPORTED_CAPABILITIES`], such as
+/// `fzf: no scores`, in run order.
+fn capability_notes(runs: &[ResultSet]) -> Vec<String> {
+    let mut backends: Vec<&str> = Vec::new();
+    for run in runs {
+        if !backends.contains(&run.backend.as_str()) {
+            backends.push(&run.backend);
+        }
+    }
+    backends
+        .into_iter()
+        .filter_map(|backend| {
+            let missing = Capabilities::of(backend)?.missing(&REPORTED_CAPABILITIES);
+            if missing.is_empty() {
+                return None;
+            }
+            let missing: Vec<String> = missing
+                .iter()
+                .map(|name| format!("no {}", capabilities::describe(name)))
+                .collect();
+            Some(format!("{backend}: {}", missing.join(", ")))
+        })
+        .collect()
+}
+
+/// Source revision and short config hash of a run, for the summary table.
+fn provenance(run: &ResultSet) -> (String, String) {
+    match &run.fingerprint {
+        Some(fingerprint) => (
+            fingerprint.revision(),
+            fingerprint.config_sha256()[..8].to_string(),
+        ),
+        None => ("—".to_string(), "—".to_string()),
+    }
+}
+
+/// Queries the runs re-checked after their main pass, slowest refined
+/// median first.
+fn rechecked(runs: &[ResultSet]) -> Vec<(&ResultSet, &QueryResult, &Recheck)> {
+    let mut rows: Vec<(&ResultSet, &QueryResult, &Recheck)> = runs
+        .iter()
+        .flat_map(|run| {
+            run.queries
+                .iter()
+                .filter_map(move |q| Some((run, q, q.recheck.as_ref()?)))
+        })
+        .collect();
+    rows.sort_by(|a, b| b.2.median_ms.partial_cmp(&a.2.median_ms).unwrap());
+    rows
+}
+
+/// Startup phases recorded by any run, in the order they first appear;
+/// empty when no run recorded a startup.
+fn startup_phases(runs: &[ResultSet]) -> Vec<&str> {
+    let mut phases: Vec<&str> = Vec::new();
+    for startup in runs.iter().filter_map(|run| run.startup.as_ref()) {
+        for (phase, _) in &startup.phases {
+            if !phases.contains(&phase.as_str()) {
+                phases.push(phase);
+            }
+        }
+    }
+    phases
+}
+
+/// Footprints measured by any run, by run and then as measured.
+fn footprints(runs: &[ResultSet]) -> Vec<(&ResultSet, &Footprint)> {
+    runs.iter()
+        .flat_map(|run| run.memory.iter().map(move |footprint| (run, footprint)))
+        .collect()
+}
+
+fn mib(bytes: u64) -> String {
+    format!("{:.1}", bytes as f64 / (1u64 << 20) as f64)
+}
+
+/// Categories present in any run, in report order with unknown ones last.
+fn categories_of(runs: &[ResultSet]) -> Vec<String> {
+    order_categories(
+        runs.iter()
+            .flat_map(|r| r.queries.iter().map(|q| q.category.clone())),
+    )
+}
+
+/// Distinct `categories` in report order, with unknown ones last in order
+/// of first appearance.
+fn order_categories(categories: impl IntoIterator<Item = String>) -> Vec<String> {
+    let mut seen: Vec<String> = Vec::new();
+    for category in categories {
+        if !seen.contains(&category) {
+            seen.push(category);
+        }
+    }
+    let mut ordered: Vec<String> = CATEGORY_ORDER
+        .iter()
+        .filter(|c| seen.iter().any(|s| s == *c))
+        .map(|c| c.to_string())
+        .collect();
+    ordered.extend(
+        seen.into_iter()
+            .filter(|c| !CATEGORY_ORDER.contains(&c.as_str())),
+    );
+    ordered
+}
@@ -0,0 +1,359 @@
+//! Markdown rendering, sized for a PR description.
+
+use crate::charts::Chart;
+use crate::wins::{attribute, tally};
+use crate::{
+    capability_notes, categories_of, footprints, mib, mixed_configs, mixed_corpora, provenance,
+    rechecked, startup_phases, winner, Options, Regressions, Stats,
+};
+use harness::results::ResultSet;
+use std::fmt::Write as _;
+
+pub fn render(runs: &[ResultSet], charts: &[Chart], options: &Options) -> String {
+    let mut out = String::new();
+    writeln!(out, "## Fuzzy matching comparison").unwrap();
+    writeln!(out).unwrap();
+    if mixed_corpora(runs) {
+        writeln!(out, "> **Note:** the runs used different corpora, so their numbers are not directly comparable.").unwrap();
+        writeln!(out).unwrap();
+    }
+    if mixed_configs(runs) {
+        writeln!(
+            out,
+            "> **Note:** the runs used different matcher configurations (see the Config column)."
+        )
+        .unwrap();
+        writeln!(out).unwrap();
+    }
+
+    let notes = capability_notes(runs);
+    if !notes.is_empty() {
+        writeln!(
+            out,
+            "> **Note:** not every backend supports every mode: {}.",
+            escape(&notes.join("; "))
+        )
+        .unwrap();
+        writeln!(out).unwrap();
+    }
+
+    render_summary(&mut out, runs);
+    render_startup(&mut out, runs);
+    render_memory(&mut out, runs);
+    if let Some(dir) = &options.charts_dir {
+        render_charts(&mut out, charts, dir);
+    }
+    if runs.len() > 1 {
+        let baseline = &runs[0];
+        render_winners(&mut out, runs);
+        render_wins(&mut out, runs);
+        writeln!(out, "### Regressions against {}", escape(&baseline.label())).unwrap();
+        writeln!(out).unwrap();
+        for run in &runs[1..] {
+            render_regressions(&mut out, baseline, run, options);
+        }
+    }
+    render_rechecks(&mut out, runs);
+    out
+}
+
+fn render_summary(out: &mut String, runs: &[ResultSet]) {
+    writeln!(out, "### Summary").unwrap();
+    writeln!(out).unwrap();
+    writeln!(
+        out,
+        "| Run | Corpus | Revision | Config | Queries | Ground truth | Total median (ms) |"
+    )
+    .unwrap();
+    writeln!(out, "|---|---|---|---|--:|--:|--:|").unwrap();
+    for run in runs {
+        let stats = Stats::of(&run.queries);
+        let (revision, config) = provenance(run);
+        writeln!(
+            out,
+            "| {} | {} | {} | {} | {} | {} | {} |",
+            escape(&run.label()),
+            escape(run.corpus.as_deref().unwrap_or("—")),
+            revision,
+            config,
+            stats.queries,
+            stats.hit_rate_cell(),
+            stats.latency_cell()
+        )
+        .unwrap();
+    }
+    writeln!(out).unwrap();
+}
+
+/// Links to the chart files written under `dir`.
+/// One-off startup phases against warmed-up passes, for runs that
+/// recorded them.
+fn render_startup(out: &mut String, runs: &[ResultSet]) {
+    let phases = startup_phases(runs);
+    if phases.is_empty() {
+        return;
+    }
+    writeln!(out, "### Startup and steady state").unwrap();
+    writeln!(out).unwrap();
+    let mut header = "| Run |".to_string();
+    let mut rule = "|---|".to_string();
+    for phase in &phases {
+        write!(header, " {} (ms) |", escape(phase)).unwrap();
+        rule.push_str("--:|");
+    }
+    writeln!(
+        out,
+        "{header} Startup (ms) | Steady pass (ms) | Per query (ms) |"
+    )
+    .unwrap();
+    writeln!(out, "{rule}--:|--:|--:|").unwrap();
+    for run in runs {
+        let mut row = format!("| {} |", escape(&run.label()));
+        for phase in &phases {
+            let ms = run.startup.as_ref().and_then(|s| s.phase_ms(phase));
+            write!(
+                row,
+                " {} |",
+                ms.map_or("—".to_string(), |ms| format!("{ms:.1}"))
+            )
+            .unwrap();
+        }
+        let startup = run
+            .startup
+            .as_ref()
+            .map_or("—".to_string(), |s| format!("{:.1}", s.total_ms()));
+        let (pass, per_query) =
+            run.steady_state
+                .as_ref()
+                .map_or(("—".to_string(), "—".to_string()), |s| {
+                    (
+                        format!("{:.1}", s.pass_median_ms),
+                        format!("{:.3}", s.per_query_ms()),
+                    )
+                });
+        writeln!(out, "{row} {startup} | {pass} | {per_query} |").unwrap();
+    }
+    writeln!(out).unwrap();
+}
+
+fn render_memory(out: &mut String, runs: &[ResultSet]) {
+    let rows = footprints(runs);
+    if rows.is_empty() {
+        return;
+    }
+    writeln!(out, "### Memory footprint").unwrap();
+    writeln!(out).unwrap();
+    writeln!(
+        out,
+        "| Run | Candidates | Held as | Heap (MiB) | Resident (MiB) | Heap per candidate (B) |"
+    )
+    .unwrap();
+    writeln!(out, "|---|--:|---|--:|--:|--:|").unwrap();
+    for (run, footprint) in rows {
+        writeln!(
+            out,
+            "| {} | {} | {} | {} | {} | {:.1} |",
+            escape(&run.label()),
+            footprint.candidates,
+            escape(&footprint.representation),
+            mib(footprint.heap_bytes),
+            footprint.resident_bytes.map_or("—".to_string(), mib),
+            footprint.heap_bytes_per_candidate()
+        )
+        .unwrap();
+    }
+    writeln!(out).unwrap();
+}
+
+fn render_charts(out: &mut String, charts: &[Chart], dir: &str) {
+    if charts.is_empty() {
+        return;
+    }
+    writeln!(out, "### Charts").unwrap();
+    writeln!(out).unwrap();
+    for chart in charts {
+        let path = format!("{}/{}", dir.trim_end_matches('/'), chart.file_name);
+        writeln!(out, "![{}]({})", chart.title, path.replace(' ', "%20")).unwrap();
+        writeln!(out).unwrap();
+    }
+}
+
+/// One row per category with each run's hit rate and latency. The winner
+/// has the best hit rate, with latency breaking ties; categories no run
+/// judged are won on latency alone.
+fn render_winners(out: &mut String, runs: &[ResultSet]) {
+    writeln!(out, "### Per-category winners").unwrap();
+    writeln!(out).unwrap();
+    let header: Vec<String> = runs.iter().map(|r| escape(&r.label())).collect();
+    writeln!(out, "| Category | {} | Winner |", header.join(" | ")).unwrap();
+    writeln!(out, "|---|{}---|", "--:|".repeat(runs.len())).unwrap();
+
+    for category in categories_of(runs) {
+        let stats: Vec<Stats> = runs
+            .iter()
+            .map(|run| Stats::of_category(run, &category))
+            .collect();
+        let cells: Vec<String> = stats.iter().map(Stats::cell).collect();
+        let winner = winner(&stats)
+            .map(|i| format!("**{}**", escape(&runs[i].label())))
+            .unwrap_or_else(|| "—".to_string());
+        writeln!(out, "| {} | {} | {} |", category, cells.join(" | "), winner).unwrap();
+    }
+    writeln!(out).unwrap();
+}
+
+/// Queries won outright per category, quality wins then latency wins.
+fn render_wins(out: &mut String, runs: &[ResultSet]) {
+    let tallies = tally(runs, &attribute(runs));
+    if tallies.is_empty() {
+        return;
+    }
+    writeln!(out, "### Queries won (quality / latency)").unwrap();
+    writeln!(out).unwrap();
+    let header: Vec<String> = runs.iter().map(|r| escape(&r.label())).collect();
+    writeln!(out, "| Category | {} | Ties |", header.join(" | ")).unwrap();
+    writeln!(out, "|---|{}--:|", "--:|".repeat(runs.len())).unwrap();
+    for t in &tallies {
+        let cells: Vec<String> = (0..runs.len()).map(|i| t.cell(i)).collect();
+        writeln!(
+            out,
+            "| {} | {} | {} |",
+            t.category,
+            cells.join(" | "),
+            t.ties_cell()
+        )
+        .unwrap();
+    }
+    writeln!(out).unwrap();
+}
+
+fn render_regressions(out: &mut String, baseline: &ResultSet, run: &ResultSet, options: &Options) {
+    writeln!(out, "#### {}", escape(&run.label())).unwrap();
+    writeln!(out).unwrap();
+
+    let regressions = Regressions::find(baseline, run, options.threshold);
+    if regressions.shared == 0 {
+        writeln!(out, "No queries in common with the baseline.").unwrap();
+        writeln!(out).unwrap();
+        return;
+    }
+    if let Some(line) = regressions.latency_line() {
+        writeln!(out, "{line}").unwrap();
+        writeln!(out).unwrap();
+    }
+    if regressions.is_empty() {
+        writeln!(
+            out,
+            "No regressions across {} shared queries.",
+            regressions.shared
+        )
+        .unwrap();
+        writeln!(out).unwrap();
+        return;
+    }
+
+    if !regressions.categories.is_empty() {
+        writeln!(out, "| Category | Regression |").unwrap();
+        writeln!(out, "|---|---|").unwrap();
+        for (category, problems) in &regressions.categories {
+            writeln!(out, "| {} | {} |", category, problems.join("; ")).unwrap();
+        }
+        writeln!(out).unwrap();
+    }
+    let max = options.max_queries;
+    if !regressions.lost.is_empty() {
+        let lost = &regressions.lost;
+        writeln!(
+            out,
+            "Queries that no longer find their expected result ({}):",
+            lost.len()
+        )
+        .unwrap();
+        writeln!(out).unwrap();
+        for q in lost.iter().take(max) {
+            let top = q
+                .results
+                .as_ref()
+                .and_then(|r| r.first())
+                .map(|hit| format!("top result `{}`", hit.name))
+                .unwrap_or_else(|| "no results".to_string());
+            writeln!(
+                out,
+                "- `{}` ({}, {}): expected `{}`, {}",
+                q.query, q.field, q.category, q.expected_name, top
+            )
+            .unwrap();
+        }
+        if lost.len() > max {
+            writeln!(out, "- … and {} more", lost.len() - max).unwrap();
+        }
+        writeln!(out).unwrap();
+    }
+    if !regressions.slowed.is_empty() {
+        let slowed = &regressions.slowed;
+        writeln!(
+            out,
+            "Queries more than {}% slower ({}):",
+            options.threshold,
+            slowed.len()
+        )
+        .unwrap();
+        writeln!(out).unwrap();
+        for (q, a, b) in slowed.iter().take(max) {
+            writeln!(
+                out,
+                "- `{}` ({}, {}): {a:.2}ms → {b:.2}ms",
+                q.query, q.field, q.category
+            )
+            .unwrap();
+        }
+        if slowed.len() > max {
+            writeln!(out, "- … and {} more", slowed.len() - max).unwrap();
+        }
+        writeln!(out).unwrap();
+    }
+}
+
+/// The slowest queries of each run, timed again on their own. A first-pass
+/// median far from the re-checked one was noise rather than a slow query.
+fn render_rechecks(out: &mut String, runs: &[ResultSet]) {
+    let rows = rechecked(runs);
+    if rows.is_empty() {
+        return;
+    }
+    writeln!(out, "### Slow queries, re-checked").unwrap();
+    writeln!(out).unwrap();
+    writeln!(
+        out,
+        "| Run | Query | Field | Category | First pass (ms) | Re-checked (ms) | p99 (ms) | Std dev (ms) | Flamegraph |"
+    )
+    .unwrap();
+    writeln!(out, "|---|---|---|---|--:|--:|--:|--:|---|").unwrap();
+    for (run, q, recheck) in rows {
+        let first = q.median_ms.map_or("—".to_string(), |ms| format!("{ms:.3}"));
+        let flamegraph = recheck.flamegraph.as_ref().map_or("—".to_string(), |path| {
+            format!("[svg]({})", path.replace(' ', "%20"))
+        });
+        writeln!(
+            out,
+            "| {} | `{}` | {} | {} | {} | {:.3} ({} runs) | {:.3} | {:.3} | {} |",
+            escape(&run.label()),
+            escape(&q.query),
+            q.field,
+            q.category,
+            first,
+            recheck.median_ms,
+            recheck.iterations,
+            recheck.p99_ms,
+            recheck.stddev_ms,
+            flamegraph
+        )
+        .unwrap();
+    }
+    writeln!(out).unwrap();
+}
+
+/// Keeps labels from breaking the table.
+fn escape(text: &str) -> String {
+    text.replace('|', "\\|")
+}
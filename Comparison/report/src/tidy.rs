@@ -0,0 +1,117 @@
+//! `report tidy`: every recorded number as its own row, for pandas and R.
+//!
+//! The Markdown and HTML tables are wide, one column per run, which reads
+//! well but has to be pivoted before it can be grouped or plotted. This
+//! writes long-format CSV instead: one row per backend, run, query and
+//! metric, with the query's field and category alongside, so a notebook
+//! can filter and aggregate without reshaping. A metric a run did not
+//! record has no row rather than an empty value.
+
+use crate::{positional, read};
+use harness::cli::{flag_value, parsed_flag};
+use harness::judgments::{load_judgments, Judgment};
+use harness::metrics::ndcg_at;
+use harness::results::QueryResult;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, Write};
+
+const HEADER: [&str; 10] = [
+    "backend",
+    "run",
+    "config",
+    "revision",
+    "query",
+    "field",
+    "category",
+    "frequency",
+    "metric",
+    "value",
+];
+
+pub fn run(args: &[String]) {
+    let k: usize = parsed_flag(args, "--k", 10);
+    let paths = positional(args, 2, &["--judgments", "--k", "--out"]);
+    assert!(
+        !paths.is_empty(),
+        "Usage: report tidy RESULTS.json... [--judgments PATH] [--k N] [--out PATH]"
+    );
+    let judgments = match flag_value(args, "--judgments") {
+        Some(path) => load_judgments(path).expect("Failed to read judgments"),
+        None => Vec::new(),
+    };
+    let mut by_query: HashMap<(&str, &str), Vec<&Judgment>> = HashMap::new();
+    for j in &judgments {
+        by_query
+            .entry((j.query.as_str(), j.field.as_str()))
+            .or_default()
+            .push(j);
+    }
+
+    let out: Box<dyn Write> = match flag_value(args, "--out") {
+        Some(path) => Box::new(File::create(path).expect("Failed to create output file")),
+        None => Box::new(io::stdout()),
+    };
+    let mut writer = csv::Writer::from_writer(out);
+    writer.write_record(HEADER).expect("Failed to write table");
+    let ndcg_metric = format!("ndcg_at_{k}");
+    for path in paths {
+        let set = read(path);
+        let (config, revision) = match &set.fingerprint {
+            Some(fingerprint) => (
+                fingerprint.config_sha256()[..8].to_string(),
+                fingerprint.revision(),
+            ),
+            None => (String::new(), String::new()),
+        };
+        for q in &set.queries {
+            let judged = by_query
+                .get(&(q.query.as_str(), q.field.as_str()))
+                .map_or(&[][..], Vec::as_slice);
+            let ndcg = ndcg_at(q, judged, k);
+            for (metric, value) in metrics(q, &ndcg_metric, ndcg) {
+                writer
+                    .write_record([
+                        &set.backend,
+                        &set.run,
+                        &config,
+                        &revision,
+                        &q.query,
+                        &q.field,
+                        &q.category,
+                        &q.frequency.to_string(),
+                        metric,
+                        &value,
+                    ])
+                    .expect("Failed to write table");
+            }
+        }
+    }
+    writer.flush().expect("Failed to write table");
+}
+
+/// The metrics `q` recorded, named and formatted for the table.
+fn metrics<'a>(q: &QueryResult, ndcg_metric: &'a str, ndcg: Option<f64>) -> Vec<(&'a str, String)> {
+    let ms = |value: f64| format!("{value:.4}");
+    let mut rows = Vec::new();
+    if let Some(median) = q.median_ms {
+        rows.push(("median_ms", ms(median)));
+    }
+    if let Some(min) = q.min_ms {
+        rows.push(("min_ms", ms(min)));
+    }
+    if let Some(matches) = q.matches {
+        rows.push(("matches", matches.to_string()));
+    }
+    if let Some(found) = q.found() {
+        rows.push(("found", u8::from(found).to_string()));
+    }
+    if let Some(ndcg) = ndcg {
+        rows.push((ndcg_metric, format!("{ndcg:.4}")));
+    }
+    if let Some(recheck) = &q.recheck {
+        rows.push(("recheck_median_ms", ms(recheck.median_ms)));
+        rows.push(("recheck_p99_ms", ms(recheck.p99_ms)));
+    }
+    rows
+}
@@ -0,0 +1,157 @@
+//! `report tradeoff`: latency against ranking quality, one row per backend,
+//! run and category.
+//!
+//! Benchmark and quality runs usually write separate result files for the
+//! same configuration, so files are joined on backend and run label: each
+//! query takes its timing from the first file that timed it and its result
+//! list from the first that recorded one. The output is a tidy CSV with a
+//! header row, ready to plot as a scatter of p95 latency against NDCG.
+
+use crate::{order_categories, positional, read};
+use harness::cli::{flag_value, parsed_flag};
+use harness::judgments::{load_judgments, Judgment};
+use harness::metrics::{ndcg_at, percentile};
+use harness::results::{QueryResult, ResultSet};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, Write};
+
+const HEADER: [&str; 10] = [
+    "backend",
+    "run",
+    "config",
+    "revision",
+    "category",
+    "queries",
+    "timed",
+    "latency_p95_ms",
+    "judged",
+    "ndcg",
+];
+
+/// The joined queries of one backend and run.
+struct Joined {
+    backend: String,
+    run: String,
+    config: String,
+    revision: String,
+    /// Merged record per (query, field), in first-seen order.
+    queries: Vec<QueryResult>,
+}
+
+pub fn run(args: &[String]) {
+    let k: usize = parsed_flag(args, "--k", 10);
+    let paths = positional(args, 2, &["--judgments", "--k", "--out"]);
+    assert!(
+        !paths.is_empty(),
+        "Usage: report tradeoff RESULTS.json... [--judgments PATH] [--k N] [--out PATH]"
+    );
+    let judgments = match flag_value(args, "--judgments") {
+        Some(path) => load_judgments(path).expect("Failed to read judgments"),
+        None => Vec::new(),
+    };
+    let mut by_query: HashMap<(&str, &str), Vec<&Judgment>> = HashMap::new();
+    for j in &judgments {
+        by_query
+            .entry((j.query.as_str(), j.field.as_str()))
+            .or_default()
+            .push(j);
+    }
+
+    let runs: Vec<ResultSet> = paths.iter().map(|path| read(path)).collect();
+    let joined = join(&runs);
+
+    let out: Box<dyn Write> = match flag_value(args, "--out") {
+        Some(path) => Box::new(File::create(path).expect("Failed to create output file")),
+        None => Box::new(io::stdout()),
+    };
+    let mut writer = csv::Writer::from_writer(out);
+    writer.write_record(HEADER).expect("Failed to write table");
+    for group in &joined {
+        let categories = order_categories(group.queries.iter().map(|q| q.category.clone()));
+        let scopes = std::iter::once(None).chain(categories.iter().map(Some));
+        for category in scopes {
+            let queries: Vec<&QueryResult> = group
+                .queries
+                .iter()
+                .filter(|q| category.is_none_or(|c| q.category == *c))
+                .collect();
+            let latencies: Vec<f64> = queries.iter().filter_map(|q| q.median_ms).collect();
+            let scores: Vec<f64> = queries
+                .iter()
+                .filter_map(|q| {
+                    let judged = by_query
+                        .get(&(q.query.as_str(), q.field.as_str()))
+                        .map_or(&[][..], Vec::as_slice);
+                    ndcg_at(q, judged, k)
+                })
+                .collect();
+            let ndcg =
+                (!scores.is_empty()).then(|| scores.iter().sum::<f64>() / scores.len() as f64);
+            writer
+                .write_record([
+                    group.backend.clone(),
+                    group.run.clone(),
+                    group.config.clone(),
+                    group.revision.clone(),
+                    category.cloned().unwrap_or_else(|| "all".to_string()),
+                    queries.len().to_string(),
+                    latencies.len().to_string(),
+                    percentile(&latencies, 95.0).map_or(String::new(), |ms| format!("{ms:.4}")),
+                    scores.len().to_string(),
+                    ndcg.map_or(String::new(), |n| format!("{n:.4}")),
+                ])
+                .expect("Failed to write table");
+        }
+    }
+    writer.flush().expect("Failed to write table");
+}
+
+/// Groups `runs` by backend and run label, merging their queries.
+fn join(runs: &[ResultSet]) -> Vec<Joined> {
+    let mut joined: Vec<Joined> = Vec::new();
+    for set in runs {
+        let idx = match joined
+            .iter()
+            .position(|j| j.backend == set.backend && j.run == set.run)
+        {
+            Some(idx) => idx,
+            None => {
+                joined.push(Joined {
+                    backend: set.backend.clone(),
+                    run: set.run.clone(),
+                    config: String::new(),
+                    revision: String::new(),
+                    queries: Vec::new(),
+                });
+                joined.len() - 1
+            }
+        };
+        let group = &mut joined[idx];
+        if let Some(fingerprint) = &set.fingerprint {
+            if group.config.is_empty() {
+                group.config = fingerprint.config_sha256()[..8].to_string();
+                group.revision = fingerprint.revision();
+            }
+        }
+        for q in &set.queries {
+            match group
+                .queries
+                .iter_mut()
+                .find(|have| have.query == q.query && have.field == q.field)
+            {
+                Some(have) => {
+                    if have.median_ms.is_none() {
+                        have.median_ms = q.median_ms;
+                        have.min_ms = q.min_ms;
+                    }
+                    if have.results.is_none() {
+                        have.results = q.results.clone();
+                    }
+                }
+                None => group.queries.push(q.clone()),
+            }
+        }
+    }
+    joined
+}
@@ -0,0 +1,338 @@
+//! `report trends`: per-category latency and quality across recorded runs.
+//!
+//! Each backend gets a table per metric with one row per run, oldest first,
+//! and a final row with the change across the window. Categories whose
+//! latency grew by more than the threshold over the window, or whose hit
+//! rate fell, are called out, since a few percent per week never trips a
+//! single comparison.
+
+use crate::{html, order_categories};
+use harness::cli::{flag_value, has_flag, parsed_flag};
+use harness::store::{CategoryPoint, RunFilter, Store, StoredRun};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::fs;
+
+/// The recorded runs of one backend with their per-category points.
+struct Series {
+    backend: String,
+    runs: Vec<StoredRun>,
+    categories: Vec<String>,
+    points: Vec<HashMap<String, CategoryPoint>>,
+}
+
+/// A metric tracked over the runs.
+#[derive(Clone, Copy)]
+enum Metric {
+    Latency,
+    HitRate,
+}
+
+impl Metric {
+    fn title(self) -> &'static str {
+        match self {
+            Metric::Latency => "Summed median latency (ms)",
+            Metric::HitRate => "Ground-truth hit rate (%)",
+        }
+    }
+
+    fn value(self, point: &CategoryPoint) -> Option<f64> {
+        match self {
+            Metric::Latency => point.latency_ms,
+            Metric::HitRate => point.hit_rate().map(|r| r * 100.0),
+        }
+    }
+
+    fn format(self, value: f64) -> String {
+        match self {
+            Metric::Latency => format!("{value:.2}"),
+            Metric::HitRate => format!("{value:.1}%"),
+        }
+    }
+
+    /// Latency changes read as percentages, hit rates as points.
+    fn change(self, first: f64, last: f64) -> String {
+        match self {
+            Metric::Latency if first > 0.0 => format!("{:+.1}%", (last / first - 1.0) * 100.0),
+            Metric::Latency => "—".to_string(),
+            Metric::HitRate => format!("{:+.1} pp", last - first),
+        }
+    }
+}
+
+impl Series {
+    fn values(&self, metric: Metric, category: &str) -> Vec<Option<f64>> {
+        self.points
+            .iter()
+            .map(|points| points.get(category).and_then(|p| metric.value(p)))
+            .collect()
+    }
+
+    /// First and last recorded value of a category within the window.
+    fn ends(&self, metric: Metric, category: &str) -> Option<(f64, f64)> {
+        let values: Vec<f64> = self
+            .values(metric, category)
+            .into_iter()
+            .flatten()
+            .collect();
+        (values.len() > 1).then(|| (values[0], values[values.len() - 1]))
+    }
+
+    fn has(&self, metric: Metric) -> bool {
+        self.categories
+            .iter()
+            .any(|c| self.values(metric, c).iter().any(Option::is_some))
+    }
+
+    /// Header and rows of a metric's table, ending with the change row.
+    fn table(&self, metric: Metric) -> (Vec<String>, Vec<Vec<String>>) {
+        let mut header = vec!["Recorded".to_string(), "Run".to_string()];
+        header.extend(self.categories.iter().cloned());
+        let mut rows: Vec<Vec<String>> = self
+            .runs
+            .iter()
+            .zip(&self.points)
+            .map(|(run, points)| {
+                let mut row = vec![date(run.recorded_at), run.run.clone()];
+                row.extend(self.categories.iter().map(|c| {
+                    points
+                        .get(c)
+                        .and_then(|p| metric.value(p))
+                        .map(|v| metric.format(v))
+                        .unwrap_or_else(|| "—".to_string())
+                }));
+                row
+            })
+            .collect();
+        let mut change = vec!["Change".to_string(), String::new()];
+        change.extend(self.categories.iter().map(|c| {
+            self.ends(metric, c)
+                .map(|(first, last)| metric.change(first, last))
+                .unwrap_or_else(|| "—".to_string())
+        }));
+        rows.push(change);
+        (header, rows)
+    }
+
+    /// Categories that got slower or less accurate across the window.
+    fn drift(&self, threshold: f64) -> Vec<String> {
+        let since = self
+            .runs
+            .first()
+            .map(|r| date(r.recorded_at))
+            .unwrap_or_default();
+        let mut drift = Vec::new();
+        for category in &self.categories {
+            if let Some((first, last)) = self.ends(Metric::Latency, category) {
+                if last > first * (1.0 + threshold / 100.0) {
+                    drift.push(format!(
+                        "`{category}` latency {} since {since}",
+                        Metric::Latency.change(first, last)
+                    ));
+                }
+            }
+            if let Some((first, last)) = self.ends(Metric::HitRate, category) {
+                if last < first {
+                    drift.push(format!(
+                        "`{category}` hit rate {} since {since}",
+                        Metric::HitRate.change(first, last)
+                    ));
+                }
+            }
+        }
+        drift
+    }
+}
+
+pub fn run(args: &[String]) {
+    let db = flag_value(args, "--db").expect("trends requires --db PATH");
+    let filter = RunFilter {
+        backend: flag_value(args, "--backend").map(String::from),
+        corpus: flag_value(args, "--corpus").map(String::from),
+        last: Some(parsed_flag(args, "--last", 30)),
+    };
+    let threshold: f64 = parsed_flag(args, "--threshold", 10.0);
+
+    let store = Store::open(db).expect("Failed to open results database");
+    let runs = store
+        .runs(&filter)
+        .expect("Failed to read results database");
+    let mut series: Vec<Series> = Vec::new();
+    for run in runs {
+        let points = store
+            .category_points(run.id)
+            .expect("Failed to read results database");
+        let idx = match series.iter().position(|s| s.backend == run.backend) {
+            Some(idx) => idx,
+            None => {
+                series.push(Series {
+                    backend: run.backend.clone(),
+                    runs: Vec::new(),
+                    categories: Vec::new(),
+                    points: Vec::new(),
+                });
+                series.len() - 1
+            }
+        };
+        let s = &mut series[idx];
+        s.runs.push(run);
+        s.points.push(
+            points
+                .into_iter()
+                .map(|p| (p.category.clone(), p))
+                .collect(),
+        );
+    }
+    for s in &mut series {
+        s.categories = order_categories(s.points.iter().flat_map(|p| p.keys().cloned()));
+    }
+
+    let report = if has_flag(args, "--html") {
+        render_html(&series, threshold)
+    } else {
+        render_markdown(&series, threshold)
+    };
+    match flag_value(args, "--out") {
+        Some(path) => fs::write(path, report).expect("Failed to write report"),
+        None => print!("{report}"),
+    }
+}
+
+fn render_markdown(series: &[Series], threshold: f64) -> String {
+    let mut out = String::new();
+    if series.is_empty() {
+        writeln!(out, "No recorded runs match.").unwrap();
+        return out;
+    }
+    for s in series {
+        writeln!(out, "## Trends: {}", s.backend).unwrap();
+        writeln!(out).unwrap();
+        writeln!(out, "{}", span(s)).unwrap();
+        writeln!(out).unwrap();
+        for metric in [Metric::Latency, Metric::HitRate] {
+            if !s.has(metric) {
+                continue;
+            }
+            writeln!(out, "### {}", metric.title()).unwrap();
+            writeln!(out).unwrap();
+            let (header, rows) = s.table(metric);
+            writeln!(out, "| {} |", header.join(" | ")).unwrap();
+            writeln!(out, "|---|---|{}", "--:|".repeat(header.len() - 2)).unwrap();
+            for row in rows {
+                let row: Vec<String> = row.iter().map(|c| c.replace('|', "\\|")).collect();
+                writeln!(out, "| {} |", row.join(" | ")).unwrap();
+            }
+            writeln!(out).unwrap();
+        }
+        let drift = s.drift(threshold);
+        if !drift.is_empty() {
+            writeln!(out, "Drifting: {}.", drift.join("; ")).unwrap();
+            writeln!(out).unwrap();
+        }
+    }
+    out
+}
+
+fn render_html(series: &[Series], threshold: f64) -> String {
+    let mut body = String::new();
+    if series.is_empty() {
+        body.push_str("<p>No recorded runs match.</p>\n");
+    }
+    for s in series {
+        writeln!(body, "<h2>{}</h2>", html::escape(&s.backend)).unwrap();
+        writeln!(body, "<p>{}</p>", html::escape(&span(s))).unwrap();
+        let drift = s.drift(threshold);
+        if !drift.is_empty() {
+            let drift: Vec<String> = drift
+                .iter()
+                .map(|d| html::escape(&d.replace('`', "")))
+                .collect();
+            writeln!(
+                body,
+                "<p class=\"note\">Drifting: {}.</p>",
+                drift.join("; ")
+            )
+            .unwrap();
+        }
+        let x_labels: Vec<String> = s
+            .runs
+            .iter()
+            .map(|r| {
+                if r.run.is_empty() {
+                    date(r.recorded_at)
+                } else {
+                    format!("{} {}", date(r.recorded_at), r.run)
+                }
+            })
+            .collect();
+        body.push_str(&html::legend(&s.categories));
+        body.push_str("<div class=\"charts\">");
+        for metric in [Metric::Latency, Metric::HitRate] {
+            if !s.has(metric) {
+                continue;
+            }
+            let values: Vec<Vec<Option<f64>>> =
+                s.categories.iter().map(|c| s.values(metric, c)).collect();
+            let fixed_max = matches!(metric, Metric::HitRate).then_some(100.0);
+            body.push_str(&html::line_chart(
+                metric.title(),
+                &x_labels,
+                &values,
+                fixed_max,
+            ));
+        }
+        body.push_str("</div>\n");
+        for metric in [Metric::Latency, Metric::HitRate] {
+            if !s.has(metric) {
+                continue;
+            }
+            writeln!(body, "<h3>{}</h3>", metric.title()).unwrap();
+            let (header, rows) = s.table(metric);
+            body.push_str("<table><thead><tr>");
+            for h in &header {
+                write!(body, "<th>{}</th>", html::escape(h)).unwrap();
+            }
+            body.push_str("</tr></thead><tbody>\n");
+            for row in rows {
+                body.push_str("<tr>");
+                for (i, cell) in row.iter().enumerate() {
+                    let class = if i < 2 { "" } else { " class=\"num\"" };
+                    write!(body, "<td{class}>{}</td>", html::escape(cell)).unwrap();
+                }
+                body.push_str("</tr>\n");
+            }
+            body.push_str("</tbody></table>\n");
+        }
+    }
+    html::page("Fuzzy matching trends", &body)
+}
+
+/// "12 runs from 2026-09-01 to 2026-10-15."
+fn span(series: &Series) -> String {
+    let (Some(first), Some(last)) = (series.runs.first(), series.runs.last()) else {
+        return String::new();
+    };
+    format!(
+        "{} run{} from {} to {}.",
+        series.runs.len(),
+        if series.runs.len() == 1 { "" } else { "s" },
+        date(first.recorded_at),
+        date(last.recorded_at)
+    )
+}
+
+/// `YYYY-MM-DD` (UTC) of a Unix timestamp.
+fn date(unix_seconds: u64) -> String {
+    // Days to civil date, after Howard Hinnant's `civil_from_days`
+    let days = (unix_seconds / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02}")
+}
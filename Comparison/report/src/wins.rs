@@ -0,0 +1,229 @@
+//! Which run won each query, on quality and on latency.
+//!
+//! The per-category winner tables say who is best on average. They do not
+//! say whether a backend wins a category outright or only edges it by
+//! winning a few queries big. Attributing each query shows that: a query
+//! is won on quality by the run that ranks its expected result highest,
+//! and on latency by the run with the lowest median, if it beats the
+//! runner-up by more than timer noise. Anything else is a tie, and a query
+//! that no run answers, or that only one run recorded, has no winner.
+//!
+//! `report winners` exports the attribution per query as CSV, and the
+//! comparison report counts the wins per category.
+
+use crate::{categories_of, positional, read, MIN_LATENCY_DELTA_MS};
+use harness::cli::flag_value;
+use harness::queries::SKIP;
+use harness::results::{QueryResult, ResultSet};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, Write};
+
+/// Who won one query on one measure.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Win {
+    /// The index of the winning run.
+    Run(usize),
+    /// Two or more runs were equally good.
+    Tie,
+    /// Fewer than two runs measured the query, or none answered it.
+    Nobody,
+}
+
+/// One query of the first run and who won it.
+pub struct Attribution<'a> {
+    pub query: &'a QueryResult,
+    /// The rank of the expected result in each run, 0 for first.
+    pub ranks: Vec<Option<usize>>,
+    pub quality: Win,
+    pub latency: Win,
+}
+
+/// Every query of the first run, attributed across `runs`. Queries are
+/// matched by text and field.
+pub fn attribute(runs: &[ResultSet]) -> Vec<Attribution<'_>> {
+    let lookups: Vec<HashMap<(&str, &str), &QueryResult>> = runs
+        .iter()
+        .map(|run| {
+            run.queries
+                .iter()
+                .map(|q| ((q.query.as_str(), q.field.as_str()), q))
+                .collect()
+        })
+        .collect();
+    runs.first()
+        .map_or(&[][..], |run| &run.queries[..])
+        .iter()
+        .map(|query| {
+            let key = (query.query.as_str(), query.field.as_str());
+            let results: Vec<Option<&QueryResult>> =
+                lookups.iter().map(|l| l.get(&key).copied()).collect();
+            let ranks: Vec<Option<usize>> =
+                results.iter().map(|r| r.and_then(expected_rank)).collect();
+            let recorded = results
+                .iter()
+                .filter(|r| r.is_some_and(|r| r.results.is_some()))
+                .count();
+            let quality = if recorded < 2 {
+                Win::Nobody
+            } else {
+                best(&ranks, |rank| rank as f64, 0.0)
+            };
+            let medians: Vec<Option<f64>> = results
+                .iter()
+                .map(|r| r.and_then(|r| r.median_ms))
+                .collect();
+            let latency = if medians.iter().flatten().count() < 2 {
+                Win::Nobody
+            } else {
+                best(&medians, |ms| ms, MIN_LATENCY_DELTA_MS)
+            };
+            Attribution {
+                query,
+                ranks,
+                quality,
+                latency,
+            }
+        })
+        .collect()
+}
+
+/// Wins per run in one category.
+pub struct Tally {
+    pub category: String,
+    pub quality: Vec<usize>,
+    pub quality_ties: usize,
+    pub latency: Vec<usize>,
+    pub latency_ties: usize,
+}
+
+impl Tally {
+    /// Whether any query in the category had a quality or latency result.
+    pub fn is_empty(&self) -> bool {
+        self.quality.iter().chain(&self.latency).sum::<usize>()
+            + self.quality_ties
+            + self.latency_ties
+            == 0
+    }
+
+    /// `quality wins / latency wins`, as a table cell.
+    pub fn cell(&self, run: usize) -> String {
+        format!("{} / {}", self.quality[run], self.latency[run])
+    }
+
+    pub fn ties_cell(&self) -> String {
+        format!("{} / {}", self.quality_ties, self.latency_ties)
+    }
+}
+
+/// Win counts per category, in report order, skipping categories where no
+/// query was won or tied.
+pub fn tally(runs: &[ResultSet], attributions: &[Attribution<'_>]) -> Vec<Tally> {
+    categories_of(runs)
+        .into_iter()
+        .map(|category| {
+            let mut tally = Tally {
+                quality: vec![0; runs.len()],
+                quality_ties: 0,
+                latency: vec![0; runs.len()],
+                latency_ties: 0,
+                category,
+            };
+            for a in attributions
+                .iter()
+                .filter(|a| a.query.category == tally.category)
+            {
+                match a.quality {
+                    Win::Run(i) => tally.quality[i] += 1,
+                    Win::Tie => tally.quality_ties += 1,
+                    Win::Nobody => {}
+                }
+                match a.latency {
+                    Win::Run(i) => tally.latency[i] += 1,
+                    Win::Tie => tally.latency_ties += 1,
+                    Win::Nobody => {}
+                }
+            }
+            tally
+        })
+        .filter(|tally| !tally.is_empty())
+        .collect()
+}
+
+/// `report winners`: one CSV row per query of the first file.
+pub fn run(args: &[String]) {
+    let paths = positional(args, 2, &["--out"]);
+    assert!(
+        paths.len() > 1,
+        "Usage: report winners RESULTS.json RESULTS.json... [--out PATH]"
+    );
+    let runs: Vec<ResultSet> = paths.iter().map(|path| read(path)).collect();
+    let out: Box<dyn Write> = match flag_value(args, "--out") {
+        Some(path) => Box::new(File::create(path).expect("Failed to create output file")),
+        None => Box::new(io::stdout()),
+    };
+    let mut writer = csv::Writer::from_writer(out);
+    let mut header = vec![
+        "query".to_string(),
+        "field".to_string(),
+        "category".to_string(),
+        "quality_winner".to_string(),
+        "latency_winner".to_string(),
+    ];
+    for run in &runs {
+        header.push(format!("{} rank", run.label()));
+    }
+    writer.write_record(&header).expect("Failed to write table");
+    let name = |win: Win| match win {
+        Win::Run(i) => runs[i].label(),
+        Win::Tie => "tie".to_string(),
+        Win::Nobody => String::new(),
+    };
+    for a in attribute(&runs) {
+        let mut row = vec![
+            a.query.query.clone(),
+            a.query.field.clone(),
+            a.query.category.clone(),
+            name(a.quality),
+            name(a.latency),
+        ];
+        row.extend(
+            a.ranks
+                .iter()
+                .map(|rank| rank.map(|r| (r + 1).to_string()).unwrap_or_default()),
+        );
+        writer.write_record(&row).expect("Failed to write table");
+    }
+    writer.flush().expect("Failed to write table");
+}
+
+/// The 0-based rank of the first result whose name contains the expected
+/// name, case-insensitively, among everything the run recorded.
+fn expected_rank(result: &QueryResult) -> Option<usize> {
+    if result.expected_name.is_empty() || result.expected_name == SKIP {
+        return None;
+    }
+    let expected = result.expected_name.to_lowercase();
+    result
+        .results
+        .as_ref()?
+        .iter()
+        .position(|hit| hit.name.to_lowercase().contains(&expected))
+}
+
+/// The run with the lowest value, when it beats every other by more than
+/// `margin`; a tie when it does not; nobody when no run has a value.
+fn best<T: Copy>(values: &[Option<T>], key: impl Fn(T) -> f64, margin: f64) -> Win {
+    let mut scored: Vec<(usize, f64)> = values
+        .iter()
+        .enumerate()
+        .filter_map(|(i, v)| v.map(|v| (i, key(v))))
+        .collect();
+    scored.sort_by(|a, b| a.1.total_cmp(&b.1));
+    match scored.as_slice() {
+        [] => Win::Nobody,
+        [(i, _)] => Win::Run(*i),
+        [(i, first), (_, second), ..] if second - first > margin => Win::Run(*i),
+        _ => Win::Tie,
+    }
+}
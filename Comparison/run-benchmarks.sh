@@ -243,7 +243,7 @@ if $RUN_FM_ED && $RUN_NUCLEO && $RUN_RF_WR && $RUN_RF_PR; then
 
     awk -v has_ifrit="$RUN_IFRIT" -v has_sw="$RUN_FM_SW" -v has_contains="$RUN_CONTAINS" '
 BEGIN {
-    n_cats = split("exact_symbol exact_name exact_isin prefix typo substring multi_word symbol_spaces abbreviation", cats, " ")
+    n_cats = split("exact_symbol exact_name exact_isin prefix typo substring multi_word symbol_spaces abbreviation adversarial", cats, " ")
 }
 
 # Extract total median from lines like:
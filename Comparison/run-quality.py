@@ -17,12 +17,19 @@ If no matcher flags given, all are run except Ifrit (very slow, must be explicit
   --rf-wratio    Run RapidFuzz WRatio only
   --rf-partial   Run RapidFuzz PartialRatio only
   --skip-build   Skip building harnesses (assume pre-built)
+  --results-dir DIR  Also write each matcher's results to DIR/<matcher>.json in
+                     the unified results schema (see harness/src/results.rs),
+                     for the `report` binary
+  --run LABEL    Run label recorded in those files (a branch or commit)
 """
 
 import subprocess
 import sys
 import os
 import csv
+import math
+import random
+import tempfile
 from collections import defaultdict
 
 # --- Parse flags ---
@@ -30,6 +37,20 @@ MATCHER_FLAGS = {'--fm', '--fm-ed', '--fm-sw', '--nucleo', '--rf', '--rf-wratio'
 given_flags = set(arg for arg in sys.argv[1:] if arg in MATCHER_FLAGS)
 SKIP_BUILD = '--skip-build' in sys.argv
 
+
+def _flag_value(flag):
+    if flag not in sys.argv:
+        return None
+    idx = sys.argv.index(flag)
+    if idx + 1 >= len(sys.argv):
+        print(f"Error: {flag} requires a value")
+        sys.exit(1)
+    return sys.argv[idx + 1]
+
+
+RESULTS_DIR = _flag_value('--results-dir')
+RUN_LABEL = _flag_value('--run') or ''
+
 if not given_flags:
     # Default: all except Ifrit (very slow)
     RUN_FM_ED = True
@@ -57,10 +78,55 @@ QUERIES_FILE = os.path.join(REPO_ROOT, "Resources", "queries.tsv")
 
 # Binary paths (after building)
 FM_BIN = os.path.join(SCRIPT_DIR, "quality-fuzzymatch", ".build", "release", "quality-fuzzymatch")
-NUCLEO_BIN = os.path.join(SCRIPT_DIR, "quality-nucleo", "target", "release", "quality-nucleo")
+NUCLEO_BIN = os.path.join(SCRIPT_DIR, "target", "release", "quality-nucleo")
 IFRIT_BIN = os.path.join(SCRIPT_DIR, "quality-ifrit", ".build", "release", "quality-ifrit")
 RAPIDFUZZ_BIN = os.path.join(SCRIPT_DIR, "quality-rapidfuzz", "quality-rapidfuzz")
 FZF_BIN = "fzf"
+QUERY_GEN_BIN = os.path.join(SCRIPT_DIR, "target", "release", "query-gen")
+CORPUS_CHECK_BIN = os.path.join(SCRIPT_DIR, "target", "release", "corpus-check")
+
+# Results tag of each matcher, as in the unified results files
+TAGS = {
+    'FuzzyMatcher': 'fuzzymatch',
+    'FM(SW)': 'fuzzymatch-sw',
+    'nucleo': 'nucleo',
+    'RF(WRatio)': 'rapidfuzz-wratio',
+    'RF(Partial)': 'rapidfuzz-partial',
+    'fzf': 'fzf',
+    'Ifrit': 'ifrit',
+}
+
+# What each matcher can do, by tag; mirrors harness/src/capabilities.rs.
+# A matcher without scores is shown by rank alone, and one without
+# normalization or multi-atom matching misses accented or reordered
+# queries by design rather than by bug.
+CAPABILITIES = {
+    'fuzzymatch': {'scores': True, 'indices': False, 'normalization': True, 'multi_atom': False},
+    'fuzzymatch-sw': {'scores': True, 'indices': False, 'normalization': True, 'multi_atom': True},
+    'nucleo': {'scores': True, 'indices': True, 'normalization': True, 'multi_atom': True},
+    'rapidfuzz-wratio': {'scores': True, 'indices': False, 'normalization': False, 'multi_atom': False},
+    'rapidfuzz-partial': {'scores': True, 'indices': False, 'normalization': False, 'multi_atom': False},
+    'fzf': {'scores': False, 'indices': False, 'normalization': True, 'multi_atom': True},
+    'ifrit': {'scores': True, 'indices': False, 'normalization': False, 'multi_atom': False},
+}
+
+# The capabilities this comparison depends on, as the header states them
+CAPABILITY_NAMES = {
+    'scores': 'scores',
+    'normalization': 'diacritic folding',
+    'multi_atom': 'word-by-word matching',
+}
+
+
+def supports(name, capability):
+    """Whether matcher `name` has `capability`; unknown matchers are assumed to."""
+    return CAPABILITIES.get(TAGS.get(name, name), {}).get(capability, True)
+
+
+def lacking(name):
+    """Capabilities matcher `name` lacks, described for a reader."""
+    return [label for capability, label in CAPABILITY_NAMES.items()
+            if not supports(name, capability)]
 
 # Category display order — maps TSV category names to display names
 CATEGORY_MAP = {
@@ -73,6 +139,8 @@ CATEGORY_MAP = {
     "multi_word": "Multi-word descriptive",
     "symbol_spaces": "Symbol with spaces",
     "abbreviation": "Abbreviation (first letters)",
+    "long_text": "Long text / description",
+    "adversarial": "Adversarial / worst case",
 }
 
 CATEGORY_ORDER = [
@@ -85,6 +153,8 @@ CATEGORY_ORDER = [
     "multi_word",
     "symbol_spaces",
     "abbreviation",
+    "long_text",
+    "adversarial",
 ]
 
 
@@ -111,6 +181,31 @@ def load_queries(path):
     return queries
 
 
+def fingerprint(config):
+    """What produced a run: git revision, dirty flag and matcher config,
+    hashed the way harness/src/fingerprint.rs hashes it."""
+    import hashlib
+
+    def git(*args):
+        try:
+            out = subprocess.run(["git", "-C", SCRIPT_DIR, *args],
+                                 capture_output=True, text=True, check=True)
+            return out.stdout.strip()
+        except (FileNotFoundError, subprocess.CalledProcessError):
+            return None
+
+    sha = git("rev-parse", "HEAD")
+    status = git("status", "--porcelain") if sha else None
+    lines = ''.join(f"{k}={config[k]}\n" for k in sorted(config))
+    return {
+        'git_sha': sha,
+        'git_dirty': None if status is None else status != '',
+        'features': [],
+        'config': config,
+        'config_sha256': hashlib.sha256(lines.encode()).hexdigest(),
+    }
+
+
 def build_all():
     """Build selected harnesses."""
     if RUN_FM_ED or RUN_FM_SW:
@@ -149,17 +244,78 @@ def build_all():
         )
         print("  done")
 
-    if RUN_FZF:
-        # Check fzf is available
-        try:
-            subprocess.run([FZF_BIN, "--version"], capture_output=True, check=True)
-            print("fzf found")
-        except (FileNotFoundError, subprocess.CalledProcessError):
-            print("WARNING: fzf not found in PATH, fzf results will be empty")
+    print("Building query-gen and corpus-check for corpus analysis (release)...")
+    try:
+        subprocess.run(
+            ["cargo", "build", "--release", "-p", "query-gen", "-p", "corpus-check"],
+            cwd=SCRIPT_DIR,
+            check=True, capture_output=True
+        )
+        print("  done")
+    except (FileNotFoundError, subprocess.CalledProcessError):
+        print("  failed; query difficulty and near duplicates will be left out")
 
     print()
 
 
+def load_difficulty():
+    """Rate every query with `query-gen --difficulty` (see harness::difficulty).
+
+    Returns (q, f) -> {'score', 'char_share', 'lookalikes', 'accepted'}, or an
+    empty dict when query-gen has not been built.
+    """
+    if not os.path.isfile(QUERY_GEN_BIN):
+        return {}
+    out = subprocess.run(
+        [QUERY_GEN_BIN, "--difficulty", QUERIES_FILE, "--tsv", TSV_FILE],
+        capture_output=True, text=True, check=True
+    )
+    difficulty = {}
+    for line in out.stdout.splitlines()[1:]:
+        q, f, _cat, char_share, lookalikes, accepted, score = line.split('\t')
+        difficulty[(q, f)] = {
+            'score': float(score),
+            'char_share': float(char_share),
+            'lookalikes': int(lookalikes),
+            'accepted': int(accepted) if accepted else None,
+        }
+    return difficulty
+
+
+def load_near_duplicates():
+    """Cluster near-duplicate corpus names with `corpus-check --near-duplicates`
+    (see harness::near_duplicates).
+
+    Returns (lowercased name -> cluster key, cluster key -> lowercased member
+    names), or empty dicts when corpus-check has not been built. Results are
+    matched by name because not every matcher reports corpus lines.
+    """
+    if not os.path.isfile(CORPUS_CHECK_BIN):
+        return {}, {}
+    with tempfile.NamedTemporaryFile(suffix='.tsv') as out:
+        subprocess.run(
+            [CORPUS_CHECK_BIN, "--near-duplicates", "--tsv", TSV_FILE, "--out", out.name],
+            capture_output=True, check=True
+        )
+        with open(out.name) as f:
+            rows = [line.rstrip('\n').split('\t') for line in f][1:]
+    cluster_of = {}
+    members = defaultdict(list)
+    for _line, cluster, _symbol, name, _isin in rows:
+        cluster_of.setdefault(name.lower(), cluster)
+        members[cluster].append(name.lower())
+    return cluster_of, members
+
+
+def fzf_available():
+    """Whether the fzf binary can be run."""
+    try:
+        subprocess.run([FZF_BIN, "--version"], capture_output=True, check=True)
+        return True
+    except (FileNotFoundError, subprocess.CalledProcessError):
+        return False
+
+
 def run_stdin_tool(binary, queries, timeout=300, extra_args=None):
     """Run a stdin-based quality harness and parse results."""
     input_data = '\n'.join(f'{q}\t{f}' for q, f, *_ in queries) + '\n'
@@ -178,6 +334,14 @@ def run_stdin_tool(binary, queries, timeout=300, extra_args=None):
         if not line:
             continue
         parts = line.split('\t')
+        # quality-nucleo appends provenance: `line=N`, then one
+        # `column=value` field per metadata column
+        provenance = {}
+        for i in range(6, len(parts)):
+            if parts[i].startswith('line='):
+                provenance = dict(p.split('=', 1) for p in parts[i:] if '=' in p)
+                parts = parts[:i]
+                break
         if len(parts) >= 6:
             key = (parts[0], parts[1])
             entry = {
@@ -189,6 +353,9 @@ def run_stdin_tool(binary, queries, timeout=300, extra_args=None):
             # FuzzyMatcher has a 'kind' field at index 4
             if len(parts) >= 7:
                 entry['kind'] = parts[4]
+            if provenance:
+                entry['line'] = int(provenance.pop('line'))
+                entry['metadata'] = provenance
             results[key].append(entry)
     return results
 
@@ -268,6 +435,161 @@ def check_ground_truth(results, key, expected, top_n):
     return False
 
 
+# Ranking metrics are taken over each query's first RANK_DEPTH results, and
+# their confidence intervals from BOOTSTRAP_RESAMPLES resamples of the judged
+# queries. The seed is fixed so a rerun on the same results prints the same
+# intervals.
+RANK_DEPTH = 10
+BOOTSTRAP_RESAMPLES = 2000
+BOOTSTRAP_SEED = 0
+CONFIDENCE = 0.95
+
+
+def first_relevant_rank(results, key, expected):
+    """0-based rank of the first of the top RANK_DEPTH results whose name contains
+    expected_name (case-insensitive), or None."""
+    expected_lower = expected.lower()
+    for rank, entry in enumerate(results.get(key, [])[:RANK_DEPTH]):
+        if expected_lower in entry.get('name', '').lower():
+            return rank
+    return None
+
+
+def ranking_scores(results, entries):
+    """Per-query Top-1, reciprocal rank and NDCG for `entries` of (q, f, expected).
+
+    With one relevant result per query, as with expected names, NDCG is the
+    gain of that result's rank, 1 / log2(rank + 2), as harness::metrics has it.
+    """
+    scores = {'Top-1': [], 'MRR': [], 'NDCG': []}
+    for q, f, expected in entries:
+        rank = first_relevant_rank(results, (q, f), expected)
+        scores['Top-1'].append(1.0 if rank == 0 else 0.0)
+        scores['MRR'].append(0.0 if rank is None else 1.0 / (rank + 1))
+        scores['NDCG'].append(0.0 if rank is None else 1.0 / math.log2(rank + 2))
+    return scores
+
+
+def bootstrap_means(series, resamples=BOOTSTRAP_RESAMPLES, seed=BOOTSTRAP_SEED):
+    """The mean of each of `series`, equally long per-query value lists, with the
+    bounds of its CONFIDENCE bootstrap interval as (mean, low, high).
+
+    Every series is resampled with the same queries each time, so intervals on
+    differences between paired series come out right.
+    """
+    n = len(series[0]) if series else 0
+    if n == 0:
+        return [None for _ in series]
+    rng = random.Random(seed)
+    means = [[] for _ in series]
+    for _ in range(resamples):
+        picks = [rng.randrange(n) for _ in range(n)]
+        for values, resampled in zip(series, means):
+            resampled.append(sum(values[i] for i in picks) / n)
+    tail = (1.0 - CONFIDENCE) / 2.0
+    intervals = []
+    for values, resampled in zip(series, means):
+        resampled.sort()
+        low = resampled[int(tail * resamples)]
+        high = resampled[min(resamples - 1, int((1.0 - tail) * resamples))]
+        intervals.append((sum(values) / n, low, high))
+    return intervals
+
+
+def fmt_interval(interval, signed=False):
+    """`0.812 [0.771, 0.850]`, or with `signed` `+0.012 [-0.004, +0.027]`."""
+    mean, low, high = interval
+    spec = '+.3f' if signed else '.3f'
+    return f"{mean:{spec}} [{low:{spec}}, {high:{spec}}]"
+
+
+# Judged queries listed, hardest first, under QUERY DIFFICULTY
+HARDEST_SHOWN = 15
+
+
+def print_difficulty(difficulty, judged, query_meta, all_results, tool_names, sep):
+    """Ground truth hit rates weighted by query difficulty, and how each matcher
+    fared on the hardest judged queries."""
+    rated = [(q, f, expected) for q, f, expected in judged if (q, f) in difficulty]
+    if not rated:
+        return
+
+    def top_n(q, f):
+        return 5 if query_meta[(q, f)][0] in ('typo', 'prefix', 'abbreviation') else 1
+
+    def hit(name, q, f, expected):
+        return check_ground_truth(all_results[name], (q, f), expected, top_n(q, f))
+
+    print(f"\n{sep}")
+    print(f"QUERY DIFFICULTY — {len(rated)} judged queries rated from the corpus alone")
+    print(sep)
+    print()
+    weights = [difficulty[(q, f)]['score'] for q, f, _ in rated]
+    total_weight = sum(weights) or 1.0
+    print(f"{'Matcher':<20} {'Hit rate':>10} {'Weighted by difficulty':>24}")
+    print("─" * 56)
+    for name in tool_names:
+        hits = [hit(name, q, f, expected) for q, f, expected in rated]
+        plain = sum(hits) / len(rated)
+        weighted = sum(w for w, h in zip(weights, hits) if h) / total_weight
+        print(f"{name:<20} {plain:>9.1%} {weighted:>23.1%}")
+    print()
+    print(f"Hardest {min(HARDEST_SHOWN, len(rated))} (✓ when the ground truth check passes):")
+    hardest = sorted(rated, key=lambda e: -difficulty[(e[0], e[1])]['score'])[:HARDEST_SHOWN]
+    header = f"  {'Query':<24} {'Field':<7} {'Score':>5} {'Alike':>6} {'Accepts':>7}"
+    for name in tool_names:
+        header += f"  {name[:12]:>12}"
+    print(header)
+    for q, f, expected in hardest:
+        d = difficulty[(q, f)]
+        accepted = '' if d['accepted'] is None else d['accepted']
+        row = f"  {q[:24]:<24} {f:<7} {d['score']:>5.2f} {d['lookalikes']:>6} {accepted:>7}"
+        for name in tool_names:
+            row += f"  {'✓' if hit(name, q, f, expected) else '✗':>12}"
+        print(row)
+    print()
+    print("Difficulty averages the share of rows holding the query's rarest character and its")
+    print("lookalikes: rows starting with the query that the expected name does not accept.")
+    print("Accepts counts the rows the expected name accepts; a hit on a query with many says little.")
+
+
+# Near-duplicate misses listed per matcher
+NEAR_MISSES_SHOWN = 10
+
+
+def print_near_duplicate_misses(cluster_of, cluster_members, judged, query_meta,
+                                all_results, tool_names, sep):
+    """Ground truth misses whose top result is a near duplicate of an accepted row:
+    the matcher found the right family of names and picked the wrong twin."""
+    print(f"\n{sep}")
+    print("NEAR-DUPLICATE MISSES — top result clustered with an accepted row")
+    print(sep)
+    print()
+    for name in tool_names:
+        res = all_results[name]
+        misses = []
+        explained = []
+        for q, f, expected in judged:
+            top_n = 5 if query_meta[(q, f)][0] in ('typo', 'prefix', 'abbreviation') else 1
+            if check_ground_truth(res, (q, f), expected, top_n):
+                continue
+            misses.append((q, f))
+            results = res.get((q, f), [])
+            if not results:
+                continue
+            top = results[0].get('name', '')
+            cluster = cluster_of.get(top.lower())
+            twin = next((m for m in cluster_members.get(cluster, [])
+                         if expected.lower() in m), None)
+            if twin is not None:
+                explained.append((q, top, twin))
+        print(f"{name}: {len(explained)} of {len(misses)} misses ranked a near duplicate first")
+        for q, top, twin in explained[:NEAR_MISSES_SHOWN]:
+            print(f"  {q[:24]:<24} got {top[:40]!r} for {twin[:40]!r}")
+    print()
+    print("A near duplicate is within a normalized edit distance of 0.1 of the cluster's first row.")
+
+
 def fmt_result(r, col_w):
     """Format a single result entry for display."""
     score = r.get('score', '-')
@@ -291,6 +613,13 @@ def main():
 
     queries = load_queries(QUERIES_FILE)
 
+    # Skip a matcher that cannot run here rather than report an empty column
+    global RUN_FZF
+    skipped = []
+    if RUN_FZF and not fzf_available():
+        RUN_FZF = False
+        skipped.append('fzf (not found in PATH)')
+
     enabled = []
     if RUN_FM_ED: enabled.append('FuzzyMatcher')
     if RUN_FM_SW: enabled.append('FM(SW)')
@@ -303,6 +632,8 @@ def main():
     print(f"Loaded {len(queries)} queries")
     print(f"Corpus: {TSV_FILE}")
     print(f"Running: {', '.join(enabled)}")
+    if skipped:
+        print(f"Skipped: {', '.join(skipped)}")
     print()
 
     # Build selected harnesses
@@ -358,6 +689,19 @@ def main():
     else:
         instruments = []
 
+    print("Rating query difficulty...", flush=True)
+    difficulty = load_difficulty()
+    if difficulty:
+        print(f"  Rated {len(difficulty)} queries")
+    else:
+        print("  Skipped: query-gen not built")
+    print("Clustering near-duplicate names...", flush=True)
+    cluster_of, cluster_members = load_near_duplicates()
+    if cluster_members:
+        print(f"  {len(cluster_members)} clusters covering {len(cluster_of)} distinct names")
+    else:
+        print("  Skipped: corpus-check not built")
+
     print()
 
     # ─── Save individual results to /tmp for parallel collation ───
@@ -365,15 +709,7 @@ def main():
 
     def _save_results_to_tmp(name, results, queries):
         """Save per-matcher results to /tmp/quality-{name}-latest.json."""
-        tag = {
-            'FuzzyMatcher': 'fuzzymatch',
-            'FM(SW)': 'fuzzymatch-sw',
-            'nucleo': 'nucleo',
-            'RF(WRatio)': 'rapidfuzz-wratio',
-            'RF(Partial)': 'rapidfuzz-partial',
-            'fzf': 'fzf',
-            'Ifrit': 'ifrit',
-        }.get(name, name.lower().replace(' ', '-'))
+        tag = TAGS.get(name, name.lower().replace(' ', '-'))
         path = f"/tmp/quality-{tag}-latest.json"
         data = {}
         for (q, f), entries in results.items():
@@ -381,6 +717,47 @@ def main():
             data[key] = entries
         with open(path, 'w') as fp:
             _json.dump(data, fp)
+        if RESULTS_DIR:
+            _save_unified_results(tag, results, queries)
+
+    def _score(value):
+        try:
+            return float(value)
+        except (TypeError, ValueError):
+            return None
+
+    def _save_unified_results(tag, results, queries):
+        """Write results in the schema the `report` binary reads."""
+        os.makedirs(RESULTS_DIR, exist_ok=True)
+        records = []
+        for q, f, cat, expected in queries:
+            record = {
+                'query': q,
+                'field': f,
+                'category': cat,
+                'expected_name': expected,
+                'frequency': 1,
+                'results': [
+                    {'symbol': e.get('symbol', ''), 'name': e.get('name', ''),
+                     'score': _score(e.get('score')),
+                     **{k: e[k] for k in ('line', 'metadata') if k in e}}
+                    for e in results.get((q, f), [])
+                ],
+            }
+            if (q, f) in difficulty:
+                record['difficulty'] = difficulty[(q, f)]
+            records.append(record)
+        path = os.path.join(RESULTS_DIR, f"{tag}.json")
+        with open(path, 'w') as fp:
+            _json.dump({
+                'schema_version': 1,
+                'backend': tag,
+                'run': RUN_LABEL,
+                'corpus': os.path.basename(TSV_FILE),
+                'corpus_sha256': None,
+                'fingerprint': fingerprint({'matcher': tag, 'queries': os.path.basename(QUERIES_FILE)}),
+                'queries': records,
+            }, fp, indent=2)
 
     if RUN_FM_ED: _save_results_to_tmp('FuzzyMatcher', fm_results, queries)
     if RUN_FM_SW: _save_results_to_tmp('FM(SW)', fm_sw_results, queries)
@@ -416,6 +793,12 @@ def main():
         print(f"Corpus: {len(instruments)} instruments")
     print(f"Queries: {len(queries)}")
     print(f"Matchers: {', '.join(tool_names)}")
+    for name in tool_names:
+        missing = lacking(name)
+        if missing:
+            print(f"  {name}: no {', no '.join(missing)}")
+    if skipped:
+        print(f"Skipped: {', '.join(skipped)}")
     print()
 
     # Summary stats
@@ -455,7 +838,7 @@ def main():
             for name in tool_names:
                 matches = tool_results[name]
                 if i < len(matches):
-                    if name == 'fzf':
+                    if not supports(name, 'scores'):
                         sym = matches[i].get('symbol', '')[:10]
                         nm = matches[i].get('name', '')
                         if len(nm) > 20:
@@ -621,6 +1004,49 @@ def main():
         print(f"Note: {len(queries) - total_evaluated} queries skipped (_SKIP_): exact_symbol, symbol_spaces, short prefix.")
         print(f"Typo, prefix, and abbreviation categories use top-5 (correct result in first 5); all others use top-1.")
 
+        # Ranking metrics, with how far a few hundred queries can be trusted
+        print(f"\n{sep}")
+        print(f"RANKING METRICS — mean [{CONFIDENCE:.0%} bootstrap CI] over {total_evaluated} judged queries")
+        print(sep)
+        print()
+        judged = [entry for cat in all_category_keys for entry in eval_by_category.get(cat, [])]
+        metric_names = ['Top-1', 'MRR', 'NDCG']
+        scores = {name: ranking_scores(all_results[name], judged) for name in tool_names}
+        reference = tool_names[0]
+        series = []
+        for name in tool_names:
+            for metric in metric_names:
+                series.append(scores[name][metric])
+                if name != reference:
+                    series.append([b - a for a, b in zip(scores[reference][metric], scores[name][metric])])
+        intervals = iter(bootstrap_means(series))
+        labels = {'Top-1': 'Top-1', 'MRR': f'MRR@{RANK_DEPTH}', 'NDCG': f'NDCG@{RANK_DEPTH}'}
+        print(f"{'Matcher':<20} " + "  ".join(f"{labels[m]:>22}" for m in metric_names))
+        print("─" * (20 + 24 * len(metric_names)))
+        differences = []
+        for name in tool_names:
+            row = f"{name:<20} "
+            for metric in metric_names:
+                row += f"  {fmt_interval(next(intervals)):>22}"
+                if name != reference:
+                    differences.append((name, metric, next(intervals)))
+            print(row)
+        if differences:
+            print()
+            print(f"Paired difference from {reference} (significant when the interval leaves out 0):")
+            for name, metric, interval in differences:
+                _, low, high = interval
+                verdict = 'significant' if low > 0 or high < 0 else 'within noise'
+                print(f"  {name + ' ' + metric:<34} {fmt_interval(interval, signed=True):>26}  {verdict}")
+        print()
+        print(f"Each query counts its expected result at its rank within the top {RANK_DEPTH}, whatever its category.")
+
+        if difficulty:
+            print_difficulty(difficulty, judged, query_meta, all_results, tool_names, sep)
+        if cluster_members:
+            print_near_duplicate_misses(cluster_of, cluster_members, judged, query_meta,
+                                        all_results, tool_names, sep)
+
     # ─── Overall summary ───
 
     print(f"\n{sep}")
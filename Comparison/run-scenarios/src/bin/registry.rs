@@ -0,0 +1,15 @@
+//! The harness's built-in backends behind `harness::registry`'s command
+//! line: `list`, `bench` and `serve`.
+//!
+//! Usage: registry list | bench BACKEND [flags] | serve BACKEND [--preset NAME] CORPUS
+//!
+//! A campaign's `[[config]]` tables only vary nucleo's matching options. To
+//! run nucleo under a preset, or out of process beside the in-process run,
+//! name this binary in a `[[registered]]` table, as an out-of-tree crate's
+//! registry binary would be.
+
+use harness::registry::Registry;
+
+fn main() {
+    Registry::new().main();
+}
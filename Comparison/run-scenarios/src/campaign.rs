@@ -0,0 +1,675 @@
+//! Campaign files: which corpora, query sets, backends and matcher
+//! configurations to run against each other.
+//!
+//! ```toml
+//! name = "nightly"
+//! iterations = 5
+//! backends = ["nucleo", "fuzzymatch-sw"]
+//! histogram_bin_width = 10   # optional, score histogram bins
+//!
+//! [category_iterations]      # optional, timed passes per query category
+//! typo = 15
+//! multi_word = 12
+//! exact_symbol = 2
+//!
+//! [[corpus]]
+//! name = "export"
+//! path = "../Resources/instruments-export.tsv"
+//! map = "symbol=ticker,name=title"   # optional, as bench-nucleo --map
+//! dedup = true                       # optional
+//!
+//! [[queries]]
+//! name = "default"
+//! path = "../Resources/queries.tsv"
+//!
+//! [[external]]                       # optional, a backend run as a command
+//! name = "fuzzymatch-sw"
+//! command = ["../quality-fuzzymatch/.build/release/quality-fuzzymatch", "--sw"]
+//!
+//! [[registered]]                     # optional, a backend of a registry binary
+//! bin = "../../acme-bench/target/release/acme-bench"
+//! backend = "acme"
+//! name = "acme-search"               # optional, defaults to `backend`
+//! preset = "autocomplete"            # optional, passed as --preset
+//!
+//! [[config]]
+//! name = "default"
+//! case = "ignore"          # ignore | smart | respect
+//! normalization = "smart"  # smart | never
+//! atom = "fuzzy"           # fuzzy | substring | prefix | postfix | exact
+//! prefer_prefix = false
+//!
+//! [limits.nucleo]          # optional, per backend
+//! time_limit_s = 900       # wall-clock time per scenario
+//! memory_mb = 4096         # resident set of the runner
+//!
+//! [warmup.fuzzymatch-sw]    # optional, per backend
+//! queries = 20              # untimed queries first, or "all"
+//! index = true              # read the corpus (or columns) through first
+//! discard_first = true      # run one extra timed pass and drop it
+//! ```
+//!
+//! Relative paths are resolved against the campaign file's directory, and
+//! may use Windows separators.
+//! Without `[[corpus]]` or `[[queries]]` tables the bundled export and query
+//! set are used, without `[[config]]` a single default configuration
+//! matching `bench-nucleo`, and without `backends` just nucleo. A backend
+//! without `[limits]` runs unlimited. A backend without `[warmup]`, or
+//! keys it leaves out, gets the defaults of `harness::warmup`: nucleo runs
+//! every query once untimed, and an external backend has its corpus file
+//! read into the page cache. Unknown keys are errors.
+//!
+//! Some categories are much noisier than others: a typo or multi-word
+//! query's time depends on how far the matcher's search goes, while an
+//! exact symbol lookup barely varies. `[category_iterations]` gives a
+//! category its own number of timed passes, so a campaign's time goes
+//! where the medians need it. Categories it leaves out run `iterations`
+//! passes. Keys must be categories from `harness::queries::CATEGORY_ORDER`.
+//!
+//! An `[[external]]` backend is any command that speaks the quality
+//! harnesses' line protocol (see `harness::subprocess`): it is run once per
+//! corpus and query set with the corpus path appended, in a child process
+//! of its own. The `[[config]]` tables and a corpus's `map` and `dedup` are
+//! nucleo settings and do not reach it. A first word containing `/` is
+//! resolved against the campaign file's directory, and any other is looked
+//! up on `PATH`.
+//!
+//! A `[[registered]]` backend is one an out-of-tree crate registered with
+//! `harness::registry`, named by the crate's binary and the backend's
+//! registered name. Loading the campaign runs `BIN list` and rejects a
+//! backend the binary does not register; the scenarios then run
+//! `BIN serve BACKEND` as an external backend in every other respect.
+
+use harness::corpus::FieldMap;
+use harness::input;
+use harness::limits::Limits;
+use harness::queries::CATEGORY_ORDER;
+use harness::resource_path;
+use harness::warmup::Warmup;
+use nucleo_matcher::pattern::{AtomKind, CaseMatching, Normalization};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::process::Command;
+use std::time::Duration;
+use toml::{Table, Value};
+
+/// Backends the runner can execute in-process.
+pub const BACKENDS: [&str; 1] = ["nucleo"];
+
+/// The configuration name of an external backend's scenarios, which the
+/// `[[config]]` tables do not apply to.
+pub const EXTERNAL_CONFIG: &str = "cli";
+
+pub struct Campaign {
+    pub name: String,
+    pub iterations: usize,
+    /// Timed passes of each category with its own count, instead of
+    /// `iterations`.
+    pub category_iterations: BTreeMap<String, usize>,
+    /// Width of the bins in `histograms.tsv`, on each backend's own scale.
+    pub histogram_bin_width: f64,
+    pub corpora: Vec<CorpusSpec>,
+    pub query_sets: Vec<QuerySetSpec>,
+    pub backends: Vec<String>,
+    pub configs: Vec<MatcherConfig>,
+    pub externals: Vec<ExternalSpec>,
+    /// Limits on each scenario, by backend.
+    pub limits: BTreeMap<String, Limits>,
+    /// Warm-up policy of each backend with a `[warmup]` table.
+    pub warmup: BTreeMap<String, Warmup>,
+}
+
+pub struct CorpusSpec {
+    pub name: String,
+    pub path: String,
+    /// The `map` value as written, kept for fingerprints.
+    pub map_spec: String,
+    pub map: FieldMap,
+    pub dedup: bool,
+}
+
+/// A backend run as a child process.
+pub struct ExternalSpec {
+    pub name: String,
+    /// Program and arguments; the corpus path is appended.
+    pub command: Vec<String>,
+}
+
+pub struct QuerySetSpec {
+    pub name: String,
+    pub path: String,
+}
+
+/// One named nucleo configuration.
+#[derive(Clone, Debug)]
+pub struct MatcherConfig {
+    pub name: String,
+    pub case: String,
+    pub normalization: String,
+    pub atom: String,
+    pub prefer_prefix: bool,
+}
+
+impl Default for MatcherConfig {
+    fn default() -> Self {
+        MatcherConfig {
+            name: "default".to_string(),
+            case: "ignore".to_string(),
+            normalization: "smart".to_string(),
+            atom: "fuzzy".to_string(),
+            prefer_prefix: false,
+        }
+    }
+}
+
+impl MatcherConfig {
+    pub fn case_matching(&self) -> CaseMatching {
+        match self.case.as_str() {
+            "smart" => CaseMatching::Smart,
+            "respect" => CaseMatching::Respect,
+            _ => CaseMatching::Ignore,
+        }
+    }
+
+    pub fn normalization(&self) -> Normalization {
+        match self.normalization.as_str() {
+            "never" => Normalization::Never,
+            _ => Normalization::Smart,
+        }
+    }
+
+    pub fn atom_kind(&self) -> AtomKind {
+        match self.atom.as_str() {
+            "substring" => AtomKind::Substring,
+            "prefix" => AtomKind::Prefix,
+            "postfix" => AtomKind::Postfix,
+            "exact" => AtomKind::Exact,
+            _ => AtomKind::Fuzzy,
+        }
+    }
+
+    /// The settings as fingerprint entries.
+    pub fn entries(&self) -> BTreeMap<String, String> {
+        [
+            ("case", self.case.clone()),
+            ("normalization", self.normalization.clone()),
+            ("atom", self.atom.clone()),
+            ("prefer_prefix", self.prefer_prefix.to_string()),
+        ]
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), value))
+        .collect()
+    }
+}
+
+impl Campaign {
+    pub fn load(path: &str) -> Result<Campaign, String> {
+        let text = input::read_to_string(path).map_err(|e| format!("{path}: {e}"))?;
+        let table: Table = text.parse().map_err(|e: toml::de::Error| e.to_string())?;
+        let base = Path::new(path).parent().unwrap_or(Path::new("."));
+        let resolve = |p: &str| {
+            base.join(input::native_path(p))
+                .to_string_lossy()
+                .to_string()
+        };
+
+        let mut campaign = Campaign {
+            name: Path::new(path)
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            iterations: 5,
+            category_iterations: BTreeMap::new(),
+            histogram_bin_width: 10.0,
+            corpora: Vec::new(),
+            query_sets: Vec::new(),
+            backends: vec!["nucleo".to_string()],
+            configs: Vec::new(),
+            externals: Vec::new(),
+            limits: BTreeMap::new(),
+            warmup: BTreeMap::new(),
+        };
+        let mut warmup = None;
+        for (key, value) in &table {
+            match key.as_str() {
+                "name" => campaign.name = string(key, value)?,
+                "iterations" => {
+                    campaign.iterations = value
+                        .as_integer()
+                        .and_then(|n| usize::try_from(n).ok())
+                        .filter(|n| *n > 0)
+                        .ok_or_else(|| format!("{key} must be a positive integer"))?
+                }
+                "category_iterations" => {
+                    let categories = value.as_table().ok_or_else(|| {
+                        "category_iterations must be a table of categories".to_string()
+                    })?;
+                    for (category, count) in categories {
+                        if !CATEGORY_ORDER.contains(&category.as_str()) {
+                            return Err(format!(
+                                "unknown category `{category}` in category_iterations (known: {})",
+                                CATEGORY_ORDER.join(", ")
+                            ));
+                        }
+                        let count = count
+                            .as_integer()
+                            .and_then(|n| usize::try_from(n).ok())
+                            .filter(|n| *n > 0)
+                            .ok_or_else(|| {
+                                format!("category_iterations.{category} must be a positive integer")
+                            })?;
+                        campaign.category_iterations.insert(category.clone(), count);
+                    }
+                }
+                "histogram_bin_width" => {
+                    campaign.histogram_bin_width = value
+                        .as_float()
+                        .or_else(|| value.as_integer().map(|n| n as f64))
+                        .filter(|w| *w > 0.0)
+                        .ok_or_else(|| format!("{key} must be a positive number"))?
+                }
+                "backends" => {
+                    campaign.backends = array(key, value)?
+                        .iter()
+                        .map(|v| string(key, v))
+                        .collect::<Result<_, _>>()?;
+                }
+                "corpus" => {
+                    for entry in array(key, value)? {
+                        let entry = fields(key, entry, &["name", "path", "map", "dedup"])?;
+                        let path = string("corpus.path", required(entry, "corpus", "path")?)?;
+                        let map_spec = match entry.get("map") {
+                            Some(v) => string("corpus.map", v)?,
+                            None => String::new(),
+                        };
+                        let map = if map_spec.is_empty() {
+                            FieldMap::default()
+                        } else {
+                            map_spec.parse()?
+                        };
+                        campaign.corpora.push(CorpusSpec {
+                            name: name(entry, "corpus", &path)?,
+                            path: resolve(&path),
+                            map_spec,
+                            map,
+                            dedup: boolean(entry, "corpus.dedup")?,
+                        });
+                    }
+                }
+                "queries" => {
+                    for entry in array(key, value)? {
+                        let entry = fields(key, entry, &["name", "path"])?;
+                        let path = string("queries.path", required(entry, "queries", "path")?)?;
+                        campaign.query_sets.push(QuerySetSpec {
+                            name: name(entry, "queries", &path)?,
+                            path: resolve(&path),
+                        });
+                    }
+                }
+                "external" => {
+                    for entry in array(key, value)? {
+                        let entry = fields(key, entry, &["name", "command"])?;
+                        let name = string("external.name", required(entry, "external", "name")?)?;
+                        if BACKENDS.contains(&name.as_str()) {
+                            return Err(format!(
+                                "external backend `{name}` shadows a built-in one"
+                            ));
+                        }
+                        let mut command: Vec<String> =
+                            array("external.command", required(entry, "external", "command")?)?
+                                .iter()
+                                .map(|v| string("external.command", v))
+                                .collect::<Result<_, _>>()?;
+                        match command.first_mut() {
+                            Some(program) if program.contains(['/', '\\']) => {
+                                *program = resolve(program)
+                            }
+                            Some(_) => {}
+                            None => return Err(format!("external.command of `{name}` is empty")),
+                        }
+                        campaign.externals.push(ExternalSpec { name, command });
+                    }
+                }
+                "registered" => {
+                    for entry in array(key, value)? {
+                        let entry = fields(key, entry, &["name", "bin", "backend", "preset"])?;
+                        let backend = string(
+                            "registered.backend",
+                            required(entry, "registered", "backend")?,
+                        )?;
+                        let name = match entry.get("name") {
+                            Some(v) => string("registered.name", v)?,
+                            None => backend.clone(),
+                        };
+                        if BACKENDS.contains(&name.as_str()) {
+                            return Err(format!(
+                                "registered backend `{name}` shadows a built-in one; give it a `name`"
+                            ));
+                        }
+                        let mut bin =
+                            string("registered.bin", required(entry, "registered", "bin")?)?;
+                        if bin.contains(['/', '\\']) {
+                            bin = resolve(&bin);
+                        }
+                        let listed = registered_backends(&bin)?;
+                        if !listed.contains(&backend) {
+                            return Err(format!(
+                                "`{bin}` does not register backend `{backend}` (it lists: {})",
+                                listed.join(", ")
+                            ));
+                        }
+                        let mut command = vec![bin, "serve".to_string(), backend];
+                        if let Some(preset) = entry.get("preset") {
+                            command.push("--preset".to_string());
+                            command.push(string("registered.preset", preset)?);
+                        }
+                        campaign.externals.push(ExternalSpec { name, command });
+                    }
+                }
+                "config" => {
+                    for entry in array(key, value)? {
+                        campaign.configs.push(matcher_config(entry)?);
+                    }
+                }
+                "limits" => {
+                    let backends = value
+                        .as_table()
+                        .ok_or_else(|| "limits must be a table of backends".to_string())?;
+                    for (backend, entry) in backends {
+                        campaign
+                            .limits
+                            .insert(backend.clone(), limits(backend, entry)?);
+                    }
+                }
+                "warmup" => {
+                    warmup = Some(
+                        value
+                            .as_table()
+                            .ok_or_else(|| "warmup must be a table of backends".to_string())?,
+                    );
+                }
+                _ => return Err(format!("unknown key `{key}`")),
+            }
+        }
+
+        // Checked once every table is read, since `[[external]]` declares
+        // backends that `backends` and `[limits]` may name
+        let known = |backend: &str| {
+            BACKENDS.contains(&backend) || campaign.externals.iter().any(|e| e.name == backend)
+        };
+        if let Some(unknown) = campaign.backends.iter().find(|b| !known(b)) {
+            return Err(format!(
+                "unknown backend `{unknown}` (built in: {}; declare others in [[external]] \
+                 or [[registered]])",
+                BACKENDS.join(", ")
+            ));
+        }
+        if let Some(unknown) = campaign.limits.keys().find(|b| !known(b)) {
+            return Err(format!("limits for unknown backend `{unknown}`"));
+        }
+        // Each backend's keys fall back to its own defaults, which depend on
+        // whether it is external
+        for (backend, entry) in warmup.into_iter().flatten() {
+            if !known(backend) {
+                return Err(format!("warmup for unknown backend `{backend}`"));
+            }
+            let policy = warmup_policy(backend, entry, campaign.default_warmup(backend))?;
+            campaign.warmup.insert(backend.clone(), policy);
+        }
+
+        if campaign.corpora.is_empty() {
+            campaign.corpora.push(CorpusSpec {
+                name: "instruments-export".to_string(),
+                path: resource_path("instruments-export.tsv"),
+                map_spec: String::new(),
+                map: FieldMap::default(),
+                dedup: false,
+            });
+        }
+        if campaign.query_sets.is_empty() {
+            campaign.query_sets.push(QuerySetSpec {
+                name: "queries".to_string(),
+                path: resource_path("queries.tsv"),
+            });
+        }
+        if campaign.configs.is_empty() {
+            campaign.configs.push(MatcherConfig::default());
+        }
+        for names in [
+            campaign.corpora.iter().map(|c| &c.name).collect::<Vec<_>>(),
+            campaign.query_sets.iter().map(|q| &q.name).collect(),
+            campaign.configs.iter().map(|c| &c.name).collect(),
+            campaign.externals.iter().map(|e| &e.name).collect(),
+        ] {
+            for (i, name) in names.iter().enumerate() {
+                if names[..i].contains(name) {
+                    return Err(format!("name `{name}` is used twice"));
+                }
+            }
+        }
+        Ok(campaign)
+    }
+
+    /// Timed passes of each query in `category`.
+    pub fn iterations_for(&self, category: &str) -> usize {
+        self.category_iterations
+            .get(category)
+            .copied()
+            .unwrap_or(self.iterations)
+    }
+
+    /// Limits on each scenario of `backend`.
+    pub fn limits(&self, backend: &str) -> Limits {
+        self.limits.get(backend).copied().unwrap_or_default()
+    }
+
+    /// How each scenario of `backend` is warmed up.
+    pub fn warmup(&self, backend: &str) -> Warmup {
+        self.warmup
+            .get(backend)
+            .copied()
+            .unwrap_or_else(|| self.default_warmup(backend))
+    }
+
+    /// The warm-up of `backend` without a `[warmup]` table, which its
+    /// fingerprint records departures from.
+    pub fn default_warmup(&self, backend: &str) -> Warmup {
+        if self.external(backend).is_some() {
+            Warmup::SUBPROCESS
+        } else {
+            Warmup::IN_PROCESS
+        }
+    }
+
+    /// The `[[external]]` declaration of `backend`, or `None` for a
+    /// built-in one.
+    pub fn external(&self, backend: &str) -> Option<&ExternalSpec> {
+        self.externals.iter().find(|e| e.name == backend)
+    }
+
+    /// Configurations each scenario of `backend` runs under.
+    pub fn config_names(&self, backend: &str) -> Vec<&str> {
+        match self.external(backend) {
+            Some(_) => vec![EXTERNAL_CONFIG],
+            None => self.configs.iter().map(|c| c.name.as_str()).collect(),
+        }
+    }
+
+    /// Scenarios in the campaign: every corpus × query set × backend ×
+    /// configuration, with one configuration for an external backend.
+    pub fn scenario_count(&self) -> usize {
+        let per_grid: usize = self
+            .backends
+            .iter()
+            .map(|b| self.config_names(b).len())
+            .sum();
+        self.corpora.len() * self.query_sets.len() * per_grid
+    }
+}
+
+fn matcher_config(value: &Value) -> Result<MatcherConfig, String> {
+    let entry = fields(
+        "config",
+        value,
+        &["name", "case", "normalization", "atom", "prefer_prefix"],
+    )?;
+    let defaults = MatcherConfig::default();
+    let choice = |key: &str, allowed: &[&str], default: &str| -> Result<String, String> {
+        match entry.get(key) {
+            Some(v) => {
+                let s = string(&format!("config.{key}"), v)?;
+                if allowed.contains(&s.as_str()) {
+                    Ok(s)
+                } else {
+                    Err(format!(
+                        "config.{key} must be one of {}",
+                        allowed.join(", ")
+                    ))
+                }
+            }
+            None => Ok(default.to_string()),
+        }
+    };
+    Ok(MatcherConfig {
+        name: match entry.get("name") {
+            Some(v) => string("config.name", v)?,
+            None => return Err("every [[config]] needs a name".to_string()),
+        },
+        case: choice("case", &["ignore", "smart", "respect"], &defaults.case)?,
+        normalization: choice(
+            "normalization",
+            &["smart", "never"],
+            &defaults.normalization,
+        )?,
+        atom: choice(
+            "atom",
+            &["fuzzy", "substring", "prefix", "postfix", "exact"],
+            &defaults.atom,
+        )?,
+        prefer_prefix: boolean(entry, "config.prefer_prefix")?,
+    })
+}
+
+fn limits(backend: &str, value: &Value) -> Result<Limits, String> {
+    let table = format!("limits.{backend}");
+    let entry = value
+        .as_table()
+        .ok_or_else(|| format!("[{table}] must be a table"))?;
+    let mut limits = Limits::default();
+    for (key, value) in entry {
+        let amount = value
+            .as_integer()
+            .and_then(|n| u64::try_from(n).ok())
+            .filter(|n| *n > 0)
+            .ok_or_else(|| format!("{table}.{key} must be a positive integer"))?;
+        match key.as_str() {
+            "time_limit_s" => limits.time = Some(Duration::from_secs(amount)),
+            "memory_mb" => limits.memory_bytes = Some(amount << 20),
+            _ => return Err(format!("unknown key `{table}.{key}`")),
+        }
+    }
+    Ok(limits)
+}
+
+fn warmup_policy(backend: &str, value: &Value, default: Warmup) -> Result<Warmup, String> {
+    let table = format!("warmup.{backend}");
+    let entry = value
+        .as_table()
+        .ok_or_else(|| format!("[{table}] must be a table"))?;
+    let mut warmup = default;
+    for (key, value) in entry {
+        let flag = || {
+            value
+                .as_bool()
+                .ok_or_else(|| format!("{table}.{key} must be true or false"))
+        };
+        match key.as_str() {
+            "queries" => {
+                let queries = match value {
+                    Value::String(s) => Warmup::parse_queries(s).ok(),
+                    Value::Integer(n) => usize::try_from(*n).ok().map(Some),
+                    _ => None,
+                };
+                warmup.queries = queries.ok_or_else(|| {
+                    format!("{table}.{key} must be a number of queries or \"all\"")
+                })?;
+            }
+            "index" => warmup.index = flag()?,
+            "discard_first" => warmup.discard_first = flag()?,
+            _ => return Err(format!("unknown key `{table}.{key}`")),
+        }
+    }
+    Ok(warmup)
+}
+
+/// An array-of-tables entry, checked against its allowed keys.
+/// The backends the registry binary `bin` lists.
+fn registered_backends(bin: &str) -> Result<Vec<String>, String> {
+    let output = Command::new(bin)
+        .arg("list")
+        .output()
+        .map_err(|e| format!("could not run `{bin} list`: {e}"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!(
+            "`{bin} list` failed ({}): {}",
+            output.status,
+            stderr.lines().last().unwrap_or("")
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect())
+}
+
+fn fields<'a>(table: &str, value: &'a Value, allowed: &[&str]) -> Result<&'a Table, String> {
+    let entry = value
+        .as_table()
+        .ok_or_else(|| format!("[[{table}]] entries must be tables"))?;
+    if let Some(key) = entry.keys().find(|k| !allowed.contains(&k.as_str())) {
+        return Err(format!("unknown key `{table}.{key}`"));
+    }
+    Ok(entry)
+}
+
+fn required<'a>(entry: &'a Table, table: &str, key: &str) -> Result<&'a Value, String> {
+    entry
+        .get(key)
+        .ok_or_else(|| format!("every [[{table}]] needs a {key}"))
+}
+
+/// The entry's `name`, or the file stem of its path.
+fn name(entry: &Table, table: &str, path: &str) -> Result<String, String> {
+    match entry.get("name") {
+        Some(v) => string(&format!("{table}.name"), v),
+        None => Ok(Path::new(path)
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default()),
+    }
+}
+
+fn boolean(entry: &Table, key: &str) -> Result<bool, String> {
+    let field = key.rsplit('.').next().unwrap_or(key);
+    match entry.get(field) {
+        Some(v) => v
+            .as_bool()
+            .ok_or_else(|| format!("{key} must be true or false")),
+        None => Ok(false),
+    }
+}
+
+fn array<'a>(key: &str, value: &'a Value) -> Result<&'a Vec<Value>, String> {
+    value
+        .as_array()
+        .ok_or_else(|| format!("{key} must be an array"))
+}
+
+fn string(key: &str, value: &Value) -> Result<String, String> {
+    value
+        .as_str()
+        .map(String::from)
+        .ok_or_else(|| format!("{key} must be a string"))
+}
@@ -0,0 +1,102 @@
+//! Checkpoints of a campaign in progress, so an interrupted run can pick
+//! up where it stopped.
+//!
+//! The grid is checkpointed by cell: one backend's scenarios, every
+//! configuration, on one corpus and query set. When a cell finishes, the
+//! runner records what its scenarios left behind (result file names, score
+//! histograms, process accounting, or why a scenario stopped) and rewrites
+//! `checkpoint.json` in the bundle, through a temporary file so a run killed
+//! mid-write leaves the previous checkpoint whole. `--resume` reads it back
+//! and skips the cells it lists, and a corpus whose cells are all done is
+//! not even loaded.
+//!
+//! The checkpoint holds the SHA-256 of the campaign file, and resuming
+//! under a changed campaign is refused: its cells may no longer mean what
+//! they did.
+
+use serde_json::{json, Value};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub struct Checkpoint {
+    path: PathBuf,
+    campaign_sha256: String,
+    /// Finished cells, each `{corpus, queries, backend, scenarios}`.
+    cells: Vec<Value>,
+}
+
+impl Checkpoint {
+    /// An empty checkpoint for a fresh run into `out_dir`.
+    pub fn new(out_dir: &str, campaign_sha256: &str) -> Checkpoint {
+        Checkpoint {
+            path: Path::new(out_dir).join("checkpoint.json"),
+            campaign_sha256: campaign_sha256.to_string(),
+            cells: Vec::new(),
+        }
+    }
+
+    /// The checkpoint left in `out_dir` by an earlier run of the same
+    /// campaign, or an empty one when there is none.
+    pub fn resume(out_dir: &str, campaign_sha256: &str) -> Result<Checkpoint, String> {
+        let mut checkpoint = Checkpoint::new(out_dir, campaign_sha256);
+        let text = match fs::read_to_string(&checkpoint.path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(checkpoint),
+            Err(e) => return Err(format!("{}: {e}", checkpoint.path.display())),
+        };
+        let value: Value = serde_json::from_str(&text)
+            .map_err(|e| format!("{}: {e}", checkpoint.path.display()))?;
+        if value.get("campaign_sha256").and_then(Value::as_str) != Some(campaign_sha256) {
+            return Err(format!(
+                "{} was written for a different campaign file; rerun without --resume",
+                checkpoint.path.display()
+            ));
+        }
+        checkpoint.cells = value
+            .get("cells")
+            .and_then(Value::as_array)
+            .cloned()
+            .ok_or_else(|| format!("{}: no cells", checkpoint.path.display()))?;
+        Ok(checkpoint)
+    }
+
+    /// The scenarios of a finished cell, or `None` when it has yet to run.
+    pub fn cell(&self, corpus: &str, queries: &str, backend: &str) -> Option<&[Value]> {
+        self.cells
+            .iter()
+            .find(|cell| {
+                cell["corpus"] == corpus && cell["queries"] == queries && cell["backend"] == backend
+            })
+            .and_then(|cell| cell["scenarios"].as_array())
+            .map(Vec::as_slice)
+    }
+
+    /// Cells finished so far.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Records a finished cell and saves the checkpoint.
+    pub fn complete(
+        &mut self,
+        corpus: &str,
+        queries: &str,
+        backend: &str,
+        scenarios: Vec<Value>,
+    ) -> io::Result<()> {
+        self.cells.push(json!({
+            "corpus": corpus,
+            "queries": queries,
+            "backend": backend,
+            "scenarios": scenarios,
+        }));
+        let text = serde_json::to_string_pretty(&json!({
+            "campaign_sha256": self.campaign_sha256,
+            "cells": self.cells,
+        }))? + "\n";
+        let partial = self.path.with_extension("json.partial");
+        fs::write(&partial, text)?;
+        fs::rename(&partial, &self.path)
+    }
+}
@@ -0,0 +1,1318 @@
+//! Runs a benchmark campaign: every corpus × query set × backend × matcher
+//! configuration in one go.
+//!
+//! Usage: run-scenarios CAMPAIGN.toml [--out DIR] [--baseline DIR] [--notify URL]
+//!                      [--jobs N] [--memory-budget-mb MB]
+//!                      [--pin-cores [--numa-node N]] [--dry-run] [--resume]
+//!
+//! The campaign format is described in `campaign.rs`. Each corpus is loaded,
+//! deduplicated and split into candidate columns once, then shared by every
+//! scenario that runs on it. Each scenario is timed like `bench-nucleo`
+//! (its backend's warm-up, then `iterations` timed passes, or a category's
+//! own count from `[category_iterations]`) and keeps its top results. The warm-up is set per backend with `[warmup]` tables and
+//! listed in `bundle.json`, and a scenario's fingerprint records where it
+//! departs from the backend's default. The bundle written to `--out` (default `scenarios/<campaign>`)
+//! holds one result file per scenario under `results/`, in the unified
+//! schema so `report` and `gate` read them, a `bundle.json` index of the
+//! scenarios, and a `summary.md` with a table per corpus and query set.
+//! `histograms.tsv` counts the scores of every match in each scenario, per
+//! query category, in bins of the campaign's `histogram_bin_width`, and how
+//! many of them were the expected instrument. Scores are only comparable
+//! within a backend, so these are what calibration curves onto a common
+//! scale are fitted from. They are counted by a second scan of each query
+//! after the timed passes, so neither the timings nor the warm-up change.
+//! `--dry-run` lists the scenarios without running them. `--pin-cores` pins
+//! the runner to one core (see `harness::affinity`).
+//!
+//! A scenario that goes past its backend's `[limits]` is stopped between
+//! queries and the campaign moves on. It writes no result file; the index
+//! and summary list it with the limit it broke.
+//!
+//! `[[external]]` backends run as child processes (see
+//! `harness::subprocess`), killed when they go past their limits. They are
+//! not timed per query, so the summary leaves their latency blank and
+//! instead lists each one's exit status, wall and CPU time and peak
+//! resident set. Their stderr is saved under `logs/`, and one that fails or
+//! is killed is listed with the reason and the last line it printed.
+//!
+//! `--jobs N` runs up to N cells (one backend's scenarios on one corpus and
+//! query set) at once, scheduled by `harness::schedule`. With `--pin-cores`
+//! each runs on a core of its own, and N is capped at the node's cores.
+//! `--memory-budget-mb MB` holds a cell back until the runner's resident
+//! set, the reservations of the cells running and its own fit in MB. An
+//! external cell reserves its memory limit, or the size of its corpus file
+//! when it has none; nucleo cells scan the corpus the runner holds and
+//! reserve nothing. Corpora are still loaded one at a time, and the columns
+//! of every query set on a corpus are prepared before its cells start.
+//!
+//! Progress is checkpointed after each backend finishes a corpus and query
+//! set (see `checkpoint.rs`). `--resume` continues an interrupted campaign
+//! in the same `--out` directory from its checkpoint, reusing the result
+//! files of the cells already done, and `bundle.json` and the summary mark
+//! the scenarios carried over.
+//!
+//! `--baseline DIR` names an earlier bundle. Each scenario with a result file
+//! of the same name there is checked against it with the default `gate`
+//! tolerances, and the summary lists what regressed. With the `notify`
+//! feature, `--notify URL` (or `RUN_SCENARIOS_WEBHOOK`) posts a short version
+//! of the summary to a webhook when the campaign finishes; a failed post is
+//! reported but does not fail the run.
+//!
+//! `--log-format json` (with `--log-file PATH`) logs each scenario and each
+//! query's timing as JSON lines, as `bench-nucleo` and `quality-nucleo` do.
+
+mod campaign;
+mod checkpoint;
+#[cfg(feature = "notify")]
+mod notify;
+
+use campaign::{Campaign, CorpusSpec, ExternalSpec, MatcherConfig, QuerySetSpec};
+use checkpoint::Checkpoint;
+use harness::affinity::Placement;
+use harness::cli::{flag_value, has_flag};
+use harness::corpus::{load_corpus_mapped, Corpus};
+use harness::dedup::Duplicates;
+use harness::fingerprint::Fingerprint;
+use harness::histogram::ScoreHistogram;
+use harness::limits::Limits;
+use harness::logging;
+use harness::manifest::{self, Manifest};
+use harness::memory;
+use harness::metrics::percentile;
+use harness::prepared::PreparedCorpus;
+use harness::queries::{load_queries, Query, SKIP};
+use harness::results::{Hit, QueryResult, ResultSet, Startup, SteadyState};
+use harness::schedule::{Budget, Scheduler};
+use harness::subprocess::{self, Outcome};
+use harness::tolerance::{self, Tolerances, Violation};
+use harness::topk::TopK;
+use harness::warmup::{self, Warmup};
+use nucleo_matcher::pattern::Pattern;
+use nucleo_matcher::{Config, Matcher, Utf32Str};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::env;
+use std::fmt::Write as _;
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Instant;
+use tracing::{info, info_span};
+
+/// Results per query kept in each scenario's result file.
+const RECORDED_RESULTS: usize = 10;
+
+/// One finished scenario, for the index and summary.
+struct Finished {
+    corpus: String,
+    queries: String,
+    backend: String,
+    config: String,
+    file: String,
+    results: ResultSet,
+    /// Scores of every match, per query category.
+    histograms: BTreeMap<String, ScoreHistogram>,
+    /// What broke the tolerances against the baseline bundle, or `None`
+    /// when the baseline has no such scenario.
+    violations: Option<Vec<Violation>>,
+    /// The child process of an external backend.
+    process: Option<Process>,
+    /// Whether it was run before the campaign was interrupted and resumed.
+    resumed: bool,
+}
+
+impl Finished {
+    /// What a resumed run needs besides the result file.
+    fn checkpoint(&self) -> Value {
+        json!({
+            "config": self.config,
+            "file": self.file,
+            "histograms": self
+                .histograms
+                .iter()
+                .map(|(category, histogram)| (category.clone(), histogram.to_json()))
+                .collect::<serde_json::Map<_, _>>(),
+            "process": self.process.as_ref().map(Process::to_json),
+        })
+    }
+}
+
+/// A scenario stopped for going past its backend's limits, or an external
+/// one whose process failed.
+struct Stopped {
+    name: String,
+    backend: String,
+    reason: String,
+    process: Option<Process>,
+    resumed: bool,
+}
+
+impl Stopped {
+    fn checkpoint(&self) -> Value {
+        json!({
+            "stopped": self.name,
+            "reason": self.reason,
+            "process": self.process.as_ref().map(Process::to_json),
+        })
+    }
+}
+
+/// How an external backend's child process ended and what it used; its
+/// output is not kept.
+struct Process {
+    outcome: Outcome,
+    /// The saved stderr, relative to the bundle, when it printed any.
+    stderr_file: Option<String>,
+}
+
+impl Process {
+    fn to_json(&self) -> Value {
+        let mut value = self.outcome.to_json();
+        value["stderr"] = json!(self.stderr_file);
+        value
+    }
+
+    fn from_json(value: &Value) -> Option<Process> {
+        Some(Process {
+            outcome: Outcome::from_json(value)?,
+            stderr_file: value
+                .get("stderr")
+                .and_then(Value::as_str)
+                .map(String::from),
+        })
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    logging::init(&args).unwrap_or_else(|e| panic!("Invalid logging flags: {e}"));
+    let campaign_path = args
+        .iter()
+        .skip(1)
+        .enumerate()
+        .find(|(i, a)| {
+            !a.starts_with("--")
+                && ![
+                    "--out",
+                    "--baseline",
+                    "--notify",
+                    "--numa-node",
+                    "--jobs",
+                    "--memory-budget-mb",
+                    "--log-format",
+                    "--log-file",
+                ]
+                .contains(&args[*i].as_str())
+        })
+        .map(|(_, a)| a)
+        .expect("Usage: run-scenarios CAMPAIGN.toml [--out DIR] [--dry-run] [--resume]");
+    let campaign =
+        Campaign::load(campaign_path).unwrap_or_else(|e| panic!("Invalid campaign: {e}"));
+    let out_dir = flag_value(&args, "--out")
+        .map(String::from)
+        .unwrap_or_else(|| format!("scenarios/{}", campaign.name));
+
+    println!(
+        "Campaign {}: {} corpora x {} query sets x {} backends x {} configs = {} scenarios \
+         (one config per external backend)",
+        campaign.name,
+        campaign.corpora.len(),
+        campaign.query_sets.len(),
+        campaign.backends.len(),
+        campaign.configs.len(),
+        campaign.scenario_count()
+    );
+    if has_flag(&args, "--dry-run") {
+        for corpus in &campaign.corpora {
+            for queries in &campaign.query_sets {
+                for backend in &campaign.backends {
+                    for config in campaign.config_names(backend) {
+                        println!("  {}", slug(&corpus.name, &queries.name, backend, config));
+                    }
+                }
+            }
+        }
+        return;
+    }
+
+    let mut budget = Budget {
+        jobs: flag_value(&args, "--jobs").map_or(1, |n| {
+            n.parse()
+                .ok()
+                .filter(|&n| n > 0)
+                .unwrap_or_else(|| panic!("Invalid --jobs `{n}`"))
+        }),
+        memory_bytes: flag_value(&args, "--memory-budget-mb").map(|mb| {
+            mb.parse::<u64>()
+                .unwrap_or_else(|_| panic!("Invalid --memory-budget-mb `{mb}`"))
+                << 20
+        }),
+    };
+
+    // Pinned before any corpus is loaded, so it lands on the pinned node
+    let placement = Placement::from_args(&args).unwrap_or_else(|e| panic!("Cannot pin cores: {e}"));
+    if let Some(placement) = &placement {
+        let core = placement
+            .pin(0)
+            .unwrap_or_else(|e| panic!("Cannot pin cores: {e}"));
+        println!("Pinned to core {} on NUMA node {}", core, placement.node);
+        if budget.jobs > placement.cores.len() {
+            println!(
+                "NUMA node {} has {} cores to pin to; running {} cells at a time",
+                placement.node,
+                placement.cores.len(),
+                placement.cores.len()
+            );
+            budget.jobs = placement.cores.len();
+        }
+    }
+
+    let baseline_dir = flag_value(&args, "--baseline");
+    let query_sets: Vec<(&QuerySetSpec, Vec<Query>)> = campaign
+        .query_sets
+        .iter()
+        .map(|spec| {
+            let queries = load_queries(&spec.path)
+                .unwrap_or_else(|e| panic!("Failed to read queries {}: {e}", spec.path));
+            (spec, queries)
+        })
+        .collect();
+
+    fs::create_dir_all(Path::new(&out_dir).join("results")).expect("Failed to create bundle");
+    let campaign_sha256 =
+        manifest::sha256_file(campaign_path).expect("Failed to read campaign file");
+    let mut checkpoint = if has_flag(&args, "--resume") {
+        let checkpoint = Checkpoint::resume(&out_dir, &campaign_sha256)
+            .unwrap_or_else(|e| panic!("Cannot resume: {e}"));
+        println!(
+            "Resuming with {} finished cells from {out_dir}",
+            checkpoint.len()
+        );
+        checkpoint
+    } else {
+        Checkpoint::new(&out_dir, &campaign_sha256)
+    };
+    let mut finished = Vec::new();
+    let mut stopped = Vec::new();
+    for corpus_spec in &campaign.corpora {
+        let done = |queries: &str, backend: &str| {
+            checkpoint
+                .cell(&corpus_spec.name, queries, backend)
+                .is_some()
+        };
+        if query_sets
+            .iter()
+            .all(|(spec, _)| campaign.backends.iter().all(|b| done(&spec.name, b)))
+        {
+            println!("Resuming corpus {} from the checkpoint", corpus_spec.name);
+            for (queries_spec, _) in &query_sets {
+                for backend in &campaign.backends {
+                    let scenarios = checkpoint
+                        .cell(&corpus_spec.name, &queries_spec.name, backend)
+                        .unwrap_or_default();
+                    let (done, stop) = resume_cell(
+                        scenarios,
+                        (&corpus_spec.name, &queries_spec.name, backend),
+                        &out_dir,
+                        baseline_dir,
+                    );
+                    finished.extend(done);
+                    stopped.extend(stop);
+                }
+            }
+            continue;
+        }
+        let (corpus, loaded) = prepare(corpus_spec);
+        let corpus_sha256 = Manifest::read_for(&corpus_spec.path)
+            .ok()
+            .flatten()
+            .map(|m| m.sha256);
+        let sets: Vec<QuerySet> = query_sets
+            .iter()
+            .map(|(spec, queries)| {
+                let prepare_start = Instant::now();
+                let columns = PreparedCorpus::builder(&corpus)
+                    .fields(queries.iter().map(|q| q.field.as_str()))
+                    .build();
+                let mut startup = loaded.clone();
+                startup.push("prepare", prepare_start.elapsed().as_secs_f64() * 1000.0);
+                QuerySet {
+                    spec,
+                    queries,
+                    columns,
+                    startup,
+                }
+            })
+            .collect();
+        let grid = CorpusRun {
+            campaign: &campaign,
+            spec: corpus_spec,
+            corpus: &corpus,
+            sha256: corpus_sha256.as_deref(),
+            out_dir: &out_dir,
+            baseline_dir,
+        };
+
+        // Cells in grid order, so the bundle lists them the same way however
+        // many ran at once
+        let cells: Vec<(&QuerySet, &String)> = sets
+            .iter()
+            .flat_map(|set| campaign.backends.iter().map(move |backend| (set, backend)))
+            .collect();
+        let reservations: Vec<u64> = cells
+            .iter()
+            .map(|(_, backend)| reservation(&campaign, corpus_spec, backend))
+            .collect();
+        let mut outcomes: Vec<Option<(Vec<Finished>, Vec<Stopped>)>> =
+            cells.iter().map(|_| None).collect();
+        let mut waiting = Vec::new();
+        for (i, (set, backend)) in cells.iter().enumerate() {
+            match checkpoint.cell(&corpus_spec.name, &set.spec.name, backend) {
+                Some(scenarios) => {
+                    println!(
+                        "Resuming {} from the checkpoint",
+                        slug(&corpus_spec.name, &set.spec.name, backend, "*")
+                    );
+                    outcomes[i] = Some(resume_cell(
+                        scenarios,
+                        (&corpus_spec.name, &set.spec.name, backend),
+                        &out_dir,
+                        baseline_dir,
+                    ));
+                }
+                None => waiting.push(i),
+            }
+        }
+
+        thread::scope(|scope| {
+            let (done_tx, done_rx) = mpsc::channel();
+            let mut scheduler = Scheduler::new(budget);
+            while !waiting.is_empty() || !scheduler.is_idle() {
+                loop {
+                    let waiting_reservations: Vec<u64> =
+                        waiting.iter().map(|&i| reservations[i]).collect();
+                    let in_use = memory::resident_bytes().unwrap_or(0);
+                    let Some(next) = scheduler.next(&waiting_reservations, in_use) else {
+                        break;
+                    };
+                    let i = waiting.remove(next);
+                    let slot = scheduler.start(reservations[i]);
+                    let (set, backend) = cells[i];
+                    let (grid, placement, done_tx) = (&grid, placement.as_ref(), done_tx.clone());
+                    scope.spawn(move || {
+                        if let Some(placement) = placement {
+                            placement
+                                .pin(slot)
+                                .unwrap_or_else(|e| panic!("Cannot pin cores: {e}"));
+                        }
+                        let cell =
+                            panic::catch_unwind(AssertUnwindSafe(|| run_cell(grid, set, backend)));
+                        let _ = done_tx.send((i, slot, cell));
+                    });
+                }
+                let (i, slot, cell) = done_rx.recv().expect("every running cell reports back");
+                let (done, stop, scenarios) = cell.unwrap_or_else(|e| panic::resume_unwind(e));
+                scheduler.finish(slot);
+                let (set, backend) = cells[i];
+                checkpoint
+                    .complete(&corpus_spec.name, &set.spec.name, backend, scenarios)
+                    .expect("Failed to write checkpoint");
+                outcomes[i] = Some((done, stop));
+            }
+        });
+        for (done, stop) in outcomes.into_iter().flatten() {
+            finished.extend(done);
+            stopped.extend(stop);
+        }
+    }
+
+    write_index(&campaign, &finished, &stopped, budget.jobs, &out_dir);
+    write_histograms(&finished, &out_dir);
+    let summary = summary(&campaign, &finished, &stopped, budget.jobs, baseline_dir);
+    fs::write(Path::new(&out_dir).join("summary.md"), &summary).expect("Failed to write summary");
+    println!();
+    print!("{summary}");
+    println!("Bundle written to {out_dir}");
+
+    let webhook = flag_value(&args, "--notify").map(String::from).or_else(|| {
+        env::var("RUN_SCENARIOS_WEBHOOK")
+            .ok()
+            .filter(|url| !url.is_empty())
+    });
+    if let Some(url) = webhook {
+        notify_finished(&url, &notification(&campaign, &finished, &stopped));
+    }
+}
+
+#[cfg(feature = "notify")]
+fn notify_finished(url: &str, text: &str) {
+    match notify::post(url, text) {
+        Ok(()) => println!("Posted summary to webhook"),
+        Err(e) => eprintln!("Warning: failed to post summary to webhook: {e}"),
+    }
+}
+
+#[cfg(not(feature = "notify"))]
+fn notify_finished(_url: &str, _text: &str) {
+    eprintln!("Warning: built without the `notify` feature; no summary posted");
+}
+
+/// `corpus--queries--backend--config`, for file names.
+fn slug(corpus: &str, queries: &str, backend: &str, config: &str) -> String {
+    [corpus, queries, backend, config]
+        .iter()
+        .map(|part| {
+            part.chars()
+                .map(|c| {
+                    if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                        c
+                    } else {
+                        '_'
+                    }
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("--")
+}
+
+/// What every cell on one corpus shares.
+struct CorpusRun<'a> {
+    campaign: &'a Campaign,
+    spec: &'a CorpusSpec,
+    corpus: &'a Corpus,
+    sha256: Option<&'a str>,
+    out_dir: &'a str,
+    baseline_dir: Option<&'a str>,
+}
+
+/// A query set, with the corpus columns its queries search and the startup
+/// phases of preparing them.
+struct QuerySet<'a> {
+    spec: &'a QuerySetSpec,
+    queries: &'a [Query],
+    columns: PreparedCorpus,
+    startup: Startup,
+}
+
+/// Memory a cell of `backend` is expected to need besides the runner's: an
+/// external backend's memory limit, or without one the size of the corpus
+/// file it loads. nucleo cells scan the corpus the runner already holds.
+fn reservation(campaign: &Campaign, corpus: &CorpusSpec, backend: &str) -> u64 {
+    if campaign.external(backend).is_none() {
+        return 0;
+    }
+    campaign
+        .limits(backend)
+        .memory_bytes
+        .unwrap_or_else(|| fs::metadata(&corpus.path).map_or(0, |m| m.len()))
+}
+
+/// Runs every configuration of `backend` on one corpus and query set, with
+/// what each scenario leaves for the checkpoint.
+fn run_cell(
+    grid: &CorpusRun,
+    set: &QuerySet,
+    backend: &str,
+) -> (Vec<Finished>, Vec<Stopped>, Vec<Value>) {
+    let campaign = grid.campaign;
+    let corpus_spec = grid.spec;
+    let mut finished = Vec::new();
+    let mut stopped = Vec::new();
+    let mut cell = Vec::new();
+    for config_name in campaign.config_names(backend) {
+        let name = slug(&corpus_spec.name, &set.spec.name, backend, config_name);
+        println!("Running {name}...");
+        let _scenario = info_span!(
+            "scenario",
+            scenario = %name,
+            backend = %backend,
+            corpus = %corpus_spec.name,
+            queries = %set.spec.name,
+            config = %config_name
+        )
+        .entered();
+        let limits = campaign.limits(backend);
+        let warmup = campaign.warmup(backend);
+        let warmup_entries = warmup.entries(&campaign.default_warmup(backend));
+        let (run, process) = match campaign.external(backend) {
+            Some(external) => {
+                let (run, process) = run_external(
+                    external,
+                    &corpus_spec.path,
+                    set.queries,
+                    &limits,
+                    &warmup,
+                    grid.out_dir,
+                    &name,
+                );
+                let run = run.map(|mut results| {
+                    let mut entries = warmup_entries;
+                    entries.insert("command".to_string(), external.command.join(" "));
+                    results.fingerprint = Some(Fingerprint::capture(&[], entries));
+                    (results, BTreeMap::new())
+                });
+                (run, process)
+            }
+            None => {
+                let config = campaign
+                    .configs
+                    .iter()
+                    .find(|c| c.name == config_name)
+                    .expect("config names come from the campaign");
+                let run = run_nucleo(
+                    grid.corpus,
+                    &set.columns,
+                    set.queries,
+                    config,
+                    campaign,
+                    &limits,
+                    &warmup,
+                )
+                .map(|(mut results, histograms)| {
+                    results.startup = Some(set.startup.clone());
+                    let mut entries = warmup_entries;
+                    entries.extend(config.entries());
+                    entries.insert("iterations".to_string(), campaign.iterations.to_string());
+                    if !campaign.category_iterations.is_empty() {
+                        let counts: Vec<String> = campaign
+                            .category_iterations
+                            .iter()
+                            .map(|(category, n)| format!("{category}={n}"))
+                            .collect();
+                        entries.insert("category_iterations".to_string(), counts.join(","));
+                    }
+                    entries.insert("dedup".to_string(), corpus_spec.dedup.to_string());
+                    entries.insert("map".to_string(), corpus_spec.map_spec.clone());
+                    results.fingerprint = Some(Fingerprint::capture(&[], entries));
+                    (results, histograms)
+                });
+                (run, None)
+            }
+        };
+        let (mut results, histograms) = match run {
+            Ok(run) => run,
+            Err(reason) => {
+                eprintln!("Stopped {name}: {reason}");
+                let scenario = Stopped {
+                    name,
+                    backend: backend.to_string(),
+                    reason,
+                    process,
+                    resumed: false,
+                };
+                cell.push(scenario.checkpoint());
+                stopped.push(scenario);
+                continue;
+            }
+        };
+        results.run = config_name.to_string();
+        results.corpus = Some(corpus_spec.name.clone());
+        results.corpus_sha256 = grid.sha256.map(String::from);
+
+        let file = format!("results/{name}.json");
+        results
+            .write(&Path::new(grid.out_dir).join(&file).to_string_lossy())
+            .expect("Failed to write results file");
+        let violations = check_baseline(grid.baseline_dir, &file, &results);
+        let scenario = Finished {
+            corpus: corpus_spec.name.clone(),
+            queries: set.spec.name.clone(),
+            backend: backend.to_string(),
+            config: config_name.to_string(),
+            file,
+            results,
+            histograms,
+            violations,
+            process,
+            resumed: false,
+        };
+        cell.push(scenario.checkpoint());
+        finished.push(scenario);
+    }
+    (finished, stopped, cell)
+}
+
+/// Loads a corpus once for all of its scenarios, with the time it took
+/// as the `load` phase of their startup.
+fn prepare(spec: &CorpusSpec) -> (Corpus, Startup) {
+    println!("Loading corpus {} from {}...", spec.name, spec.path);
+    let start = Instant::now();
+    let mut corpus = load_corpus_mapped(&spec.path, &spec.map).expect("Failed to read corpus");
+    if spec.dedup {
+        corpus = Duplicates::find(&corpus).remove_from(&corpus);
+    }
+    let mut startup = Startup::default();
+    startup.push("load", start.elapsed().as_secs_f64() * 1000.0);
+    println!("Loaded {} instruments", corpus.len());
+    (corpus, startup)
+}
+
+/// Times every query under `config` and records its top results, with the
+/// scores of all its matches per category, or says which of `limits` the
+/// run went past.
+fn run_nucleo(
+    corpus: &Corpus,
+    columns: &PreparedCorpus,
+    queries: &[Query],
+    config: &MatcherConfig,
+    campaign: &Campaign,
+    limits: &Limits,
+    warmup: &Warmup,
+) -> Result<(ResultSet, BTreeMap<String, ScoreHistogram>), String> {
+    let started = Instant::now();
+    let iterations: Vec<usize> = queries
+        .iter()
+        .map(|q| campaign.iterations_for(&q.category))
+        .collect();
+    let mut matcher_config = Config::DEFAULT;
+    matcher_config.prefer_prefix = config.prefer_prefix;
+    let mut matcher = Matcher::new(matcher_config);
+    let patterns: Vec<Pattern> = queries
+        .iter()
+        .map(|q| {
+            Pattern::new(
+                &q.text,
+                config.case_matching(),
+                config.normalization(),
+                config.atom_kind(),
+            )
+        })
+        .collect();
+    let mut buf = Vec::new();
+    if warmup.index {
+        for field in columns.fields() {
+            columns.column(field).touch();
+        }
+    }
+
+    // Pass 0 runs the warm-up queries untimed. A discarded pass is timed
+    // like the rest but not kept, and the first pass kept keeps results.
+    // A query sits out the passes past its category's count. Scores are
+    // counted once the timing is done, in one more scan each.
+    let warmup_queries = warmup.query_count(queries.len());
+    let passes: Vec<usize> = iterations.iter().map(|&n| warmup.timed_passes(n)).collect();
+    let discarded = warmup.timed_passes(0);
+    let scans = warmup_queries + passes.iter().sum::<usize>() + queries.len();
+    let mut scanned = 0;
+    let mut timings: Vec<Vec<f64>> = iterations.iter().map(|&n| Vec::with_capacity(n)).collect();
+    let mut records: Vec<QueryResult> = queries.iter().map(QueryResult::new).collect();
+    for pass in 0..=passes.iter().copied().max().unwrap_or(0) {
+        let count = if pass == 0 {
+            warmup_queries
+        } else {
+            queries.len()
+        };
+        for (qi, q) in queries.iter().enumerate().take(count) {
+            if pass > passes[qi] {
+                continue;
+            }
+            let start = Instant::now();
+            let mut matches = 0;
+            let mut top = TopK::new(RECORDED_RESULTS);
+            for (ci, candidate) in columns.column(&q.field).iter().enumerate() {
+                buf.clear();
+                if let Some(score) =
+                    patterns[qi].score(Utf32Str::new(candidate, &mut buf), &mut matcher)
+                {
+                    matches += 1;
+                    top.push(score, ci);
+                }
+            }
+            let elapsed_ms = start.elapsed().as_secs_f64() * 1000.0;
+            scanned += 1;
+            if let Err(exceeded) = limits.check(started) {
+                return Err(format!("{exceeded} after {scanned} of {scans} query scans"));
+            }
+            if pass <= discarded {
+                continue;
+            }
+            timings[qi].push(elapsed_ms);
+            if pass == discarded + 1 {
+                let top = top.into_sorted();
+                records[qi].matches = Some(matches);
+                records[qi].results = Some(
+                    top.iter()
+                        .map(|&(score, idx)| {
+                            let inst = corpus.get(idx);
+                            Hit {
+                                symbol: inst.symbol.to_string(),
+                                name: inst.name.to_string(),
+                                score: Some(score as f64),
+                            }
+                        })
+                        .collect(),
+                );
+            }
+        }
+    }
+    let mut histograms: BTreeMap<String, ScoreHistogram> = BTreeMap::new();
+    for (qi, q) in queries.iter().enumerate() {
+        let histogram = histograms
+            .entry(q.category.clone())
+            .or_insert_with(|| ScoreHistogram::new(campaign.histogram_bin_width));
+        count_scores(histogram, corpus, columns, q, &patterns[qi], &mut matcher);
+        scanned += 1;
+        if let Err(exceeded) = limits.check(started) {
+            return Err(format!("{exceeded} after {scanned} of {scans} query scans"));
+        }
+    }
+    // Only passes that timed every query are comparable as totals
+    let full_passes = iterations.iter().copied().min().unwrap_or(0);
+    let pass_totals: Vec<f64> = (0..full_passes)
+        .map(|pass| timings.iter().map(|times| times[pass]).sum())
+        .collect();
+    for (record, times) in records.iter_mut().zip(&mut timings) {
+        times.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        record.median_ms = Some(times[times.len() / 2]);
+        record.min_ms = Some(times[0]);
+        let _query = info_span!(
+            "query",
+            query = %record.query,
+            field = %record.field,
+            category = %record.category
+        )
+        .entered();
+        info!(
+            duration_ms = times[times.len() / 2],
+            min_ms = times[0],
+            matches = record.matches,
+            "timed"
+        );
+    }
+
+    let results = ResultSet {
+        backend: "nucleo".to_string(),
+        run: String::new(),
+        corpus: None,
+        corpus_sha256: None,
+        fingerprint: None,
+        startup: None,
+        steady_state: SteadyState::from_passes(&pass_totals, queries.len()),
+        memory: Vec::new(),
+        queries: records,
+    };
+    Ok((results, histograms))
+}
+
+/// Checks `results` against the file of the same name in the baseline
+/// bundle, or `None` when the baseline has no such file.
+fn check_baseline(
+    baseline_dir: Option<&str>,
+    file: &str,
+    results: &ResultSet,
+) -> Option<Vec<Violation>> {
+    let path = Path::new(baseline_dir?).join(file);
+    if !path.exists() {
+        return None;
+    }
+    let baseline = ResultSet::read(&path.to_string_lossy())
+        .unwrap_or_else(|e| panic!("Failed to read baseline {}: {e}", path.display()));
+    Some(tolerance::check(&Tolerances::default(), &baseline, results).violations)
+}
+
+/// The scenarios of a `(corpus, queries, backend)` cell finished before the
+/// campaign was interrupted, rebuilt from its checkpoint and result files.
+fn resume_cell(
+    scenarios: &[Value],
+    (corpus, queries, backend): (&str, &str, &str),
+    out_dir: &str,
+    baseline_dir: Option<&str>,
+) -> (Vec<Finished>, Vec<Stopped>) {
+    let mut finished = Vec::new();
+    let mut stopped = Vec::new();
+    let invalid = format!("Cannot resume: damaged checkpoint for {corpus} × {queries} × {backend}");
+    for scenario in scenarios {
+        let process = scenario.get("process").and_then(Process::from_json);
+        if let Some(name) = scenario.get("stopped").and_then(Value::as_str) {
+            stopped.push(Stopped {
+                name: name.to_string(),
+                backend: backend.to_string(),
+                reason: scenario["reason"].as_str().unwrap_or_default().to_string(),
+                process,
+                resumed: true,
+            });
+            continue;
+        }
+        let (Some(config), Some(file)) = (scenario["config"].as_str(), scenario["file"].as_str())
+        else {
+            panic!("{invalid}")
+        };
+        let path = Path::new(out_dir).join(file);
+        let results = ResultSet::read(&path.to_string_lossy())
+            .unwrap_or_else(|e| panic!("Cannot resume: {}: {e}", path.display()));
+        let histograms = scenario["histograms"]
+            .as_object()
+            .and_then(|histograms| {
+                histograms
+                    .iter()
+                    .map(|(category, histogram)| {
+                        Some((category.clone(), ScoreHistogram::from_json(histogram)?))
+                    })
+                    .collect::<Option<_>>()
+            })
+            .expect(&invalid);
+        finished.push(Finished {
+            corpus: corpus.to_string(),
+            queries: queries.to_string(),
+            backend: backend.to_string(),
+            config: config.to_string(),
+            file: file.to_string(),
+            violations: check_baseline(baseline_dir, file, &results),
+            results,
+            histograms,
+            process,
+            resumed: true,
+        });
+    }
+    (finished, stopped)
+}
+
+/// Runs an external backend over `queries` against the corpus at
+/// `corpus_path` in a child process, saving its stderr under the bundle's
+/// `logs/`. Gives its results, or why it failed, with the process when it
+/// started at all. Warm-up runs are made first and only their failures are
+/// kept.
+fn run_external(
+    external: &ExternalSpec,
+    corpus_path: &str,
+    queries: &[Query],
+    limits: &Limits,
+    warmup: &Warmup,
+    out_dir: &str,
+    name: &str,
+) -> (Result<ResultSet, String>, Option<Process>) {
+    let input = |queries: &[Query]| -> String {
+        queries
+            .iter()
+            .map(|q| format!("{}\t{}\n", q.text, q.field))
+            .collect()
+    };
+    let mut command = external.command.clone();
+    command.push(corpus_path.to_string());
+    if warmup.index {
+        if let Err(e) = warmup::read_through(corpus_path) {
+            return (Err(format!("could not read {corpus_path}: {e}")), None);
+        }
+    }
+    let warmup_queries = &queries[..warmup.query_count(queries.len())];
+    let warmup_runs = [
+        (!warmup_queries.is_empty()).then_some(("warm-up", warmup_queries)),
+        warmup.discard_first.then_some(("discarded first", queries)),
+    ];
+    for (run, queries) in warmup_runs.into_iter().flatten() {
+        let failure = match subprocess::run(&command, &input(queries), limits) {
+            Ok(outcome) => outcome.failure(),
+            Err(e) => Some(format!("could not run `{}`: {e}", command[0])),
+        };
+        if let Some(reason) = failure {
+            return (Err(format!("{run} run: {reason}")), None);
+        }
+    }
+    let mut outcome = match subprocess::run(&command, &input(queries), limits) {
+        Ok(outcome) => outcome,
+        Err(e) => return (Err(format!("could not run `{}`: {e}", command[0])), None),
+    };
+    let stderr_file = (!outcome.stderr.is_empty()).then(|| {
+        let file = format!("logs/{name}.stderr");
+        let path = Path::new(out_dir).join(&file);
+        fs::create_dir_all(path.parent().unwrap()).expect("Failed to create logs directory");
+        fs::write(&path, &outcome.stderr).expect("Failed to write stderr log");
+        file
+    });
+    let failure = outcome.failure();
+    let mut hits = subprocess::ranked_hits(&outcome.stdout);
+    outcome.stdout.clear();
+    outcome.stderr.clear();
+    let process = Some(Process {
+        outcome,
+        stderr_file,
+    });
+    if let Some(reason) = failure {
+        return (Err(reason), process);
+    }
+
+    let records = queries
+        .iter()
+        .map(|q| {
+            let mut record = QueryResult::new(q);
+            let mut results = hits
+                .remove(&(q.text.clone(), q.field.clone()))
+                .unwrap_or_default();
+            results.truncate(RECORDED_RESULTS);
+            record.results = Some(results);
+            record
+        })
+        .collect();
+    let results = ResultSet {
+        backend: external.name.clone(),
+        run: String::new(),
+        corpus: None,
+        corpus_sha256: None,
+        fingerprint: None,
+        startup: None,
+        steady_state: None,
+        memory: Vec::new(),
+        queries: records,
+    };
+    (Ok(results), process)
+}
+
+/// Adds the score of every match of `q` to `histogram`, marking those whose
+/// name contains the expected name, as [`QueryResult::found`] does.
+fn count_scores(
+    histogram: &mut ScoreHistogram,
+    corpus: &Corpus,
+    columns: &PreparedCorpus,
+    q: &Query,
+    pattern: &Pattern,
+    matcher: &mut Matcher,
+) {
+    let expected = (!q.expected_name.is_empty() && q.expected_name != SKIP)
+        .then(|| q.expected_name.to_lowercase());
+    let mut buf = Vec::new();
+    for (ci, candidate) in columns.column(&q.field).iter().enumerate() {
+        buf.clear();
+        if let Some(score) = pattern.score(Utf32Str::new(candidate, &mut buf), matcher) {
+            let relevant = expected
+                .as_ref()
+                .is_some_and(|e| corpus.get(ci).name.to_lowercase().contains(e));
+            histogram.add(score as f64, relevant);
+        }
+    }
+}
+
+/// Headline numbers of a scenario: summed median latency, p95 of the
+/// per-query medians, and ground-truth hits over judged queries.
+fn headline(results: &ResultSet) -> (f64, f64, usize, usize) {
+    let medians: Vec<f64> = results.queries.iter().filter_map(|q| q.median_ms).collect();
+    let verdicts: Vec<bool> = results
+        .queries
+        .iter()
+        .filter_map(QueryResult::found)
+        .collect();
+    (
+        medians.iter().sum(),
+        percentile(&medians, 95.0).unwrap_or(0.0),
+        verdicts.iter().filter(|found| **found).count(),
+        verdicts.len(),
+    )
+}
+
+fn write_index(
+    campaign: &Campaign,
+    finished: &[Finished],
+    stopped: &[Stopped],
+    jobs: usize,
+    out_dir: &str,
+) {
+    let scenarios: Vec<_> = finished
+        .iter()
+        .map(|f| {
+            let (total_ms, p95_ms, found, judged) = headline(&f.results);
+            json!({
+                "corpus": f.corpus,
+                "queries": f.queries,
+                "backend": f.backend,
+                "config": f.config,
+                "file": f.file,
+                "total_median_ms": total_ms,
+                "p95_ms": p95_ms,
+                "found": found,
+                "judged": judged,
+                "regressions": f.violations.as_ref().map(|v| {
+                    v.iter().map(Violation::to_string).collect::<Vec<_>>()
+                }),
+                "process": f.process.as_ref().map(Process::to_json),
+                "resumed": f.resumed,
+            })
+        })
+        .collect();
+    let index = json!({
+        "campaign": campaign.name,
+        "iterations": campaign.iterations,
+        "category_iterations": campaign.category_iterations,
+        "warmup": campaign
+            .backends
+            .iter()
+            .map(|backend| (backend.clone(), campaign.warmup(backend).to_json()))
+            .collect::<serde_json::Map<_, _>>(),
+        "jobs": jobs,
+        "histograms": "histograms.tsv",
+        "scenarios": scenarios,
+        "stopped": stopped
+            .iter()
+            .map(|s| {
+                json!({
+                    "scenario": s.name,
+                    "backend": s.backend,
+                    "reason": s.reason,
+                    "process": s.process.as_ref().map(Process::to_json),
+                    "resumed": s.resumed,
+                })
+            })
+            .collect::<Vec<_>>(),
+    });
+    fs::write(
+        Path::new(out_dir).join("bundle.json"),
+        serde_json::to_string_pretty(&index).unwrap() + "\n",
+    )
+    .expect("Failed to write bundle index");
+}
+
+/// One row per scenario, category and non-empty score bin.
+fn write_histograms(finished: &[Finished], out_dir: &str) {
+    let mut out =
+        String::from("corpus\tqueries\tbackend\tconfig\tcategory\tlower\tupper\tcount\trelevant\n");
+    for f in finished {
+        for (category, histogram) in &f.histograms {
+            for bin in histogram.bins() {
+                writeln!(
+                    out,
+                    "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+                    f.corpus,
+                    f.queries,
+                    f.backend,
+                    f.config,
+                    category,
+                    bin.lower,
+                    bin.upper,
+                    bin.count,
+                    bin.relevant
+                )
+                .unwrap();
+            }
+        }
+    }
+    fs::write(Path::new(out_dir).join("histograms.tsv"), out)
+        .expect("Failed to write score histograms");
+}
+
+/// A Markdown table per corpus and query set, one row per backend and
+/// configuration, fastest first.
+fn summary(
+    campaign: &Campaign,
+    finished: &[Finished],
+    stopped: &[Stopped],
+    jobs: usize,
+    baseline_dir: Option<&str>,
+) -> String {
+    let mut out = String::new();
+    writeln!(out, "## Campaign {}", campaign.name).unwrap();
+    writeln!(out).unwrap();
+    if jobs > 1 {
+        writeln!(
+            out,
+            "Up to {jobs} cells ran at once, sharing the machine's caches and memory \
+             bandwidth."
+        )
+        .unwrap();
+        writeln!(out).unwrap();
+    }
+    let resumed = finished.iter().filter(|f| f.resumed).count()
+        + stopped.iter().filter(|s| s.resumed).count();
+    if resumed > 0 {
+        writeln!(
+            out,
+            "Resumed from a checkpoint: {resumed} of {} scenarios ran before the interruption, \
+             marked ↻ below.",
+            finished.len() + stopped.len()
+        )
+        .unwrap();
+        writeln!(out).unwrap();
+    }
+    for corpus in &campaign.corpora {
+        for queries in &campaign.query_sets {
+            let mut rows: Vec<&Finished> = finished
+                .iter()
+                .filter(|f| f.corpus == corpus.name && f.queries == queries.name)
+                .collect();
+            // External backends are not timed per query, so they go last
+            rows.sort_by(|a, b| {
+                (a.process.is_some(), headline(&a.results).0)
+                    .partial_cmp(&(b.process.is_some(), headline(&b.results).0))
+                    .unwrap()
+            });
+            writeln!(out, "### {} × {}", corpus.name, queries.name).unwrap();
+            writeln!(out).unwrap();
+            writeln!(
+                out,
+                "| Backend | Config | Total median (ms) | p95 (ms) | Ground truth |"
+            )
+            .unwrap();
+            writeln!(out, "|---|---|--:|--:|--:|").unwrap();
+            for f in rows {
+                let (total_ms, p95_ms, found, judged) = headline(&f.results);
+                let hit_rate = if judged > 0 {
+                    format!(
+                        "{:.1}% ({found}/{judged})",
+                        found as f64 * 100.0 / judged as f64
+                    )
+                } else {
+                    "—".to_string()
+                };
+                let (total, p95) = match f.process {
+                    Some(_) => ("—".to_string(), "—".to_string()),
+                    None => (format!("{total_ms:.2}"), format!("{p95_ms:.3}")),
+                };
+                writeln!(
+                    out,
+                    "| {} | {}{} | {total} | {p95} | {hit_rate} |",
+                    f.backend,
+                    f.config,
+                    if f.resumed { " ↻" } else { "" }
+                )
+                .unwrap();
+            }
+            writeln!(out).unwrap();
+        }
+    }
+    let processes: Vec<(String, &Process)> = finished
+        .iter()
+        .filter_map(|f| Some((scenario_name(&f.file), f.process.as_ref()?)))
+        .chain(
+            stopped
+                .iter()
+                .filter_map(|s| Some((s.name.clone(), s.process.as_ref()?))),
+        )
+        .collect();
+    if !processes.is_empty() {
+        writeln!(out, "### External processes").unwrap();
+        writeln!(out).unwrap();
+        writeln!(
+            out,
+            "| Scenario | Exit | Wall (s) | CPU (s) | Max RSS (MiB) | stderr |"
+        )
+        .unwrap();
+        writeln!(out, "|---|---|--:|--:|--:|---|").unwrap();
+        for (name, process) in processes {
+            let outcome = &process.outcome;
+            let exit = match (outcome.stopped, outcome.code, outcome.signal) {
+                (Some(_), _, _) => "killed at limit".to_string(),
+                (None, Some(code), _) => code.to_string(),
+                (None, None, signal) => format!("signal {}", signal.unwrap_or(0)),
+            };
+            writeln!(
+                out,
+                "| {name} | {exit} | {:.2} | {:.2} | {:.1} | {} |",
+                outcome.usage.wall_ms / 1000.0,
+                (outcome.usage.user_ms + outcome.usage.system_ms) / 1000.0,
+                outcome.usage.max_rss_bytes as f64 / (1u64 << 20) as f64,
+                process.stderr_file.as_deref().unwrap_or("—")
+            )
+            .unwrap();
+        }
+        writeln!(out).unwrap();
+    }
+    if !stopped.is_empty() {
+        writeln!(out, "### Stopped scenarios").unwrap();
+        writeln!(out).unwrap();
+        for s in stopped {
+            let mark = if s.resumed { " ↻" } else { "" };
+            writeln!(out, "- **{}**{mark}: {}", s.name, s.reason).unwrap();
+        }
+        writeln!(out).unwrap();
+    }
+    if let Some(dir) = baseline_dir {
+        writeln!(out, "### Regressions against {dir}").unwrap();
+        writeln!(out).unwrap();
+        let compared = finished.iter().filter(|f| f.violations.is_some()).count();
+        let regressed: Vec<&Finished> = finished
+            .iter()
+            .filter(|f| f.violations.as_ref().is_some_and(|v| !v.is_empty()))
+            .collect();
+        if regressed.is_empty() {
+            writeln!(out, "None across {compared} compared scenarios.").unwrap();
+        }
+        for f in regressed {
+            writeln!(out, "- **{}**", scenario_name(&f.file)).unwrap();
+            for violation in f.violations.iter().flatten() {
+                writeln!(out, "  - {violation}").unwrap();
+            }
+        }
+        writeln!(out).unwrap();
+    }
+    out
+}
+
+/// The scenario a result file under `results/` belongs to.
+fn scenario_name(file: &str) -> String {
+    file.trim_start_matches("results/")
+        .trim_end_matches(".json")
+        .to_string()
+}
+
+/// A few lines for chat: the fastest configuration per corpus and query set
+/// and the scenarios that regressed.
+fn notification(campaign: &Campaign, finished: &[Finished], stopped: &[Stopped]) -> String {
+    let mut out = format!(
+        "Campaign {} finished: {} scenarios.",
+        campaign.name,
+        finished.len()
+    );
+    if !stopped.is_empty() {
+        write!(out, " {} stopped early.", stopped.len()).unwrap();
+    }
+    for corpus in &campaign.corpora {
+        for queries in &campaign.query_sets {
+            let fastest = finished
+                .iter()
+                .filter(|f| f.corpus == corpus.name && f.queries == queries.name)
+                .filter(|f| f.process.is_none())
+                .min_by(|a, b| {
+                    headline(&a.results)
+                        .0
+                        .partial_cmp(&headline(&b.results).0)
+                        .unwrap()
+                });
+            if let Some(f) = fastest {
+                let (total_ms, _, found, judged) = headline(&f.results);
+                write!(
+                    out,
+                    "\n• {} × {}: fastest {}/{} at {total_ms:.1}ms",
+                    corpus.name, queries.name, f.backend, f.config
+                )
+                .unwrap();
+                if judged > 0 {
+                    write!(
+                        out,
+                        ", {:.1}% ground truth",
+                        found as f64 * 100.0 / judged as f64
+                    )
+                    .unwrap();
+                }
+            }
+        }
+    }
+    let regressed: Vec<&Finished> = finished
+        .iter()
+        .filter(|f| f.violations.as_ref().is_some_and(|v| !v.is_empty()))
+        .collect();
+    if finished.iter().any(|f| f.violations.is_some()) {
+        if regressed.is_empty() {
+            out.push_str("\nNo regressions against the baseline.");
+        } else {
+            write!(
+                out,
+                "\nRegressions against the baseline in {} scenarios:",
+                regressed.len()
+            )
+            .unwrap();
+            for f in regressed {
+                let violations = f.violations.as_deref().unwrap_or_default();
+                write!(
+                    out,
+                    "\n• {}/{} on {} × {}: {}",
+                    f.backend, f.config, f.corpus, f.queries, violations[0]
+                )
+                .unwrap();
+                if violations.len() > 1 {
+                    write!(out, " (+{} more)", violations.len() - 1).unwrap();
+                }
+            }
+        }
+    }
+    out
+}
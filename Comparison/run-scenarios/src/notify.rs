@@ -0,0 +1,19 @@
+//! Posts a campaign summary to a webhook. Requires the `notify` feature.
+//!
+//! The body is `{"text": ...}`, which Slack incoming webhooks and most chat
+//! integrations that mimic them (Mattermost, Rocket.Chat, Discord's `/slack`
+//! endpoint) accept as a plain message.
+
+use serde_json::json;
+use std::time::Duration;
+
+/// Sends `text` to `url`. Any non-2xx answer is an error.
+pub fn post(url: &str, text: &str) -> Result<(), String> {
+    let body = json!({ "text": text }).to_string();
+    ureq::post(url)
+        .timeout(Duration::from_secs(30))
+        .set("Content-Type", "application/json")
+        .send_string(&body)
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
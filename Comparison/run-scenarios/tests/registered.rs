@@ -0,0 +1,108 @@
+use harness::results::ResultSet;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+const CORPUS: &str = "symbol\tname\tisin\n\
+                      AAPL\tApple Inc.\tUS0378331005\n\
+                      APLE\tApple Hospitality REIT\tUS03784Y2000\n\
+                      MSFT\tMicrosoft Corp.\tUS5949181045\n";
+const QUERIES: &str = "aapl\tsymbol\texact_symbol\tApple Inc.\n\
+                       microsoft\tname\tprefix\tMicrosoft Corp.\n";
+
+/// A fresh directory holding the corpus, the queries and `campaign`, with
+/// `BIN` in it replaced by the path of the `registry` binary.
+fn campaign_dir(name: &str, campaign: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("run-scenarios-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("corpus.tsv"), CORPUS).unwrap();
+    fs::write(dir.join("queries.tsv"), QUERIES).unwrap();
+    let campaign = campaign.replace("BIN", env!("CARGO_BIN_EXE_registry"));
+    fs::write(dir.join("campaign.toml"), campaign).unwrap();
+    dir
+}
+
+fn run(dir: &Path) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_run-scenarios"))
+        .arg(dir.join("campaign.toml"))
+        .arg("--out")
+        .arg(dir.join("bundle"))
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn runs_a_registered_backend_by_name() {
+    let dir = campaign_dir(
+        "registered",
+        r#"
+name = "registered"
+iterations = 1
+backends = ["nucleo", "nucleo-cli"]
+
+[[corpus]]
+name = "tiny"
+path = "corpus.tsv"
+
+[[queries]]
+name = "tiny"
+path = "queries.tsv"
+
+[[registered]]
+name = "nucleo-cli"
+bin = "BIN"
+backend = "nucleo"
+"#,
+    );
+    let output = run(&dir);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let bundle: Value =
+        serde_json::from_str(&fs::read_to_string(dir.join("bundle/bundle.json")).unwrap()).unwrap();
+    let scenario = bundle["scenarios"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|s| s["backend"] == "nucleo-cli")
+        .expect("no scenario for the registered backend");
+    assert_eq!(scenario["process"]["exit_code"], 0, "{scenario}");
+    let file = dir.join("bundle").join(scenario["file"].as_str().unwrap());
+    let results = ResultSet::read(file.to_str().unwrap()).unwrap();
+    assert_eq!(results.backend, "nucleo-cli");
+    let top = |i: usize| {
+        results.queries[i].results.as_ref().unwrap()[0]
+            .symbol
+            .clone()
+    };
+    assert_eq!(top(0), "AAPL");
+    assert_eq!(top(1), "MSFT");
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn rejects_a_backend_the_binary_does_not_register() {
+    let dir = campaign_dir(
+        "unregistered",
+        r#"
+backends = ["acme"]
+
+[[registered]]
+bin = "BIN"
+backend = "acme"
+"#,
+    );
+    let output = run(&dir);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("does not register backend `acme` (it lists: nucleo)"),
+        "{stderr}"
+    );
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,6 @@
+fn main() {
+    let protoc = protoc_bin_vendored::protoc_bin_path().expect("No vendored protoc for this host");
+    std::env::set_var("PROTOC", protoc);
+    tonic_build::compile_protos("proto/search.proto")
+        .expect("Failed to compile proto/search.proto");
+}
@@ -0,0 +1,260 @@
+//! Serves streaming corpus searches over gRPC.
+//!
+//! Usage: search-grpc [--tsv PATH] [--map symbol=KEY,name=KEY,isin=KEY]
+//!                    [--addr 127.0.0.1:50051] [--metrics-addr 127.0.0.1:9464]
+//!        search-grpc --connect http://127.0.0.1:50051 --query TEXT
+//!                    [--field name] [--min-score N] [--limit N]
+//!
+//! The service is defined in `proto/search.proto`, for clients to generate
+//! their own stubs from. A search streams each row whose score reaches the
+//! request's minimum as soon as the scan reaches it, then a summary, so a
+//! client can render the first results while the rest of the corpus is
+//! still being scored. Scans run on the blocking pool and stop early when
+//! the client hangs up or the hit limit is reached.
+//!
+//! `--metrics-addr` serves `GET /metrics` over HTTP on a second address,
+//! in the Prometheus text format `search-server` uses (see
+//! `harness::telemetry`). A search's latency runs from the request to its
+//! `Done` event, and its returned results are the hits streamed.
+//!
+//! With `--connect` it is a client instead: it runs one search and prints
+//! each event with the time it arrived, which shows the time to the first
+//! hit next to the time to the whole answer.
+
+use axum::http::header;
+use axum::routing::get;
+use axum::Router;
+use harness::cli::{flag_value, parsed_flag};
+use harness::corpus::{load_corpus_mapped, Corpus, FieldMap};
+use harness::prepared::PreparedCorpus;
+use harness::resource_path;
+use harness::telemetry::{SearchMetrics, SearchObservation, CONTENT_TYPE};
+use nucleo_matcher::pattern::{AtomKind, CaseMatching, Normalization, Pattern};
+use nucleo_matcher::{Config, Matcher, Utf32Str};
+use std::env;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::transport::Server;
+use tonic::{Request, Response, Status};
+
+mod proto {
+    tonic::include_proto!("fuzzymatch.search.v1");
+}
+
+use proto::search_client::SearchClient;
+use proto::search_event::Event;
+use proto::search_server::{Search, SearchServer};
+use proto::{Done, Hit, SearchEvent, SearchRequest};
+
+/// Events buffered per search before the scan waits for the client.
+const STREAM_BUFFER: usize = 64;
+
+struct Index {
+    corpus: Corpus,
+    prepared: PreparedCorpus,
+}
+
+struct SearchService {
+    index: Arc<Index>,
+    metrics: Arc<SearchMetrics>,
+}
+
+#[tonic::async_trait]
+impl Search for SearchService {
+    type SearchStream = ReceiverStream<Result<SearchEvent, Status>>;
+
+    async fn search(
+        &self,
+        request: Request<SearchRequest>,
+    ) -> Result<Response<Self::SearchStream>, Status> {
+        let received = Instant::now();
+        let request = request.into_inner();
+        if request.query.trim().is_empty() {
+            self.metrics.reject();
+            return Err(Status::invalid_argument("empty query"));
+        }
+        let (events, stream) = mpsc::channel(STREAM_BUFFER);
+        let index = Arc::clone(&self.index);
+        let metrics = Arc::clone(&self.metrics);
+        tokio::task::spawn_blocking(move || {
+            let mut observed = scan(&index, &request, &events);
+            observed.latency = received.elapsed();
+            metrics.observe(&observed);
+        });
+        Ok(Response::new(ReceiverStream::new(stream)))
+    }
+}
+
+/// Scans for `request`, sending each hit as it is found and a summary at
+/// the end. Stops early if the client has gone. Returns the rows scanned
+/// and matched and the hits sent.
+fn scan(
+    index: &Index,
+    request: &SearchRequest,
+    events: &mpsc::Sender<Result<SearchEvent, Status>>,
+) -> SearchObservation {
+    let start = Instant::now();
+    let field = if request.field.is_empty() {
+        "name"
+    } else {
+        &request.field
+    };
+    let pattern = Pattern::new(
+        &request.query,
+        CaseMatching::Ignore,
+        Normalization::Smart,
+        AtomKind::Fuzzy,
+    );
+    let mut matcher = Matcher::new(Config::DEFAULT);
+    let mut buf = Vec::new();
+    let (mut scanned, mut matches, mut sent) = (0u64, 0u64, 0u32);
+    for (idx, candidate) in index.prepared.column(field).iter().enumerate() {
+        scanned += 1;
+        let Some(score) = pattern.score(Utf32Str::new(candidate, &mut buf), &mut matcher) else {
+            continue;
+        };
+        matches += 1;
+        if score < request.min_score {
+            continue;
+        }
+        let row = index.corpus.get(idx);
+        let hit = Hit {
+            row: idx as u32,
+            symbol: row.symbol.to_string(),
+            name: row.name.to_string(),
+            isin: row.isin.to_string(),
+            score,
+        };
+        let event = SearchEvent {
+            event: Some(Event::Hit(hit)),
+        };
+        if events.blocking_send(Ok(event)).is_err() {
+            break;
+        }
+        sent += 1;
+        if sent == request.limit {
+            break;
+        }
+    }
+    let done = Done {
+        scanned,
+        matches,
+        scan_ms: start.elapsed().as_secs_f64() * 1000.0,
+    };
+    let _ = events.blocking_send(Ok(SearchEvent {
+        event: Some(Event::Done(done)),
+    }));
+    SearchObservation {
+        scanned: scanned as usize,
+        matched: matches as usize,
+        returned: sent as usize,
+        ..SearchObservation::default()
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let runtime = tokio::runtime::Runtime::new().expect("Failed to start runtime");
+    if let Some(url) = flag_value(&args, "--connect") {
+        runtime.block_on(connect(url.to_string(), &args));
+        return;
+    }
+
+    let tsv_path = flag_value(&args, "--tsv")
+        .map(str::to_string)
+        .unwrap_or_else(|| resource_path("instruments-export.tsv"));
+    let field_map: FieldMap = parsed_flag(&args, "--map", FieldMap::default());
+    let addr = flag_value(&args, "--addr")
+        .unwrap_or("127.0.0.1:50051")
+        .parse()
+        .expect("Invalid --addr");
+
+    let corpus = load_corpus_mapped(&tsv_path, &field_map).expect("Failed to read corpus");
+    let prepared = PreparedCorpus::new(&corpus, []);
+    eprintln!("Loaded {} rows from {}", corpus.len(), tsv_path);
+    let rows = corpus.len();
+    let metrics = Arc::new(SearchMetrics::new("search_grpc"));
+    let service = SearchService {
+        index: Arc::new(Index { corpus, prepared }),
+        metrics: Arc::clone(&metrics),
+    };
+    if let Some(metrics_addr) = flag_value(&args, "--metrics-addr") {
+        let listener = runtime
+            .block_on(tokio::net::TcpListener::bind(metrics_addr))
+            .unwrap_or_else(|e| panic!("Failed to listen on {metrics_addr}: {e}"));
+        let app = Router::new().route(
+            "/metrics",
+            get(move || async move {
+                let gauges = [("rows", "Rows in the corpus.", rows as f64)];
+                (
+                    [(header::CONTENT_TYPE, CONTENT_TYPE)],
+                    metrics.render(&gauges),
+                )
+            }),
+        );
+        eprintln!("Metrics on http://{}/metrics", metrics_addr);
+        runtime.spawn(async move {
+            axum::serve(listener, app)
+                .await
+                .expect("Metrics server failed")
+        });
+    }
+    eprintln!("Listening on {}", addr);
+    runtime
+        .block_on(
+            Server::builder()
+                .add_service(SearchServer::new(service))
+                .serve(addr),
+        )
+        .expect("Server failed");
+}
+
+/// Runs one search against a server and prints its events as they arrive.
+async fn connect(url: String, args: &[String]) {
+    let query = flag_value(args, "--query").expect("--connect needs --query TEXT");
+    let request = SearchRequest {
+        query: query.to_string(),
+        field: flag_value(args, "--field").unwrap_or("name").to_string(),
+        min_score: parsed_flag(args, "--min-score", 0),
+        limit: parsed_flag(args, "--limit", 20),
+    };
+    let mut client = SearchClient::connect(url.clone())
+        .await
+        .unwrap_or_else(|e| panic!("Failed to connect to {url}: {e}"));
+    let start = Instant::now();
+    let mut stream = client
+        .search(request)
+        .await
+        .unwrap_or_else(|e| panic!("Search failed: {}", e.message()))
+        .into_inner();
+    let mut first_hit = None;
+    while let Some(event) = stream
+        .message()
+        .await
+        .unwrap_or_else(|e| panic!("Stream failed: {}", e.message()))
+    {
+        let at_ms = start.elapsed().as_secs_f64() * 1000.0;
+        match event.event {
+            Some(Event::Hit(hit)) => {
+                first_hit.get_or_insert(at_ms);
+                println!(
+                    "{:>9.2} ms  {:>5}  {}\t{}\t{}",
+                    at_ms, hit.score, hit.symbol, hit.name, hit.isin
+                );
+            }
+            Some(Event::Done(done)) => {
+                println!();
+                println!(
+                    "Done at {:.2} ms: {} matches in {} rows scanned ({:.2} ms server-side)",
+                    at_ms, done.matches, done.scanned, done.scan_ms
+                );
+                if let Some(first) = first_hit {
+                    println!("First hit at {:.2} ms", first);
+                }
+            }
+            None => {}
+        }
+    }
+}
@@ -0,0 +1,520 @@
+//! Serves corpus searches over HTTP, for measuring latency end to end.
+//!
+//! Usage: search-server [--tsv PATH] [--map symbol=KEY,name=KEY,isin=KEY]
+//!                      [--addr 127.0.0.1:8080] [--threads N] [--compact-at 0.25]
+//!                      [--index PATH [--trust-index]] [--preset NAME]
+//!                      [--variant PRESET [--variant-share 0.1] [--experiment NAME]]
+//!                      [--experiment-log PATH]
+//!        search-server --summarize PATH
+//!
+//! `GET /search?q=QUERY&field=name&limit=10` scans the prepared field with
+//! the nucleo pattern the harnesses time, parsed and ranked as `--preset`
+//! says (a field the corpus lacks is searched as the name, as in the
+//! harnesses), and answers with JSON:
+//!
+//! ```text
+//! {"request_id": "18f3a2c4e01-7", "query": "...", "field": "name",
+//!  "matches": 12, "scan_ms": 0.84,
+//!  "snapshot": {"corpus_sha256": "...", "generation": 0},
+//!  "results": [{"symbol": "...", "name": "...", "isin": "...", "score": 151,
+//!               "highlights": {"name": [[0, 4], [9, 12]]}}]}
+//! ```
+//!
+//! `snapshot` names the corpus state the answer came from: the corpus
+//! file's SHA-256 (from its manifest, or hashed at startup) and how many
+//! delistings had changed it. A client that logs it with each answer can
+//! tell a stale result from one the corpus has since changed under.
+//!
+//! `field` may list several fields, as in `field=symbol,name`: a row then
+//! scores its best field, and `highlights` holds the `[start, end)`
+//! character ranges of every listed field the query matched, so a client
+//! can draw the symbol and name of a result without matching them again.
+//!
+//! `scan_ms` is the scan alone, so a client can tell it apart from what
+//! routing, JSON and the network add (see `load-test`). Scans run on the
+//! blocking pool, so concurrent requests are scored in parallel up to the
+//! worker thread count. `GET /health` answers `ok` once the corpus is
+//! loaded.
+//!
+//! `GET /metrics` exports counts of searches and invalid requests, and
+//! histograms of each search's latency, rows scanned, rows matched and
+//! results returned, in the Prometheus text format (see
+//! `harness::telemetry`), with the live rows, tombstones and generation
+//! of the corpus as gauges.
+//!
+//! `POST /delist?field=isin&value=...` deletes every row whose field holds
+//! the value, and answers `{"deleted": 2, "live": ..., "tombstones": ...,
+//! "compactions": ..., "snapshot": ...}` with the snapshot searches see
+//! from then on. The rows are tombstoned rather than removed (see
+//! `harness::mutable`), so they drop out of the very next search without
+//! the columns being built again. Once tombstones make up more than
+//! `--compact-at` of the rows, the delisting request also compacts the
+//! corpus, and searches wait for it to finish.
+//!
+//! `--index PATH` maps a saved prepared index (see `harness::prepared`)
+//! instead of preparing the columns, so the server shares one copy of
+//! them in the page cache with any harness run reading the same index.
+//! The corpus is still loaded for the rows results show. A compaction
+//! builds the columns again in the server's own memory. Opening compares
+//! the index with its checksum unless `--trust-index` is given.
+//!
+//! `--variant PRESET` runs an experiment (see `harness::experiment`): a
+//! `--variant-share` of users is ranked with the variant preset instead of
+//! `--preset`. A search's `user` parameter, or else its `session`, decides
+//! the arm, so a user stays in one; a search with neither is assigned on
+//! its own. Answers then carry `"experiment": {"name": ..., "arm":
+//! "variant", "preset": ..., "config_sha256": ...}`. `--experiment-log
+//! PATH` appends each search, with its arm and the ISINs it answered, to
+//! PATH as a JSON line, and `POST /click?request_id=...&rank=N` records
+//! that the user opened the Nth result of that search. `--summarize PATH`
+//! prints the click-through of each arm in such a log and exits.
+
+use axum::extract::{Query, State};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use harness::cli::{flag_value, has_flag, parsed_flag};
+use harness::corpus::{load_corpus_mapped, FieldMap};
+use harness::experiment::{summarize, Arm, EventLog, Experiment};
+use harness::highlight::Highlights;
+use harness::manifest::{self, Manifest};
+use harness::mutable::{MutableCorpus, COMPACT_AT};
+use harness::prepared::{Column, PreparedCorpus};
+use harness::preset::Preset;
+use harness::resource_path;
+use harness::telemetry::{SearchMetrics, SearchObservation, CONTENT_TYPE};
+use harness::topk::TopK;
+use nucleo_matcher::{Config, Matcher, Utf32Str};
+use serde_json::{json, Value};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::env;
+use std::fs::File;
+use std::io::BufReader;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+/// Results returned when a request does not ask for a number.
+const DEFAULT_LIMIT: usize = 10;
+/// The most results one request may ask for.
+const MAX_LIMIT: usize = 1000;
+
+type Index = Arc<RwLock<MutableCorpus>>;
+
+#[derive(Clone)]
+struct AppState {
+    index: Index,
+    ranking: Arc<Ranking>,
+    metrics: Arc<SearchMetrics>,
+}
+
+/// How searches are ranked and logged.
+struct Ranking {
+    /// `--preset`, for every search outside an experiment's variant.
+    control: Arm,
+    experiment: Option<Experiment>,
+    log: Option<EventLog>,
+    /// The server's start time, prefixing request ids so that they stay
+    /// unique across restarts appending to one log.
+    started_ms: u64,
+    requests: AtomicU64,
+}
+
+thread_local! {
+    // A matcher's scratch space is sized on first use; keeping one per
+    // blocking-pool thread spares every request that allocation.
+    static MATCHER: RefCell<Matcher> = RefCell::new(Matcher::new(Config::DEFAULT));
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if let Some(path) = flag_value(&args, "--summarize") {
+        print_summary(path);
+        return;
+    }
+    let preset = Preset::from_args(&args).unwrap_or_else(|e| panic!("Invalid preset: {e}"));
+    let experiment =
+        Experiment::from_args(&args).unwrap_or_else(|e| panic!("Invalid experiment: {e}"));
+    let log = flag_value(&args, "--experiment-log")
+        .map(|path| EventLog::open(path).unwrap_or_else(|e| panic!("Failed to open {path}: {e}")));
+    let tsv_path = flag_value(&args, "--tsv")
+        .map(str::to_string)
+        .unwrap_or_else(|| resource_path("instruments-export.tsv"));
+    let field_map: FieldMap = parsed_flag(&args, "--map", FieldMap::default());
+    let addr = flag_value(&args, "--addr").unwrap_or("127.0.0.1:8080");
+    let compact_at: f64 = parsed_flag(&args, "--compact-at", COMPACT_AT);
+
+    let start = Instant::now();
+    let corpus = load_corpus_mapped(&tsv_path, &field_map).expect("Failed to read corpus");
+    let rows = corpus.len();
+    let mut index = match flag_value(&args, "--index") {
+        Some(path) => {
+            let open = if has_flag(&args, "--trust-index") {
+                PreparedCorpus::open_trusted
+            } else {
+                PreparedCorpus::open
+            };
+            let prepared = open(path).unwrap_or_else(|e| panic!("Failed to open index: {e}"));
+            prepared
+                .check(&corpus, [])
+                .unwrap_or_else(|e| panic!("Index {path} does not match the corpus: {e}"));
+            eprintln!("Opened {}: {}", path, prepared.summary());
+            MutableCorpus::with_prepared(corpus, prepared, &[], compact_at)
+        }
+        None => MutableCorpus::new(corpus, &[], compact_at),
+    };
+    index.set_corpus_sha256(
+        Manifest::read_for(&tsv_path)
+            .ok()
+            .flatten()
+            .map(|m| m.sha256)
+            .or_else(|| manifest::sha256_file(&tsv_path).ok()),
+    );
+    eprintln!(
+        "Loaded {} rows from {} in {:.0} ms, snapshot {}",
+        rows,
+        tsv_path,
+        start.elapsed().as_secs_f64() * 1000.0,
+        index.snapshot()
+    );
+    if let Some(experiment) = &experiment {
+        eprintln!(
+            "Experiment {}: {:.0}% of users ranked with {} instead of {}",
+            experiment.name,
+            experiment.share * 100.0,
+            experiment.variant.preset.name,
+            experiment.control.preset.name
+        );
+    }
+    let state = AppState {
+        index: Arc::new(RwLock::new(index)),
+        ranking: Arc::new(Ranking {
+            control: Arm::new("control", preset),
+            experiment,
+            log,
+            started_ms: now_ms(),
+            requests: AtomicU64::new(0),
+        }),
+        metrics: Arc::new(SearchMetrics::new("search_server")),
+    };
+
+    let mut runtime = tokio::runtime::Builder::new_multi_thread();
+    if let Some(threads) = flag_value(&args, "--threads") {
+        let threads: usize = threads.parse().expect("Invalid --threads");
+        runtime
+            .worker_threads(threads)
+            .max_blocking_threads(threads);
+    }
+    let runtime = runtime
+        .enable_all()
+        .build()
+        .expect("Failed to start runtime");
+    runtime.block_on(async {
+        let app = Router::new()
+            .route("/search", get(search))
+            .route("/delist", post(delist))
+            .route("/click", post(click))
+            .route("/metrics", get(metrics))
+            .route("/health", get(|| async { "ok" }))
+            .with_state(state);
+        let listener = tokio::net::TcpListener::bind(addr)
+            .await
+            .unwrap_or_else(|e| panic!("Failed to listen on {addr}: {e}"));
+        eprintln!("Listening on http://{}", addr);
+        axum::serve(listener, app).await.expect("Server failed");
+    });
+}
+
+async fn search(
+    State(state): State<AppState>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Response {
+    let start = Instant::now();
+    let Some(query) = params.get("q").cloned() else {
+        state.metrics.reject();
+        return bad_request("missing `q`");
+    };
+    let field = params
+        .get("field")
+        .cloned()
+        .unwrap_or_else(|| "name".to_string());
+    let limit = match params.get("limit").map(|l| l.parse::<usize>()) {
+        None => DEFAULT_LIMIT,
+        Some(Ok(limit)) if limit <= MAX_LIMIT => limit,
+        Some(_) => {
+            state.metrics.reject();
+            return bad_request(&format!("`limit` must be 0 to {MAX_LIMIT}"));
+        }
+    };
+    let metrics = Arc::clone(&state.metrics);
+    let ranking = Arc::clone(&state.ranking);
+    let request_id = format!(
+        "{:x}-{}",
+        ranking.started_ms,
+        ranking.requests.fetch_add(1, Ordering::Relaxed)
+    );
+    let unit = params
+        .get("user")
+        .or_else(|| params.get("session"))
+        .cloned()
+        .unwrap_or_else(|| request_id.clone());
+    let (body, scanned) = tokio::task::spawn_blocking(move || {
+        let arm = ranking.arm(&unit);
+        let index = state.index.read().unwrap();
+        let mut body = scan(&index, arm, &query, &field, limit);
+        let scanned = index.len();
+        drop(index);
+        body["request_id"] = json!(request_id);
+        if let Some(experiment) = &ranking.experiment {
+            body["experiment"] = json!({
+                "name": experiment.name,
+                "arm": arm.label,
+                "preset": arm.preset.name,
+                "config_sha256": arm.config_sha256,
+            });
+        }
+        if let Some(log) = &ranking.log {
+            let event = json!({
+                "event": "search",
+                "ts_ms": now_ms(),
+                "request_id": request_id,
+                "unit": unit,
+                "experiment": ranking.experiment.as_ref().map(|e| e.name.as_str()),
+                "arm": arm.label,
+                "preset": arm.preset.name,
+                "config_sha256": arm.config_sha256,
+                "query": query,
+                "field": field,
+                "matches": body["matches"],
+                "results": body["results"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .map(|r| r["isin"].clone())
+                    .collect::<Vec<_>>(),
+                "snapshot": body["snapshot"],
+            });
+            if let Err(e) = log.record(&event) {
+                eprintln!("Failed to log search {request_id}: {e}");
+            }
+        }
+        (body, scanned)
+    })
+    .await
+    .expect("Scan panicked");
+    metrics.observe(&SearchObservation {
+        latency: start.elapsed(),
+        scanned,
+        matched: body["matches"].as_u64().unwrap_or_default() as usize,
+        returned: body["results"].as_array().map_or(0, Vec::len),
+    });
+    Json(body).into_response()
+}
+
+async fn metrics(State(state): State<AppState>) -> Response {
+    let index = state.index.read().unwrap();
+    let gauges = [
+        ("live_rows", "Rows searches can match.", index.len() as f64),
+        (
+            "tombstones",
+            "Deleted rows awaiting compaction.",
+            index.tombstones() as f64,
+        ),
+        (
+            "compactions_total",
+            "Compactions run since startup.",
+            index.compactions() as f64,
+        ),
+        (
+            "corpus_generation",
+            "Deletions that removed rows since startup.",
+            index.snapshot().generation as f64,
+        ),
+    ];
+    let text = state.metrics.render(&gauges);
+    ([(header::CONTENT_TYPE, CONTENT_TYPE)], text).into_response()
+}
+
+async fn click(
+    State(state): State<AppState>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Response {
+    let Some(log) = &state.ranking.log else {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(json!({ "error": "clicks are only recorded with --experiment-log" })),
+        )
+            .into_response();
+    };
+    let Some(request_id) = params.get("request_id") else {
+        return bad_request("missing `request_id`");
+    };
+    let rank = match params.get("rank").map(|r| r.parse::<u64>()) {
+        Some(Ok(rank)) if rank >= 1 => rank,
+        _ => return bad_request("`rank` must be the clicked result's position, from 1"),
+    };
+    let event = json!({
+        "event": "click",
+        "ts_ms": now_ms(),
+        "request_id": request_id,
+        "rank": rank,
+        "isin": params.get("isin"),
+    });
+    match log.record(&event) {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({ "error": e.to_string() })),
+        )
+            .into_response(),
+    }
+}
+
+async fn delist(
+    State(state): State<AppState>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Response {
+    let (Some(field), Some(value)) = (params.get("field").cloned(), params.get("value").cloned())
+    else {
+        return bad_request("missing `field` or `value`");
+    };
+    // On the blocking pool, since the deletion may compact the corpus
+    let body = tokio::task::spawn_blocking(move || {
+        let mut index = state.index.write().unwrap();
+        let deleted = index.delete(&field, &value);
+        json!({
+            "deleted": deleted,
+            "live": index.len(),
+            "tombstones": index.tombstones(),
+            "compactions": index.compactions(),
+            "snapshot": index.snapshot().to_json(),
+        })
+    })
+    .await
+    .expect("Delisting panicked");
+    Json(body).into_response()
+}
+
+fn bad_request(message: &str) -> Response {
+    (StatusCode::BAD_REQUEST, Json(json!({ "error": message }))).into_response()
+}
+
+impl Ranking {
+    /// The arm ranking the searches of unit `key`.
+    fn arm(&self, key: &str) -> &Arm {
+        match &self.experiment {
+            Some(experiment) => experiment.assign(key),
+            None => &self.control,
+        }
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis() as u64)
+}
+
+/// Prints the click-through of every arm in the event log at `path`.
+fn print_summary(path: &str) {
+    let file = File::open(path).unwrap_or_else(|e| panic!("Failed to open {path}: {e}"));
+    let arms =
+        summarize(BufReader::new(file)).unwrap_or_else(|e| panic!("Failed to read {path}: {e}"));
+    println!(
+        "{:<28} {:<8} {:<16} {:>9} {:>8} {:>7} {:>7}  Config",
+        "Experiment", "Arm", "Preset", "Searches", "Clicked", "CTR", "MRR"
+    );
+    for arm in &arms {
+        println!(
+            "{:<28} {:<8} {:<16} {:>9} {:>8} {:>6.1}% {:>7.3}  {}",
+            arm.experiment,
+            arm.arm,
+            arm.preset,
+            arm.searches,
+            arm.clicked,
+            arm.click_through() * 100.0,
+            arm.mrr(),
+            &arm.config_sha256[..arm.config_sha256.len().min(12)]
+        );
+    }
+}
+
+/// Scans each of the comma-separated `field`s for `query` over the live
+/// rows, scoring a row by its best field, and builds the response body.
+/// The arm's preset parses the query and orders equal scores, comparing
+/// the first field's text when shorter candidates come first.
+fn scan(index: &MutableCorpus, arm: &Arm, query: &str, field: &str, limit: usize) -> Value {
+    let start = Instant::now();
+    let preset = &arm.preset;
+    let pattern = preset.pattern(query);
+    let mut fields: Vec<&str> = field
+        .split(',')
+        .map(str::trim)
+        .filter(|f| !f.is_empty())
+        .collect();
+    if fields.is_empty() {
+        fields.push("name");
+    }
+    let columns: Vec<&Column> = fields.iter().map(|f| index.prepared().column(f)).collect();
+    let mut buf = Vec::new();
+    // Ties by row keep only the best `limit` as they come; ties by length
+    // need every match to sort
+    let by_row = preset.tie_break == "row";
+    let mut top = TopK::new(if by_row { limit } else { 0 });
+    let mut all = Vec::new();
+    let mut matches = 0;
+    MATCHER.with_borrow_mut(|matcher| {
+        matcher.config = preset.config();
+        for idx in index.live_rows() {
+            let best = columns
+                .iter()
+                .filter_map(|column| {
+                    pattern.score(Utf32Str::new(column.get(idx), &mut buf), matcher)
+                })
+                .max();
+            if let Some(score) = best {
+                matches += 1;
+                if by_row {
+                    top.push(score, idx);
+                } else {
+                    all.push((score, idx));
+                }
+            }
+        }
+    });
+    let ranked = if by_row {
+        top.into_sorted()
+    } else {
+        preset.rank(&mut all, |idx| columns[0].get(idx));
+        all.truncate(limit);
+        all
+    };
+    let scan_ms = start.elapsed().as_secs_f64() * 1000.0;
+    let results: Vec<Value> = MATCHER.with_borrow_mut(|matcher| {
+        ranked
+            .into_iter()
+            .map(|(score, idx)| {
+                let row = index.corpus().get(idx);
+                let texts = fields.iter().zip(&columns).map(|(f, c)| (*f, c.get(idx)));
+                let highlights = Highlights::new(&pattern, matcher, texts);
+                json!({
+                    "symbol": row.symbol,
+                    "name": row.name,
+                    "isin": row.isin,
+                    "score": score,
+                    "highlights": highlights.to_json(),
+                })
+            })
+            .collect()
+    });
+    json!({
+        "query": query,
+        "field": field,
+        "matches": matches,
+        "scan_ms": scan_ms,
+        "snapshot": index.snapshot().to_json(),
+        "results": results,
+    })
+}
@@ -0,0 +1,94 @@
+//! The matcher in a browser, for the demo page in `www/`.
+//!
+//! [`Search`] holds a corpus uploaded as TSV, prepared the way the
+//! harnesses scan it, and answers each keystroke with the top rows as JSON
+//! for the page to render. Scoring is the same nucleo fuzzy pattern the
+//! benchmarks time, so what the page ranks first is what the quality
+//! harness judges. The page measures latency itself: `std::time::Instant`
+//! is not available on `wasm32-unknown-unknown`.
+
+use harness::corpus::{read_tsv_corpus, Corpus, FieldMap};
+use harness::prepared::PreparedCorpus;
+use harness::topk::TopK;
+use nucleo_matcher::pattern::{AtomKind, CaseMatching, Normalization, Pattern};
+use nucleo_matcher::{Config, Matcher, Utf32Str};
+use serde_json::{json, Value};
+use wasm_bindgen::prelude::*;
+
+/// A loaded corpus and the matcher state reused across searches.
+#[wasm_bindgen]
+pub struct Search {
+    corpus: Corpus,
+    prepared: PreparedCorpus,
+    matcher: Matcher,
+    buf: Vec<char>,
+}
+
+#[wasm_bindgen]
+impl Search {
+    /// Loads a TSV corpus with `symbol`, `name` and `isin` columns.
+    #[wasm_bindgen(constructor)]
+    pub fn new(tsv: &str) -> Result<Search, JsError> {
+        let corpus = read_tsv_corpus(&mut tsv.as_bytes(), &FieldMap::default())?;
+        let prepared = PreparedCorpus::new(&corpus, []);
+        Ok(Search {
+            corpus,
+            prepared,
+            matcher: Matcher::new(Config::DEFAULT),
+            buf: Vec::new(),
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.corpus.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.corpus.is_empty()
+    }
+
+    /// The `limit` best rows for `query` in `field` (`symbol`, `name` or
+    /// `isin`), as a JSON object with the match count and, per row, its
+    /// fields, score and the highlighted character positions of the
+    /// searched field.
+    pub fn search(&mut self, query: &str, field: &str, limit: usize) -> String {
+        let pattern = Pattern::new(
+            query,
+            CaseMatching::Ignore,
+            Normalization::Smart,
+            AtomKind::Fuzzy,
+        );
+        let column = self.prepared.column(field);
+        let mut top = TopK::new(limit);
+        let mut matches = 0;
+        for (idx, candidate) in column.iter().enumerate() {
+            let haystack = Utf32Str::new(candidate, &mut self.buf);
+            if let Some(score) = pattern.score(haystack, &mut self.matcher) {
+                matches += 1;
+                top.push(score, idx);
+            }
+        }
+
+        let mut indices = Vec::new();
+        let rows: Vec<Value> = top
+            .into_sorted()
+            .into_iter()
+            .map(|(score, idx)| {
+                indices.clear();
+                let haystack = Utf32Str::new(column.get(idx), &mut self.buf);
+                pattern.indices(haystack, &mut self.matcher, &mut indices);
+                indices.sort_unstable();
+                indices.dedup();
+                let row = self.corpus.get(idx);
+                json!({
+                    "symbol": row.symbol,
+                    "name": row.name,
+                    "isin": row.isin,
+                    "score": score,
+                    "highlight": indices,
+                })
+            })
+            .collect();
+        json!({ "matches": matches, "rows": rows }).to_string()
+    }
+}
@@ -16,6 +16,21 @@ let package = Package(
             targets: ["FuzzyMatch"]
         )
     ],
+    traits: [
+        .trait(
+            name: "FullUnicode",
+            description: "Case folding for ASCII, Latin-1 Supplement, Greek and basic Cyrillic"
+        ),
+        .trait(
+            name: "LatinExtended",
+            description: "Case folding and diacritic removal for ASCII and Latin-1 Supplement only"
+        ),
+        .trait(
+            name: "ASCIIOnly",
+            description: "Case folding for ASCII only; other bytes are matched as they are"
+        ),
+        .default(enabledTraits: ["FullUnicode"])
+    ],
     targets: [
         .target(
             name: "FuzzyMatch",
@@ -0,0 +1,63 @@
+// ===----------------------------------------------------------------------===//
+//
+// This source file is part of the FuzzyMatch open source project
+//
+// Copyright (c) 2026 Ordo One, AB. and the FuzzyMatch project authors
+// Licensed under Apache License v2.0
+//
+// See LICENSE for license information
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// ===----------------------------------------------------------------------===//
+
+import Synchronization
+
+/// A signal that abandons a scan already in progress.
+///
+/// Search-as-you-type scans the candidates again on every keystroke, and on a
+/// large list the user has often typed the next character before the previous
+/// scan finishes. Pass a token to
+/// ``FuzzyMatcher/topMatches(_:against:limit:cancellation:)`` or
+/// ``FuzzyMatcher/matches(_:against:cancellation:)`` and call ``cancel()``
+/// from any thread to stop it.
+///
+/// The scan reads the token once per chunk of ``checkInterval`` candidates
+/// rather than once per candidate, so the check costs nothing measurable and a
+/// cancelled scan returns after at most one more chunk of scoring.
+///
+/// ## Example
+///
+/// ```swift
+/// let token = CancellationToken()
+/// let scan = Task.detached {
+///     matcher.topMatches(names, against: query, limit: 50, cancellation: token)
+/// }
+///
+/// // The user typed another character:
+/// token.cancel()
+/// if let results = await scan.value {
+///     show(results)
+/// }
+/// ```
+///
+/// A token cannot be reset; create a new one for each scan.
+public final class CancellationToken: Sendable {
+    /// Candidates scored between two reads of the token.
+    public static let checkInterval = 256
+
+    private let cancelled = Atomic<Bool>(false)
+
+    /// Creates a token that has not been cancelled.
+    public init() {}
+
+    /// Asks every scan holding this token to stop.
+    public func cancel() {
+        cancelled.store(true, ordering: .relaxed)
+    }
+
+    /// Whether ``cancel()`` has been called.
+    public var isCancelled: Bool {
+        cancelled.load(ordering: .relaxed)
+    }
+}
@@ -0,0 +1,109 @@
+// ===----------------------------------------------------------------------===//
+//
+// This source file is part of the FuzzyMatch open source project
+//
+// Copyright (c) 2026 Ordo One, AB. and the FuzzyMatch project authors
+// Licensed under Apache License v2.0
+//
+// See LICENSE for license information
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// ===----------------------------------------------------------------------===//
+
+/// Digit run matching for ``DigitRunMatching/contiguous(wholeNumberBonus:)``.
+///
+/// A digit run is a maximal sequence of ASCII digits. Under contiguous
+/// matching, every digit run in the query must appear at the start of a digit
+/// run in the candidate, so the `7` of "db x-trackers 7" can match `7` or `75`
+/// but not the `7` in the middle of `2017` or one scattered across an ISIN.
+///
+/// ## Example
+///
+/// ```
+/// query "msci world 1c"   runs: ["1"]
+/// "MSCI World UCITS ETF 1C"  → "1" starts the run "1"   ✓ (whole number)
+/// "MSCI World 10C"           → "1" starts the run "10"  ✓
+/// "MSCI World 2021"          → no run starts with "1"   ✗
+/// ```
+///
+/// UTF-8 continuation and lead bytes are all `>= 0x80`, so runs can be found
+/// on the original candidate bytes without lowercasing or decoding them.
+
+/// Whether a byte is an ASCII digit.
+@inlinable
+internal func isASCIIDigit(_ byte: UInt8) -> Bool {
+    byte >= 0x30 && byte <= 0x39
+}
+
+/// Finds the maximal runs of ASCII digits in a byte sequence.
+///
+/// - Parameter bytes: The bytes to scan (typically the lowercased query).
+/// - Returns: The start and length of each run, in order.
+@inlinable
+internal func computeDigitRuns(_ bytes: [UInt8]) -> [(start: Int, length: Int)] {
+    var runs: [(start: Int, length: Int)] = []
+    var i = 0
+    while i < bytes.count {
+        if isASCIIDigit(bytes[i]) {
+            let start = i
+            while i < bytes.count && isASCIIDigit(bytes[i]) {
+                i += 1
+            }
+            runs.append((start: start, length: i - start))
+        } else {
+            i += 1
+        }
+    }
+    return runs
+}
+
+/// Checks every query digit run against the candidate's digit runs.
+///
+/// - Parameters:
+///   - candidate: The candidate's bytes.
+///   - query: The lowercased query bytes.
+///   - runs: The query's digit runs, from ``computeDigitRuns(_:)``.
+/// - Returns: `nil` if some query run does not start a digit run of the
+///   candidate; otherwise how many query runs equal a whole candidate run.
+@inlinable
+internal func matchDigitRuns(
+    candidate: Span<UInt8>,
+    query: [UInt8],
+    runs: [(start: Int, length: Int)]
+) -> Int? {
+    let count = candidate.count
+    var wholeNumbers = 0
+    for run in runs {
+        var found = false
+        var whole = false
+        var i = 0
+        while i < count && !whole {
+            guard isASCIIDigit(candidate[i]) else {
+                i += 1
+                continue
+            }
+            // i starts a candidate run; compare the query run against its prefix
+            let start = i
+            while i < count && isASCIIDigit(candidate[i]) {
+                i += 1
+            }
+            let candidateRunLength = i - start
+            guard candidateRunLength >= run.length else { continue }
+            var equal = true
+            for k in 0..<run.length where candidate[start + k] != query[run.start + k] {
+                equal = false
+                break
+            }
+            if equal {
+                found = true
+                whole = candidateRunLength == run.length
+            }
+        }
+        guard found else { return nil }
+        if whole {
+            wholeNumbers += 1
+        }
+    }
+    return wholeNumbers
+}
@@ -64,12 +64,56 @@ extension FuzzyMatcher {
         _ candidates: some Sequence<String>,
         against query: FuzzyQuery,
         limit: Int = 10
+    ) -> [MatchResult] {
+        scanTopMatches(candidates, against: query, limit: limit, cancellation: nil)
+    }
+
+    /// Returns the top matches from a sequence of candidates, or `nil` if the
+    /// scan was cancelled.
+    ///
+    /// The scan checks `cancellation` every ``CancellationToken/checkInterval``
+    /// candidates and stops at the first check after ``CancellationToken/cancel()``.
+    /// A token cancelled at any point before this method returns yields `nil`,
+    /// even if the last candidates had already been scored.
+    ///
+    /// - Parameters:
+    ///   - candidates: The candidates to search.
+    ///   - query: A prepared query from ``prepare(_:)``.
+    ///   - limit: Maximum number of results to return. Default is `10`.
+    ///   - cancellation: A token another thread can cancel to abandon the scan.
+    /// - Returns: An array of ``MatchResult`` sorted by score descending,
+    ///   containing at most `limit` elements, or `nil` if the scan was cancelled.
+    public func topMatches(
+        _ candidates: some Sequence<String>,
+        against query: FuzzyQuery,
+        limit: Int = 10,
+        cancellation: CancellationToken
+    ) -> [MatchResult]? {
+        let results = scanTopMatches(candidates, against: query, limit: limit, cancellation: cancellation)
+        return cancellation.isCancelled ? nil : results
+    }
+
+    private func scanTopMatches(
+        _ candidates: some Sequence<String>,
+        against query: FuzzyQuery,
+        limit: Int,
+        cancellation: CancellationToken?
     ) -> [MatchResult] {
         var buffer = makeBuffer()
         var results: [MatchResult] = []
         results.reserveCapacity(limit)
+        var untilCheck = 0
 
         for candidate in candidates {
+            if let cancellation {
+                if untilCheck == 0 {
+                    if cancellation.isCancelled {
+                        return []
+                    }
+                    untilCheck = CancellationToken.checkInterval
+                }
+                untilCheck -= 1
+            }
             guard let match = score(candidate, against: query, buffer: &buffer) else {
                 continue
             }
@@ -113,11 +157,49 @@ extension FuzzyMatcher {
     public func matches(
         _ candidates: some Sequence<String>,
         against query: FuzzyQuery
+    ) -> [MatchResult] {
+        scanMatches(candidates, against: query, cancellation: nil)
+    }
+
+    /// Returns all matching candidates sorted by score descending, or `nil` if
+    /// the scan was cancelled.
+    ///
+    /// Cancellation works as in ``topMatches(_:against:limit:cancellation:)``.
+    ///
+    /// - Parameters:
+    ///   - candidates: The candidates to search.
+    ///   - query: A prepared query from ``prepare(_:)``.
+    ///   - cancellation: A token another thread can cancel to abandon the scan.
+    /// - Returns: An array of ``MatchResult`` sorted by score descending, or
+    ///   `nil` if the scan was cancelled.
+    public func matches(
+        _ candidates: some Sequence<String>,
+        against query: FuzzyQuery,
+        cancellation: CancellationToken
+    ) -> [MatchResult]? {
+        let results = scanMatches(candidates, against: query, cancellation: cancellation)
+        return cancellation.isCancelled ? nil : results
+    }
+
+    private func scanMatches(
+        _ candidates: some Sequence<String>,
+        against query: FuzzyQuery,
+        cancellation: CancellationToken?
     ) -> [MatchResult] {
         var buffer = makeBuffer()
         var results: [MatchResult] = []
+        var untilCheck = 0
 
         for candidate in candidates {
+            if let cancellation {
+                if untilCheck == 0 {
+                    if cancellation.isCancelled {
+                        return []
+                    }
+                    untilCheck = CancellationToken.checkInterval
+                }
+                untilCheck -= 1
+            }
             if let match = score(candidate, against: query, buffer: &buffer) {
                 results.append(MatchResult(candidate: candidate, match: match))
             }
@@ -131,6 +131,200 @@ extension FuzzyMatcher {
         return results
     }
 
+    /// Returns the top matches from a sequence of items after applying domain
+    /// boosts, sorted by boosted score descending.
+    ///
+    /// Each matching item is scored on its `keyPath` property, then every boost
+    /// in `boosts` adds its amount to the score (see ``ScoreBoost``). The top
+    /// `limit` are chosen by the boosted ``ItemMatchResult/score``, and each
+    /// result lists the contributions in ``ItemMatchResult/boosts``.
+    ///
+    /// - Parameters:
+    ///   - candidates: The items to search.
+    ///   - keyPath: A key path to the string property to match against.
+    ///   - query: A prepared query from ``prepare(_:)``.
+    ///   - limit: Maximum number of results to return. Default is `10`.
+    ///   - boosts: The boosts to apply to each match, in order.
+    /// - Returns: An array of ``ItemMatchResult`` sorted by boosted score
+    ///   descending, containing at most `limit` elements.
+    public func topMatches<Item>(
+        _ candidates: some Sequence<Item>,
+        by keyPath: KeyPath<Item, String>,
+        against query: FuzzyQuery,
+        limit: Int = 10,
+        boosts: [ScoreBoost<Item>]
+    ) -> [ItemMatchResult<Item>] {
+        var buffer = makeBuffer()
+        var results: [ItemMatchResult<Item>] = []
+        results.reserveCapacity(limit)
+
+        for candidate in candidates {
+            guard let result = boostedMatch(candidate, by: keyPath, against: query, boosts: boosts, buffer: &buffer) else {
+                continue
+            }
+            if results.count < limit {
+                results.append(result)
+                if results.count == limit {
+                    results.sort { $0.score > $1.score }
+                }
+            } else if result.score > results[results.count - 1].score {
+                results[results.count - 1] = result
+                results.sort { $0.score > $1.score }
+            }
+        }
+
+        if results.count < limit {
+            results.sort { $0.score > $1.score }
+        }
+
+        return results
+    }
+
+    /// Returns all matching items after applying domain boosts, sorted by
+    /// boosted score descending.
+    ///
+    /// Boosts apply as in ``topMatches(_:by:against:limit:boosts:)``.
+    ///
+    /// - Parameters:
+    ///   - candidates: The items to search.
+    ///   - keyPath: A key path to the string property to match against.
+    ///   - query: A prepared query from ``prepare(_:)``.
+    ///   - boosts: The boosts to apply to each match, in order.
+    /// - Returns: An array of ``ItemMatchResult`` sorted by boosted score descending.
+    public func matches<Item>(
+        _ candidates: some Sequence<Item>,
+        by keyPath: KeyPath<Item, String>,
+        against query: FuzzyQuery,
+        boosts: [ScoreBoost<Item>]
+    ) -> [ItemMatchResult<Item>] {
+        var buffer = makeBuffer()
+        var results: [ItemMatchResult<Item>] = []
+
+        for candidate in candidates {
+            if let result = boostedMatch(candidate, by: keyPath, against: query, boosts: boosts, buffer: &buffer) {
+                results.append(result)
+            }
+        }
+
+        results.sort { $0.score > $1.score }
+        return results
+    }
+
+    /// Returns the best match per group of items sharing a key, sorted by score
+    /// descending.
+    ///
+    /// Every item is scored on its `keyPath` property, with `boosts` applied as
+    /// in ``topMatches(_:by:against:limit:boosts:)``. Matching items are then
+    /// grouped by their `groupKey` property, and each ``MatchGroup`` keeps its
+    /// highest-scoring item and the number of items that matched. Groups are
+    /// ranked by their best score, and equal scores keep the order in which the
+    /// groups first appeared in `candidates`, so the result is deterministic.
+    ///
+    /// - Parameters:
+    ///   - candidates: The items to search.
+    ///   - keyPath: A key path to the string property to match against.
+    ///   - groupKey: A key path to the property items are grouped by, such as
+    ///     an ISIN or issuer.
+    ///   - query: A prepared query from ``prepare(_:)``.
+    ///   - limit: Maximum number of groups to return. Default is `10`.
+    ///   - boosts: The boosts to apply to each match, in order. Default is none.
+    /// - Returns: An array of ``MatchGroup`` sorted by best score descending,
+    ///   containing at most `limit` elements.
+    ///
+    /// ## Example
+    ///
+    /// ```swift
+    /// struct Listing {
+    ///     let ticker: String
+    ///     let name: String
+    ///     let isin: String
+    /// }
+    ///
+    /// let query = matcher.prepare("deutsche bank")
+    /// for group in matcher.groupedMatches(listings, by: \.name, groupedBy: \.isin, against: query) {
+    ///     print("\(group.best.item.name) (\(group.count) listings)")
+    /// }
+    /// ```
+    public func groupedMatches<Item, Key: Hashable>(
+        _ candidates: some Sequence<Item>,
+        by keyPath: KeyPath<Item, String>,
+        groupedBy groupKey: KeyPath<Item, Key>,
+        against query: FuzzyQuery,
+        limit: Int = 10,
+        boosts: [ScoreBoost<Item>] = []
+    ) -> [MatchGroup<Key, Item>] {
+        var buffer = makeBuffer()
+        var groups: [MatchGroup<Key, Item>] = []
+        var indices: [Key: Int] = [:]
+
+        for candidate in candidates {
+            guard let result = boostedMatch(candidate, by: keyPath, against: query, boosts: boosts, buffer: &buffer) else {
+                continue
+            }
+            let key = candidate[keyPath: groupKey]
+            if let index = indices[key] {
+                let group = groups[index]
+                let best = result.score > group.best.score ? result : group.best
+                groups[index] = MatchGroup(key: key, best: best, count: group.count + 1)
+            } else {
+                indices[key] = groups.count
+                groups.append(MatchGroup(key: key, best: result, count: 1))
+            }
+        }
+
+        // Sort positions rather than groups so ties fall back to first appearance.
+        let order = groups.indices.sorted { lhs, rhs in
+            let lhsScore = groups[lhs].best.score
+            let rhsScore = groups[rhs].best.score
+            return lhsScore != rhsScore ? lhsScore > rhsScore : lhs < rhs
+        }
+        return order.prefix(max(limit, 0)).map { groups[$0] }
+    }
+
+    /// Returns the best match per group of items sharing a key, sorted by score
+    /// descending.
+    ///
+    /// This is a convenience method that handles query preparation internally.
+    /// For scoring many queries against the same candidates, prefer the
+    /// ``prepare(_:)`` + `groupedMatches(_:by:groupedBy:against:)` with the
+    /// prepared ``FuzzyQuery`` instead.
+    ///
+    /// - Parameters:
+    ///   - candidates: The items to search.
+    ///   - keyPath: A key path to the string property to match against.
+    ///   - groupKey: A key path to the property items are grouped by.
+    ///   - query: The query string to match against.
+    ///   - limit: Maximum number of groups to return. Default is `10`.
+    ///   - boosts: The boosts to apply to each match, in order. Default is none.
+    /// - Returns: An array of ``MatchGroup`` sorted by best score descending,
+    ///   containing at most `limit` elements.
+    public func groupedMatches<Item, Key: Hashable>(
+        _ candidates: some Sequence<Item>,
+        by keyPath: KeyPath<Item, String>,
+        groupedBy groupKey: KeyPath<Item, Key>,
+        against query: String,
+        limit: Int = 10,
+        boosts: [ScoreBoost<Item>] = []
+    ) -> [MatchGroup<Key, Item>] {
+        groupedMatches(candidates, by: keyPath, groupedBy: groupKey, against: prepare(query), limit: limit, boosts: boosts)
+    }
+
+    /// Scores one item and applies `boosts` to its match, or returns `nil` if
+    /// the item does not match.
+    func boostedMatch<Item>(
+        _ candidate: Item,
+        by keyPath: KeyPath<Item, String>,
+        against query: FuzzyQuery,
+        boosts: [ScoreBoost<Item>],
+        buffer: inout ScoringBuffer
+    ) -> ItemMatchResult<Item>? {
+        guard let match = score(candidate[keyPath: keyPath], against: query, buffer: &buffer) else {
+            return nil
+        }
+        let contributions = boosts.compactMap { $0.contribution(for: candidate, match: match) }
+        return ItemMatchResult(item: candidate, match: match, boosts: contributions)
+    }
+
     /// Returns the top matches from a sequence of items, matching against a string
     /// property extracted via key path, sorted by score descending.
     ///
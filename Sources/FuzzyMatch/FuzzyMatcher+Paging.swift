@@ -0,0 +1,100 @@
+// ===----------------------------------------------------------------------===//
+//
+// This source file is part of the FuzzyMatch open source project
+//
+// Copyright (c) 2026 Ordo One, AB. and the FuzzyMatch project authors
+// Licensed under Apache License v2.0
+//
+// See LICENSE for license information
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// ===----------------------------------------------------------------------===//
+
+extension FuzzyMatcher {
+    /// Returns one page of matching candidates, sorted by score descending.
+    ///
+    /// Results are ordered by score descending, and candidates with equal
+    /// scores by their position in `candidates`. That order is total, so
+    /// for the same query and the same candidates in the same order,
+    /// consecutive pages neither repeat nor skip a result. This is what a
+    /// "show more results" control needs; ``topMatches(_:against:limit:)-7q3wo``
+    /// makes no promise about how equal scores are ordered.
+    ///
+    /// Each page scans every candidate and keeps the best `offset + limit`,
+    /// so later pages cost more memory but no more scoring.
+    ///
+    /// - Parameters:
+    ///   - candidates: The candidates to search.
+    ///   - query: A prepared query from ``prepare(_:)``.
+    ///   - offset: The number of results to skip.
+    ///   - limit: Maximum number of results to return.
+    /// - Returns: An array of ``MatchResult`` holding at most `limit` results,
+    ///   starting at position `offset` in the full ordering. Empty once
+    ///   `offset` passes the last match.
+    ///
+    /// ## Example
+    ///
+    /// ```swift
+    /// let matcher = FuzzyMatcher()
+    /// let query = matcher.prepare("bank")
+    /// let first = matcher.matches(names, against: query, offset: 0, limit: 20)
+    /// // "Show more"
+    /// let second = matcher.matches(names, against: query, offset: 20, limit: 20)
+    /// ```
+    public func matches(
+        _ candidates: some Sequence<String>,
+        against query: FuzzyQuery,
+        offset: Int,
+        limit: Int
+    ) -> [MatchResult] {
+        scanPage(candidates, text: { $0 }, against: query, offset: offset, limit: limit) {
+            MatchResult(candidate: $0, match: $1)
+        }
+    }
+
+    /// Returns one page of matching items, matching against a string property
+    /// extracted via key path, sorted by score descending.
+    ///
+    /// This is the generic counterpart of ``matches(_:against:offset:limit:)``,
+    /// with the same ordering: score descending, then position in `candidates`.
+    ///
+    /// - Parameters:
+    ///   - candidates: The items to search.
+    ///   - keyPath: A key path to the string property to match against.
+    ///   - query: A prepared query from ``prepare(_:)``.
+    ///   - offset: The number of results to skip.
+    ///   - limit: Maximum number of results to return.
+    /// - Returns: An array of ``ItemMatchResult`` holding at most `limit`
+    ///   results, starting at position `offset` in the full ordering.
+    public func matches<Item>(
+        _ candidates: some Sequence<Item>,
+        by keyPath: KeyPath<Item, String>,
+        against query: FuzzyQuery,
+        offset: Int,
+        limit: Int
+    ) -> [ItemMatchResult<Item>] {
+        scanPage(candidates, text: { $0[keyPath: keyPath] }, against: query, offset: offset, limit: limit) {
+            ItemMatchResult(item: $0, match: $1)
+        }
+    }
+
+    /// Keeps the best `offset + limit` matches with `scanLimited` and
+    /// returns those after the first `offset`.
+    private func scanPage<Item, Result>(
+        _ candidates: some Sequence<Item>,
+        text: (Item) -> String,
+        against query: FuzzyQuery,
+        offset: Int,
+        limit: Int,
+        result: (Item, ScoredMatch) -> Result
+    ) -> [Result] {
+        precondition(offset >= 0, "offset must not be negative")
+        guard limit > 0 else {
+            return []
+        }
+        let (keep, overflow) = offset.addingReportingOverflow(limit)
+        let kept = scanLimited(candidates, text: text, against: query, maxResults: overflow ? Int.max : keep, result: result)
+        return Array(kept.results.dropFirst(offset))
+    }
+}
@@ -165,15 +165,22 @@ public struct FuzzyMatcher: Sendable {
             lowercased.removeSubrange(lowercasedLength..<lowercased.count)
         }
 
-        // Compute character bitmask
-        let charBitmask = computeCharBitmask(lowercased)
+        // Split off noise words; only the remaining words are matched
+        var noiseWords: [[UInt8]] = []
+        if let tokens = config.noiseTokens?.tokens, let split = splitNoiseWords(lowercased, tokens: tokens) {
+            lowercased = split.core
+            noiseWords = split.noise
+        }
+
+        // Compute character bitmask (an empty mask lets every candidate through)
+        let charBitmask = config.prefilters.contains(.charBitmask) ? computeCharBitmask(lowercased) : 0
 
         // Check if query contains spaces (multi-word)
         let containsSpaces = lowercased.contains(0x20)
 
-        // Compute trigrams (only if query is long enough)
+        // Compute trigrams (only if query is long enough and the trigram prefilter is on)
         let trigrams: Set<UInt32>
-        if lowercased.count >= 3 {
+        if lowercased.count >= 3 && config.prefilters.contains(.trigrams) {
             trigrams = computeTrigrams(lowercased)
         } else {
             trigrams = []
@@ -185,6 +192,7 @@ public struct FuzzyMatcher: Sendable {
             charBitmask: charBitmask,
             trigrams: trigrams,
             containsSpaces: containsSpaces,
+            noiseWords: noiseWords,
             config: config
         )
     }
@@ -281,9 +289,10 @@ public struct FuzzyMatcher: Sendable {
             candidateLength: candidate.utf8.count
         )
         // Dispatch based on matching algorithm
+        let match: ScoredMatch?
         switch query.config.algorithm {
         case .smithWaterman(let swConfig):
-            return scoreSmithWatermanImpl(
+            match = scoreSmithWatermanImpl(
                 candidate.utf8.span,
                 against: query,
                 swConfig: swConfig,
@@ -294,30 +303,41 @@ public struct FuzzyMatcher: Sendable {
 
         case .editDistance(let edConfig):
             // Fast path for 1-character queries: single scan, no buffer needed.
-            // Skip for multi-byte queries (e.g. Latin Extended "à" = 2 UTF-8 bytes).
+            // Skip for multi-byte queries (e.g. Latin Extended "à" = 2 UTF-8 bytes),
+            // and for a digit the full pipeline must match as a number.
             let queryLength = query.lowercased.count
-            if queryLength == 1 {
-                return scoreTinyQuery1(
+            if queryLength == 1 && query.digitRuns.isEmpty {
+                match = scoreTinyQuery1(
                     candidate.utf8.span,
                     candidateLength: candidate.utf8.count,
                     q0: query.lowercased[0],
                     edConfig: edConfig,
                     minScore: query.config.minScore
                 )
+            } else {
+                // Pass components separately to avoid exclusivity conflicts with Span borrowing
+                match = scoreImpl(
+                    candidate.utf8.span,
+                    against: query,
+                    edConfig: edConfig,
+                    candidateStorage: &buffer.candidateStorage,
+                    editDistanceState: &buffer.editDistanceState,
+                    matchPositions: &buffer.matchPositions,
+                    alignmentState: &buffer.alignmentState,
+                    wordInitials: &buffer.wordInitials
+                )
             }
+        }
 
-            // Pass components separately to avoid exclusivity conflicts with Span borrowing
-            return scoreImpl(
-                candidate.utf8.span,
-                against: query,
-                edConfig: edConfig,
-                candidateStorage: &buffer.candidateStorage,
-                editDistanceState: &buffer.editDistanceState,
-                matchPositions: &buffer.matchPositions,
-                alignmentState: &buffer.alignmentState,
-                wordInitials: &buffer.wordInitials
-            )
+        // Reward candidates containing the noise words removed from the query
+        guard let match, !query.noiseWords.isEmpty,
+            let weight = query.config.noiseTokens?.weight, weight > 0 else {
+            return match
         }
+        let found = countWholeWords(in: candidate.utf8.span, words: query.noiseWords)
+        guard found > 0 else { return match }
+        let reward = weight * Double(found) / Double(query.noiseWords.count)
+        return ScoredMatch(score: min(match.score + reward, 1.0), kind: match.kind)
     }
 
     // MARK: - Scoring State
@@ -382,6 +402,19 @@ public struct FuzzyMatcher: Sendable {
             return nil
         }
 
+        // Digit runs: each number in the query must start a number in the candidate
+        var wholeNumbers = 0
+        if !query.digitRuns.isEmpty {
+            guard let matched = matchDigitRuns(
+                candidate: candidateUTF8,
+                query: query.lowercased,
+                runs: query.digitRuns
+            ) else {
+                return nil
+            }
+            wholeNumbers = matched
+        }
+
         let effectiveMaxEditDistance = query.effectiveMaxEditDistance
 
         // Ensure buffer capacity and lowercase the candidate
@@ -491,6 +524,10 @@ public struct FuzzyMatcher: Sendable {
         )
 
         if state.bestScore >= query.config.minScore {
+            // Whole-number bonus only reorders matches; it never lifts a non-match
+            if wholeNumbers > 0, case .contiguous(let bonus) = edConfig.digitRuns {
+                state.bestScore = min(state.bestScore + Double(wholeNumbers) * bonus, 1.0)
+            }
             return ScoredMatch(score: state.bestScore, kind: state.bestKind)
         }
 
@@ -54,6 +54,7 @@ public struct FuzzyQuery: Sendable, Equatable {
             && lhs.charBitmask == rhs.charBitmask
             && lhs.trigrams == rhs.trigrams
             && lhs.containsSpaces == rhs.containsSpaces
+            && lhs.noiseWords == rhs.noiseWords
             && lhs.config == rhs.config
             && lhs.effectiveMaxEditDistance == rhs.effectiveMaxEditDistance
             && lhs.bitmaskTolerance == rhs.bitmaskTolerance
@@ -112,6 +113,13 @@ public struct FuzzyQuery: Sendable, Equatable {
     /// Byte ranges of individual query words for multi-word Smith-Waterman matching.
     @usableFromInline let atoms: [(start: Int, length: Int)]
 
+    /// Noise words removed from ``lowercased`` under ``MatchConfig/noiseTokens``.
+    @usableFromInline let noiseWords: [[UInt8]]
+
+    /// Byte ranges of the query's digit runs, when the edit distance config
+    /// matches them contiguously; empty otherwise.
+    @usableFromInline let digitRuns: [(start: Int, length: Int)]
+
     /// Creates a new fuzzy query with precomputed matching data.
     @usableFromInline
     init(
@@ -120,6 +128,7 @@ public struct FuzzyQuery: Sendable, Equatable {
         charBitmask: UInt64,
         trigrams: Set<UInt32>,
         containsSpaces: Bool = false,
+        noiseWords: [[UInt8]] = [],
         config: MatchConfig
     ) {
         self.original = original
@@ -127,6 +136,7 @@ public struct FuzzyQuery: Sendable, Equatable {
         self.charBitmask = charBitmask
         self.trigrams = trigrams
         self.containsSpaces = containsSpaces
+        self.noiseWords = noiseWords
         self.config = config
 
         let queryLength = lowercased.count
@@ -139,12 +149,18 @@ public struct FuzzyQuery: Sendable, Equatable {
             let emed = min(maxED, max(1, (queryLength - 1) / 2))
             self.effectiveMaxEditDistance = emed
             self.bitmaskTolerance = queryLength <= 3 ? 0 : emed
-            self.minCandidateLength = queryLength - emed
+            self.minCandidateLength = config.prefilters.contains(.lengthBounds) ? queryLength - emed : 0
+            if case .contiguous = edConfig.digitRuns {
+                self.digitRuns = computeDigitRuns(lowercased)
+            } else {
+                self.digitRuns = []
+            }
 
         case .smithWaterman:
             self.effectiveMaxEditDistance = 0
             self.bitmaskTolerance = 0
             self.minCandidateLength = 0
+            self.digitRuns = []
         }
 
         // Split multi-word Smith-Waterman queries into atoms
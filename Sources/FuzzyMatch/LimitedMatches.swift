@@ -0,0 +1,182 @@
+// ===----------------------------------------------------------------------===//
+//
+// This source file is part of the FuzzyMatch open source project
+//
+// Copyright (c) 2026 Ordo One, AB. and the FuzzyMatch project authors
+// Licensed under Apache License v2.0
+//
+// See LICENSE for license information
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// ===----------------------------------------------------------------------===//
+
+/// The best matches up to a limit, and how many there were in all.
+///
+/// Returned by ``FuzzyMatcher/matches(_:against:maxResults:)`` and its key
+/// path counterpart. When ``overflowed`` is `true`, more candidates matched
+/// than ``results`` holds, and a list can say so: "showing the first 500 of
+/// 12,418".
+///
+/// ## Example
+///
+/// ```swift
+/// let limited = matcher.matches(names, against: query, maxResults: 500)
+/// if limited.overflowed {
+///     print("Showing the first \(limited.results.count) of \(limited.matchCount)")
+/// }
+/// ```
+public struct LimitedMatches<Element> {
+    /// The best matches, by score descending, then position among the
+    /// candidates. At most ``maxResults`` of them.
+    public let results: [Element]
+
+    /// The number of candidates that matched, including those left out of
+    /// ``results``.
+    public let matchCount: Int
+
+    /// The limit the matches were collected with.
+    public let maxResults: Int
+
+    /// Creates a limited result set.
+    ///
+    /// - Parameters:
+    ///   - results: The matches kept, best first.
+    ///   - matchCount: The number of candidates that matched in all.
+    ///   - maxResults: The limit the matches were collected with.
+    public init(results: [Element], matchCount: Int, maxResults: Int) {
+        self.results = results
+        self.matchCount = matchCount
+        self.maxResults = maxResults
+    }
+
+    /// Whether matches were left out because the limit was reached.
+    public var overflowed: Bool {
+        matchCount > results.count
+    }
+}
+
+extension LimitedMatches: Sendable where Element: Sendable {}
+extension LimitedMatches: Equatable where Element: Equatable {}
+
+extension FuzzyMatcher {
+    /// Returns the best matching candidates up to a limit, with the number
+    /// of matches left out.
+    ///
+    /// Collection stops growing once `maxResults` matches are held: from
+    /// then on a match is kept only when it beats the weakest one held, which
+    /// it replaces, so memory and sorting are bounded by the limit however
+    /// broad the query. Every candidate is still scored, so ``LimitedMatches/results``
+    /// are the true best `maxResults` and ``LimitedMatches/matchCount`` is
+    /// exact.
+    ///
+    /// Results are ordered by score descending and equal scores by position
+    /// in `candidates`, as in ``matches(_:against:offset:limit:)``, so the
+    /// results are the first page of that ordering.
+    ///
+    /// - Parameters:
+    ///   - candidates: The candidates to search.
+    ///   - query: A prepared query from ``prepare(_:)``.
+    ///   - maxResults: The most results to return.
+    /// - Returns: A ``LimitedMatches`` of ``MatchResult``.
+    ///
+    /// ## Example
+    ///
+    /// ```swift
+    /// let matcher = FuzzyMatcher()
+    /// let query = matcher.prepare("fund")
+    /// let limited = matcher.matches(names, against: query, maxResults: 500)
+    /// // limited.results.count == 500, limited.overflowed == true
+    /// ```
+    public func matches(
+        _ candidates: some Sequence<String>,
+        against query: FuzzyQuery,
+        maxResults: Int
+    ) -> LimitedMatches<MatchResult> {
+        scanLimited(candidates, text: { $0 }, against: query, maxResults: maxResults) {
+            MatchResult(candidate: $0, match: $1)
+        }
+    }
+
+    /// Returns the best matching items up to a limit, with the number of
+    /// matches left out, matching against a string property extracted via
+    /// key path.
+    ///
+    /// This is the generic counterpart of ``matches(_:against:maxResults:)``.
+    ///
+    /// - Parameters:
+    ///   - candidates: The items to search.
+    ///   - keyPath: A key path to the string property to match against.
+    ///   - query: A prepared query from ``prepare(_:)``.
+    ///   - maxResults: The most results to return.
+    /// - Returns: A ``LimitedMatches`` of ``ItemMatchResult``.
+    public func matches<Item>(
+        _ candidates: some Sequence<Item>,
+        by keyPath: KeyPath<Item, String>,
+        against query: FuzzyQuery,
+        maxResults: Int
+    ) -> LimitedMatches<ItemMatchResult<Item>> {
+        scanLimited(candidates, text: { $0[keyPath: keyPath] }, against: query, maxResults: maxResults) {
+            ItemMatchResult(item: $0, match: $1)
+        }
+    }
+
+    /// Keeps the best `maxResults` matches in a min-heap, the weakest at
+    /// the root, counting every match. Sorts once, at the end. Paging uses
+    /// it too, with room for every page up to the one asked for.
+    func scanLimited<Item, Result>(
+        _ candidates: some Sequence<Item>,
+        text: (Item) -> String,
+        against query: FuzzyQuery,
+        maxResults: Int,
+        result: (Item, ScoredMatch) -> Result
+    ) -> LimitedMatches<Result> {
+        precondition(maxResults >= 0, "maxResults must not be negative")
+        typealias Entry = (result: Result, score: Double, position: Int)
+
+        // Positions only grow during the scan, so a later match replaces
+        // the weakest only with a strictly higher score.
+        func weaker(_ lhs: Entry, _ rhs: Entry) -> Bool {
+            lhs.score != rhs.score ? lhs.score < rhs.score : lhs.position > rhs.position
+        }
+
+        var buffer = makeBuffer()
+        var heap: [Entry] = []
+        heap.reserveCapacity(Swift.min(maxResults, 1024))
+        var matchCount = 0
+
+        for (position, candidate) in candidates.enumerated() {
+            guard let match = score(text(candidate), against: query, buffer: &buffer) else {
+                continue
+            }
+            matchCount += 1
+            if heap.count < maxResults {
+                heap.append((result(candidate, match), match.score, position))
+                var child = heap.count - 1
+                while child > 0 {
+                    let parent = (child - 1) / 2
+                    guard weaker(heap[child], heap[parent]) else { break }
+                    heap.swapAt(child, parent)
+                    child = parent
+                }
+            } else if maxResults > 0 && match.score > heap[0].score {
+                heap[0] = (result(candidate, match), match.score, position)
+                var parent = 0
+                while true {
+                    let left = 2 * parent + 1
+                    guard left < heap.count else { break }
+                    var child = left
+                    if left + 1 < heap.count && weaker(heap[left + 1], heap[left]) {
+                        child = left + 1
+                    }
+                    guard weaker(heap[child], heap[parent]) else { break }
+                    heap.swapAt(parent, child)
+                    parent = child
+                }
+            }
+        }
+
+        heap.sort { weaker($1, $0) }
+        return LimitedMatches(results: heap.map { $0.result }, matchCount: matchCount, maxResults: maxResults)
+    }
+}
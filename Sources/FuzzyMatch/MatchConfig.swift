@@ -193,6 +193,73 @@ public enum GapPenalty: Sendable, Equatable, Codable {
     }
 }
 
+/// How numbers in a query are matched against numbers in a candidate.
+///
+/// By default digits are matched like any other character, so the edit distance
+/// pipeline may line up the `7` of "db x-trackers 7" with a `7` anywhere in the
+/// candidate, including inside an unrelated number or an ISIN. For instrument
+/// names, where a number usually picks out one share class or series, that
+/// produces rankings no user would expect.
+///
+/// - ``fuzzy``: (Default) Digits are ordinary characters.
+/// - ``contiguous(wholeNumberBonus:)``: Each run of digits in the query must
+///   start a run of digits in the candidate, so "7" matches `7` and `75` but not
+///   the `7` in `2017`. Candidates without such a number are rejected, and typos
+///   inside a number are not forgiven. Each query number that equals a whole
+///   number of the candidate adds `wholeNumberBonus` to the score.
+///
+/// ## Example
+///
+/// ```swift
+/// let config = EditDistanceConfig(digitRuns: .contiguous(wholeNumberBonus: 0.05))
+/// let matcher = FuzzyMatcher(config: MatchConfig(algorithm: .editDistance(config)))
+/// ```
+public enum DigitRunMatching: Sendable, Equatable, Codable {
+    /// Digits are matched like any other character.
+    case fuzzy
+
+    /// Query numbers must start candidate numbers.
+    ///
+    /// - Parameter wholeNumberBonus: Added to the score for each query number
+    ///   equal to a whole number of the candidate. Typical values: 0.0-0.1.
+    case contiguous(wholeNumberBonus: Double)
+
+    /// The default: digits are matched fuzzily.
+    public static let `default`: DigitRunMatching = .fuzzy
+
+    private enum CodingKeys: String, CodingKey {
+        case type, wholeNumberBonus
+    }
+
+    public init(from decoder: Decoder) throws {
+        let container = try decoder.container(keyedBy: CodingKeys.self)
+        let type = try container.decode(String.self, forKey: .type)
+        switch type {
+        case "fuzzy":
+            self = .fuzzy
+        case "contiguous":
+            let bonus = try container.decode(Double.self, forKey: .wholeNumberBonus)
+            self = .contiguous(wholeNumberBonus: bonus)
+        default:
+            throw DecodingError.dataCorruptedError(
+                forKey: .type, in: container,
+                debugDescription: "Unknown DigitRunMatching type: \(type)"
+            )
+        }
+    }
+
+    public func encode(to encoder: Encoder) throws {
+        var container = encoder.container(keyedBy: CodingKeys.self)
+        switch self {
+        case .fuzzy:
+            try container.encode("fuzzy", forKey: .type)
+        case .contiguous(let bonus):
+            try container.encode("contiguous", forKey: .type)
+            try container.encode(bonus, forKey: .wholeNumberBonus)
+        }
+    }
+}
+
 /// Configuration for the edit distance matching algorithm.
 ///
 /// Controls the Damerau-Levenshtein edit distance pipeline, including how many
@@ -460,6 +527,15 @@ public struct EditDistanceConfig: Sendable, Equatable, Codable {
     /// - `< 1.0`: Reduce acronym match scores
     public var acronymWeight: Double
 
+    /// How runs of digits in the query are matched.
+    ///
+    /// - ``DigitRunMatching/fuzzy``: Default, digits are ordinary characters
+    /// - ``DigitRunMatching/contiguous(wholeNumberBonus:)``: Query numbers must
+    ///   start candidate numbers; whole-number matches earn a bonus
+    ///
+    /// Configurations encoded before this setting existed decode as `.fuzzy`.
+    public var digitRuns: DigitRunMatching
+
     /// The default edit distance configuration.
     public static let `default` = Self()
 
@@ -548,7 +624,180 @@ public struct EditDistanceConfig: Sendable, Equatable, Codable {
         self.firstMatchBonusRange = firstMatchBonusRange
         self.lengthPenalty = lengthPenalty
         self.acronymWeight = acronymWeight
+        self.digitRuns = .fuzzy
+    }
+
+    /// Creates a new edit distance configuration that matches runs of digits
+    /// as chosen.
+    ///
+    /// The other parameters and their defaults are those of
+    /// ``init(maxEditDistance:longQueryMaxEditDistance:longQueryThreshold:prefixWeight:substringWeight:wordBoundaryBonus:consecutiveBonus:gapPenalty:firstMatchBonus:firstMatchBonusRange:lengthPenalty:acronymWeight:)``.
+    ///
+    /// - Parameter digitRuns: How runs of digits in the query are matched.
+    public init(
+        maxEditDistance: Int = 2,
+        longQueryMaxEditDistance: Int = 3,
+        longQueryThreshold: Int = 13,
+        prefixWeight: Double = 1.5,
+        substringWeight: Double = 1.0,
+        wordBoundaryBonus: Double = 0.1,
+        consecutiveBonus: Double = 0.05,
+        gapPenalty: GapPenalty = .default,
+        firstMatchBonus: Double = 0.15,
+        firstMatchBonusRange: Int = 10,
+        lengthPenalty: Double = 0.003,
+        acronymWeight: Double = 1.0,
+        digitRuns: DigitRunMatching
+    ) {
+        self.init(
+            maxEditDistance: maxEditDistance,
+            longQueryMaxEditDistance: longQueryMaxEditDistance,
+            longQueryThreshold: longQueryThreshold,
+            prefixWeight: prefixWeight,
+            substringWeight: substringWeight,
+            wordBoundaryBonus: wordBoundaryBonus,
+            consecutiveBonus: consecutiveBonus,
+            gapPenalty: gapPenalty,
+            firstMatchBonus: firstMatchBonus,
+            firstMatchBonusRange: firstMatchBonusRange,
+            lengthPenalty: lengthPenalty,
+            acronymWeight: acronymWeight
+        )
+        self.digitRuns = digitRuns
+    }
+
+    private enum CodingKeys: String, CodingKey {
+        case maxEditDistance, longQueryMaxEditDistance, longQueryThreshold
+        case prefixWeight, substringWeight, wordBoundaryBonus, consecutiveBonus
+        case gapPenalty, firstMatchBonus, firstMatchBonusRange, lengthPenalty
+        case acronymWeight, digitRuns
+    }
+
+    public init(from decoder: Decoder) throws {
+        let container = try decoder.container(keyedBy: CodingKeys.self)
+        self.maxEditDistance = try container.decode(Int.self, forKey: .maxEditDistance)
+        self.longQueryMaxEditDistance = try container.decode(Int.self, forKey: .longQueryMaxEditDistance)
+        self.longQueryThreshold = try container.decode(Int.self, forKey: .longQueryThreshold)
+        self.prefixWeight = try container.decode(Double.self, forKey: .prefixWeight)
+        self.substringWeight = try container.decode(Double.self, forKey: .substringWeight)
+        self.wordBoundaryBonus = try container.decode(Double.self, forKey: .wordBoundaryBonus)
+        self.consecutiveBonus = try container.decode(Double.self, forKey: .consecutiveBonus)
+        self.gapPenalty = try container.decode(GapPenalty.self, forKey: .gapPenalty)
+        self.firstMatchBonus = try container.decode(Double.self, forKey: .firstMatchBonus)
+        self.firstMatchBonusRange = try container.decode(Int.self, forKey: .firstMatchBonusRange)
+        self.lengthPenalty = try container.decode(Double.self, forKey: .lengthPenalty)
+        self.acronymWeight = try container.decode(Double.self, forKey: .acronymWeight)
+        self.digitRuns = try container.decodeIfPresent(DigitRunMatching.self, forKey: .digitRuns) ?? .fuzzy
+    }
+}
+
+/// The prefilters that may reject a candidate before it is scored.
+///
+/// Each prefilter is a cheap check that throws out candidates the scorer would
+/// almost certainly reject anyway:
+///
+/// - ``lengthBounds``: the candidate is shorter than the query less its edit budget.
+/// - ``charBitmask``: the candidate lacks more of the query's characters than
+///   the edit budget allows.
+/// - ``trigrams``: the candidate shares too few of the query's trigrams.
+///
+/// They are tuned to keep recall, not proven to, so all three are on by
+/// default and turning one off is meant for measuring what it costs in
+/// results and saves in time, not for production use.
+///
+/// ## Example
+///
+/// ```swift
+/// // Score every candidate the trigram filter would have rejected
+/// let matcher = FuzzyMatcher(config: MatchConfig(prefilters: [.lengthBounds, .charBitmask]))
+/// ```
+public struct PrefilterSet: OptionSet, Sendable, Hashable {
+    public let rawValue: UInt8
+
+    public init(rawValue: UInt8) {
+        self.rawValue = rawValue
+    }
+
+    /// Rejects candidates too short to be within the edit budget of the query.
+    public static let lengthBounds = Self(rawValue: 1 << 0)
+
+    /// Rejects candidates missing too many of the query's characters.
+    public static let charBitmask = Self(rawValue: 1 << 1)
+
+    /// Rejects candidates sharing too few of the query's trigrams.
+    public static let trigrams = Self(rawValue: 1 << 2)
+
+    /// Every prefilter, the default. An empty set scores every candidate.
+    public static let all: Self = [.lengthBounds, .charBitmask, .trigrams]
+}
+
+/// Query words that say little about which candidate is meant.
+///
+/// Instrument names end in legal forms and share-class wording — "AG", "PLC",
+/// "Inc", "SA", "Class", "Registered Shares" — that thousands of names share.
+/// Matched like any other word, they dominate short queries: "nestle ag"
+/// spends a third of its characters on "ag", which every Swiss company has.
+///
+/// When a query has both noise words and other words, the noise words are
+/// removed before matching and the rest is scored as usual. A candidate
+/// containing a removed word as a whole word then gains `weight` times the
+/// share of removed words it contains, so "nestle ag" still prefers "Nestle AG"
+/// to "Nestle SA" without rejecting the latter. A weight of `0` ignores noise
+/// words entirely. A query made only of noise words, such as "AG", is matched
+/// unchanged.
+///
+/// Tokens, queries and candidates are compared after the matcher's own
+/// folding, so case and Latin-1 accents do not matter: "Société" is removed
+/// from a query typed "societe". A token of several words, such as
+/// "Registered Shares", is removed only where the query has those words in
+/// that order; where tokens overlap, the longest is removed.
+///
+/// Noise words describe a field, so set them on the matcher that scores names
+/// rather than one shared with symbols or ISINs.
+///
+/// ## Example
+///
+/// ```swift
+/// let names = FuzzyMatcher(config: MatchConfig(noiseTokens: .instrumentNames))
+/// let query = names.prepare("nestle ag")   // matched as "nestle"
+/// ```
+public struct NoiseTokens: Sendable, Equatable, Codable {
+    /// The noise words, folded as queries are and with single spaces between
+    /// the words of a multi-word token.
+    public var tokens: Set<String>
+
+    /// Score added, in proportion, for candidates containing the removed words.
+    public var weight: Double
+
+    /// Creates a noise word list.
+    ///
+    /// - Parameters:
+    ///   - tokens: The noise words, each one word or several separated by
+    ///     spaces. They are folded as queries are; empty tokens are dropped.
+    ///   - weight: Score added when a candidate contains every removed word.
+    ///     Default is `0.05`; `0` ignores noise words.
+    public init(_ tokens: Set<String>, weight: Double = 0.05) {
+        self.tokens = Set(tokens.compactMap(foldNoiseToken))
+        self.weight = weight
+    }
+
+    private enum CodingKeys: String, CodingKey {
+        case tokens, weight
     }
+
+    public init(from decoder: Decoder) throws {
+        let container = try decoder.container(keyedBy: CodingKeys.self)
+        self.init(
+            try container.decode(Set<String>.self, forKey: .tokens),
+            weight: try container.decode(Double.self, forKey: .weight)
+        )
+    }
+
+    /// Legal forms and share-class words common in instrument names.
+    public static let instrumentNames = Self([
+        "ag", "plc", "inc", "sa", "se", "nv", "ab", "asa", "oyj", "spa", "ltd", "corp", "co",
+        "class", "registered", "shares", "share", "ordinary", "the"
+    ])
 }
 
 /// Configuration for fuzzy matching behavior.
@@ -623,6 +872,19 @@ public struct MatchConfig: Sendable, Equatable, Codable {
     ///   Configure scoring constants via ``SmithWatermanConfig``.
     public var algorithm: MatchingAlgorithm
 
+    /// The prefilters applied before scoring. Default is ``PrefilterSet/all``.
+    ///
+    /// This is a measurement switch rather than a tuning knob, so it is not
+    /// part of the encoded configuration: a decoded configuration always has
+    /// every prefilter on.
+    public var prefilters: PrefilterSet
+
+    /// Query words removed before matching and only weakly rewarded.
+    /// Default is `nil`, which matches every query word.
+    ///
+    /// See ``NoiseTokens``.
+    public var noiseTokens: NoiseTokens?
+
     /// The default configuration using edit distance matching.
     public static let editDistance = Self()
 
@@ -673,6 +935,53 @@ public struct MatchConfig: Sendable, Equatable, Codable {
     ) {
         self.minScore = minScore
         self.algorithm = algorithm
+        self.prefilters = .all
+        self.noiseTokens = nil
+    }
+
+    /// Creates a new match configuration with noise words.
+    ///
+    /// - Parameters:
+    ///   - minScore: Minimum score threshold (0.0-1.0). Default is `0.3`.
+    ///   - algorithm: The matching algorithm with its configuration. Default is `.editDistance()`.
+    ///   - noiseTokens: Query words to remove before matching, or `nil` for none.
+    public init(
+        minScore: Double = 0.3,
+        algorithm: MatchingAlgorithm = .editDistance(),
+        noiseTokens: NoiseTokens?
+    ) {
+        self.init(minScore: minScore, algorithm: algorithm, prefilters: .all, noiseTokens: noiseTokens)
+    }
+
+    /// Creates a new match configuration with a chosen set of prefilters.
+    ///
+    /// - Parameters:
+    ///   - minScore: Minimum score threshold (0.0-1.0). Default is `0.3`.
+    ///   - algorithm: The matching algorithm with its configuration. Default is `.editDistance()`.
+    ///   - prefilters: The prefilters applied before scoring.
+    ///   - noiseTokens: Query words to remove before matching. Default is `nil`.
+    public init(
+        minScore: Double = 0.3,
+        algorithm: MatchingAlgorithm = .editDistance(),
+        prefilters: PrefilterSet,
+        noiseTokens: NoiseTokens? = nil
+    ) {
+        self.minScore = minScore
+        self.algorithm = algorithm
+        self.prefilters = prefilters
+        self.noiseTokens = noiseTokens
+    }
+
+    private enum CodingKeys: String, CodingKey {
+        case minScore, algorithm, noiseTokens
+    }
+
+    public init(from decoder: Decoder) throws {
+        let container = try decoder.container(keyedBy: CodingKeys.self)
+        self.minScore = try container.decode(Double.self, forKey: .minScore)
+        self.algorithm = try container.decode(MatchingAlgorithm.self, forKey: .algorithm)
+        self.prefilters = .all
+        self.noiseTokens = try container.decodeIfPresent(NoiseTokens.self, forKey: .noiseTokens)
     }
 
     /// The edit distance configuration, if using edit distance mode.
@@ -707,6 +1016,17 @@ extension GapPenalty: CustomDebugStringConvertible {
     }
 }
 
+extension DigitRunMatching: CustomDebugStringConvertible {
+    public var debugDescription: String {
+        switch self {
+        case .fuzzy:
+            "DigitRunMatching.fuzzy"
+        case .contiguous(let bonus):
+            "DigitRunMatching.contiguous(wholeNumberBonus: \(bonus))"
+        }
+    }
+}
+
 extension MatchingAlgorithm: CustomDebugStringConvertible {
     public var debugDescription: String {
         switch self {
@@ -720,7 +1040,7 @@ extension MatchingAlgorithm: CustomDebugStringConvertible {
 
 extension EditDistanceConfig: CustomDebugStringConvertible {
     public var debugDescription: String {
-        "EditDistanceConfig(maxED: \(maxEditDistance), longED: \(longQueryMaxEditDistance)@\(longQueryThreshold), prefix: \(prefixWeight), substring: \(substringWeight), boundary: \(wordBoundaryBonus), consecutive: \(consecutiveBonus), gap: \(gapPenalty.debugDescription), firstMatch: \(firstMatchBonus)@\(firstMatchBonusRange), length: \(lengthPenalty), acronym: \(acronymWeight))"
+        "EditDistanceConfig(maxED: \(maxEditDistance), longED: \(longQueryMaxEditDistance)@\(longQueryThreshold), prefix: \(prefixWeight), substring: \(substringWeight), boundary: \(wordBoundaryBonus), consecutive: \(consecutiveBonus), gap: \(gapPenalty.debugDescription), firstMatch: \(firstMatchBonus)@\(firstMatchBonusRange), length: \(lengthPenalty), acronym: \(acronymWeight), digits: \(digitRuns.debugDescription))"
     }
 }
 
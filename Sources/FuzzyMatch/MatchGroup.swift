@@ -0,0 +1,63 @@
+// ===----------------------------------------------------------------------===//
+//
+// This source file is part of the FuzzyMatch open source project
+//
+// Copyright (c) 2026 Ordo One, AB. and the FuzzyMatch project authors
+// Licensed under Apache License v2.0
+//
+// See LICENSE for license information
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// ===----------------------------------------------------------------------===//
+
+/// Matching items that share a key, represented by the best-scored one.
+///
+/// Returned by the `groupedMatches(_:by:groupedBy:against:)` methods of
+/// ``FuzzyMatcher``. One instrument is often listed on many venues under the
+/// same ISIN, and a query for its name matches every listing equally well.
+/// Grouping by the ISIN (or the issuer) collapses those rows into one, so a
+/// UI can show "Deutsche Bank (12 listings)" instead of twelve nearly
+/// identical results.
+///
+/// ## Example
+///
+/// ```swift
+/// for group in matcher.groupedMatches(listings, by: \.name, groupedBy: \.isin, against: query) {
+///     print("\(group.best.item.name) (\(group.count) listings)")
+/// }
+/// ```
+public struct MatchGroup<Key: Hashable, Item> {
+    /// The key every item in the group shares.
+    public let key: Key
+
+    /// The group's highest-scoring match. Among equal scores, the one that
+    /// came first in the candidates.
+    public let best: ItemMatchResult<Item>
+
+    /// How many items with this key matched the query, including ``best``.
+    public let count: Int
+
+    /// Creates a new match group.
+    ///
+    /// - Parameters:
+    ///   - key: The key every item in the group shares.
+    ///   - best: The group's highest-scoring match.
+    ///   - count: How many items with this key matched.
+    public init(key: Key, best: ItemMatchResult<Item>, count: Int) {
+        self.key = key
+        self.best = best
+        self.count = count
+    }
+}
+
+extension MatchGroup: Sendable where Key: Sendable, Item: Sendable {}
+extension MatchGroup: Equatable where Item: Equatable {}
+extension MatchGroup: Hashable where Item: Hashable {}
+
+extension MatchGroup: CustomStringConvertible {
+    /// A textual representation of the match group.
+    public var description: String {
+        "MatchGroup(key: \(key), count: \(count), best: \(best))"
+    }
+}
@@ -0,0 +1,133 @@
+// ===----------------------------------------------------------------------===//
+//
+// This source file is part of the FuzzyMatch open source project
+//
+// Copyright (c) 2026 Ordo One, AB. and the FuzzyMatch project authors
+// Licensed under Apache License v2.0
+//
+// See LICENSE for license information
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// ===----------------------------------------------------------------------===//
+
+/// Noise word handling for ``NoiseTokens``.
+///
+/// Noise words are split off the lowercased query when it is prepared, so the
+/// scoring pipelines only ever see the remaining words. After a candidate has
+/// matched, the removed words are looked up in it as whole words to compute
+/// the small reward ``NoiseTokens/weight`` allows. Tokens and candidates are
+/// folded with ``lowercaseUTF8(from:into:isASCII:)``, as queries are.
+
+/// Folds a noise token as ``FuzzyMatcher/prepare(_:)`` folds a query, with
+/// single spaces between its words.
+///
+/// - Parameter token: The token as given.
+/// - Returns: The folded token, or `nil` if it has no words.
+internal func foldNoiseToken(_ token: String) -> String? {
+    let bytes = Array(token.utf8)
+    var folded = [UInt8](repeating: 0, count: bytes.count)
+    let length = lowercaseUTF8(from: bytes.span, into: &folded, isASCII: bytes.allSatisfy { $0 < 0x80 })
+    let words = folded[..<length].split(separator: 0x20)
+    guard !words.isEmpty else { return nil }
+    return String(decoding: Array(words.joined(separator: [0x20])), as: UTF8.self)
+}
+
+/// Splits noise words off a lowercased query.
+///
+/// A multi-word token is removed where the query has its words in a row. At
+/// each word the longest token starting there wins.
+///
+/// - Parameters:
+///   - lowercased: The lowercased query bytes.
+///   - tokens: The folded noise tokens.
+/// - Returns: The remaining words joined by single spaces and the removed
+///   tokens, or `nil` if the query has no noise words or nothing else.
+internal func splitNoiseWords(
+    _ lowercased: [UInt8],
+    tokens: Set<String>
+) -> (core: [UInt8], noise: [[UInt8]])? {
+    let words = lowercased.split(separator: 0x20)
+    let longest = tokens.reduce(1) { longest, token in
+        max(longest, token.utf8.reduce(1) { $1 == 0x20 ? $0 + 1 : $0 })
+    }
+    var core: [UInt8] = []
+    var noise: [[UInt8]] = []
+    var start = 0
+    scan: while start < words.count {
+        for length in stride(from: min(longest, words.count - start), through: 1, by: -1) {
+            let run = Array(words[start..<start + length].joined(separator: [0x20]))
+            if tokens.contains(String(decoding: run, as: UTF8.self)) {
+                noise.append(run)
+                start += length
+                continue scan
+            }
+        }
+        if !core.isEmpty {
+            core.append(0x20)
+        }
+        core.append(contentsOf: words[start])
+        start += 1
+    }
+    guard !noise.isEmpty, !core.isEmpty else { return nil }
+    return (core, noise)
+}
+
+/// Whether a byte is part of a word: an ASCII letter or digit, or any byte of
+/// a multi-byte character.
+@inlinable
+internal func isWordByte(_ byte: UInt8) -> Bool {
+    (byte >= 0x30 && byte <= 0x39) || (byte >= 0x61 && byte <= 0x7A)
+        || (byte >= 0x41 && byte <= 0x5A) || byte >= 0x80
+}
+
+/// Counts the words that occur in the candidate as whole words.
+///
+/// - Parameters:
+///   - candidate: The candidate's original bytes, folded here as queries are.
+///   - words: The folded words to look for.
+/// - Returns: How many of `words` occur at least once.
+internal func countWholeWords(in candidate: Span<UInt8>, words: [[UInt8]]) -> Int {
+    var isASCII = true
+    for i in 0..<candidate.count where candidate[i] >= 0x80 {
+        isASCII = false
+        break
+    }
+    if isASCII {
+        return countFoldedWholeWords(in: candidate, words: words)
+    }
+    // Only a candidate that matched gets here, so the copy is rare
+    var folded = [UInt8](repeating: 0, count: candidate.count)
+    let length = lowercaseUTF8(from: candidate, into: &folded, isASCII: false)
+    folded.removeSubrange(length...)
+    return countFoldedWholeWords(in: folded.span, words: words)
+}
+
+/// Counts the words that occur as whole words in a candidate whose
+/// non-ASCII bytes are already folded; ASCII letters are compared
+/// case-insensitively.
+private func countFoldedWholeWords(in candidate: Span<UInt8>, words: [[UInt8]]) -> Int {
+    let count = candidate.count
+    var found = 0
+    for word in words {
+        var i = 0
+        search: while i + word.count <= count {
+            if i == 0 || !isWordByte(candidate[i - 1]) {
+                let end = i + word.count
+                if end == count || !isWordByte(candidate[end]) {
+                    var equal = true
+                    for k in 0..<word.count where lowercaseASCII(candidate[i + k]) != word[k] {
+                        equal = false
+                        break
+                    }
+                    if equal {
+                        found += 1
+                        break search
+                    }
+                }
+            }
+            i += 1
+        }
+    }
+    return found
+}
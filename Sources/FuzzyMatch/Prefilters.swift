@@ -25,6 +25,16 @@
 /// ```
 ///
 /// Each prefilter has increasing cost but catches candidates the previous filter missed.
+///
+/// ## Script Coverage
+///
+/// The byte-level case folding below covers as many scripts as the package's
+/// traits enable. `FullUnicode`, the default, folds Latin-1 Supplement, Greek and
+/// basic Cyrillic. `LatinExtended` keeps Latin-1 folding, diacritic removal and
+/// combining mark stripping but leaves Greek and Cyrillic bytes as they are.
+/// `ASCIIOnly` (or no trait at all) folds ASCII alone. When several are enabled
+/// the most complete wins. Each helper compiles down to the identity for a
+/// script that is left out, so the branches that call it fold away.
 
 /// Converts an ASCII uppercase letter to lowercase.
 ///
@@ -76,10 +86,15 @@ internal func isMultiByteLead(_ byte: UInt8) -> Bool {
 ///
 /// These marks modify the preceding base character (e.g., e + ◌́ = é) and are
 /// stripped during matching so that decomposed forms match their base characters.
+/// Under the `ASCIIOnly` trait nothing is a combining mark, and they are kept.
 @inlinable
 internal func isCombiningMark(lead: UInt8, second: UInt8) -> Bool {
-    (lead == 0xCC && second >= 0x80 && second <= 0xBF) ||
-    (lead == 0xCD && second >= 0x80 && second <= 0xAF)
+    #if FullUnicode || LatinExtended
+    return (lead == 0xCC && second >= 0x80 && second <= 0xBF) ||
+        (lead == 0xCD && second >= 0x80 && second <= 0xAF)
+    #else
+    return false
+    #endif
 }
 
 /// Maps a lowercased Latin-1 Supplement second byte to its ASCII base letter.
@@ -91,10 +106,13 @@ internal func isCombiningMark(lead: UInt8, second: UInt8) -> Bool {
 /// This function expects the second byte to already be lowercased (in range 0xA0–0xBF
 /// for letters). Call ``lowercaseLatinExtended(_:)`` first if the byte may be uppercase.
 ///
+/// Under the `ASCIIOnly` trait no character has a mapping.
+///
 /// - Parameter lowercasedSecondByte: The lowercased second byte of a 0xC3-prefixed UTF-8 sequence.
 /// - Returns: The ASCII base letter (0x61–0x7A), or `0` if no mapping exists.
 @inlinable
 internal func latin1ToASCII(_ lowercasedSecondByte: UInt8) -> UInt8 {
+    #if FullUnicode || LatinExtended
     switch lowercasedSecondByte {
     case 0xA0...0xA5: return 0x61  // à-å → a
     case 0xA7: return 0x63          // ç → c
@@ -107,6 +125,9 @@ internal func latin1ToASCII(_ lowercasedSecondByte: UInt8) -> UInt8 {
     case 0xBF: return 0x79          // ÿ → y
     default: return 0               // æ, ð, ø, þ, ß, ×, ÷ — no ASCII base
     }
+    #else
+    return 0
+    #endif
 }
 
 /// Lowercases the second byte of a 2-byte UTF-8 Latin-1 Supplement sequence.
@@ -114,16 +135,19 @@ internal func latin1ToASCII(_ lowercasedSecondByte: UInt8) -> UInt8 {
 /// Uppercase Latin-1 Supplement characters (U+00C0-U+00DE, except U+00D7 ×)
 /// are encoded as 0xC3 followed by 0x80-0x9E. Adding 0x20 to the second byte
 /// converts to the corresponding lowercase character (U+00E0-U+00FE, except U+00F7 ÷).
+/// Under the `ASCIIOnly` trait the byte is returned unchanged.
 ///
 /// - Parameter secondByte: The second byte of a 0xC3-prefixed UTF-8 sequence.
 /// - Returns: The lowercased second byte if it was uppercase Latin-1, otherwise unchanged.
 @inlinable
 internal func lowercaseLatinExtended(_ secondByte: UInt8) -> UInt8 {
+    #if FullUnicode || LatinExtended
     // 0x80-0x9E maps to uppercase U+00C0-U+00DE
     // 0x97 = U+00D7 (multiplication sign ×) is NOT a letter, skip it
     if secondByte >= 0x80 && secondByte <= 0x9E && secondByte != 0x97 {
         return secondByte + 0x20
     }
+    #endif
     return secondByte
 }
 
@@ -133,12 +157,15 @@ internal func lowercaseLatinExtended(_ secondByte: UInt8) -> UInt8 {
 /// - CE 91–9F (Α-Ο): lowercase = CE B1–BF (add 0x20 to second byte)
 /// - CE A0–A9 (Π-Ω): lowercase = CF 80–89 (change lead to CF, subtract 0x20)
 ///
+/// Only the `FullUnicode` trait folds Greek; otherwise the pair is returned unchanged.
+///
 /// - Parameters:
 ///   - lead: The lead byte (0xCE or 0xCF).
 ///   - second: The second byte of the UTF-8 sequence.
 /// - Returns: The lowercased (lead, second) pair.
 @inlinable
 internal func lowercaseGreek(lead: UInt8, second: UInt8) -> (UInt8, UInt8) {
+    #if FullUnicode
     if lead == 0xCE {
         // CE 91-9F → CE B1-BF (uppercase Α-Ο → lowercase α-ο)
         if second >= 0x91 && second <= 0x9F {
@@ -149,6 +176,7 @@ internal func lowercaseGreek(lead: UInt8, second: UInt8) -> (UInt8, UInt8) {
             return (0xCF, second &- 0x20)
         }
     }
+    #endif
     return (lead, second)
 }
 
@@ -159,12 +187,15 @@ internal func lowercaseGreek(lead: UInt8, second: UInt8) -> (UInt8, UInt8) {
 /// - D0 A0–AF (Р-Я): lowercase = D1 80–8F (change lead to D1, subtract 0x20)
 /// - D0 80–8F (Ё etc., U+0400-U+040F): lowercase = D1 90–9F (change lead to D1, add 0x10)
 ///
+/// Only the `FullUnicode` trait folds Cyrillic; otherwise the pair is returned unchanged.
+///
 /// - Parameters:
 ///   - lead: The lead byte (0xD0 or 0xD1).
 ///   - second: The second byte of the UTF-8 sequence.
 /// - Returns: The lowercased (lead, second) pair.
 @inlinable
 internal func lowercaseCyrillic(lead: UInt8, second: UInt8) -> (UInt8, UInt8) {
+    #if FullUnicode
     if lead == 0xD0 {
         // D0 90-9F → D0 B0-BF (uppercase А-П → lowercase а-п)
         if second >= 0x90 && second <= 0x9F {
@@ -179,6 +210,7 @@ internal func lowercaseCyrillic(lead: UInt8, second: UInt8) -> (UInt8, UInt8) {
             return (0xD1, second &+ 0x10)
         }
     }
+    #endif
     return (lead, second)
 }
 
@@ -0,0 +1,176 @@
+// ===----------------------------------------------------------------------===//
+//
+// This source file is part of the FuzzyMatch open source project
+//
+// Copyright (c) 2026 Ordo One, AB. and the FuzzyMatch project authors
+// Licensed under Apache License v2.0
+//
+// See LICENSE for license information
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// ===----------------------------------------------------------------------===//
+
+/// Matches yielded in descending score order, sorted only as far as they
+/// are read.
+///
+/// ``FuzzyMatcher/matches(_:against:)-1fvd5`` sorts every match before
+/// returning, which for a broad query on a large corpus means sorting
+/// thousands of results a caller may never look at. `RankedMatches` keeps
+/// the matches in a binary heap instead: building it is linear in the
+/// number of matches, and each ``next()`` takes the best remaining one in
+/// logarithmic time. Reading the first `k` of `n` matches costs
+/// `O(n + k log n)` rather than `O(n log n)`.
+///
+/// Results come out by score descending, and candidates with equal scores
+/// in their order in the scanned sequence, the same total order as
+/// ``FuzzyMatcher/matches(_:against:offset:limit:)``. Every candidate has
+/// already been scored when the sequence is created; only the ordering is
+/// deferred.
+///
+/// `RankedMatches` is its own iterator. Calling ``next()`` on a variable
+/// reads on from where the last call stopped; `for`-`in` and `prefix(_:)`
+/// read a copy and leave the variable where it was.
+///
+/// ## Example
+///
+/// ```swift
+/// let matcher = FuzzyMatcher()
+/// let query = matcher.prepare("bank")
+/// var ranked = matcher.rankedMatches(names, against: query)
+/// var shown: [MatchResult] = []
+/// while shown.count < 20, let result = ranked.next() {
+///     shown.append(result)
+/// }
+/// // Later, if the user scrolls, ranked.next() continues with the 21st
+/// ```
+public struct RankedMatches<Element>: Sequence, IteratorProtocol {
+    internal typealias Entry = (element: Element, score: Double, position: Int)
+
+    private var heap: [Entry]
+
+    /// Arranges `entries` into a heap, best first.
+    internal init(_ entries: [Entry]) {
+        heap = entries
+        var index = heap.count / 2
+        while index > 0 {
+            index -= 1
+            siftDown(from: index)
+        }
+    }
+
+    /// The number of matches not yet read.
+    public var remaining: Int {
+        heap.count
+    }
+
+    public var underestimatedCount: Int {
+        heap.count
+    }
+
+    /// Removes and returns the best remaining match, or `nil` once every
+    /// match has been read.
+    public mutating func next() -> Element? {
+        guard let last = heap.popLast() else {
+            return nil
+        }
+        guard !heap.isEmpty else {
+            return last.element
+        }
+        let best = heap[0]
+        heap[0] = last
+        siftDown(from: 0)
+        return best.element
+    }
+
+    /// Whether `lhs` comes out before `rhs`: a higher score, or an equal
+    /// score and an earlier position.
+    @inline(__always)
+    private static func precedes(_ lhs: Entry, _ rhs: Entry) -> Bool {
+        lhs.score != rhs.score ? lhs.score > rhs.score : lhs.position < rhs.position
+    }
+
+    private mutating func siftDown(from start: Int) {
+        var parent = start
+        let count = heap.count
+        while true {
+            let left = 2 * parent + 1
+            guard left < count else { return }
+            var child = left
+            if left + 1 < count && Self.precedes(heap[left + 1], heap[left]) {
+                child = left + 1
+            }
+            guard Self.precedes(heap[child], heap[parent]) else { return }
+            heap.swapAt(parent, child)
+            parent = child
+        }
+    }
+}
+
+extension RankedMatches: Sendable where Element: Sendable {}
+
+extension FuzzyMatcher {
+    /// Returns all matching candidates as a sequence that is sorted as it
+    /// is read, best first.
+    ///
+    /// Use this instead of ``matches(_:against:)-1fvd5`` when the caller
+    /// usually stops after a few results but may want more, such as a list
+    /// that loads as it scrolls. When the number of results is known up
+    /// front, ``topMatches(_:against:limit:)-7q3wo`` is cheaper still.
+    ///
+    /// - Parameters:
+    ///   - candidates: The candidates to search.
+    ///   - query: A prepared query from ``prepare(_:)``.
+    /// - Returns: A ``RankedMatches`` of ``MatchResult`` ordered by score
+    ///   descending, then position in `candidates`.
+    ///
+    /// ## Example
+    ///
+    /// ```swift
+    /// let matcher = FuzzyMatcher()
+    /// let query = matcher.prepare("bank")
+    /// for result in matcher.rankedMatches(names, against: query).prefix(5) {
+    ///     print(result.candidate, result.match.score)
+    /// }
+    /// ```
+    public func rankedMatches(
+        _ candidates: some Sequence<String>,
+        against query: FuzzyQuery
+    ) -> RankedMatches<MatchResult> {
+        var buffer = makeBuffer()
+        var entries: [RankedMatches<MatchResult>.Entry] = []
+        for (position, candidate) in candidates.enumerated() {
+            if let match = score(candidate, against: query, buffer: &buffer) {
+                entries.append((MatchResult(candidate: candidate, match: match), match.score, position))
+            }
+        }
+        return RankedMatches(entries)
+    }
+
+    /// Returns all matching items as a sequence that is sorted as it is
+    /// read, best first, matching against a string property extracted via
+    /// key path.
+    ///
+    /// This is the generic counterpart of ``rankedMatches(_:against:)``.
+    ///
+    /// - Parameters:
+    ///   - candidates: The items to search.
+    ///   - keyPath: A key path to the string property to match against.
+    ///   - query: A prepared query from ``prepare(_:)``.
+    /// - Returns: A ``RankedMatches`` of ``ItemMatchResult`` ordered by
+    ///   score descending, then position in `candidates`.
+    public func rankedMatches<Item>(
+        _ candidates: some Sequence<Item>,
+        by keyPath: KeyPath<Item, String>,
+        against query: FuzzyQuery
+    ) -> RankedMatches<ItemMatchResult<Item>> {
+        var buffer = makeBuffer()
+        var entries: [RankedMatches<ItemMatchResult<Item>>.Entry] = []
+        for (position, candidate) in candidates.enumerated() {
+            if let match = score(candidate[keyPath: keyPath], against: query, buffer: &buffer) {
+                entries.append((ItemMatchResult(item: candidate, match: match), match.score, position))
+            }
+        }
+        return RankedMatches(entries)
+    }
+}
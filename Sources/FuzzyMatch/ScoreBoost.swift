@@ -0,0 +1,110 @@
+// ===----------------------------------------------------------------------===//
+//
+// This source file is part of the FuzzyMatch open source project
+//
+// Copyright (c) 2026 Ordo One, AB. and the FuzzyMatch project authors
+// Licensed under Apache License v2.0
+//
+// See LICENSE for license information
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// ===----------------------------------------------------------------------===//
+
+/// A domain-specific adjustment applied to each matched item after scoring.
+///
+/// The matcher scores text alone, so it cannot know that a primary listing
+/// should outrank a secondary one, or that an active instrument should
+/// outrank a delisted one. A `ScoreBoost` adds that knowledge: after an
+/// item's string property has been scored, each boost looks at the item and
+/// its ``ScoredMatch`` and returns an amount to add to the score (negative
+/// to demote). Results are ranked by the boosted score, and every non-zero
+/// amount is kept in ``ItemMatchResult/boosts`` so the final order can be
+/// explained.
+///
+/// Boosts run only on items that matched, so they never turn a non-match
+/// into a match.
+///
+/// ## Example
+///
+/// ```swift
+/// struct Listing: Sendable {
+///     let ticker: String
+///     let name: String
+///     let isPrimary: Bool
+///     let isDelisted: Bool
+/// }
+///
+/// let boosts: [ScoreBoost<Listing>] = [
+///     .when("primary listing", add: 0.05) { $0.isPrimary },
+///     .when("delisted", add: -0.2) { $0.isDelisted },
+/// ]
+/// let results = matcher.topMatches(listings, by: \.name, against: query, boosts: boosts)
+/// for result in results {
+///     print(result.item.ticker, result.score, result.boosts)
+/// }
+/// ```
+public struct ScoreBoost<Item>: Sendable {
+    /// The name recorded with each contribution, such as `"primary listing"`.
+    public let name: String
+
+    let adjustment: @Sendable (Item, ScoredMatch) -> Double
+
+    /// Creates a boost that computes its amount from the item and its match.
+    ///
+    /// - Parameters:
+    ///   - name: The name recorded with each contribution.
+    ///   - adjustment: Returns the amount to add to the match's score, or `0`
+    ///     to leave it unchanged.
+    public init(_ name: String, adjustment: @escaping @Sendable (Item, ScoredMatch) -> Double) {
+        self.name = name
+        self.adjustment = adjustment
+    }
+
+    /// Creates a boost that adds a fixed amount to items satisfying `predicate`.
+    ///
+    /// - Parameters:
+    ///   - name: The name recorded with each contribution.
+    ///   - amount: The amount to add; negative to demote.
+    ///   - predicate: Whether the boost applies to an item.
+    public static func when(
+        _ name: String,
+        add amount: Double,
+        _ predicate: @escaping @Sendable (Item) -> Bool
+    ) -> Self {
+        Self(name) { item, _ in predicate(item) ? amount : 0 }
+    }
+
+    /// The boost's contribution for one matched item, or `nil` if it adds nothing.
+    func contribution(for item: Item, match: ScoredMatch) -> BoostContribution? {
+        let amount = adjustment(item, match)
+        return amount == 0 ? nil : BoostContribution(name: name, amount: amount)
+    }
+}
+
+/// One boost's adjustment to a match's score.
+///
+/// Kept in ``ItemMatchResult/boosts`` in the order the boosts were given, so
+/// a result's position can be traced to its text score and each domain boost.
+public struct BoostContribution: Sendable, Hashable, Codable, CustomStringConvertible {
+    /// The name of the ``ScoreBoost`` that made the adjustment.
+    public let name: String
+
+    /// The amount added to the score; negative for a demotion.
+    public let amount: Double
+
+    /// Creates a contribution.
+    ///
+    /// - Parameters:
+    ///   - name: The name of the boost.
+    ///   - amount: The amount added to the score.
+    public init(name: String, amount: Double) {
+        self.name = name
+        self.amount = amount
+    }
+
+    /// A textual representation of the contribution.
+    public var description: String {
+        "\(name): \(amount >= 0 ? "+" : "")\(amount)"
+    }
+}
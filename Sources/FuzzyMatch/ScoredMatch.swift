@@ -196,6 +196,18 @@ public struct ItemMatchResult<Item> {
     /// The match score and kind.
     public let match: ScoredMatch
 
+    /// The adjustments ``ScoreBoost``s made to the score, in the order the
+    /// boosts were given. Empty when no boost applied.
+    public let boosts: [BoostContribution]
+
+    /// The score results are ranked by: the match score plus every boost.
+    ///
+    /// Equal to `match.score` when ``boosts`` is empty. Unlike
+    /// ``ScoredMatch/score`` it is not confined to 0.0–1.0.
+    public var score: Double {
+        boosts.reduce(match.score) { $0 + $1.amount }
+    }
+
     /// Creates a new item match result.
     ///
     /// - Parameters:
@@ -204,6 +216,19 @@ public struct ItemMatchResult<Item> {
     public init(item: Item, match: ScoredMatch) {
         self.item = item
         self.match = match
+        self.boosts = []
+    }
+
+    /// Creates a new item match result with the boosts applied to its score.
+    ///
+    /// - Parameters:
+    ///   - item: The original item that matched.
+    ///   - match: The scored match containing score and kind.
+    ///   - boosts: The boost contributions applied to the match.
+    public init(item: Item, match: ScoredMatch, boosts: [BoostContribution]) {
+        self.item = item
+        self.match = match
+        self.boosts = boosts
     }
 }
 
@@ -212,7 +237,7 @@ extension ItemMatchResult: Equatable where Item: Equatable {}
 extension ItemMatchResult: Hashable where Item: Hashable {}
 
 extension ItemMatchResult: Comparable where Item: Equatable {
-    /// Compares two item match results by their scores.
+    /// Compares two item match results by their boosted scores.
     ///
     /// This enables sorting results from lowest to highest score.
     /// For highest-first sorting, use `>` or `sorted(by: >)`.
@@ -220,15 +245,18 @@ extension ItemMatchResult: Comparable where Item: Equatable {
     /// - Parameters:
     ///   - lhs: The left-hand side result.
     ///   - rhs: The right-hand side result.
-    /// - Returns: `true` if `lhs.match.score < rhs.match.score`.
+    /// - Returns: `true` if `lhs.score < rhs.score`.
     public static func < (lhs: Self, rhs: Self) -> Bool {
-        lhs.match < rhs.match
+        lhs.score < rhs.score
     }
 }
 
 extension ItemMatchResult: CustomStringConvertible {
     /// A textual representation of the item match result.
     public var description: String {
-        "ItemMatchResult(item: \(item), match: \(match))"
+        if boosts.isEmpty {
+            return "ItemMatchResult(item: \(item), match: \(match))"
+        }
+        return "ItemMatchResult(item: \(item), match: \(match), boosts: \(boosts))"
     }
 }
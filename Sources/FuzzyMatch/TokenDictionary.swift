@@ -0,0 +1,196 @@
+// ===----------------------------------------------------------------------===//
+//
+// This source file is part of the FuzzyMatch open source project
+//
+// Copyright (c) 2026 Ordo One, AB. and the FuzzyMatch project authors
+// Licensed under Apache License v2.0
+//
+// See LICENSE for license information
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// ===----------------------------------------------------------------------===//
+
+/// The words of a corpus with the number of candidates each appears in.
+///
+/// The matcher scores every character of a query alike, so matching "Fund",
+/// which thousands of instrument names contain, counts as much as matching
+/// "Nestlé", which picks out one company. A `TokenDictionary` built once over
+/// the candidates knows the difference: a word's ``rarity(of:)`` is its inverse
+/// document frequency scaled to 0.0–1.0, near `1` for a word in one candidate
+/// and `0` for a word in all of them.
+///
+/// Words are split at anything other than a letter or digit, lowercased, and
+/// folded the way the matcher folds them, so "Nestlé" and "nestle" are the
+/// same word. ``ScoreBoost/rareTokens(_:for:weight:name:text:)`` turns the
+/// dictionary into a boost that rewards matching rare words.
+///
+/// ## Example
+///
+/// ```swift
+/// let dictionary = TokenDictionary(instruments.lazy.map(\.name))
+/// dictionary.rarity(of: "fund")    // ≈ 0.1
+/// dictionary.rarity(of: "nestle")  // ≈ 0.9
+/// ```
+public struct TokenDictionary: Sendable, Equatable {
+    /// The number of candidates counted.
+    public private(set) var documentCount: Int
+
+    private var frequencies: [String: Int]
+
+    /// Counts the words of each candidate text, once per candidate.
+    ///
+    /// - Parameter texts: The candidate texts, typically one field of every item.
+    public init(_ texts: some Sequence<String>) {
+        documentCount = 0
+        frequencies = [:]
+        for text in texts {
+            documentCount += 1
+            for token in Set(Self.tokens(in: text)) {
+                frequencies[token, default: 0] += 1
+            }
+        }
+    }
+
+    /// The number of distinct words counted.
+    public var count: Int {
+        frequencies.count
+    }
+
+    /// The number of candidates containing `token`.
+    ///
+    /// - Parameter token: A word; it is folded like the candidates' words.
+    public func documentFrequency(of token: String) -> Int {
+        guard let folded = Self.tokens(in: token).first else { return 0 }
+        return frequencies[folded] ?? 0
+    }
+
+    /// How rare `token` is: `log((N + 1) / (df + 1)) / log(N + 1)` for `N`
+    /// candidates, `df` of which contain it.
+    ///
+    /// - Parameter token: A word; it is folded like the candidates' words.
+    /// - Returns: `1` for a word no candidate contains, falling towards `0`
+    ///   for a word every candidate contains. `0` for an empty dictionary.
+    public func rarity(of token: String) -> Double {
+        rarity(ofFolded: Self.tokens(in: token).first ?? "")
+    }
+
+    /// ``rarity(of:)`` for a word already split and folded by ``tokens(in:)``.
+    func rarity(ofFolded token: String) -> Double {
+        guard documentCount > 0 else { return 0 }
+        let total = Double(documentCount + 1)
+        let frequency = Double((frequencies[token] ?? 0) + 1)
+        return naturalLog(total / frequency) / naturalLog(total)
+    }
+
+    /// Splits a text into lowercased, folded words.
+    ///
+    /// - Parameter text: The text to split.
+    /// - Returns: The words in order, repeats included.
+    public static func tokens(in text: String) -> [String] {
+        var tokens: [String] = []
+        forEachWord(in: text) { word in
+            tokens.append(String(decoding: word, as: UTF8.self))
+        }
+        return tokens
+    }
+
+    /// Folds `text` as the matcher folds candidates and calls `body` with the
+    /// bytes of each word, without making a string of it.
+    static func forEachWord(in text: String, _ body: (ArraySlice<UInt8>) -> Void) {
+        let utf8 = text.utf8
+        var folded = [UInt8](repeating: 0, count: utf8.count)
+        let length = lowercaseUTF8(from: utf8.span, into: &folded, isASCII: utf8.allSatisfy { $0 < 0x80 })
+        var start = 0
+        for i in 0...length where i == length || !isWordByte(folded[i]) {
+            if i > start {
+                body(folded[start..<i])
+            }
+            start = i + 1
+        }
+    }
+}
+
+extension ScoreBoost {
+    /// Creates a boost rewarding items whose text contains the query's rarer words.
+    ///
+    /// Each query word is looked up among the item's words; a word the user is
+    /// still typing counts when it starts one of them. The amount is `weight`
+    /// times the mean ``TokenDictionary/rarity(of:)`` over the query's words,
+    /// counting the rarest item word each one matched and `0` for words it did
+    /// not, so "nestle fund" lifts "Nestlé Fund" much more than "Global Fund".
+    ///
+    /// The boost is built for one query: create it next to the call that
+    /// scores that query.
+    ///
+    /// Boosts run only on items that matched, and this one folds and splits
+    /// each such item's text again, making a string only of the words a query
+    /// word starts. Its cost therefore grows with the number of matches; for
+    /// queries that match much of a large corpus, a `minScore` that keeps the
+    /// matches few keeps it small.
+    ///
+    /// - Parameters:
+    ///   - dictionary: Word frequencies of the field being searched.
+    ///   - query: The prepared query being scored.
+    ///   - weight: The amount for an item matching only words no candidate
+    ///     has seen. Default is `0.1`.
+    ///   - name: The name recorded with each contribution. Default is `"rare tokens"`.
+    ///   - text: The item's text, the same field `dictionary` was built from.
+    /// - Returns: A boost to pass to the key-path matching APIs.
+    ///
+    /// ## Example
+    ///
+    /// ```swift
+    /// let dictionary = TokenDictionary(instruments.lazy.map(\.name))
+    /// let query = matcher.prepare("nestle fund")
+    /// let boosts: [ScoreBoost<Instrument>] = [.rareTokens(dictionary, for: query) { $0.name }]
+    /// for result in matcher.topMatches(instruments, by: \.name, against: query, boosts: boosts) {
+    ///     print(result.item.name, result.boosts)  // e.g. Nestlé Fund [rare tokens: +0.031]
+    /// }
+    /// ```
+    public static func rareTokens(
+        _ dictionary: TokenDictionary,
+        for query: FuzzyQuery,
+        weight: Double = 0.1,
+        name: String = "rare tokens",
+        text: @escaping @Sendable (Item) -> String
+    ) -> Self {
+        let queryTokens = TokenDictionary.tokens(in: query.original).map { Array($0.utf8) }
+        guard !queryTokens.isEmpty else {
+            return Self(name) { _, _ in 0 }
+        }
+        return Self(name) { item, _ in
+            // The rarest item word each query word starts
+            var best = [Double](repeating: 0, count: queryTokens.count)
+            TokenDictionary.forEachWord(in: text(item)) { word in
+                var rarity: Double?
+                for (index, queryToken) in queryTokens.enumerated() where word.starts(with: queryToken) {
+                    let wordRarity = rarity ?? dictionary.rarity(ofFolded: String(decoding: word, as: UTF8.self))
+                    rarity = wordRarity
+                    best[index] = max(best[index], wordRarity)
+                }
+            }
+            return weight * best.reduce(0, +) / Double(queryTokens.count)
+        }
+    }
+}
+
+/// Natural logarithm of a positive value, accurate to about 1e-12.
+///
+/// The library does not link Foundation, so this splits `value` into `m * 2^e`
+/// with `m` in `[1, 2)` and sums the series `ln(m) = 2 * atanh((m - 1) / (m + 1))`,
+/// which converges quickly since `(m - 1) / (m + 1) < 1/3`.
+internal func naturalLog(_ value: Double) -> Double {
+    let ln2 = 0.693_147_180_559_945_3
+    let ratio = (value.significand - 1) / (value.significand + 1)
+    let ratioSquared = ratio * ratio
+    var term = ratio
+    var sum = 0.0
+    var k = 1.0
+    while abs(term) > 1e-17 {
+        sum += term / k
+        term *= ratioSquared
+        k += 2
+    }
+    return Double(value.exponent) * ln2 + 2 * sum
+}
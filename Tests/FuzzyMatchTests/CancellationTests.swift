@@ -0,0 +1,90 @@
+// ===----------------------------------------------------------------------===//
+//
+// This source file is part of the FuzzyMatch open source project
+//
+// Copyright (c) 2026 Ordo One, AB. and the FuzzyMatch project authors
+// Licensed under Apache License v2.0
+//
+// See LICENSE for license information
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// ===----------------------------------------------------------------------===//
+
+@testable import FuzzyMatch
+import Testing
+
+// MARK: - Helpers
+
+private let names = (0..<5_000).map { "Instrument \($0) Holdings" }
+
+/// Candidates that cancel `token` once `cancelAt` have been read, and count
+/// how many the scan read in total.
+private struct CancellingCandidates: Sequence {
+    let base: [String]
+    let cancelAt: Int
+    let token: CancellationToken
+    let consumed: Counter
+
+    final class Counter {
+        var value = 0
+    }
+
+    func makeIterator() -> AnyIterator<String> {
+        var index = 0
+        return AnyIterator {
+            guard index < base.count else {
+                return nil
+            }
+            if index == cancelAt {
+                token.cancel()
+            }
+            defer { index += 1 }
+            consumed.value += 1
+            return base[index]
+        }
+    }
+}
+
+// MARK: - Cancellation Tests
+
+@Test func uncancelledTopMatchesEqualPlainTopMatches() {
+    let matcher = FuzzyMatcher()
+    let query = matcher.prepare("instr 42")
+    let plain = matcher.topMatches(names, against: query, limit: 20)
+    let token = CancellationToken()
+    #expect(matcher.topMatches(names, against: query, limit: 20, cancellation: token) == plain)
+    #expect(!token.isCancelled)
+}
+
+@Test func uncancelledMatchesEqualPlainMatches() {
+    let matcher = FuzzyMatcher()
+    let query = matcher.prepare("holdings 17")
+    let token = CancellationToken()
+    #expect(matcher.matches(names, against: query, cancellation: token) == matcher.matches(names, against: query))
+}
+
+@Test func cancelledTokenReturnsNil() {
+    let matcher = FuzzyMatcher()
+    let query = matcher.prepare("instr")
+    let token = CancellationToken()
+    token.cancel()
+    #expect(token.isCancelled)
+    #expect(matcher.topMatches(names, against: query, cancellation: token) == nil)
+    #expect(matcher.matches(names, against: query, cancellation: token) == nil)
+}
+
+@Test func cancellationStopsWithinOneChunk() {
+    let matcher = FuzzyMatcher()
+    let query = matcher.prepare("instr")
+    let token = CancellationToken()
+    let candidates = CancellingCandidates(
+        base: names,
+        cancelAt: 1_000,
+        token: token,
+        consumed: CancellingCandidates.Counter()
+    )
+    #expect(matcher.topMatches(candidates, against: query, limit: 10, cancellation: token) == nil)
+    #expect(candidates.consumed.value > 1_000)
+    #expect(candidates.consumed.value <= 1_000 + CancellationToken.checkInterval)
+}
@@ -653,6 +653,16 @@ import Testing
     }
 }
 
+@Test func matchConfigDoesNotEncodePrefilters() throws {
+    let original = MatchConfig(minScore: 0.5, prefilters: [.lengthBounds])
+    let data = try JSONEncoder().encode(original)
+    let decoded = try JSONDecoder().decode(MatchConfig.self, from: data)
+
+    #expect(!String(decoding: data, as: UTF8.self).contains("prefilters"))
+    #expect(decoded.minScore == 0.5)
+    #expect(decoded.prefilters == .all)
+}
+
 @Test func matchKindCodableRoundTrip() throws {
     let encoder = JSONEncoder()
     let decoder = JSONDecoder()
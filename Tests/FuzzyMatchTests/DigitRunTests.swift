@@ -0,0 +1,140 @@
+// ===----------------------------------------------------------------------===//
+//
+// This source file is part of the FuzzyMatch open source project
+//
+// Copyright (c) 2026 Ordo One, AB. and the FuzzyMatch project authors
+// Licensed under Apache License v2.0
+//
+// See LICENSE for license information
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// ===----------------------------------------------------------------------===//
+
+import Foundation
+@testable import FuzzyMatch
+import Testing
+
+private func contiguousMatcher(bonus: Double = 0.05) -> FuzzyMatcher {
+    FuzzyMatcher(config: MatchConfig(
+        algorithm: .editDistance(EditDistanceConfig(digitRuns: .contiguous(wholeNumberBonus: bonus)))
+    ))
+}
+
+// MARK: - Digit Run Extraction
+
+@Test func digitRunsAreMaximalDigitSequences() {
+    let runs = computeDigitRuns(Array("db x-trackers 7 2030 1c".utf8))
+    #expect(runs.map(\.start) == [14, 16, 21])
+    #expect(runs.map(\.length) == [1, 4, 1])
+}
+
+@Test func queryWithoutDigitsHasNoRuns() {
+    #expect(computeDigitRuns(Array("vanguard".utf8)).isEmpty)
+}
+
+@Test func digitRunsAreOnlyPreparedForContiguousMatching() {
+    #expect(FuzzyMatcher().prepare("msci world 7").digitRuns.isEmpty)
+    #expect(contiguousMatcher().prepare("msci world 7").digitRuns.count == 1)
+    #expect(FuzzyMatcher(config: .smithWaterman).prepare("msci world 7").digitRuns.isEmpty)
+}
+
+// MARK: - Digit Run Matching
+
+@Test func queryRunMustStartCandidateRun() {
+    let query = Array("7".utf8)
+    let runs = computeDigitRuns(query)
+    let whole = Array("Fund 7".utf8)
+    let prefix = Array("Fund 75".utf8)
+    let inside = Array("Fund 2017".utf8)
+
+    #expect(matchDigitRuns(candidate: whole.span, query: query, runs: runs) == 1)
+    #expect(matchDigitRuns(candidate: prefix.span, query: query, runs: runs) == 0)
+    #expect(matchDigitRuns(candidate: inside.span, query: query, runs: runs) == nil)
+}
+
+@Test func everyQueryRunMustMatch() {
+    let query = Array("2 30".utf8)
+    let runs = computeDigitRuns(query)
+    let both = Array("Bond 2.30".utf8)
+    let one = Array("Bond 2.50".utf8)
+
+    #expect(matchDigitRuns(candidate: both.span, query: query, runs: runs) == 2)
+    #expect(matchDigitRuns(candidate: one.span, query: query, runs: runs) == nil)
+}
+
+@Test func wholeNumberIsPreferredOverEarlierPrefix() {
+    let query = Array("7".utf8)
+    let runs = computeDigitRuns(query)
+    let candidate = Array("Series 75 Class 7".utf8)
+
+    #expect(matchDigitRuns(candidate: candidate.span, query: query, runs: runs) == 1)
+}
+
+// MARK: - Scoring
+
+@Test func contiguousDigitsRejectScatteredNumbers() {
+    let matcher = contiguousMatcher()
+    var buffer = matcher.makeBuffer()
+    let query = matcher.prepare("db x-trackers 7")
+
+    #expect(matcher.score("DB X-Trackers 7-10 Year Bond", against: query, buffer: &buffer) != nil)
+    #expect(matcher.score("DB X-Trackers II 2017 Bond", against: query, buffer: &buffer) == nil)
+    #expect(matcher.score("DB X-Trackers IE00B3RBWM25", against: query, buffer: &buffer) == nil)
+}
+
+@Test func singleDigitQueryMatchesOnlyNumbersStartingWithIt() {
+    let matcher = contiguousMatcher()
+    var buffer = matcher.makeBuffer()
+    let query = matcher.prepare("7")
+
+    #expect(matcher.score("7", against: query, buffer: &buffer)?.score == 1.0)
+    #expect(matcher.score("7 Eleven", against: query, buffer: &buffer) != nil)
+    #expect(matcher.score("Fund 2017", against: query, buffer: &buffer) == nil)
+}
+
+@Test func wholeNumberBonusRanksExactNumberFirst() {
+    let matcher = contiguousMatcher(bonus: 0.1)
+    var buffer = matcher.makeBuffer()
+    let query = matcher.prepare("fund 7")
+
+    let whole = matcher.score("Fund 7", against: query, buffer: &buffer)
+    let prefix = matcher.score("Fund 75", against: query, buffer: &buffer)
+    #expect(whole != nil && prefix != nil)
+    if let whole, let prefix {
+        #expect(whole.score > prefix.score)
+    }
+}
+
+@Test func wholeNumberBonusIsNotAppliedWithoutDigits() {
+    let plain = FuzzyMatcher()
+    let contiguous = contiguousMatcher(bonus: 0.1)
+    var buffer = plain.makeBuffer()
+
+    let plainMatch = plain.score("Vanguard Total Stock", against: plain.prepare("vangaurd"), buffer: &buffer)
+    let contiguousMatch = contiguous.score("Vanguard Total Stock", against: contiguous.prepare("vangaurd"), buffer: &buffer)
+    #expect(plainMatch?.score == contiguousMatch?.score)
+}
+
+// MARK: - Configuration
+
+@Test func digitRunMatchingCodableRoundTrip() throws {
+    let encoder = JSONEncoder()
+    let decoder = JSONDecoder()
+
+    for original: DigitRunMatching in [.fuzzy, .contiguous(wholeNumberBonus: 0.05)] {
+        let data = try encoder.encode(original)
+        #expect(try decoder.decode(DigitRunMatching.self, from: data) == original)
+    }
+}
+
+@Test func editDistanceConfigWithoutDigitRunsDecodesAsFuzzy() throws {
+    let encoded = try JSONEncoder().encode(EditDistanceConfig(maxEditDistance: 1))
+    var object = try #require(JSONSerialization.jsonObject(with: encoded) as? [String: Any])
+    object.removeValue(forKey: "digitRuns")
+    let legacy = try JSONSerialization.data(withJSONObject: object)
+
+    let decoded = try JSONDecoder().decode(EditDistanceConfig.self, from: legacy)
+    #expect(decoded.maxEditDistance == 1)
+    #expect(decoded.digitRuns == .fuzzy)
+}
@@ -0,0 +1,84 @@
+// ===----------------------------------------------------------------------===//
+//
+// This source file is part of the FuzzyMatch open source project
+//
+// Copyright (c) 2026 Ordo One, AB. and the FuzzyMatch project authors
+// Licensed under Apache License v2.0
+//
+// See LICENSE for license information
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// ===----------------------------------------------------------------------===//
+
+@testable import FuzzyMatch
+import Testing
+
+private struct Positioned {
+    let position: Int
+    let name: String
+}
+
+// Many duplicates, so the limit falls among tied scores
+private let names: [String] = (0..<60).map { i in
+    ["Bank of America", "Deutsche Bank", "Bank of America", "Banco Santander", "Bankinter"][i % 5]
+} + ["Microsoft", "Apple", "Bank"]
+
+@Test func limitedMatchesAreTheFirstPage() {
+    let matcher = FuzzyMatcher()
+    let query = matcher.prepare("bank")
+    for maxResults in [1, 7, 25] {
+        let limited = matcher.matches(names, against: query, maxResults: maxResults)
+        #expect(limited.results == matcher.matches(names, against: query, offset: 0, limit: maxResults))
+    }
+}
+
+@Test func overflowIsSignalledWithTheTotalCount() {
+    let matcher = FuzzyMatcher()
+    let query = matcher.prepare("bank")
+    let total = matcher.matches(names, against: query).count
+
+    let limited = matcher.matches(names, against: query, maxResults: 5)
+    #expect(limited.results.count == 5)
+    #expect(limited.matchCount == total)
+    #expect(limited.overflowed)
+    #expect(limited.maxResults == 5)
+
+    let all = matcher.matches(names, against: query, maxResults: total)
+    #expect(all.results.count == total)
+    #expect(!all.overflowed)
+}
+
+@Test func bestMatchIsKeptWhenItComesLast() {
+    let matcher = FuzzyMatcher()
+    let query = matcher.prepare("bank")
+    let limited = matcher.matches(names, against: query, maxResults: 3)
+    #expect(limited.results.first?.candidate == "Bank")
+}
+
+@Test func zeroMaxResultsOnlyCounts() {
+    let matcher = FuzzyMatcher()
+    let query = matcher.prepare("bank")
+    let limited = matcher.matches(names, against: query, maxResults: 0)
+    #expect(limited.results.isEmpty)
+    #expect(limited.matchCount > 0)
+    #expect(limited.overflowed)
+}
+
+@Test func noMatchesDoNotOverflow() {
+    let matcher = FuzzyMatcher()
+    let limited = matcher.matches(names, against: matcher.prepare("zzzzqqq"), maxResults: 10)
+    #expect(limited == LimitedMatches(results: [], matchCount: 0, maxResults: 10))
+    #expect(!limited.overflowed)
+}
+
+@Test func limitedItemMatchesKeepCandidateOrderForTies() {
+    let matcher = FuzzyMatcher()
+    let query = matcher.prepare("bank")
+    let items = names.enumerated().map { Positioned(position: $0.offset, name: $0.element) }
+    let limited = matcher.matches(items, by: \.name, against: query, maxResults: 12)
+    let paged = matcher.matches(items, by: \.name, against: query, offset: 0, limit: 12)
+
+    #expect(limited.results.map(\.item.position) == paged.map(\.item.position))
+    #expect(limited.overflowed)
+}
@@ -0,0 +1,88 @@
+// ===----------------------------------------------------------------------===//
+//
+// This source file is part of the FuzzyMatch open source project
+//
+// Copyright (c) 2026 Ordo One, AB. and the FuzzyMatch project authors
+// Licensed under Apache License v2.0
+//
+// See LICENSE for license information
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// ===----------------------------------------------------------------------===//
+
+@testable import FuzzyMatch
+import Testing
+
+private struct Listing: Equatable, Hashable, Sendable {
+    let ticker: String
+    let name: String
+    let isin: String
+    let isPrimary: Bool
+}
+
+private let listings = [
+    Listing(ticker: "DBK.F", name: "Deutsche Bank AG", isin: "DE0005140008", isPrimary: false),
+    Listing(ticker: "DBK.DE", name: "Deutsche Bank AG", isin: "DE0005140008", isPrimary: true),
+    Listing(ticker: "DB", name: "Deutsche Bank AG", isin: "DE0005140008", isPrimary: false),
+    Listing(ticker: "DBK.MI", name: "Deutsche Bank", isin: "DE0005140008", isPrimary: false),
+    Listing(ticker: "DB1.DE", name: "Deutsche Boerse AG", isin: "DE0005810055", isPrimary: true),
+    Listing(ticker: "MSFT", name: "Microsoft Corporation", isin: "US5949181045", isPrimary: true)
+]
+
+@Test func groupsCollapseListingsOfOneInstrument() {
+    let matcher = FuzzyMatcher()
+    let query = matcher.prepare("deutsche bank")
+    let groups = matcher.groupedMatches(listings, by: \.name, groupedBy: \.isin, against: query)
+
+    #expect(groups.first?.key == "DE0005140008")
+    #expect(groups.first?.count == 4)
+    #expect(Set(groups.map(\.key)).count == groups.count)
+    #expect(!groups.contains { $0.key == "US5949181045" })
+}
+
+@Test func groupRepresentativeIsTheBestScoredMember() {
+    let matcher = FuzzyMatcher()
+    let query = matcher.prepare("deutsche bank")
+    let members = matcher.matches(listings.filter { $0.isin == "DE0005140008" }, by: \.name, against: query)
+    let group = matcher.groupedMatches(listings, by: \.name, groupedBy: \.isin, against: query)[0]
+
+    #expect(group.best.score == members.map(\.score).max())
+}
+
+@Test func tiedRepresentativeIsTheFirstCandidate() {
+    let matcher = FuzzyMatcher()
+    let query = matcher.prepare("deutsche bank ag")
+    let groups = matcher.groupedMatches(listings, by: \.name, groupedBy: \.isin, against: query)
+    #expect(groups.first?.best.item.ticker == "DBK.F")
+}
+
+@Test func boostsChooseTheRepresentative() {
+    let matcher = FuzzyMatcher()
+    let query = matcher.prepare("deutsche bank ag")
+    let boosts: [ScoreBoost<Listing>] = [.when("primary listing", add: 0.05) { $0.isPrimary }]
+    let groups = matcher.groupedMatches(listings, by: \.name, groupedBy: \.isin, against: query, boosts: boosts)
+    #expect(groups.first?.best.item.ticker == "DBK.DE")
+    #expect(groups.first?.best.boosts == [BoostContribution(name: "primary listing", amount: 0.05)])
+}
+
+@Test func groupsAreSortedAndLimited() {
+    let matcher = FuzzyMatcher()
+    let query = matcher.prepare("deutsche")
+    let groups = matcher.groupedMatches(listings, by: \.name, groupedBy: \.isin, against: query)
+    for (a, b) in zip(groups, groups.dropFirst()) {
+        #expect(a.best.score >= b.best.score)
+    }
+
+    let limited = matcher.groupedMatches(listings, by: \.name, groupedBy: \.isin, against: query, limit: 1)
+    #expect(limited.count == 1)
+    #expect(limited.first?.key == groups.first?.key)
+    #expect(matcher.groupedMatches(listings, by: \.name, groupedBy: \.isin, against: query, limit: 0).isEmpty)
+}
+
+@Test func stringQueryGroupsLikePreparedQuery() {
+    let matcher = FuzzyMatcher()
+    let prepared = matcher.groupedMatches(listings, by: \.name, groupedBy: \.isin, against: matcher.prepare("deutsche"))
+    let convenience = matcher.groupedMatches(listings, by: \.name, groupedBy: \.isin, against: "deutsche")
+    #expect(prepared == convenience)
+}
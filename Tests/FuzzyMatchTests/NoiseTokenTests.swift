@@ -0,0 +1,163 @@
+// ===----------------------------------------------------------------------===//
+//
+// This source file is part of the FuzzyMatch open source project
+//
+// Copyright (c) 2026 Ordo One, AB. and the FuzzyMatch project authors
+// Licensed under Apache License v2.0
+//
+// See LICENSE for license information
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// ===----------------------------------------------------------------------===//
+
+import Foundation
+@testable import FuzzyMatch
+import Testing
+
+// MARK: - Query Preparation
+
+@Test func noiseWordsAreRemovedFromPreparedQuery() {
+    let matcher = FuzzyMatcher(config: MatchConfig(noiseTokens: .instrumentNames))
+    let query = matcher.prepare("Nestle AG Registered Shares")
+
+    #expect(query.lowercased == Array("nestle".utf8))
+    #expect(query.noiseWords == [Array("ag".utf8), Array("registered".utf8), Array("shares".utf8)])
+    #expect(query.original == "Nestle AG Registered Shares")
+    #expect(!query.containsSpaces)
+}
+
+@Test func queryOfOnlyNoiseWordsIsUnchanged() {
+    let matcher = FuzzyMatcher(config: MatchConfig(noiseTokens: .instrumentNames))
+    let query = matcher.prepare("AG")
+
+    #expect(query.lowercased == Array("ag".utf8))
+    #expect(query.noiseWords.isEmpty)
+}
+
+@Test func noiseWordsAreKeptWithoutConfiguration() {
+    let query = FuzzyMatcher().prepare("Nestle AG")
+    #expect(query.lowercased == Array("nestle ag".utf8))
+    #expect(query.noiseWords.isEmpty)
+}
+
+@Test func noiseTokensAreLowercased() {
+    let tokens = NoiseTokens(["PLC", "Inc"])
+    #expect(tokens.tokens == ["plc", "inc"])
+}
+
+@Test func noiseTokensAreFoldedAsQueriesAre() {
+    let tokens = NoiseTokens(["Société", " Registered   Shares ", " "])
+    #expect(tokens.tokens == ["societe", "registered shares"])
+}
+
+@Test func accentedNoiseTokenMatchesUnaccentedQuery() {
+    let matcher = FuzzyMatcher(config: MatchConfig(noiseTokens: NoiseTokens(["Société"])))
+    let query = matcher.prepare("Total societe")
+
+    #expect(query.lowercased == Array("total".utf8))
+    #expect(query.noiseWords == [Array("societe".utf8)])
+}
+
+@Test func multiWordNoiseTokenIsRemovedAsSequence() {
+    let matcher = FuzzyMatcher(config: MatchConfig(noiseTokens: NoiseTokens(["Registered Shares", "AG"])))
+    let query = matcher.prepare("Nestle AG Registered  Shares")
+
+    #expect(query.lowercased == Array("nestle".utf8))
+    #expect(query.noiseWords == [Array("ag".utf8), Array("registered shares".utf8)])
+}
+
+@Test func multiWordNoiseTokenNeedsItsWordsInOrder() {
+    let matcher = FuzzyMatcher(config: MatchConfig(noiseTokens: NoiseTokens(["Registered Shares"])))
+    let query = matcher.prepare("Nestle Shares Registered")
+
+    #expect(query.lowercased == Array("nestle shares registered".utf8))
+    #expect(query.noiseWords.isEmpty)
+}
+
+@Test func longestNoiseTokenWins() {
+    let matcher = FuzzyMatcher(config: MatchConfig(noiseTokens: NoiseTokens(["shares", "registered shares"])))
+    let query = matcher.prepare("Nestle Registered Shares")
+
+    #expect(query.lowercased == Array("nestle".utf8))
+    #expect(query.noiseWords == [Array("registered shares".utf8)])
+}
+
+// MARK: - Whole Word Lookup
+
+@Test func wholeWordsAreFoundCaseInsensitively() {
+    let words = [Array("ag".utf8), Array("sa".utf8)]
+    let candidate = Array("Nestle AG".utf8)
+    let partial = Array("Nestle Agrar".utf8)
+
+    #expect(countWholeWords(in: candidate.span, words: words) == 1)
+    #expect(countWholeWords(in: partial.span, words: words) == 0)
+}
+
+@Test func wholeWordsAreFoundInFoldedCandidate() {
+    let words = [Array("societe".utf8), Array("registered shares".utf8)]
+    let candidate = Array("Total Société, Registered Shares".utf8)
+    #expect(countWholeWords(in: candidate.span, words: words) == 2)
+}
+
+@Test func wordAfterPunctuationIsWhole() {
+    let words = [Array("inc".utf8)]
+    let candidate = Array("Apple, Inc.".utf8)
+    #expect(countWholeWords(in: candidate.span, words: words) == 1)
+}
+
+// MARK: - Scoring
+
+@Test func noiseWordsNoLongerRejectCandidates() {
+    let plain = FuzzyMatcher(config: MatchConfig(algorithm: .smithWaterman()))
+    let noisy = FuzzyMatcher(config: MatchConfig(algorithm: .smithWaterman(), noiseTokens: .instrumentNames))
+    var buffer = plain.makeBuffer()
+
+    #expect(plain.score("Nestle SA", against: plain.prepare("nestle ag"), buffer: &buffer) == nil)
+    #expect(noisy.score("Nestle SA", against: noisy.prepare("nestle ag"), buffer: &buffer) != nil)
+}
+
+@Test func candidateWithNoiseWordRanksHigher() {
+    let matcher = FuzzyMatcher(config: MatchConfig(noiseTokens: .instrumentNames))
+    var buffer = matcher.makeBuffer()
+    let query = matcher.prepare("nestle ag")
+
+    let withWord = matcher.score("Nestle AG", against: query, buffer: &buffer)
+    let without = matcher.score("Nestle SA", against: query, buffer: &buffer)
+    #expect(withWord != nil && without != nil)
+    if let withWord, let without {
+        #expect(withWord.score > without.score)
+    }
+}
+
+@Test func zeroWeightIgnoresNoiseWords() {
+    let matcher = FuzzyMatcher(config: MatchConfig(noiseTokens: NoiseTokens(["ag", "sa"], weight: 0)))
+    var buffer = matcher.makeBuffer()
+    let query = matcher.prepare("nestle ag")
+
+    let withWord = matcher.score("Nestle AG", against: query, buffer: &buffer)
+    let without = matcher.score("Nestle SA", against: query, buffer: &buffer)
+    #expect(withWord?.score == without?.score)
+}
+
+// MARK: - Configuration
+
+@Test func noiseTokensCodableRoundTrip() throws {
+    let original = MatchConfig(noiseTokens: .instrumentNames)
+    let data = try JSONEncoder().encode(original)
+    let decoded = try JSONDecoder().decode(MatchConfig.self, from: data)
+    #expect(decoded.noiseTokens == .instrumentNames)
+}
+
+@Test func decodedNoiseTokensAreFolded() throws {
+    let data = Data(#"{"tokens":["AG","Société"],"weight":0.02}"#.utf8)
+    let decoded = try JSONDecoder().decode(NoiseTokens.self, from: data)
+    #expect(decoded == NoiseTokens(["ag", "societe"], weight: 0.02))
+}
+
+@Test func matchConfigWithoutNoiseTokensDecodesAsNil() throws {
+    let data = try JSONEncoder().encode(MatchConfig())
+    let decoded = try JSONDecoder().decode(MatchConfig.self, from: data)
+    #expect(!String(decoding: data, as: UTF8.self).contains("noiseTokens"))
+    #expect(decoded.noiseTokens == nil)
+}
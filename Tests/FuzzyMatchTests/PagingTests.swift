@@ -0,0 +1,78 @@
+// ===----------------------------------------------------------------------===//
+//
+// This source file is part of the FuzzyMatch open source project
+//
+// Copyright (c) 2026 Ordo One, AB. and the FuzzyMatch project authors
+// Licensed under Apache License v2.0
+//
+// See LICENSE for license information
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// ===----------------------------------------------------------------------===//
+
+@testable import FuzzyMatch
+import Testing
+
+private struct Positioned {
+    let position: Int
+    let name: String
+}
+
+// Many duplicates, so most scores tie and only the tie-break orders them
+private let names: [String] = (0..<60).map { i in
+    ["Bank of America", "Deutsche Bank", "Bank of America", "Banco Santander", "Bankinter"][i % 5]
+} + ["Microsoft", "Apple"]
+
+@Test func pagesConcatenateToTheFullOrdering() {
+    let matcher = FuzzyMatcher()
+    let query = matcher.prepare("bank")
+    let all = matcher.matches(names, against: query, offset: 0, limit: names.count)
+
+    var paged: [MatchResult] = []
+    var offset = 0
+    while true {
+        let page = matcher.matches(names, against: query, offset: offset, limit: 7)
+        if page.isEmpty { break }
+        #expect(page.count <= 7)
+        paged += page
+        offset += page.count
+    }
+    #expect(paged == all)
+    #expect(all.count == matcher.matches(names, against: query).count)
+}
+
+@Test func equalScoresKeepCandidateOrder() {
+    let matcher = FuzzyMatcher()
+    let query = matcher.prepare("bank")
+    var buffer = matcher.makeBuffer()
+    let positioned = names.enumerated().compactMap { position, name in
+        matcher.score(name, against: query, buffer: &buffer).map { (position, $0.score) }
+    }
+    let expected = positioned.sorted { $0.1 != $1.1 ? $0.1 > $1.1 : $0.0 < $1.0 }.map { names[$0.0] }
+
+    let items = names.enumerated().map { Positioned(position: $0.offset, name: $0.element) }
+    let page = matcher.matches(items, by: \.name, against: query, offset: 3, limit: 20)
+    #expect(page.map(\.item.name) == Array(expected.dropFirst(3).prefix(20)))
+    for (a, b) in zip(page, page.dropFirst()) where a.match.score == b.match.score {
+        #expect(a.item.position < b.item.position)
+    }
+}
+
+@Test func repeatedPagesAreIdentical() {
+    let matcher = FuzzyMatcher()
+    let query = matcher.prepare("ban")
+    let first = matcher.matches(names, against: query, offset: 10, limit: 10)
+    let second = matcher.matches(names, against: query, offset: 10, limit: 10)
+    #expect(first == second)
+}
+
+@Test func pagesPastTheEndAreEmpty() {
+    let matcher = FuzzyMatcher()
+    let query = matcher.prepare("bank")
+    let count = matcher.matches(names, against: query).count
+    #expect(matcher.matches(names, against: query, offset: count, limit: 10).isEmpty)
+    #expect(matcher.matches(names, against: query, offset: count - 1, limit: 10).count == 1)
+    #expect(matcher.matches(names, against: query, offset: 0, limit: 0).isEmpty)
+    #expect(matcher.matches(names, against: query, offset: 5, limit: .max).count == count - 5)
+}
@@ -431,3 +431,50 @@ import Testing
     let result = matcher.score("Åäö Test", against: query, buffer: &buffer)
     #expect(result != nil)
 }
+
+// MARK: - Disabling Prefilters
+
+@Test func defaultConfigEnablesEveryPrefilter() {
+    #expect(MatchConfig().prefilters == .all)
+    #expect(MatchConfig.smithWaterman.prefilters == .all)
+}
+
+@Test func disabledPrefiltersLetEveryCandidateThrough() {
+    let matcher = FuzzyMatcher(config: MatchConfig(prefilters: []))
+    let query = matcher.prepare("vanguard")
+
+    #expect(query.minCandidateLength == 0)
+    #expect(query.charBitmask == 0)
+    #expect(query.trigrams.isEmpty)
+}
+
+@Test func eachPrefilterIsDisabledOnItsOwn() {
+    let all = FuzzyMatcher().prepare("vanguard")
+    let noLength = FuzzyMatcher(config: MatchConfig(prefilters: [.charBitmask, .trigrams])).prepare("vanguard")
+    let noBitmask = FuzzyMatcher(config: MatchConfig(prefilters: [.lengthBounds, .trigrams])).prepare("vanguard")
+    let noTrigrams = FuzzyMatcher(config: MatchConfig(prefilters: [.lengthBounds, .charBitmask])).prepare("vanguard")
+
+    #expect(noLength.minCandidateLength == 0)
+    #expect(noLength.charBitmask == all.charBitmask && noLength.trigrams == all.trigrams)
+    #expect(noBitmask.charBitmask == 0)
+    #expect(noBitmask.minCandidateLength == all.minCandidateLength && noBitmask.trigrams == all.trigrams)
+    #expect(noTrigrams.trigrams.isEmpty)
+    #expect(noTrigrams.minCandidateLength == all.minCandidateLength && noTrigrams.charBitmask == all.charBitmask)
+}
+
+@Test func disablingPrefiltersKeepsScoresOfCandidatesTheyPass() {
+    let filtered = FuzzyMatcher()
+    let unfiltered = FuzzyMatcher(config: MatchConfig(prefilters: []))
+    var buffer = filtered.makeBuffer()
+    let candidates = ["Vanguard", "Vanguard Total Stock", "Vangaurd Group", "Avanza", "van", "Guardian"]
+
+    for text in ["vanguard", "vangaurd", "vng"] {
+        let filteredQuery = filtered.prepare(text)
+        let unfilteredQuery = unfiltered.prepare(text)
+        for candidate in candidates {
+            guard let match = filtered.score(candidate, against: filteredQuery, buffer: &buffer) else { continue }
+            let unfilteredMatch = unfiltered.score(candidate, against: unfilteredQuery, buffer: &buffer)
+            #expect(unfilteredMatch?.score == match.score, "\(text) vs \(candidate)")
+        }
+    }
+}
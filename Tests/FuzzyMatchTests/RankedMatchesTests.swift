@@ -0,0 +1,78 @@
+// ===----------------------------------------------------------------------===//
+//
+// This source file is part of the FuzzyMatch open source project
+//
+// Copyright (c) 2026 Ordo One, AB. and the FuzzyMatch project authors
+// Licensed under Apache License v2.0
+//
+// See LICENSE for license information
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// ===----------------------------------------------------------------------===//
+
+@testable import FuzzyMatch
+import Testing
+
+private struct Positioned {
+    let position: Int
+    let name: String
+}
+
+// Many duplicates, so the tie-break decides much of the order
+private let names: [String] = (0..<60).map { i in
+    ["Bank of America", "Deutsche Bank", "Bank of America", "Banco Santander", "Bankinter"][i % 5]
+} + ["Microsoft", "Apple", "Banque de France"]
+
+@Test func rankedMatchesFollowThePagedOrdering() {
+    let matcher = FuzzyMatcher()
+    let query = matcher.prepare("bank")
+    let ranked = Array(matcher.rankedMatches(names, against: query))
+    let paged = matcher.matches(names, against: query, offset: 0, limit: names.count)
+
+    #expect(ranked == paged)
+    #expect(!ranked.isEmpty)
+}
+
+@Test func rankedMatchesCanBeReadInSteps() {
+    let matcher = FuzzyMatcher()
+    let query = matcher.prepare("ban")
+    var ranked = matcher.rankedMatches(names, against: query)
+    let total = ranked.remaining
+
+    var read: [MatchResult] = []
+    while read.count < 5, let result = ranked.next() {
+        read.append(result)
+    }
+    #expect(ranked.remaining == total - 5)
+    read += Array(ranked)
+    #expect(read == matcher.matches(names, against: query, offset: 0, limit: names.count))
+}
+
+@Test func firstRankedMatchesScoreLikeTopMatches() {
+    let matcher = FuzzyMatcher()
+    let query = matcher.prepare("bnk")
+    let ranked = matcher.rankedMatches(names, against: query).prefix(3).map(\.match.score)
+    #expect(ranked == matcher.topMatches(names, against: query, limit: 3).map(\.match.score))
+}
+
+@Test func rankedMatchesWithoutMatchesAreEmpty() {
+    let matcher = FuzzyMatcher()
+    var ranked = matcher.rankedMatches(names, against: matcher.prepare("zzzzqqq"))
+    #expect(ranked.remaining == 0)
+    #expect(ranked.next() == nil)
+    #expect(ranked.next() == nil)
+}
+
+@Test func rankedItemMatchesKeepCandidateOrderForTies() {
+    let matcher = FuzzyMatcher()
+    let query = matcher.prepare("bank")
+    let items = names.enumerated().map { Positioned(position: $0.offset, name: $0.element) }
+    let ranked = Array(matcher.rankedMatches(items, by: \.name, against: query))
+
+    let paged = matcher.matches(items, by: \.name, against: query, offset: 0, limit: items.count)
+    #expect(ranked.map(\.item.position) == paged.map(\.item.position))
+    for (a, b) in zip(ranked, ranked.dropFirst()) {
+        #expect(a.match.score > b.match.score || (a.match.score == b.match.score && a.item.position < b.item.position))
+    }
+}
@@ -0,0 +1,93 @@
+// ===----------------------------------------------------------------------===//
+//
+// This source file is part of the FuzzyMatch open source project
+//
+// Copyright (c) 2026 Ordo One, AB. and the FuzzyMatch project authors
+// Licensed under Apache License v2.0
+//
+// See LICENSE for license information
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// ===----------------------------------------------------------------------===//
+
+@testable import FuzzyMatch
+import Testing
+
+private struct Listing: Equatable, Hashable, Sendable {
+    let ticker: String
+    let name: String
+    let isPrimary: Bool
+    let isDelisted: Bool
+}
+
+// The same company on three venues: text scores tie, so only boosts can order them
+private let listings = [
+    Listing(ticker: "DBK.F", name: "Deutsche Bank AG", isPrimary: false, isDelisted: false),
+    Listing(ticker: "DBK.OLD", name: "Deutsche Bank AG", isPrimary: false, isDelisted: true),
+    Listing(ticker: "DBK.DE", name: "Deutsche Bank AG", isPrimary: true, isDelisted: false),
+    Listing(ticker: "MSFT", name: "Microsoft Corporation", isPrimary: true, isDelisted: false)
+]
+
+private let boosts: [ScoreBoost<Listing>] = [
+    .when("primary listing", add: 0.05) { $0.isPrimary },
+    .when("delisted", add: -0.2) { $0.isDelisted }
+]
+
+@Test func boostsReorderTiedMatches() {
+    let matcher = FuzzyMatcher()
+    let query = matcher.prepare("deutsche bank")
+    let results = matcher.topMatches(listings, by: \.name, against: query, boosts: boosts)
+    #expect(results.map(\.item.ticker) == ["DBK.DE", "DBK.F", "DBK.OLD"])
+}
+
+@Test func boostContributionsAreRecorded() {
+    let matcher = FuzzyMatcher()
+    let query = matcher.prepare("deutsche bank")
+    let results = matcher.matches(listings, by: \.name, against: query, boosts: boosts)
+    let byTicker = Dictionary(uniqueKeysWithValues: results.map { ($0.item.ticker, $0) })
+
+    #expect(byTicker["DBK.DE"]?.boosts == [BoostContribution(name: "primary listing", amount: 0.05)])
+    #expect(byTicker["DBK.F"]?.boosts.isEmpty == true)
+    #expect(byTicker["DBK.OLD"]?.boosts == [BoostContribution(name: "delisted", amount: -0.2)])
+
+    for result in results {
+        let total = result.boosts.reduce(0) { $0 + $1.amount }
+        #expect(abs(result.score - (result.match.score + total)) < 1e-12)
+    }
+}
+
+@Test func boostsNeverAddMatches() {
+    let matcher = FuzzyMatcher()
+    let query = matcher.prepare("deutsche bank")
+    let everything: [ScoreBoost<Listing>] = [.when("everything", add: 10) { _ in true }]
+    let results = matcher.matches(listings, by: \.name, against: query, boosts: everything)
+    #expect(!results.contains { $0.item.ticker == "MSFT" })
+}
+
+@Test func topMatchesSelectsByBoostedScore() {
+    let matcher = FuzzyMatcher()
+    let query = matcher.prepare("deutsche bank")
+    let results = matcher.topMatches(listings, by: \.name, against: query, limit: 1, boosts: boosts)
+    #expect(results.map(\.item.ticker) == ["DBK.DE"])
+}
+
+@Test func boostsCanReadTheMatch() {
+    let matcher = FuzzyMatcher()
+    let query = matcher.prepare("deutsche bank")
+    let exactOnly = ScoreBoost<Listing>("exact") { _, match in match.kind == .exact ? 1 : 0 }
+    let results = matcher.matches(listings, by: \.name, against: query, boosts: [exactOnly])
+    for result in results {
+        #expect(result.boosts.isEmpty == (result.match.kind != .exact))
+    }
+}
+
+@Test func unboostedResultsScoreAsTheirMatch() {
+    let matcher = FuzzyMatcher()
+    let query = matcher.prepare("deutsche")
+    let results = matcher.topMatches(listings, by: \.name, against: query)
+    for result in results {
+        #expect(result.boosts.isEmpty)
+        #expect(result.score == result.match.score)
+    }
+}
@@ -0,0 +1,105 @@
+// ===----------------------------------------------------------------------===//
+//
+// This source file is part of the FuzzyMatch open source project
+//
+// Copyright (c) 2026 Ordo One, AB. and the FuzzyMatch project authors
+// Licensed under Apache License v2.0
+//
+// See LICENSE for license information
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// ===----------------------------------------------------------------------===//
+
+import Foundation
+@testable import FuzzyMatch
+import Testing
+
+private struct Fund: Equatable, Hashable, Sendable {
+    let name: String
+}
+
+private let funds = [
+    Fund(name: "Nestlé Fund"),
+    Fund(name: "Global Fund"),
+    Fund(name: "Europe Fund"),
+    Fund(name: "Nordic Fund"),
+    Fund(name: "Asia Fund")
+]
+
+// MARK: - Tokenization
+
+@Test func tokensAreLowercasedAndFolded() {
+    #expect(TokenDictionary.tokens(in: "Nestlé S.A. (Reg.)") == ["nestle", "s", "a", "reg"])
+    #expect(TokenDictionary.tokens(in: "  ").isEmpty)
+}
+
+// MARK: - Frequencies
+
+@Test func documentFrequencyCountsEachCandidateOnce() {
+    let dictionary = TokenDictionary(["Fund Fund", "Global Fund", "Nestlé"])
+    #expect(dictionary.documentCount == 3)
+    #expect(dictionary.documentFrequency(of: "FUND") == 2)
+    #expect(dictionary.documentFrequency(of: "nestle") == 1)
+    #expect(dictionary.documentFrequency(of: "missing") == 0)
+    #expect(dictionary.count == 3)
+}
+
+@Test func rarityFallsWithFrequency() {
+    let dictionary = TokenDictionary(funds.map(\.name))
+    #expect(dictionary.rarity(of: "missing") == 1.0)
+    #expect(dictionary.rarity(of: "fund") == 0.0)
+    #expect(dictionary.rarity(of: "nestle") > 0.5)
+    #expect(TokenDictionary([String]()).rarity(of: "fund") == 0)
+}
+
+@Test func naturalLogMatchesFoundation() {
+    for value in [0.001, 0.5, 1.0, 2.0, 3.0, 10.0, 272_000.0] {
+        #expect(abs(naturalLog(value) - log(value)) < 1e-12)
+    }
+}
+
+// MARK: - Boost
+
+@Test func rareTokenBoostFavoursRareWords() {
+    let dictionary = TokenDictionary(funds.map(\.name))
+    let matcher = FuzzyMatcher()
+    let query = matcher.prepare("nestle fund")
+    let boost = ScoreBoost<Fund>.rareTokens(dictionary, for: query) { $0.name }
+    let match = ScoredMatch(score: 0.5, kind: .prefix)
+
+    let nestle = boost.contribution(for: funds[0], match: match)
+    let global = boost.contribution(for: funds[1], match: match)
+    #expect(nestle?.name == "rare tokens")
+    #expect((nestle?.amount ?? 0) > 0.02)
+    #expect(global == nil)
+}
+
+@Test func rareTokenBoostCountsPrefixes() {
+    let dictionary = TokenDictionary(funds.map(\.name))
+    let query = FuzzyMatcher().prepare("nes")
+    let boost = ScoreBoost<Fund>.rareTokens(dictionary, for: query, weight: 1.0) { $0.name }
+    let amount = boost.contribution(for: funds[0], match: ScoredMatch(score: 1, kind: .prefix))?.amount
+    #expect(amount == dictionary.rarity(of: "nestle"))
+}
+
+@Test func rareTokenBoostTakesRarestWordPerQueryWord() {
+    let dictionary = TokenDictionary(funds.map(\.name) + ["Nordea Fund", "Nordic Bond"])
+    let query = FuzzyMatcher().prepare("nor")
+    let boost = ScoreBoost<Fund>.rareTokens(dictionary, for: query, weight: 1.0) { $0.name }
+    let both = Fund(name: "Nordic Nordea")
+    let amount = boost.contribution(for: both, match: ScoredMatch(score: 1, kind: .prefix))?.amount
+    #expect(dictionary.rarity(of: "nordea") > dictionary.rarity(of: "nordic"))
+    #expect(amount == dictionary.rarity(of: "nordea"))
+}
+
+@Test func rareTokenBoostIsRecordedInResults() {
+    let dictionary = TokenDictionary(funds.map(\.name))
+    let matcher = FuzzyMatcher()
+    let query = matcher.prepare("nestle")
+    let boosts: [ScoreBoost<Fund>] = [.rareTokens(dictionary, for: query, weight: 0.2) { $0.name }]
+    let results = matcher.topMatches(funds, by: \.name, against: query, boosts: boosts)
+
+    #expect(results.first?.item == funds[0])
+    #expect(results.first?.boosts.map(\.name) == ["rare tokens"])
+}
@@ -0,0 +1,86 @@
+// ===----------------------------------------------------------------------===//
+//
+// This source file is part of the FuzzyMatch open source project
+//
+// Copyright (c) 2026 Ordo One, AB. and the FuzzyMatch project authors
+// Licensed under Apache License v2.0
+//
+// See LICENSE for license information
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// ===----------------------------------------------------------------------===//
+
+@testable import FuzzyMatch
+import Testing
+
+// The rest of the suite is written against the default `FullUnicode` trait.
+// These tests check the folding of whichever trait set the package was built
+// with, e.g. `swift test --traits LatinExtended --filter UnicodeTraitTests`.
+
+// MARK: - ASCII (every trait set)
+
+@Test func asciiFoldsUnderEveryTrait() {
+    #expect(lowercaseASCII(0x41) == 0x61)
+    #expect(lowercaseASCII(0x5A) == 0x7A)
+    #expect(lowercaseASCII(0x31) == 0x31)
+
+    let matcher = FuzzyMatcher()
+    let query = matcher.prepare("APPLE")
+    var buffer = matcher.makeBuffer()
+    #expect(matcher.score("apple", against: query, buffer: &buffer)?.kind == .exact)
+}
+
+// MARK: - Latin-1 Supplement
+
+#if FullUnicode || LatinExtended
+@Test func latinFoldsUnderLatinTraits() {
+    #expect(lowercaseLatinExtended(0x9C) == 0xBC)  // Ü → ü
+    #expect(latin1ToASCII(0xBC) == 0x75)           // ü → u
+    #expect(isCombiningMark(lead: 0xCC, second: 0x81))
+
+    let matcher = FuzzyMatcher()
+    let query = matcher.prepare("uber")
+    var buffer = matcher.makeBuffer()
+    #expect(matcher.score("ÜBER", against: query, buffer: &buffer)?.kind == .exact)
+}
+#else
+@Test func latinIsLeftAsBytesUnderASCIIOnly() {
+    #expect(lowercaseLatinExtended(0x9C) == 0x9C)
+    #expect(latin1ToASCII(0xBC) == 0)
+    #expect(!isCombiningMark(lead: 0xCC, second: 0x81))
+
+    let matcher = FuzzyMatcher()
+    let query = matcher.prepare("über")
+    var buffer = matcher.makeBuffer()
+    #expect(matcher.score("über", against: query, buffer: &buffer)?.kind == .exact)
+}
+#endif
+
+// MARK: - Greek and Cyrillic
+
+#if FullUnicode
+@Test func greekAndCyrillicFoldUnderFullUnicode() {
+    let greek = lowercaseGreek(lead: 0xCE, second: 0x91)  // Α → α
+    #expect(greek.0 == 0xCE && greek.1 == 0xB1)
+    let cyrillic = lowercaseCyrillic(lead: 0xD0, second: 0xA0)  // Р → р
+    #expect(cyrillic.0 == 0xD1 && cyrillic.1 == 0x80)
+
+    let matcher = FuzzyMatcher()
+    let query = matcher.prepare("БЕОГРАД")
+    var buffer = matcher.makeBuffer()
+    #expect(matcher.score("београд", against: query, buffer: &buffer)?.kind == .exact)
+}
+#else
+@Test func greekAndCyrillicAreLeftAsBytesWithoutFullUnicode() {
+    let greek = lowercaseGreek(lead: 0xCE, second: 0x91)
+    #expect(greek.0 == 0xCE && greek.1 == 0x91)
+    let cyrillic = lowercaseCyrillic(lead: 0xD0, second: 0xA0)
+    #expect(cyrillic.0 == 0xD0 && cyrillic.1 == 0xA0)
+
+    let matcher = FuzzyMatcher()
+    let query = matcher.prepare("београд")
+    var buffer = matcher.makeBuffer()
+    #expect(matcher.score("београд", against: query, buffer: &buffer)?.kind == .exact)
+}
+#endif